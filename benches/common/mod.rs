@@ -0,0 +1,59 @@
+//! Geometries shared by the benches.
+
+//every bench uses only some of them
+#![allow(dead_code)]
+
+use geoms::coord;
+use geoms::coordinate::Coordinate;
+use geoms::geometry::Geometry;
+use geoms::shapes;
+
+/// The vertices of the rings and lines of each size.
+pub const SIZES: [(&str, usize); 3] = [("small", 16), ("medium", 1_000), ("huge", 10_000)];
+
+/// Returns a closed star shaped ring of `n` vertices around the center, alternating between two
+/// radii so rays from the center cross it many times.
+pub fn star(center: (f64, f64), outer: f64, inner: f64, n: usize) -> Vec<Coordinate> {
+    let mut ring: Vec<Coordinate> = (0..n)
+        .map(|i| {
+            let angle = i as f64 / n as f64 * std::f64::consts::TAU;
+            let radius = if i % 2 == 0 { outer } else { inner };
+            coord!(center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect();
+    ring.push(ring[0].clone());
+    ring
+}
+
+/// The geometries the operations are timed on, with rings and lines of `n` vertices.
+pub struct Fixtures {
+    /// A star shaped polygon.
+    pub polygon: Geometry,
+    /// Two overlapping circles, whose boundaries cross twice.
+    pub disc: Geometry,
+    pub shifted: Geometry,
+    /// A wave and a copy of it moved off it.
+    pub line: Geometry,
+    pub far_line: Geometry,
+    /// A grid of points over the polygon.
+    pub points: Vec<Coordinate>,
+}
+
+impl Fixtures {
+    pub fn new(n: usize) -> Fixtures {
+        let line = Geometry::LineString {
+            coordinates: (0..n).map(|i| coord!(i as f64, (i as f64 * 0.1).sin() * 50.0)).collect(),
+        };
+        let far_line = Geometry::LineString {
+            coordinates: line.coordinates().iter().map(|c| coord!(c.x() + 0.5, c.y() + 10.0)).collect(),
+        };
+        Fixtures {
+            polygon: Geometry::Polygon { coordinates: vec![star((0.0, 0.0), 100.0, 60.0, n)] },
+            disc: shapes::circle(&coord!(0, 0), 100.0, n),
+            shifted: shapes::circle(&coord!(30, 10), 100.0, n),
+            line,
+            far_line,
+            points: (0..100).map(|i| coord!((i % 10) as f64 * 20.0 - 90.0, (i / 10) as f64 * 20.0 - 90.0)).collect(),
+        }
+    }
+}
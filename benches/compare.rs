@@ -0,0 +1,91 @@
+//! Times the operations the library shares with the `geo` crate on the same geometries with both,
+//! so the reports show how far apart they are at every size.
+//!
+//! Run `cargo bench --bench compare`, or `cargo bench --bench compare -- <filter>` to only time the
+//! operations whose name matches the filter.
+
+mod common;
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use geo::{Area, BooleanOps, Contains, Distance, Euclidean, Length};
+use geoms::algorithm::point_in_polygon::{IndexedPointInAreaLocator, Location};
+use geoms::coordinate::Coordinate;
+use geoms::geometry::Geometry;
+
+use common::{Fixtures, SIZES};
+
+fn to_line(coordinates: &[Coordinate]) -> geo::LineString {
+    coordinates.iter().map(|c| geo::coord! { x: c.x(), y: c.y() }).collect()
+}
+
+/// Returns the shell of a polygon of the library as a polygon of `geo`.
+fn to_polygon(polygon: &Geometry) -> geo::Polygon {
+    match polygon {
+        Geometry::Polygon { coordinates } => geo::Polygon::new(to_line(&coordinates[0]), vec![]),
+        _ => unreachable!(),
+    }
+}
+
+fn measures(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compare measures");
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        let (polygon, line) = (to_polygon(&fixtures.polygon), to_line(&fixtures.line.coordinates()));
+        group.bench_function(BenchmarkId::new("area geoms", size), |b| b.iter(|| fixtures.polygon.area()));
+        group.bench_function(BenchmarkId::new("area geo", size), |b| b.iter(|| polygon.unsigned_area()));
+        group.bench_function(BenchmarkId::new("length geoms", size), |b| b.iter(|| fixtures.line.length()));
+        group.bench_function(BenchmarkId::new("length geo", size), |b| b.iter(|| Euclidean.length(&line)));
+    }
+    group.finish();
+}
+
+fn point_in_polygon(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compare point in polygon x100");
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        let locator = IndexedPointInAreaLocator::new(&fixtures.polygon);
+        let polygon = to_polygon(&fixtures.polygon);
+        let points: Vec<geo::Point> = fixtures.points.iter().map(|p| geo::Point::new(p.x(), p.y())).collect();
+        group.bench_function(BenchmarkId::new("geoms", size), |b| {
+            b.iter(|| fixtures.points.iter().filter(|p| fixtures.polygon.contains_point(p)).count())
+        });
+        group.bench_function(BenchmarkId::new("geoms indexed", size), |b| {
+            b.iter(|| fixtures.points.iter().filter(|p| locator.locate(p) == Location::Interior).count())
+        });
+        group.bench_function(BenchmarkId::new("geo", size), |b| b.iter(|| points.iter().filter(|p| polygon.contains(*p)).count()));
+    }
+    group.finish();
+}
+
+fn distance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compare distance");
+    group.sample_size(10);
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        let (line, far_line) = (to_line(&fixtures.line.coordinates()), to_line(&fixtures.far_line.coordinates()));
+        group.bench_function(BenchmarkId::new("geoms", size), |b| b.iter(|| fixtures.line.distance(&fixtures.far_line)));
+        group.bench_function(BenchmarkId::new("geo", size), |b| b.iter(|| Euclidean.distance(&line, &far_line)));
+    }
+    group.finish();
+}
+
+fn overlay(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compare overlay");
+    group.sample_size(10).measurement_time(Duration::from_secs(2));
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        let (disc, shifted) = (to_polygon(&fixtures.disc), to_polygon(&fixtures.shifted));
+        group.bench_function(BenchmarkId::new("union geoms", size), |b| b.iter(|| fixtures.disc.union(&fixtures.shifted).unwrap()));
+        group.bench_function(BenchmarkId::new("union geo", size), |b| b.iter(|| disc.union(&shifted)));
+        group.bench_function(BenchmarkId::new("intersection geoms", size), |b| {
+            b.iter(|| fixtures.disc.intersection(&fixtures.shifted).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("intersection geo", size), |b| b.iter(|| disc.intersection(&shifted)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, measures, point_in_polygon, distance, overlay);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+//! Times the envelope, area and point in polygon loops on a ring of a million vertices.
+//!
+//! Compare `cargo bench --bench simd` with `cargo bench --bench simd --features simd`.
+
+mod common;
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use geoms::algorithm::area::ring_signed_area;
+use geoms::algorithm::point_in_polygon::locate_in_ring;
+use geoms::coord;
+use geoms::coordinate::Coordinate;
+use geoms::envelope::Envelope;
+
+use common::star;
+
+fn loops(c: &mut Criterion) {
+    let ring = star((0.0, 0.0), 100.0, 60.0, 1_000_000);
+    let point = coord!(0.5, 0.25);
+
+    //the runs with and without the feature are kept apart in the reports
+    let mut group = c.benchmark_group(if cfg!(feature = "simd") { "simd" } else { "scalar" });
+    group.bench_function("envelope", |b| b.iter(|| Envelope::of_slice(black_box(&ring))));
+    group.bench_function("signed area", |b| b.iter(|| ring_signed_area(black_box(&ring))));
+    group.bench_function("point in polygon", |b| b.iter(|| locate_in_ring(black_box(&point), black_box(&ring))));
+    group.finish();
+}
+
+criterion_group!(benches, loops);
+criterion_main!(benches);
@@ -0,0 +1,105 @@
+//! Times the main operations of the library on small, medium and huge geometries with criterion,
+//! which reports the changes in their performance since the previous run.
+//!
+//! Run `cargo bench --bench suite`, or `cargo bench --bench suite -- <filter>` to only time the
+//! operations whose name matches the filter.
+
+mod common;
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use geoms::algorithm::point_in_polygon::{locate_in_polygon, IndexedPointInAreaLocator};
+use geoms::coord;
+use geoms::coordinate::Coordinate;
+use geoms::geometry::Geometry;
+use geoms::io::{geojson, wkb, wkt};
+use geoms::operation::distance::IndexedFacetDistance;
+
+use common::{star, Fixtures, SIZES};
+
+fn parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parsing");
+    for (size, n) in SIZES {
+        let polygon = Fixtures::new(n).polygon;
+        let (text, bytes, json) = (polygon.as_text(), polygon.as_binary(), polygon.as_geojson());
+        group.bench_with_input(BenchmarkId::new("parse wkt", size), &text, |b, text| b.iter(|| wkt::read(text).unwrap()));
+        group.bench_with_input(BenchmarkId::new("parse wkb", size), &bytes, |b, bytes| b.iter(|| wkb::read(bytes).unwrap()));
+        group.bench_with_input(BenchmarkId::new("parse geojson", size), &json, |b, json| b.iter(|| geojson::read(json).unwrap()));
+        group.bench_with_input(BenchmarkId::new("write wkt", size), &polygon, |b, polygon| b.iter(|| polygon.as_text()));
+    }
+    group.finish();
+}
+
+fn measures(c: &mut Criterion) {
+    let mut group = c.benchmark_group("measures");
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        group.bench_with_input(BenchmarkId::new("area", size), &fixtures.polygon, |b, polygon| b.iter(|| polygon.area()));
+        group.bench_with_input(BenchmarkId::new("length", size), &fixtures.line, |b, line| b.iter(|| line.length()));
+    }
+    group.finish();
+}
+
+fn point_in_polygon(c: &mut Criterion) {
+    let mut group = c.benchmark_group("point in polygon x100");
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        let rings = vec![star((0.0, 0.0), 100.0, 60.0, n)];
+        let locator = IndexedPointInAreaLocator::new(&fixtures.polygon);
+        group.bench_function(BenchmarkId::new("plain", size), |b| {
+            b.iter(|| {
+                for p in &fixtures.points {
+                    black_box(locate_in_polygon(p, &rings));
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("indexed", size), |b| {
+            b.iter(|| {
+                for p in &fixtures.points {
+                    black_box(locator.locate(p));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn distance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("distance");
+    group.sample_size(10);
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        group.bench_function(BenchmarkId::new("plain", size), |b| b.iter(|| fixtures.line.distance(&fixtures.far_line)));
+        group.bench_function(BenchmarkId::new("indexed", size), |b| {
+            b.iter(|| IndexedFacetDistance::new(&fixtures.line).distance(&fixtures.far_line))
+        });
+    }
+    group.finish();
+}
+
+fn overlay(c: &mut Criterion) {
+    let mut group = c.benchmark_group("overlay");
+    group.sample_size(10).measurement_time(Duration::from_secs(2));
+    for (size, n) in SIZES {
+        let fixtures = Fixtures::new(n);
+        group.bench_function(BenchmarkId::new("union", size), |b| b.iter(|| fixtures.disc.union(&fixtures.shifted).unwrap()));
+        group.bench_function(BenchmarkId::new("intersection", size), |b| {
+            b.iter(|| fixtures.disc.intersection(&fixtures.shifted).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn buffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("buffer");
+    let point = Geometry::Point { coordinates: coord!(0, 0) };
+    for (size, n) in SIZES {
+        group.bench_with_input(BenchmarkId::new("circle", size), &n, |b, &n| b.iter(|| point.buffer_circle(10.0, n).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parsing, measures, point_in_polygon, distance, overlay, buffer);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+//! Low level geometric algorithms shared by the geometry operations.
+
+pub mod alpha_shape;
+pub mod area;
+pub mod calipers;
+pub mod centroid;
+pub mod convex_decomposition;
+pub mod convex_hull;
+pub mod dd;
+pub mod label;
+pub mod line_intersection;
+pub mod line_metrics;
+pub mod locate;
+pub mod plane;
+pub mod point_in_polygon;
+pub mod relate;
+pub mod shape_match;
+#[cfg(feature = "simd")]
+pub(crate) mod simd;
+pub mod skeleton;
+
+pub use alpha_shape::alpha_shape;
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::algorithm::area::ring_signed_area;
+use crate::cluster::DisjointSet;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::triangulate::delaunay;
+
+/// Returns the alpha shape of a set of points, the outline of the region covered by the
+/// triangles of their Delaunay triangulation whose circumscribed circle has a radius of at most
+/// alpha. Large values give the convex hull, while smaller ones carve concavities and holes into
+/// it and split it where the points are sparse.
+///
+/// The result is a multi polygon with counter clockwise shells and clockwise holes, the largest
+/// polygon first. Where the region touches itself at a point, the rings are split there, so that
+/// they are simple. Points and edges not covered by any triangle are left out.
+///
+/// # Arguments
+///
+/// * `points` - The points to outline.
+/// * `alpha` - The largest circumscribed radius of the triangles of the shape.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::alpha_shape;
+///
+/// //a 4 by 4 grid of points with its middle row of the top half missing
+/// let mut points = Vec::new();
+/// for x in 0..5 {
+///     for y in 0..5 {
+///         if !(y == 3 && x > 0 && x < 4) {
+///             points.push(coord!(x, y));
+///         }
+///     }
+/// }
+/// assert_eq!(alpha_shape(&points, 10.0).area(), 16.0);
+/// //small triangles cannot bridge the missing points, leaving the bottom half and two wedges
+/// //along the sides of the top half
+/// assert_eq!(alpha_shape(&points, 0.75).area(), 10.0);
+/// ```
+pub fn alpha_shape(points: &[Coordinate], alpha: f64) -> Geometry {
+    let triangles: Vec<[usize; 3]> = delaunay(points)
+        .into_iter()
+        .filter(|t| circumradius(&points[t[0]], &points[t[1]], &points[t[2]]) <= alpha)
+        .collect();
+
+    //the triangles sharing an edge belong to the same polygon
+    let mut owners: HashMap<(usize, usize), usize> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            owners.insert((t[k], t[(k + 1) % 3]), i);
+        }
+    }
+    let mut sets = DisjointSet::new(triangles.len());
+    for (&(a, b), &i) in &owners {
+        if let Some(&j) = owners.get(&(b, a)) {
+            sets.union_into(i.max(j), i.min(j));
+        }
+    }
+
+    //the edges without a neighbouring triangle, with the inside of the shape on their left
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut boundary: Vec<(usize, usize)> = owners.keys().filter(|(a, b)| !owners.contains_key(&(*b, *a))).copied().collect();
+    boundary.sort();
+    for &(a, b) in &boundary {
+        outgoing.entry(a).or_default().push(b);
+    }
+
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    let mut shells: HashMap<usize, Vec<Coordinate>> = HashMap::new();
+    let mut holes: HashMap<usize, Vec<Vec<Coordinate>>> = HashMap::new();
+    for &start in &boundary {
+        if visited.contains(&start) {
+            continue;
+        }
+        let mut ring = vec![start.0];
+        let (mut a, mut b) = start;
+        visited.insert(start);
+        while b != start.0 {
+            ring.push(b);
+            //where several edges leave the vertex, the one bounding the same part of the inside
+            //is the first one clockwise from the way back
+            let back = (points[a].y() - points[b].y()).atan2(points[a].x() - points[b].x());
+            let c = outgoing[&b]
+                .iter()
+                .copied()
+                .filter(|&c| !visited.contains(&(b, c)))
+                .min_by(|&c, &d| {
+                    let turn = |e: usize| {
+                        let angle = (points[e].y() - points[b].y()).atan2(points[e].x() - points[b].x());
+                        (back - angle).rem_euclid(std::f64::consts::TAU)
+                    };
+                    turn(c).total_cmp(&turn(d))
+                });
+            let Some(c) = c else {
+                break;
+            };
+            visited.insert((b, c));
+            (a, b) = (b, c);
+        }
+        let mut coordinates: Vec<Coordinate> = ring.iter().map(|&v| points[v].clone()).collect();
+        //rings start at their lowest left vertex, so the result does not depend on hashing
+        let first = (0..coordinates.len())
+            .min_by(|&i, &j| {
+                let (p, q) = (&coordinates[i], &coordinates[j]);
+                p.x().total_cmp(&q.x()).then(p.y().total_cmp(&q.y()))
+            })
+            .unwrap_or(0);
+        coordinates.rotate_left(first);
+        coordinates.push(coordinates[0].clone());
+        let polygon = sets.find(owners[&start]);
+        if ring_signed_area(&coordinates) > 0.0 {
+            shells.insert(polygon, coordinates);
+        } else {
+            holes.entry(polygon).or_default().push(coordinates);
+        }
+    }
+
+    let mut polygons: Vec<Vec<Vec<Coordinate>>> = shells
+        .into_iter()
+        .map(|(polygon, shell)| {
+            let mut rings = vec![shell];
+            let mut inner = holes.remove(&polygon).unwrap_or_default();
+            inner.sort_by(|p, q| p[0].x().total_cmp(&q[0].x()).then(p[0].y().total_cmp(&q[0].y())));
+            rings.extend(inner);
+            rings
+        })
+        .collect();
+    polygons.sort_by(|p, q| {
+        let area = |rings: &Vec<Vec<Coordinate>>| rings.iter().map(|r| ring_signed_area(r)).sum::<f64>();
+        area(q).total_cmp(&area(p)).then(p[0][0].x().total_cmp(&q[0][0].x())).then(p[0][0].y().total_cmp(&q[0][0].y()))
+    });
+    Geometry::MultiPolygon { coordinates: polygons }
+}
+
+/// Returns the radius of the circle through the three points, infinite if they are collinear.
+fn circumradius(a: &Coordinate, b: &Coordinate, c: &Coordinate) -> f64 {
+    let (ab, bc, ca) = ((b.x() - a.x()).hypot(b.y() - a.y()), (c.x() - b.x()).hypot(c.y() - b.y()), (a.x() - c.x()).hypot(a.y() - c.y()));
+    let doubled_area = ((b.x() - a.x()) * (c.y() - a.y()) - (b.y() - a.y()) * (c.x() - a.x())).abs();
+    if doubled_area == 0.0 {
+        return f64::INFINITY;
+    }
+    ab * bc * ca / (2.0 * doubled_area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_holes_and_pinches() {
+        //a 6 by 6 grid missing the points around its middle, which opens a hole reaching one
+        //unit beyond the missing points
+        let mut points = Vec::new();
+        for x in 0..7 {
+            for y in 0..7 {
+                if !((2..=4).contains(&x) && (2..=4).contains(&y)) || (x != 3 && y != 3) {
+                    points.push(coord!(x, y));
+                }
+            }
+        }
+        let shape = alpha_shape(&points, 0.75);
+        let Geometry::MultiPolygon { coordinates } = &shape else { panic!("{:?}", shape) };
+        assert_eq!(coordinates.len(), 1);
+        assert_eq!(coordinates[0].len(), 2);
+        assert_eq!(coordinates[0][1], vec![
+            coord!(1, 3), coord!(2, 4), coord!(3, 5), coord!(4, 4), coord!(5, 3), coord!(4, 2), coord!(3, 1), coord!(2, 2), coord!(1, 3)
+        ]);
+        assert_eq!(shape.area(), 28.0);
+
+        //two triangles touching at a vertex make two simple polygons, the wide side triangles
+        //between them being left out
+        let points = vec![coord!(0, 0), coord!(2, 0), coord!(1, 2), coord!(0, 4), coord!(2, 4)];
+        let shape = alpha_shape(&points, 1.5);
+        let Geometry::MultiPolygon { coordinates } = &shape else { panic!("{:?}", shape) };
+        assert_eq!(coordinates, &vec![
+            vec![vec![coord!(0, 0), coord!(2, 0), coord!(1, 2), coord!(0, 0)]],
+            vec![vec![coord!(0, 4), coord!(1, 2), coord!(2, 4), coord!(0, 4)]],
+        ]);
+    }
+
+    #[test]
+    fn test_large_alpha_is_convex_hull() {
+        let points = vec![coord!(0, 0), coord!(10, 1), coord!(7, 6), coord!(3, 3), coord!(1, 8), coord!(5, 2)];
+        let shape = alpha_shape(&points, f64::INFINITY);
+        let Geometry::MultiPolygon { coordinates } = &shape else { panic!("{:?}", shape) };
+        assert_eq!(Geometry::Polygon { coordinates: coordinates[0].clone() }, crate::algorithm::convex_hull::convex_hull(&points));
+        assert_eq!(alpha_shape(&points, 0.1), Geometry::MultiPolygon { coordinates: vec![] });
+        assert_eq!(alpha_shape(&[coord!(0, 0), coord!(1, 1), coord!(2, 2)], 10.0), Geometry::MultiPolygon { coordinates: vec![] });
+    }
+}
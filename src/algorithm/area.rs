@@ -0,0 +1,138 @@
+use crate::coordinate::Coordinate;
+
+/// Returns the signed planar area of a ring, positive if the ring is counter clockwise and
+/// negative if it is clockwise.
+///
+/// # Arguments
+///
+/// * `ring` - The coordinates of a closed ring.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::area::ring_signed_area;
+///
+/// let ring = vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)];
+/// assert_eq!(ring_signed_area(&ring), 4.0);
+///
+/// let reversed: Vec<Coordinate> = ring.into_iter().rev().collect();
+/// assert_eq!(ring_signed_area(&reversed), -4.0);
+/// ```
+pub fn ring_signed_area(ring: &[Coordinate]) -> f64 {
+    if ring.len() < 3 {
+        return 0.0;
+    }
+    //shoelace formula relative to the first vertex to reduce rounding errors
+    #[cfg(feature = "simd")]
+    let sum = crate::algorithm::simd::shoelace(ring);
+    #[cfg(not(feature = "simd"))]
+    let sum: f64 = {
+        let origin = &ring[0];
+        ring.windows(2)
+            .map(|w| {
+                let (ax, ay) = (w[0].x() - origin.x(), w[0].y() - origin.y());
+                let (bx, by) = (w[1].x() - origin.x(), w[1].y() - origin.y());
+                ax * by - bx * ay
+            })
+            .sum()
+    };
+    sum / 2.0
+}
+
+/// Returns the planar area of a ring.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::area::ring_area;
+///
+/// let ring = vec![coord!(0, 0), coord!(0, 2), coord!(2, 2), coord!(2, 0), coord!(0, 0)];
+/// assert_eq!(ring_area(&ring), 4.0);
+/// ```
+pub fn ring_area(ring: &[Coordinate]) -> f64 {
+    ring_signed_area(ring).abs()
+}
+
+/// Returns the normal vector of a ring computed with Newell's method. Its length is twice the
+/// area of the ring, measured in the plane of the ring.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::area::ring_normal;
+///
+/// let ring = vec![coord!(0, 0, 0), coord!(2, 0, 0), coord!(2, 0, 2), coord!(0, 0, 2), coord!(0, 0, 0)];
+/// assert_eq!(ring_normal(&ring), [0.0, -8.0, 0.0]);
+/// ```
+pub fn ring_normal(ring: &[Coordinate]) -> [f64; 3] {
+    let mut normal = [0.0; 3];
+    if ring.is_empty() {
+        return normal;
+    }
+    let origin = &ring[0];
+    for w in ring.windows(2) {
+        let (ax, ay, az) = (w[0].x() - origin.x(), w[0].y() - origin.y(), w[0].z() - origin.z());
+        let (bx, by, bz) = (w[1].x() - origin.x(), w[1].y() - origin.y(), w[1].z() - origin.z());
+        normal[0] += (ay - by) * (az + bz);
+        normal[1] += (az - bz) * (ax + bx);
+        normal[2] += (ax - bx) * (ay + by);
+    }
+    normal
+}
+
+/// Returns the area of a ring measured in its own plane, rather than in its planar projection.
+/// The ring is expected to be planar.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::area::ring_area_3d;
+///
+/// //a vertical wall
+/// let ring = vec![coord!(0, 0, 0), coord!(2, 0, 0), coord!(2, 0, 2), coord!(0, 0, 2), coord!(0, 0, 0)];
+/// assert_eq!(ring_area_3d(&ring), 4.0);
+/// ```
+pub fn ring_area_3d(ring: &[Coordinate]) -> f64 {
+    let [x, y, z] = ring_normal(ring);
+    (x * x + y * y + z * z).sqrt() / 2.0
+}
+
+/// Returns the planar area of a polygon given as its shell followed by its holes.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::area::polygon_area;
+///
+/// let polygon = vec![
+///     vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+///     vec![coord!(1, 1), coord!(2, 1), coord!(2, 2), coord!(1, 2), coord!(1, 1)],
+/// ];
+/// assert_eq!(polygon_area(&polygon), 15.0);
+/// ```
+pub fn polygon_area(rings: &[Vec<Coordinate>]) -> f64 {
+    polygon_area_with(rings, ring_area)
+}
+
+/// Returns the area of a polygon measured in its own plane.
+pub fn polygon_area_3d(rings: &[Vec<Coordinate>]) -> f64 {
+    polygon_area_with(rings, ring_area_3d)
+}
+
+fn polygon_area_with(rings: &[Vec<Coordinate>], area: fn(&[Coordinate]) -> f64) -> f64 {
+    let mut rings = rings.iter();
+    match rings.next() {
+        Some(shell) => area(shell) - rings.map(|hole| area(hole)).sum::<f64>(),
+        None => 0.0,
+    }
+}
@@ -0,0 +1,182 @@
+//! Rectangles enclosing or approximating shapes, found by rotating calipers around the convex
+//! hull or by fitting the edges of a polygon, as used to square building outlines.
+
+use crate::algorithm::area::ring_signed_area;
+use crate::algorithm::convex_hull::convex_hull_of;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the rectangle of smallest area containing a set of points, in any orientation, as a
+/// counter clockwise polygon. Collinear points give the line string between the furthest two,
+/// a single point gives that point, and no points give an empty geometry collection.
+///
+/// One side of the smallest rectangle lies along an edge of the convex hull, so the calipers
+/// are rotated around the hull, tracking the furthest vertices along and across every edge in
+/// linear time.
+///
+/// # Arguments
+///
+/// * `points` - The points to enclose.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::calipers::oriented_envelope;
+///
+/// //a square turned by 45 degrees is its own oriented envelope
+/// let diamond = vec![coord!(1, 0), coord!(2, 1), coord!(1, 2), coord!(0, 1)];
+/// let envelope = oriented_envelope(&diamond);
+/// assert!((envelope.area() - 2.0).abs() < 1e-12);
+/// ```
+pub fn oriented_envelope(points: &[Coordinate]) -> Geometry {
+    oriented_envelope_of(points)
+}
+
+/// Returns the oriented envelope of the points, which are only borrowed.
+pub(crate) fn oriented_envelope_of<'a>(points: impl IntoIterator<Item = &'a Coordinate>) -> Geometry {
+    let hull = match convex_hull_of(points) {
+        Geometry::Polygon { mut coordinates } => {
+            let mut ring = coordinates.swap_remove(0);
+            ring.pop();
+            ring
+        }
+        degenerate => return degenerate,
+    };
+    let n = hull.len();
+    let dot = |k: usize, origin: &Coordinate, (dx, dy): (f64, f64)| {
+        (hull[k % n].x() - origin.x()) * dx + (hull[k % n].y() - origin.y()) * dy
+    };
+    //the furthest vertices along the edge, across it and back along it
+    let (mut ahead, mut across, mut behind) = (1, 1, 1);
+    let mut best: Option<(f64, [Coordinate; 4])> = None;
+    for i in 0..n {
+        let (p, q) = (&hull[i], &hull[(i + 1) % n]);
+        let length = p.distance_2d(q);
+        let u = ((q.x() - p.x()) / length, (q.y() - p.y()) / length);
+        let v = (-u.1, u.0);
+        ahead = ahead.max(i + 1);
+        while dot(ahead + 1, p, u) > dot(ahead, p, u) {
+            ahead += 1;
+        }
+        across = across.max(ahead);
+        while dot(across + 1, p, v) > dot(across, p, v) {
+            across += 1;
+        }
+        behind = behind.max(across);
+        while dot(behind + 1, p, u) < dot(behind, p, u) {
+            behind += 1;
+        }
+        let (min_u, max_u, max_v) = (dot(behind, p, u), dot(ahead, p, u), dot(across, p, v));
+        let area = (max_u - min_u) * max_v;
+        if best.as_ref().is_none_or(|(smallest, _)| area < *smallest) {
+            let corner = |s: f64, t: f64| Coordinate::new(p.x() + u.0 * s + v.0 * t, p.y() + u.1 * s + v.1 * t, 0.0);
+            best = Some((area, [corner(min_u, 0.0), corner(max_u, 0.0), corner(max_u, max_v), corner(min_u, max_v)]));
+        }
+    }
+    let (_, [a, b, c, d]) = best.expect("The hull has at least three vertices");
+    Geometry::Polygon { coordinates: vec![vec![a.clone(), b, c, d, a]] }
+}
+
+/// Returns the rectangle fitting a ring best in the least squares sense, as a closed counter
+/// clockwise ring, or None if the ring has no area. Meant to square noisy building outlines.
+///
+/// The orientation of the rectangle is the mean direction of the edges modulo a right angle,
+/// weighted by their length. Every edge is then given to the side of the rectangle its
+/// direction is closest to, and each side is placed at the position minimizing the squared
+/// distance to its edges. Sides without edges touch the furthest vertex in their direction.
+///
+/// # Arguments
+///
+/// * `ring` - The vertices of the ring, in any direction.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::calipers::fit_rectangle;
+///
+/// //a digitized building with a small kink along its bottom side
+/// let outline = vec![coord!(0, 0), coord!(5, 0.2), coord!(10, 0), coord!(10, 4), coord!(0, 4), coord!(0, 0)];
+/// let rectangle = fit_rectangle(&outline).unwrap();
+/// assert_eq!(rectangle.len(), 5);
+/// assert!(rectangle.iter().all(|c| c.y().abs() < 0.2 || (c.y() - 4.0).abs() < 0.2));
+/// ```
+pub fn fit_rectangle(ring: &[Coordinate]) -> Option<Vec<Coordinate>> {
+    let signed_area = ring_signed_area(ring);
+    if ring.len() < 4 || signed_area == 0.0 || signed_area.is_nan() {
+        return None;
+    }
+    //going counter clockwise, the bottom, right, top and left sides head at 0, 90, 180 and 270 degrees
+    let direction = if signed_area > 0.0 { 1.0 } else { -1.0 };
+    let edges: Vec<(&Coordinate, &Coordinate)> = ring
+        .windows(2)
+        .filter(|w| !w[0].equals_2d(&w[1]))
+        .map(|w| if direction > 0.0 { (&w[0], &w[1]) } else { (&w[1], &w[0]) })
+        .collect();
+    let angle = |(a, b): &(&Coordinate, &Coordinate)| (b.y() - a.y()).atan2(b.x() - a.x());
+    let theta = dominant_direction(ring);
+    let (s, c) = theta.sin_cos();
+    let rotate = |p: &Coordinate| (p.x() * c + p.y() * s, -p.x() * s + p.y() * c);
+
+    //the length weighted sum of the positions of the edges of every side, and their length
+    let mut sides = [(0.0, 0.0); 4];
+    for edge in &edges {
+        let side = ((angle(edge) - theta) / std::f64::consts::FRAC_PI_2).round().rem_euclid(4.0) as usize;
+        let (a, b) = (rotate(edge.0), rotate(edge.1));
+        let middle = if side.is_multiple_of(2) { (a.1 + b.1) / 2.0 } else { (a.0 + b.0) / 2.0 };
+        let length = edge.0.distance_2d(edge.1);
+        sides[side].0 += middle * length;
+        sides[side].1 += length;
+    }
+    let rotated: Vec<(f64, f64)> = ring.iter().map(rotate).collect();
+    let extent = |f: fn(&(f64, f64)) -> f64, max: bool| {
+        let values = rotated.iter().map(f);
+        if max { values.fold(f64::NEG_INFINITY, f64::max) } else { values.fold(f64::INFINITY, f64::min) }
+    };
+    let position = |side: usize, fallback: f64| if sides[side].1 > 0.0 { sides[side].0 / sides[side].1 } else { fallback };
+    let bottom = position(0, extent(|p| p.1, false));
+    let right = position(1, extent(|p| p.0, true));
+    let top = position(2, extent(|p| p.1, true));
+    let left = position(3, extent(|p| p.0, false));
+    let corner = |x: f64, y: f64| Coordinate::new(x * c - y * s, x * s + y * c, 0.0);
+    let first = corner(left, bottom);
+    Some(vec![first.clone(), corner(right, bottom), corner(right, top), corner(left, top), first])
+}
+
+/// Returns the mean direction of the segments of a line modulo a right angle, weighted by their
+/// length, in radians between -π/4 and π/4.
+pub(crate) fn dominant_direction(line: &[Coordinate]) -> f64 {
+    let (sin, cos) = line.windows(2).fold((0.0, 0.0), |(sin, cos), w| {
+        let (dx, dy) = (w[1].x() - w[0].x(), w[1].y() - w[0].y());
+        let (angle, length) = (dy.atan2(dx), dx.hypot(dy));
+        (sin + length * (4.0 * angle).sin(), cos + length * (4.0 * angle).cos())
+    });
+    sin.atan2(cos) / 4.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_rectangles_of_a_turned_rectangle() {
+        //a 4 by 2 rectangle turned by 30 degrees, with an extra vertex inside
+        let (s, c) = 30f64.to_radians().sin_cos();
+        let turn = |x: f64, y: f64| coord!(x * c - y * s + 7.0, x * s + y * c - 3.0);
+        let ring = [turn(0.0, 0.0), turn(4.0, 0.0), turn(4.0, 2.0), turn(0.0, 2.0), turn(0.0, 0.0)];
+        let points: Vec<Coordinate> = ring.iter().cloned().chain([turn(1.0, 1.0)]).collect();
+        let envelope = oriented_envelope(&points);
+        assert!((envelope.area() - 8.0).abs() < 1e-9);
+        //the same rectangle clockwise fits itself
+        let reversed: Vec<Coordinate> = ring.iter().rev().cloned().collect();
+        let fitted = fit_rectangle(&reversed).unwrap();
+        assert!((ring_signed_area(&fitted) - 8.0).abs() < 1e-9);
+        assert!(fitted.iter().all(|a| ring.iter().any(|b| a.equals_2d_with_tolerance(b, 1e-9))));
+        assert_eq!(oriented_envelope(&[coord!(0, 0), coord!(1, 1), coord!(3, 3)]).length(), 18f64.sqrt());
+        assert_eq!(fit_rectangle(&[coord!(0, 0), coord!(1, 1), coord!(0, 0)]), None);
+    }
+}
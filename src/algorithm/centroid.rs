@@ -0,0 +1,127 @@
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the planar centroid of a geometry, or None if it has no coordinates.
+///
+/// Only the parts of the highest dimension are taken into account: the centroid of the area of
+/// the polygons, otherwise of the length of the lines, otherwise the mean of the points. Polygons
+/// without area are treated as their rings, and lines without length as their points, so
+/// degenerate geometries still get a centroid.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::algorithm::centroid::centroid;
+///
+/// let polygon = Geometry::Polygon { coordinates: vec![
+///     vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+///     vec![coord!(2, 0), coord!(4, 0), coord!(4, 2), coord!(2, 2), coord!(2, 0)],
+/// ] };
+/// assert_eq!(centroid(&polygon), Some(coord!(5.0 / 3.0, 7.0 / 3.0)));
+///
+/// //the point is ignored since the line has a length
+/// let collection = Geometry::GeometryCollection { geometries: vec![
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 0)] },
+///     Geometry::Point { coordinates: coord!(10, 10) },
+/// ] };
+/// assert_eq!(centroid(&collection), Some(coord!(1, 0)));
+/// ```
+pub fn centroid(geometry: &Geometry) -> Option<Coordinate> {
+    let mut sums = Sums::default();
+    sums.add(geometry);
+    let (x, y) = if sums.area != 0.0 {
+        (sums.area_x / sums.area, sums.area_y / sums.area)
+    } else if sums.length > 0.0 {
+        (sums.length_x / sums.length, sums.length_y / sums.length)
+    } else if sums.points > 0 {
+        (sums.points_x / sums.points as f64, sums.points_y / sums.points as f64)
+    } else {
+        return None;
+    };
+    Some(Coordinate::new(x, y, 0.0))
+}
+
+/// The moments of the parts of every dimension, to pick the highest one with a measure at the end.
+#[derive(Default)]
+struct Sums {
+    area: f64,
+    area_x: f64,
+    area_y: f64,
+    length: f64,
+    length_x: f64,
+    length_y: f64,
+    points: usize,
+    points_x: f64,
+    points_y: f64,
+}
+
+impl Sums {
+    fn add(&mut self, geometry: &Geometry) {
+        match geometry {
+            Geometry::Point { coordinates } => self.add_points(std::slice::from_ref(coordinates)),
+            Geometry::MultiPoint { coordinates } => self.add_points(coordinates),
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => self.add_line(coordinates),
+            Geometry::MultiLineString { coordinates } => coordinates.iter().for_each(|line| self.add_line(line)),
+            Geometry::Polygon { coordinates } => self.add_polygon(coordinates),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter().for_each(|polygon| self.add_polygon(polygon))
+            }
+            Geometry::Tin { coordinates } => {
+                coordinates.iter().for_each(|triangle| self.add_polygon(std::slice::from_ref(triangle)))
+            }
+            Geometry::GeometryCollection { geometries } => geometries.iter().for_each(|g| self.add(g)),
+        }
+    }
+
+    fn add_points(&mut self, points: &[Coordinate]) {
+        for p in points {
+            self.points += 1;
+            self.points_x += p.x();
+            self.points_y += p.y();
+        }
+    }
+
+    fn add_line(&mut self, line: &[Coordinate]) {
+        for w in line.windows(2) {
+            let length = (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y());
+            self.length += length;
+            self.length_x += length * (w[0].x() + w[1].x()) / 2.0;
+            self.length_y += length * (w[0].y() + w[1].y()) / 2.0;
+        }
+        self.add_points(line);
+    }
+
+    fn add_polygon(&mut self, rings: &[Vec<Coordinate>]) {
+        for (i, ring) in rings.iter().enumerate() {
+            //the shell adds its area whatever its orientation, and the holes remove theirs
+            let (area, x, y) = ring_moments(ring);
+            let sign = if (i == 0) == (area >= 0.0) { 1.0 } else { -1.0 };
+            self.area += sign * area;
+            self.area_x += sign * x;
+            self.area_y += sign * y;
+            self.add_line(ring);
+        }
+    }
+}
+
+/// Returns the signed area of a closed ring and its first moments about the axes.
+fn ring_moments(ring: &[Coordinate]) -> (f64, f64, f64) {
+    let Some(origin) = ring.first() else {
+        return (0.0, 0.0, 0.0);
+    };
+    //relative to the first vertex to reduce rounding errors
+    let (mut area, mut x, mut y) = (0.0, 0.0, 0.0);
+    for w in ring.windows(2) {
+        let (ax, ay) = (w[0].x() - origin.x(), w[0].y() - origin.y());
+        let (bx, by) = (w[1].x() - origin.x(), w[1].y() - origin.y());
+        let cross = ax * by - bx * ay;
+        area += cross;
+        x += (ax + bx) * cross;
+        y += (ay + by) * cross;
+    }
+    area /= 2.0;
+    (area, x / 6.0 + area * origin.x(), y / 6.0 + area * origin.y())
+}
@@ -0,0 +1,129 @@
+//! Decomposition of polygons into convex parts, the shapes collision detection and physics
+//! engines work with.
+
+use std::collections::HashMap;
+
+use crate::algorithm::line_intersection::{orientation, Orientation};
+use crate::coordinate::Coordinate;
+use crate::triangulate::triangulate_polygon;
+
+/// The hashable planar location of a coordinate.
+type Key = (u64, u64);
+
+/// Returns a polygon split into convex polygons covering it without overlapping, as closed
+/// counter clockwise rings without holes or collinear vertices.
+///
+/// The polygon is triangulated by ear clipping, then neighbouring parts are merged across the
+/// diagonals they share as long as the merged part stays convex, following Hertel and
+/// Mehlhorn. Only vertices of the polygon are used, and the parts are at most four times as many
+/// as in the smallest convex decomposition.
+///
+/// # Arguments
+///
+/// * `polygon` - The shell and the holes of the polygon, expected to be valid.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::convex_decomposition::convex_decomposition;
+///
+/// //an L shaped room splits into two rectangles
+/// let room = vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 1), coord!(1, 1), coord!(1, 2), coord!(0, 2), coord!(0, 0)]];
+/// let parts = convex_decomposition(&room);
+/// assert_eq!(parts.len(), 2);
+/// assert!(parts.iter().all(|part| part.len() == 5));
+/// ```
+pub fn convex_decomposition(polygon: &[Vec<Coordinate>]) -> Vec<Vec<Coordinate>> {
+    let mut parts: Vec<Option<Vec<Coordinate>>> =
+        triangulate_polygon(polygon).into_iter().map(|t| Some(t.to_vec())).collect();
+    //the part on the left of every directed edge
+    let mut owners: HashMap<(Key, Key), usize> = HashMap::new();
+    for (k, part) in parts.iter().enumerate().filter_map(|(k, p)| Some((k, p.as_ref()?))) {
+        for i in 0..part.len() {
+            owners.insert((part[i].key(), part[(i + 1) % part.len()].key()), k);
+        }
+    }
+    for k in 0..parts.len() {
+        let mut i = 0;
+        while let Some(part) = &parts[k] {
+            if i == part.len() {
+                break;
+            }
+            let (a, b) = (part[i].key(), part[(i + 1) % part.len()].key());
+            let merged = match owners.get(&(b, a)) {
+                Some(&other) if other != k => parts[other].as_ref().and_then(|o| merge(part, i, o)).map(|m| (other, m)),
+                _ => None,
+            };
+            match merged {
+                Some((other, merged)) => {
+                    owners.remove(&(a, b));
+                    owners.remove(&(b, a));
+                    for j in 0..merged.len() {
+                        owners.insert((merged[j].key(), merged[(j + 1) % merged.len()].key()), k);
+                    }
+                    parts[k] = Some(merged);
+                    parts[other] = None;
+                    i = 0;
+                }
+                None => i += 1,
+            }
+        }
+    }
+    parts
+        .into_iter()
+        .flatten()
+        .map(|part| {
+            let n = part.len();
+            let mut ring: Vec<Coordinate> = (0..n)
+                .filter(|&i| orientation(&part[(i + n - 1) % n], &part[i], &part[(i + 1) % n]) != Orientation::Collinear)
+                .map(|i| part[i].clone())
+                .collect();
+            ring.push(ring[0].clone());
+            ring
+        })
+        .collect()
+}
+
+/// Returns the union of two counter clockwise parts sharing the edge of the first one at the
+/// index, or None if it is not convex.
+fn merge(part: &[Coordinate], i: usize, other: &[Coordinate]) -> Option<Vec<Coordinate>> {
+    let (n, m) = (part.len(), other.len());
+    let (a, b) = (&part[i], &part[(i + 1) % n]);
+    let j = (0..m).find(|&j| other[j].equals_2d(b) && other[(j + 1) % m].equals_2d(a))?;
+    //around the part from the far end of the edge back to its start, then around the other part
+    let merged: Vec<Coordinate> = (1..=n)
+        .map(|k| part[(i + k) % n].clone())
+        .chain((2..m).map(|k| other[(j + k) % m].clone()))
+        .collect();
+    let len = merged.len();
+    (0..len)
+        .all(|k| orientation(&merged[(k + len - 1) % len], &merged[k], &merged[(k + 1) % len]) != Orientation::Clockwise)
+        .then_some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::area::{polygon_area, ring_signed_area};
+    use crate::coord;
+
+    #[test]
+    fn test_decomposition_of_a_polygon_with_a_hole() {
+        let frame = vec![
+            vec![coord!(0, 0), coord!(6, 0), coord!(6, 6), coord!(0, 6), coord!(0, 0)],
+            vec![coord!(2, 2), coord!(2, 4), coord!(4, 4), coord!(4, 2), coord!(2, 2)],
+        ];
+        let parts = convex_decomposition(&frame);
+        //a frame needs at least four convex parts
+        assert!(parts.len() >= 4);
+        let area: f64 = parts.iter().map(|part| ring_signed_area(part)).sum();
+        assert!((area - polygon_area(&frame)).abs() < 1e-12);
+        for part in &parts {
+            let n = part.len() - 1;
+            assert!((0..n).all(|i| orientation(&part[i], &part[i + 1], &part[(i + 2) % n]) == Orientation::CounterClockwise));
+        }
+        assert!(convex_decomposition(&[vec![coord!(0, 0), coord!(1, 1), coord!(2, 2), coord!(0, 0)]]).is_empty());
+    }
+}
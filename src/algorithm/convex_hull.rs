@@ -0,0 +1,63 @@
+use crate::algorithm::line_intersection::{orientation, Orientation};
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the convex hull of a set of points, computed with the monotone chain algorithm.
+/// The result is a counter clockwise polygon, or a line string or a point when the points are
+/// collinear or all equal, and an empty geometry collection when there are no points.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::algorithm::convex_hull::convex_hull;
+///
+/// let points = vec![coord!(0, 0), coord!(2, 0), coord!(1, 1), coord!(2, 2), coord!(0, 2)];
+/// assert_eq!(convex_hull(&points), Geometry::Polygon { coordinates: vec![
+///     vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]
+/// ] });
+///
+/// let points = vec![coord!(0, 0), coord!(1, 1), coord!(2, 2)];
+/// assert_eq!(convex_hull(&points), Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 2)] });
+/// ```
+pub fn convex_hull(points: &[Coordinate]) -> Geometry {
+    convex_hull_of(points)
+}
+
+/// Returns the convex hull of the points, which are only borrowed.
+pub(crate) fn convex_hull_of<'a>(points: impl IntoIterator<Item = &'a Coordinate>) -> Geometry {
+    let mut sorted: Vec<&Coordinate> = points.into_iter().collect();
+    sorted.sort_by(|a, b| a.x().total_cmp(&b.x()).then(a.y().total_cmp(&b.y())));
+    sorted.dedup_by(|a, b| a.equals_2d(b));
+
+    match sorted.len() {
+        0 => return Geometry::GeometryCollection { geometries: vec![] },
+        1 => return Geometry::Point { coordinates: sorted[0].clone() },
+        _ => {}
+    }
+
+    //lower and upper chains, keeping only strict left turns
+    let mut hull: Vec<&Coordinate> = Vec::with_capacity(sorted.len() + 1);
+    for pass in [sorted.clone(), sorted.iter().rev().copied().collect()] {
+        let start = hull.len();
+        for p in pass {
+            while hull.len() >= start + 2
+                && orientation(hull[hull.len() - 2], hull[hull.len() - 1], p) != Orientation::CounterClockwise
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        //the last point of each chain is the first of the next one
+        hull.pop();
+    }
+
+    if hull.len() < 3 {
+        return Geometry::LineString { coordinates: vec![sorted[0].clone(), sorted[sorted.len() - 1].clone()] };
+    }
+    let mut ring: Vec<Coordinate> = hull.into_iter().cloned().collect();
+    ring.push(ring[0].clone());
+    Geometry::Polygon { coordinates: vec![ring] }
+}
@@ -0,0 +1,385 @@
+//! Double-double arithmetic, representing a number as the unevaluated sum of two floating point
+//! numbers for about 106 bits of precision, and the geometric predicates built on it.
+//!
+//! The predicates first evaluate in plain floating point and only fall back to double-double
+//! arithmetic when the result is too close to zero for its sign to be trusted. With the `exact`
+//! feature, the few results still too close to zero in double-double arithmetic are evaluated
+//! again in exact rational arithmetic.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::coordinate::Coordinate;
+
+/// A number with about twice the precision of a `f64`, stored as a leading part and the rounding
+/// error of that part, which is at most half an ulp of it.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct DD {
+    hi: f64,
+    lo: f64,
+}
+
+impl DD {
+    /// Returns the number equal to the sum of the two parts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::algorithm::dd::DD;
+    ///
+    /// let x = DD::new(1.0, 1e-20);
+    /// assert_eq!((x - DD::from(1.0)).to_f64(), 1e-20);
+    /// ```
+    pub fn new(hi: f64, lo: f64) -> DD {
+        let (hi, lo) = two_sum(hi, lo);
+        DD { hi, lo }
+    }
+
+    /// Returns the leading part of the number.
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// Returns the trailing part of the number.
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Returns the number rounded to the nearest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        self.hi + self.lo
+    }
+
+    /// Returns the exact product of two floating point numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::algorithm::dd::DD;
+    ///
+    /// let x = 1.0 + f64::EPSILON;
+    /// let square = DD::product(x, x);
+    /// assert_eq!(square.hi(), 1.0 + 2.0 * f64::EPSILON);
+    /// assert_eq!(square.lo(), f64::EPSILON * f64::EPSILON);
+    /// ```
+    pub fn product(a: f64, b: f64) -> DD {
+        let hi = a * b;
+        DD { hi, lo: a.mul_add(b, -hi) }
+    }
+
+    /// Returns the exact difference of two floating point numbers.
+    pub fn difference(a: f64, b: f64) -> DD {
+        let (hi, lo) = two_sum(a, -b);
+        DD { hi, lo }
+    }
+
+    /// Returns -1, 0 or 1 as the number is negative, zero or positive.
+    pub fn signum(&self) -> i32 {
+        if self.hi > 0.0 {
+            1
+        } else if self.hi < 0.0 {
+            -1
+        } else {
+            0
+        }
+    }
+}
+
+impl From<f64> for DD {
+    fn from(value: f64) -> DD {
+        DD { hi: value, lo: 0.0 }
+    }
+}
+
+impl Neg for DD {
+    type Output = DD;
+
+    fn neg(self) -> DD {
+        DD { hi: -self.hi, lo: -self.lo }
+    }
+}
+
+impl Add for DD {
+    type Output = DD;
+
+    fn add(self, other: DD) -> DD {
+        let (hi, lo) = two_sum(self.hi, other.hi);
+        let (lo_hi, lo_lo) = two_sum(self.lo, other.lo);
+        let (hi, lo) = quick_two_sum(hi, lo + lo_hi);
+        let (hi, lo) = quick_two_sum(hi, lo + lo_lo);
+        DD { hi, lo }
+    }
+}
+
+impl Sub for DD {
+    type Output = DD;
+
+    fn sub(self, other: DD) -> DD {
+        self + -other
+    }
+}
+
+impl Mul for DD {
+    type Output = DD;
+
+    fn mul(self, other: DD) -> DD {
+        let product = DD::product(self.hi, other.hi);
+        let lo = product.lo + (self.hi * other.lo + self.lo * other.hi);
+        let (hi, lo) = quick_two_sum(product.hi, lo);
+        DD { hi, lo }
+    }
+}
+
+impl Div for DD {
+    type Output = DD;
+
+    fn div(self, other: DD) -> DD {
+        //long division, each step correcting the quotient by the remainder
+        let q1 = self.hi / other.hi;
+        let remainder = self - other * DD::from(q1);
+        let q2 = remainder.hi / other.hi;
+        let remainder = remainder - other * DD::from(q2);
+        let q3 = remainder.hi / other.hi;
+        let (hi, lo) = quick_two_sum(q1, q2);
+        DD { hi, lo } + DD::from(q3)
+    }
+}
+
+/// Bound of the rounding errors of the double-double evaluation of the predicates, relative to
+/// the sum of the magnitudes of their terms.
+#[cfg(feature = "exact")]
+const DD_ERROR: f64 = 1e-29;
+
+/// Returns the rounded sum of two numbers and its rounding error.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    (sum, (a - a_virtual) + (b - b_virtual))
+}
+
+/// Returns the rounded sum of two numbers and its rounding error, provided that `|a| >= |b|`.
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    (sum, b - (sum - a))
+}
+
+/// Returns the sign of the determinant of the orientation of the point `r` relative to the
+/// directed segment `p` -> `q`: 1 if counter clockwise, -1 if clockwise and 0 if collinear.
+/// The determinant is evaluated in floating point, and again in double-double arithmetic when its
+/// rounding errors could have changed its sign, then in exact rational arithmetic with the `exact`
+/// feature if they still could.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::dd::orientation_index;
+///
+/// assert_eq!(orientation_index(&coord!(0, 0), &coord!(1, 0), &coord!(1, 1)), 1);
+/// //a point a few ulps off a long segment
+/// let (p, q) = (coord!(0.5, 0.5), coord!(12.0, 12.0));
+/// assert_eq!(orientation_index(&p, &q, &coord!(24.0, 24.0 + 32.0 * f64::EPSILON)), 1);
+/// assert_eq!(orientation_index(&p, &q, &coord!(24.0, 24.0)), 0);
+/// ```
+pub fn orientation_index(p: &Coordinate, q: &Coordinate, r: &Coordinate) -> i32 {
+    let (left, right) = ((q.x() - p.x()) * (r.y() - p.y()), (q.y() - p.y()) * (r.x() - p.x()));
+    let det = left - right;
+    //bound of the rounding errors of the evaluation, after Shewchuk
+    if det.abs() > (left.abs() + right.abs()) * 3.4e-16 {
+        return if det > 0.0 { 1 } else { -1 };
+    }
+    let (qx, qy) = (DD::difference(q.x(), p.x()), DD::difference(q.y(), p.y()));
+    let (rx, ry) = (DD::difference(r.x(), p.x()), DD::difference(r.y(), p.y()));
+    let det = qx * ry - qy * rx;
+    //the products are exact when the differences are, and so is the sign of their difference
+    #[cfg(feature = "exact")]
+    if [qx, qy, rx, ry].iter().any(|d| d.lo() != 0.0) && det.to_f64().abs() <= (left.abs() + right.abs()) * DD_ERROR {
+        if let Some(sign) = exact::orientation_index(p, q, r) {
+            return sign;
+        }
+    }
+    det.signum()
+}
+
+/// Returns the sign of the in-circle determinant of the point `p` and the circle through `a`, `b`
+/// and `c`: 1 if the point is inside of the circle, -1 if it is outside of it and 0 if it lies on
+/// it, when the three points are counter clockwise. The signs are swapped for clockwise points.
+/// The determinant is evaluated as in [`orientation_index`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::dd::in_circle;
+///
+/// let (a, b, c) = (coord!(0, 0), coord!(2, 0), coord!(2, 2));
+/// assert_eq!(in_circle(&a, &b, &c, &coord!(1, 1)), 1);
+/// assert_eq!(in_circle(&a, &b, &c, &coord!(0, 2)), 0);
+/// assert_eq!(in_circle(&a, &b, &c, &coord!(3, 3)), -1);
+/// ```
+pub fn in_circle(a: &Coordinate, b: &Coordinate, c: &Coordinate, p: &Coordinate) -> i32 {
+    let (adx, ady) = (a.x() - p.x(), a.y() - p.y());
+    let (bdx, bdy) = (b.x() - p.x(), b.y() - p.y());
+    let (cdx, cdy) = (c.x() - p.x(), c.y() - p.y());
+    let (alift, blift, clift) = (adx * adx + ady * ady, bdx * bdx + bdy * bdy, cdx * cdx + cdy * cdy);
+    let (bc, ca, ab) = (bdx * cdy - cdx * bdy, cdx * ady - adx * cdy, adx * bdy - bdx * ady);
+    let det = alift * bc + blift * ca + clift * ab;
+    let permanent = alift * ((bdx * cdy).abs() + (cdx * bdy).abs())
+        + blift * ((cdx * ady).abs() + (adx * cdy).abs())
+        + clift * ((adx * bdy).abs() + (bdx * ady).abs());
+    //bound of the rounding errors of the evaluation, after Shewchuk
+    if det.abs() > permanent * 2.3e-15 {
+        return if det > 0.0 { 1 } else { -1 };
+    }
+    let (adx, ady) = (DD::difference(a.x(), p.x()), DD::difference(a.y(), p.y()));
+    let (bdx, bdy) = (DD::difference(b.x(), p.x()), DD::difference(b.y(), p.y()));
+    let (cdx, cdy) = (DD::difference(c.x(), p.x()), DD::difference(c.y(), p.y()));
+    let (alift, blift, clift) = (adx * adx + ady * ady, bdx * bdx + bdy * bdy, cdx * cdx + cdy * cdy);
+    let det = alift * (bdx * cdy - cdx * bdy) + blift * (cdx * ady - adx * cdy) + clift * (adx * bdy - bdx * ady);
+    #[cfg(feature = "exact")]
+    if det.to_f64().abs() <= permanent * DD_ERROR {
+        if let Some(sign) = exact::in_circle(a, b, c, p) {
+            return sign;
+        }
+    }
+    det.signum()
+}
+
+/// Returns the intersection point of the lines through `p1` -> `p2` and `q1` -> `q2`, computed in
+/// double-double arithmetic, or None if the lines are parallel. The z value of the point is 0.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::dd::line_intersection;
+///
+/// let point = line_intersection(&coord!(0, 0), &coord!(2, 2), &coord!(0, 2), &coord!(2, 0));
+/// assert_eq!(point, Some(coord!(1, 1)));
+/// assert_eq!(line_intersection(&coord!(0, 0), &coord!(2, 2), &coord!(0, 1), &coord!(2, 3)), None);
+/// ```
+pub fn line_intersection(p1: &Coordinate, p2: &Coordinate, q1: &Coordinate, q2: &Coordinate) -> Option<Coordinate> {
+    let (px, py) = (DD::difference(p2.x(), p1.x()), DD::difference(p2.y(), p1.y()));
+    let (qx, qy) = (DD::difference(q2.x(), q1.x()), DD::difference(q2.y(), q1.y()));
+    let denominator = px * qy - py * qx;
+    if denominator.signum() == 0 {
+        return None;
+    }
+    let (dx, dy) = (DD::difference(q1.x(), p1.x()), DD::difference(q1.y(), p1.y()));
+    let t = (dx * qy - dy * qx) / denominator;
+    let x = DD::from(p1.x()) + t * px;
+    let y = DD::from(p1.y()) + t * py;
+    Some(Coordinate::new(x.to_f64(), y.to_f64(), 0.0))
+}
+
+/// The predicates in exact rational arithmetic, where every finite `f64` is a rational number.
+#[cfg(feature = "exact")]
+mod exact {
+    use std::cmp::Ordering;
+
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+
+    use crate::coordinate::Coordinate;
+
+    /// Returns the differences of the x and y of two coordinates, or None if one is not finite.
+    fn difference(a: &Coordinate, b: &Coordinate) -> Option<(BigRational, BigRational)> {
+        let value = BigRational::from_float;
+        Some((value(a.x())? - value(b.x())?, value(a.y())? - value(b.y())?))
+    }
+
+    fn sign(det: BigRational) -> i32 {
+        match det.cmp(&BigRational::from_integer(BigInt::from(0))) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        }
+    }
+
+    pub(super) fn orientation_index(p: &Coordinate, q: &Coordinate, r: &Coordinate) -> Option<i32> {
+        let ((qx, qy), (rx, ry)) = (difference(q, p)?, difference(r, p)?);
+        Some(sign(qx * ry - qy * rx))
+    }
+
+    pub(super) fn in_circle(a: &Coordinate, b: &Coordinate, c: &Coordinate, p: &Coordinate) -> Option<i32> {
+        let ((adx, ady), (bdx, bdy), (cdx, cdy)) = (difference(a, p)?, difference(b, p)?, difference(c, p)?);
+        let lift = |x: &BigRational, y: &BigRational| x * x + y * y;
+        let (alift, blift, clift) = (lift(&adx, &ady), lift(&bdx, &bdy), lift(&cdx, &cdy));
+        let det = alift * (&bdx * &cdy - &cdx * &bdy) + blift * (&cdx * &ady - &adx * &cdy) + clift * (&adx * &bdy - &bdx * &ady);
+        Some(sign(det))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_arithmetic() {
+        let third = DD::from(1.0) / DD::from(3.0);
+        let error = DD::from(1.0) - third * DD::from(3.0);
+        assert!(error.to_f64().abs() < 1e-31);
+        assert!((third.hi() - 1.0 / 3.0).abs() == 0.0 && third.lo() != 0.0);
+
+        //a sum whose floating point evaluation loses the small term entirely
+        let sum = DD::from(1e16) + DD::from(1.0) - DD::from(1e16);
+        assert_eq!(sum.to_f64(), 1.0);
+        assert_eq!(DD::product(1e8 + 1.0, 1e8 - 1.0) - DD::from(1e16), DD::from(-1.0));
+    }
+
+    #[test]
+    fn test_predicates_near_degenerate() {
+        //points on a line through coordinates that are not exact, where the plain determinant
+        //changes sign with the rounding errors
+        let (p, q) = (coord!(0.1, 0.1), coord!(0.3, 0.3));
+        let mut signs = [0; 3];
+        for k in 0..200 {
+            let r = coord!(0.2 + k as f64 * f64::EPSILON, 0.2);
+            let sign = orientation_index(&p, &q, &r);
+            signs[(sign + 1) as usize] += 1;
+            //moving the point to the right of the line never turns it back to the left
+            if k > 0 {
+                assert!(sign <= 0);
+            }
+        }
+        assert!(signs[0] > 190);
+
+        //four points of a circle away from the origin
+        let (a, b, c, d) = (coord!(1e6, 1e6), coord!(1e6 + 3.0, 1e6), coord!(1e6 + 3.0, 1e6 + 4.0), coord!(1e6, 1e6 + 4.0));
+        assert_eq!(in_circle(&a, &b, &c, &d), 0);
+        assert_eq!(in_circle(&a, &b, &c, &coord!(1e6, 1e6 + 4.0 - 1e-9)), 1);
+        assert_eq!(in_circle(&a, &b, &c, &coord!(1e6, 1e6 + 4.0 + 1e-9)), -1);
+    }
+
+    #[test]
+    #[cfg(feature = "exact")]
+    fn test_exact_fallback() {
+        //a point very close to a line between points with more bits than double-double products
+        //keep, where double-double arithmetic gives the wrong side
+        let (p, q, r) = (
+            coord!(4.645769811970458e-16, 2.4778925658225146e-16),
+            coord!(1.444264700826358, 1.762894391911761),
+            coord!(2.7113884394362144, 3.30956747155947),
+        );
+        let (qx, qy) = (DD::difference(q.x(), p.x()), DD::difference(q.y(), p.y()));
+        let (rx, ry) = (DD::difference(r.x(), p.x()), DD::difference(r.y(), p.y()));
+        assert_eq!((qx * ry - qy * rx).signum(), 1);
+        assert_eq!(orientation_index(&p, &q, &r), -1);
+        assert_eq!(orientation_index(&q, &p, &r), 1);
+
+        //a point very close to a circle through the origin, on which double-double arithmetic
+        //finds it
+        let (a, b, c) = (
+            coord!(-0.41207803127619846, 0.9223106597586526),
+            coord!(-0.0028315904070423947, 1.9971603688884163),
+            coord!(-0.4069319238995053, 0.8566732351846408),
+        );
+        let p = coord!(1.9494926991112267e-16, -3.3013592974600712e-15);
+        assert_eq!(in_circle(&a, &b, &c, &p), -1);
+        assert_eq!(in_circle(&b, &a, &c, &p), 1);
+    }
+}
@@ -0,0 +1,380 @@
+//! Placement of labels inside of polygons.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::algorithm::centroid::centroid;
+use crate::algorithm::skeleton::approximate_medial_axis;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+
+/// Returns the center and the radius of the largest circle fitting inside of a polygon, or None
+/// if the polygon has no area. The center is the pole of inaccessibility of the polygon, see
+/// [`pole_of_inaccessibility`].
+///
+/// The polygon envelope is split into square cells, which are subdivided while they may hold a
+/// center further from the boundary than the best one found by more than the tolerance. The
+/// search starts from the best of the center of the envelope and the centroid of the polygon, and
+/// the distances to the boundary are found through an index of its segments.
+///
+/// # Arguments
+///
+/// * `polygon` - The rings of the polygon, the shell first.
+/// * `tolerance` - The maximum difference between the radius found and the largest one. It must be positive.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::label::maximum_inscribed_circle;
+///
+/// //an L shape, whose widest part is its corner where the circle touches the inner vertex
+/// let polygon = vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 4), coord!(4, 4), coord!(4, 10), coord!(0, 10), coord!(0, 0)]];
+/// let (center, radius) = maximum_inscribed_circle(&polygon, 0.01).unwrap();
+/// let largest = 4.0 * 2f64.sqrt() / (1.0 + 2f64.sqrt());
+/// assert!(radius > largest - 0.01 && radius <= largest);
+/// assert!(center.x() < 4.0 && center.y() < 4.0);
+/// ```
+pub fn maximum_inscribed_circle(polygon: &[Vec<Coordinate>], tolerance: f64) -> Option<(Coordinate, f64)> {
+    if !(tolerance.is_finite() && tolerance > 0.0) {
+        panic!("The tolerance of the inscribed circle must be positive");
+    }
+    let envelope = Envelope::of(polygon.first()?);
+    let size = envelope.width().min(envelope.height());
+    if envelope.is_empty() || size == 0.0 {
+        return None;
+    }
+    let boundary = Boundary::new(polygon, envelope);
+
+    let mut cells = BinaryHeap::new();
+    let mut y = envelope.min_y();
+    while y < envelope.max_y() {
+        let mut x = envelope.min_x();
+        while x < envelope.max_x() {
+            cells.push(Cell::new(x + size / 2.0, y + size / 2.0, size / 2.0, &boundary));
+            x += size;
+        }
+        y += size;
+    }
+    let center = envelope.center()?;
+    let mut best = Cell::new(center.x(), center.y(), 0.0, &boundary);
+    //the centroid is a good first guess for compact shapes, which lets more cells be discarded
+    if let Some(centroid) = centroid(&Geometry::Polygon { coordinates: polygon.to_vec() }) {
+        let guess = Cell::new(centroid.x(), centroid.y(), 0.0, &boundary);
+        if guess.distance > best.distance {
+            best = guess;
+        }
+    }
+    while let Some(cell) = cells.pop() {
+        if cell.distance > best.distance {
+            best = cell.clone();
+        }
+        //the cells come out by their potential, so none of the rest can do better
+        if cell.potential - best.distance <= tolerance {
+            break;
+        }
+        let half = cell.half / 2.0;
+        for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            cells.push(Cell::new(cell.x + dx * half, cell.y + dy * half, half, &boundary));
+        }
+    }
+    if best.distance <= 0.0 {
+        return None;
+    }
+    Some((Coordinate::new(best.x, best.y, 0.0), best.distance))
+}
+
+/// Returns the pole of inaccessibility of a polygon, its interior point most distant from the
+/// boundary, found with the polylabel algorithm, or None if the polygon has no area. It is the
+/// usual anchor for the label of a polygon, as it lies in its widest part even when the
+/// centroid falls outside of it.
+///
+/// # Arguments
+///
+/// * `polygon` - The rings of the polygon, the shell first.
+/// * `precision` - The maximum difference between the distance from the point found to the
+///   boundary and the largest one. It must be positive, and smaller values take longer.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::label::pole_of_inaccessibility;
+///
+/// //a square with a square hole in its lower left part
+/// let polygon = vec![
+///     vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+///     vec![coord!(1, 1), coord!(1, 5), coord!(5, 5), coord!(5, 1), coord!(1, 1)],
+/// ];
+/// let pole = pole_of_inaccessibility(&polygon, 0.01).unwrap();
+/// assert!(pole.x() > 5.0 && pole.y() > 5.0);
+/// ```
+pub fn pole_of_inaccessibility(polygon: &[Vec<Coordinate>], precision: f64) -> Option<Coordinate> {
+    if !(precision.is_finite() && precision > 0.0) {
+        panic!("The precision of the pole of inaccessibility must be positive");
+    }
+    maximum_inscribed_circle(polygon, precision).map(|(center, _)| center)
+}
+
+/// The segments of the rings of a polygon, indexed to find their distance to points quickly.
+struct Boundary<'a> {
+    tree: STRtree<(&'a Coordinate, &'a Coordinate)>,
+    envelope: Envelope,
+}
+
+impl<'a> Boundary<'a> {
+    fn new(polygon: &'a [Vec<Coordinate>], envelope: Envelope) -> Boundary<'a> {
+        let segments = polygon
+            .iter()
+            .flat_map(|ring| ring.windows(2))
+            .map(|w| (Envelope::of([&w[0], &w[1]]), (&w[0], &w[1])))
+            .collect();
+        Boundary { tree: STRtree::new(segments), envelope }
+    }
+
+    /// Returns the distance from a point to the boundary, negative outside of the polygon.
+    fn signed_distance(&self, x: f64, y: f64) -> f64 {
+        let p = Coordinate::new(x, y, 0.0);
+        let target = Envelope::new(x, y, x, y);
+        let distance = self
+            .tree
+            .nearest(&target, f64::INFINITY, |(a, b)| point_segment_distance(&p, a, b))
+            .map_or(f64::INFINITY, |(_, d)| d);
+        //the crossings of a ray to the right, found through the index
+        let mut inside = false;
+        if x <= self.envelope.max_x() {
+            for (a, b) in self.tree.query(&Envelope::new(x, y, self.envelope.max_x(), y)) {
+                if (a.y() > y) != (b.y() > y) && x < a.x() + (y - a.y()) * (b.x() - a.x()) / (b.y() - a.y()) {
+                    inside = !inside;
+                }
+            }
+        }
+        //points on the boundary are at a distance of zero either way
+        if inside || distance == 0.0 {
+            distance
+        } else {
+            -distance
+        }
+    }
+}
+
+/// A square cell searched for the center of the largest inscribed circle.
+#[derive(Clone)]
+struct Cell {
+    x: f64,
+    y: f64,
+    half: f64,
+    //the distance from the center to the boundary, negative outside of the polygon
+    distance: f64,
+    //the largest distance a point of the cell can have
+    potential: f64,
+}
+
+impl Cell {
+    fn new(x: f64, y: f64, half: f64, boundary: &Boundary) -> Cell {
+        let distance = boundary.signed_distance(x, y);
+        Cell { x, y, half, distance, potential: distance + half * std::f64::consts::SQRT_2 }
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.potential.total_cmp(&other.potential)
+    }
+}
+
+fn point_segment_distance(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let squared = dx * dx + dy * dy;
+    let t = if squared == 0.0 { 0.0 } else { (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / squared).clamp(0.0, 1.0) };
+    (p.x() - a.x() - t * dx).hypot(p.y() - a.y() - t * dy)
+}
+
+/// Returns the longest path along the approximate medial axis of a polygon, which follows the
+/// middle of its elongated parts and suits curved labels. The path runs between the two ends of
+/// the axis found furthest apart along it. When the axis has no ends, its longest loop is returned.
+///
+/// # Arguments
+///
+/// * `polygon` - The rings of the polygon, the shell first.
+/// * `spacing` - The maximum distance between the boundary points the axis is computed from. It must be positive.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::label::label_line;
+///
+/// let river = vec![vec![coord!(0, 0), coord!(20, 0), coord!(20, 2), coord!(0, 2), coord!(0, 0)]];
+/// let line = label_line(&river, 0.25);
+/// //from one end of the river to the other, through its middle
+/// assert!((line[0].x() - line[line.len() - 1].x()).abs() > 19.0);
+/// assert!(line.iter().filter(|c| c.x() > 1.0 && c.x() < 19.0).all(|c| c.y() == 1.0));
+/// ```
+pub fn label_line(polygon: &[Vec<Coordinate>], spacing: f64) -> Vec<Coordinate> {
+    let axis = approximate_medial_axis(polygon, spacing);
+
+    //the lines of the axis join at their ends, which are the nodes of a graph
+    let mut nodes: HashMap<&Coordinate, usize> = HashMap::new();
+    let mut edges: Vec<(usize, usize, f64)> = Vec::with_capacity(axis.len());
+    for line in &axis {
+        let mut node = |c| {
+            let next = nodes.len();
+            *nodes.entry(c).or_insert(next)
+        };
+        let (a, b) = (node(&line[0]), node(&line[line.len() - 1]));
+        let length = line.windows(2).map(|w| (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y())).sum();
+        edges.push((a, b, length));
+    }
+    if nodes.is_empty() {
+        return Vec::new();
+    }
+    let mut incident = vec![Vec::new(); nodes.len()];
+    for (i, &(a, b, _)) in edges.iter().enumerate() {
+        incident[a].push(i);
+        incident[b].push(i);
+    }
+
+    //the furthest node from any node is an end of the longest path of a tree
+    let (start, _) = furthest(0, &edges, &incident);
+    let (end, previous) = furthest(start, &edges, &incident);
+    let mut path: Vec<Coordinate> = Vec::new();
+    let mut node = end;
+    while let Some(edge) = previous[node] {
+        let (a, b, _) = edges[edge];
+        let line = &axis[edge];
+        let forward: Box<dyn Iterator<Item = &Coordinate>> =
+            if b == node { Box::new(line.iter().rev()) } else { Box::new(line.iter()) };
+        for c in forward.skip(if path.is_empty() { 0 } else { 1 }) {
+            path.push(c.clone());
+        }
+        node = if b == node { a } else { b };
+    }
+    //the path was walked from its end
+    path.reverse();
+    if path.is_empty() {
+        //an axis made only of loops, such as the one of a ring shaped polygon
+        let length = |line: &Vec<Coordinate>| line.windows(2).map(|w| (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y())).sum::<f64>();
+        return axis.iter().max_by(|a, b| length(a).total_cmp(&length(b))).cloned().unwrap_or_default();
+    }
+    path
+}
+
+/// Returns the node furthest from the start along the edges, and the edge leading to every node
+/// on its shortest path from the start.
+fn furthest(start: usize, edges: &[(usize, usize, f64)], incident: &[Vec<usize>]) -> (usize, Vec<Option<usize>>) {
+    let mut distances = vec![f64::INFINITY; incident.len()];
+    let mut previous = vec![None; incident.len()];
+    let mut queue = BinaryHeap::new();
+    distances[start] = 0.0;
+    queue.push(Reached(0.0, start));
+    while let Some(Reached(distance, node)) = queue.pop() {
+        if distance > distances[node] {
+            continue;
+        }
+        for &edge in &incident[node] {
+            let (a, b, length) = edges[edge];
+            let other = if a == node { b } else { a };
+            if distance + length < distances[other] {
+                distances[other] = distance + length;
+                previous[other] = Some(edge);
+                queue.push(Reached(distance + length, other));
+            }
+        }
+    }
+    let mut end = start;
+    for (node, &distance) in distances.iter().enumerate() {
+        if distance.is_finite() && distance > distances[end] {
+            end = node;
+        }
+    }
+    (end, previous)
+}
+
+/// A node reached at a distance, ordered so that the closest comes out of a heap first.
+struct Reached(f64, usize);
+
+impl PartialEq for Reached {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Reached {}
+
+impl PartialOrd for Reached {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Reached {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::algorithm::point_in_polygon::{locate_in_polygon, Location};
+
+    #[test]
+    fn test_inscribed_circle_avoids_holes() {
+        //a square frame whose widest part is the bottom band
+        let polygon = vec![
+            vec![coord!(0, 0), coord!(20, 0), coord!(20, 20), coord!(0, 20), coord!(0, 0)],
+            vec![coord!(4, 8), coord!(16, 8), coord!(16, 16), coord!(4, 16), coord!(4, 8)],
+        ];
+        let (center, radius) = maximum_inscribed_circle(&polygon, 1e-3).unwrap();
+        assert!((radius - 4.0).abs() <= 1e-3);
+        assert!((center.y() - 4.0).abs() < 0.1);
+        assert_eq!(locate_in_polygon(&center, &polygon), Location::Interior);
+
+        let flat = vec![vec![coord!(0, 0), coord!(5, 0), coord!(0, 0)]];
+        assert_eq!(maximum_inscribed_circle(&flat, 0.1), None);
+    }
+
+    #[test]
+    fn test_label_line_follows_the_longest_branch() {
+        //a T shape with a long horizontal bar
+        let polygon = vec![vec![
+            coord!(0, 10),
+            coord!(30, 10),
+            coord!(30, 12),
+            coord!(16, 12),
+            coord!(16, 16),
+            coord!(14, 16),
+            coord!(14, 12),
+            coord!(0, 12),
+            coord!(0, 10),
+        ]];
+        let line = label_line(&polygon, 0.2);
+        let (first, last) = (&line[0], &line[line.len() - 1]);
+        assert!(first.x().min(last.x()) < 2.0 && first.x().max(last.x()) > 28.0);
+        for c in &line {
+            assert_eq!(locate_in_polygon(c, &polygon), Location::Interior);
+        }
+        assert!(label_line(&[], 1.0).is_empty());
+    }
+}
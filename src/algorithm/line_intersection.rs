@@ -0,0 +1,305 @@
+use crate::algorithm::dd;
+use crate::coordinate::Coordinate;
+
+/// The orientation of a point relative to a directed segment.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Orientation {
+    Clockwise,
+    Collinear,
+    CounterClockwise,
+}
+
+/// Returns the orientation of the point `r` relative to the directed segment `p` -> `q`.
+/// Only the planar projection of the coordinates is taken into account, and nearly collinear
+/// points are resolved in double-double arithmetic, see [`dd::orientation_index`].
+///
+/// # Arguments
+///
+/// * `p` - The start of the segment.
+/// * `q` - The end of the segment.
+/// * `r` - The point to test.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_intersection::{orientation, Orientation};
+///
+/// assert_eq!(orientation(&coord!(0, 0), &coord!(1, 0), &coord!(1, 1)), Orientation::CounterClockwise);
+/// assert_eq!(orientation(&coord!(0, 0), &coord!(1, 0), &coord!(1, -1)), Orientation::Clockwise);
+/// assert_eq!(orientation(&coord!(0, 0), &coord!(1, 0), &coord!(2, 0)), Orientation::Collinear);
+/// ```
+pub fn orientation(p: &Coordinate, q: &Coordinate, r: &Coordinate) -> Orientation {
+    match dd::orientation_index(p, q, r) {
+        1 => Orientation::CounterClockwise,
+        -1 => Orientation::Clockwise,
+        _ => Orientation::Collinear,
+    }
+}
+
+/// Returns true if the point `p` lies on the segment `a` -> `b`, endpoints included.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_intersection::point_on_segment;
+///
+/// assert!(point_on_segment(&coord!(1, 1), &coord!(0, 0), &coord!(2, 2)));
+/// assert!(point_on_segment(&coord!(0, 0), &coord!(0, 0), &coord!(2, 2)));
+/// assert!(!point_on_segment(&coord!(3, 3), &coord!(0, 0), &coord!(2, 2)));
+/// ```
+pub fn point_on_segment(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> bool {
+    orientation(a, b, p) == Orientation::Collinear
+        && p.x() >= a.x().min(b.x())
+        && p.x() <= a.x().max(b.x())
+        && p.y() >= a.y().min(b.y())
+        && p.y() <= a.y().max(b.y())
+}
+
+/// Returns the z value at the planar location of `p` interpolated along the segment `a` -> `b`.
+/// The location is projected on the segment, so points outside of it take the z of the closest endpoint.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_intersection::interpolate_z;
+///
+/// assert_eq!(interpolate_z(&coord!(1, 0), &coord!(0, 0, 10), &coord!(4, 0, 50)), 20.0);
+/// assert_eq!(interpolate_z(&coord!(9, 0), &coord!(0, 0, 10), &coord!(4, 0, 50)), 50.0);
+/// ```
+pub fn interpolate_z(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    let dx = b.x() - a.x();
+    let dy = b.y() - a.y();
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return a.z();
+    }
+    let fraction = (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / length_squared).clamp(0.0, 1.0);
+    a.z() + fraction * (b.z() - a.z())
+}
+
+/// Policy used to assign a z value to the vertices created at the intersection of two segments.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ZInterpolation {
+    /// New vertices are planar and get a z value of 0.
+    Zero,
+    /// New vertices get the mean of the z values interpolated along both segments.
+    #[default]
+    Average,
+    /// New vertices get the z value interpolated along the first segment only.
+    First,
+}
+
+impl ZInterpolation {
+    /// Returns a coordinate at the planar location of `p` with the z value given by the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The policy.
+    /// * `p` - The intersection location.
+    /// * `p1`, `p2` - The first segment.
+    /// * `q1`, `q2` - The second segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::algorithm::line_intersection::ZInterpolation;
+    ///
+    /// let (p1, p2) = (coord!(0, 0, 0), coord!(2, 0, 10));
+    /// let (q1, q2) = (coord!(1, -1, 20), coord!(1, 1, 20));
+    /// let p = coord!(1, 0);
+    /// assert_eq!(ZInterpolation::Zero.apply(&p, &p1, &p2, &q1, &q2).z(), 0.0);
+    /// assert_eq!(ZInterpolation::Average.apply(&p, &p1, &p2, &q1, &q2).z(), 12.5);
+    /// assert_eq!(ZInterpolation::First.apply(&p, &p1, &p2, &q1, &q2).z(), 5.0);
+    /// ```
+    pub fn apply(
+        &self,
+        p: &Coordinate,
+        p1: &Coordinate,
+        p2: &Coordinate,
+        q1: &Coordinate,
+        q2: &Coordinate,
+    ) -> Coordinate {
+        let z = match self {
+            ZInterpolation::Zero => 0.0,
+            ZInterpolation::Average => {
+                (interpolate_z(p, p1, p2) + interpolate_z(p, q1, q2)) / 2.0
+            }
+            ZInterpolation::First => interpolate_z(p, p1, p2),
+        };
+        p.set_z(z)
+    }
+}
+
+/// The result of intersecting two segments.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SegmentIntersection {
+    /// The segments do not intersect.
+    None,
+    /// The segments intersect at a single point.
+    Point(Coordinate),
+    /// The segments are collinear and overlap between the two coordinates.
+    Collinear(Coordinate, Coordinate),
+}
+
+/// Computes the intersection of the segments `p1` -> `p2` and `q1` -> `q2`.
+/// The z values of the resulting coordinates are assigned with the given policy.
+///
+/// # Arguments
+///
+/// * `p1`, `p2` - The first segment.
+/// * `q1`, `q2` - The second segment.
+/// * `z` - The policy used to compute the z value of the intersection.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_intersection::{intersection, SegmentIntersection, ZInterpolation};
+///
+/// let result = intersection(&coord!(0, 0, 0), &coord!(2, 2, 20), &coord!(0, 2, 10), &coord!(2, 0, 10), ZInterpolation::Average);
+/// assert_eq!(result, SegmentIntersection::Point(coord!(1, 1, 10)));
+///
+/// let result = intersection(&coord!(0, 0), &coord!(2, 0), &coord!(1, 0), &coord!(3, 0), ZInterpolation::Zero);
+/// assert_eq!(result, SegmentIntersection::Collinear(coord!(1, 0), coord!(2, 0)));
+///
+/// let result = intersection(&coord!(0, 0), &coord!(1, 0), &coord!(0, 1), &coord!(1, 1), ZInterpolation::Zero);
+/// assert_eq!(result, SegmentIntersection::None);
+/// ```
+pub fn intersection(
+    p1: &Coordinate,
+    p2: &Coordinate,
+    q1: &Coordinate,
+    q2: &Coordinate,
+    z: ZInterpolation,
+) -> SegmentIntersection {
+    //quick rejection using the envelopes of the segments
+    if p1.x().max(p2.x()) < q1.x().min(q2.x())
+        || q1.x().max(q2.x()) < p1.x().min(p2.x())
+        || p1.y().max(p2.y()) < q1.y().min(q2.y())
+        || q1.y().max(q2.y()) < p1.y().min(p2.y())
+    {
+        return SegmentIntersection::None;
+    }
+
+    let o1 = orientation(p1, p2, q1);
+    let o2 = orientation(p1, p2, q2);
+    let o3 = orientation(q1, q2, p1);
+    let o4 = orientation(q1, q2, p2);
+
+    if (o1 == o2 && o1 != Orientation::Collinear) || (o3 == o4 && o3 != Orientation::Collinear) {
+        return SegmentIntersection::None;
+    }
+
+    if o1 == Orientation::Collinear
+        && o2 == Orientation::Collinear
+        && o3 == Orientation::Collinear
+        && o4 == Orientation::Collinear
+    {
+        return collinear_intersection(p1, p2, q1, q2, z);
+    }
+
+    //the intersection is an endpoint of one of the segments
+    let endpoint = if o1 == Orientation::Collinear {
+        Some(q1)
+    } else if o2 == Orientation::Collinear {
+        Some(q2)
+    } else if o3 == Orientation::Collinear {
+        Some(p1)
+    } else if o4 == Orientation::Collinear {
+        Some(p2)
+    } else {
+        None
+    };
+    if let Some(endpoint) = endpoint {
+        return SegmentIntersection::Point(z.apply(endpoint, p1, p2, q1, q2));
+    }
+
+    //proper intersection, computed in double-double arithmetic and kept within the envelope of
+    //both segments, which the last rounding could leave
+    let location = match dd::line_intersection(p1, p2, q1, q2) {
+        Some(location) => location,
+        None => return SegmentIntersection::None,
+    };
+    let x = location.x().clamp(p1.x().min(p2.x()).max(q1.x().min(q2.x())), p1.x().max(p2.x()).min(q1.x().max(q2.x())));
+    let y = location.y().clamp(p1.y().min(p2.y()).max(q1.y().min(q2.y())), p1.y().max(p2.y()).min(q1.y().max(q2.y())));
+    SegmentIntersection::Point(z.apply(&Coordinate::new(x, y, 0.0), p1, p2, q1, q2))
+}
+
+/// Computes the intersection of two collinear segments.
+fn collinear_intersection(
+    p1: &Coordinate,
+    p2: &Coordinate,
+    q1: &Coordinate,
+    q2: &Coordinate,
+    z: ZInterpolation,
+) -> SegmentIntersection {
+    //the overlap is delimited by the endpoints lying on the other segment
+    let mut ends: Vec<&Coordinate> = Vec::with_capacity(4);
+    for (candidate, a, b) in [(q1, p1, p2), (q2, p1, p2), (p1, q1, q2), (p2, q1, q2)] {
+        if point_on_segment(candidate, a, b) && !ends.iter().any(|end| end.equals_2d(candidate)) {
+            ends.push(candidate);
+        }
+    }
+    match ends.len() {
+        0 => SegmentIntersection::None,
+        1 => SegmentIntersection::Point(z.apply(ends[0], p1, p2, q1, q2)),
+        _ => {
+            //keep the two endpoints furthest apart
+            let first = ends[0];
+            let last = ends
+                .iter()
+                .skip(1)
+                .max_by(|a, b| {
+                    let da = (a.x() - first.x()).powi(2) + (a.y() - first.y()).powi(2);
+                    let db = (b.x() - first.x()).powi(2) + (b.y() - first.y()).powi(2);
+                    da.total_cmp(&db)
+                })
+                .unwrap();
+            SegmentIntersection::Collinear(
+                z.apply(first, p1, p2, q1, q2),
+                z.apply(last, p1, p2, q1, q2),
+            )
+        }
+    }
+}
+
+/// Calls `f` with every pair of segments whose envelopes overlap, using a sort and sweep along the x axis.
+/// The pair is passed with the lowest index first. Stops as soon as `f` returns false, in which case
+/// false is returned.
+pub(crate) fn sweep_segment_pairs<F>(segments: &[(&Coordinate, &Coordinate)], mut f: F) -> bool
+where
+    F: FnMut(usize, usize) -> bool,
+{
+    let min_x = |i: usize| segments[i].0.x().min(segments[i].1.x());
+    let max_x = |i: usize| segments[i].0.x().max(segments[i].1.x());
+    let min_y = |i: usize| segments[i].0.y().min(segments[i].1.y());
+    let max_y = |i: usize| segments[i].0.y().max(segments[i].1.y());
+
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_unstable_by(|&a, &b| min_x(a).total_cmp(&min_x(b)));
+
+    for (position, &a) in order.iter().enumerate() {
+        let a_max_x = max_x(a);
+        for &b in &order[position + 1..] {
+            if min_x(b) > a_max_x {
+                break;
+            }
+            if min_y(a) > max_y(b) || min_y(b) > max_y(a) {
+                continue;
+            }
+            if !f(a.min(b), a.max(b)) {
+                return false;
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,155 @@
+//! Measures of the shape of lines, such as how much a river meanders or how sharply a track
+//! turns, computed in the plane.
+
+use crate::coordinate::Coordinate;
+
+/// Returns the sinuosity of a line: its length over the straight distance between its ends,
+/// which is 1 for a straight line and grows as the line meanders. Returns None if the ends of the
+/// line are the same point, as for closed lines, or if it has fewer than two vertices.
+///
+/// # Arguments
+///
+/// * `line` - The vertices of the line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_metrics::sinuosity;
+///
+/// let river = vec![coord!(0, 0), coord!(3, 4), coord!(6, 0)];
+/// assert_eq!(sinuosity(&river), Some(10.0 / 6.0));
+/// assert_eq!(sinuosity(&[coord!(0, 0), coord!(1, 1), coord!(0, 0)]), None);
+/// ```
+pub fn sinuosity(line: &[Coordinate]) -> Option<f64> {
+    let chord = chord(line)?;
+    if chord == 0.0 {
+        return None;
+    }
+    Some(length(line) / chord)
+}
+
+/// Returns the straightness of a line: the straight distance between its ends over its length,
+/// between 0 and 1, the inverse of the sinuosity. Closed lines have a straightness of 0. Returns
+/// None if the line has no length.
+///
+/// # Arguments
+///
+/// * `line` - The vertices of the line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_metrics::straightness;
+///
+/// let track = vec![coord!(0, 0), coord!(3, 4), coord!(6, 0)];
+/// assert_eq!(straightness(&track), Some(0.6));
+/// assert_eq!(straightness(&[coord!(1, 1), coord!(1, 1)]), None);
+/// ```
+pub fn straightness(line: &[Coordinate]) -> Option<f64> {
+    let length = length(line);
+    if length == 0.0 {
+        return None;
+    }
+    Some(chord(line)? / length)
+}
+
+/// Returns the azimuth of every segment of a line, in degrees clockwise from north in the range
+/// [0, 360), north being the direction of the y axis. Segments without length have no direction
+/// and are skipped.
+///
+/// # Arguments
+///
+/// * `line` - The vertices of the line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_metrics::azimuths;
+///
+/// let route = vec![coord!(0, 0), coord!(0, 1), coord!(0, 1), coord!(1, 1), coord!(0, 0)];
+/// assert_eq!(azimuths(&route).collect::<Vec<f64>>(), [0.0, 90.0, 225.0]);
+/// ```
+pub fn azimuths(line: &[Coordinate]) -> impl Iterator<Item = f64> + '_ {
+    line.windows(2)
+        .filter(|w| !w[0].equals_2d(&w[1]))
+        .map(|w| (w[1].x() - w[0].x()).atan2(w[1].y() - w[0].y()).to_degrees().rem_euclid(360.0))
+}
+
+/// Returns the curvature of a line at its interior vertices: the angle it turns by at the vertex,
+/// in radians, over half the length of the segments meeting there. Left turns are positive and
+/// right turns negative, and the curvature along an arc of a circle is close to one over its
+/// radius. Repeated vertices are skipped, so the line without them gives the vertices measured,
+/// from the second to the second to last.
+///
+/// # Arguments
+///
+/// * `line` - The vertices of the line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::line_metrics::curvature;
+///
+/// //a left turn at a right angle, then going straight
+/// let road = vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(2, 4)];
+/// let curvature = curvature(&road);
+/// assert!((curvature[0] - std::f64::consts::FRAC_PI_2 / 2.0).abs() < 1e-12);
+/// assert_eq!(curvature[1], 0.0);
+/// ```
+pub fn curvature(line: &[Coordinate]) -> Vec<f64> {
+    let mut vertices: Vec<&Coordinate> = line.iter().collect();
+    vertices.dedup_by(|a, b| a.equals_2d(b));
+    vertices
+        .windows(3)
+        .map(|w| {
+            let (ax, ay) = (w[1].x() - w[0].x(), w[1].y() - w[0].y());
+            let (bx, by) = (w[2].x() - w[1].x(), w[2].y() - w[1].y());
+            let turn = (ax * by - ay * bx).atan2(ax * bx + ay * by);
+            turn / ((ax.hypot(ay) + bx.hypot(by)) / 2.0)
+        })
+        .collect()
+}
+
+/// Returns the planar length of a line.
+fn length(line: &[Coordinate]) -> f64 {
+    line.windows(2).map(|w| w[0].distance_2d(&w[1])).sum()
+}
+
+/// Returns the planar distance between the ends of a line, or None if it has fewer than two
+/// vertices.
+fn chord(line: &[Coordinate]) -> Option<f64> {
+    if line.len() < 2 {
+        return None;
+    }
+    Some(line[0].distance_2d(&line[line.len() - 1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_metrics_of_an_arc() {
+        //half of a circle of radius 10, turning left
+        let arc: Vec<Coordinate> = (0..=180)
+            .map(|i| (i as f64).to_radians())
+            .map(|a| coord!(10.0 * a.cos(), 10.0 * a.sin()))
+            .collect();
+        assert!((sinuosity(&arc).unwrap() - std::f64::consts::PI / 2.0).abs() < 1e-4);
+        assert!(curvature(&arc).iter().all(|k| (k - 0.1).abs() < 1e-4));
+        let azimuths: Vec<f64> = azimuths(&arc).collect();
+        assert_eq!(azimuths.len(), 180);
+        assert!((azimuths[0] - 359.5).abs() < 1e-9 && (azimuths[179] - 180.5).abs() < 1e-9);
+        assert!(curvature(&[coord!(0, 0), coord!(1, 1)]).is_empty());
+        assert_eq!(sinuosity(&[]), None);
+    }
+}
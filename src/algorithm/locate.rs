@@ -0,0 +1,186 @@
+//! Location of points relative to geometries of any type, collections included, answering many
+//! queries against the same geometry through an index of its segments.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::algorithm::line_intersection::point_on_segment;
+use crate::algorithm::point_in_polygon::Location;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::index::quadtree::Quadtree;
+use crate::operation::extract;
+
+/// Identifies a location exactly, with the negative zero taken as zero.
+type Key = (u64, u64);
+
+/// Locates points in the interior, on the boundary or in the exterior of a geometry made of parts
+/// of any dimension, in the plane, as the DE-9IM does.
+///
+/// The parts of a higher dimension hide those of a lower one: a point inside of a polygon is in
+/// the interior whatever lines or points are there, and a point on the rings of the polygons but
+/// inside of none of them is on the boundary. Otherwise the boundary of lines follows the mod 2
+/// rule, made of the ends shared by an odd number of open lines, and the rest of the lines and
+/// the points are in the interior. Polygons may overlap, as in collections. The segments of the
+/// rings and of the lines are kept in quadtrees, so a query only visits the segments near the
+/// point and those crossing the ray going from it towards positive x.
+pub struct Locator {
+    //the segments of the rings, with the index of their polygon
+    rings: Quadtree<(Coordinate, Coordinate, usize)>,
+    lines: Quadtree<(Coordinate, Coordinate)>,
+    //the number of open lines ending at every end
+    ends: HashMap<Key, usize>,
+    //the points, and the lines of a single vertex
+    points: HashSet<Key>,
+    max_x: f64,
+}
+
+impl Locator {
+    /// Builds a locator over the parts of a geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::algorithm::locate::Locator;
+    /// use geoms::algorithm::point_in_polygon::Location;
+    ///
+    /// let site = Geometry::GeometryCollection { geometries: vec![
+    ///     Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] },
+    ///     Geometry::LineString { coordinates: vec![coord!(2, 2), coord!(8, 2)] },
+    ///     Geometry::Point { coordinates: coord!(9, 9) },
+    /// ] };
+    /// let locator = Locator::new(&site);
+    /// //the part of the line inside of the square is hidden by it
+    /// assert_eq!(locator.locate(&coord!(2, 2)), Location::Interior);
+    /// assert_eq!(locator.locate(&coord!(4, 1)), Location::Boundary);
+    /// assert_eq!(locator.locate(&coord!(6, 2)), Location::Interior);
+    /// assert_eq!(locator.locate(&coord!(8, 2)), Location::Boundary);
+    /// assert_eq!(locator.locate(&coord!(9, 9)), Location::Interior);
+    /// assert_eq!(locator.locate(&coord!(6, 3)), Location::Exterior);
+    /// ```
+    pub fn new(geometry: &Geometry) -> Locator {
+        let mut segments = Vec::new();
+        for (polygon, rings) in geometry.polygons().enumerate() {
+            for w in rings.iter().flat_map(|ring| ring.windows(2)) {
+                segments.push((Envelope::of_slice(w), (w[0].clone(), w[1].clone(), polygon)));
+            }
+        }
+        let max_x = segments.iter().fold(f64::NEG_INFINITY, |max_x, s| max_x.max(s.0.max_x()));
+        let (mut lines, mut ends) = (Vec::new(), HashMap::new());
+        let mut points: HashSet<Key> = extract::points(geometry).iter().map(Coordinate::key).collect();
+        for line in extract::lines(geometry) {
+            match line.len() {
+                0 => {}
+                1 => {
+                    points.insert(line[0].key());
+                }
+                n => {
+                    if !line[0].equals_2d(&line[n - 1]) {
+                        *ends.entry(line[0].key()).or_insert(0) += 1;
+                        *ends.entry(line[n - 1].key()).or_insert(0) += 1;
+                    }
+                    lines.extend(line.windows(2).map(|w| (Envelope::of_slice(w), (w[0].clone(), w[1].clone()))));
+                }
+            }
+        }
+        Locator { rings: Quadtree::new(segments), lines: Quadtree::new(lines), ends, points, max_x }
+    }
+
+    /// Returns the location of a point relative to the geometry.
+    pub fn locate(&self, p: &Coordinate) -> Location {
+        if let Some(location) = self.locate_in_area(p) {
+            return location;
+        }
+        let ends = self.ends.get(&p.key()).copied().unwrap_or(0);
+        if ends % 2 == 1 {
+            return Location::Boundary;
+        }
+        let around = Envelope::new(p.x(), p.y(), p.x(), p.y());
+        let on_line = || self.lines.query(&around).into_iter().any(|(a, b)| point_on_segment(p, a, b));
+        match ends > 0 || on_line() || self.points.contains(&p.key()) {
+            true => Location::Interior,
+            false => Location::Exterior,
+        }
+    }
+
+    /// Returns the location of a point relative to the polygons, or None if it is outside of
+    /// them and off their rings.
+    fn locate_in_area(&self, p: &Coordinate) -> Option<Location> {
+        if self.rings.is_empty() {
+            return None;
+        }
+        //the polygons whose rings the ray crosses an odd number of times, and those touching p
+        let (mut odd, mut touching) = (HashSet::new(), HashSet::new());
+        let ray = Envelope::new(p.x(), p.y(), self.max_x.max(p.x()), p.y());
+        for (a, b, polygon) in self.rings.query(&ray) {
+            if point_on_segment(p, a, b) {
+                touching.insert(*polygon);
+            } else if (a.y() > p.y()) != (b.y() > p.y()) {
+                let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+                if p.x() < x && !odd.remove(polygon) {
+                    odd.insert(*polygon);
+                }
+            }
+        }
+        if odd.difference(&touching).next().is_some() {
+            Some(Location::Interior)
+        } else if !touching.is_empty() {
+            Some(Location::Boundary)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::point_in_polygon::locate_in_polygon;
+    use crate::coord;
+    use crate::shapes;
+
+    #[test]
+    fn test_overlapping_polygons_match_direct_location() {
+        let disc = shapes::circle(&coord!(0, 0), 10.0, 200);
+        let frame = Geometry::Polygon { coordinates: vec![
+            vec![coord!(5, -5), coord!(20, -5), coord!(20, 5), coord!(5, 5), coord!(5, -5)],
+            vec![coord!(8, -2), coord!(8, 2), coord!(12, 2), coord!(12, -2), coord!(8, -2)],
+        ] };
+        let collection = Geometry::GeometryCollection { geometries: vec![disc.clone(), frame.clone()] };
+        let locator = Locator::new(&collection);
+        let polygons: Vec<Vec<Vec<Coordinate>>> = collection.polygons().cloned().collect();
+        for i in 0..=60 {
+            for j in 0..=40 {
+                let p = coord!(i as f64 * 0.5 - 12.0, j as f64 * 0.5 - 10.0);
+                let locations: Vec<Location> = polygons.iter().map(|rings| locate_in_polygon(&p, rings)).collect();
+                let expected = if locations.contains(&Location::Interior) {
+                    Location::Interior
+                } else if locations.contains(&Location::Boundary) {
+                    Location::Boundary
+                } else {
+                    Location::Exterior
+                };
+                assert_eq!(locator.locate(&p), expected, "{:?}", p);
+            }
+        }
+        //the hole of the frame is covered by the disc, but its far side is not
+        assert_eq!(locator.locate(&coord!(8, 0)), Location::Interior);
+        assert_eq!(locator.locate(&coord!(12, 0)), Location::Boundary);
+
+        //two lines ending at the same place, and a closed line, have no boundary there
+        let lines = Geometry::MultiLineString { coordinates: vec![
+            vec![coord!(0, 0), coord!(1, 0)],
+            vec![coord!(1, 0), coord!(1, 1)],
+            vec![coord!(5, 5), coord!(6, 5), coord!(5, 6), coord!(5, 5)],
+        ] };
+        let locator = Locator::new(&lines);
+        assert_eq!(locator.locate(&coord!(1, 0)), Location::Interior);
+        assert_eq!(locator.locate(&coord!(0, 0)), Location::Boundary);
+        assert_eq!(locator.locate(&coord!(5, 5)), Location::Interior);
+        assert_eq!(locator.locate(&coord!(0.5, 0.5)), Location::Exterior);
+        assert_eq!(Locator::new(&Geometry::GeometryCollection { geometries: vec![] }).locate(&coord!(0, 0)), Location::Exterior);
+    }
+}
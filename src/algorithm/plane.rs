@@ -0,0 +1,162 @@
+//! Planes fitted through the rings of 3D polygons, such as roof faces, to measure them in their
+//! own plane and check that they are flat.
+
+use crate::algorithm::area::ring_normal;
+use crate::coordinate::Coordinate;
+
+/// A plane in space, given by a point on it and its unit normal.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Plane {
+    /// A point of the plane, the mean of the vertices it was fitted to.
+    pub origin: Coordinate,
+    /// The unit vector perpendicular to the plane, pointing to where the rings turn counter
+    /// clockwise.
+    pub normal: [f64; 3],
+}
+
+impl Plane {
+    /// Returns the plane best fitting a set of rings, or None if they enclose no area.
+    ///
+    /// The normal is the sum of the normals of the rings computed with Newell's method, so the
+    /// holes of polygons, going the other way, are taken out of their shells, and the plane goes
+    /// through the mean of the vertices. The closing vertex of every ring is not counted twice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::algorithm::plane::Plane;
+    ///
+    /// //a roof face rising along y
+    /// let face = vec![vec![coord!(0, 0, 0), coord!(4, 0, 0), coord!(4, 3, 4), coord!(0, 3, 4), coord!(0, 0, 0)]];
+    /// let plane = Plane::fit(&face).unwrap();
+    /// assert_eq!(plane.origin, coord!(2, 1.5, 2));
+    /// assert_eq!(plane.normal, [0.0, -0.8, 0.6]);
+    /// assert_eq!(plane.signed_distance(&coord!(2, -2.5, 5)), 5.0);
+    /// ```
+    pub fn fit<'a>(rings: impl IntoIterator<Item = &'a Vec<Coordinate>>) -> Option<Plane> {
+        let (mut normal, mut sum, mut count) = ([0.0; 3], [0.0; 3], 0);
+        for ring in rings {
+            let [x, y, z] = ring_normal(ring);
+            normal = [normal[0] + x, normal[1] + y, normal[2] + z];
+            let open = match ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+                true => &ring[..ring.len() - 1],
+                false => &ring[..],
+            };
+            for c in open {
+                sum = [sum[0] + c.x(), sum[1] + c.y(), sum[2] + c.z()];
+            }
+            count += open.len();
+        }
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        if length.is_nan() || length <= 0.0 || count == 0 {
+            return None;
+        }
+        let n = count as f64;
+        Some(Plane {
+            origin: Coordinate::new(sum[0] / n, sum[1] / n, sum[2] / n),
+            normal: normal.map(|v| v / length),
+        })
+    }
+
+    /// Returns the distance from the plane to a point, positive on the side the normal points to.
+    pub fn signed_distance(&self, point: &Coordinate) -> f64 {
+        let [x, y, z] = self.normal;
+        (point.x() - self.origin.x()) * x + (point.y() - self.origin.y()) * y + (point.z() - self.origin.z()) * z
+    }
+
+    /// Returns the area of a ring projected on the plane.
+    pub fn ring_area(&self, ring: &[Coordinate]) -> f64 {
+        let [x, y, z] = ring_normal(ring);
+        (x * self.normal[0] + y * self.normal[1] + z * self.normal[2]).abs() / 2.0
+    }
+}
+
+/// Returns the area of a polygon given as its shell followed by its holes, with all its rings
+/// projected on the plane best fitting them, or 0 if they enclose no area.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::plane::polygon_area_in_plane;
+///
+/// let face = vec![
+///     vec![coord!(0, 0, 0), coord!(4, 0, 0), coord!(4, 3, 4), coord!(0, 3, 4), coord!(0, 0, 0)],
+///     vec![coord!(1, 0.75, 1), coord!(1, 2.25, 3), coord!(3, 2.25, 3), coord!(3, 0.75, 1), coord!(1, 0.75, 1)],
+/// ];
+/// assert!((polygon_area_in_plane(&face) - 15.0).abs() < 1e-12);
+/// ```
+pub fn polygon_area_in_plane(rings: &[Vec<Coordinate>]) -> f64 {
+    let plane = match Plane::fit(rings) {
+        Some(plane) => plane,
+        None => return 0.0,
+    };
+    match rings.split_first() {
+        Some((shell, holes)) => plane.ring_area(shell) - holes.iter().map(|hole| plane.ring_area(hole)).sum::<f64>(),
+        None => 0.0,
+    }
+}
+
+/// Returns true if every vertex of the rings lies within the tolerance of the plane best
+/// fitting them. Rings enclosing no area, as collinear ones, are planar.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::plane::is_planar;
+///
+/// let warped = vec![vec![coord!(0, 0, 0), coord!(4, 0, 0), coord!(4, 4, 0.1), coord!(0, 4, 0), coord!(0, 0, 0)]];
+/// assert!(is_planar(&warped, 0.05));
+/// assert!(!is_planar(&warped, 0.01));
+/// ```
+pub fn is_planar(rings: &[Vec<Coordinate>], tolerance: f64) -> bool {
+    match Plane::fit(rings) {
+        Some(plane) => rings.iter().flatten().all(|c| plane.signed_distance(c).abs() <= tolerance),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::area::polygon_area_3d;
+    use crate::coord;
+
+    #[test]
+    fn test_fit_of_tilted_and_warped_faces() {
+        //a square tilted about the x axis, with a hole going the other way
+        let (c, s) = (0.6, 0.8);
+        let tilt = |x: f64, y: f64| coord!(x, y * c, y * s);
+        let face = vec![
+            vec![tilt(0.0, 0.0), tilt(10.0, 0.0), tilt(10.0, 10.0), tilt(0.0, 10.0), tilt(0.0, 0.0)],
+            vec![tilt(2.0, 2.0), tilt(2.0, 4.0), tilt(4.0, 4.0), tilt(4.0, 2.0), tilt(2.0, 2.0)],
+        ];
+        let plane = Plane::fit(&face).unwrap();
+        assert!((plane.normal[0]).abs() < 1e-12 && (plane.normal[1] + s).abs() < 1e-12 && (plane.normal[2] - c).abs() < 1e-12);
+        assert!(face.iter().flatten().all(|p| plane.signed_distance(p).abs() < 1e-9));
+        assert!((polygon_area_in_plane(&face) - 96.0).abs() < 1e-9);
+        assert!((polygon_area_in_plane(&face) - polygon_area_3d(&face)).abs() < 1e-9);
+        assert!(is_planar(&face, 1e-9));
+
+        //lifting a corner warps the face, and its vertices leave the plane on both sides
+        let mut warped = face[..1].to_vec();
+        warped[0][2] = coord!(10, 6, 9);
+        let plane = Plane::fit(&warped).unwrap();
+        let distances: Vec<f64> = warped[0].iter().map(|p| plane.signed_distance(p)).collect();
+        assert!(distances.iter().any(|&d| d > 0.1) && distances.iter().any(|&d| d < -0.1));
+        assert!(!is_planar(&warped, 0.1));
+
+        //vertical and collinear rings
+        let wall = vec![vec![coord!(0, 0, 0), coord!(3, 4, 0), coord!(3, 4, 2), coord!(0, 0, 2), coord!(0, 0, 0)]];
+        assert_eq!(polygon_area_in_plane(&wall), 10.0);
+        let line = vec![vec![coord!(0, 0, 0), coord!(1, 1, 1), coord!(2, 2, 2), coord!(0, 0, 0)]];
+        assert_eq!(Plane::fit(&line), None);
+        assert!(is_planar(&line, 0.0));
+        assert_eq!(polygon_area_in_plane(&[]), 0.0);
+    }
+}
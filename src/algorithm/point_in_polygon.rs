@@ -0,0 +1,195 @@
+use crate::algorithm::line_intersection::point_on_segment;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::index::intervalrtree::IntervalRTree;
+
+/// The topological location of a point relative to a geometry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Location {
+    Interior,
+    Boundary,
+    Exterior,
+}
+
+/// Returns the location of the point relative to a ring, using the planar projection of the coordinates.
+/// The ring is expected to be closed.
+///
+/// # Arguments
+///
+/// * `p` - The point to locate.
+/// * `ring` - The coordinates of the ring.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::point_in_polygon::{locate_in_ring, Location};
+///
+/// let ring = vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)];
+/// assert_eq!(locate_in_ring(&coord!(2, 2), &ring), Location::Interior);
+/// assert_eq!(locate_in_ring(&coord!(4, 2), &ring), Location::Boundary);
+/// assert_eq!(locate_in_ring(&coord!(5, 2), &ring), Location::Exterior);
+/// ```
+pub fn locate_in_ring(p: &Coordinate, ring: &[Coordinate]) -> Location {
+    #[cfg(feature = "simd")]
+    let inside = match crate::algorithm::simd::crossings(p, ring) {
+        Some(inside) => inside,
+        None => return Location::Boundary,
+    };
+    #[cfg(not(feature = "simd"))]
+    let inside = {
+        let mut inside = false;
+        for window in ring.windows(2) {
+            let (a, b) = (&window[0], &window[1]);
+            if point_on_segment(p, a, b) {
+                return Location::Boundary;
+            }
+            //crossing number of a ray towards positive x
+            if (a.y() > p.y()) != (b.y() > p.y()) {
+                let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+                if p.x() < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    };
+    if inside {
+        Location::Interior
+    } else {
+        Location::Exterior
+    }
+}
+
+/// Returns the location of the point relative to a polygon, given as its shell followed by its holes.
+///
+/// # Arguments
+///
+/// * `p` - The point to locate.
+/// * `rings` - The rings of the polygon, the first one being the shell.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::point_in_polygon::{locate_in_polygon, Location};
+///
+/// let polygon = vec![
+///     vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+///     vec![coord!(1, 1), coord!(2, 1), coord!(2, 2), coord!(1, 2), coord!(1, 1)],
+/// ];
+/// assert_eq!(locate_in_polygon(&coord!(3, 3), &polygon), Location::Interior);
+/// assert_eq!(locate_in_polygon(&coord!(1.5, 1.5), &polygon), Location::Exterior);
+/// assert_eq!(locate_in_polygon(&coord!(2, 1.5), &polygon), Location::Boundary);
+/// ```
+pub fn locate_in_polygon(p: &Coordinate, rings: &[Vec<Coordinate>]) -> Location {
+    let mut rings = rings.iter();
+    let shell = match rings.next() {
+        Some(shell) => shell,
+        None => return Location::Exterior,
+    };
+    match locate_in_ring(p, shell) {
+        Location::Interior => {}
+        location => return location,
+    }
+    for hole in rings {
+        match locate_in_ring(p, hole) {
+            Location::Interior => return Location::Exterior,
+            Location::Boundary => return Location::Boundary,
+            Location::Exterior => {}
+        }
+    }
+    Location::Interior
+}
+
+/// Locates points relative to the polygonal parts of a geometry, answering repeated queries
+/// faster than [`locate_in_polygon`] by indexing the y ranges of the segments of all the rings,
+/// so only the segments crossing the horizontal line through a point are checked. The polygons
+/// are expected to be valid.
+pub struct IndexedPointInAreaLocator {
+    segments: IntervalRTree<(Coordinate, Coordinate)>,
+}
+
+impl IndexedPointInAreaLocator {
+    /// Builds a locator over the polygons of the geometry, which may be a polygon, a multipolygon
+    /// or a collection holding them. Other parts are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::algorithm::point_in_polygon::{IndexedPointInAreaLocator, Location};
+    ///
+    /// let polygons = Geometry::MultiPolygon { coordinates: vec![
+    ///     vec![
+    ///         vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+    ///         vec![coord!(1, 1), coord!(2, 1), coord!(2, 2), coord!(1, 2), coord!(1, 1)],
+    ///     ],
+    ///     vec![vec![coord!(6, 0), coord!(8, 0), coord!(8, 2), coord!(6, 0)]],
+    /// ]};
+    /// let locator = IndexedPointInAreaLocator::new(&polygons);
+    /// assert_eq!(locator.locate(&coord!(3, 3)), Location::Interior);
+    /// assert_eq!(locator.locate(&coord!(1.5, 1.5)), Location::Exterior);
+    /// assert_eq!(locator.locate(&coord!(7, 1)), Location::Boundary);
+    /// assert_eq!(locator.locate(&coord!(7.5, 1)), Location::Interior);
+    /// assert_eq!(locator.locate(&coord!(5, 1)), Location::Exterior);
+    /// ```
+    pub fn new(geometry: &Geometry) -> Self {
+        let segments = geometry
+            .polygons()
+            .flatten()
+            .flat_map(|ring| ring.windows(2))
+            .map(|w| (w[0].y(), w[1].y(), (w[0].clone(), w[1].clone())))
+            .collect();
+        Self { segments: IntervalRTree::new(segments) }
+    }
+
+    /// Returns the location of the point relative to the polygons.
+    pub fn locate(&self, p: &Coordinate) -> Location {
+        let mut inside = false;
+        for (a, b) in self.segments.query(p.y(), p.y()) {
+            if point_on_segment(p, a, b) {
+                return Location::Boundary;
+            }
+            //crossing number of a ray towards positive x, over the rings of all the polygons
+            if (a.y() > p.y()) != (b.y() > p.y()) {
+                let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+                if p.x() < x {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside {
+            Location::Interior
+        } else {
+            Location::Exterior
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::shapes;
+
+    #[test]
+    fn test_indexed_matches_direct() {
+        let disc = shapes::circle(&coord!(0, 0), 10.0, 500);
+        let rings = disc.polygons().next().unwrap().clone();
+        let locator = IndexedPointInAreaLocator::new(&disc);
+        for i in 0..=40 {
+            for j in 0..=40 {
+                let p = coord!(i as f64 - 20.0, j as f64 * 0.5 - 10.0);
+                assert_eq!(locator.locate(&p), locate_in_polygon(&p, &rings));
+            }
+        }
+        for vertex in &rings[0] {
+            assert_eq!(locator.locate(vertex), Location::Boundary);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! The DE-9IM intersection matrix of two geometries, telling the dimension of the intersection of
+//! the interior, boundary and exterior of one with those of the other.
+
+use std::fmt;
+
+use crate::algorithm::locate::Locator;
+use crate::algorithm::point_in_polygon::{locate_in_polygon, Location};
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::extract;
+use crate::operation::overlay::node_paths;
+
+/// The dimensions of the intersections of the interior, boundary and exterior of a geometry, in
+/// the rows, with those of another one, in the columns, None standing for an empty intersection.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IntersectionMatrix {
+    cells: [[Option<u8>; 3]; 3],
+}
+
+impl IntersectionMatrix {
+    /// Returns the dimension of the intersection of a part of the first geometry with a part of
+    /// the second, or None if they do not meet.
+    pub fn get(&self, a: Location, b: Location) -> Option<u8> {
+        self.cells[index(a)][index(b)]
+    }
+
+    /// Returns true if the matrix matches a pattern of nine characters read row by row, as
+    /// `T*F**FFF*`: `T` for any dimension, `F` for an empty intersection, `*` for anything, and
+    /// `0`, `1` or `2` for that dimension.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern does not have nine characters.
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        if pattern.len() != 9 {
+            panic!("A DE-9IM pattern must have nine characters");
+        }
+        self.cells.iter().flatten().zip(pattern).all(|(cell, p)| match (p, cell) {
+            ('*', _) => true,
+            ('T' | 't', Some(_)) => true,
+            ('F' | 'f', None) => true,
+            (p, Some(d)) => p.to_digit(10) == Some(*d as u32),
+            _ => false,
+        })
+    }
+
+    /// Raises the dimension of a cell to the given one.
+    fn set(&mut self, a: Location, b: Location, dimension: u8) {
+        let cell = &mut self.cells[index(a)][index(b)];
+        *cell = (*cell).max(Some(dimension));
+    }
+}
+
+impl fmt::Display for IntersectionMatrix {
+    /// Writes the matrix as its nine characters read row by row, as `212101212`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for cell in self.cells.iter().flatten() {
+            match cell {
+                Some(d) => write!(f, "{}", d)?,
+                None => write!(f, "F")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn index(location: Location) -> usize {
+    match location {
+        Location::Interior => 0,
+        Location::Boundary => 1,
+        Location::Exterior => 2,
+    }
+}
+
+/// Returns the DE-9IM intersection matrix of two geometries of any type, in the plane.
+///
+/// The lines and the rings of both geometries are split where they meet, and every piece, every
+/// node and every point is located relative to both geometries. The areas are located by sampling
+/// both sides of every piece close to its middle, so polygons meeting at slivers thinner than a
+/// millionth of the length of their edges may be missed. The boundary of lines follows the mod 2
+/// rule: it is made of the ends shared by an odd number of open lines.
+///
+/// # Arguments
+///
+/// * `a` - The geometry of the rows.
+/// * `b` - The geometry of the columns.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::algorithm::relate::relate;
+///
+/// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+/// let road = Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(3, 1)] };
+/// let matrix = relate(&square, &road);
+/// assert_eq!(matrix.to_string(), "1020F1102");
+/// //the road crosses the square
+/// assert!(matrix.matches("1*T***T**"));
+/// ```
+pub fn relate(a: &Geometry, b: &Geometry) -> IntersectionMatrix {
+    let mut matrix = IntersectionMatrix { cells: [[None; 3]; 3] };
+    matrix.set(Location::Exterior, Location::Exterior, 2);
+    let (paths_a, paths_b) = (paths(a), paths(b));
+    let pieces = node_paths(paths_a.iter().chain(&paths_b).map(|p| p.as_slice()));
+    let (locator_a, locator_b) = (Locator::new(a), Locator::new(b));
+    let locate_both = |c: &Coordinate| (locator_a.locate(c), locator_b.locate(c));
+    for (start, end) in &pieces {
+        for node in [start, end] {
+            let (la, lb) = locate_both(node);
+            matrix.set(la, lb, 0);
+        }
+        let middle = Coordinate::new((start.x() + end.x()) / 2.0, (start.y() + end.y()) / 2.0, 0.0);
+        let (la, lb) = locate_both(&middle);
+        matrix.set(la, lb, 1);
+        //the faces on both sides of the piece
+        let (nx, ny) = ((start.y() - end.y()) * 1e-7, (end.x() - start.x()) * 1e-7);
+        for side in [1.0, -1.0] {
+            let p = Coordinate::new(middle.x() + side * nx, middle.y() + side * ny, 0.0);
+            if let (Some(la), Some(lb)) = (locate_area(a, &p), locate_area(b, &p)) {
+                matrix.set(la, lb, 2);
+            }
+        }
+    }
+    for p in extract::points(a).iter().chain(&extract::points(b)) {
+        let (la, lb) = locate_both(p);
+        matrix.set(la, lb, 0);
+    }
+    matrix
+}
+
+/// Returns the lines and the rings of the polygons of the geometry.
+fn paths(geometry: &Geometry) -> Vec<Vec<Coordinate>> {
+    let mut paths = extract::lines(geometry);
+    paths.extend(geometry.polygons().flatten().cloned());
+    paths
+}
+
+/// Returns whether a point off the lines and rings is inside of the polygons of a geometry, or
+/// None if it turns out to be on a ring.
+fn locate_area(geometry: &Geometry, p: &Coordinate) -> Option<Location> {
+    let mut location = Location::Exterior;
+    for polygon in geometry.polygons() {
+        match locate_in_polygon(p, polygon) {
+            Location::Interior => location = Location::Interior,
+            Location::Boundary => return None,
+            Location::Exterior => {}
+        }
+    }
+    Some(location)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_matrices_of_every_dimension() {
+        let square = |x: f64| Geometry::Polygon { coordinates: vec![vec![
+            coord!(x, 0), coord!(x + 2.0, 0), coord!(x + 2.0, 2), coord!(x, 2), coord!(x, 0)
+        ]] };
+        let cases = [
+            //overlapping, touching along an edge, apart and equal polygons
+            (square(0.0), Geometry::Polygon { coordinates: vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]] }, "212101212"),
+            (square(0.0), square(1.0), "212111212"),
+            (square(0.0), square(2.0), "FF2F11212"),
+            (square(0.0), square(5.0), "FF2FF1212"),
+            (square(0.0), square(0.0), "2FFF1FFF2"),
+            //a line ending on the boundary of the square, and one along it
+            (Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(2, 1)] }, square(0.0), "1FF00F212"),
+            (Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 0)] }, square(0.0), "F1FF0F212"),
+            //crossing lines, and a closed line, which has no boundary, touched by a point
+            (
+                Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 2)] },
+                Geometry::LineString { coordinates: vec![coord!(0, 2), coord!(2, 0)] },
+                "0F1FF0102",
+            ),
+            (
+                Geometry::Point { coordinates: coord!(0, 0) },
+                Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(0, 1), coord!(0, 0)] },
+                "0FFFFF1F2",
+            ),
+            (Geometry::MultiPoint { coordinates: vec![coord!(0, 0), coord!(9, 9)] }, square(0.0), "F00FFF212"),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(relate(&a, &b).to_string(), expected, "{} {}", a.as_text(), b.as_text());
+            //the matrix of the other order is transposed
+            let transposed: String = [0, 3, 6, 1, 4, 7, 2, 5, 8].iter().map(|&i| expected.as_bytes()[i] as char).collect();
+            assert_eq!(relate(&b, &a).to_string(), transposed);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Comparison of the shapes of polygons regardless of their position, size and orientation, such
+//! as telling whether two footprints digitized from different sources are the same building.
+
+use std::f64::consts::PI;
+
+use crate::algorithm::area::ring_signed_area;
+use crate::coordinate::Coordinate;
+
+/// Returns the similarity of the shapes of the shells of two polygons between 0 and 1, or None if
+/// any of them has no area.
+///
+/// The shells are compared through their turning functions, which give the direction of the
+/// boundary along its length scaled to 1, as in the method of Arkin et al. The distance between
+/// the shapes is the smallest L2 distance between the turning functions over every rotation and
+/// every pair of starting vertices, and the similarity is one minus that distance over π, or 0
+/// when the distance is larger. It does not change when a polygon is moved, scaled, rotated or
+/// digitized from another vertex or in the other direction, and is 1 for the same shape, but it
+/// tells mirror images apart.
+///
+/// # Arguments
+///
+/// * `a` - The rings of the first polygon. Only its shell is compared.
+/// * `b` - The rings of the second polygon. Only its shell is compared.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::shape_match::similarity;
+///
+/// let footprint = vec![vec![coord!(0, 0), coord!(20, 0), coord!(20, 10), coord!(10, 10), coord!(10, 20), coord!(0, 20), coord!(0, 0)]];
+/// //the same building, twice as large, turned a right angle and digitized clockwise
+/// let other_source = vec![vec![coord!(100, 100), coord!(60, 100), coord!(60, 120), coord!(80, 120), coord!(80, 140), coord!(100, 140), coord!(100, 100)]];
+/// assert!((similarity(&footprint, &other_source).unwrap() - 1.0).abs() < 1e-9);
+///
+/// let square = vec![vec![coord!(0, 0), coord!(20, 0), coord!(20, 20), coord!(0, 20), coord!(0, 0)]];
+/// assert!(similarity(&footprint, &square).unwrap() < 0.9);
+/// ```
+pub fn similarity(a: &[Vec<Coordinate>], b: &[Vec<Coordinate>]) -> Option<f64> {
+    let a = turning_functions(a.first()?)?;
+    let b = turning_functions(b.first()?)?;
+    let distance = a
+        .iter()
+        .flat_map(|fa| b.iter().map(move |fb| turning_distance(fa, fb)))
+        .fold(f64::INFINITY, f64::min);
+    Some((1.0 - distance / PI).max(0.0))
+}
+
+/// A turning function: the start of every edge along the boundary, from 0 to 1, with the
+/// direction of the edge relative to the first one, in radians.
+type TurningFunction = Vec<(f64, f64)>;
+
+/// Returns the turning functions of a ring starting at each of its vertices, going
+/// counterclockwise, or None if the ring has no area.
+fn turning_functions(ring: &[Coordinate]) -> Option<Vec<TurningFunction>> {
+    let mut vertices: Vec<&Coordinate> = ring.iter().collect();
+    vertices.dedup_by(|a, b| a.equals_2d(b));
+    if vertices.len() > 1 && vertices[0].equals_2d(vertices[vertices.len() - 1]) {
+        vertices.pop();
+    }
+    let signed_area = ring_signed_area(ring);
+    if vertices.len() < 3 || signed_area == 0.0 {
+        return None;
+    }
+    if signed_area < 0.0 {
+        vertices.reverse();
+    }
+    let n = vertices.len();
+    //the length, direction and turn at the start of every edge
+    let edges: Vec<(f64, f64)> = (0..n)
+        .map(|i| {
+            let (p, q) = (vertices[i], vertices[(i + 1) % n]);
+            ((q.x() - p.x()).hypot(q.y() - p.y()), (q.y() - p.y()).atan2(q.x() - p.x()))
+        })
+        .collect();
+    let perimeter: f64 = edges.iter().map(|e| e.0).sum();
+    let turns: Vec<f64> = (0..n)
+        .map(|i| {
+            let turn = edges[i].1 - edges[(i + n - 1) % n].1;
+            (turn + PI).rem_euclid(2.0 * PI) - PI
+        })
+        .collect();
+    Some(
+        (0..n)
+            .map(|start| {
+                let (mut s, mut angle) = (0.0, 0.0);
+                (0..n)
+                    .map(|k| {
+                        let i = (start + k) % n;
+                        if k > 0 {
+                            angle += turns[i];
+                        }
+                        let step = (s, angle);
+                        s += edges[i].0 / perimeter;
+                        step
+                    })
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+/// Returns the L2 distance between two turning functions after turning one of them by the angle
+/// bringing them closest.
+fn turning_distance(a: &TurningFunction, b: &TurningFunction) -> f64 {
+    let mut breaks: Vec<f64> = a.iter().chain(b.iter()).map(|step| step.0).chain([1.0]).collect();
+    breaks.sort_by(f64::total_cmp);
+    breaks.dedup();
+    let value = |f: &TurningFunction, s: f64| f[f.partition_point(|step| step.0 <= s) - 1].1;
+    let (mut sum, mut sum_squared) = (0.0, 0.0);
+    for w in breaks.windows(2) {
+        let difference = value(a, w[0]) - value(b, w[0]);
+        sum += difference * (w[1] - w[0]);
+        sum_squared += difference * difference * (w[1] - w[0]);
+    }
+    //the best rotation removes the mean of the difference
+    (sum_squared - sum * sum).max(0.0).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_similarity_of_rectangles_and_degenerate_rings() {
+        let rectangle = |width: f64| vec![vec![coord!(0, 0), coord!(width, 0), coord!(width, 1), coord!(0, 1), coord!(0, 0)]];
+        //a redundant vertex along an edge changes nothing
+        let with_collinear = vec![vec![coord!(0, 0), coord!(1, 0), coord!(2, 0), coord!(2, 1), coord!(0, 1), coord!(0, 0)]];
+        assert!((similarity(&rectangle(2.0), &with_collinear).unwrap() - 1.0).abs() < 1e-12);
+        //the more elongated, the less similar to a square
+        let square = rectangle(1.0);
+        let scores: Vec<f64> = [1.5, 3.0, 10.0].iter().map(|&w| similarity(&square, &rectangle(w)).unwrap()).collect();
+        assert!(scores[0] > scores[1] && scores[1] > scores[2]);
+        assert!((similarity(&rectangle(3.0), &square).unwrap() - scores[1]).abs() < 1e-12);
+        let flat = vec![vec![coord!(0, 0), coord!(1, 1), coord!(2, 2), coord!(0, 0)]];
+        assert_eq!(similarity(&square, &flat), None);
+        assert_eq!(similarity(&square, &[]), None);
+    }
+}
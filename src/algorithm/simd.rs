@@ -0,0 +1,164 @@
+//! Vectorised versions of the hot loops over coordinate slices, enabled by the `simd` feature.
+//!
+//! The loops work on fixed size chunks with one accumulator per lane and no branches inside a
+//! chunk, a shape the compiler turns into SIMD instructions on stable Rust without intrinsics.
+//! The results match the scalar loops, except for the rounding of the shoelace sum, which is
+//! added in a different order.
+
+use crate::algorithm::line_intersection::point_on_segment;
+use crate::coordinate::Coordinate;
+
+const LANES: usize = 4;
+
+/// Returns twice the signed area of a ring, computed relative to its first vertex.
+pub(crate) fn shoelace(ring: &[Coordinate]) -> f64 {
+    let Some(origin) = ring.first() else {
+        return 0.0;
+    };
+    let (ox, oy) = (origin.x(), origin.y());
+    let segments = ring.len() - 1;
+    let mut sums = [0.0; LANES];
+    let mut start = 0;
+    while start + LANES <= segments {
+        let (a, b) = (&ring[start..start + LANES], &ring[start + 1..start + LANES + 1]);
+        for lane in 0..LANES {
+            sums[lane] += (a[lane].x() - ox) * (b[lane].y() - oy) - (b[lane].x() - ox) * (a[lane].y() - oy);
+        }
+        start += LANES;
+    }
+    let mut sum = sums.iter().sum::<f64>();
+    for w in ring[start..].windows(2) {
+        sum += (w[0].x() - ox) * (w[1].y() - oy) - (w[1].x() - ox) * (w[0].y() - oy);
+    }
+    sum
+}
+
+/// Returns the minimum and maximum x and y of the coordinates, or None if there are none.
+pub(crate) fn bounds(coordinates: &[Coordinate]) -> Option<[f64; 4]> {
+    let first = coordinates.first()?;
+    let mut min_x = [first.x(); LANES];
+    let mut min_y = [first.y(); LANES];
+    let mut max_x = min_x;
+    let mut max_y = min_y;
+    //comparisons rather than f64::min and f64::max, whose handling of NaN does not vectorise
+    let mut chunks = coordinates.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        for lane in 0..LANES {
+            let (x, y) = (chunk[lane].x(), chunk[lane].y());
+            min_x[lane] = if x < min_x[lane] { x } else { min_x[lane] };
+            min_y[lane] = if y < min_y[lane] { y } else { min_y[lane] };
+            max_x[lane] = if x > max_x[lane] { x } else { max_x[lane] };
+            max_y[lane] = if y > max_y[lane] { y } else { max_y[lane] };
+        }
+    }
+    for c in chunks.remainder() {
+        min_x[0] = min_x[0].min(c.x());
+        min_y[0] = min_y[0].min(c.y());
+        max_x[0] = max_x[0].max(c.x());
+        max_y[0] = max_y[0].max(c.y());
+    }
+    let fold = |values: [f64; LANES], f: fn(f64, f64) -> f64| values.into_iter().reduce(f).unwrap_or(f64::NAN);
+    Some([fold(min_x, f64::min), fold(min_y, f64::min), fold(max_x, f64::max), fold(max_y, f64::max)])
+}
+
+/// Returns None if the point is on the ring, or else whether a ray from the point towards
+/// positive x crosses the ring an odd number of times.
+pub(crate) fn crossings(p: &Coordinate, ring: &[Coordinate]) -> Option<bool> {
+    let (px, py) = (p.x(), p.y());
+    let segments = ring.len().saturating_sub(1);
+    let mut inside = false;
+    let mut start = 0;
+    while start + LANES <= segments {
+        let (a, b) = (&ring[start..start + LANES], &ring[start + 1..start + LANES + 1]);
+        //the lanes are only screened here, the few segments whose box holds the point or that
+        //straddle its ray being checked one by one below
+        let mut flagged = false;
+        for lane in 0..LANES {
+            let (ax, ay, bx, by) = (a[lane].x(), a[lane].y(), b[lane].x(), b[lane].y());
+            let near = (px >= ax || px >= bx) & (px <= ax || px <= bx) & (py >= ay || py >= by) & (py <= ay || py <= by);
+            let straddles = (ay > py) != (by > py);
+            flagged |= near | straddles;
+        }
+        if flagged {
+            for lane in 0..LANES {
+                match segment_crossing(p, &a[lane], &b[lane]) {
+                    None => return None,
+                    Some(crosses) => inside ^= crosses,
+                }
+            }
+        }
+        start += LANES;
+    }
+    for w in ring[start..].windows(2) {
+        match segment_crossing(p, &w[0], &w[1]) {
+            None => return None,
+            Some(crosses) => inside ^= crosses,
+        }
+    }
+    Some(inside)
+}
+
+/// Returns None if the point is on the segment, or else whether the ray from the point towards
+/// positive x crosses it, as the scalar loop does.
+fn segment_crossing(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> Option<bool> {
+    if point_on_segment(p, a, b) {
+        return None;
+    }
+    if (a.y() > p.y()) != (b.y() > p.y()) {
+        let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+        return Some(p.x() < x);
+    }
+    Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_matches_scalar() {
+        //a star shaped ring with many vertices and points around and on it
+        let n = 1001;
+        let mut ring: Vec<Coordinate> = (0..n)
+            .map(|i| {
+                let angle = i as f64 / n as f64 * std::f64::consts::TAU;
+                let radius = if i % 2 == 0 { 10.0 } else { 4.0 };
+                coord!(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        ring.push(ring[0].clone());
+
+        let scalar: f64 = ring.windows(2).map(|w| {
+            let (ax, ay) = (w[0].x() - ring[0].x(), w[0].y() - ring[0].y());
+            let (bx, by) = (w[1].x() - ring[0].x(), w[1].y() - ring[0].y());
+            ax * by - bx * ay
+        }).sum();
+        assert!((shoelace(&ring) - scalar).abs() < 1e-9 * scalar.abs());
+
+        let xs = ring.iter().map(|c| c.x());
+        let ys = ring.iter().map(|c| c.y());
+        assert_eq!(
+            bounds(&ring),
+            Some([xs.clone().fold(f64::INFINITY, f64::min), ys.clone().fold(f64::INFINITY, f64::min), xs.fold(f64::NEG_INFINITY, f64::max), ys.fold(f64::NEG_INFINITY, f64::max)])
+        );
+
+        for i in 0..200 {
+            let p = coord!((i % 20) as f64 - 10.0, (i / 20) as f64 * 2.0 - 10.0);
+            let mut expected = Some(false);
+            for w in ring.windows(2) {
+                if point_on_segment(&p, &w[0], &w[1]) {
+                    expected = None;
+                    break;
+                }
+                if (w[0].y() > p.y()) != (w[1].y() > p.y())
+                    && p.x() < w[0].x() + (p.y() - w[0].y()) * (w[1].x() - w[0].x()) / (w[1].y() - w[0].y())
+                {
+                    expected = expected.map(|inside| !inside);
+                }
+            }
+            assert_eq!(crossings(&p, &ring), expected);
+        }
+        assert_eq!(crossings(&ring[7], &ring), None);
+    }
+}
@@ -0,0 +1,606 @@
+//! Centerlines of polygons: the medial axis approximated from a Voronoi diagram, and the straight
+//! skeleton.
+
+use std::collections::HashMap;
+
+use crate::algorithm::point_in_polygon::{locate_in_polygon, Location};
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::triangulate::delaunay;
+
+/// Returns an approximation of the medial axis of a polygon, the set of centers of the circles
+/// touching its boundary at two points or more, as lines merged at their branching points.
+///
+/// The rings are densified so that no segment is longer than the spacing, and the axis is made of
+/// the edges of the Voronoi diagram of their vertices lying inside of the polygon. A smaller
+/// spacing gives a more accurate axis at a higher cost.
+///
+/// # Arguments
+///
+/// * `polygon` - The rings of the polygon, the shell first.
+/// * `spacing` - The maximum distance between the boundary points. It must be positive.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::skeleton::approximate_medial_axis;
+///
+/// let rectangle = vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 2), coord!(0, 2), coord!(0, 0)]];
+/// let axis = approximate_medial_axis(&rectangle, 0.25);
+/// //the longest line runs along the middle of the rectangle
+/// let longest = axis.iter().max_by(|a, b| a.len().cmp(&b.len())).unwrap();
+/// assert!(longest.iter().all(|c| (0.9..=1.1).contains(&c.y())));
+/// ```
+pub fn approximate_medial_axis(polygon: &[Vec<Coordinate>], spacing: f64) -> Vec<Vec<Coordinate>> {
+    if !(spacing.is_finite() && spacing > 0.0) {
+        panic!("The spacing of the boundary points must be positive");
+    }
+    let mut points = Vec::new();
+    //the indices of the first and last points of the ring of every point
+    let mut ring_ranges = Vec::new();
+    for ring in polygon {
+        let ring_start = points.len();
+        for segment in ring.windows(2) {
+            let (a, b) = (&segment[0], &segment[1]);
+            let length = (b.x() - a.x()).hypot(b.y() - a.y());
+            let steps = ((length / spacing).ceil() as usize).max(1);
+            for step in 0..steps {
+                let t = step as f64 / steps as f64;
+                points.push(Coordinate::new(a.x() + t * (b.x() - a.x()), a.y() + t * (b.y() - a.y()), 0.0));
+            }
+        }
+        if points.len() > ring_start {
+            ring_ranges.resize(points.len(), (ring_start, points.len() - 1));
+        }
+    }
+    //the edges between consecutive boundary points are crossed by the boundary itself
+    let consecutive = |a: usize, b: usize| {
+        let (a, b) = (a.min(b), a.max(b));
+        ring_ranges[a] == ring_ranges[b] && (b - a == 1 || (a, b) == ring_ranges[a])
+    };
+
+    //the circumcenters of the Delaunay triangles are the vertices of the Voronoi diagram
+    let triangles = delaunay(&points);
+    let centers: Vec<Option<Coordinate>> = triangles
+        .iter()
+        .map(|t| {
+            let center = circumcenter(&points[t[0]], &points[t[1]], &points[t[2]])?;
+            match locate_in_polygon(&center, polygon) {
+                Location::Interior => Some(center),
+                _ => None,
+            }
+        })
+        .collect();
+
+    //every Delaunay edge shared by two triangles is crossed by the Voronoi edge joining their centers
+    let mut first_triangle: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut segments = Vec::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let edge = (t[k].min(t[(k + 1) % 3]), t[k].max(t[(k + 1) % 3]));
+            if let Some(j) = first_triangle.insert(edge, i) {
+                if consecutive(edge.0, edge.1) {
+                    continue;
+                }
+                if let (Some(a), Some(b)) = (&centers[i], &centers[j]) {
+                    segments.push((a.clone(), b.clone()));
+                }
+            }
+        }
+    }
+    merge_segments(segments)
+}
+
+fn circumcenter(a: &Coordinate, b: &Coordinate, c: &Coordinate) -> Option<Coordinate> {
+    let (bx, by) = (b.x() - a.x(), b.y() - a.y());
+    let (cx, cy) = (c.x() - a.x(), c.y() - a.y());
+    let d = 2.0 * (bx * cy - by * cx);
+    if d == 0.0 {
+        return None;
+    }
+    let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+    let x = (cy * b2 - by * c2) / d;
+    let y = (bx * c2 - cx * b2) / d;
+    Some(Coordinate::new(a.x() + x, a.y() + y, 0.0))
+}
+
+/// Returns the straight skeleton of a polygon, the trace of the vertices of its boundary while
+/// every edge moves inwards at the same speed, as lines merged at their branching points.
+///
+/// The skeleton is computed by simulating the shrinking boundary, handling the events where an
+/// edge collapses, or where a reflex vertex splits an edge, one at a time. Every event checks all
+/// the pairs of vertices and edges, so the cost grows with the cube of the number of vertices.
+///
+/// # Arguments
+///
+/// * `polygon` - The rings of the polygon, the shell first.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::algorithm::skeleton::straight_skeleton;
+///
+/// let rectangle = vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 2), coord!(0, 2), coord!(0, 0)]];
+/// let skeleton = straight_skeleton(&rectangle);
+/// assert_eq!(skeleton.len(), 5);
+/// assert!(skeleton.contains(&vec![coord!(1, 1), coord!(3, 1)]) || skeleton.contains(&vec![coord!(3, 1), coord!(1, 1)]));
+/// ```
+pub fn straight_skeleton(polygon: &[Vec<Coordinate>]) -> Vec<Vec<Coordinate>> {
+    let envelope = Envelope::of(polygon.iter().flatten());
+    if envelope.is_empty() {
+        return vec![];
+    }
+    let scale = envelope.width().max(envelope.height());
+    let mut wavefront = Wavefront { polygons: Vec::new(), arcs: Vec::new(), epsilon: scale * 1e-9 };
+
+    for (i, ring) in polygon.iter().enumerate() {
+        let mut points: Vec<(f64, f64)> = ring.iter().map(|c| (c.x(), c.y())).collect();
+        points.dedup();
+        if points.len() > 1 && points[0] == points[points.len() - 1] {
+            points.pop();
+        }
+        //the inside of the polygon lies on the left of the shell and of the holes
+        if (signed_area(&points) > 0.0) != (i == 0) {
+            points.reverse();
+        }
+        let vertices = wavefront.simplify(points);
+        if vertices.len() >= 3 {
+            wavefront.polygons.push(vertices);
+        } else if i == 0 {
+            return vec![];
+        }
+    }
+    wavefront.run();
+
+    let segments = wavefront
+        .arcs
+        .iter()
+        .map(|(a, b)| (Coordinate::new(a.0, a.1, 0.0), Coordinate::new(b.0, b.1, 0.0)))
+        .collect();
+    merge_segments(segments)
+}
+
+type Point = (f64, f64);
+
+/// A vertex of the shrinking boundary, with the directions of the edges before and after it.
+#[derive(Debug, Clone)]
+struct Vertex {
+    position: Point,
+    start: Point,
+    before: Point,
+    after: Point,
+    velocity: Option<Point>,
+}
+
+impl Vertex {
+    fn new(position: Point, start: Point, before: Point, after: Point) -> Vertex {
+        //the vertex moves away from both edge lines at unit speed, which is impossible when the
+        //edges run back on each other
+        let (n1, n2) = ((-before.1, before.0), (-after.1, after.0));
+        let denominator = 1.0 + n1.0 * n2.0 + n1.1 * n2.1;
+        let velocity = if denominator < 1e-9 {
+            None
+        } else {
+            Some(((n1.0 + n2.0) / denominator, (n1.1 + n2.1) / denominator))
+        };
+        Vertex { position, start, before, after, velocity }
+    }
+
+    fn is_reflex(&self) -> bool {
+        cross(self.before, self.after) < 0.0
+    }
+
+    fn position_at(&self, time: f64) -> Point {
+        let velocity = self.velocity.unwrap_or((0.0, 0.0));
+        (self.position.0 + time * velocity.0, self.position.1 + time * velocity.1)
+    }
+}
+
+enum Event {
+    /// An edge collapses, or any pair of neighbouring vertices meets.
+    Collapse,
+    /// A reflex vertex of a polygon hits an edge of the same or of another polygon.
+    Split { polygon: usize, vertex: usize, target: usize, edge: usize },
+}
+
+struct Wavefront {
+    polygons: Vec<Vec<Vertex>>,
+    arcs: Vec<(Point, Point)>,
+    epsilon: f64,
+}
+
+impl Wavefront {
+    /// Creates the vertices of a ring, dropping the vertices between collinear edges.
+    fn simplify(&self, mut points: Vec<Point>) -> Vec<Vertex> {
+        loop {
+            let n = points.len();
+            if n < 3 {
+                return vec![];
+            }
+            let straight = (0..n).find(|&i| {
+                let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+                let (u, v) = (direction(a, b), direction(b, c));
+                u.is_none() || v.is_none() || (cross(u.unwrap(), v.unwrap()).abs() < 1e-12 && dot(u.unwrap(), v.unwrap()) > 0.0)
+            });
+            match straight {
+                Some(i) => {
+                    points.remove(i);
+                }
+                None => break,
+            }
+        }
+        let n = points.len();
+        (0..n)
+            .map(|i| {
+                let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+                Vertex::new(b, b, direction(a, b).unwrap(), direction(b, c).unwrap())
+            })
+            .collect()
+    }
+
+    fn run(&mut self) {
+        let vertex_count: usize = self.polygons.iter().map(|p| p.len()).sum();
+        let mut remaining = 4 * vertex_count * vertex_count + 16;
+        self.clean();
+        while !self.polygons.is_empty() && remaining > 0 {
+            remaining -= 1;
+            let (time, event) = match self.next_event() {
+                Some(next) => next,
+                None => break,
+            };
+            for polygon in &mut self.polygons {
+                for vertex in polygon.iter_mut() {
+                    vertex.position = vertex.position_at(time);
+                }
+            }
+            if let Event::Split { polygon, vertex, target, edge } = event {
+                self.split(polygon, vertex, target, edge);
+            }
+            self.clean();
+        }
+        //whatever is left when the events run out stays where it is
+        for polygon in std::mem::take(&mut self.polygons) {
+            for vertex in polygon {
+                self.arc(vertex.start, vertex.position);
+            }
+        }
+    }
+
+    fn arc(&mut self, a: Point, b: Point) {
+        if a != b {
+            self.arcs.push((a, b));
+        }
+    }
+
+    fn coincide(&self, a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() <= self.epsilon && (a.1 - b.1).abs() <= self.epsilon
+    }
+
+    /// Returns the time to the next event and the event.
+    fn next_event(&self) -> Option<(f64, Event)> {
+        let mut next: Option<(f64, Event)> = None;
+        let mut consider = |time: f64, event: Event| {
+            if time.is_finite() && next.as_ref().is_none_or(|(t, _)| time < *t) {
+                next = Some((time.max(0.0), event));
+            }
+        };
+        for (p, polygon) in self.polygons.iter().enumerate() {
+            let n = polygon.len();
+            for i in 0..n {
+                let (a, b) = (&polygon[i], &polygon[(i + 1) % n]);
+                //edges shorten when their ends move towards each other
+                let (va, vb) = (a.velocity.unwrap_or((0.0, 0.0)), b.velocity.unwrap_or((0.0, 0.0)));
+                let rate = dot((vb.0 - va.0, vb.1 - va.1), a.after);
+                let length = dot((b.position.0 - a.position.0, b.position.1 - a.position.1), a.after);
+                if rate < 0.0 {
+                    consider(length / -rate, Event::Collapse);
+                }
+            }
+            for (v, vertex) in polygon.iter().enumerate() {
+                let velocity = match vertex.velocity {
+                    Some(velocity) if vertex.is_reflex() => velocity,
+                    _ => continue,
+                };
+                for (q, target) in self.polygons.iter().enumerate() {
+                    let m = target.len();
+                    for j in 0..m {
+                        if q == p && (j == v || (j + 1) % m == v) {
+                            continue;
+                        }
+                        let (a, b) = (&target[j], &target[(j + 1) % m]);
+                        let normal = (-a.after.1, a.after.0);
+                        let distance = dot((vertex.position.0 - a.position.0, vertex.position.1 - a.position.1), normal);
+                        let rate = 1.0 - dot(velocity, normal);
+                        if distance < -self.epsilon || rate <= 0.0 {
+                            continue;
+                        }
+                        let time = distance.max(0.0) / rate;
+                        //the hit must fall inside of the edge as it will be then
+                        let hit = vertex.position_at(time);
+                        let (start, end) = (a.position_at(time), b.position_at(time));
+                        if dot((hit.0 - start.0, hit.1 - start.1), a.after) >= -self.epsilon
+                            && dot((end.0 - hit.0, end.1 - hit.1), a.after) >= -self.epsilon
+                        {
+                            consider(time, Event::Split { polygon: p, vertex: v, target: q, edge: j });
+                        }
+                    }
+                }
+            }
+        }
+        next
+    }
+
+    /// Splits a polygon in two where its vertex hit one of its edges, or joins two polygons where
+    /// the vertex of one hit an edge of the other.
+    fn split(&mut self, p: usize, v: usize, q: usize, j: usize) {
+        let vertex = self.polygons[p][v].clone();
+        let edge = self.polygons[q][j].after;
+        let hit = vertex.position;
+        self.arc(vertex.start, hit);
+        let first = Vertex::new(hit, hit, vertex.before, edge);
+        let second = Vertex::new(hit, hit, edge, vertex.after);
+        let rotate = |polygon: &Vec<Vertex>, from: usize, to: usize| -> Vec<Vertex> {
+            //the vertices from one index to another, both included, going round the polygon
+            let n = polygon.len();
+            let count = (to + n - from) % n + 1;
+            (0..count).map(|k| polygon[(from + k) % n].clone()).collect()
+        };
+        if p == q {
+            let polygon = self.polygons.swap_remove(p);
+            let n = polygon.len();
+            let mut one = vec![first];
+            one.extend(rotate(&polygon, (j + 1) % n, (v + n - 1) % n));
+            let mut two = vec![second];
+            two.extend(rotate(&polygon, (v + 1) % n, j));
+            self.polygons.push(one);
+            self.polygons.push(two);
+        } else {
+            let target = &self.polygons[q];
+            let m = target.len();
+            let mut joined = vec![first];
+            joined.extend(rotate(target, (j + 1) % m, j));
+            joined.push(second);
+            let polygon = &self.polygons[p];
+            let n = polygon.len();
+            if n > 1 {
+                joined.extend(rotate(polygon, (v + 1) % n, (v + n - 1) % n));
+            }
+            self.polygons[p] = joined;
+            self.polygons.swap_remove(q);
+        }
+    }
+
+    /// Merges the neighbouring vertices that met, lets the vertices between edges running back on
+    /// each other slide to their closest neighbour, and removes the polygons that collapsed.
+    fn clean(&mut self) {
+        let mut polygons = std::mem::take(&mut self.polygons);
+        for polygon in &mut polygons {
+            loop {
+                let n = polygon.len();
+                if n < 2 {
+                    break;
+                }
+                let met = (0..n).find(|&i| self.coincide(polygon[i].position, polygon[(i + 1) % n].position));
+                let slide = || (0..n).find(|&i| polygon[i].velocity.is_none());
+                let i = match (met, slide()) {
+                    (Some(i), _) => i,
+                    (None, Some(i)) => {
+                        //the vertex joins the closest neighbour along the folded edges
+                        let (before, after) = (&polygon[(i + n - 1) % n], &polygon[(i + 1) % n]);
+                        let position = polygon[i].position;
+                        let to_before = distance(position, before.position);
+                        let to_after = distance(position, after.position);
+                        let (target, index) = if to_before <= to_after {
+                            (before.position, (i + n - 1) % n)
+                        } else {
+                            (after.position, i)
+                        };
+                        let start = polygon[i].start;
+                        self.arc(start, target);
+                        polygon[i].start = target;
+                        polygon[i].position = target;
+                        index
+                    }
+                    (None, None) => break,
+                };
+                let (a, b) = (polygon[i].clone(), polygon[(i + 1) % n].clone());
+                let position = a.position;
+                self.arc(a.start, position);
+                self.arc(b.start, position);
+                let merged = Vertex::new(position, position, a.before, b.after);
+                if i + 1 == n {
+                    polygon.remove(i);
+                    polygon[0] = merged;
+                } else {
+                    polygon[i] = merged;
+                    polygon.remove(i + 1);
+                }
+            }
+        }
+        for polygon in polygons {
+            let points: Vec<Point> = polygon.iter().map(|v| v.position).collect();
+            if polygon.len() < 3 || signed_area(&points).abs() <= self.epsilon * self.epsilon * 1e6 {
+                //a flat polygon leaves its edges in the skeleton
+                for (i, vertex) in polygon.iter().enumerate() {
+                    self.arc(vertex.start, vertex.position);
+                    let next = &polygon[(i + 1) % polygon.len()];
+                    if polygon.len() > 1 && !self.coincide(vertex.position, next.position) {
+                        self.arc(vertex.position, next.position);
+                    }
+                }
+            } else {
+                self.polygons.push(polygon);
+            }
+        }
+    }
+}
+
+fn signed_area(points: &[Point]) -> f64 {
+    let n = points.len();
+    (0..n).map(|i| cross(points[i], points[(i + 1) % n])).sum::<f64>() / 2.0
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Returns the unit vector from one point to another, or None if they are equal.
+fn direction(a: Point, b: Point) -> Option<Point> {
+    let length = distance(a, b);
+    if length == 0.0 {
+        return None;
+    }
+    Some(((b.0 - a.0) / length, (b.1 - a.1) / length))
+}
+
+/// Joins segments sharing their ends into lines, breaking them where three or more segments meet.
+/// Repeated segments are kept once.
+pub(crate) fn merge_segments(segments: Vec<(Coordinate, Coordinate)>) -> Vec<Vec<Coordinate>> {
+    let mut unique: Vec<(Coordinate, Coordinate)> = Vec::with_capacity(segments.len());
+    let mut seen = std::collections::HashSet::new();
+    for (a, b) in segments {
+        if a == b {
+            continue;
+        }
+        let key = if (a.x(), a.y()) < (b.x(), b.y()) { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+        if seen.insert(key) {
+            unique.push((a, b));
+        }
+    }
+    let mut incident: HashMap<&Coordinate, Vec<usize>> = HashMap::new();
+    for (i, (a, b)) in unique.iter().enumerate() {
+        incident.entry(a).or_default().push(i);
+        incident.entry(b).or_default().push(i);
+    }
+    let mut used = vec![false; unique.len()];
+    let mut lines = Vec::new();
+    let walk = |from: &Coordinate, first: usize, used: &mut Vec<bool>| {
+        let mut line = vec![from.clone()];
+        let (mut node, mut segment) = (from, first);
+        loop {
+            used[segment] = true;
+            let (a, b) = &unique[segment];
+            node = if a == node { b } else { a };
+            line.push(node.clone());
+            let next = &incident[node];
+            if next.len() != 2 {
+                break;
+            }
+            segment = if next[0] == segment { next[1] } else { next[0] };
+            if used[segment] {
+                break;
+            }
+        }
+        line
+    };
+    //lines run between nodes where the segments do not simply follow each other
+    for i in 0..unique.len() {
+        for end in [&unique[i].0, &unique[i].1] {
+            if !used[i] && incident[end].len() != 2 {
+                lines.push(walk(end, i, &mut used));
+            }
+        }
+    }
+    //what is left are closed loops
+    for i in 0..unique.len() {
+        if !used[i] {
+            lines.push(walk(&unique[i].0, i, &mut used));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    fn total_length(lines: &[Vec<Coordinate>]) -> f64 {
+        lines.iter().flat_map(|line| line.windows(2)).map(|w| (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y())).sum()
+    }
+
+    fn assert_inside(lines: &[Vec<Coordinate>], polygon: &[Vec<Coordinate>]) {
+        for c in lines.iter().flatten() {
+            assert_ne!(locate_in_polygon(c, polygon), Location::Exterior, "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_straight_skeleton_of_convex_polygons() {
+        let square = vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]];
+        let skeleton = straight_skeleton(&square);
+        assert_eq!(skeleton.len(), 4);
+        assert!((total_length(&skeleton) - 4.0 * 2f64.sqrt()).abs() < 1e-9);
+
+        //a rectangle with its vertices given clockwise
+        let rectangle = vec![vec![coord!(0, 0), coord!(0, 2), coord!(10, 2), coord!(10, 0), coord!(0, 0)]];
+        let skeleton = straight_skeleton(&rectangle);
+        assert!((total_length(&skeleton) - (8.0 + 4.0 * 2f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_straight_skeleton_with_reflex_vertices() {
+        //an L shape with arms 2 wide
+        let l = vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 2), coord!(2, 2), coord!(2, 10), coord!(0, 10), coord!(0, 0)]];
+        let skeleton = straight_skeleton(&l);
+        assert_inside(&skeleton, &l);
+        //the arms are 8 long from the inner corner, and the diagonals at the ends and corners
+        let expected = 2.0 * 8.0 + 2.0 * 2.0 * 2f64.sqrt() + 2.0 * 2f64.sqrt();
+        assert!((total_length(&skeleton) - expected).abs() < 1e-9, "{}", total_length(&skeleton));
+
+        //a square with a square hole, the ring between them being 1 wide
+        let frame = vec![
+            vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+            vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)],
+        ];
+        let skeleton = straight_skeleton(&frame);
+        assert_inside(&skeleton, &frame);
+        let expected = 4.0 * 3.0 + 8.0 * 0.5 * 2f64.sqrt();
+        assert!((total_length(&skeleton) - expected).abs() < 1e-9, "{}", total_length(&skeleton));
+    }
+
+    #[test]
+    fn test_straight_skeleton_stays_inside() {
+        //a star with sharp reflex vertices
+        let mut star: Vec<Coordinate> = (0..14)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::PI / 7.0;
+                let r = if i % 2 == 0 { 10.0 } else { 3.0 + (i % 3) as f64 };
+                coord!(r * angle.cos(), r * angle.sin())
+            })
+            .collect();
+        star.push(star[0].clone());
+        let polygon = vec![star];
+        let skeleton = straight_skeleton(&polygon);
+        assert_inside(&skeleton, &polygon);
+        //every vertex of the polygon starts a branch
+        for c in &polygon[0] {
+            assert!(skeleton.iter().any(|line| line[0] == *c || line[line.len() - 1] == *c));
+        }
+    }
+
+    #[test]
+    fn test_medial_axis_stays_inside() {
+        let polygon = vec![
+            vec![coord!(0, 0), coord!(20, 0), coord!(20, 4), coord!(12, 4), coord!(12, 12), coord!(8, 12), coord!(8, 4), coord!(0, 4), coord!(0, 0)],
+        ];
+        let axis = approximate_medial_axis(&polygon, 0.2);
+        assert_inside(&axis, &polygon);
+        //the axis reaches the middle of every arm
+        for target in [coord!(3, 2), coord!(17, 2), coord!(10, 9)] {
+            assert!(axis.iter().flatten().any(|c| (c.x() - target.x()).hypot(c.y() - target.y()) < 0.3));
+        }
+    }
+}
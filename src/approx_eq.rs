@@ -0,0 +1,143 @@
+//! Fuzzy equality of coordinates, coordinate sequences and geometries through the traits of the
+//! `approx` crate, enabled by the `approx` feature, so tests can compare computed geometries with
+//! `assert_relative_eq!` and `assert_abs_diff_eq!`.
+//!
+//! Geometries are equal when they have the same type and structure, and their coordinates are
+//! equal one by one in x, y and z within the tolerances.
+
+use approx::{AbsDiffEq, RelativeEq};
+
+use crate::coordinate::coordinate_sequences::CoordinateSequence;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+impl AbsDiffEq for Coordinate {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Coordinate, epsilon: f64) -> bool {
+        self.x().abs_diff_eq(&other.x(), epsilon)
+            && self.y().abs_diff_eq(&other.y(), epsilon)
+            && self.z().abs_diff_eq(&other.z(), epsilon)
+    }
+}
+
+impl RelativeEq for Coordinate {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Coordinate, epsilon: f64, max_relative: f64) -> bool {
+        self.x().relative_eq(&other.x(), epsilon, max_relative)
+            && self.y().relative_eq(&other.y(), epsilon, max_relative)
+            && self.z().relative_eq(&other.z(), epsilon, max_relative)
+    }
+}
+
+impl AbsDiffEq for CoordinateSequence {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &CoordinateSequence, epsilon: f64) -> bool {
+        path_eq(self.get_coordinates(), other.get_coordinates(), &|a, b| a.abs_diff_eq(b, epsilon))
+    }
+}
+
+impl RelativeEq for CoordinateSequence {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &CoordinateSequence, epsilon: f64, max_relative: f64) -> bool {
+        path_eq(self.get_coordinates(), other.get_coordinates(), &|a, b| a.relative_eq(b, epsilon, max_relative))
+    }
+}
+
+impl AbsDiffEq for Geometry {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Geometry, epsilon: f64) -> bool {
+        geometry_eq(self, other, &|a, b| a.abs_diff_eq(b, epsilon))
+    }
+}
+
+impl RelativeEq for Geometry {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Geometry, epsilon: f64, max_relative: f64) -> bool {
+        geometry_eq(self, other, &|a, b| a.relative_eq(b, epsilon, max_relative))
+    }
+}
+
+type CoordinateEq<'a> = dyn Fn(&Coordinate, &Coordinate) -> bool + 'a;
+
+fn path_eq(a: &[Coordinate], b: &[Coordinate], eq: &CoordinateEq) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
+
+fn paths_eq(a: &[Vec<Coordinate>], b: &[Vec<Coordinate>], eq: &CoordinateEq) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| path_eq(a, b, eq))
+}
+
+fn geometry_eq(a: &Geometry, b: &Geometry, eq: &CoordinateEq) -> bool {
+    match (a, b) {
+        (Geometry::Point { coordinates: a }, Geometry::Point { coordinates: b }) => eq(a, b),
+        (Geometry::LineString { coordinates: a }, Geometry::LineString { coordinates: b })
+        | (Geometry::LinearRing { coordinates: a }, Geometry::LinearRing { coordinates: b })
+        | (Geometry::MultiPoint { coordinates: a }, Geometry::MultiPoint { coordinates: b }) => path_eq(a, b, eq),
+        (Geometry::Polygon { coordinates: a }, Geometry::Polygon { coordinates: b })
+        | (Geometry::MultiLineString { coordinates: a }, Geometry::MultiLineString { coordinates: b })
+        | (Geometry::Tin { coordinates: a }, Geometry::Tin { coordinates: b }) => paths_eq(a, b, eq),
+        (Geometry::MultiPolygon { coordinates: a }, Geometry::MultiPolygon { coordinates: b })
+        | (Geometry::PolyhedralSurface { coordinates: a }, Geometry::PolyhedralSurface { coordinates: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| paths_eq(a, b, eq))
+        }
+        (Geometry::GeometryCollection { geometries: a }, Geometry::GeometryCollection { geometries: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| geometry_eq(a, b, eq))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use approx::{assert_abs_diff_eq, assert_abs_diff_ne, assert_relative_eq, relative_eq};
+
+    #[test]
+    fn test_fuzzy_equality() {
+        let ring = vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)];
+        let square = Geometry::Polygon { coordinates: vec![ring.clone()] };
+        //rotated back and forth, which leaves rounding errors
+        let (sin, cos) = 0.1f64.sin_cos();
+        let rotated = Geometry::Polygon { coordinates: vec![ring.iter().map(|c| {
+            let (x, y) = (c.x() * cos - c.y() * sin, c.x() * sin + c.y() * cos);
+            coord!(x * cos + y * sin, y * cos - x * sin)
+        }).collect()] };
+        assert_ne!(rotated, square);
+        assert_relative_eq!(rotated, square, epsilon = 1e-12);
+
+        assert_abs_diff_eq!(coord!(1, 2, 3), coord!(1.0 + 1e-10, 2, 3), epsilon = 1e-9);
+        assert_abs_diff_ne!(coord!(1, 2, 3), coord!(1, 2, 3.1), epsilon = 1e-9);
+        //the same coordinates in geometries of different types
+        assert_abs_diff_ne!(Geometry::LineString { coordinates: ring.clone() }, Geometry::LinearRing { coordinates: ring });
+        assert!(relative_eq!(
+            CoordinateSequence::new(vec![coord!(0, 0), coord!(1e6, 1e6)]),
+            CoordinateSequence::new(vec![coord!(0, 0), coord!(1e6 + 1e-6, 1e6)]),
+            max_relative = 1e-9
+        ));
+    }
+}
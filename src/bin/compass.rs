@@ -0,0 +1,403 @@
+//! The `compass` command, reading features from a file or the standard input, processing them with
+//! the library and writing them to the standard output as they are read.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::ExitCode;
+
+use geoms::envelope::Envelope;
+use geoms::error::Error;
+use geoms::feature::Feature;
+use geoms::geometry::Geometry;
+#[cfg(feature = "flatgeobuf")]
+use geoms::io::flatgeobuf;
+use geoms::io::geojson::{self, RingOrientation, SeqReader, SeqWriter};
+use geoms::io::wkb::{self, HexCase};
+use geoms::io::wkt;
+use geoms::operation::overlay::unary_union;
+use geoms::stream::{CsvReader, FeatureStream};
+
+const USAGE: &str = "usage: compass <command> [arguments] [options] [input]
+
+Reads features from the input file, or the standard input if it is missing or '-', and writes the
+result to the standard output.
+
+commands:
+  convert                 write the features in another format
+  buffer <distance>       replace the geometries by the area within the distance of them
+  simplify <tolerance>    simplify the geometries with the Douglas-Peucker algorithm
+  validate                report the invalid geometries, exiting with 1 if there are any
+  union                   dissolve all the geometries into one
+  stats                   summarize the features
+
+options:
+  --from <format>         the format of the input: geojson, geojsonseq, wkt, wkb, csv or fgb, by
+                          default guessed from the extension of the file or the start of the input
+  --to <format>           the format of the output: geojson, geojsonseq, wkt, wkb or fgb, by default
+                          geojsonseq
+  -h, --help              print this message
+
+WKT and WKB, as hexadecimal text, hold a geometry per line. FlatGeobuf (fgb) needs compass built
+with the flatgeobuf feature, and is written once all the features are read.";
+
+/// The error given for FlatGeobuf input or output when the feature is not built in.
+const NO_FLATGEOBUF: &str = "FlatGeobuf needs compass built with the flatgeobuf feature";
+
+/// The formats features are read and written in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    GeoJson,
+    GeoJsonSeq,
+    Wkt,
+    Wkb,
+    Csv,
+    FlatGeobuf,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Format, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "geojson" | "json" => Ok(Format::GeoJson),
+            "geojsonseq" | "geojsonl" | "geojsons" | "ndjson" | "jsonl" => Ok(Format::GeoJsonSeq),
+            "wkt" => Ok(Format::Wkt),
+            "wkb" | "hex" => Ok(Format::Wkb),
+            "csv" => Ok(Format::Csv),
+            "fgb" | "flatgeobuf" if cfg!(feature = "flatgeobuf") => Ok(Format::FlatGeobuf),
+            "fgb" | "flatgeobuf" => Err(NO_FLATGEOBUF.to_string()),
+            _ => Err(format!("unknown format '{}'", name)),
+        }
+    }
+}
+
+/// What the command does with the features.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Command {
+    Convert,
+    Buffer(f64),
+    Simplify(f64),
+    Validate,
+    Union,
+    Stats,
+}
+
+/// The parsed command line.
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Command,
+    input: Option<String>,
+    from: Option<Format>,
+    to: Format,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let result = parse_args(&args).and_then(|options| {
+        let input: Box<dyn BufRead> = match options.input.as_deref() {
+            None | Some("-") => Box::new(io::stdin().lock()),
+            Some(path) => Box::new(BufReader::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?)),
+        };
+        run(&options, input, &mut io::stdout().lock())
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(message) => {
+            eprintln!("compass: {}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Returns the options of the command line, without the name of the program.
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let name = args.next().ok_or("missing the command")?;
+    let mut number = |what: &str| -> Result<f64, String> {
+        let text = args.next().ok_or_else(|| format!("{} needs a {}", name, what))?;
+        text.parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .ok_or_else(|| format!("the {} '{}' is not a number", what, text))
+    };
+    let command = match name.as_str() {
+        "convert" => Command::Convert,
+        "buffer" => Command::Buffer(number("distance")?),
+        "simplify" => Command::Simplify(number("tolerance")?),
+        "validate" => Command::Validate,
+        "union" => Command::Union,
+        "stats" => Command::Stats,
+        _ => return Err(format!("unknown command '{}', see compass --help", name)),
+    };
+    let mut options = Options { command, input: None, from: None, to: Format::GeoJsonSeq };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let format = Format::parse(args.next().ok_or_else(|| format!("{} needs a format", arg))?)?;
+                if arg == "--from" {
+                    options.from = Some(format);
+                } else if format == Format::Csv {
+                    return Err("features cannot be written as CSV".to_string());
+                } else {
+                    options.to = format;
+                }
+            }
+            _ if options.input.is_none() && (arg == "-" || !arg.starts_with('-')) => options.input = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// Runs the command on the input, returning false if it found invalid geometries.
+fn run(options: &Options, mut input: Box<dyn BufRead>, output: &mut dyn Write) -> Result<bool, String> {
+    let from = match options.from {
+        Some(format) => format,
+        None => match options.input.as_deref().and_then(|path| path.rsplit_once('.')) {
+            Some((_, extension)) => Format::parse(extension)?,
+            None => guess(input.as_mut()).map_err(|e| e.to_string())?,
+        },
+    };
+    let features = read(from, input)?;
+    let written = match options.command {
+        Command::Convert => write(features, options.to, output),
+        Command::Buffer(distance) => write(Box::new(features.buffer(distance)), options.to, output),
+        Command::Simplify(tolerance) => {
+            write(Box::new(features.map_geometries(move |g| Ok(g.simplify(tolerance)))), options.to, output)
+        }
+        Command::Validate => return validate(features, output),
+        Command::Union => union(features).and_then(|f| write(Box::new(std::iter::once(Ok(f))), options.to, output)),
+        Command::Stats => stats(features, output),
+    };
+    written.map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// The features read, one at a time.
+type Features<'a> = Box<dyn Iterator<Item = Result<Feature, Error>> + 'a>;
+
+/// Returns the format of an input without a name from its first character.
+fn guess(input: &mut dyn BufRead) -> io::Result<Format> {
+    let buffer = input.fill_buf()?;
+    if buffer.starts_with(b"fgb") {
+        return Ok(Format::FlatGeobuf);
+    }
+    let start = buffer.iter().find(|b| !b.is_ascii_whitespace()).copied();
+    Ok(match start {
+        Some(0x1e) => Format::GeoJsonSeq,
+        Some(b'{') => Format::GeoJson,
+        Some(b) if b.is_ascii_digit() => Format::Wkb,
+        _ => Format::Wkt,
+    })
+}
+
+/// Returns the features of the input as they are read. A GeoJSON document is read as a whole.
+fn read(format: Format, mut input: Box<dyn BufRead>) -> Result<Features<'static>, String> {
+    let per_line = |parse: fn(&str) -> Result<Geometry, Error>, input: Box<dyn BufRead>| -> Features<'static> {
+        Box::new(input.lines().filter(|line| line.as_ref().map_or(true, |l| !l.trim().is_empty())).map(move |line| {
+            Ok(Feature::new(parse(line?.trim())?))
+        }))
+    };
+    Ok(match format {
+        Format::GeoJson => {
+            let mut text = String::new();
+            input.read_to_string(&mut text).map_err(|e| e.to_string())?;
+            let features = geojson::read_features(&text).map_err(|e| e.to_string())?;
+            Box::new(features.into_iter().map(Ok))
+        }
+        Format::GeoJsonSeq => Box::new(SeqReader::new(input)),
+        Format::Wkt => per_line(wkt::read, input),
+        Format::Wkb => per_line(wkb::read_hex, input),
+        Format::Csv => Box::new(CsvReader::new(input).map_err(|e| e.to_string())?),
+        Format::FlatGeobuf => read_flatgeobuf(input)?,
+    })
+}
+
+#[cfg(feature = "flatgeobuf")]
+fn read_flatgeobuf(input: Box<dyn BufRead>) -> Result<Features<'static>, String> {
+    Ok(Box::new(flatgeobuf::FeatureReader::new(input).map_err(|e| e.to_string())?))
+}
+
+#[cfg(not(feature = "flatgeobuf"))]
+fn read_flatgeobuf(_: Box<dyn BufRead>) -> Result<Features<'static>, String> {
+    Err(NO_FLATGEOBUF.to_string())
+}
+
+#[cfg(feature = "flatgeobuf")]
+fn write_flatgeobuf(features: Features, output: &mut dyn Write) -> Result<(), Error> {
+    Ok(output.write_all(&flatgeobuf::write_features(&features.collect_features()?))?)
+}
+
+#[cfg(not(feature = "flatgeobuf"))]
+fn write_flatgeobuf(_: Features, _: &mut dyn Write) -> Result<(), Error> {
+    Err(Error::Io(NO_FLATGEOBUF.to_string()))
+}
+
+/// Writes the features as they come, or stops at the first error. A GeoJSON document and a
+/// FlatGeobuf file are written once all the features are read.
+fn write(features: Features, format: Format, output: &mut dyn Write) -> Result<(), Error> {
+    match format {
+        Format::GeoJson => {
+            let features = features.collect_features()?;
+            writeln!(output, "{}", geojson::write_features(&features, RingOrientation::Rfc7946))?;
+        }
+        Format::GeoJsonSeq => {
+            let mut writer = SeqWriter::new(output).with_orientation(RingOrientation::Rfc7946);
+            for feature in features {
+                writer.write(&feature?)?;
+            }
+        }
+        Format::FlatGeobuf => write_flatgeobuf(features, output)?,
+        Format::Wkt | Format::Wkb | Format::Csv => {
+            for feature in features {
+                let feature = feature?;
+                let text = match format {
+                    Format::Wkb => wkb::write_hex(feature.geometry(), HexCase::Upper),
+                    _ => wkt::write(feature.geometry()),
+                };
+                writeln!(output, "{}", text)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the validity errors of every feature, returning true if there are none.
+fn validate(features: Features, output: &mut dyn Write) -> Result<bool, String> {
+    let (mut count, mut invalid) = (0, 0);
+    for (i, feature) in features.enumerate() {
+        let feature = feature.map_err(|e| format!("feature {}: {}", i, e))?;
+        let errors = feature.geometry().validity_errors();
+        if !errors.is_empty() {
+            invalid += 1;
+        }
+        for error in errors {
+            writeln!(output, "feature {}: {}", i, error).map_err(|e| e.to_string())?;
+        }
+        count += 1;
+    }
+    writeln!(output, "{} features, {} invalid", count, invalid).map_err(|e| e.to_string())?;
+    Ok(invalid == 0)
+}
+
+/// Returns a feature with the union of all the geometries.
+fn union(features: Features) -> Result<Feature, Error> {
+    let geometries = features.map(|f| f.map(|f| f.geometry().clone())).collect::<Result<Vec<Geometry>, Error>>()?;
+    Ok(Feature::new(unary_union(&Geometry::GeometryCollection { geometries })?))
+}
+
+/// Writes the number of features of every type, their vertices, extent, area and length.
+fn stats(features: Features, output: &mut dyn Write) -> Result<(), Error> {
+    let mut types: BTreeMap<&str, usize> = BTreeMap::new();
+    let (mut count, mut vertices, mut area, mut length) = (0, 0, 0.0, 0.0);
+    let mut envelope = Envelope::empty();
+    for feature in features {
+        let feature = feature?;
+        let geometry = feature.geometry();
+        *types.entry(kind(geometry)).or_default() += 1;
+        count += 1;
+        vertices += geometry.coords_count();
+        area += geometry.area();
+        length += geometry.length();
+        envelope = envelope.merge(&geometry.envelope());
+    }
+    writeln!(output, "features: {}", count)?;
+    for (kind, n) in types {
+        writeln!(output, "  {}: {}", kind, n)?;
+    }
+    writeln!(output, "vertices: {}", vertices)?;
+    match envelope.is_empty() {
+        true => writeln!(output, "extent: empty")?,
+        false => writeln!(
+            output,
+            "extent: {} {} {} {}",
+            envelope.min_x(),
+            envelope.min_y(),
+            envelope.max_x(),
+            envelope.max_y()
+        )?,
+    }
+    writeln!(output, "area: {}", area)?;
+    writeln!(output, "length: {}", length)?;
+    Ok(())
+}
+
+/// Returns the name of the type of a geometry.
+fn kind(geometry: &Geometry) -> &'static str {
+    match geometry {
+        Geometry::Point { .. } => "Point",
+        Geometry::LineString { .. } => "LineString",
+        Geometry::LinearRing { .. } => "LinearRing",
+        Geometry::Polygon { .. } => "Polygon",
+        Geometry::MultiPoint { .. } => "MultiPoint",
+        Geometry::MultiLineString { .. } => "MultiLineString",
+        Geometry::MultiPolygon { .. } => "MultiPolygon",
+        Geometry::GeometryCollection { .. } => "GeometryCollection",
+        Geometry::PolyhedralSurface { .. } => "PolyhedralSurface",
+        Geometry::Tin { .. } => "Tin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(args: &[&str], input: &'static str) -> (Result<bool, String>, String) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut output = Vec::new();
+        let result = parse_args(&args).and_then(|options| run(&options, Box::new(input.as_bytes()), &mut output));
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_commands_on_wkt_lines() {
+        let input = "POINT (0 0)\n\nLINESTRING (0 0, 1 0.01, 2 0)\n";
+        assert_eq!(run_with(&["simplify", "0.1", "--to", "wkt"], input), (Ok(true), "POINT (0 0)\nLINESTRING (0 0, 2 0)\n".to_string()));
+        let (result, stats) = run_with(&["stats"], input);
+        assert_eq!(result, Ok(true));
+        assert!(stats.starts_with("features: 2\n  LineString: 1\n  Point: 1\nvertices: 4\nextent: 0 0 2 0.01\n"));
+        let bowtie = "{\"type\": \"Polygon\", \"coordinates\": [[[0, 0], [2, 2], [2, 0], [0, 2], [0, 0]]]}";
+        let (result, report) = run_with(&["validate", "--from", "geojsonseq"], bowtie);
+        assert_eq!(result, Ok(false));
+        assert!(report.ends_with("1 features, 1 invalid\n"));
+        let squares = "POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0))\nPOLYGON ((1 0, 3 0, 3 2, 1 2, 1 0))\n";
+        let (result, union) = run_with(&["union", "--to", "geojson"], squares);
+        assert_eq!(result, Ok(true));
+        assert_eq!(geojson::read_features(&union).unwrap().features()[0].geometry().area(), 6.0);
+        //the errors of the input stop the output where they are met
+        assert_eq!(run_with(&["convert", "--to", "wkb"], "POINT (1 2)\nPOINT (1\n").1, "0101000000000000000000F03F0000000000000040\n");
+        assert!(run_with(&["convert", "--from", "fgb"], "").0.is_err());
+        assert!(parse_args(&["buffer".to_string()]).is_err());
+    }
+
+    #[cfg(feature = "flatgeobuf")]
+    #[test]
+    fn test_flatgeobuf_conversions() {
+        let convert = |args: &[&str], input: Vec<u8>| -> Vec<u8> {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let mut output = Vec::new();
+            let result = parse_args(&args).and_then(|options| run(&options, Box::new(io::Cursor::new(input)), &mut output));
+            assert_eq!(result, Ok(true));
+            output
+        };
+        let wkt = "POINT Z (1 2 3)\nLINESTRING (0 0, 1 1)\n";
+        let fgb = convert(&["convert", "--to", "fgb"], wkt.as_bytes().to_vec());
+        assert!(fgb.starts_with(b"fgb\x03"));
+        //the input is known to be FlatGeobuf from its first bytes
+        assert_eq!(String::from_utf8(convert(&["convert", "--to", "wkt"], fgb.clone())).unwrap(), wkt);
+        let stats = String::from_utf8(convert(&["stats", "--from", "fgb"], fgb)).unwrap();
+        assert!(stats.starts_with("features: 2\n"));
+    }
+
+    #[cfg(not(feature = "flatgeobuf"))]
+    #[test]
+    fn test_flatgeobuf_needs_the_feature() {
+        assert_eq!(run_with(&["convert", "--to", "fgb"], "POINT (0 0)").0, Err(NO_FLATGEOBUF.to_string()));
+        //found from the first bytes of the input
+        assert_eq!(run_with(&["convert"], "fgb\x03fgb\x00").0, Err(NO_FLATGEOBUF.to_string()));
+    }
+}
@@ -0,0 +1,405 @@
+//! Clustering of point sets and of arbitrary geometries.
+
+use crate::algorithm::convex_hull::convex_hull;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::index::kdtree::KdTree;
+use crate::index::strtree::STRtree;
+
+/// The result of a clustering: one label per clustered item, None for noise.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Clusters {
+    labels: Vec<Option<usize>>,
+    count: usize,
+}
+
+impl Clusters {
+    /// Returns the cluster of every item, in the order of the input, or None for noise.
+    pub fn labels(&self) -> &Vec<Option<usize>> {
+        &self.labels
+    }
+
+    /// Returns the number of clusters.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the indices of the items of every cluster.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::cluster::dbscan;
+    ///
+    /// let points = vec![coord!(0, 0), coord!(10, 10), coord!(0, 1), coord!(10, 11)];
+    /// let clusters = dbscan(&points, 1.5, 2);
+    /// assert_eq!(clusters.members(), vec![vec![0, 2], vec![1, 3]]);
+    /// ```
+    pub fn members(&self) -> Vec<Vec<usize>> {
+        let mut members = vec![Vec::new(); self.count];
+        for (i, label) in self.labels.iter().enumerate() {
+            if let Some(label) = label {
+                members[*label].push(i);
+            }
+        }
+        members
+    }
+
+    /// Returns the centroid of every cluster as a point.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The clusters.
+    /// * `points` - The points that were clustered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::cluster::kmeans;
+    ///
+    /// let points = vec![coord!(0, 0), coord!(10, 10), coord!(0, 2), coord!(10, 12)];
+    /// let clusters = kmeans(&points, 2, 100);
+    /// assert_eq!(clusters.centroids(&points), vec![
+    ///     Geometry::Point { coordinates: coord!(0, 1) },
+    ///     Geometry::Point { coordinates: coord!(10, 11) },
+    /// ]);
+    /// ```
+    pub fn centroids(&self, points: &[Coordinate]) -> Vec<Geometry> {
+        self.members()
+            .iter()
+            .map(|members| Geometry::Point { coordinates: mean(members.iter().map(|&i| &points[i])) })
+            .collect()
+    }
+
+    /// Returns the convex hull of every cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The clusters.
+    /// * `points` - The points that were clustered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::cluster::dbscan;
+    ///
+    /// let points = vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1), coord!(9, 9)];
+    /// let hulls = dbscan(&points, 1.0, 2).hulls(&points);
+    /// assert_eq!(hulls.len(), 1);
+    /// assert_eq!(hulls[0].area(), 1.0);
+    /// ```
+    pub fn hulls(&self, points: &[Coordinate]) -> Vec<Geometry> {
+        self.members()
+            .iter()
+            .map(|members| {
+                let cluster: Vec<Coordinate> = members.iter().map(|&i| points[i].clone()).collect();
+                convex_hull(&cluster)
+            })
+            .collect()
+    }
+}
+
+/// Clusters points with the DBSCAN algorithm, using a KD-tree for the neighbourhood queries.
+/// Points with at least `min_points` points within `eps` of them (themselves included) are core
+/// points. Clusters are made of core points reachable from each other and the points in their
+/// neighbourhoods, while the remaining points are noise.
+///
+/// # Arguments
+///
+/// * `points` - The points to cluster.
+/// * `eps` - The radius of the neighbourhood of every point.
+/// * `min_points` - The minimum size of the neighbourhood of a core point.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::cluster::dbscan;
+///
+/// let points = vec![coord!(0, 0), coord!(0, 1), coord!(1, 0), coord!(50, 50), coord!(20, 20), coord!(20, 21)];
+/// let clusters = dbscan(&points, 1.5, 2);
+/// assert_eq!(clusters.count(), 2);
+/// assert_eq!(clusters.labels(), &vec![Some(0), Some(0), Some(0), None, Some(1), Some(1)]);
+/// ```
+pub fn dbscan(points: &[Coordinate], eps: f64, min_points: usize) -> Clusters {
+    let tree = KdTree::new(points);
+    let mut labels: Vec<Option<usize>> = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut count = 0;
+
+    for start in 0..points.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let neighbours = tree.query_radius(&points[start], eps);
+        if neighbours.len() < min_points {
+            continue;
+        }
+        //expand a new cluster from the core point
+        let cluster = count;
+        count += 1;
+        labels[start] = Some(cluster);
+        let mut queue = neighbours;
+        while let Some(i) = queue.pop() {
+            if labels[i].is_none() {
+                labels[i] = Some(cluster);
+            }
+            if visited[i] {
+                continue;
+            }
+            visited[i] = true;
+            let neighbours = tree.query_radius(&points[i], eps);
+            if neighbours.len() >= min_points {
+                queue.extend(neighbours);
+            }
+        }
+    }
+    Clusters { labels, count }
+}
+
+/// Clusters points in `k` groups with the K-means algorithm. The initial centers are chosen
+/// deterministically, starting from the first point and adding the point furthest from the
+/// centers already chosen. Iterates until the assignment is stable or `max_iterations` is reached,
+/// and always assigns the points to their nearest initial center, even with no iteration.
+///
+/// # Arguments
+///
+/// * `points` - The points to cluster.
+/// * `k` - The number of clusters. If there are fewer points, every point is its own cluster.
+/// * `max_iterations` - The maximum number of iterations, counting the first assignment.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::cluster::kmeans;
+///
+/// let points = vec![coord!(0, 0), coord!(0, 1), coord!(9, 9), coord!(10, 10), coord!(1, 0)];
+/// let clusters = kmeans(&points, 2, 100);
+/// assert_eq!(clusters.labels(), &vec![Some(0), Some(0), Some(1), Some(1), Some(0)]);
+/// ```
+pub fn kmeans(points: &[Coordinate], k: usize, max_iterations: usize) -> Clusters {
+    let k = k.min(points.len());
+    if k == 0 {
+        return Clusters { labels: vec![None; points.len()], count: 0 };
+    }
+
+    //farthest first initialisation
+    let mut centers: Vec<Coordinate> = vec![points[0].clone()];
+    let mut closest: Vec<f64> = points.iter().map(|p| squared_distance(p, &points[0])).collect();
+    while centers.len() < k {
+        let (next, _) = closest
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let center = points[next].clone();
+        for (d, p) in closest.iter_mut().zip(points) {
+            *d = d.min(squared_distance(p, &center));
+        }
+        centers.push(center);
+    }
+
+    let mut labels: Vec<usize> = vec![usize::MAX; points.len()];
+    for _ in 0..max_iterations.max(1) {
+        let mut changed = false;
+        for (label, p) in labels.iter_mut().zip(points) {
+            let nearest = (0..k)
+                .min_by(|&a, &b| squared_distance(p, &centers[a]).total_cmp(&squared_distance(p, &centers[b])))
+                .unwrap();
+            if *label != nearest {
+                *label = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        for (cluster, center) in centers.iter_mut().enumerate() {
+            let members = labels.iter().zip(points).filter(|(l, _)| **l == cluster).map(|(_, p)| p);
+            //empty clusters keep their previous center
+            if labels.contains(&cluster) {
+                *center = mean(members);
+            }
+        }
+    }
+    Clusters { labels: labels.into_iter().map(Some).collect(), count: k }
+}
+
+/// Groups geometries whose distance to each other is within a threshold, transitively: two
+/// geometries share a cluster if they are linked by a chain of geometries each within the
+/// distance of the next one. Candidate pairs come from an STR-tree over the geometry envelopes.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GeometryDistanceClusterer {
+    distance: f64,
+}
+
+impl GeometryDistanceClusterer {
+    /// Creates a new clusterer with the given distance threshold.
+    pub fn new(distance: f64) -> Self {
+        Self { distance }
+    }
+
+    /// Returns the distance threshold of the clusterer.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// Returns the cluster of every geometry. Clusters are numbered in the order of their first geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::cluster::GeometryDistanceClusterer;
+    ///
+    /// let geometries = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+    ///     Geometry::Point { coordinates: coord!(20, 0) },
+    ///     Geometry::Point { coordinates: coord!(5, 1) },
+    ///     Geometry::Point { coordinates: coord!(21, 0) },
+    /// ];
+    /// let clusters = GeometryDistanceClusterer::new(1.5).labels(&geometries);
+    /// assert_eq!(clusters.labels(), &vec![Some(0), Some(1), Some(0), Some(1)]);
+    /// ```
+    pub fn labels(&self, geometries: &[Geometry]) -> Clusters {
+        let tree = STRtree::new(geometries.iter().map(|g| (g.envelope(), ())).collect());
+        let mut sets = DisjointSet::new(geometries.len());
+        for (i, geometry) in geometries.iter().enumerate() {
+            for j in tree.query_indices(&geometry.envelope().expand_by(self.distance)) {
+                if j > i && sets.find(i) != sets.find(j) && geometry.distance(&geometries[j]) <= self.distance {
+                    sets.union(i, j);
+                }
+            }
+        }
+
+        let mut roots: Vec<Option<usize>> = vec![None; geometries.len()];
+        let mut count = 0;
+        let mut labels = Vec::with_capacity(geometries.len());
+        for i in 0..geometries.len() {
+            let root = sets.find(i);
+            let label = *roots[root].get_or_insert_with(|| {
+                count += 1;
+                count - 1
+            });
+            labels.push(Some(label));
+        }
+        Clusters { labels, count }
+    }
+
+    /// Returns every cluster as a geometry collection of copies of its geometries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::cluster::GeometryDistanceClusterer;
+    ///
+    /// let a = Geometry::Point { coordinates: coord!(0, 0) };
+    /// let b = Geometry::Point { coordinates: coord!(0, 1) };
+    /// let c = Geometry::Point { coordinates: coord!(9, 9) };
+    /// let clusters = GeometryDistanceClusterer::new(1.0).cluster(&[a.clone(), b.clone(), c.clone()]);
+    /// assert_eq!(clusters, vec![
+    ///     Geometry::GeometryCollection { geometries: vec![a, b] },
+    ///     Geometry::GeometryCollection { geometries: vec![c] },
+    /// ]);
+    /// ```
+    pub fn cluster(&self, geometries: &[Geometry]) -> Vec<Geometry> {
+        self.labels(geometries)
+            .members()
+            .into_iter()
+            .map(|members| Geometry::GeometryCollection {
+                geometries: members.into_iter().map(|i| geometries[i].clone()).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Union-find over the indices 0..n, with path halving and union by size.
+pub(crate) struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub(crate) fn new(n: usize) -> Self {
+        Self { parents: (0..n).collect(), sizes: vec![1; n] }
+    }
+
+    /// Returns the root of the set of an element, compressing the path to it.
+    pub(crate) fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    /// Joins the sets of two elements, under the root of the larger one.
+    pub(crate) fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if self.sizes[a] < self.sizes[b] {
+            self.union_into(a, b);
+        } else {
+            self.union_into(b, a);
+        }
+    }
+
+    /// Joins the set of an element into the set of another, whose root stays the root.
+    pub(crate) fn union_into(&mut self, from: usize, into: usize) {
+        let (from, into) = (self.find(from), self.find(into));
+        if from == into {
+            return;
+        }
+        self.parents[from] = into;
+        self.sizes[into] += self.sizes[from];
+    }
+}
+
+fn squared_distance(a: &Coordinate, b: &Coordinate) -> f64 {
+    let dx = a.x() - b.x();
+    let dy = a.y() - b.y();
+    dx * dx + dy * dy
+}
+
+/// Returns the mean of a non empty set of coordinates.
+fn mean<'a>(coordinates: impl Iterator<Item = &'a Coordinate>) -> Coordinate {
+    let (mut x, mut y, mut z, mut n) = (0.0, 0.0, 0.0, 0.0);
+    for c in coordinates {
+        x += c.x();
+        y += c.y();
+        z += c.z();
+        n += 1.0;
+    }
+    Coordinate::new(x / n, y / n, z / n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_kmeans_without_iterations() {
+        let points = vec![coord!(0, 0), coord!(0, 1), coord!(9, 9), coord!(10, 10), coord!(1, 0)];
+        //the points go to their nearest initial center, the first point and the furthest from it
+        let clusters = kmeans(&points, 2, 0);
+        assert_eq!(clusters.labels(), &vec![Some(0), Some(0), Some(1), Some(1), Some(0)]);
+        assert_eq!(clusters.members(), vec![vec![0, 1, 4], vec![2, 3]]);
+        assert_eq!(kmeans(&points, 2, 1), clusters);
+    }
+}
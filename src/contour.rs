@@ -0,0 +1,137 @@
+//! Extraction of contour lines from triangulated surfaces.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the contour lines of a triangulated surface at the given z levels, as a multi line
+/// string with the z of every line set to its level. The lines are oriented with the higher part
+/// of the surface on their left, and the ones going round a summit or a pit are closed.
+///
+/// Vertices lying exactly on a level are considered above it, so lines pass through them without
+/// being duplicated.
+///
+/// # Arguments
+///
+/// * `points` - The vertices of the surface.
+/// * `triangles` - The triangles of the surface as triples of point indices in counter clockwise order.
+/// * `levels` - The z values of the contour lines.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::contour::contour;
+///
+/// let points = vec![coord!(0, 0, 0), coord!(2, 0, 0), coord!(2, 2, 2), coord!(0, 2, 2)];
+/// let lines = contour(&points, &[[0, 1, 2], [0, 2, 3]], &[1.0]);
+/// assert_eq!(lines, Geometry::MultiLineString { coordinates: vec![
+///     vec![coord!(0, 1, 1), coord!(1, 1, 1), coord!(2, 1, 1)]
+/// ] });
+/// ```
+pub fn contour(points: &[Coordinate], triangles: &[[usize; 3]], levels: &[f64]) -> Geometry {
+    let mut lines = Vec::new();
+    for &level in levels {
+        //the crossings are identified by the edge they lie on, so the segments of neighbouring
+        //triangles join exactly
+        let mut next: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for triangle in triangles {
+            let above = triangle.map(|v| points[v].z() >= level);
+            let mut down = None;
+            let mut up = None;
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                let edge = (a.min(b), a.max(b));
+                match (above[k], above[(k + 1) % 3]) {
+                    (true, false) => down = Some(edge),
+                    (false, true) => up = Some(edge),
+                    _ => {}
+                }
+            }
+            if let (Some(down), Some(up)) = (down, up) {
+                next.insert(down, up);
+            }
+        }
+
+        //open lines start on the border of the surface, at crossings no segment leads to
+        let ends: HashSet<(usize, usize)> = next.values().copied().collect();
+        let mut starts: Vec<(usize, usize)> = next.keys().copied().filter(|k| !ends.contains(k)).collect();
+        starts.sort();
+        let mut loops: Vec<(usize, usize)> = next.keys().copied().collect();
+        loops.sort();
+        for start in starts.into_iter().chain(loops) {
+            if !next.contains_key(&start) {
+                continue;
+            }
+            let mut line = vec![crossing(points, start, level)];
+            let mut edge = start;
+            while let Some(following) = next.remove(&edge) {
+                let c = crossing(points, following, level);
+                if !line[line.len() - 1].equals_2d(&c) {
+                    line.push(c);
+                }
+                edge = following;
+            }
+            if line.len() > 1 {
+                lines.push(line);
+            }
+        }
+    }
+    Geometry::MultiLineString { coordinates: lines }
+}
+
+/// Returns the point of an edge crossing between a vertex below the level and one above it.
+fn crossing(points: &[Coordinate], edge: (usize, usize), level: f64) -> Coordinate {
+    let (a, b) = (&points[edge.0], &points[edge.1]);
+    let t = (level - a.z()) / (b.z() - a.z());
+    Coordinate::new(a.x() + t * (b.x() - a.x()), a.y() + t * (b.y() - a.y()), level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::envelope::Envelope;
+    use crate::interpolate::{Interpolator, TinInterpolator};
+
+    #[test]
+    fn test_closed_contours_around_a_summit() {
+        //a cone with its summit at the origin
+        let mut points = vec![coord!(0, 0, 10)];
+        for ring in 1..=4 {
+            for k in 0..16 {
+                let angle = k as f64 * std::f64::consts::PI / 8.0 + ring as f64 * 0.1;
+                let r = ring as f64 * 2.0;
+                points.push(coord!(r * angle.cos(), r * angle.sin(), 10.0 - r));
+            }
+        }
+        let tin = TinInterpolator::new(&points);
+        let lines = tin.contour(&[3.0, 5.0, 7.0]);
+        let Geometry::MultiLineString { coordinates } = &lines else { panic!() };
+        assert_eq!(coordinates.len(), 3);
+        for line in coordinates {
+            let level = line[0].z();
+            assert_eq!(line[0], line[line.len() - 1]);
+            //higher ground on the left means counter clockwise around the summit
+            assert!(crate::algorithm::area::ring_signed_area(line) > 0.0);
+            for c in line {
+                assert_eq!(c.z(), level);
+                assert!((tin.z_at(c).unwrap() - level).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_contours() {
+        let tin = TinInterpolator::new(&[coord!(0, 0, 0), coord!(10, 0, 10), coord!(0, 10, 0), coord!(10, 10, 10)]);
+        let grid = tin.grid(&Envelope::new(0.0, 0.0, 10.0, 10.0), 1.0);
+        let Geometry::MultiLineString { coordinates } = grid.contour(&[4.5, 20.0]) else { panic!() };
+        assert_eq!(coordinates.len(), 1);
+        assert!(coordinates[0].iter().all(|c| (c.x() - 4.5).abs() < 1e-9));
+        //the x grows to the right, so the line heads down
+        assert!(coordinates[0][0].y() > coordinates[0][coordinates[0].len() - 1].y());
+    }
+}
@@ -44,7 +44,6 @@ impl Coordinate {
     /// 
     /// Coordinate::new(3.0, 7.0, 5.0);
     /// ```    
-    
     pub fn new(x: f64, y: f64, z: f64) -> Coordinate {
         if x.is_nan() || y.is_nan() || z.is_nan() {
             panic!("Coordinate values cannot be NaN");
@@ -257,7 +256,156 @@ impl Coordinate {
         self.x() == other.x() && self.y() == other.y()
     }
     
-    /// Returns whether the planar projections of the two coordinates are equal within a given tolerance.
+    /// Returns the planar distance between the coordinates, ignoring z. The distance is computed
+    /// with `hypot`, so it does not overflow for coordinates far apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The first coordinate.
+    /// * `other` - The second coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coordinate::Coordinate;
+    ///
+    /// let coordinate1 = Coordinate::new(1.0, 1.0, 0.0);
+    /// let coordinate2 = Coordinate::new(4.0, 5.0, 7.0);
+    /// assert_eq!(coordinate1.distance_2d(&coordinate2), 5.0);
+    ///
+    /// let coordinate1 = Coordinate::new(-1e300, 0.0, 0.0);
+    /// let coordinate2 = Coordinate::new(1e300, 0.0, 0.0);
+    /// assert_eq!(coordinate1.distance_2d(&coordinate2), 2e300);
+    /// ```
+    pub fn distance_2d(&self, other: &Coordinate) -> f64 {
+        (self.x() - other.x()).hypot(self.y() - other.y())
+    }
+
+    /// Returns the distance in space between the coordinates, computed with `hypot` as
+    /// [`Coordinate::distance_2d`] is.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The first coordinate.
+    /// * `other` - The second coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coordinate::Coordinate;
+    ///
+    /// let coordinate1 = Coordinate::new(0.0, 0.0, 0.0);
+    /// let coordinate2 = Coordinate::new(2.0, 3.0, 6.0);
+    /// assert_eq!(coordinate1.distance_3d(&coordinate2), 7.0);
+    /// ```
+    pub fn distance_3d(&self, other: &Coordinate) -> f64 {
+        (self.x() - other.x()).hypot(self.y() - other.y()).hypot(self.z() - other.z())
+    }
+
+    /// Returns the square of the planar distance between the coordinates, which avoids the
+    /// square root when distances are only compared. Unlike [`Coordinate::distance_2d`], it
+    /// overflows to infinity for coordinates more than about 1e154 apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The first coordinate.
+    /// * `other` - The second coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coordinate::Coordinate;
+    ///
+    /// let coordinate1 = Coordinate::new(1.0, 1.0, 0.0);
+    /// let coordinate2 = Coordinate::new(4.0, 5.0, 7.0);
+    /// assert_eq!(coordinate1.distance_squared(&coordinate2), 25.0);
+    /// ```
+    pub fn distance_squared(&self, other: &Coordinate) -> f64 {
+        let (dx, dy) = (self.x() - other.x(), self.y() - other.y());
+        dx * dx + dy * dy
+    }
+
+    /// Returns whether the coordinates differ by at most the tolerance in each of x, y and z.
+    /// A difference equal to the tolerance is within it, so a tolerance of zero means exact
+    /// equality, and a negative or NaN tolerance is never met.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The first coordinate.
+    /// * `other` - The second coordinate.
+    /// * `tolerance` - The maximum allowed difference between the coordinates' values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coordinate::Coordinate;
+    ///
+    /// let coordinate1 = Coordinate::new(1.0, 2.0, 3.0);
+    /// let coordinate2 = Coordinate::new(1.5, 2.5, 2.5);
+    /// assert!(coordinate1.within_tolerance(&coordinate2, 0.5));
+    /// assert!(!coordinate1.within_tolerance(&coordinate2, 0.49));
+    /// assert!(coordinate1.within_tolerance(&coordinate1, 0.0));
+    /// ```
+    pub fn within_tolerance(&self, other: &Coordinate, tolerance: f64) -> bool {
+        self.equals_2d_with_tolerance(other, tolerance) && self.equals_in_z_with_tolerance(other, tolerance)
+    }
+
+    /// Returns whether the planar distance between the coordinates is at most the given
+    /// distance. Unlike [`Coordinate::equals_2d_with_tolerance`], which compares each axis on
+    /// its own and so accepts a square around the coordinate, this accepts a circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The first coordinate.
+    /// * `other` - The second coordinate.
+    /// * `distance` - The maximum allowed distance between the coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coordinate::Coordinate;
+    ///
+    /// let coordinate1 = Coordinate::new(0.0, 0.0, 0.0);
+    /// let coordinate2 = Coordinate::new(3.0, 4.0, 10.0);
+    /// assert!(coordinate1.within_distance(&coordinate2, 5.0));
+    /// //within 4 along each axis, but 5 apart
+    /// assert!(coordinate1.equals_2d_with_tolerance(&coordinate2, 4.0));
+    /// assert!(!coordinate1.within_distance(&coordinate2, 4.0));
+    /// ```
+    pub fn within_distance(&self, other: &Coordinate, distance: f64) -> bool {
+        self.distance_2d(other) <= distance
+    }
+
+    /// Returns whether the distance in space between the coordinates is at most the given
+    /// distance, computed with [`Coordinate::distance_3d`] so that it does not overflow for
+    /// coordinates far apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The first coordinate.
+    /// * `other` - The second coordinate.
+    /// * `distance` - The maximum allowed distance between the coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coordinate::Coordinate;
+    ///
+    /// let coordinate1 = Coordinate::new(0.0, 0.0, 0.0);
+    /// let coordinate2 = Coordinate::new(2.0, 3.0, 6.0);
+    /// assert!(coordinate1.within_distance_3d(&coordinate2, 7.0));
+    /// assert!(!coordinate1.within_distance_3d(&coordinate2, 6.9));
+    /// //the squares of the differences overflow, but not the distance
+    /// let far = Coordinate::new(2e200, 3e200, 6e200);
+    /// assert!(coordinate1.within_distance_3d(&far, 7e200));
+    /// assert!(!coordinate1.within_distance_3d(&far, 6.9e200));
+    /// ```
+    pub fn within_distance_3d(&self, other: &Coordinate, distance: f64) -> bool {
+        self.distance_3d(other) <= distance
+    }
+
+    /// Returns whether the planar projections of the two coordinates differ by at most the
+    /// tolerance in x and in y.
     ///
     /// # Arguments
     ///
@@ -277,13 +425,19 @@ impl Coordinate {
     /// let coordinate1 = Coordinate::new(1.0, 2.0, 5.0);
     /// let coordinate2 = Coordinate::new(3.0, 4.0, 5.0);
     /// assert!(!coordinate1.equals_2d_with_tolerance(&coordinate2, 0.1));
+    ///
+    /// //a zero tolerance only accepts equal values
+    /// let coordinate1 = Coordinate::new(0.5, 0.5, 0.0);
+    /// let coordinate2 = Coordinate::new(0.5, 0.5 + f64::EPSILON / 2.0, 0.0);
+    /// assert!(!coordinate1.equals_2d_with_tolerance(&coordinate2, 0.0));
+    /// assert!(coordinate1.equals_2d_with_tolerance(&coordinate1, 0.0));
     /// ```
     pub fn equals_2d_with_tolerance(&self, other: &Coordinate, tolerance: f64) -> bool {
-        (self.x() - other.x()).abs() - tolerance < f64::EPSILON
-        && (self.y() - other.y()).abs() - tolerance < f64::EPSILON
+        (self.x() - other.x()).abs() <= tolerance && (self.y() - other.y()).abs() <= tolerance
     }
 
-    /// Checks if the coordinates are equal within a given tolerance.
+    /// Checks if the coordinates are equal within a given tolerance, the same as
+    /// [`Coordinate::within_tolerance`].
     ///
     /// # Arguments
     ///
@@ -305,11 +459,10 @@ impl Coordinate {
     /// assert!(!coordinate1.equals_with_tolerance(&coordinate2, 0.1));
     /// ```
     pub fn equals_with_tolerance(&self, other: &Coordinate, tolerance: f64) -> bool {
-        self.equals_2d_with_tolerance(other, tolerance) 
-        && (self.z() - other.z()).abs() - tolerance < f64::EPSILON
+        self.within_tolerance(other, tolerance)
     }
 
-    /// Checks if the z values of the coordinates are equal within a given tolerance.
+    /// Checks if the z values of the coordinates differ by at most the given tolerance.
     ///
     /// # Arguments
     ///
@@ -323,7 +476,7 @@ impl Coordinate {
     /// use geoms::coordinate::Coordinate;
     ///
     /// let coordinate1 = Coordinate::new(1.0, 2.0, 3.0);
-    /// let coordinate2 = Coordinate::new(1.0, 2.0, 3.1);
+    /// let coordinate2 = Coordinate::new(1.0, 2.0, 3.05);
     /// assert!(coordinate1.equals_in_z_with_tolerance(&coordinate2, 0.1));
     ///
     /// let coordinate1 = Coordinate::new(1.0, 2.0, 3.0);
@@ -331,7 +484,13 @@ impl Coordinate {
     /// assert!(!coordinate1.equals_in_z_with_tolerance(&coordinate2, 0.1));
     /// ```
     pub fn equals_in_z_with_tolerance(&self, other: &Coordinate, tolerance: f64) -> bool {
-        (self.z() - other.z()).abs() - tolerance < f64::EPSILON
+        (self.z() - other.z()).abs() <= tolerance
+    }
+
+    /// Returns the hashable planar location of the coordinate, with negative zeros made positive
+    /// so they match positive ones.
+    pub(crate) fn key(&self) -> (u64, u64) {
+        ((self.x() + 0.0).to_bits(), (self.y() + 0.0).to_bits())
     }
 
  }
@@ -351,7 +510,6 @@ impl Coordinate {
     /// let coordinate = Coordinate::new(1.0, 2.0, 3.0);
     /// assert_eq!(coordinate.to_string(), "(1, 2, 3)");
     /// ```
-
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "({}, {}, {})", self.x(), self.y(), self.z())
     }
@@ -362,11 +520,11 @@ impl Coordinate {
 impl Eq for Coordinate {}
 
 /// Implements the hash trait for the Coordinate struct.
+/// Negative zeros hash as positive ones, as the two are equal.
 impl Hash for Coordinate {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.x.to_bits().hash(state);
-        self.y.to_bits().hash(state);
-        self.z.to_bits().hash(state);
+        self.key().hash(state);
+        (self.z + 0.0).to_bits().hash(state);
     }
 }
 
@@ -385,7 +543,6 @@ impl Hash for Coordinate {
 /// let coord = coord!(10.0, 20, 30.0 );
 /// assert_eq!(coord, Coordinate::new(10.0, 20.0, 30.0));
 /// ```
-
 #[macro_export]
 macro_rules! coord {    
     ( $x:expr, $y:expr ) => {
@@ -1,8 +1,12 @@
+use std::sync::Arc;
+
 use crate::coordinate::Coordinate;
 
-#[derive(Clone)]
+/// A sequence of coordinates held in a shared buffer, so clones are cheap and share the
+/// coordinates until one of them is changed, which copies them.
+#[derive(Clone, PartialEq)]
 pub struct CoordinateSequence {
-    coordinates: Vec<Coordinate>,
+    coordinates: Arc<Vec<Coordinate>>,
 }
 
 impl CoordinateSequence {
@@ -18,9 +22,57 @@ impl CoordinateSequence {
     /// let sequence = CoordinateSequence::new(coordinates);
     /// ```
     pub fn new(coordinates: Vec<Coordinate>) -> Self {
+        Self { coordinates: Arc::new(coordinates) }
+    }
+
+    /// Creates a new CoordinateSequence sharing the coordinates of the buffer
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coordinate::coordinate_sequences::CoordinateSequence;
+    ///
+    /// let coordinates = Arc::new(vec![coord!(0, 0), coord!(1, 1)]);
+    /// let sequence = CoordinateSequence::from_shared(coordinates.clone());
+    /// assert!(Arc::ptr_eq(&sequence.to_shared(), &coordinates));
+    /// ```
+    pub fn from_shared(coordinates: Arc<Vec<Coordinate>>) -> Self {
         Self { coordinates }
     }
 
+    /// Returns the shared buffer of the coordinates of the sequence, without copying them
+    pub fn to_shared(&self) -> Arc<Vec<Coordinate>> {
+        self.coordinates.clone()
+    }
+
+    /// Returns true if both sequences share the same buffer, as clones do until one of them is
+    /// changed
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coordinate::coordinate_sequences::CoordinateSequence;
+    ///
+    /// let sequence = CoordinateSequence::new(vec![coord!(0, 0), coord!(1, 1), coord!(2, 2)]);
+    /// let mut copy = sequence.clone();
+    /// assert!(copy.shares_with(&sequence));
+    /// copy.coordinates_mut()[0] = coord!(5, 5);
+    /// assert!(!copy.shares_with(&sequence));
+    /// assert_eq!(sequence.get_coordinate(0), Some(&coord!(0, 0)));
+    /// ```
+    pub fn shares_with(&self, other: &CoordinateSequence) -> bool {
+        Arc::ptr_eq(&self.coordinates, &other.coordinates)
+    }
+
+    /// Returns the coordinates of the sequence for changing them, copying them first if the
+    /// buffer is shared with other sequences
+    pub fn coordinates_mut(&mut self) -> &mut [Coordinate] {
+        Arc::make_mut(&mut self.coordinates).as_mut_slice()
+    }
+
     /// Returns the coordinates of the sequence
     /// 
     /// # Examples
@@ -68,14 +120,13 @@ impl CoordinateSequence {
     /// ```
     pub fn set_coordinate(&self, index: usize, coordinate: Coordinate) -> Self {
         let mut new_sequence = self.clone();
-        new_sequence.coordinates[index] = coordinate;
+        new_sequence.coordinates_mut()[index] = coordinate;
         new_sequence
     }
 
     pub fn add_coordinate(&self, coordinate: Coordinate) -> Self {
-        let mut new_sequence = self.clone();
-        new_sequence.coordinates.push(coordinate);
-        new_sequence
+        let coordinates = self.coordinates.iter().cloned().chain(std::iter::once(coordinate)).collect();
+        Self { coordinates: Arc::new(coordinates) }
     }
 
     // pub fn remove_coordinate(&mut self, index: usize) -> Option<Coordinate> {
@@ -92,7 +143,7 @@ impl CoordinateSequence {
 
     pub fn has_duplicates(&self) -> bool {
         let mut set = std::collections::HashSet::new();
-        for coord in &self.coordinates {
+        for coord in self.coordinates.iter() {
             if !set.insert(coord) {
                 return true;
             }
@@ -0,0 +1,172 @@
+//! Coordinate reference systems and the transformation of coordinates between them, for moving
+//! data between longitudes and latitudes and the projection of web maps.
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geodesic::SEMI_MAJOR_AXIS;
+use crate::geometry::Geometry;
+use crate::operation::edit::{EditCoordinates, GeometryEditor};
+
+/// The largest latitude of Web Mercator, in degrees, where the projected square ends.
+pub const WEB_MERCATOR_MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// The largest absolute value of the x and y of Web Mercator, in meters.
+pub const WEB_MERCATOR_EXTENT: f64 = std::f64::consts::PI * SEMI_MAJOR_AXIS;
+
+/// A coordinate reference system.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Crs {
+    /// Longitude (x) and latitude (y) in degrees on the WGS84 ellipsoid, EPSG:4326.
+    Wgs84,
+    /// The spherical Mercator projection of web maps, in meters, EPSG:3857.
+    WebMercator,
+}
+
+impl Crs {
+    /// Returns the reference system with the given EPSG code, or None if it is not supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::crs::Crs;
+    ///
+    /// assert_eq!(Crs::from_epsg(3857), Some(Crs::WebMercator));
+    /// assert_eq!(Crs::from_epsg(27700), None);
+    /// ```
+    pub fn from_epsg(code: u32) -> Option<Crs> {
+        match code {
+            4326 => Some(Crs::Wgs84),
+            3857 | 900913 => Some(Crs::WebMercator),
+            _ => None,
+        }
+    }
+
+    /// Returns the EPSG code of the reference system.
+    pub fn epsg(&self) -> u32 {
+        match self {
+            Crs::Wgs84 => 4326,
+            Crs::WebMercator => 3857,
+        }
+    }
+}
+
+/// Returns the coordinate transformed from one reference system to another, keeping its z, or an
+/// error if it lies outside of the area where any of them is defined, as a latitude beyond 90
+/// degrees or too close to a pole for Web Mercator.
+///
+/// # Arguments
+///
+/// * `coordinate` - The coordinate to transform.
+/// * `from` - The reference system of the coordinate.
+/// * `to` - The reference system to transform to.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::crs::{transform, Crs};
+///
+/// let projected = transform(&coord!(180, 0), Crs::Wgs84, Crs::WebMercator).unwrap();
+/// assert!((projected.x() - 20_037_508.342_789).abs() < 1e-6);
+/// let back = transform(&projected, Crs::WebMercator, Crs::Wgs84).unwrap();
+/// assert!(back.equals_2d_with_tolerance(&coord!(180, 0), 1e-9));
+/// assert!(transform(&coord!(0, 89), Crs::Wgs84, Crs::WebMercator).is_err());
+/// ```
+pub fn transform(coordinate: &Coordinate, from: Crs, to: Crs) -> Result<Coordinate, Error> {
+    let (x, y) = (coordinate.x(), coordinate.y());
+    let (lon, lat) = match from {
+        Crs::Wgs84 => {
+            if !(-180.0..=180.0).contains(&x) || !(-90.0..=90.0).contains(&y) {
+                return Err(Error::Projection(format!("({} {}) is not a longitude and latitude", x, y)));
+            }
+            (x, y)
+        }
+        Crs::WebMercator => {
+            if x.abs() > WEB_MERCATOR_EXTENT || y.abs() > WEB_MERCATOR_EXTENT {
+                return Err(Error::Projection(format!("({} {}) is outside of Web Mercator", x, y)));
+            }
+            let lat = 2.0 * (y / SEMI_MAJOR_AXIS).exp().atan() - std::f64::consts::FRAC_PI_2;
+            ((x / SEMI_MAJOR_AXIS).to_degrees(), lat.to_degrees())
+        }
+    };
+    let (x, y) = match to {
+        Crs::Wgs84 => (lon, lat),
+        Crs::WebMercator => {
+            if lat.abs() > WEB_MERCATOR_MAX_LATITUDE {
+                return Err(Error::Projection(format!("the latitude {} is too close to a pole for Web Mercator", lat)));
+            }
+            let y = (std::f64::consts::FRAC_PI_4 + lat.to_radians() / 2.0).tan().ln();
+            (SEMI_MAJOR_AXIS * lon.to_radians(), SEMI_MAJOR_AXIS * y)
+        }
+    };
+    Ok(Coordinate::new(x, y, coordinate.z()))
+}
+
+/// Returns the geometry with all its coordinates transformed from one reference system to
+/// another, or the error of the first coordinate that cannot be transformed. See [`transform`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to transform.
+/// * `from` - The reference system of the geometry.
+/// * `to` - The reference system to transform to.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::crs::{transform_geometry, Crs};
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0)] };
+/// let projected = transform_geometry(&line, Crs::Wgs84, Crs::WebMercator).unwrap();
+/// assert!((projected.length() - 111_319.491).abs() < 1e-3);
+/// ```
+pub fn transform_geometry(geometry: &Geometry, from: Crs, to: Crs) -> Result<Geometry, Error> {
+    let mut error = None;
+    let transformed = {
+        let mut project = EditCoordinates(|coordinates: &[Coordinate], _| {
+            coordinates
+                .iter()
+                .map(|c| {
+                    transform(c, from, to).unwrap_or_else(|e| {
+                        error.get_or_insert(e);
+                        c.clone()
+                    })
+                })
+                .collect()
+        });
+        GeometryEditor::new().edit(geometry, &mut project)
+    };
+    match (error, transformed) {
+        (Some(error), _) => Err(error),
+        (None, Some(transformed)) => Ok(transformed),
+        (None, None) => Ok(geometry.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_round_trip_and_errors_of_geometries() {
+        let polygon = Geometry::Polygon { coordinates: vec![vec![
+            coord!(-10, -60, 5), coord!(170, -60, 5), coord!(170, 80, 5), coord!(-10, 80, 5), coord!(-10, -60, 5)
+        ]] };
+        let projected = transform_geometry(&polygon, Crs::Wgs84, Crs::WebMercator).unwrap();
+        let back = transform_geometry(&projected, Crs::WebMercator, Crs::Wgs84).unwrap();
+        for (a, b) in polygon.coords_iter().zip(back.coords_iter()) {
+            assert!(a.equals_2d_with_tolerance(b, 1e-9));
+            assert_eq!(b.z(), 5.0);
+        }
+        //the first coordinate that cannot be transformed is reported
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(200, 0), coord!(0, 95)] };
+        let error = transform_geometry(&line, Crs::Wgs84, Crs::Wgs84).unwrap_err();
+        assert_eq!(error, Error::Projection("(200 0) is not a longitude and latitude".to_string()));
+        assert!(transform(&coord!(0, WEB_MERCATOR_EXTENT * 1.01), Crs::WebMercator, Crs::Wgs84).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+use std::fmt;
+
+use crate::coordinate::Coordinate;
+
+/// Represents an axis aligned planar rectangle, such as the bounding box of a geometry.
+/// An envelope can be empty, in which case it does not intersect nor contain anything.
+///
+/// An envelope may also have a range of z, making it a box for indexing and querying 3D data by
+/// volume. Planar envelopes span all of z, so they meet boxes at any height, and merging a box
+/// with a planar envelope gives a planar envelope.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Envelope {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    //infinite for planar envelopes, and inverted for empty ones so they merge into anything
+    min_z: f64,
+    max_z: f64,
+}
+
+impl Envelope {
+    /// Creates a new envelope spanning between two corners, given in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `x1`, `y1` - The first corner.
+    /// * `x2`, `y2` - The opposite corner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(3.0, 4.0, 1.0, 2.0);
+    /// assert_eq!(envelope.min_x(), 1.0);
+    /// assert_eq!(envelope.max_y(), 4.0);
+    /// ```
+    pub fn new(x1: f64, y1: f64, x2: f64, y2: f64) -> Envelope {
+        Envelope {
+            min_x: x1.min(x2),
+            min_y: y1.min(y2),
+            max_x: x1.max(x2),
+            max_y: y1.max(y2),
+            min_z: f64::NEG_INFINITY,
+            max_z: f64::INFINITY,
+        }
+    }
+
+    /// Creates a new box spanning between two corners, given in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `x1`, `y1`, `z1` - The first corner.
+    /// * `x2`, `y2`, `z2` - The opposite corner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new_3d(3.0, 4.0, 10.0, 1.0, 2.0, -5.0);
+    /// assert_eq!(envelope, Envelope::new(1.0, 2.0, 3.0, 4.0).with_z_range(-5.0, 10.0));
+    /// assert_eq!(envelope.min_z(), -5.0);
+    /// ```
+    pub fn new_3d(x1: f64, y1: f64, z1: f64, x2: f64, y2: f64, z2: f64) -> Envelope {
+        Envelope::new(x1, y1, x2, y2).with_z_range(z1, z2)
+    }
+
+    /// Creates an empty envelope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// assert!(Envelope::empty().is_empty());
+    /// assert!(!Envelope::empty().intersects(&Envelope::new(0.0, 0.0, 1.0, 1.0)));
+    /// ```
+    pub fn empty() -> Envelope {
+        Envelope {
+            min_x: f64::INFINITY,
+            min_y: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            max_y: f64::NEG_INFINITY,
+            min_z: f64::INFINITY,
+            max_z: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Creates the smallest envelope containing the coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::of(&[coord!(1, 5), coord!(3, -2), coord!(0, 0)]);
+    /// assert_eq!(envelope, Envelope::new(0.0, -2.0, 3.0, 5.0));
+    /// ```
+    pub fn of<'a>(coordinates: impl IntoIterator<Item = &'a Coordinate>) -> Envelope {
+        coordinates.into_iter().fold(Envelope::empty(), |envelope, c| envelope.expand_to_include(c))
+    }
+
+    /// Creates the smallest box containing the coordinates, z included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::of_3d(&[coord!(1, 5, 30), coord!(3, -2, 10), coord!(0, 0, 20)]);
+    /// assert_eq!(envelope, Envelope::new_3d(0.0, -2.0, 10.0, 3.0, 5.0, 30.0));
+    /// assert!(Envelope::of_3d(&[]).is_empty());
+    /// ```
+    pub fn of_3d<'a>(coordinates: impl IntoIterator<Item = &'a Coordinate>) -> Envelope {
+        coordinates.into_iter().fold(Envelope::empty(), |envelope, c| envelope.expand_to_include_3d(c))
+    }
+
+    /// Creates the smallest envelope containing a slice of coordinates, scanning it in SIMD
+    /// lanes with the `simd` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let coordinates = vec![coord!(1, 5), coord!(3, -2), coord!(0, 0)];
+    /// assert_eq!(Envelope::of_slice(&coordinates), Envelope::of(&coordinates));
+    /// assert!(Envelope::of_slice(&[]).is_empty());
+    /// ```
+    pub fn of_slice(coordinates: &[Coordinate]) -> Envelope {
+        #[cfg(feature = "simd")]
+        return match crate::algorithm::simd::bounds(coordinates) {
+            Some([min_x, min_y, max_x, max_y]) => Envelope::new(min_x, min_y, max_x, max_y),
+            None => Envelope::empty(),
+        };
+        #[cfg(not(feature = "simd"))]
+        Envelope::of(coordinates)
+    }
+
+    /// Returns true if the envelope is empty.
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x
+    }
+
+    /// Returns the minimum x value of the envelope.
+    pub fn min_x(&self) -> f64 {
+        self.min_x
+    }
+
+    /// Returns the minimum y value of the envelope.
+    pub fn min_y(&self) -> f64 {
+        self.min_y
+    }
+
+    /// Returns the maximum x value of the envelope.
+    pub fn max_x(&self) -> f64 {
+        self.max_x
+    }
+
+    /// Returns the maximum y value of the envelope.
+    pub fn max_y(&self) -> f64 {
+        self.max_y
+    }
+
+    /// Returns the minimum z value of the envelope, negative infinity if it is planar.
+    pub fn min_z(&self) -> f64 {
+        self.min_z
+    }
+
+    /// Returns the maximum z value of the envelope, infinity if it is planar.
+    pub fn max_z(&self) -> f64 {
+        self.max_z
+    }
+
+    /// Returns true if the envelope is not empty and is bounded in z.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// assert!(Envelope::new_3d(0.0, 0.0, 0.0, 1.0, 1.0, 1.0).is_3d());
+    /// assert!(!Envelope::new(0.0, 0.0, 1.0, 1.0).is_3d());
+    /// ```
+    pub fn is_3d(&self) -> bool {
+        !self.is_empty() && (self.min_z > f64::NEG_INFINITY || self.max_z < f64::INFINITY)
+    }
+
+    /// Returns a copy of the envelope spanning between two values of z, given in any order.
+    /// Empty envelopes stay empty.
+    pub fn with_z_range(&self, z1: f64, z2: f64) -> Envelope {
+        if self.is_empty() {
+            return *self;
+        }
+        Envelope { min_z: z1.min(z2), max_z: z1.max(z2), ..*self }
+    }
+
+    /// Returns the width of the envelope, or 0 if it is empty.
+    pub fn width(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { self.max_x - self.min_x }
+    }
+
+    /// Returns the height of the envelope, or 0 if it is empty.
+    pub fn height(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { self.max_y - self.min_y }
+    }
+
+    /// Returns the area of the envelope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// assert_eq!(Envelope::new(0.0, 0.0, 2.0, 3.0).area(), 6.0);
+    /// assert_eq!(Envelope::empty().area(), 0.0);
+    /// ```
+    pub fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
+
+    /// Returns the center of the envelope, or None if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// assert_eq!(Envelope::new(0.0, 0.0, 2.0, 4.0).center(), Some(coord!(1, 2)));
+    /// ```
+    pub fn center(&self) -> Option<Coordinate> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(Coordinate::new((self.min_x + self.max_x) / 2.0, (self.min_y + self.max_y) / 2.0, 0.0))
+    }
+
+    /// Returns a new envelope that also contains the coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(0.0, 0.0, 1.0, 1.0).expand_to_include(&coord!(3, -1));
+    /// assert_eq!(envelope, Envelope::new(0.0, -1.0, 3.0, 1.0));
+    /// ```
+    pub fn expand_to_include(&self, coordinate: &Coordinate) -> Envelope {
+        Envelope {
+            min_x: self.min_x.min(coordinate.x()),
+            min_y: self.min_y.min(coordinate.y()),
+            max_x: self.max_x.max(coordinate.x()),
+            max_y: self.max_y.max(coordinate.y()),
+            min_z: f64::NEG_INFINITY,
+            max_z: f64::INFINITY,
+        }
+    }
+
+    /// Returns a new box that also contains the coordinate, z included. Planar envelopes stay
+    /// planar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new_3d(0.0, 0.0, 0.0, 1.0, 1.0, 1.0).expand_to_include_3d(&coord!(3, -1, 5));
+    /// assert_eq!(envelope, Envelope::new_3d(0.0, -1.0, 0.0, 3.0, 1.0, 5.0));
+    /// ```
+    pub fn expand_to_include_3d(&self, coordinate: &Coordinate) -> Envelope {
+        Envelope {
+            min_z: self.min_z.min(coordinate.z()),
+            max_z: self.max_z.max(coordinate.z()),
+            ..self.expand_to_include(coordinate)
+        }
+    }
+
+    /// Returns a new envelope containing both envelopes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(0.0, 0.0, 1.0, 1.0).merge(&Envelope::new(2.0, 2.0, 3.0, 3.0));
+    /// assert_eq!(envelope, Envelope::new(0.0, 0.0, 3.0, 3.0));
+    /// assert_eq!(envelope.merge(&Envelope::empty()), envelope);
+    /// let volume = Envelope::new_3d(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+    /// assert_eq!(volume.merge(&Envelope::new_3d(2.0, 2.0, 2.0, 3.0, 3.0, 3.0)), Envelope::new_3d(0.0, 0.0, 0.0, 3.0, 3.0, 3.0));
+    /// assert!(!volume.merge(&envelope).is_3d());
+    /// ```
+    pub fn merge(&self, other: &Envelope) -> Envelope {
+        Envelope {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+            min_z: self.min_z.min(other.min_z),
+            max_z: self.max_z.max(other.max_z),
+        }
+    }
+
+    /// Returns a new envelope grown by the distance on every side, above and below included for
+    /// boxes. Empty envelopes stay empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// assert_eq!(Envelope::new(0.0, 0.0, 1.0, 1.0).expand_by(1.0), Envelope::new(-1.0, -1.0, 2.0, 2.0));
+    /// ```
+    pub fn expand_by(&self, distance: f64) -> Envelope {
+        if self.is_empty() {
+            return *self;
+        }
+        Envelope::new(self.min_x - distance, self.min_y - distance, self.max_x + distance, self.max_y + distance)
+            .with_z_range(self.min_z - distance, self.max_z + distance)
+    }
+
+    /// Returns true if the envelopes share at least one point. Boxes must also overlap in z.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(0.0, 0.0, 1.0, 1.0);
+    /// assert!(envelope.intersects(&Envelope::new(1.0, 1.0, 2.0, 2.0)));
+    /// assert!(!envelope.intersects(&Envelope::new(1.5, 0.0, 2.0, 2.0)));
+    /// let volume = Envelope::new_3d(0.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+    /// assert!(volume.intersects(&envelope));
+    /// assert!(!volume.intersects(&Envelope::new_3d(0.0, 0.0, 2.0, 1.0, 1.0, 3.0)));
+    /// ```
+    pub fn intersects(&self, other: &Envelope) -> bool {
+        !(self.is_empty()
+            || other.is_empty()
+            || other.min_x > self.max_x
+            || other.max_x < self.min_x
+            || other.min_y > self.max_y
+            || other.max_y < self.min_y
+            || other.min_z > self.max_z
+            || other.max_z < self.min_z)
+    }
+
+    /// Returns true if the coordinate lies in the envelope, boundary included. Boxes also check
+    /// its z.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(0.0, 0.0, 1.0, 1.0);
+    /// assert!(envelope.contains_coordinate(&coord!(1, 0.5)));
+    /// assert!(!envelope.contains_coordinate(&coord!(2, 0.5)));
+    /// ```
+    pub fn contains_coordinate(&self, coordinate: &Coordinate) -> bool {
+        coordinate.x() >= self.min_x
+            && coordinate.x() <= self.max_x
+            && coordinate.y() >= self.min_y
+            && coordinate.y() <= self.max_y
+            && coordinate.z() >= self.min_z
+            && coordinate.z() <= self.max_z
+    }
+
+    /// Returns true if the other envelope lies in this one, boundary included. A box contains
+    /// no planar envelope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(0.0, 0.0, 2.0, 2.0);
+    /// assert!(envelope.contains(&Envelope::new(0.0, 0.0, 1.0, 1.0)));
+    /// assert!(!envelope.contains(&Envelope::new(1.0, 1.0, 3.0, 3.0)));
+    /// assert!(envelope.contains(&Envelope::new_3d(0.0, 0.0, 5.0, 1.0, 1.0, 6.0)));
+    /// assert!(!Envelope::new_3d(0.0, 0.0, 0.0, 2.0, 2.0, 2.0).contains(&Envelope::new(0.0, 0.0, 1.0, 1.0)));
+    /// ```
+    pub fn contains(&self, other: &Envelope) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && other.min_x >= self.min_x
+            && other.max_x <= self.max_x
+            && other.min_y >= self.min_y
+            && other.max_y <= self.max_y
+            && other.min_z >= self.min_z
+            && other.max_z <= self.max_z
+    }
+
+    /// Returns the planar distance between the envelopes, 0 if they intersect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new(0.0, 0.0, 1.0, 1.0);
+    /// assert_eq!(envelope.distance(&Envelope::new(4.0, 5.0, 6.0, 6.0)), 5.0);
+    /// assert_eq!(envelope.distance(&Envelope::new(0.5, 0.5, 6.0, 6.0)), 0.0);
+    /// ```
+    pub fn distance(&self, other: &Envelope) -> f64 {
+        let dx = (other.min_x - self.max_x).max(self.min_x - other.max_x).max(0.0);
+        let dy = (other.min_y - self.max_y).max(self.min_y - other.max_y).max(0.0);
+        dx.hypot(dy)
+    }
+}
+
+impl fmt::Display for Envelope {
+    /// Returns the string representation of the envelope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    ///
+    /// assert_eq!(Envelope::new(0.0, 1.0, 2.0, 3.0).to_string(), "Env[0 : 2, 1 : 3]");
+    /// assert_eq!(Envelope::empty().to_string(), "Env[empty]");
+    /// assert_eq!(Envelope::new_3d(0.0, 1.0, 4.0, 2.0, 3.0, 5.0).to_string(), "Env[0 : 2, 1 : 3, 4 : 5]");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            write!(f, "Env[empty]")
+        } else if self.is_3d() {
+            write!(f, "Env[{} : {}, {} : {}, {} : {}]", self.min_x, self.max_x, self.min_y, self.max_y, self.min_z, self.max_z)
+        } else {
+            write!(f, "Env[{} : {}, {} : {}]", self.min_x, self.max_x, self.min_y, self.max_y)
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// Errors returned by the fallible operations of the library.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    /// The input is not valid WKT. Carries a description of the problem.
+    InvalidWkt(String),
+    /// The input is not valid WKB. Carries a description of the problem.
+    InvalidWkb(String),
+    /// The input is not valid GeoJSON. Carries a description of the problem.
+    InvalidGeoJson(String),
+    /// A geometry being built is not valid. Carries the first validity error found.
+    InvalidGeometry(String),
+    /// The coordinates do not form a linear ring. Carries a description of the problem.
+    InvalidRing(String),
+    /// The positions and times of a trajectory do not match. Carries a description of the problem.
+    InvalidTrajectory(String),
+    /// An operation could not build a consistent topology, usually because of floating point
+    /// errors. Carries a description of the problem.
+    Topology(String),
+    /// The input passes one of the limits set on a reader. Carries a description of the limit.
+    LimitExceeded(String),
+    /// A coordinate cannot be transformed between two reference systems. Carries a description
+    /// of the problem.
+    Projection(String),
+    /// The input is not valid CSV. Carries a description of the problem.
+    InvalidCsv(String),
+    /// The input is not valid FlatGeobuf. Carries a description of the problem.
+    InvalidFlatGeobuf(String),
+    /// Reading or writing a stream failed. Carries the message of the underlying error.
+    Io(String),
+    /// A long operation was cancelled through its progress sink before it finished.
+    Cancelled,
+}
+
+impl fmt::Display for Error {
+    /// Returns the string representation of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::error::Error;
+    ///
+    /// let error = Error::InvalidWkt("unexpected end of input".to_string());
+    /// assert_eq!(error.to_string(), "invalid WKT: unexpected end of input");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidWkt(message) => write!(f, "invalid WKT: {}", message),
+            Error::InvalidWkb(message) => write!(f, "invalid WKB: {}", message),
+            Error::InvalidGeoJson(message) => write!(f, "invalid GeoJSON: {}", message),
+            Error::InvalidGeometry(message) => write!(f, "invalid geometry: {}", message),
+            Error::InvalidRing(message) => write!(f, "invalid ring: {}", message),
+            Error::InvalidTrajectory(message) => write!(f, "invalid trajectory: {}", message),
+            Error::Topology(message) => write!(f, "topology error: {}", message),
+            Error::LimitExceeded(message) => write!(f, "limit exceeded: {}", message),
+            Error::Projection(message) => write!(f, "projection error: {}", message),
+            Error::InvalidCsv(message) => write!(f, "invalid CSV: {}", message),
+            Error::InvalidFlatGeobuf(message) => write!(f, "invalid FlatGeobuf: {}", message),
+            Error::Io(message) => write!(f, "I/O error: {}", message),
+            Error::Cancelled => write!(f, "the operation was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        Error::Io(error.to_string())
+    }
+}
@@ -0,0 +1,476 @@
+//! Geometries carrying named attributes, and collections of them.
+
+use std::collections::BTreeMap;
+
+use crate::crs::{self, Crs};
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+use crate::operation::distance;
+use crate::operation::overlay::{overlay, unary_union, OverlayOp};
+
+/// The value of an attribute of a feature.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+impl Value {
+    /// Returns the value as a number, or None if it is not one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a string, or None if it is not one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::Bool(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Value {
+        Value::Number(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::String(value)
+    }
+}
+
+/// A geometry with named attributes, such as a parcel with its owner and value.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Feature {
+    geometry: Geometry,
+    properties: BTreeMap<String, Value>,
+}
+
+impl Feature {
+    /// Creates a feature without attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, Value};
+    ///
+    /// let well = Feature::new(Geometry::Point { coordinates: coord!(3, 4) })
+    ///     .with_property("name", "north")
+    ///     .with_property("depth", 120.0);
+    /// assert_eq!(well.property("depth"), Some(&Value::Number(120.0)));
+    /// assert_eq!(well.property("owner"), None);
+    /// ```
+    pub fn new(geometry: Geometry) -> Feature {
+        Feature { geometry, properties: BTreeMap::new() }
+    }
+
+    /// Returns the feature with an attribute set.
+    pub fn with_property(mut self, name: &str, value: impl Into<Value>) -> Feature {
+        self.set_property(name, value);
+        self
+    }
+
+    /// Returns the geometry of the feature.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Replaces the geometry of the feature.
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.geometry = geometry;
+    }
+
+    /// Returns the value of an attribute, or None if the feature does not have it.
+    pub fn property(&self, name: &str) -> Option<&Value> {
+        self.properties.get(name)
+    }
+
+    /// Sets the value of an attribute, replacing its previous value.
+    pub fn set_property(&mut self, name: &str, value: impl Into<Value>) {
+        self.properties.insert(name.to_string(), value.into());
+    }
+
+    /// Returns all the attributes of the feature, ordered by name.
+    pub fn properties(&self) -> &BTreeMap<String, Value> {
+        &self.properties
+    }
+}
+
+/// The pieces of two layers of polygons kept by [`FeatureCollection::overlay`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OverlayKind {
+    /// The areas covered by both layers.
+    Intersection,
+    /// The areas covered by the first layer, split where they are covered by the second one.
+    Identity,
+    /// The areas covered by any of the layers, split where they are covered by both.
+    Union,
+}
+
+/// An ordered set of features, such as a layer read from a file.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct FeatureCollection {
+    features: Vec<Feature>,
+}
+
+impl FeatureCollection {
+    /// Creates a collection holding the features.
+    pub fn new(features: Vec<Feature>) -> FeatureCollection {
+        FeatureCollection { features }
+    }
+
+    /// Returns the features of the collection.
+    pub fn features(&self) -> &[Feature] {
+        &self.features
+    }
+
+    /// Adds a feature at the end of the collection.
+    pub fn push(&mut self, feature: Feature) {
+        self.features.push(feature);
+    }
+
+    /// Returns the number of features of the collection.
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    /// Returns true if the collection has no features.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Returns an iterator over the features of the collection.
+    pub fn iter(&self) -> std::slice::Iter<'_, Feature> {
+        self.features.iter()
+    }
+
+    /// Returns the geometries of all the features gathered in a single geometry: a multi point,
+    /// multi line string or multi polygon when they are all of the same kind, otherwise a
+    /// geometry collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection};
+    ///
+    /// let wells = FeatureCollection::new(vec![
+    ///     Feature::new(Geometry::Point { coordinates: coord!(0, 0) }),
+    ///     Feature::new(Geometry::MultiPoint { coordinates: vec![coord!(1, 1), coord!(2, 2)] }),
+    /// ]);
+    /// assert_eq!(wells.collect_geometry().as_text(), "MULTIPOINT ((0 0), (1 1), (2 2))");
+    /// ```
+    pub fn collect_geometry(&self) -> Geometry {
+        let geometries = self.features.iter().map(|f| &f.geometry);
+        let mut points = Vec::new();
+        let mut lines = Vec::new();
+        let mut polygons = Vec::new();
+        let mut others = 0;
+        for geometry in geometries.clone() {
+            match geometry {
+                Geometry::Point { coordinates } => points.push(coordinates.clone()),
+                Geometry::MultiPoint { coordinates } => points.extend(coordinates.iter().cloned()),
+                Geometry::LineString { coordinates } => lines.push(coordinates.clone()),
+                Geometry::MultiLineString { coordinates } => lines.extend(coordinates.iter().cloned()),
+                Geometry::Polygon { coordinates } => polygons.push(coordinates.clone()),
+                Geometry::MultiPolygon { coordinates } => polygons.extend(coordinates.iter().cloned()),
+                _ => others += 1,
+            }
+        }
+        let kinds = [!points.is_empty(), !lines.is_empty(), !polygons.is_empty()].iter().filter(|&&k| k).count();
+        match (kinds, others) {
+            (1, 0) if !points.is_empty() => Geometry::MultiPoint { coordinates: points },
+            (1, 0) if !lines.is_empty() => Geometry::MultiLineString { coordinates: lines },
+            (1, 0) => Geometry::MultiPolygon { coordinates: polygons },
+            _ => Geometry::GeometryCollection { geometries: geometries.cloned().collect() },
+        }
+    }
+
+    /// Returns the bounding box of the geometries of all the features.
+    pub fn envelope(&self) -> Envelope {
+        self.features.iter().fold(Envelope::empty(), |envelope, f| envelope.merge(&f.geometry.envelope()))
+    }
+
+    /// Returns the features whose geometries intersect the envelope, in the order of the
+    /// collection, as when drawing a map window. See [`crate::operation::filter_bbox`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection, Value};
+    ///
+    /// let wells: FeatureCollection = (0..10)
+    ///     .map(|i| Feature::new(Geometry::Point { coordinates: coord!(i, i) }).with_property("id", i as f64))
+    ///     .collect();
+    /// let window = wells.filter_bbox(&Envelope::new(2.5, 0.0, 5.0, 10.0));
+    /// let ids: Vec<&Value> = window.iter().filter_map(|f| f.property("id")).collect();
+    /// assert_eq!(ids, [&Value::Number(3.0), &Value::Number(4.0), &Value::Number(5.0)]);
+    /// ```
+    pub fn filter_bbox(&self, envelope: &Envelope) -> Vec<&Feature> {
+        let geometries: Vec<&Geometry> = self.features.iter().map(|f| &f.geometry).collect();
+        distance::indices_within_distance(&geometries, &distance::rectangle(envelope), 0.0)
+            .into_iter()
+            .map(|i| &self.features[i])
+            .collect()
+    }
+
+    /// Returns the polygons of the features of both collections split where they overlap, each
+    /// piece with the attributes of the features covering it, as the identity and union tools of
+    /// GIS do. Where features of both collections have an attribute of the same name, the one of
+    /// the other collection is renamed with a `_2` suffix. The points and lines of the features
+    /// are ignored.
+    ///
+    /// The pieces covered by both collections come first, one for every pair of overlapping
+    /// features in the order of the features of this collection and then of the other one, each
+    /// followed by the part of the feature of this collection not covered by the other
+    /// collection, with an identity or union. With a union, the parts of the features of the
+    /// other collection not covered by this one come last. Pieces without area are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The collection to overlay this one with.
+    /// * `kind` - The pieces to keep.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection, OverlayKind, Value};
+    ///
+    /// let rectangle = |x1: f64, x2: f64| Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(x1, 0), coord!(x2, 0), coord!(x2, 10), coord!(x1, 10), coord!(x1, 0)
+    /// ]] };
+    /// let parcels: FeatureCollection = vec![Feature::new(rectangle(0.0, 10.0)).with_property("owner", "Ada")].into_iter().collect();
+    /// let zones: FeatureCollection = vec![
+    ///     Feature::new(rectangle(-5.0, 4.0)).with_property("zone", "farm"),
+    ///     Feature::new(rectangle(4.0, 8.0)).with_property("zone", "forest"),
+    /// ].into_iter().collect();
+    ///
+    /// let identity = parcels.overlay(&zones, OverlayKind::Identity).unwrap();
+    /// let pieces: Vec<(f64, Option<&Value>)> = identity.iter().map(|f| (f.geometry().area(), f.property("zone"))).collect();
+    /// assert_eq!(pieces, [(40.0, Some(&Value::from("farm"))), (40.0, Some(&Value::from("forest"))), (20.0, None)]);
+    /// assert!(identity.iter().all(|f| f.property("owner") == Some(&Value::from("Ada"))));
+    ///
+    /// assert_eq!(parcels.overlay(&zones, OverlayKind::Intersection).unwrap().total_area(), 80.0);
+    /// let union = parcels.overlay(&zones, OverlayKind::Union).unwrap();
+    /// assert_eq!((union.len(), union.total_area()), (4, 150.0));
+    /// assert_eq!(union.features()[3].property("owner"), None);
+    /// ```
+    pub fn overlay(&self, other: &FeatureCollection, kind: OverlayKind) -> Result<FeatureCollection, Error> {
+        let tree = STRtree::new(other.iter().enumerate().map(|(j, f)| (f.geometry.envelope(), j)).collect());
+        let mut covering: Vec<Vec<usize>> = vec![Vec::new(); other.len()];
+        let mut pieces = FeatureCollection::default();
+        for (i, feature) in self.iter().enumerate() {
+            let mut candidates: Vec<usize> = tree.query(&feature.geometry.envelope()).into_iter().copied().collect();
+            candidates.sort();
+            let mut overlapping = Vec::new();
+            for j in candidates {
+                let shared = overlay(&feature.geometry, &other.features[j].geometry, OverlayOp::Intersection)?;
+                if shared.area() > 0.0 {
+                    pieces.push(Feature { geometry: shared, properties: merge(&feature.properties, &other.features[j].properties) });
+                    overlapping.push(other.features[j].geometry.clone());
+                    covering[j].push(i);
+                }
+            }
+            if kind != OverlayKind::Intersection {
+                let covered = unary_union(&Geometry::GeometryCollection { geometries: overlapping })?;
+                let rest = overlay(&feature.geometry, &covered, OverlayOp::Difference)?;
+                if rest.area() > 0.0 {
+                    pieces.push(Feature { geometry: rest, properties: feature.properties.clone() });
+                }
+            }
+        }
+        if kind == OverlayKind::Union {
+            for (j, feature) in other.iter().enumerate() {
+                let covered = unary_union(&Geometry::GeometryCollection {
+                    geometries: covering[j].iter().map(|&i| self.features[i].geometry.clone()).collect(),
+                })?;
+                let rest = overlay(&feature.geometry, &covered, OverlayOp::Difference)?;
+                if rest.area() > 0.0 {
+                    pieces.push(Feature { geometry: rest, properties: feature.properties.clone() });
+                }
+            }
+        }
+        Ok(pieces)
+    }
+
+    /// Returns the features with their geometries transformed from one reference system to
+    /// another, and the index and error of every feature with a coordinate that could not be
+    /// transformed, which is left out of the collection instead of failing the whole of it. The
+    /// features are transformed on all cores when the `parallel` feature is enabled. See
+    /// [`crate::crs::transform`].
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The reference system of the features.
+    /// * `to` - The reference system to transform to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection, Value};
+    /// use geoms::crs::Crs;
+    ///
+    /// let stations = FeatureCollection::new(vec![
+    ///     Feature::new(Geometry::Point { coordinates: coord!(0, 0) }).with_property("name", "Null Island"),
+    ///     Feature::new(Geometry::Point { coordinates: coord!(0, 90) }).with_property("name", "North Pole"),
+    ///     Feature::new(Geometry::Point { coordinates: coord!(-0.1, 51.5) }).with_property("name", "London"),
+    /// ]);
+    /// let (projected, errors) = stations.transform_crs(Crs::Wgs84, Crs::WebMercator);
+    /// assert_eq!(projected.len(), 2);
+    /// assert_eq!(projected.features()[1].property("name"), Some(&Value::from("London")));
+    /// assert_eq!(errors.iter().map(|(i, _)| *i).collect::<Vec<usize>>(), [1]);
+    /// ```
+    pub fn transform_crs(&self, from: Crs, to: Crs) -> (FeatureCollection, Vec<(usize, Error)>) {
+        let transform = |feature: &Feature| -> Result<Feature, Error> {
+            let geometry = crs::transform_geometry(&feature.geometry, from, to)?;
+            Ok(Feature { geometry, properties: feature.properties.clone() })
+        };
+        #[cfg(feature = "parallel")]
+        let results: Vec<Result<Feature, Error>> = {
+            use rayon::prelude::*;
+            self.features.par_iter().map(transform).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<Result<Feature, Error>> = self.features.iter().map(transform).collect();
+
+        let mut transformed = FeatureCollection::default();
+        let mut errors = Vec::new();
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(feature) => transformed.push(feature),
+                Err(error) => errors.push((i, error)),
+            }
+        }
+        (transformed, errors)
+    }
+
+    /// Returns the sum of the planar areas of the geometries of all the features.
+    pub fn total_area(&self) -> f64 {
+        self.features.iter().map(|f| f.geometry.area()).sum()
+    }
+
+    /// Returns the sum of the planar lengths of the geometries of all the features.
+    pub fn total_length(&self) -> f64 {
+        self.features.iter().map(|f| f.geometry.length()).sum()
+    }
+
+    /// Returns the centroid of the geometries of all the features taken together, or None if they
+    /// are all empty. See [`crate::algorithm::centroid::centroid`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection};
+    ///
+    /// let square = |x: f64, size: f64| Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(x, 0), coord!(x + size, 0), coord!(x + size, size), coord!(x, size), coord!(x, 0)
+    /// ]] };
+    /// let parcels = FeatureCollection::new(vec![Feature::new(square(0.0, 2.0)), Feature::new(square(4.0, 2.0))]);
+    /// assert_eq!(parcels.total_area(), 8.0);
+    /// assert_eq!(parcels.total_length(), 16.0);
+    /// assert_eq!(parcels.centroid(), Some(Geometry::Point { coordinates: coord!(3, 1) }));
+    /// ```
+    pub fn centroid(&self) -> Option<Geometry> {
+        Geometry::GeometryCollection { geometries: self.features.iter().map(|f| f.geometry.clone()).collect() }
+            .centroid()
+    }
+}
+
+/// Returns the attributes of both maps, with the names of the second one also in the first one
+/// given a `_2` suffix.
+fn merge(first: &BTreeMap<String, Value>, second: &BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+    let mut merged = first.clone();
+    for (name, value) in second {
+        let name = if first.contains_key(name) { format!("{}_2", name) } else { name.clone() };
+        merged.insert(name, value.clone());
+    }
+    merged
+}
+
+impl FromIterator<Feature> for FeatureCollection {
+    fn from_iter<I: IntoIterator<Item = Feature>>(iter: I) -> FeatureCollection {
+        FeatureCollection { features: iter.into_iter().collect() }
+    }
+}
+
+impl IntoIterator for FeatureCollection {
+    type Item = Feature;
+    type IntoIter = std::vec::IntoIter<Feature>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.features.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FeatureCollection {
+    type Item = &'a Feature;
+    type IntoIter = std::slice::Iter<'a, Feature>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.features.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_overlay_attribute_clash() {
+        let square = |x: f64| Geometry::Polygon { coordinates: vec![vec![
+            coord!(x, 0), coord!(x + 2.0, 0), coord!(x + 2.0, 2), coord!(x, 2), coord!(x, 0)
+        ]] };
+        let a = FeatureCollection::new(vec![Feature::new(square(0.0)).with_property("name", "a")]);
+        let b = FeatureCollection::new(vec![
+            Feature::new(square(1.0)).with_property("name", "b"),
+            //lines are ignored
+            Feature::new(Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 2)] }).with_property("name", "c"),
+        ]);
+        let pieces = a.overlay(&b, OverlayKind::Intersection).unwrap();
+        assert_eq!(pieces.len(), 1);
+        let names: Vec<(&String, &Value)> = pieces.features()[0].properties().iter().collect();
+        assert_eq!(names, [(&"name".to_string(), &Value::from("a")), (&"name_2".to_string(), &Value::from("b"))]);
+    }
+}
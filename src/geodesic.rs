@@ -0,0 +1,363 @@
+//! Measures on the WGS84 ellipsoid for coordinates given as longitude (x) and latitude (y) in
+//! degrees, with distances in meters and bearings in degrees clockwise from north.
+
+use crate::coordinate::Coordinate;
+
+/// The semi-major axis of the WGS84 ellipsoid, in meters.
+pub const SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+
+/// The flattening of the WGS84 ellipsoid.
+pub const FLATTENING: f64 = 1.0 / 298.257_223_563;
+
+/// The mean radius of the earth, in meters.
+pub const MEAN_RADIUS: f64 = 6_371_008.8;
+
+/// How coordinates are measured: in the plane of the coordinate system, or on the ellipsoid for
+/// longitudes and latitudes in degrees.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Mode {
+    #[default]
+    Planar,
+    Geodesic,
+}
+
+/// Returns the length of the shortest path between two points on the ellipsoid, in meters.
+///
+/// The distance is computed with the formulas of Vincenty, which are accurate to less than a
+/// millimeter. Points on the equator more than (1 - f) * 180 degrees apart are joined over the
+/// poles, not along the equator. For other nearly antipodal points, where the formulas do not
+/// converge, a sphere of the mean radius of the earth is used instead.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geodesic::distance;
+///
+/// //one degree along the equator
+/// assert!((distance(&coord!(0, 0), &coord!(1, 0)) - 111_319.491).abs() < 1e-3);
+/// ```
+pub fn distance(from: &Coordinate, to: &Coordinate) -> f64 {
+    inverse(from, to).0
+}
+
+/// Returns the bearing at the start of the shortest path between two points on the ellipsoid, in
+/// degrees clockwise from north in the range [0, 360). The bearing between coincident points is 0.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geodesic::initial_bearing;
+///
+/// assert!((initial_bearing(&coord!(0, 0), &coord!(0, 10)) - 0.0).abs() < 1e-9);
+/// assert!((initial_bearing(&coord!(0, 0), &coord!(10, 0)) - 90.0).abs() < 1e-9);
+/// ```
+pub fn initial_bearing(from: &Coordinate, to: &Coordinate) -> f64 {
+    inverse(from, to).1
+}
+
+/// Returns the point reached by following the shortest path on the ellipsoid from a point with
+/// the given initial bearing for the given distance. The longitude of the result is in the range
+/// [-180, 180] and its z is the one of the start.
+///
+/// # Arguments
+///
+/// * `from` - The start, as longitude and latitude in degrees.
+/// * `bearing` - The initial bearing in degrees clockwise from north.
+/// * `distance` - The distance in meters.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geodesic::destination;
+///
+/// let reached = destination(&coord!(179.5, 0), 90.0, 111_319.491);
+/// assert!((reached.x() + 179.5).abs() < 1e-8 && reached.y().abs() < 1e-8);
+/// ```
+pub fn destination(from: &Coordinate, bearing: f64, distance: f64) -> Coordinate {
+    let b = SEMI_MAJOR_AXIS * (1.0 - FLATTENING);
+    let alpha1 = bearing.to_radians();
+    let (sin_alpha1, cos_alpha1) = alpha1.sin_cos();
+    let tan_u1 = (1.0 - FLATTENING) * from.y().to_radians().tan();
+    let cos_u1 = 1.0 / (1.0 + tan_u1 * tan_u1).sqrt();
+    let sin_u1 = tan_u1 * cos_u1;
+    let sigma1 = tan_u1.atan2(cos_alpha1);
+    let sin_alpha = cos_u1 * sin_alpha1;
+    let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+    let (a_coefficient, b_coefficient) = series_coefficients(cos2_alpha, b);
+
+    let mut sigma = distance / (b * a_coefficient);
+    let (mut sin_sigma, mut cos_sigma, mut cos_2sigma_m);
+    loop {
+        cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+        (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let delta = delta_sigma(b_coefficient, sin_sigma, cos_sigma, cos_2sigma_m);
+        let previous = sigma;
+        sigma = distance / (b * a_coefficient) + delta;
+        if (sigma - previous).abs() < 1e-12 {
+            break;
+        }
+    }
+    (sin_sigma, cos_sigma) = sigma.sin_cos();
+    cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+
+    let x = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_alpha1;
+    let latitude = (sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_alpha1)
+        .atan2((1.0 - FLATTENING) * (sin_alpha * sin_alpha + x * x).sqrt());
+    let lambda = (sin_sigma * sin_alpha1).atan2(cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_alpha1);
+    let c = FLATTENING / 16.0 * cos2_alpha * (4.0 + FLATTENING * (4.0 - 3.0 * cos2_alpha));
+    let l = lambda
+        - (1.0 - c)
+            * FLATTENING
+            * sin_alpha
+            * (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+    let longitude = normalize_longitude(from.x() + l.to_degrees());
+    Coordinate::new(longitude, latitude.to_degrees(), from.z())
+}
+
+/// Returns a line following the shortest paths on the ellipsoid between its vertices, with points
+/// inserted so that no segment is longer than the maximum distance, as needed to draw long routes
+/// as curves on a projected map. The line is cut where it crosses the antimeridian, so every part
+/// keeps its longitudes on one side of it and the parts meet at longitudes 180 and -180. The z of
+/// the inserted points is interpolated along their segment.
+///
+/// # Arguments
+///
+/// * `line` - The vertices of the line, as longitudes and latitudes in degrees.
+/// * `max_distance` - The longest segment allowed, in meters, which must be positive.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geodesic::densify;
+///
+/// //from Tokyo to San Francisco over the Pacific
+/// let flight = vec![coord!(139.78, 35.55), coord!(-122.38, 37.62)];
+/// let parts = densify(&flight, 100_000.0);
+/// assert_eq!(parts.len(), 2);
+/// assert_eq!((parts[0].last().unwrap().x(), parts[1][0].x()), (180.0, -180.0));
+/// //the great circle goes far north of the straight line on the map
+/// assert!(parts.iter().flatten().any(|c| c.y() > 47.0));
+/// ```
+pub fn densify(line: &[Coordinate], max_distance: f64) -> Vec<Vec<Coordinate>> {
+    if max_distance.is_nan() || max_distance <= 0.0 {
+        panic!("The maximum distance must be positive");
+    }
+    let mut points: Vec<Coordinate> = line.iter().take(1).cloned().collect();
+    for w in line.windows(2) {
+        let (length, bearing) = inverse(&w[0], &w[1]);
+        let steps = (length / max_distance).ceil().max(1.0) as usize;
+        for i in 1..steps {
+            let fraction = i as f64 / steps as f64;
+            let z = w[0].z() + fraction * (w[1].z() - w[0].z());
+            points.push(destination(&w[0], bearing, fraction * length).set_z(z));
+        }
+        points.push(w[1].clone());
+    }
+
+    let mut parts = vec![Vec::new()];
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            let previous = &points[i - 1];
+            let delta = point.x() - previous.x();
+            if delta.abs() > 180.0 {
+                //the line crosses the antimeridian, where the longitude wraps around
+                let edge = if previous.x() > 0.0 { 180.0 } else { -180.0 };
+                let fraction = (edge - previous.x()) / (delta - 360.0 * delta.signum());
+                let y = previous.y() + fraction * (point.y() - previous.y());
+                let z = previous.z() + fraction * (point.z() - previous.z());
+                parts.last_mut().unwrap().push(Coordinate::new(edge, y, z));
+                parts.push(vec![Coordinate::new(-edge, y, z)]);
+            }
+        }
+        parts.last_mut().unwrap().push(point.clone());
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Returns the distance and the initial bearing between two points on the ellipsoid.
+fn inverse(from: &Coordinate, to: &Coordinate) -> (f64, f64) {
+    let b = SEMI_MAJOR_AXIS * (1.0 - FLATTENING);
+    let l = (to.x() - from.x()).to_radians();
+    let u1 = ((1.0 - FLATTENING) * from.y().to_radians().tan()).atan();
+    let u2 = ((1.0 - FLATTENING) * to.y().to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+    let l_wrapped = normalize_longitude(to.x() - from.x()).to_radians();
+    if u1 == 0.0 && u2 == 0.0 && l_wrapped.abs() > (1.0 - FLATTENING) * std::f64::consts::PI {
+        return equatorial_antipodal_inverse(l_wrapped, b);
+    }
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+        if sin_sigma == 0.0 {
+            return (0.0, 0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        //lines along the equator have no vertex
+        let cos_2sigma_m = if cos2_alpha == 0.0 { 0.0 } else { cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha };
+        let c = FLATTENING / 16.0 * cos2_alpha * (4.0 + FLATTENING * (4.0 - 3.0 * cos2_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * FLATTENING
+                * sin_alpha
+                * (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+        if (lambda - previous).abs() < 1e-12 {
+            let (a_coefficient, b_coefficient) = series_coefficients(cos2_alpha, b);
+            let distance = b * a_coefficient * (sigma - delta_sigma(b_coefficient, sin_sigma, cos_sigma, cos_2sigma_m));
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let bearing = (cos_u2 * sin_lambda).atan2(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            return (distance, normalize_bearing(bearing.to_degrees()));
+        }
+    }
+    spherical_inverse(from, to)
+}
+
+/// Returns the distance and the initial bearing between two points on the equator whose difference
+/// of longitude `l` in radians is too large for the shortest path to follow the equator. The path
+/// leaves the equator with the azimuth alpha and meets it again half way around the auxiliary
+/// sphere, where sigma is pi, so the series of Vincenty reduce to
+/// `l = pi - (1 - C) * f * sin(alpha) * pi`, solved for alpha by iteration, and
+/// `distance = b * A * pi`.
+fn equatorial_antipodal_inverse(l: f64, b: f64) -> (f64, f64) {
+    let pi = std::f64::consts::PI;
+    let mut sin_alpha = 0.0;
+    for _ in 0..20 {
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        let c = FLATTENING / 16.0 * cos2_alpha * (4.0 + FLATTENING * (4.0 - 3.0 * cos2_alpha));
+        let next = ((pi - l.abs()) / ((1.0 - c) * FLATTENING * pi)).min(1.0);
+        let converged = (next - sin_alpha).abs() < 1e-15;
+        sin_alpha = next;
+        if converged {
+            break;
+        }
+    }
+    let (a_coefficient, _) = series_coefficients(1.0 - sin_alpha * sin_alpha, b);
+    //westwards the path leaves northwards to the west, the mirror image of the path eastwards
+    let bearing = sin_alpha.asin().to_degrees() * l.signum();
+    (b * a_coefficient * pi, normalize_bearing(bearing))
+}
+
+/// Returns the distance and the initial bearing between two points on a sphere of the mean radius.
+fn spherical_inverse(from: &Coordinate, to: &Coordinate) -> (f64, f64) {
+    let (phi1, phi2) = (from.y().to_radians(), to.y().to_radians());
+    let delta_lambda = (to.x() - from.x()).to_radians();
+    let h = ((phi2 - phi1) / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
+    let distance = 2.0 * MEAN_RADIUS * h.sqrt().min(1.0).asin();
+    let bearing = (delta_lambda.sin() * phi2.cos())
+        .atan2(phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * delta_lambda.cos());
+    (distance, normalize_bearing(bearing.to_degrees()))
+}
+
+/// Returns the coefficients A and B of the series of Vincenty.
+fn series_coefficients(cos2_alpha: f64, b: f64) -> (f64, f64) {
+    let u2 = cos2_alpha * (SEMI_MAJOR_AXIS * SEMI_MAJOR_AXIS - b * b) / (b * b);
+    let a = 1.0 + u2 / 16384.0 * (4096.0 + u2 * (-768.0 + u2 * (320.0 - 175.0 * u2)));
+    let b = u2 / 1024.0 * (256.0 + u2 * (-128.0 + u2 * (74.0 - 47.0 * u2)));
+    (a, b)
+}
+
+fn delta_sigma(b: f64, sin_sigma: f64, cos_sigma: f64, cos_2sigma_m: f64) -> f64 {
+    let c2 = cos_2sigma_m * cos_2sigma_m;
+    b * sin_sigma
+        * (cos_2sigma_m
+            + b / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * c2)
+                    - b / 6.0 * cos_2sigma_m * (-3.0 + 4.0 * sin_sigma * sin_sigma) * (-3.0 + 4.0 * c2)))
+}
+
+fn normalize_bearing(degrees: f64) -> f64 {
+    let bearing = degrees.rem_euclid(360.0);
+    //rounding can bring tiny negative angles to 360
+    if bearing == 360.0 {
+        0.0
+    } else {
+        bearing
+    }
+}
+
+fn normalize_longitude(degrees: f64) -> f64 {
+    if (-180.0..=180.0).contains(&degrees) {
+        degrees
+    } else {
+        (degrees + 180.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_vincenty_reference() {
+        //Flinders Peak to Buninyong, the example of Vincenty's paper
+        let flinders = coord!(144.424_867_89, -37.951_033_42);
+        let buninyong = coord!(143.926_495_54, -37.652_821_14);
+        assert!((distance(&flinders, &buninyong) - 54_972.271).abs() < 1e-3);
+        let bearing = 306.0 + 52.0 / 60.0 + 5.37 / 3600.0;
+        assert!((initial_bearing(&flinders, &buninyong) - bearing).abs() < 1e-5);
+        let reached = destination(&flinders, bearing, 54_972.271);
+        assert!((reached.x() - buninyong.x()).abs() < 1e-7);
+        assert!((reached.y() - buninyong.y()).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_equatorial_antipodes() {
+        //references from GeographicLib, over the poles
+        let origin = coord!(0, 0);
+        assert!((distance(&origin, &coord!(180, 0)) - 20_003_931.458_6).abs() < 1e-3);
+        assert_eq!(initial_bearing(&origin, &coord!(180, 0)), 0.0);
+        assert!((distance(&origin, &coord!(179.9, 0)) - 20_003_008.421_5).abs() < 1e-3);
+        assert!((initial_bearing(&origin, &coord!(179.9, 0)) - 9.545_673).abs() < 1e-5);
+        assert!((initial_bearing(&origin, &coord!(-179.9, 0)) - (360.0 - 9.545_673)).abs() < 1e-5);
+        assert!((distance(&coord!(10, 0), &coord!(-170.5, 0)) - 19_980_861.908_9).abs() < 1e-3);
+        //closer points stay on the equator
+        assert!((distance(&origin, &coord!(179, 0)) - 19_926_188.852).abs() < 1e-3);
+        assert_eq!(initial_bearing(&origin, &coord!(179, 0)), 90.0);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let from = coord!(-70.5, 42.25);
+        for (bearing, meters) in [(0.0, 1e6), (45.0, 5e6), (135.0, 1.2e7), (271.5, 250.0), (180.0, 1.5e7)] {
+            let to = destination(&from, bearing, meters);
+            assert!((distance(&from, &to) - meters).abs() < 1e-3, "{} {}", bearing, meters);
+            assert!((initial_bearing(&from, &to) - bearing).abs() < 1e-7, "{} {}", bearing, meters);
+        }
+        //nearly antipodal points fall back to the sphere
+        let d = distance(&coord!(0, 0), &coord!(179.7, 0.5));
+        assert!((d - std::f64::consts::PI * MEAN_RADIUS).abs() < 1e5);
+    }
+
+    #[test]
+    fn test_densify_westwards_over_the_antimeridian() {
+        let line = vec![coord!(-175, -10, 0), coord!(175, -10, 100), coord!(175.001, -10, 100)];
+        let parts = densify(&line, 50_000.0);
+        assert_eq!(parts.len(), 2);
+        assert_eq!((parts[0].last().unwrap().x(), parts[1][0].x()), (-180.0, 180.0));
+        assert_eq!(parts[0].last().unwrap(), &parts[1][0].set_x(-180.0));
+        //the cut is halfway, where the z is interpolated too
+        assert!((parts[1][0].z() - 50.0).abs() < 1.0);
+        for w in parts.iter().flat_map(|part| part.windows(2)) {
+            assert!(distance(&w[0], &w[1]) <= 50_000.0 + 1e-6);
+        }
+        //the last segment is short and is kept as it is
+        assert_eq!(&parts[1][parts[1].len() - 2..], &line[1..]);
+        assert!(densify(&[], 1.0).is_empty());
+    }
+}
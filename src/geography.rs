@@ -0,0 +1,373 @@
+//! Geometries of longitudes and latitudes measured on the WGS84 ellipsoid, as the geography type
+//! of PostGIS, so that distances, lengths and areas come out in meters without choosing the
+//! geodesic variant of every operation.
+
+use crate::coordinate::Coordinate;
+use crate::crs::{self, Crs};
+use crate::error::Error;
+use crate::geodesic::{self, FLATTENING, MEAN_RADIUS, SEMI_MAJOR_AXIS};
+use crate::geometry::Geometry;
+use crate::operation::buffer::directional_buffer;
+use crate::operation::edit::{EditCoordinates, GeometryEditor};
+
+/// A geometry whose coordinates are longitudes (x) and latitudes (y) in degrees, measured on the
+/// WGS84 ellipsoid. Distances and lengths are in meters and areas in square meters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Geography {
+    geometry: Geometry,
+}
+
+impl Geography {
+    /// Creates a geography from a geometry, or returns an error if any of its coordinates is not a
+    /// longitude and latitude.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::geography::Geography;
+    ///
+    /// assert!(Geography::new(Geometry::Point { coordinates: coord!(-3.7, 40.4) }).is_ok());
+    /// assert!(Geography::new(Geometry::Point { coordinates: coord!(440000, 4474000) }).is_err());
+    /// ```
+    pub fn new(geometry: Geometry) -> Result<Geography, Error> {
+        crs::transform_geometry(&geometry, Crs::Wgs84, Crs::Wgs84)?;
+        Ok(Geography { geometry })
+    }
+
+    /// Returns the geometry of the geography.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Returns the geometry of the geography, consuming it.
+    pub fn into_geometry(self) -> Geometry {
+        self.geometry
+    }
+
+    /// Returns the length in meters of the lines and rings of the geography, following the
+    /// shortest path on the ellipsoid between their vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::geography::Geography;
+    ///
+    /// let meridian = Geography::new(Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 1)] }).unwrap();
+    /// assert!((meridian.length() - 110_574.389).abs() < 1e-3);
+    /// ```
+    pub fn length(&self) -> f64 {
+        let (_, segments) = facets(&self.geometry);
+        segments.iter().map(|(a, b)| geodesic::distance(a, b)).sum()
+    }
+
+    /// Returns the area in square meters of the polygons of the geography, whose edges are taken
+    /// as great circles of the authalic sphere, the sphere with the surface of the ellipsoid,
+    /// after mapping the latitudes to it so that areas are kept. Every polygon is measured as the
+    /// smaller of the two areas its shell divides the earth into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::geography::Geography;
+    ///
+    /// //a cell of one degree at the equator
+    /// let cell = Geography::new(Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1), coord!(0, 0)
+    /// ]] }).unwrap();
+    /// assert!((cell.area() / 12_308_778_361.0 - 1.0).abs() < 1e-4);
+    /// ```
+    pub fn area(&self) -> f64 {
+        self.geometry
+            .polygons()
+            .map(|rings| {
+                let mut areas = rings.iter().map(|ring| ring_area(ring));
+                let shell = areas.next().unwrap_or(0.0);
+                (shell - areas.sum::<f64>()).max(0.0)
+            })
+            .sum()
+    }
+
+    /// Returns the shortest distance in meters between the geography and another one on the
+    /// ellipsoid, or 0 if any of them is empty. Geographies that intersect in the plane of their
+    /// longitudes and latitudes are at distance 0, so this is exact for points and approximate
+    /// for long segments crossing each other, whose geodesics bend away from the straight lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other geography.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::geography::Geography;
+    ///
+    /// let madrid = Geography::new(Geometry::Point { coordinates: coord!(-3.7038, 40.4168) }).unwrap();
+    /// let paris = Geography::new(Geometry::Point { coordinates: coord!(2.3522, 48.8566) }).unwrap();
+    /// assert!((madrid.distance(&paris) / 1000.0 - 1053.0).abs() < 0.1);
+    ///
+    /// //the equator is the closest to a point straight north of it
+    /// let equator = Geography::new(Geometry::LineString { coordinates: vec![coord!(-10, 0), coord!(10, 0)] }).unwrap();
+    /// let north = Geography::new(Geometry::Point { coordinates: coord!(5, 1) }).unwrap();
+    /// assert!((north.distance(&equator) - 110_574.389).abs() < 1e-3);
+    /// ```
+    pub fn distance(&self, other: &Geography) -> f64 {
+        if self.geometry.coords_count() == 0 || other.geometry.coords_count() == 0 {
+            return 0.0;
+        }
+        if self.geometry.distance(&other.geometry) == 0.0 {
+            return 0.0;
+        }
+        let (points_a, segments_a) = facets(&self.geometry);
+        let (points_b, segments_b) = facets(&other.geometry);
+        //the closest points of two geodesics that do not cross include an end of one of them
+        let directed = |points: &[&Coordinate], segments: &[(&Coordinate, &Coordinate)], to_points: &[&Coordinate], to_segments: &[(&Coordinate, &Coordinate)]| {
+            points
+                .iter()
+                .copied()
+                .chain(segments.iter().flat_map(|&(a, b)| [a, b]))
+                .flat_map(|p| {
+                    let to_points = to_points.iter().map(move |q| geodesic::distance(p, q));
+                    let to_segments = to_segments.iter().map(move |(a, b)| segment_distance(p, a, b));
+                    to_points.chain(to_segments)
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+        directed(&points_a, &segments_a, &points_b, &segments_b).min(directed(&points_b, &segments_b, &points_a, &segments_a))
+    }
+
+    /// Returns true if the geographies are within the given distance in meters of each other.
+    /// See [`Geography::distance`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::geography::Geography;
+    ///
+    /// let a = Geography::new(Geometry::Point { coordinates: coord!(0, 0) }).unwrap();
+    /// let b = Geography::new(Geometry::Point { coordinates: coord!(0.001, 0) }).unwrap();
+    /// assert!(a.is_within_distance(&b, 112.0));
+    /// assert!(!a.is_within_distance(&b, 111.0));
+    /// ```
+    pub fn is_within_distance(&self, other: &Geography, distance: f64) -> bool {
+        self.distance(other) <= distance
+    }
+
+    /// Returns the area within the given distance in meters of the geography, with arcs of 32
+    /// segments for a full turn. The buffer is built in an azimuthal equidistant projection
+    /// centered on the geography, which keeps the distances from its center, so it is accurate
+    /// for geographies spanning up to a few hundred kilometers and grows less precise far from
+    /// the center.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - The distance in meters, finite and not negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::geography::Geography;
+    ///
+    /// let well = Geography::new(Geometry::Point { coordinates: coord!(10, 60) }).unwrap();
+    /// let zone = well.buffer(1000.0).unwrap();
+    /// let area = std::f64::consts::PI * 1000.0 * 1000.0;
+    /// assert!((zone.area() / area - 1.0).abs() < 0.01);
+    /// //at this latitude a kilometer spans twice as many degrees of longitude as of latitude
+    /// let envelope = zone.geometry().envelope();
+    /// assert!((envelope.width() / envelope.height() - 2.0).abs() < 0.01);
+    /// ```
+    pub fn buffer(&self, distance: f64) -> Result<Geography, Error> {
+        let envelope = self.geometry.envelope();
+        if envelope.is_empty() {
+            return Ok(Geography { geometry: Geometry::MultiPolygon { coordinates: vec![] } });
+        }
+        let center = Coordinate::new((envelope.min_x() + envelope.max_x()) / 2.0, (envelope.min_y() + envelope.max_y()) / 2.0, 0.0);
+        let projected = edit(&self.geometry, |c| azimuthal_equidistant(&center, c));
+        let buffered = directional_buffer(&projected, distance, 0.0, 0.0)?;
+        Ok(Geography { geometry: edit(&buffered, |c| inverse_azimuthal_equidistant(&center, c)) })
+    }
+}
+
+impl TryFrom<Geometry> for Geography {
+    type Error = Error;
+
+    fn try_from(geometry: Geometry) -> Result<Geography, Error> {
+        Geography::new(geometry)
+    }
+}
+
+impl From<Geography> for Geometry {
+    fn from(geography: Geography) -> Geometry {
+        geography.geometry
+    }
+}
+
+/// Returns the points and the segments of the lines and rings of a geometry.
+fn facets(geometry: &Geometry) -> (Vec<&Coordinate>, Vec<(&Coordinate, &Coordinate)>) {
+    let mut points = Vec::new();
+    let mut segments = Vec::new();
+    fn add_path<'a>(path: &'a [Coordinate], points: &mut Vec<&'a Coordinate>, segments: &mut Vec<(&'a Coordinate, &'a Coordinate)>) {
+        if path.len() == 1 {
+            points.push(&path[0]);
+        }
+        segments.extend(path.windows(2).map(|w| (&w[0], &w[1])));
+    }
+    let mut stack = vec![geometry];
+    while let Some(geometry) = stack.pop() {
+        match geometry {
+            Geometry::Point { coordinates } => points.push(coordinates),
+            Geometry::MultiPoint { coordinates } => points.extend(coordinates),
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+                add_path(coordinates, &mut points, &mut segments)
+            }
+            Geometry::MultiLineString { coordinates } => {
+                coordinates.iter().for_each(|line| add_path(line, &mut points, &mut segments))
+            }
+            Geometry::Polygon { coordinates } => {
+                coordinates.iter().for_each(|ring| add_path(ring, &mut points, &mut segments))
+            }
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => coordinates
+                .iter()
+                .flatten()
+                .for_each(|ring| add_path(ring, &mut points, &mut segments)),
+            Geometry::Tin { coordinates } => {
+                coordinates.iter().for_each(|ring| add_path(ring, &mut points, &mut segments))
+            }
+            Geometry::GeometryCollection { geometries } => stack.extend(geometries.iter().rev()),
+        }
+    }
+    (points, segments)
+}
+
+/// Returns the distance on the ellipsoid from a point to the geodesic between two others, found
+/// by a golden section search along the geodesic.
+fn segment_distance(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    let length = geodesic::distance(a, b);
+    if length == 0.0 {
+        return geodesic::distance(p, a);
+    }
+    let bearing = geodesic::initial_bearing(a, b);
+    let at = |fraction: f64| geodesic::distance(p, &geodesic::destination(a, bearing, fraction * length));
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = (0.0, 1.0);
+    //a millimeter along the longest geodesics
+    while (high - low) * length > 1e-3 {
+        let (left, right) = (high - ratio * (high - low), low + ratio * (high - low));
+        if at(left) < at(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+    at((low + high) / 2.0).min(geodesic::distance(p, a)).min(geodesic::distance(p, b))
+}
+
+/// Returns the area of a ring on the authalic sphere, from the spherical excess of the triangles
+/// between every edge and the north pole.
+fn ring_area(ring: &[Coordinate]) -> f64 {
+    let excess: f64 = ring
+        .windows(2)
+        .map(|w| {
+            let delta = (w[1].x() - w[0].x() + 180.0).rem_euclid(360.0) - 180.0;
+            let t1 = (authalic_latitude(w[0].y()) / 2.0).tan();
+            let t2 = (authalic_latitude(w[1].y()) / 2.0).tan();
+            2.0 * ((delta.to_radians() / 2.0).tan() * (t1 + t2)).atan2(1.0 + t1 * t2)
+        })
+        .sum();
+    let radius_squared = SEMI_MAJOR_AXIS * SEMI_MAJOR_AXIS * authalic_q(1.0) / 2.0;
+    let area = excess.abs() * radius_squared;
+    //the ring also bounds the rest of the earth
+    area.min(4.0 * std::f64::consts::PI * radius_squared - area)
+}
+
+/// Returns the authalic latitude, in radians, of a latitude in degrees.
+fn authalic_latitude(latitude: f64) -> f64 {
+    (authalic_q(latitude.to_radians().sin()) / authalic_q(1.0)).clamp(-1.0, 1.0).asin()
+}
+
+/// Returns the q function of the authalic latitude for the sine of a latitude.
+fn authalic_q(sin: f64) -> f64 {
+    let e2 = FLATTENING * (2.0 - FLATTENING);
+    let e = e2.sqrt();
+    (1.0 - e2) * (sin / (1.0 - e2 * sin * sin) - ((1.0 - e * sin) / (1.0 + e * sin)).ln() / (2.0 * e))
+}
+
+/// Returns the geometry with every coordinate mapped by the function.
+fn edit<F: Fn(&Coordinate) -> Coordinate>(geometry: &Geometry, f: F) -> Geometry {
+    let mut map = EditCoordinates(|coordinates: &[Coordinate], _| coordinates.iter().map(&f).collect());
+    GeometryEditor::new().edit(geometry, &mut map).unwrap_or_else(|| geometry.clone())
+}
+
+/// Returns the position of a longitude and latitude in meters in the spherical azimuthal
+/// equidistant projection centered on another one.
+fn azimuthal_equidistant(center: &Coordinate, c: &Coordinate) -> Coordinate {
+    let (phi0, phi) = (center.y().to_radians(), c.y().to_radians());
+    let lambda = (c.x() - center.x()).to_radians();
+    let cos_c = (phi0.sin() * phi.sin() + phi0.cos() * phi.cos() * lambda.cos()).clamp(-1.0, 1.0);
+    let angle = cos_c.acos();
+    let k = if angle == 0.0 { 1.0 } else { angle / angle.sin() };
+    let x = k * phi.cos() * lambda.sin();
+    let y = k * (phi0.cos() * phi.sin() - phi0.sin() * phi.cos() * lambda.cos());
+    Coordinate::new(MEAN_RADIUS * x, MEAN_RADIUS * y, c.z())
+}
+
+/// Returns the longitude and latitude of a position in meters in the spherical azimuthal
+/// equidistant projection centered on a longitude and latitude.
+fn inverse_azimuthal_equidistant(center: &Coordinate, c: &Coordinate) -> Coordinate {
+    let (x, y) = (c.x() / MEAN_RADIUS, c.y() / MEAN_RADIUS);
+    let angle = x.hypot(y);
+    if angle == 0.0 {
+        return Coordinate::new(center.x(), center.y(), c.z());
+    }
+    let phi0 = center.y().to_radians();
+    let (sin_c, cos_c) = angle.sin_cos();
+    let phi = (cos_c * phi0.sin() + y * sin_c * phi0.cos() / angle).clamp(-1.0, 1.0).asin();
+    let lambda = (x * sin_c).atan2(angle * phi0.cos() * cos_c - y * phi0.sin() * sin_c);
+    let longitude = (center.x() + lambda.to_degrees() + 180.0).rem_euclid(360.0) - 180.0;
+    Coordinate::new(longitude, phi.to_degrees(), c.z())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_polygons_with_holes_and_projection_round_trip() {
+        let square = |x: f64, y: f64, size: f64| vec![coord!(x, y), coord!(x + size, y), coord!(x + size, y + size), coord!(x, y + size), coord!(x, y)];
+        let cell = Geography::new(Geometry::Polygon { coordinates: vec![square(0.0, 0.0, 1.0)] }).unwrap();
+        let framed = Geography::new(Geometry::Polygon { coordinates: vec![square(0.0, 0.0, 1.0), square(0.25, 0.25, 0.5)] }).unwrap();
+        let hole = Geography::new(Geometry::Polygon { coordinates: vec![square(0.25, 0.25, 0.5)] }).unwrap();
+        assert!((framed.area() - (cell.area() - hole.area())).abs() < 1e-3);
+        //clockwise rings have the same area
+        let reversed = Geography::new(Geometry::Polygon { coordinates: vec![square(0.0, 0.0, 1.0).into_iter().rev().collect()] }).unwrap();
+        assert!((reversed.area() - cell.area()).abs() < 1e-3);
+
+        let center = coord!(-70, -33);
+        for c in [coord!(-70, -33), coord!(-69.5, -33.2), coord!(-71, -32)] {
+            let back = inverse_azimuthal_equidistant(&center, &azimuthal_equidistant(&center, &c));
+            assert!(back.equals_2d_with_tolerance(&c, 1e-9));
+        }
+        //the distance from the center is kept
+        let projected = azimuthal_equidistant(&center, &coord!(-69.5, -33.2));
+        let spherical = projected.x().hypot(projected.y());
+        assert!((spherical / geodesic::distance(&center, &coord!(-69.5, -33.2)) - 1.0).abs() < 0.005);
+    }
+}
@@ -1,5 +1,49 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::algorithm::line_intersection::{intersection, point_on_segment, sweep_segment_pairs, SegmentIntersection, ZInterpolation};
+use crate::algorithm::alpha_shape::alpha_shape;
+use crate::algorithm::area;
+use crate::algorithm::calipers;
+use crate::algorithm::centroid;
+use crate::algorithm::convex_decomposition;
+use crate::algorithm::convex_hull::convex_hull_of;
+use crate::algorithm::label;
+use crate::algorithm::plane::{self, Plane};
+use crate::algorithm::point_in_polygon::{locate_in_polygon, locate_in_ring, Location};
+use crate::algorithm::relate::{self, IntersectionMatrix};
+use crate::algorithm::skeleton;
 use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::geodesic::{self, Mode};
+use crate::io::wkb::HexCase;
+use crate::io::{geojson, wkb, wkt};
+use crate::operation::affine::AffineTransform;
+use crate::operation::buffer;
+use crate::operation::clean;
+use crate::operation::distance::{self, DistanceOp};
+use crate::operation::extract;
+use crate::operation::extrude;
+use crate::operation::fishnet::{self, CellShare};
+use crate::operation::line_merge;
+use crate::operation::overlay::{overlay, self_union, unary_union, OverlayOp};
+use crate::operation::points;
+use crate::operation::regularize;
+use crate::operation::sample;
+use crate::operation::simplify;
+use crate::operation::snap;
+use crate::operation::valid;
+use crate::shapes;
+
+pub mod builder;
+pub mod edit_session;
+pub mod generalized;
+pub mod line_segment;
+pub mod pool;
+pub mod prepared;
 
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub enum Geometry {
     Point { coordinates: Coordinate },
     LineString { coordinates: Vec<Coordinate> },
@@ -9,92 +53,2360 @@ pub enum Geometry {
     MultiLineString { coordinates: Vec<Vec<Coordinate>> },
     MultiPolygon { coordinates: Vec<Vec<Vec<Coordinate>>> },
     GeometryCollection { geometries: Vec<Geometry> },    
+    /// A 3D surface made of polygonal patches sharing their edges.
+    PolyhedralSurface { coordinates: Vec<Vec<Vec<Coordinate>>> },
+    /// A triangulated irregular network. Every triangle is given as its closed ring of four coordinates.
+    Tin { coordinates: Vec<Vec<Coordinate>> },
 }
 
 //set of possible geometries
 impl Geometry {
     //predicates
 
-    // / Returns true if the geometry is a simple geometry
-    // / A simple geometry is one that has no anomalous geometric points, such as self intersection or self tangency.
-    // /
-    // / # Examples
-    // / ```
-    // / use geoms::geometry::Geometry;
-    // / use geoms::coordinate::Coordinate;
-    // / use geoms::coord;
-    // / 
-    // / let point = Geometry::Point { coordinates: coord!(0, 0) };
-    // / assert!(point.is_simple());
-    // / 
-    // / //no self intersections
-    // / let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(2, 2)] };
-    // / assert!(line.is_simple());
-    // / 
-    // / //no self intersections, closed line
-    // / let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(0, 0)] };
-    // / assert!(line.is_simple());
-    // / 
-    // / //self intersection at the end
-    // / let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(1, 1)] };
-    // / assert!(!line.is_simple());
-    // / 
-    // / //self intersection at the beginning
-    // / let line = Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(1, 1), coord!(0, 0)] };
-    // / assert!(!line.is_simple());
-    // / 
-    // / ```
+    /// Returns true if the geometry is a simple geometry
+    /// A simple geometry is one that has no anomalous geometric points, such as self intersection or self tangency.
+    /// Lines must not have repeated vertices nor cross or touch themselves, except at the endpoints of closed lines.
+    /// The lines of a multi line string can only touch each other at their endpoints, and polygons are simple
+    /// if their rings are simple.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    /// 
+    /// let point = Geometry::Point { coordinates: coord!(0, 0) };
+    /// assert!(point.is_simple());
+    /// 
+    /// //no self intersections
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(2, 2)] };
+    /// assert!(line.is_simple());
+    /// 
+    /// //no self intersections, closed line
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(0, 0)] };
+    /// assert!(line.is_simple());
+    /// 
+    /// //self intersection at the end
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(1, 1)] };
+    /// assert!(!line.is_simple());
+    /// 
+    /// //self intersection at the beginning
+    /// let line = Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(1, 1), coord!(0, 0)] };
+    /// assert!(!line.is_simple());
+    ///
+    /// //crossing itself
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 2), coord!(2, 0), coord!(0, 2)] };
+    /// assert!(!line.is_simple());
+    /// ```
     pub fn is_simple(&self) -> bool {
-        unimplemented!()
-        //the code below only looks for duplicated vertices, it does not check for self intersections :(
-        // //helper function that checks if a set of coordinates are simple
-        // fn is_simple_coordinates(coordinates: &Vec<Coordinate>) -> bool {
-        //     let mut coords = coordinates.iter();
-        //     let initial = coords.next().unwrap();
-        //     let mut set: HashSet<&Coordinate> = HashSet::new();
-        //     let mut initial_state = true;
-        //     let mut state = true;
-
-        //     //check if it is contained in the set, if it is, state is false
-        //     loop {
-        //         match coords.next() {
-        //             Some(coordinate) => {
-        //                 //if the previous state was false, and it is not the end of the loop, return false
-        //                 if !state || !initial_state { 
-        //                     break false;
-        //                 }
-        //                 //check against the initial coordinate
-        //                 else if initial == coordinate {
-        //                     initial_state = false;
-        //                 }                       
-        //                 //add the coordinate to the set,if it is already in the set, state is false
-        //                 else if !set.insert(coordinate) {
-        //                     state = false;
-        //                 }
-        //             },
-        //             //if it reaches the end, and state is false, check if the first and last coordinates are the same
-        //             None => {
-        //                 if !state {
-        //                     break false;
-        //                 } else {
-        //                     break true;
-        //                 }
-        //             }           
-        //         }
-        //     }
-
-        // }
-
-        // match self {
-        //     //points are always simple
-        //     Geometry::Point { .. } => true,
-        //     //lines are simple if they have no self intersections. The only duplicate coordinates are the first and last.
-        //     Geometry::LineString { coordinates } => is_simple_coordinates(&coordinates),
-        //     //Meaning, 
-        //     _ => false,
-        // }
+        match self {
+            //points are always simple
+            Geometry::Point { .. } => true,
+            //multi points are simple if no point is repeated
+            Geometry::MultiPoint { coordinates } => {
+                let mut set: HashSet<(u64, u64)> = HashSet::new();
+                coordinates.iter().all(|c| set.insert((c.x().to_bits(), c.y().to_bits())))
+            }
+            Geometry::LineString { coordinates } => are_simple_paths(&[coordinates]),
+            //rings must also be closed
+            Geometry::LinearRing { coordinates } => {
+                is_closed(coordinates) && are_simple_paths(&[coordinates])
+            }
+            Geometry::Polygon { coordinates } => {
+                coordinates.iter().all(|ring| are_simple_paths(&[ring]))
+            }
+            Geometry::MultiLineString { coordinates } => {
+                let paths: Vec<&Vec<Coordinate>> = coordinates.iter().collect();
+                are_simple_paths(&paths)
+            }
+            Geometry::MultiPolygon { coordinates } => coordinates
+                .iter()
+                .all(|polygon| polygon.iter().all(|ring| are_simple_paths(&[ring]))),
+            Geometry::GeometryCollection { geometries } => {
+                geometries.iter().all(|geometry| geometry.is_simple())
+            }
+            //the patches of 3D surfaces are not planar projections, their topology is checked by is_valid
+            Geometry::PolyhedralSurface { .. } | Geometry::Tin { .. } => true,
+        }
+    }
+
+    /// Returns true if the geometry passes the basic validity checks described in
+    /// [`crate::operation::valid::is_valid`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)] };
+    /// assert!(ring.is_valid());
+    ///
+    /// //not closed
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1)] };
+    /// assert!(!ring.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        valid::is_valid(self)
+    }
+
+    /// Returns every problem making the geometry invalid, with its location. See
+    /// [`crate::operation::valid::validity_errors`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    /// use geoms::operation::valid::ValidityErrorKind;
+    ///
+    /// let lines = Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 0), coord!(0, 0)], vec![coord!(1, 1)]] };
+    /// let errors = lines.validity_errors();
+    /// assert_eq!(errors.len(), 2);
+    /// assert!(errors.iter().all(|e| e.kind == ValidityErrorKind::TooFewPoints));
+    /// assert_eq!(errors[1].to_string(), "too few points at (1 1)");
+    /// ```
+    pub fn validity_errors(&self) -> Vec<valid::ValidityError> {
+        valid::validity_errors(self)
+    }
+    //measuring methods
+
+    /// Returns the planar area of the geometry. The patches of polyhedral surfaces and TINs are
+    /// measured through their projection on the plane.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(3, 0), coord!(3, 3), coord!(0, 3), coord!(0, 0)]] };
+    /// assert_eq!(polygon.area(), 9.0);
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(3, 0)] };
+    /// assert_eq!(line.area(), 0.0);
+    /// ```
+    pub fn area(&self) -> f64 {
+        self.area_with(area::polygon_area)
+    }
+
+    /// Returns the area of the geometry with every polygon, patch or triangle measured in its own plane.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //the floor and one wall of a unit cube
+    /// let surface = Geometry::PolyhedralSurface { coordinates: vec![
+    ///     vec![vec![coord!(0, 0, 0), coord!(0, 1, 0), coord!(1, 1, 0), coord!(1, 0, 0), coord!(0, 0, 0)]],
+    ///     vec![vec![coord!(0, 0, 0), coord!(1, 0, 0), coord!(1, 0, 1), coord!(0, 0, 1), coord!(0, 0, 0)]],
+    /// ] };
+    /// assert_eq!(surface.area(), 1.0);
+    /// assert_eq!(surface.area_3d(), 2.0);
+    /// ```
+    pub fn area_3d(&self) -> f64 {
+        self.area_with(area::polygon_area_3d)
+    }
+
+    /// Returns the area of the geometry with every polygon or patch measured in the plane best
+    /// fitting all of its rings, which suits faces that are not exactly flat, such as roofs.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //a roof face rising along y
+    /// let face = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0, 0), coord!(4, 0, 0), coord!(4, 3, 4), coord!(0, 3, 4), coord!(0, 0, 0)]] };
+    /// assert_eq!(face.area(), 12.0);
+    /// assert_eq!(face.area_in_plane(), 20.0);
+    /// ```
+    pub fn area_in_plane(&self) -> f64 {
+        self.area_with(plane::polygon_area_in_plane)
+    }
+
+    /// Returns the plane best fitting all the rings of the polygons of the geometry, or None if
+    /// they enclose no area. See [`Plane::fit`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let face = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0, 0), coord!(4, 0, 0), coord!(4, 3, 4), coord!(0, 3, 4), coord!(0, 0, 0)]] };
+    /// assert_eq!(face.best_fit_plane().unwrap().normal, [0.0, -0.8, 0.6]);
+    /// assert_eq!(Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] }.best_fit_plane(), None);
+    /// ```
+    pub fn best_fit_plane(&self) -> Option<Plane> {
+        Plane::fit(self.polygons().flatten())
+    }
+
+    /// Returns true if every polygon, patch or triangle of the geometry lies within the tolerance
+    /// of the plane best fitting its rings. Geometries without polygons are planar.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let warped = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0, 0), coord!(4, 0, 0), coord!(4, 4, 0.1), coord!(0, 4, 0), coord!(0, 0, 0)]] };
+    /// assert!(warped.is_planar(0.05));
+    /// assert!(!warped.is_planar(0.01));
+    /// ```
+    pub fn is_planar(&self, tolerance: f64) -> bool {
+        match self {
+            Geometry::Polygon { coordinates } => plane::is_planar(coordinates, tolerance),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter().all(|polygon| plane::is_planar(polygon, tolerance))
+            }
+            Geometry::GeometryCollection { geometries } => geometries.iter().all(|geometry| geometry.is_planar(tolerance)),
+            _ => true,
+        }
+    }
+
+    /// Returns the cells of a grid of square cells anchored at the origin covered by the polygons
+    /// of the geometry, with the fraction of their area covered. See
+    /// [`crate::operation::fishnet::grid_shares`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0.5, 0.5), coord!(1.5, 0.5), coord!(1.5, 1.5), coord!(0.5, 1.5), coord!(0.5, 0.5)]] };
+    /// let shares = square.grid_shares(1.0);
+    /// assert_eq!(shares.len(), 4);
+    /// assert!(shares.iter().all(|cell| cell.share == 0.25));
+    /// ```
+    pub fn grid_shares(&self, cell_size: f64) -> Vec<CellShare> {
+        fishnet::grid_shares(self, cell_size)
+    }
+
+    /// Returns a multi point of points spread uniformly at random over the polygons of the
+    /// geometry, drawn with numbers from 0 included to 1 excluded. See
+    /// [`crate::operation::sample::sample_points`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::sample::SplitMix64;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1), coord!(0, 0)]] };
+    /// let mut rng = SplitMix64::new(3);
+    /// let points = square.sample_points(10, &mut || rng.next_f64());
+    /// assert_eq!(points.coords_count(), 10);
+    /// assert!(points.coords_iter().all(|p| square.contains_point(p)));
+    /// ```
+    pub fn sample_points<R: FnMut() -> f64>(&self, n: usize, rng: &mut R) -> Geometry {
+        Geometry::MultiPoint { coordinates: sample::sample_points(self, n, rng) }
+    }
+
+    /// Returns a multi point of points spread uniformly at random along the lines of the geometry
+    /// and the rings of its polygons, drawn with numbers from 0 included to 1 excluded. See
+    /// [`crate::operation::sample::sample_points_on_line`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::sample::SplitMix64;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 0)] };
+    /// let mut rng = SplitMix64::new(3);
+    /// let points = line.sample_points_on_line(5, &mut || rng.next_f64());
+    /// assert!(points.coords_iter().all(|p| p.y() == 0.0 && (0.0..4.0).contains(&p.x())));
+    /// ```
+    pub fn sample_points_on_line<R: FnMut() -> f64>(&self, n: usize, rng: &mut R) -> Geometry {
+        Geometry::MultiPoint { coordinates: sample::sample_points_on_line(self, n, rng) }
+    }
+
+    /// Returns the number of holes of the polygons of the geometry.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let frame = Geometry::Polygon { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+    ///     vec![coord!(2, 2), coord!(2, 4), coord!(4, 4), coord!(4, 2), coord!(2, 2)],
+    ///     vec![coord!(6, 6), coord!(6, 7), coord!(7, 7), coord!(7, 6), coord!(6, 6)],
+    /// ] };
+    /// assert_eq!(frame.hole_count(), 2);
+    /// ```
+    pub fn hole_count(&self) -> usize {
+        self.polygons().map(|rings| rings.len().saturating_sub(1)).sum()
+    }
+
+    /// Returns the planar area of the holes of the polygons of the geometry.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let frame = Geometry::Polygon { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+    ///     vec![coord!(2, 2), coord!(2, 4), coord!(4, 4), coord!(4, 2), coord!(2, 2)],
+    ///     vec![coord!(6, 6), coord!(6, 7), coord!(7, 7), coord!(7, 6), coord!(6, 6)],
+    /// ] };
+    /// assert_eq!(frame.holes_area(), 5.0);
+    /// assert_eq!(frame.area(), frame.filled_area() - frame.holes_area());
+    /// ```
+    pub fn holes_area(&self) -> f64 {
+        self.polygons().flat_map(|rings| rings.iter().skip(1)).map(|ring| area::ring_area(ring)).sum()
+    }
+
+    /// Returns the planar area of the polygons of the geometry with their holes filled, the area
+    /// enclosed by their shells.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let frame = Geometry::Polygon { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+    ///     vec![coord!(2, 2), coord!(2, 4), coord!(4, 4), coord!(4, 2), coord!(2, 2)],
+    ///     vec![coord!(6, 6), coord!(6, 7), coord!(7, 7), coord!(7, 6), coord!(6, 6)],
+    /// ] };
+    /// assert_eq!(frame.filled_area(), 100.0);
+    /// ```
+    pub fn filled_area(&self) -> f64 {
+        self.polygons().filter_map(|rings| rings.first()).map(|ring| area::ring_area(ring)).sum()
+    }
+
+    fn area_with(&self, polygon_area: fn(&[Vec<Coordinate>]) -> f64) -> f64 {
+        match self {
+            Geometry::Polygon { coordinates } => polygon_area(coordinates),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter().map(|polygon| polygon_area(polygon)).sum()
+            }
+            Geometry::Tin { coordinates } => coordinates
+                .iter()
+                .map(|triangle| polygon_area(std::slice::from_ref(triangle)))
+                .sum(),
+            Geometry::GeometryCollection { geometries } => {
+                geometries.iter().map(|geometry| geometry.area_with(polygon_area)).sum()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the planar length of the geometry. Polygons return the length of their rings,
+    /// while points have no length.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0, 0), coord!(3, 4, 12), coord!(3, 10, 12)] };
+    /// assert_eq!(line.length(), 11.0);
+    /// ```
+    pub fn length(&self) -> f64 {
+        distance::length(self, false)
+    }
+
+    /// Returns the length of the geometry taking the z ordinate into account.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0, 0), coord!(3, 4, 12), coord!(3, 10, 12)] };
+    /// assert_eq!(line.length_3d(), 19.0);
+    /// ```
+    pub fn length_3d(&self) -> f64 {
+        distance::length(self, true)
+    }
+
+    /// Returns the minimum planar distance between two geometries.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(0, 3, 10) };
+    /// let line = Geometry::LineString { coordinates: vec![coord!(-5, 0, 0), coord!(5, 0, 0)] };
+    /// assert_eq!(point.distance(&line), 3.0);
+    /// ```
+    pub fn distance(&self, other: &Geometry) -> f64 {
+        DistanceOp::new(self, other).distance()
+    }
+
+    /// Returns the minimum distance between two geometries taking the z ordinate into account.
+    /// Polygons are measured through their rings.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(0, 3, 4) };
+    /// let line = Geometry::LineString { coordinates: vec![coord!(-5, 0, 0), coord!(5, 0, 0)] };
+    /// assert_eq!(point.distance_3d(&line), 5.0);
+    /// ```
+    pub fn distance_3d(&self, other: &Geometry) -> f64 {
+        DistanceOp::new(self, other).set_3d(true).distance()
+    }
+
+    /// Returns true if the geometries are within the given planar distance of each other.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(0, 3) };
+    /// let line = Geometry::LineString { coordinates: vec![coord!(-5, 0), coord!(5, 0)] };
+    /// assert!(point.is_within_distance(&line, 3.0));
+    /// assert!(!point.is_within_distance(&line, 2.0));
+    /// ```
+    pub fn is_within_distance(&self, other: &Geometry, distance: f64) -> bool {
+        DistanceOp::new(self, other).is_within_distance(distance)
+    }
+
+    /// Returns the DE-9IM intersection matrix of the geometry with another one. See
+    /// [`crate::algorithm::relate::relate`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(0, 0) };
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 0)] };
+    /// assert_eq!(point.relate(&line).to_string(), "F0FFFF102");
+    /// ```
+    pub fn relate(&self, other: &Geometry) -> IntersectionMatrix {
+        relate::relate(self, other)
+    }
+
+    /// Returns the discrete Hausdorff distance between two geometries, the largest distance from
+    /// a vertex of any of them to the other. See [`crate::operation::distance::hausdorff_distance`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 0)] };
+    /// let shifted = Geometry::LineString { coordinates: vec![coord!(0, 1), coord!(4, 2)] };
+    /// assert_eq!(line.hausdorff_distance(&shifted), 2.0);
+    /// ```
+    pub fn hausdorff_distance(&self, other: &Geometry) -> f64 {
+        distance::hausdorff_distance(self, other)
+    }
+
+    /// Returns the smallest planar distance between a vertex of the geometry and another vertex
+    /// or a segment not ending at it, or infinity if there are none. Snapping by less than the
+    /// clearance keeps the geometry valid. See [`crate::operation::distance::minimum_clearance`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let notched = Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(6, 10), coord!(5, 0.5), coord!(4, 10), coord!(0, 10), coord!(0, 0)
+    /// ]] };
+    /// assert_eq!(notched.minimum_clearance(), 0.5);
+    /// ```
+    pub fn minimum_clearance(&self) -> f64 {
+        distance::minimum_clearance(self)
+    }
+
+    /// Returns the line from a vertex of the geometry to the closest point of another vertex or
+    /// segment whose length is the minimum clearance, or an empty line string if there is none.
+    /// See [`crate::operation::distance::minimum_clearance_line`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let notched = Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(6, 10), coord!(5, 0.5), coord!(4, 10), coord!(0, 10), coord!(0, 0)
+    /// ]] };
+    /// assert_eq!(notched.minimum_clearance_line().length(), 0.5);
+    /// ```
+    pub fn minimum_clearance_line(&self) -> Geometry {
+        distance::minimum_clearance_line(self)
+    }
+
+    /// Returns the bearing from a point to another one in degrees clockwise from north, in the
+    /// range [0, 360), or None if any of the geometries is not a point. In planar mode north is
+    /// the direction of the y axis, while in geodesic mode the points are longitudes and latitudes
+    /// and the bearing is the one at the start of the shortest path on the ellipsoid.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The point at the start.
+    /// * `other` - The point at the end.
+    /// * `mode` - Whether to measure in the plane or on the ellipsoid.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geodesic::Mode;
+    /// use geoms::coord;
+    ///
+    /// let a = Geometry::Point { coordinates: coord!(0, 0) };
+    /// let b = Geometry::Point { coordinates: coord!(-1, -1) };
+    /// assert_eq!(a.bearing_to(&b, Mode::Planar), Some(225.0));
+    /// assert!((a.bearing_to(&b, Mode::Geodesic).unwrap() - 225.19).abs() < 0.01);
+    /// ```
+    pub fn bearing_to(&self, other: &Geometry, mode: Mode) -> Option<f64> {
+        let (Geometry::Point { coordinates: from }, Geometry::Point { coordinates: to }) = (self, other) else {
+            return None;
+        };
+        match mode {
+            Mode::Planar => Some((to.x() - from.x()).atan2(to.y() - from.y()).to_degrees().rem_euclid(360.0)),
+            Mode::Geodesic => Some(geodesic::initial_bearing(from, to)),
+        }
+    }
+
+    //accessors
+
+    /// Returns a copy of all the coordinates of the geometry, in order. Use
+    /// [`Geometry::coords_iter`] to visit them without copying.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let lines = Geometry::MultiLineString { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(1, 1)],
+    ///     vec![coord!(2, 2), coord!(3, 3)],
+    /// ] };
+    /// assert_eq!(lines.coordinates(), vec![coord!(0, 0), coord!(1, 1), coord!(2, 2), coord!(3, 3)]);
+    /// ```
+    pub fn coordinates(&self) -> Vec<Coordinate> {
+        let mut coordinates = Vec::with_capacity(self.coords_count());
+        coordinates.extend(self.coords_iter().cloned());
+        coordinates
+    }
+
+    /// Returns an iterator over all the coordinates of the geometry, in order, without copying
+    /// them.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(0, 3), coord!(0, 0)]] };
+    /// let max_x = polygon.coords_iter().map(|c| c.x()).fold(f64::MIN, f64::max);
+    /// assert_eq!(max_x, 4.0);
+    /// ```
+    pub fn coords_iter(&self) -> Box<dyn Iterator<Item = &Coordinate> + '_> {
+        match self {
+            Geometry::Point { coordinates } => Box::new(std::iter::once(coordinates)),
+            Geometry::LineString { coordinates }
+            | Geometry::LinearRing { coordinates }
+            | Geometry::MultiPoint { coordinates } => Box::new(coordinates.iter()),
+            Geometry::Polygon { coordinates }
+            | Geometry::MultiLineString { coordinates }
+            | Geometry::Tin { coordinates } => Box::new(coordinates.iter().flatten()),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                Box::new(coordinates.iter().flatten().flatten())
+            }
+            Geometry::GeometryCollection { geometries } => Box::new(geometries.iter().flat_map(|g| g.coords_iter())),
+        }
+    }
+
+    /// Returns the number of coordinates of the geometry, without visiting them.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let collection = Geometry::GeometryCollection { geometries: vec![
+    ///     Geometry::Point { coordinates: coord!(0, 0) },
+    ///     Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 0), coord!(1, 1)], vec![coord!(2, 2), coord!(3, 3)]] },
+    /// ] };
+    /// assert_eq!(collection.coords_count(), 5);
+    /// ```
+    pub fn coords_count(&self) -> usize {
+        match self {
+            Geometry::Point { .. } => 1,
+            Geometry::LineString { coordinates }
+            | Geometry::LinearRing { coordinates }
+            | Geometry::MultiPoint { coordinates } => coordinates.len(),
+            Geometry::Polygon { coordinates }
+            | Geometry::MultiLineString { coordinates }
+            | Geometry::Tin { coordinates } => coordinates.iter().map(Vec::len).sum(),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter().flatten().map(Vec::len).sum()
+            }
+            Geometry::GeometryCollection { geometries } => geometries.iter().map(Geometry::coords_count).sum(),
+        }
+    }
+
+    /// Returns an estimate of the memory held by the geometry, in bytes: the size of the value
+    /// itself and of the vectors it owns, counted by their capacity rather than their length, so
+    /// over allocated vectors show. The overhead of the allocator is not counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(1, 2) };
+    /// assert_eq!(point.byte_size(), std::mem::size_of::<Geometry>());
+    ///
+    /// let mut coordinates = Vec::with_capacity(100);
+    /// coordinates.extend([coord!(0, 0), coord!(1, 1)]);
+    /// let line = Geometry::LineString { coordinates };
+    /// assert_eq!(line.byte_size(), std::mem::size_of::<Geometry>() + 100 * std::mem::size_of::<Coordinate>());
+    /// ```
+    pub fn byte_size(&self) -> usize {
+        size_of::<Geometry>() + self.heap_size()
+    }
+
+    /// Returns the bytes of the vectors owned by the geometry.
+    fn heap_size(&self) -> usize {
+        let path = |path: &Vec<Coordinate>| path.capacity() * size_of::<Coordinate>();
+        let paths = |paths: &Vec<Vec<Coordinate>>| paths.capacity() * size_of::<Vec<Coordinate>>() + paths.iter().map(path).sum::<usize>();
+        match self {
+            Geometry::Point { .. } => 0,
+            Geometry::LineString { coordinates }
+            | Geometry::LinearRing { coordinates }
+            | Geometry::MultiPoint { coordinates } => path(coordinates),
+            Geometry::Polygon { coordinates }
+            | Geometry::MultiLineString { coordinates }
+            | Geometry::Tin { coordinates } => paths(coordinates),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.capacity() * size_of::<Vec<Vec<Coordinate>>>() + coordinates.iter().map(paths).sum::<usize>()
+            }
+            Geometry::GeometryCollection { geometries } => {
+                geometries.capacity() * size_of::<Geometry>() + geometries.iter().map(Geometry::heap_size).sum::<usize>()
+            }
+        }
+    }
+
+    /// Trims the capacity of every vector of the geometry to its length, releasing the memory
+    /// left over by editing or by readers reserving ahead. See [`Geometry::byte_size`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut ring = Vec::with_capacity(1000);
+    /// ring.extend([coord!(0, 0), coord!(1, 0), coord!(0, 1), coord!(0, 0)]);
+    /// let mut polygon = Geometry::Polygon { coordinates: vec![ring] };
+    /// let before = polygon.byte_size();
+    /// polygon.shrink_to_fit();
+    /// assert!(polygon.byte_size() < before / 100);
+    /// assert_eq!(polygon.coords_count(), 4);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        fn shrink_paths(paths: &mut Vec<Vec<Coordinate>>) {
+            paths.iter_mut().for_each(Vec::shrink_to_fit);
+            paths.shrink_to_fit();
+        }
+        match self {
+            Geometry::Point { .. } => {}
+            Geometry::LineString { coordinates }
+            | Geometry::LinearRing { coordinates }
+            | Geometry::MultiPoint { coordinates } => coordinates.shrink_to_fit(),
+            Geometry::Polygon { coordinates }
+            | Geometry::MultiLineString { coordinates }
+            | Geometry::Tin { coordinates } => shrink_paths(coordinates),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter_mut().for_each(shrink_paths);
+                coordinates.shrink_to_fit();
+            }
+            Geometry::GeometryCollection { geometries } => {
+                geometries.iter_mut().for_each(Geometry::shrink_to_fit);
+                geometries.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Returns the boundary of the geometry. Under the mod 2 rule, the boundary of lines is made
+    /// of the ends shared by an odd number of them, so closed lines have none. The boundary of
+    /// polygons is made of their rings, and the boundary of polyhedral surfaces and TINs of the
+    /// edges of an odd number of their patches, merged into lines. Points have an empty boundary,
+    /// and collections return the boundaries of their members.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let lines = Geometry::MultiLineString { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(1, 0)],
+    ///     vec![coord!(1, 0), coord!(1, 1)],
+    ///     vec![coord!(1, 0), coord!(2, 0)],
+    /// ] };
+    /// assert_eq!(lines.boundary().as_text(), "MULTIPOINT ((0 0), (1 0), (1 1), (2 0))");
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)] };
+    /// assert_eq!(ring.boundary().as_text(), "MULTIPOINT EMPTY");
+    ///
+    /// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)]] };
+    /// assert_eq!(polygon.boundary().as_text(), "LINESTRING (0 0, 1 0, 1 1, 0 0)");
+    /// ```
+    pub fn boundary(&self) -> Geometry {
+        match self {
+            Geometry::Point { .. } | Geometry::MultiPoint { .. } => Geometry::GeometryCollection { geometries: vec![] },
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+                Geometry::MultiPoint { coordinates: odd_ends(std::slice::from_ref(coordinates)) }
+            }
+            Geometry::MultiLineString { coordinates } => Geometry::MultiPoint { coordinates: odd_ends(coordinates) },
+            Geometry::Polygon { coordinates } if coordinates.len() == 1 => {
+                Geometry::LineString { coordinates: coordinates[0].clone() }
+            }
+            Geometry::Polygon { coordinates } => Geometry::MultiLineString { coordinates: coordinates.clone() },
+            Geometry::MultiPolygon { coordinates } => {
+                Geometry::MultiLineString { coordinates: coordinates.iter().flatten().cloned().collect() }
+            }
+            Geometry::GeometryCollection { geometries } => {
+                Geometry::GeometryCollection { geometries: geometries.iter().map(|g| g.boundary()).collect() }
+            }
+            Geometry::PolyhedralSurface { coordinates } => odd_edges(coordinates.iter().flatten()),
+            Geometry::Tin { coordinates } => odd_edges(coordinates.iter()),
+        }
+    }
+
+    /// Returns the components of a multi geometry, polyhedral surface or TIN as single
+    /// geometries, or the members of a collection. Other geometries are their only component.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let lines = Geometry::MultiLineString { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(1, 0)],
+    ///     vec![coord!(2, 0), coord!(3, 0)],
+    /// ] };
+    /// assert_eq!(lines.num_components(), 2);
+    /// assert_eq!(lines.components()[1].as_text(), "LINESTRING (2 0, 3 0)");
+    /// ```
+    pub fn components(&self) -> Vec<Geometry> {
+        match self {
+            Geometry::MultiPoint { coordinates } => {
+                coordinates.iter().map(|c| Geometry::Point { coordinates: c.clone() }).collect()
+            }
+            Geometry::MultiLineString { coordinates } => {
+                coordinates.iter().map(|line| Geometry::LineString { coordinates: line.clone() }).collect()
+            }
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter().map(|polygon| Geometry::Polygon { coordinates: polygon.clone() }).collect()
+            }
+            Geometry::Tin { coordinates } => {
+                coordinates.iter().map(|triangle| Geometry::Polygon { coordinates: vec![triangle.clone()] }).collect()
+            }
+            Geometry::GeometryCollection { geometries } => geometries.clone(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Returns the number of components of the geometry, as returned by
+    /// [`Geometry::components`], without copying them.
+    pub fn num_components(&self) -> usize {
+        match self {
+            Geometry::MultiPoint { coordinates } => coordinates.len(),
+            Geometry::MultiLineString { coordinates } | Geometry::Tin { coordinates } => coordinates.len(),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => coordinates.len(),
+            Geometry::GeometryCollection { geometries } => geometries.len(),
+            _ => 1,
+        }
+    }
+
+    /// Returns the component at the given index, as returned by [`Geometry::components`], or
+    /// None if there are not that many.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let collection = Geometry::GeometryCollection { geometries: vec![
+    ///     Geometry::Point { coordinates: coord!(0, 0) },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] },
+    /// ] };
+    /// assert_eq!(collection.geometry_n(1).unwrap().as_text(), "LINESTRING (0 0, 1 1)");
+    /// assert_eq!(collection.geometry_n(2), None);
+    /// ```
+    pub fn geometry_n(&self, n: usize) -> Option<Geometry> {
+        match self {
+            Geometry::MultiPoint { coordinates } => coordinates.get(n).map(|c| Geometry::Point { coordinates: c.clone() }),
+            Geometry::MultiLineString { coordinates } => {
+                coordinates.get(n).map(|line| Geometry::LineString { coordinates: line.clone() })
+            }
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.get(n).map(|polygon| Geometry::Polygon { coordinates: polygon.clone() })
+            }
+            Geometry::Tin { coordinates } => {
+                coordinates.get(n).map(|triangle| Geometry::Polygon { coordinates: vec![triangle.clone()] })
+            }
+            Geometry::GeometryCollection { geometries } => geometries.get(n).cloned(),
+            _ => (n == 0).then(|| self.clone()),
+        }
+    }
+
+    /// Returns the topological dimension of the geometry: 0 for points, 1 for lines and 2 for
+    /// polygons and surfaces. Collections have the highest dimension of their members, or -1
+    /// when they have none.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let collection = Geometry::GeometryCollection { geometries: vec![
+    ///     Geometry::Point { coordinates: coord!(0, 0) },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] },
+    /// ] };
+    /// assert_eq!(collection.dimension(), 1);
+    /// assert_eq!(Geometry::GeometryCollection { geometries: vec![] }.dimension(), -1);
+    /// ```
+    pub fn dimension(&self) -> i32 {
+        match self {
+            Geometry::Point { .. } | Geometry::MultiPoint { .. } => 0,
+            Geometry::LineString { .. } | Geometry::LinearRing { .. } | Geometry::MultiLineString { .. } => 1,
+            Geometry::Polygon { .. }
+            | Geometry::MultiPolygon { .. }
+            | Geometry::PolyhedralSurface { .. }
+            | Geometry::Tin { .. } => 2,
+            Geometry::GeometryCollection { geometries } => geometries.iter().map(Geometry::dimension).max().unwrap_or(-1),
+        }
+    }
+
+    /// Returns true if all the members of a collection, searching nested collections, have the
+    /// same dimension. Other geometries are always homogeneous.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(0, 0) };
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] };
+    /// let mixed = Geometry::GeometryCollection { geometries: vec![point.clone(), line] };
+    /// assert!(!mixed.is_homogeneous());
+    /// let points = Geometry::GeometryCollection { geometries: vec![point.clone(), Geometry::GeometryCollection { geometries: vec![point] }] };
+    /// assert!(points.is_homogeneous());
+    /// ```
+    pub fn is_homogeneous(&self) -> bool {
+        let mut dimensions = Vec::new();
+        extract::visit(self, &mut |g| dimensions.push(g.dimension()));
+        dimensions.windows(2).all(|w| w[0] == w[1])
+    }
+
+    /// Returns the polygon of the geometry with the largest planar area, the first one on ties,
+    /// or None if the geometry has no polygons.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let polygons = Geometry::MultiPolygon { coordinates: vec![
+    ///     vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)]],
+    ///     vec![vec![coord!(5, 0), coord!(8, 0), coord!(8, 3), coord!(5, 0)]],
+    /// ] };
+    /// assert_eq!(polygons.largest_component().unwrap().area(), 4.5);
+    /// ```
+    pub fn largest_component(&self) -> Option<Geometry> {
+        self.polygons()
+            .map(|polygon| (area::polygon_area(polygon), polygon))
+            .reduce(|largest, candidate| if candidate.0 > largest.0 { candidate } else { largest })
+            .map(|(_, polygon)| Geometry::Polygon { coordinates: polygon.clone() })
+    }
+
+    /// Returns the planar bounding box of the geometry, which is empty for empty geometries.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 3), coord!(2, -1), coord!(1, 1)] };
+    /// assert_eq!(line.envelope(), Envelope::new(0.0, -1.0, 2.0, 3.0));
+    /// ```
+    pub fn envelope(&self) -> Envelope {
+        let of_paths = |paths: &mut dyn Iterator<Item = &Vec<Coordinate>>| {
+            paths.fold(Envelope::empty(), |envelope, path| envelope.merge(&Envelope::of_slice(path)))
+        };
+        match self {
+            Geometry::Point { coordinates } => Envelope::of([coordinates]),
+            Geometry::LineString { coordinates }
+            | Geometry::LinearRing { coordinates }
+            | Geometry::MultiPoint { coordinates } => Envelope::of_slice(coordinates),
+            Geometry::Polygon { coordinates }
+            | Geometry::MultiLineString { coordinates }
+            | Geometry::Tin { coordinates } => of_paths(&mut coordinates.iter()),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                of_paths(&mut coordinates.iter().flatten())
+            }
+            Geometry::GeometryCollection { geometries } => geometries
+                .iter()
+                .fold(Envelope::empty(), |envelope, geometry| envelope.merge(&geometry.envelope())),
+        }
+    }
+
+    /// Returns the box of the geometry, with the range of the z of its coordinates.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::coord;
+    ///
+    /// let path = Geometry::LineString { coordinates: vec![coord!(0, 3, 120), coord!(2, -1, 80), coord!(1, 1, 95)] };
+    /// assert_eq!(path.envelope_3d(), Envelope::new_3d(0.0, -1.0, 80.0, 2.0, 3.0, 120.0));
+    /// ```
+    pub fn envelope_3d(&self) -> Envelope {
+        Envelope::of_3d(self.coords_iter())
+    }
+
+    //ring methods
+
+    /// Returns a linear ring over the coordinates, checking that they are closed and that there
+    /// are at least four of them, unless there are none.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let ring = Geometry::linear_ring(vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)]).unwrap();
+    /// assert_eq!(ring.as_text(), "LINEARRING (0 0, 1 0, 1 1, 0 0)");
+    ///
+    /// let open = Geometry::linear_ring(vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1)]);
+    /// assert_eq!(open.unwrap_err().to_string(), "invalid ring: the first and last coordinates differ");
+    /// ```
+    pub fn linear_ring(coordinates: Vec<Coordinate>) -> Result<Geometry, Error> {
+        if !coordinates.is_empty() {
+            if coordinates.len() < 4 {
+                return Err(Error::InvalidRing(format!("{} coordinates, at least 4 are needed", coordinates.len())));
+            }
+            if !is_closed(&coordinates) {
+                return Err(Error::InvalidRing("the first and last coordinates differ".to_string()));
+            }
+        }
+        Ok(Geometry::LinearRing { coordinates })
+    }
+
+    /// Returns true if the geometry is a line or a ring whose first and last coordinates are
+    /// equal, or a multi line string all of whose lines are. Other geometries are not closed.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(0, 1), coord!(0, 0)] };
+    /// assert!(line.is_closed());
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(0, 1)] };
+    /// assert!(!ring.is_closed());
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        match self {
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => is_closed(coordinates),
+            Geometry::MultiLineString { coordinates } => {
+                !coordinates.is_empty() && coordinates.iter().all(|line| is_closed(line))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the signed planar area enclosed by a ring, positive if it is counter clockwise,
+    /// or None if the geometry is not a ring. See [`crate::algorithm::area::ring_signed_area`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(0, 2), coord!(2, 2), coord!(2, 0), coord!(0, 0)] };
+    /// assert_eq!(ring.signed_area(), Some(-4.0));
+    /// ```
+    pub fn signed_area(&self) -> Option<f64> {
+        match self {
+            Geometry::LinearRing { coordinates } => Some(area::ring_signed_area(coordinates)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the ring is counter clockwise, or None if the geometry is not a ring or
+    /// encloses no area.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 0)] };
+    /// assert_eq!(ring.is_ccw(), Some(true));
+    ///
+    /// let flat = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(2, 0), coord!(0, 0)] };
+    /// assert_eq!(flat.is_ccw(), None);
+    /// ```
+    pub fn is_ccw(&self) -> Option<bool> {
+        self.signed_area().filter(|&area| area != 0.0).map(|area| area > 0.0)
+    }
+
+    /// Returns true if the point lies strictly inside the area enclosed by a ring or covered by
+    /// polygons. Points on the boundary are not contained, and other geometries enclose no area.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)] };
+    /// assert!(ring.contains_point(&coord!(2, 2)));
+    /// assert!(!ring.contains_point(&coord!(4, 2)));
+    /// assert!(!ring.contains_point(&coord!(5, 2)));
+    /// ```
+    pub fn contains_point(&self, point: &Coordinate) -> bool {
+        match self {
+            Geometry::LinearRing { coordinates } => locate_in_ring(point, coordinates) == Location::Interior,
+            _ => self.polygons().any(|polygon| locate_in_polygon(point, polygon) == Location::Interior),
+        }
+    }
+
+    /// Returns the line running along a ring or a line, or None for other geometries.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)] };
+    /// assert_eq!(ring.to_line_string().unwrap().as_text(), "LINESTRING (0 0, 1 0, 1 1, 0 0)");
+    /// ```
+    pub fn to_line_string(&self) -> Option<Geometry> {
+        match self {
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+                Some(Geometry::LineString { coordinates: coordinates.clone() })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the ring running along a closed line or a ring, checked as in
+    /// [`Geometry::linear_ring`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)] };
+    /// assert_eq!(line.to_linear_ring().unwrap().as_text(), "LINEARRING (0 0, 1 0, 1 1, 0 0)");
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(0, 0) };
+    /// assert_eq!(point.to_linear_ring().unwrap_err().to_string(), "invalid ring: cannot be made from a POINT");
+    /// ```
+    pub fn to_linear_ring(&self) -> Result<Geometry, Error> {
+        match self {
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+                Geometry::linear_ring(coordinates.clone())
+            }
+            _ => Err(Error::InvalidRing(format!("cannot be made from a {}", wkt::tag(self)))),
+        }
+    }
+
+    //point methods
+
+    /// Returns a point at the given planar location, with a z value of 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    ///
+    /// let point = Geometry::point(1.0, 2.0);
+    /// assert_eq!(point.as_text(), "POINT (1 2)");
+    /// assert_eq!((point.x(), point.y(), point.z()), (Some(1.0), Some(2.0), Some(0.0)));
+    /// ```
+    pub fn point(x: f64, y: f64) -> Geometry {
+        Geometry::Point { coordinates: Coordinate::new(x, y, 0.0) }
+    }
+
+    /// Returns a point at the given location in 3D.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    ///
+    /// assert_eq!(Geometry::point_3d(1.0, 2.0, 3.0).as_text(), "POINT Z (1 2 3)");
+    /// ```
+    pub fn point_3d(x: f64, y: f64, z: f64) -> Geometry {
+        Geometry::Point { coordinates: Coordinate::new(x, y, z) }
+    }
+
+    /// Returns the x value of a point, or None if the geometry is not a point.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// assert_eq!(Geometry::point(1.0, 2.0).x(), Some(1.0));
+    /// assert_eq!(Geometry::MultiPoint { coordinates: vec![coord!(1, 2)] }.x(), None);
+    /// ```
+    pub fn x(&self) -> Option<f64> {
+        match self {
+            Geometry::Point { coordinates } => Some(coordinates.x()),
+            _ => None,
+        }
+    }
+
+    /// Returns the y value of a point, or None if the geometry is not a point.
+    pub fn y(&self) -> Option<f64> {
+        match self {
+            Geometry::Point { coordinates } => Some(coordinates.y()),
+            _ => None,
+        }
+    }
+
+    /// Returns the z value of a point, or None if the geometry is not a point.
+    pub fn z(&self) -> Option<f64> {
+        match self {
+            Geometry::Point { coordinates } => Some(coordinates.z()),
+            _ => None,
+        }
+    }
+
+    /// Returns a multi point holding the points and multi points among the geometries, in order.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let points = vec![Geometry::Point { coordinates: coord!(0, 0) }, Geometry::Point { coordinates: coord!(1, 1) }];
+    /// let multi_point = Geometry::from_points(&points);
+    /// assert_eq!(multi_point.as_text(), "MULTIPOINT ((0 0), (1 1))");
+    /// assert_eq!(multi_point.to_points(), points);
+    /// ```
+    pub fn from_points(points: &[Geometry]) -> Geometry {
+        Geometry::MultiPoint { coordinates: points.iter().flat_map(extract::points).collect() }
+    }
+
+    /// Returns every point and point of a multi point of the geometry as a point, searching
+    /// nested collections.
+    pub fn to_points(&self) -> Vec<Geometry> {
+        extract::points(self).into_iter().map(|coordinates| Geometry::Point { coordinates }).collect()
+    }
+
+    /// Returns the geometry with the points of multi points at the location of an earlier point
+    /// removed. See [`crate::operation::points::dedup`]. Other geometries are returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let points = Geometry::MultiPoint { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(0, 0)] };
+    /// assert!(!points.is_simple());
+    /// assert!(points.dedup().is_simple());
+    /// assert_eq!(points.dedup().as_text(), "MULTIPOINT ((0 0), (1 1))");
+    /// ```
+    pub fn dedup(&self) -> Geometry {
+        match self {
+            Geometry::MultiPoint { coordinates } => Geometry::MultiPoint { coordinates: points::dedup(coordinates) },
+            Geometry::GeometryCollection { geometries } => {
+                Geometry::GeometryCollection { geometries: geometries.iter().map(|g| g.dedup()).collect() }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    //constructive methods
+
+    /// Returns the smallest convex geometry containing all the coordinates of the geometry.
+    /// See [`crate::algorithm::convex_hull::convex_hull`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 0), coord!(1, 1), coord!(1, 3)] };
+    /// assert_eq!(line.convex_hull().area(), 3.0);
+    /// ```
+    pub fn convex_hull(&self) -> Geometry {
+        convex_hull_of(self.coords_iter())
+    }
+
+    /// Returns the polygons of the geometry extruded by a height into a closed polyhedral
+    /// surface, or None if it has no polygons. See [`crate::operation::extrude::extrude`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let footprint = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let building = footprint.extrude(3.0).unwrap();
+    /// assert_eq!(building.area_3d(), 2.0 * 4.0 + 8.0 * 3.0);
+    /// assert_eq!(building.envelope_3d().max_z(), 3.0);
+    /// ```
+    pub fn extrude(&self, height: f64) -> Option<Geometry> {
+        extrude::extrude(self, height)
+    }
+
+    /// Returns the rectangle of smallest area containing the geometry, in any orientation, or a
+    /// line string or a point when the geometry is collinear or a single point.
+    /// See [`crate::algorithm::calipers::oriented_envelope`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 4), coord!(5, 3)] };
+    /// assert!((line.oriented_envelope().area() - 8.0).abs() < 1e-12);
+    /// ```
+    pub fn oriented_envelope(&self) -> Geometry {
+        calipers::oriented_envelope_of(self.coords_iter())
+    }
+
+    /// Returns the rectangles fitting the shells of the polygons of the geometry best in the
+    /// least squares sense, as a multi polygon with a rectangle per polygon, which is empty for
+    /// other geometries. See [`crate::algorithm::calipers::fit_rectangle`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let buildings = Geometry::MultiPolygon { coordinates: vec![
+    ///     vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 2), coord!(0, 2), coord!(0, 0)]],
+    ///     vec![vec![coord!(10, 0), coord!(12, 0.01), coord!(12, 2), coord!(10, 2), coord!(10, 0)]],
+    /// ] };
+    /// let rectangles = buildings.fit_rectangle();
+    /// assert_eq!(rectangles.num_components(), 2);
+    /// assert!((rectangles.area() - 12.0).abs() < 0.1);
+    /// ```
+    pub fn fit_rectangle(&self) -> Geometry {
+        Geometry::MultiPolygon {
+            coordinates: self
+                .polygons()
+                .filter_map(|p| calipers::fit_rectangle(p.first()?))
+                .map(|ring| vec![ring])
+                .collect(),
+        }
+    }
+
+    /// Returns the alpha shape of the coordinates of the geometry as a multi polygon.
+    /// See [`crate::algorithm::alpha_shape::alpha_shape`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //two clusters of points far apart
+    /// let points = Geometry::MultiPoint { coordinates: vec![
+    ///     coord!(0, 0), coord!(1, 0), coord!(0, 1), coord!(10, 10), coord!(11, 10), coord!(11, 11)
+    /// ] };
+    /// let Geometry::MultiPolygon { coordinates } = points.alpha_shape(1.0) else { panic!() };
+    /// assert_eq!(coordinates.len(), 2);
+    /// ```
+    pub fn alpha_shape(&self, alpha: f64) -> Geometry {
+        let points: Vec<Coordinate> = self.coords_iter().cloned().collect();
+        alpha_shape(&points, alpha)
+    }
+
+    /// Returns the area covered by both geometries, considering their polygons only.
+    /// See [`crate::operation::overlay::overlay`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let a = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let b = Geometry::Polygon { coordinates: vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]] };
+    /// assert_eq!(a.intersection(&b).unwrap().area(), 1.0);
+    /// ```
+    pub fn intersection(&self, other: &Geometry) -> Result<Geometry, Error> {
+        overlay(self, other, OverlayOp::Intersection)
+    }
+
+    /// Returns the area covered by either geometry, considering their polygons only.
+    /// See [`crate::operation::overlay::overlay`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let a = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let b = Geometry::Polygon { coordinates: vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]] };
+    /// assert_eq!(a.union(&b).unwrap().area(), 7.0);
+    /// ```
+    pub fn union(&self, other: &Geometry) -> Result<Geometry, Error> {
+        overlay(self, other, OverlayOp::Union)
+    }
+
+    /// Returns the area covered by the geometry but not by the other, considering their polygons
+    /// only. See [`crate::operation::overlay::overlay`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let a = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let b = Geometry::Polygon { coordinates: vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]] };
+    /// assert_eq!(a.difference(&b).unwrap().area(), 3.0);
+    /// ```
+    pub fn difference(&self, other: &Geometry) -> Result<Geometry, Error> {
+        overlay(self, other, OverlayOp::Difference)
+    }
+
+    /// Returns the area covered by exactly one of the geometries, considering their polygons
+    /// only. See [`crate::operation::overlay::overlay`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let a = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let b = Geometry::Polygon { coordinates: vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]] };
+    /// assert_eq!(a.sym_difference(&b).unwrap().area(), 6.0);
+    /// ```
+    pub fn sym_difference(&self, other: &Geometry) -> Result<Geometry, Error> {
+        overlay(self, other, OverlayOp::SymDifference)
+    }
+
+    /// Returns the union of the parts of the highest dimension of the geometry, such as a
+    /// collection of overlapping polygons. See [`crate::operation::overlay::unary_union`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let squares = Geometry::MultiPolygon { coordinates: vec![
+    ///     vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]],
+    ///     vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]],
+    /// ] };
+    /// assert_eq!(squares.area(), 8.0);
+    /// assert_eq!(squares.unary_union().unwrap().area(), 7.0);
+    /// ```
+    pub fn unary_union(&self) -> Result<Geometry, Error> {
+        unary_union(self)
+    }
+
+    /// Returns the geometry noded and dissolved as a whole, keeping the parts of every dimension
+    /// not covered by parts of a higher one, as `ST_UnaryUnion` does.
+    /// See [`crate::operation::overlay::self_union`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let parcel = Geometry::GeometryCollection { geometries: vec![
+    ///     Geometry::MultiPolygon { coordinates: vec![
+    ///         vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]],
+    ///         vec![vec![coord!(1, 1), coord!(3, 1), coord!(3, 3), coord!(1, 3), coord!(1, 1)]],
+    ///     ] },
+    ///     //a path leaving the parcel, of which the part outside is kept
+    ///     Geometry::LineString { coordinates: vec![coord!(1, 0.5), coord!(5, 0.5)] },
+    /// ] };
+    /// let union = parcel.self_union().unwrap();
+    /// assert_eq!(union.area(), 7.0);
+    /// assert_eq!(union.length(), 12.0 + 3.0);
+    /// ```
+    pub fn self_union(&self) -> Result<Geometry, Error> {
+        self_union(self)
+    }
+
+    /// Returns the centroid of the parts of the highest dimension of the geometry as a point, or
+    /// None if the geometry is empty. See [`crate::algorithm::centroid::centroid`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 0), coord!(4, 2)] };
+    /// assert_eq!(line.centroid(), Some(Geometry::Point { coordinates: coord!(8.0 / 3.0, 1.0 / 3.0) }));
+    /// ```
+    pub fn centroid(&self) -> Option<Geometry> {
+        Some(Geometry::Point { coordinates: centroid::centroid(self)? })
+    }
+
+    /// Returns a polygon approximating the circle of the given radius around a point, or an error
+    /// if the geometry is not a point or if the number of segments passes
+    /// [`crate::shapes::MAX_SEGMENTS`].
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The point.
+    /// * `radius` - The radius of the circle.
+    /// * `segments` - The number of segments of the polygon, at least 3.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 3 segments.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::error::Error;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(5, 5) };
+    /// let circle = point.buffer_circle(2.0, 64).unwrap();
+    /// assert!((circle.area() - 4.0 * std::f64::consts::PI).abs() < 0.1);
+    /// assert!(matches!(point.buffer_circle(2.0, usize::MAX), Err(Error::LimitExceeded(_))));
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] };
+    /// assert!(line.buffer_circle(2.0, 64).is_err());
+    /// ```
+    pub fn buffer_circle(&self, radius: f64, segments: usize) -> Result<Geometry, Error> {
+        match self {
+            Geometry::Point { coordinates } => {
+                buffer::BufferParameters::default().check(segments, 1)?;
+                Ok(shapes::circle(coordinates, radius, segments))
+            }
+            _ => Err(Error::InvalidGeometry("only points have a buffer circle".to_string())),
+        }
+    }
+
+    /// Returns a polygon approximating the sector of the circle of the given radius around a
+    /// point, going clockwise from the start bearing to the end bearing, in degrees clockwise from
+    /// north, or None if the geometry is not a point. See [`crate::shapes::sector`].
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The point.
+    /// * `radius` - The radius of the circle.
+    /// * `start_bearing` - The bearing of the first side of the sector.
+    /// * `end_bearing` - The bearing of the second side of the sector.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mast = Geometry::Point { coordinates: coord!(5, 5) };
+    /// let east = mast.sector(2.0, 45.0, 135.0).unwrap();
+    /// assert!(east.contains_point(&coord!(6.5, 5)));
+    /// assert!(!east.contains_point(&coord!(5, 6.5)));
+    /// ```
+    pub fn sector(&self, radius: f64, start_bearing: f64, end_bearing: f64) -> Option<Geometry> {
+        match self {
+            Geometry::Point { coordinates } => Some(shapes::sector(coordinates, radius, start_bearing, end_bearing)),
+            _ => None,
+        }
+    }
+
+    /// Returns the polygons of the geometry grown by the distance, with rounded corners, as a
+    /// multi polygon. See [`crate::operation::buffer::dilate`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// assert!(square.dilate(1.0).unwrap().contains_point(&coord!(4.5, 2)));
+    /// ```
+    pub fn dilate(&self, distance: f64) -> Result<Geometry, Error> {
+        buffer::dilate(self, distance)
+    }
+
+    /// Returns the polygons of the geometry shrunk by the distance, as a multi polygon.
+    /// See [`crate::operation::buffer::erode`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// assert_eq!(square.erode(1.0).unwrap().area(), 4.0);
+    /// assert!(square.erode(2.0).unwrap().num_components() == 0);
+    /// ```
+    pub fn erode(&self, distance: f64) -> Result<Geometry, Error> {
+        buffer::erode(self, distance)
+    }
+
+    /// Returns the polygons of the geometry eroded and then dilated by the distance, without
+    /// their parts narrower than twice the distance. See [`crate::operation::buffer::opening`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// assert!(square.opening(2.5).unwrap().num_components() == 0);
+    /// ```
+    pub fn opening(&self, distance: f64) -> Result<Geometry, Error> {
+        buffer::opening(self, distance)
+    }
+
+    /// Returns the polygons of the geometry dilated and then eroded by the distance, with their
+    /// gaps narrower than twice the distance filled. See [`crate::operation::buffer::closing`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// assert!((square.closing(1.0).unwrap().area() - 16.0).abs() < 1e-9);
+    /// ```
+    pub fn closing(&self, distance: f64) -> Result<Geometry, Error> {
+        buffer::closing(self, distance)
+    }
+
+    /// Returns the point reached from a point by going in the direction of the bearing for the
+    /// given distance, or None if the geometry is not a point. In geodesic mode the point is a
+    /// longitude and latitude, the distance is in meters and the shortest path on the ellipsoid is
+    /// followed. See [`Geometry::bearing_to`].
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The point at the start.
+    /// * `bearing` - The direction in degrees clockwise from north.
+    /// * `distance` - The distance to travel.
+    /// * `mode` - Whether to travel in the plane or on the ellipsoid.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geodesic::Mode;
+    /// use geoms::coord;
+    ///
+    /// let start = Geometry::Point { coordinates: coord!(2, 3, 7) };
+    /// let Some(Geometry::Point { coordinates }) = start.destination(90.0, 5.0, Mode::Planar) else { panic!() };
+    /// assert!((coordinates.x() - 7.0).abs() < 1e-12 && (coordinates.y() - 3.0).abs() < 1e-12);
+    /// assert_eq!(coordinates.z(), 7.0);
+    ///
+    /// //a degree of latitude north of the equator
+    /// let Some(Geometry::Point { coordinates }) = Geometry::Point { coordinates: coord!(0, 0) }.destination(0.0, 110_574.389, Mode::Geodesic) else { panic!() };
+    /// assert!((coordinates.y() - 1.0).abs() < 1e-7);
+    /// ```
+    pub fn destination(&self, bearing: f64, distance: f64, mode: Mode) -> Option<Geometry> {
+        let Geometry::Point { coordinates: from } = self else {
+            return None;
+        };
+        let coordinates = match mode {
+            Mode::Planar => {
+                let (sin, cos) = bearing.to_radians().sin_cos();
+                Coordinate::new(from.x() + distance * sin, from.y() + distance * cos, from.z())
+            }
+            Mode::Geodesic => geodesic::destination(from, bearing, distance),
+        };
+        Some(Geometry::Point { coordinates })
+    }
+
+    /// Returns the lines of the geometry, given as longitudes and latitudes, following the
+    /// shortest paths on the ellipsoid with points inserted every maximum distance in meters at
+    /// most, or None if the geometry is not a line string or a multi line string. Lines crossing
+    /// the antimeridian are cut there, so a line string becomes a multi line string.
+    /// See [`crate::geodesic::densify`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //from London to New York
+    /// let flight = Geometry::LineString { coordinates: vec![coord!(-0.45, 51.47), coord!(-73.78, 40.64)] };
+    /// let curve = flight.geodesic_densify(50_000.0).unwrap();
+    /// assert_eq!(curve.coords_count(), 113);
+    /// assert!(curve.coords_iter().any(|c| c.y() > 52.0));
+    /// ```
+    pub fn geodesic_densify(&self, max_distance: f64) -> Option<Geometry> {
+        let mut lines = match self {
+            Geometry::LineString { coordinates } => geodesic::densify(coordinates, max_distance),
+            Geometry::MultiLineString { coordinates } => {
+                return Some(Geometry::MultiLineString {
+                    coordinates: coordinates.iter().flat_map(|line| geodesic::densify(line, max_distance)).collect(),
+                })
+            }
+            _ => return None,
+        };
+        if lines.len() > 1 {
+            return Some(Geometry::MultiLineString { coordinates: lines });
+        }
+        Some(Geometry::LineString { coordinates: lines.pop().unwrap_or_default() })
+    }
+
+    /// Returns an approximation of the medial axis of the polygons of the geometry as a multi line
+    /// string, which is empty for other geometries. See
+    /// [`crate::algorithm::skeleton::approximate_medial_axis`].
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The polygon or multi polygon.
+    /// * `spacing` - The maximum distance between the boundary points the axis is computed from.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let river = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(20, 0), coord!(20, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let centerline = river.approximate_medial_axis(0.5);
+    /// //the center line, plus branches towards the corners shortened by the sampling
+    /// assert!(centerline.length() > 18.0 && centerline.length() < 18.0 + 4.0 * 2f64.sqrt());
+    /// ```
+    pub fn approximate_medial_axis(&self, spacing: f64) -> Geometry {
+        Geometry::MultiLineString {
+            coordinates: self.polygons().flat_map(|p| skeleton::approximate_medial_axis(p, spacing)).collect(),
+        }
+    }
+
+    /// Returns the straight skeleton of the polygons of the geometry as a multi line string, which
+    /// is empty for other geometries. See [`crate::algorithm::skeleton::straight_skeleton`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let skeleton = square.straight_skeleton();
+    /// assert!((skeleton.length() - 4.0 * 2f64.sqrt()).abs() < 1e-12);
+    /// ```
+    pub fn straight_skeleton(&self) -> Geometry {
+        Geometry::MultiLineString { coordinates: self.polygons().flat_map(|p| skeleton::straight_skeleton(p)).collect() }
+    }
+
+    /// Returns the polygons of the geometry split into convex polygons covering them without
+    /// overlapping, as a multi polygon which is empty for other geometries. See
+    /// [`crate::algorithm::convex_decomposition::convex_decomposition`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let room = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 1), coord!(1, 1), coord!(1, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let parts = room.convex_decomposition();
+    /// assert_eq!(parts.num_components(), 2);
+    /// assert_eq!(parts.area(), 3.0);
+    /// ```
+    pub fn convex_decomposition(&self) -> Geometry {
+        Geometry::MultiPolygon {
+            coordinates: self.polygons().flat_map(|p| convex_decomposition::convex_decomposition(p)).map(|ring| vec![ring]).collect(),
+        }
+    }
+
+    /// Returns the center and the radius of the largest circle fitting inside of the polygons of
+    /// the geometry, or None if it has no polygon with an area. See
+    /// [`crate::algorithm::label::maximum_inscribed_circle`].
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The polygon or multi polygon.
+    /// * `tolerance` - The maximum difference between the radius found and the largest one.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let rectangle = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(8, 0), coord!(8, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// let (center, radius) = rectangle.maximum_inscribed_circle(0.01).unwrap();
+    /// assert!((radius - 2.0).abs() <= 0.01);
+    /// assert!((center.y() - 2.0).abs() <= 0.01);
+    /// ```
+    pub fn maximum_inscribed_circle(&self, tolerance: f64) -> Option<(Coordinate, f64)> {
+        self.polygons()
+            .filter_map(|p| label::maximum_inscribed_circle(p, tolerance))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Returns the pole of inaccessibility of the polygons of the geometry as a point, the one of
+    /// the polygon with the largest inscribed circle for a multi polygon, or None if it has no
+    /// polygon with an area. See [`crate::algorithm::label::pole_of_inaccessibility`].
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The polygon or multi polygon.
+    /// * `precision` - The maximum difference between the distance from the point found to the
+    ///   boundary and the largest one.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let rectangle = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(8, 0), coord!(8, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// let Some(Geometry::Point { coordinates }) = rectangle.pole_of_inaccessibility(0.01) else { panic!() };
+    /// assert!((coordinates.y() - 2.0).abs() <= 0.01);
+    /// ```
+    pub fn pole_of_inaccessibility(&self, precision: f64) -> Option<Geometry> {
+        if !(precision.is_finite() && precision > 0.0) {
+            panic!("The precision of the pole of inaccessibility must be positive");
+        }
+        let (center, _) = self.maximum_inscribed_circle(precision)?;
+        Some(Geometry::Point { coordinates: center })
+    }
+
+    /// Returns a point inside of the polygons of the geometry where a label fits best, its pole of
+    /// inaccessibility found within a thousandth of the size of the geometry, or
+    /// None if it has no polygon with an area.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //a U shape, whose centroid would lie outside of it
+    /// let polygon = Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(8, 10), coord!(8, 2), coord!(2, 2), coord!(2, 10), coord!(0, 10), coord!(0, 0)
+    /// ]] };
+    /// let Some(Geometry::Point { coordinates }) = polygon.label_point() else { panic!() };
+    /// assert!(coordinates.y() < 2.0);
+    /// ```
+    pub fn label_point(&self) -> Option<Geometry> {
+        let envelope = self.envelope();
+        let tolerance = envelope.width().max(envelope.height()) / 1000.0;
+        if envelope.is_empty() || tolerance == 0.0 {
+            return None;
+        }
+        self.pole_of_inaccessibility(tolerance)
+    }
+
+    /// Returns a line inside of the polygons of the geometry along which a curved label fits best,
+    /// the longest path on their approximate medial axis, or None if it has no polygon with an
+    /// area. The boundary is sampled at a quarter of the width of the polygon at its widest, and
+    /// the longest line of a multi polygon is returned. See [`crate::algorithm::label::label_line`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let river = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(20, 0), coord!(20, 2), coord!(0, 2), coord!(0, 0)]] };
+    /// let line = river.label_line().unwrap();
+    /// assert!(line.length() > 18.0);
+    /// ```
+    pub fn label_line(&self) -> Option<Geometry> {
+        let envelope = self.envelope();
+        let tolerance = envelope.width().max(envelope.height()) / 1000.0;
+        if envelope.is_empty() || tolerance == 0.0 {
+            return None;
+        }
+        self.polygons()
+            .filter_map(|p| {
+                let (_, radius) = label::maximum_inscribed_circle(p, tolerance)?;
+                Some(Geometry::LineString { coordinates: label::label_line(p, radius / 2.0) })
+            })
+            .max_by(|a, b| a.length().total_cmp(&b.length()))
+    }
+
+    /// Returns the points where the lines of the geometry cross or touch themselves, as a multi
+    /// point sorted by x and then y, each location given once. Where a line runs over itself, both
+    /// ends of the overlap are returned. The closing vertex of closed lines and repeated vertices
+    /// are not intersections. Each line of a multi line string is checked on its own, and the
+    /// geometries other than lines give no point.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //a figure of eight crossing itself at (1, 1)
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 2), coord!(2, 0), coord!(0, 2), coord!(0, 0)] };
+    /// assert_eq!(line.self_intersections(), Geometry::MultiPoint { coordinates: vec![coord!(1, 1)] });
+    ///
+    /// //a line turning back over its first segment
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 0), coord!(4, 1), coord!(3, 0), coord!(1, 0)] };
+    /// assert_eq!(line.self_intersections().as_text(), "MULTIPOINT ((1 0), (3 0))");
+    /// ```
+    pub fn self_intersections(&self) -> Geometry {
+        let lines: Vec<&Vec<Coordinate>> = match self {
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => vec![coordinates],
+            Geometry::MultiLineString { coordinates } => coordinates.iter().collect(),
+            _ => vec![],
+        };
+        let mut points: Vec<Coordinate> = lines.into_iter().flat_map(|line| self_intersection_points(line)).collect();
+        points.sort_by(|p, q| p.x().total_cmp(&q.x()).then(p.y().total_cmp(&q.y())));
+        points.dedup_by(|p, q| p.equals_2d(q));
+        Geometry::MultiPoint { coordinates: points }
+    }
+
+    /// Returns the rings of every polygon of the geometry.
+    pub(crate) fn polygons(&self) -> Box<dyn Iterator<Item = &Vec<Vec<Coordinate>>> + '_> {
+        match self {
+            Geometry::Polygon { coordinates } => Box::new(std::iter::once(coordinates)),
+            Geometry::MultiPolygon { coordinates } => Box::new(coordinates.iter()),
+            Geometry::GeometryCollection { geometries } => Box::new(geometries.iter().flat_map(|g| g.polygons())),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    //editorial methods
+
+    /// Returns the geometry with every coordinate transformed, z included. See
+    /// [`AffineTransform`].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::affine::AffineTransform;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0, 0), coord!(0, 0, 2)] };
+    /// let tipped = line.affine_transform(&AffineTransform::rotation([0.0, 1.0, 0.0], PI / 2.0));
+    /// assert!(tipped.coords_iter().last().unwrap().distance_3d(&coord!(2, 0, 0)) < 1e-12);
+    /// ```
+    pub fn affine_transform(&self, transform: &AffineTransform) -> Geometry {
+        transform.transform(self)
+    }
+
+    /// Returns the lines of the geometry joined where exactly two of their ends meet. See
+    /// [`crate::operation::line_merge::line_merge`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let lines = Geometry::MultiLineString { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(1, 0)],
+    ///     vec![coord!(2, 0), coord!(1, 0)],
+    /// ] };
+    /// assert_eq!(lines.line_merge().as_text(), "MULTILINESTRING ((0 0, 1 0, 2 0))");
+    /// ```
+    pub fn line_merge(&self) -> Geometry {
+        line_merge::line_merge(self)
+    }
+
+    /// Returns the geometry without the polygons whose planar area is smaller than the given one.
+    /// A polygon that is removed becomes an empty polygon, and other geometries are returned
+    /// unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let polygons = Geometry::MultiPolygon { coordinates: vec![
+    ///     vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)]],
+    ///     vec![vec![coord!(5, 0), coord!(8, 0), coord!(8, 3), coord!(5, 0)]],
+    /// ] };
+    /// assert_eq!(polygons.filter_by_area(1.0).as_text(), "MULTIPOLYGON (((5 0, 8 0, 8 3, 5 0)))");
+    /// ```
+    pub fn filter_by_area(&self, min_area: f64) -> Geometry {
+        let keep = |polygon: &Vec<Vec<Coordinate>>| area::polygon_area(polygon) >= min_area;
+        match self {
+            Geometry::Polygon { coordinates } if !keep(coordinates) => Geometry::Polygon { coordinates: vec![] },
+            Geometry::MultiPolygon { coordinates } => {
+                Geometry::MultiPolygon { coordinates: coordinates.iter().filter(|p| keep(p)).cloned().collect() }
+            }
+            Geometry::GeometryCollection { geometries } => Geometry::GeometryCollection {
+                geometries: geometries.iter().map(|g| g.filter_by_area(min_area)).collect(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns the geometry without the holes of its polygons whose planar area is smaller than
+    /// the given one, which fills them. Other geometries are returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let frame = Geometry::Polygon { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+    ///     vec![coord!(2, 2), coord!(2, 4), coord!(4, 4), coord!(4, 2), coord!(2, 2)],
+    ///     vec![coord!(6, 6), coord!(6, 7), coord!(7, 7), coord!(7, 6), coord!(6, 6)],
+    /// ] };
+    /// let cleaned = frame.remove_small_holes(2.0);
+    /// assert_eq!(cleaned.hole_count(), 1);
+    /// assert_eq!(cleaned.area(), 96.0);
+    /// ```
+    pub fn remove_small_holes(&self, min_area: f64) -> Geometry {
+        let fill = |polygon: &Vec<Vec<Coordinate>>| -> Vec<Vec<Coordinate>> {
+            polygon
+                .iter()
+                .enumerate()
+                .filter(|(i, ring)| *i == 0 || area::ring_area(ring) >= min_area)
+                .map(|(_, ring)| ring.clone())
+                .collect()
+        };
+        match self {
+            Geometry::Polygon { coordinates } => Geometry::Polygon { coordinates: fill(coordinates) },
+            Geometry::MultiPolygon { coordinates } => {
+                Geometry::MultiPolygon { coordinates: coordinates.iter().map(fill).collect() }
+            }
+            Geometry::GeometryCollection { geometries } => Geometry::GeometryCollection {
+                geometries: geometries.iter().map(|g| g.remove_small_holes(min_area)).collect(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns the geometry without the spikes of its lines and rings, the vertices where they
+    /// turn back with an angle narrower than the tolerance, given in radians. See
+    /// [`crate::operation::clean::remove_spikes`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 0), coord!(4.05, 3), coord!(4.1, 0), coord!(8, 0)] };
+    /// assert_eq!(line.remove_spikes(0.1).as_text(), "LINESTRING (0 0, 4 0, 4.1 0, 8 0)");
+    /// ```
+    pub fn remove_spikes(&self, angle_tolerance: f64) -> Geometry {
+        clean::remove_spikes(self, angle_tolerance)
+    }
+
+    /// Returns the geometry with the outlines of its polygons squared: near collinear edges are
+    /// straightened and edges close to the main direction of their ring, or to a right angle to
+    /// it, are turned into it. See [`crate::operation::regularize::regularize`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let footprint = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0.1), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// //a square, slightly turned to follow the main direction of the edges
+    /// let regular = footprint.regularize(0.1);
+    /// assert_eq!(regular.coords_count(), 5);
+    /// assert!((regular.area() - 15.8).abs() < 1e-2);
+    /// ```
+    pub fn regularize(&self, angle_tolerance: f64) -> Geometry {
+        regularize::regularize(self, angle_tolerance)
+    }
+
+    /// Returns the geometry simplified with the algorithm of Douglas and Peucker, removing the
+    /// vertices within the tolerance of the simplified lines. See
+    /// [`crate::operation::simplify::simplify`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0.05), coord!(2, 0)] };
+    /// assert_eq!(line.simplify(0.1).as_text(), "LINESTRING (0 0, 2 0)");
+    /// ```
+    pub fn simplify(&self, tolerance: f64) -> Geometry {
+        simplify::simplify(self, tolerance)
+    }
+
+    /// Returns the geometry with its coordinates rounded to a grid, without the vertices and parts
+    /// collapsed by the rounding. See [`crate::operation::snap::snap_to_grid`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(1.26, -0.74) };
+    /// assert_eq!(point.snap_to_grid(0.5).as_text(), "POINT (1.5 -0.5)");
+    /// ```
+    pub fn snap_to_grid(&self, size: f64) -> Geometry {
+        snap::snap_to_grid(self, size)
+    }
+
+    /// Returns the geometry without the polygons and holes narrower than the minimum width. See
+    /// [`crate::operation::clean::remove_slivers`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let polygon = Geometry::Polygon { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+    ///     vec![coord!(2, 2), coord!(8, 2.01), coord!(8, 2), coord!(2, 2)],
+    /// ] };
+    /// assert_eq!(polygon.remove_slivers(0.1).as_text(), "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))");
+    /// ```
+    pub fn remove_slivers(&self, min_width: f64) -> Geometry {
+        clean::remove_slivers(self, min_width)
+    }
+
+    /// Returns the geometry with its polygons rebuilt from their noded rings, a cheap fix for
+    /// slightly invalid polygons equivalent to a buffer of zero distance. See
+    /// [`crate::operation::clean::clean_polygons`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// //a shell given clockwise, with a repeated vertex
+    /// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(0, 2), coord!(2, 2), coord!(2, 2), coord!(2, 0), coord!(0, 0)]] };
+    /// assert_eq!(polygon.clean().unwrap().as_text(), "POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0))");
+    /// ```
+    pub fn clean(&self) -> Result<Geometry, Error> {
+        clean::clean_polygons(self)
+    }
+
+    //vertex editing methods
+
+    /// Moves a vertex of a line or a ring to the coordinate, in place. The vertices of a ring are
+    /// counted without its closing coordinate, which follows the first vertex. With `check`, the
+    /// edit is refused if it would make a line cross itself or leave a ring invalid. Returns an
+    /// error, leaving the geometry as it was, if the edit is refused, the index is out of range
+    /// or the geometry is not a line or a ring.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)] };
+    /// ring.move_vertex(0, coord!(-1, -1), true).unwrap();
+    /// assert_eq!(ring.as_text(), "LINEARRING (-1 -1, 2 0, 2 2, 0 2, -1 -1)");
+    ///
+    /// //dragging a corner across an edge makes the ring cross itself
+    /// let error = ring.move_vertex(2, coord!(-2, 1), true).unwrap_err();
+    /// assert!(error.to_string().starts_with("invalid geometry: self intersection"));
+    /// assert_eq!(ring.coordinates()[2], coord!(2, 2));
+    /// ```
+    pub fn move_vertex(&mut self, index: usize, coordinate: Coordinate, check: bool) -> Result<(), Error> {
+        self.edit_vertex(None, VertexEdit::Move(index, coordinate), check)
+    }
+
+    /// Inserts a vertex into a line or a ring at the index, in place, so the new vertex has that
+    /// index. Inserting at the number of vertices appends it. See [`Geometry::move_vertex`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 0)] };
+    /// line.insert_vertex(1, coord!(1, 1), true).unwrap();
+    /// line.insert_vertex(3, coord!(3, 0), true).unwrap();
+    /// assert_eq!(line.as_text(), "LINESTRING (0 0, 1 1, 2 0, 3 0)");
+    /// assert!(line.insert_vertex(5, coord!(4, 0), false).is_err());
+    /// ```
+    pub fn insert_vertex(&mut self, index: usize, coordinate: Coordinate, check: bool) -> Result<(), Error> {
+        self.edit_vertex(None, VertexEdit::Insert(index, coordinate), check)
+    }
+
+    /// Deletes a vertex of a line or a ring, in place. Lines keep at least two vertices and rings
+    /// three, besides their closing coordinate. See [`Geometry::move_vertex`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(2, 0)] };
+    /// line.delete_vertex(1, true).unwrap();
+    /// assert_eq!(line.as_text(), "LINESTRING (0 0, 2 0)");
+    /// assert_eq!(line.delete_vertex(0, false).unwrap_err().to_string(), "invalid geometry: a line needs at least two vertices");
+    /// ```
+    pub fn delete_vertex(&mut self, index: usize, check: bool) -> Result<(), Error> {
+        self.edit_vertex(None, VertexEdit::Delete(index), check)
+    }
+
+    /// Moves a vertex of a ring of a polygon to the coordinate, in place. The shell is the ring
+    /// 0 and the holes follow. With `check`, the edit is refused if it would leave the polygon
+    /// invalid. See [`Geometry::move_vertex`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut polygon = Geometry::Polygon { coordinates: vec![
+    ///     vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+    ///     vec![coord!(1, 1), coord!(1, 2), coord!(2, 2), coord!(2, 1), coord!(1, 1)],
+    /// ] };
+    /// polygon.move_ring_vertex(1, 2, coord!(3, 3), true).unwrap();
+    /// assert_eq!(polygon.area(), 14.0);
+    /// //the hole cannot be dragged out of the shell
+    /// assert!(polygon.move_ring_vertex(1, 2, coord!(5, 5), true).is_err());
+    /// ```
+    pub fn move_ring_vertex(&mut self, ring: usize, index: usize, coordinate: Coordinate, check: bool) -> Result<(), Error> {
+        self.edit_vertex(Some(ring), VertexEdit::Move(index, coordinate), check)
+    }
+
+    /// Inserts a vertex into a ring of a polygon at the index, in place. See
+    /// [`Geometry::insert_vertex`] and [`Geometry::move_ring_vertex`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 0)]] };
+    /// polygon.insert_ring_vertex(0, 3, coord!(0, 4), true).unwrap();
+    /// assert_eq!(polygon.as_text(), "POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0))");
+    /// ```
+    pub fn insert_ring_vertex(&mut self, ring: usize, index: usize, coordinate: Coordinate, check: bool) -> Result<(), Error> {
+        self.edit_vertex(Some(ring), VertexEdit::Insert(index, coordinate), check)
+    }
+
+    /// Deletes a vertex of a ring of a polygon, in place. See [`Geometry::delete_vertex`] and
+    /// [`Geometry::move_ring_vertex`].
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let mut polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// polygon.delete_ring_vertex(0, 0, true).unwrap();
+    /// assert_eq!(polygon.as_text(), "POLYGON ((4 0, 4 4, 0 4, 4 0))");
+    /// assert!(polygon.delete_ring_vertex(0, 0, true).is_err());
+    /// ```
+    pub fn delete_ring_vertex(&mut self, ring: usize, index: usize, check: bool) -> Result<(), Error> {
+        self.edit_vertex(Some(ring), VertexEdit::Delete(index), check)
+    }
+
+    /// Applies the edit to the vertices of the line or ring, or of the ring of the polygon, and
+    /// replaces the geometry with the result if it passes the checks.
+    fn edit_vertex(&mut self, ring: Option<usize>, edit: VertexEdit, check: bool) -> Result<(), Error> {
+        let mut edited = self.clone();
+        let (coordinates, closed) = match (&mut edited, ring) {
+            (Geometry::LineString { coordinates }, None) => (coordinates, false),
+            (Geometry::LinearRing { coordinates }, None) => (coordinates, true),
+            (Geometry::Polygon { coordinates }, Some(ring)) => {
+                let count = coordinates.len();
+                let ring = coordinates
+                    .get_mut(ring)
+                    .ok_or_else(|| Error::InvalidGeometry(format!("ring {} out of range of {} rings", ring, count)))?;
+                (ring, true)
+            }
+            _ => return Err(Error::InvalidGeometry(format!("cannot edit the vertices of a {} this way", wkt::tag(self)))),
+        };
+        edit.apply(coordinates, closed)?;
+        if check {
+            check_edit(&edited)?;
+        }
+        *self = edited;
+        Ok(())
+    }
+
+    //wkt methods
+
+    /// Returns the WKT representation of the geometry.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(1, 2) };
+    /// assert_eq!(point.as_text(), "POINT (1 2)");
+    /// ```
+    pub fn as_text(&self) -> String {
+        wkt::write(self)
+    }
+
+    /// Creates a geometry from its WKT representation.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::from_text("POINT (1 2)").unwrap();
+    /// assert_eq!(point, Geometry::Point { coordinates: coord!(1, 2) });
+    /// ```
+    pub fn from_text(text: &str) -> Result<Geometry, Error> {
+        wkt::read(text)
+    }
+
+    //wkb methods
+
+    /// Returns the little endian WKB representation of the geometry.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(1, 2) };
+    /// assert_eq!(Geometry::from_binary(&point.as_binary()).unwrap(), point);
+    /// ```
+    pub fn as_binary(&self) -> Vec<u8> {
+        wkb::write(self)
+    }
+
+    /// Creates a geometry from its WKB representation.
+    pub fn from_binary(bytes: &[u8]) -> Result<Geometry, Error> {
+        wkb::read(bytes)
+    }
+
+    /// Returns the little endian WKB representation of the geometry as hex digits of the case.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    /// use geoms::io::wkb::HexCase;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(1, 2) };
+    /// assert_eq!(point.to_wkb_hex(HexCase::Upper), "0101000000000000000000F03F0000000000000040");
+    /// ```
+    pub fn to_wkb_hex(&self, case: HexCase) -> String {
+        wkb::write_hex(self, case)
+    }
+
+    /// Creates a geometry from its WKB or PostGIS extended WKB representation as hex digits.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::from_wkb_hex("0101000000000000000000f03f0000000000000040").unwrap();
+    /// assert_eq!(point, Geometry::Point { coordinates: coord!(1, 2) });
+    /// ```
+    pub fn from_wkb_hex(text: &str) -> Result<Geometry, Error> {
+        wkb::read_hex(text)
+    }
+
+    //geojson methods
+
+    /// Returns the GeoJSON representation of the geometry, with its rings as they are.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::Point { coordinates: coord!(1, 2) };
+    /// assert_eq!(point.as_geojson(), r#"{"type":"Point","coordinates":[1,2]}"#);
+    /// ```
+    pub fn as_geojson(&self) -> String {
+        geojson::write(self)
+    }
 
+    /// Creates a geometry from its GeoJSON representation.
+    ///
+    /// # Examples
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let point = Geometry::from_geojson(r#"{"type": "Point", "coordinates": [1, 2]}"#).unwrap();
+    /// assert_eq!(point, Geometry::Point { coordinates: coord!(1, 2) });
+    /// ```
+    pub fn from_geojson(text: &str) -> Result<Geometry, Error> {
+        geojson::read(text)
     }
+
     //accessors
     // fn boundary(&self) -> Option<Geometry> {
     //     match self {
@@ -110,30 +2422,18 @@ impl Geometry {
     //         _ => None,
     //     } 
     // }
-    // fn coordinates(&self) -> Vec<Coordinate>; //this might not be needed as every type has a different construct of coordinates
     // fn dimension(&self) -> i32;
-    // fn envelope(&self) -> &dyn Geometry;
     // //constructive methods
     // fn buffer(&self, distance: f64) -> &dyn Geometry;
-    // fn centroid(&self) -> Point;
-    // fn difference(&self, other: &dyn Geometry) -> &dyn Geometry;
     // fn concave_hull(&self, tolerance: f64) -> &dyn Geometry;
-    // fn convex_hull(&self) -> &dyn Geometry;
-    // fn intersection(&self, other: &dyn Geometry) -> &dyn Geometry;
     // fn reverse(&self) -> &dyn Geometry;
     // fn simplify(&self, tolerance: f64) -> &dyn Geometry;    
-    // fn sym_difference(&self, other: &dyn Geometry) -> &dyn Geometry;    
-    // fn union(&self, other: &dyn Geometry) -> &dyn Geometry;
     // //editorial methods
     // fn normalize(&self) -> &dyn Geometry;
     // fn snap(&self, other: &dyn Geometry, tolerance: f64) -> &dyn Geometry;
     // fn snap_to_grid(&self, size: f64) -> &dyn Geometry;
     // //measuring methods
     // fn area(&self) -> f64;
-    // fn distance(&self, other: &dyn Geometry) -> f64;
-    // fn length(&self) -> f64;      
-    // //distance relationships
-    // fn is_within_distance(&self, other: &dyn Geometry, distance: f64) -> bool;
     // //spatial reference system methods
     // fn set_srid(&self, srid: i32) -> &dyn Geometry;
     // fn srid(&self) -> i32;
@@ -151,14 +2451,7 @@ impl Geometry {
     // fn touches(&self, other: &dyn Geometry) -> bool;
     // fn within(&self, other: &dyn Geometry) -> bool;
     // //validation methods    
-    // fn is_valid(&self) -> bool;
     // fn make_valid(&self) -> &dyn Geometry;
-    // //wkt methods
-    // fn as_text(&self) -> String;
-    // fn from_text(&self, wkt: &str) -> &dyn Geometry;
-    // //wkb methods
-    // fn as_binary(&self) -> Vec<u8>;
-    // fn from_binary(&self, wkb: &[u8]) -> &dyn Geometry;
     // //geojson methods
     // fn as_geojson(&self) -> String;
     // fn from_geojson(&self, geojson: &str) -> &dyn Geometry;
@@ -173,7 +2466,414 @@ impl Geometry {
     // fn from_gml(&self, gml: &str) -> &dyn Geometry;
 }
 
+impl fmt::Display for Geometry {
+    /// Returns the geometry as WKT, with the ordinates rounded to the precision of the format if
+    /// one is given. See [`crate::io::wkt::write_with_precision`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1.0 / 3.0, 2)] };
+    /// assert_eq!(format!("{}", line), "LINESTRING (0 0, 0.3333333333333333 2)");
+    /// assert_eq!(format!("{:.3}", line), "LINESTRING (0 0, 0.333 2)");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => f.write_str(&wkt::write_with_precision(self, precision)),
+            None => f.write_str(&wkt::write(self)),
+        }
+    }
+}
+
+impl fmt::Debug for Geometry {
+    /// Returns a summary of the geometry, with its type, number of vertices and envelope rather
+    /// than its coordinates, which can be many. Use [`fmt::Display`] to see them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(3, 1), coord!(2, 4)] };
+    /// assert_eq!(format!("{:?}", line), "LineString { vertices: 3, envelope: (0 0, 3 4) }");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Geometry::Point { .. } => "Point",
+            Geometry::LineString { .. } => "LineString",
+            Geometry::LinearRing { .. } => "LinearRing",
+            Geometry::Polygon { .. } => "Polygon",
+            Geometry::MultiPoint { .. } => "MultiPoint",
+            Geometry::MultiLineString { .. } => "MultiLineString",
+            Geometry::MultiPolygon { .. } => "MultiPolygon",
+            Geometry::GeometryCollection { .. } => "GeometryCollection",
+            Geometry::PolyhedralSurface { .. } => "PolyhedralSurface",
+            Geometry::Tin { .. } => "Tin",
+        };
+        let envelope = self.envelope();
+        let bounds = if envelope.is_empty() {
+            "empty".to_string()
+        } else {
+            format!("({} {}, {} {})", envelope.min_x(), envelope.min_y(), envelope.max_x(), envelope.max_y())
+        };
+        write!(f, "{} {{ vertices: {}, envelope: {} }}", name, self.coords_count(), bounds)
+    }
+}
+
+impl From<Coordinate> for Geometry {
+    /// Returns a point at the coordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::geometry::Geometry;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::coord;
+    ///
+    /// assert_eq!(Geometry::from(coord!(1, 2)), Geometry::point(1.0, 2.0));
+    /// ```
+    fn from(coordinates: Coordinate) -> Geometry {
+        Geometry::Point { coordinates }
+    }
+}
+
+impl From<(f64, f64)> for Geometry {
+    /// Returns a point at the planar location, with a z value of 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::geometry::Geometry;
+    ///
+    /// let point: Geometry = (1.0, 2.0).into();
+    /// assert_eq!(point.as_text(), "POINT (1 2)");
+    /// ```
+    fn from((x, y): (f64, f64)) -> Geometry {
+        Geometry::point(x, y)
+    }
+}
+
+impl From<(f64, f64, f64)> for Geometry {
+    /// Returns a point at the location in 3D.
+    fn from((x, y, z): (f64, f64, f64)) -> Geometry {
+        Geometry::point_3d(x, y, z)
+    }
+}
+
+/// Returns an error if an edited geometry is invalid, or is a line intersecting itself.
+fn check_edit(geometry: &Geometry) -> Result<(), Error> {
+    if let Some(error) = geometry.validity_errors().into_iter().next() {
+        return Err(Error::InvalidGeometry(error.to_string()));
+    }
+    if matches!(geometry, Geometry::LineString { .. }) && !geometry.is_simple() {
+        return Err(Error::InvalidGeometry("the line would intersect itself".to_string()));
+    }
+    Ok(())
+}
+
+/// An edit of a single vertex of a line or a ring.
+#[derive(Debug, Clone)]
+enum VertexEdit {
+    Move(usize, Coordinate),
+    Insert(usize, Coordinate),
+    Delete(usize),
+}
+
+impl VertexEdit {
+    /// Applies the edit to the coordinates of a line, or of a ring whose closing coordinate
+    /// follows its first vertex, and returns the coordinate moved or deleted.
+    fn apply(self, coordinates: &mut Vec<Coordinate>, closed: bool) -> Result<Option<Coordinate>, Error> {
+        let count = if closed { coordinates.len().saturating_sub(1) } else { coordinates.len() };
+        let out_of_range = |index: usize| Error::InvalidGeometry(format!("vertex {} out of range of {} vertices", index, count));
+        let mut replaced = None;
+        match self {
+            VertexEdit::Move(index, coordinate) => {
+                if index >= count {
+                    return Err(out_of_range(index));
+                }
+                replaced = Some(std::mem::replace(&mut coordinates[index], coordinate));
+            }
+            VertexEdit::Insert(index, coordinate) => {
+                if index > count {
+                    return Err(out_of_range(index));
+                }
+                coordinates.insert(index, coordinate);
+            }
+            VertexEdit::Delete(index) => {
+                if index >= count {
+                    return Err(out_of_range(index));
+                }
+                match closed {
+                    true if count <= 3 => return Err(Error::InvalidGeometry("a ring needs at least three vertices".to_string())),
+                    false if count <= 2 => return Err(Error::InvalidGeometry("a line needs at least two vertices".to_string())),
+                    _ => {}
+                }
+                replaced = Some(coordinates.remove(index));
+            }
+        }
+        //the closing coordinate follows the first vertex, including a vertex inserted before it
+        if closed {
+            let last = coordinates.len() - 1;
+            coordinates[last] = coordinates[0].clone();
+        }
+        Ok(replaced)
+    }
+}
+
+/// Returns the ends shared by an odd number of the lines, in the order they are first met.
+fn odd_ends(lines: &[Vec<Coordinate>]) -> Vec<Coordinate> {
+    let mut counts: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut ends = Vec::new();
+    for line in lines.iter().filter(|line| !line.is_empty()) {
+        for end in [&line[0], &line[line.len() - 1]] {
+            let count = counts.entry(end.key()).or_default();
+            if *count == 0 {
+                ends.push(end.clone());
+            }
+            *count += 1;
+        }
+    }
+    ends.into_iter().filter(|end| counts[&end.key()] % 2 == 1).collect()
+}
+
+/// Returns the edges of an odd number of the rings, merged into lines. Edges are compared with
+/// their z values, since the patches of surfaces are not planar projections.
+fn odd_edges<'a>(rings: impl Iterator<Item = &'a Vec<Coordinate>>) -> Geometry {
+    let location = |c: &Coordinate| (c.key(), (c.z() + 0.0).to_bits());
+    let edge = |w: &[Coordinate]| {
+        let (a, b) = (location(&w[0]), location(&w[1]));
+        if a < b { (a, b) } else { (b, a) }
+    };
+    let mut counts: HashMap<_, usize> = HashMap::new();
+    let mut edges = Vec::new();
+    for w in rings.flat_map(|ring| ring.windows(2)) {
+        let count = counts.entry(edge(w)).or_default();
+        if *count == 0 {
+            edges.push(w.to_vec());
+        }
+        *count += 1;
+    }
+    edges.retain(|w| counts[&edge(w)] % 2 == 1);
+    line_merge::line_merge(&Geometry::MultiLineString { coordinates: edges })
+}
+
+/// Returns true if the first and last coordinates are equal.
+fn is_closed(coordinates: &[Coordinate]) -> bool {
+    coordinates.len() > 1 && coordinates[0].equals_2d(&coordinates[coordinates.len() - 1])
+}
+
+/// Returns true if the point `far` lies on the segment `a` -> `b` without being one of its endpoints.
+fn is_inside_segment(far: &Coordinate, a: &Coordinate, b: &Coordinate) -> bool {
+    point_on_segment(far, a, b) && !far.equals_2d(a) && !far.equals_2d(b)
+}
+
+/// Returns true if none of the paths intersects itself and the paths only touch each other at
+/// their endpoints.
+fn are_simple_paths(paths: &[&Vec<Coordinate>]) -> bool {
+    //offsets[i] is the index of the first segment of the i-th path
+    let mut offsets: Vec<usize> = Vec::with_capacity(paths.len() + 1);
+    let mut segments: Vec<(&Coordinate, &Coordinate)> = Vec::new();
+    for path in paths {
+        offsets.push(segments.len());
+        for window in path.windows(2) {
+            //repeated vertices
+            if window[0].equals_2d(&window[1]) {
+                return false;
+            }
+            segments.push((&window[0], &window[1]));
+        }
+    }
+    offsets.push(segments.len());
+
+    let path_of = |segment: usize| offsets.partition_point(|&offset| offset <= segment) - 1;
+    let is_boundary = |path: &Vec<Coordinate>, c: &Coordinate| {
+        !is_closed(path) && (path[0].equals_2d(c) || path[path.len() - 1].equals_2d(c))
+    };
+
+    sweep_segment_pairs(&segments, |a, b| {
+        let (path_a, path_b) = (path_of(a), path_of(b));
+        let (p1, p2) = segments[a];
+        let (q1, q2) = segments[b];
+        if path_a != path_b {
+            //different paths can only touch at points in the boundary of both
+            return match intersection(p1, p2, q1, q2, ZInterpolation::Zero) {
+                SegmentIntersection::None => true,
+                SegmentIntersection::Point(c) => {
+                    is_boundary(paths[path_a], &c) && is_boundary(paths[path_b], &c)
+                }
+                SegmentIntersection::Collinear(..) => false,
+            };
+        }
+        let count = offsets[path_a + 1] - offsets[path_a];
+        let closed = is_closed(paths[path_a]);
+        if b == a + 1 {
+            //consecutive segments share a vertex and must not fold back over each other
+            !is_inside_segment(q2, p1, p2) && !is_inside_segment(p1, q1, q2)
+        } else if closed && a == offsets[path_a] && b == offsets[path_a] + count - 1 {
+            //the first and last segments of a closed path share the closing vertex
+            !is_inside_segment(q1, p1, p2) && !is_inside_segment(p2, q1, q2)
+        } else {
+            intersection(p1, p2, q1, q2, ZInterpolation::Zero) == SegmentIntersection::None
+        }
+    })
+}
+
+/// Returns the points where the path crosses or touches itself, possibly repeated.
+fn self_intersection_points(path: &[Coordinate]) -> Vec<Coordinate> {
+    let segments: Vec<(&Coordinate, &Coordinate)> =
+        path.windows(2).filter(|w| !w[0].equals_2d(&w[1])).map(|w| (&w[0], &w[1])).collect();
+    let closed = is_closed(path);
+    let mut points = Vec::new();
+    sweep_segment_pairs(&segments, |a, b| {
+        let (p1, p2) = segments[a];
+        let (q1, q2) = segments[b];
+        let consecutive = b == a + 1 || (closed && a == 0 && b == segments.len() - 1);
+        match intersection(p1, p2, q1, q2, ZInterpolation::Zero) {
+            SegmentIntersection::None => {}
+            //consecutive segments always meet at their shared vertex
+            SegmentIntersection::Point(c) => {
+                if !consecutive {
+                    points.push(c);
+                }
+            }
+            SegmentIntersection::Collinear(c, d) => points.extend([c, d]),
+        }
+        true
+    });
+    points
+}
+
 //tests
+/// Returns the geometry, panicking with its first validity error if it is not valid. Used by the
+/// construction macros.
+#[doc(hidden)]
+pub fn __validated(geometry: Geometry) -> Geometry {
+    if geometry.coords_count() > 0 {
+        if let Some(error) = geometry.validity_errors().first() {
+            panic!("Invalid geometry: {}", error);
+        }
+    }
+    geometry
+}
+
+/// Returns the rings closed by repeating their first coordinate where they are open. Used by the
+/// construction macros.
+#[doc(hidden)]
+pub fn __closed_rings(mut rings: Vec<Vec<Coordinate>>) -> Vec<Vec<Coordinate>> {
+    for ring in rings.iter_mut().filter(|ring| !ring.is_empty() && !is_closed(ring)) {
+        ring.push(ring[0].clone());
+    }
+    rings
+}
+
+/// Creates a coordinate from two or three values without the `Coordinate` type in scope. Used by
+/// the construction macros.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __coordinate {
+    ( $x:expr, $y:expr ) => {
+        $crate::coordinate::Coordinate::new($x as f64, $y as f64, 0.0)
+    };
+    ( $x:expr, $y:expr, $z:expr ) => {
+        $crate::coordinate::Coordinate::new($x as f64, $y as f64, $z as f64)
+    };
+}
+
+/// A macro for creating points from their x, y and optional z values.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::point;
+/// use geoms::geometry::Geometry;
+///
+/// assert_eq!(point!(1, 2.5), Geometry::point(1.0, 2.5));
+/// assert_eq!(point!(1, 2, 3).as_text(), "POINT Z (1 2 3)");
+/// ```
+#[macro_export]
+macro_rules! point {
+    ( $($value:expr),+ $(,)? ) => {
+        $crate::geometry::Geometry::Point { coordinates: $crate::__coordinate!($($value),+) }
+    };
+}
+
+/// A macro for creating line strings from their coordinates, given as tuples of two or three
+/// values. Panics if the line is not valid, such as a line with fewer than two distinct points.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::line_string;
+///
+/// let line = line_string![(0, 0), (1, 1), (2, 0)];
+/// assert_eq!(line.as_text(), "LINESTRING (0 0, 1 1, 2 0)");
+/// ```
+///
+/// ```should_panic
+/// use geoms::line_string;
+///
+/// line_string![(0, 0), (0, 0)];
+/// ```
+#[macro_export]
+macro_rules! line_string {
+    ( $( ( $($value:expr),+ ) ),* $(,)? ) => {
+        $crate::geometry::__validated($crate::geometry::Geometry::LineString {
+            coordinates: vec![$( $crate::__coordinate!($($value),+) ),*],
+        })
+    };
+}
+
+/// A macro for creating polygons, either from the coordinates of their shell, or with the
+/// `exterior:` and `interiors:` keys to also give holes. Coordinates are tuples of two or three
+/// values, and open rings are closed. Panics if the polygon is not valid, such as a polygon with
+/// a self intersecting ring or a hole outside of its shell.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::polygon;
+///
+/// let triangle = polygon![(0, 0), (4, 0), (0, 4)];
+/// assert_eq!(triangle.as_text(), "POLYGON ((0 0, 4 0, 0 4, 0 0))");
+///
+/// let framed = polygon!(
+///     exterior: [(0, 0), (4, 0), (4, 4), (0, 4)],
+///     interiors: [[(1, 1), (1, 3), (3, 3), (3, 1)]],
+/// );
+/// assert_eq!(framed.area(), 12.0);
+/// ```
+///
+/// ```should_panic
+/// use geoms::polygon;
+///
+/// //a bow tie
+/// polygon![(0, 0), (2, 2), (2, 0), (0, 2)];
+/// ```
+#[macro_export]
+macro_rules! polygon {
+    (
+        exterior: [ $( ( $($value:expr),+ ) ),* $(,)? ]
+        $(, interiors: [ $( [ $( ( $($hole_value:expr),+ ) ),* $(,)? ] ),* $(,)? ] )?
+        $(,)?
+    ) => {
+        $crate::geometry::__validated($crate::geometry::Geometry::Polygon {
+            coordinates: $crate::geometry::__closed_rings(vec![
+                vec![$( $crate::__coordinate!($($value),+) ),*]
+                $( $( , vec![$( $crate::__coordinate!($($hole_value),+) ),*] )* )?
+            ]),
+        })
+    };
+    ( $( ( $($value:expr),+ ) ),* $(,)? ) => {
+        $crate::polygon!(exterior: [ $( ( $($value),+ ) ),* ])
+    };
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -207,4 +2907,107 @@ pub mod tests {
 
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_self_intersections() {
+        //a closed line touching itself at a vertex, and a line crossing itself
+        let ring = Geometry::LinearRing { coordinates: vec![
+            coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(2, 0), coord!(0, 4), coord!(0, 0)
+        ] };
+        assert_eq!(ring.self_intersections(), Geometry::MultiPoint { coordinates: vec![coord!(2, 0)] });
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 1), coord!(4, 1), coord!(3, 0), coord!(3, 3), coord!(1, 0)] };
+        assert_eq!(line.self_intersections(), Geometry::MultiPoint { coordinates: vec![coord!(5.0 / 3.0, 1), coord!(3, 1)] });
+        assert!(!ring.is_simple());
+
+        //repeated vertices and the closing vertex are not intersections, and lines are checked
+        //one by one
+        let lines = Geometry::MultiLineString { coordinates: vec![
+            vec![coord!(0, 0), coord!(1, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)],
+            vec![coord!(0, 0), coord!(1, 1)],
+        ] };
+        assert_eq!(lines.self_intersections(), Geometry::MultiPoint { coordinates: vec![] });
+        assert_eq!(Geometry::Point { coordinates: coord!(0, 0) }.self_intersections(), Geometry::MultiPoint { coordinates: vec![] });
+    }
+
+    #[test]
+    fn test_surface_boundary() {
+        //the faces of a unit cube, whose edges are all shared by two faces
+        let face = |c: [[f64; 3]; 4]| vec![c.iter().chain([&c[0]]).map(|p| coord!(p[0], p[1], p[2])).collect::<Vec<_>>()];
+        let mut faces = vec![
+            face([[0., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 0., 0.]]),
+            face([[0., 0., 0.], [1., 0., 0.], [1., 0., 1.], [0., 0., 1.]]),
+            face([[1., 0., 0.], [1., 1., 0.], [1., 1., 1.], [1., 0., 1.]]),
+            face([[1., 1., 0.], [0., 1., 0.], [0., 1., 1.], [1., 1., 1.]]),
+            face([[0., 1., 0.], [0., 0., 0.], [0., 0., 1.], [0., 1., 1.]]),
+            face([[0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.]]),
+        ];
+        let cube = Geometry::PolyhedralSurface { coordinates: faces.clone() };
+        assert_eq!(cube.boundary(), Geometry::MultiLineString { coordinates: vec![] });
+
+        //without its lid, the boundary is the rim of the box
+        faces.pop();
+        let open_box = Geometry::PolyhedralSurface { coordinates: faces };
+        let rim = open_box.boundary();
+        assert_eq!(rim.num_components(), 1);
+        assert_eq!(rim.length(), 4.0);
+        assert!(rim.is_closed());
+        assert!(rim.coords_iter().all(|c| c.z() == 1.0));
+    }
+
+    #[test]
+    fn test_holes_of_collections() {
+        let square = |x: f64, size: f64| vec![coord!(x, x), coord!(x + size, x), coord!(x + size, x + size), coord!(x, x + size), coord!(x, x)];
+        let polygons = Geometry::MultiPolygon { coordinates: vec![
+            vec![square(0.0, 10.0), square(1.0, 1.0), square(3.0, 3.0)],
+            vec![square(20.0, 2.0)],
+            vec![],
+        ] };
+        let collection = Geometry::GeometryCollection { geometries: vec![polygons, Geometry::Point { coordinates: coord!(0, 0) }] };
+        assert_eq!(collection.hole_count(), 2);
+        assert_eq!(collection.holes_area(), 10.0);
+        assert_eq!(collection.filled_area(), 104.0);
+        let cleaned = collection.remove_small_holes(5.0);
+        assert_eq!(cleaned.hole_count(), 1);
+        assert_eq!(cleaned.area(), 95.0);
+        assert_eq!(cleaned.num_components(), collection.num_components());
+    }
+
+    #[test]
+    fn test_vertex_editing_of_rings() {
+        let mut ring = Geometry::LinearRing { coordinates: vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 0)] };
+        //inserting before the first vertex moves the closing coordinate too
+        ring.insert_vertex(0, coord!(-1, 1), true).unwrap();
+        assert_eq!(ring.as_text(), "LINEARRING (-1 1, 0 0, 2 0, 2 2, -1 1)");
+        ring.delete_vertex(0, true).unwrap();
+        assert_eq!(ring.as_text(), "LINEARRING (0 0, 2 0, 2 2, 0 0)");
+        assert!(ring.move_vertex(3, coord!(1, 1), false).is_err());
+        //without the check the edit is made even if it leaves the ring invalid
+        ring.move_vertex(1, coord!(0, 0), false).unwrap();
+        assert!(!ring.is_valid());
+        assert!(ring.move_vertex(1, coord!(2, 0), true).is_ok());
+
+        let mut polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 0)]] };
+        assert_eq!(polygon.move_ring_vertex(1, 0, coord!(1, 1), false).unwrap_err().to_string(), "invalid geometry: ring 1 out of range of 1 rings");
+        let mut point = Geometry::Point { coordinates: coord!(0, 0) };
+        assert!(point.delete_vertex(0, false).is_err());
+        assert!(polygon.delete_vertex(0, false).is_err());
+    }
+
+    #[test]
+    fn test_byte_size_of_nested_collections() {
+        let mut rings = Vec::with_capacity(4);
+        rings.push(vec![coord!(0, 0), coord!(1, 0), coord!(0, 1), coord!(0, 0)]);
+        let mut geometries = Vec::with_capacity(8);
+        geometries.push(Geometry::MultiPolygon { coordinates: vec![rings] });
+        geometries.push(Geometry::Point { coordinates: coord!(5, 5) });
+        let mut collection = Geometry::GeometryCollection { geometries };
+        let (geometry, coordinate, path) = (size_of::<Geometry>(), size_of::<Coordinate>(), size_of::<Vec<Coordinate>>());
+        //the collection, its members, the polygon, its rings and the coordinates of its shell
+        let trimmed = geometry + 2 * geometry + size_of::<Vec<Vec<Coordinate>>>() + path + 4 * coordinate;
+        assert_eq!(collection.byte_size(), trimmed + 6 * geometry + 3 * path);
+        let copy = collection.clone();
+        collection.shrink_to_fit();
+        assert_eq!(collection.byte_size(), trimmed);
+        assert_eq!(collection, copy);
+    }
+}
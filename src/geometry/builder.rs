@@ -0,0 +1,177 @@
+//! Incremental construction of geometries from streaming sources, such as parsers reading one
+//! ring at a time or digitizers adding one vertex at a time.
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::{Geometry, __closed_rings};
+
+/// Assembles a polygon from its shell and holes, given whole or vertex by vertex.
+#[derive(Debug, Default, Clone)]
+pub struct PolygonBuilder {
+    exterior: Option<Vec<Coordinate>>,
+    holes: Vec<Vec<Coordinate>>,
+    ring: Vec<Coordinate>,
+}
+
+impl PolygonBuilder {
+    /// Creates a builder without any ring.
+    pub fn new() -> PolygonBuilder {
+        PolygonBuilder::default()
+    }
+
+    /// Sets the shell of the polygon, replacing any previous one.
+    pub fn push_exterior(&mut self, ring: Vec<Coordinate>) -> &mut PolygonBuilder {
+        self.exterior = Some(ring);
+        self
+    }
+
+    /// Adds a hole to the polygon.
+    pub fn push_hole(&mut self, ring: Vec<Coordinate>) -> &mut PolygonBuilder {
+        self.holes.push(ring);
+        self
+    }
+
+    /// Adds a vertex to the ring in progress.
+    pub fn push_coordinate(&mut self, coordinate: Coordinate) -> &mut PolygonBuilder {
+        self.ring.push(coordinate);
+        self
+    }
+
+    /// Ends the ring in progress, which becomes the shell if there is none yet and a hole
+    /// otherwise. Does nothing if the ring has no vertices.
+    pub fn finish_ring(&mut self) -> &mut PolygonBuilder {
+        let ring = std::mem::take(&mut self.ring);
+        if ring.is_empty() {
+            return self;
+        }
+        match self.exterior {
+            None => self.exterior = Some(ring),
+            Some(_) => self.holes.push(ring),
+        }
+        self
+    }
+
+    /// Returns the polygon, ending the ring in progress and closing open rings, or the first
+    /// problem making it invalid. See [`crate::operation::valid::validity_errors`]. A builder
+    /// without rings builds an empty polygon, and a ring with less than three vertices, which
+    /// cannot be closed into an area, is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::builder::PolygonBuilder;
+    ///
+    /// let mut builder = PolygonBuilder::new();
+    /// builder.push_exterior(vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4)]);
+    /// for c in [coord!(1, 1), coord!(1, 2), coord!(2, 2), coord!(2, 1)] {
+    ///     builder.push_coordinate(c);
+    /// }
+    /// let polygon = builder.build().unwrap();
+    /// assert_eq!(polygon.as_text(), "POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 1 2, 2 2, 2 1, 1 1))");
+    ///
+    /// let mut builder = PolygonBuilder::new();
+    /// builder.push_exterior(vec![coord!(0, 0), coord!(4, 0), coord!(4, 4)]);
+    /// builder.push_hole(vec![coord!(5, 5), coord!(6, 5), coord!(6, 6)]);
+    /// assert_eq!(builder.build().unwrap_err().to_string(), "invalid geometry: hole outside of shell at (5 5)");
+    ///
+    /// let mut builder = PolygonBuilder::new();
+    /// builder.push_exterior(vec![coord!(0, 0), coord!(4, 0), coord!(4, 4)]).push_hole(vec![]);
+    /// assert_eq!(builder.build().unwrap_err().to_string(), "invalid geometry: ring 1 has 0 vertices, a ring needs at least three");
+    /// ```
+    pub fn build(mut self) -> Result<Geometry, Error> {
+        self.finish_ring();
+        let rings: Vec<Vec<Coordinate>> = match self.exterior {
+            None if self.holes.is_empty() => vec![],
+            exterior => std::iter::once(exterior.unwrap_or_default()).chain(self.holes).collect(),
+        };
+        for (i, ring) in rings.iter().enumerate() {
+            //the closing vertex of a closed ring is not counted
+            let closed = ring.len() > 1 && ring[0] == ring[ring.len() - 1];
+            let vertices = if closed { ring.len() - 1 } else { ring.len() };
+            if vertices < 3 {
+                return Err(Error::InvalidGeometry(format!("ring {} has {} vertices, a ring needs at least three", i, vertices)));
+            }
+        }
+        let coordinates = __closed_rings(rings);
+        let polygon = Geometry::Polygon { coordinates };
+        match polygon.validity_errors().into_iter().next() {
+            Some(error) => Err(Error::InvalidGeometry(error.to_string())),
+            None => Ok(polygon),
+        }
+    }
+}
+
+/// Assembles a geometry collection from members added one by one.
+#[derive(Debug, Default, Clone)]
+pub struct GeometryCollectionBuilder {
+    geometries: Vec<Geometry>,
+}
+
+impl GeometryCollectionBuilder {
+    /// Creates a builder without any member.
+    pub fn new() -> GeometryCollectionBuilder {
+        GeometryCollectionBuilder::default()
+    }
+
+    /// Adds a member to the collection.
+    pub fn push(&mut self, geometry: Geometry) -> &mut GeometryCollectionBuilder {
+        self.geometries.push(geometry);
+        self
+    }
+
+    /// Returns the number of members added so far.
+    pub fn len(&self) -> usize {
+        self.geometries.len()
+    }
+
+    /// Returns true if no member was added.
+    pub fn is_empty(&self) -> bool {
+        self.geometries.is_empty()
+    }
+
+    /// Returns the collection, or the first problem making one of its members invalid. See
+    /// [`crate::operation::valid::validity_errors`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::{point, polygon};
+    /// use geoms::geometry::builder::GeometryCollectionBuilder;
+    ///
+    /// let mut builder = GeometryCollectionBuilder::new();
+    /// builder.push(point!(5, 5)).push(polygon![(0, 0), (1, 0), (0, 1)]);
+    /// assert_eq!(builder.len(), 2);
+    /// assert_eq!(builder.build().unwrap().as_text(), "GEOMETRYCOLLECTION (POINT (5 5), POLYGON ((0 0, 1 0, 0 1, 0 0)))");
+    /// ```
+    pub fn build(self) -> Result<Geometry, Error> {
+        let collection = Geometry::GeometryCollection { geometries: self.geometries };
+        match collection.validity_errors().into_iter().next() {
+            Some(error) => Err(Error::InvalidGeometry(error.to_string())),
+            None => Ok(collection),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_rings_that_cannot_be_closed() {
+        let triangle = || vec![coord!(0, 0), coord!(4, 0), coord!(4, 4)];
+        let error = |builder: &mut PolygonBuilder| builder.clone().build().unwrap_err().to_string();
+        assert_eq!(error(PolygonBuilder::new().push_exterior(vec![])), "invalid geometry: ring 0 has 0 vertices, a ring needs at least three");
+        assert_eq!(error(PolygonBuilder::new().push_hole(triangle())), "invalid geometry: ring 0 has 0 vertices, a ring needs at least three");
+        assert_eq!(
+            error(PolygonBuilder::new().push_exterior(triangle()).push_hole(vec![coord!(1, 1), coord!(2, 1), coord!(1, 1)])),
+            "invalid geometry: ring 1 has 2 vertices, a ring needs at least three"
+        );
+        //open and closed rings of three vertices are both accepted
+        let mut closed = triangle();
+        closed.push(coord!(0, 0));
+        assert!(PolygonBuilder::new().push_exterior(closed).push_hole(vec![coord!(3, 1), coord!(3, 2), coord!(2, 1)]).clone().build().is_ok());
+    }
+}
@@ -0,0 +1,259 @@
+//! Editing sessions keeping a log of the edits made to a geometry, so they can be undone and
+//! redone as in an interactive editor.
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::{check_edit, Geometry, VertexEdit};
+use crate::io::wkt;
+
+/// A reversible edit, as applied to the geometry.
+#[derive(Debug, Clone)]
+enum Operation {
+    Vertex { sequence: usize, edit: VertexEdit },
+    //a line string split in two becomes a multi line string
+    Split { sequence: usize, vertex: usize },
+    //merging the last two lines of a multi line string made by a split gives back a line string
+    Merge { sequence: usize, demote: bool },
+}
+
+/// A geometry under edition, recording every edit along with its inverse so the edits can be
+/// undone and redone. Only the vertices touched by an edit are stored, never whole geometries.
+///
+/// The vertices are edited in sequences: the single sequence 0 of a line string or a linear ring,
+/// the rings of a polygon, with the shell first, or the lines of a multi line string. Rings are
+/// kept closed as in [`Geometry::move_vertex`], and lines can be split at a vertex or merged with
+/// the next line they end at.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::geometry::edit_session::GeometryEditSession;
+///
+/// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(2, 0)] };
+/// let mut session = GeometryEditSession::new(road.clone());
+/// session.move_vertex(0, 1, coord!(1, 1)).unwrap();
+/// session.split(0, 1).unwrap();
+/// assert_eq!(session.geometry().as_text(), "MULTILINESTRING ((0 0, 1 1), (1 1, 2 0))");
+///
+/// assert!(session.undo() && session.undo());
+/// assert_eq!(session.geometry(), &road);
+/// assert!(session.redo());
+/// assert_eq!(session.geometry().as_text(), "LINESTRING (0 0, 1 1, 2 0)");
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeometryEditSession {
+    geometry: Geometry,
+    check: bool,
+    //the edits done and undone, each as the edit and its inverse
+    done: Vec<(Operation, Operation)>,
+    undone: Vec<(Operation, Operation)>,
+}
+
+impl GeometryEditSession {
+    /// Starts a session on the geometry, without validity checks.
+    pub fn new(geometry: Geometry) -> GeometryEditSession {
+        GeometryEditSession { geometry, check: false, done: Vec::new(), undone: Vec::new() }
+    }
+
+    /// Returns the session refusing the edits that would make a line cross itself or leave the
+    /// geometry invalid if `check` is true.
+    pub fn with_checks(self, check: bool) -> GeometryEditSession {
+        GeometryEditSession { check, ..self }
+    }
+
+    /// Returns the geometry as edited so far.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Ends the session, returning the edited geometry.
+    pub fn into_geometry(self) -> Geometry {
+        self.geometry
+    }
+
+    /// Returns true if there is an edit to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Returns true if there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Moves a vertex of a sequence to the coordinate. Returns an error, leaving the geometry as it
+    /// was, if the sequence or the vertex does not exist or the edit fails the checks.
+    pub fn move_vertex(&mut self, sequence: usize, index: usize, coordinate: Coordinate) -> Result<(), Error> {
+        self.edit(Operation::Vertex { sequence, edit: VertexEdit::Move(index, coordinate) })
+    }
+
+    /// Inserts a vertex into a sequence at the index, so the new vertex has that index. See
+    /// [`GeometryEditSession::move_vertex`].
+    pub fn insert_vertex(&mut self, sequence: usize, index: usize, coordinate: Coordinate) -> Result<(), Error> {
+        self.edit(Operation::Vertex { sequence, edit: VertexEdit::Insert(index, coordinate) })
+    }
+
+    /// Deletes a vertex of a sequence. Lines keep at least two vertices and rings three. See
+    /// [`GeometryEditSession::move_vertex`].
+    pub fn delete_vertex(&mut self, sequence: usize, index: usize) -> Result<(), Error> {
+        self.edit(Operation::Vertex { sequence, edit: VertexEdit::Delete(index) })
+    }
+
+    /// Splits a line at one of its inner vertices into two lines sharing it, turning a line
+    /// string into a multi line string. See [`GeometryEditSession::move_vertex`].
+    pub fn split(&mut self, sequence: usize, vertex: usize) -> Result<(), Error> {
+        self.edit(Operation::Split { sequence, vertex })
+    }
+
+    /// Merges a line of a multi line string with the next one, which must start where it ends.
+    /// See [`GeometryEditSession::move_vertex`].
+    pub fn merge(&mut self, sequence: usize) -> Result<(), Error> {
+        self.edit(Operation::Merge { sequence, demote: false })
+    }
+
+    /// Undoes the last edit not undone yet, returning false if there is none.
+    pub fn undo(&mut self) -> bool {
+        let Some((edit, inverse)) = self.done.pop() else { return false };
+        apply(&mut self.geometry, inverse.clone()).expect("The inverse of an edit applies to its result");
+        self.undone.push((edit, inverse));
+        true
+    }
+
+    /// Redoes the last edit undone, returning false if there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some((edit, inverse)) = self.undone.pop() else { return false };
+        apply(&mut self.geometry, edit.clone()).expect("An undone edit applies again");
+        self.done.push((edit, inverse));
+        true
+    }
+
+    /// Applies a new edit and records it, forgetting the undone edits.
+    fn edit(&mut self, edit: Operation) -> Result<(), Error> {
+        let inverse = apply(&mut self.geometry, edit.clone())?;
+        if self.check {
+            if let Err(error) = check_edit(&self.geometry) {
+                apply(&mut self.geometry, inverse).expect("The inverse of an edit applies to its result");
+                return Err(error);
+            }
+        }
+        self.done.push((edit, inverse));
+        self.undone.clear();
+        Ok(())
+    }
+}
+
+/// Applies the edit to the geometry and returns its inverse, or returns an error leaving the
+/// geometry as it was.
+fn apply(geometry: &mut Geometry, edit: Operation) -> Result<Operation, Error> {
+    match edit {
+        Operation::Vertex { sequence, edit } => {
+            let (coordinates, closed) = sequence_mut(geometry, sequence)?;
+            let inverse = match (&edit, edit.clone().apply(coordinates, closed)?) {
+                (VertexEdit::Move(index, _), Some(replaced)) => VertexEdit::Move(*index, replaced),
+                (VertexEdit::Delete(index), Some(removed)) => VertexEdit::Insert(*index, removed),
+                (VertexEdit::Move(index, _) | VertexEdit::Insert(index, _) | VertexEdit::Delete(index), _) => {
+                    VertexEdit::Delete(*index)
+                }
+            };
+            Ok(Operation::Vertex { sequence, edit: inverse })
+        }
+        Operation::Split { sequence, vertex } => {
+            let promote = matches!(geometry, Geometry::LineString { .. });
+            let line = match geometry {
+                Geometry::LineString { coordinates } if sequence == 0 => &*coordinates,
+                Geometry::MultiLineString { coordinates } if sequence < coordinates.len() => &coordinates[sequence],
+                _ => return Err(Error::InvalidGeometry(format!("no line {} to split in a {}", sequence, wkt::tag(geometry)))),
+            };
+            if vertex == 0 || vertex + 1 >= line.len() {
+                return Err(Error::InvalidGeometry(format!("vertex {} is not an inner vertex of the line", vertex)));
+            }
+            let (first, second) = (line[..=vertex].to_vec(), line[vertex..].to_vec());
+            match geometry {
+                Geometry::MultiLineString { coordinates } => {
+                    coordinates[sequence] = first;
+                    coordinates.insert(sequence + 1, second);
+                }
+                _ => *geometry = Geometry::MultiLineString { coordinates: vec![first, second] },
+            }
+            Ok(Operation::Merge { sequence, demote: promote })
+        }
+        Operation::Merge { sequence, demote } => {
+            let Geometry::MultiLineString { coordinates } = geometry else {
+                return Err(Error::InvalidGeometry(format!("cannot merge the lines of a {}", wkt::tag(geometry))));
+            };
+            let joined = match (coordinates.get(sequence), coordinates.get(sequence + 1)) {
+                (Some(a), Some(b)) => matches!((a.last(), b.first()), (Some(end), Some(start)) if end.equals_2d(start)),
+                _ => return Err(Error::InvalidGeometry(format!("no lines {} and {} to merge", sequence, sequence + 1))),
+            };
+            if !joined {
+                return Err(Error::InvalidGeometry(format!("line {} does not end where the next one starts", sequence)));
+            }
+            let next = coordinates.remove(sequence + 1);
+            let vertex = coordinates[sequence].len() - 1;
+            coordinates[sequence].extend(next.into_iter().skip(1));
+            if demote && coordinates.len() == 1 {
+                *geometry = Geometry::LineString { coordinates: coordinates.pop().unwrap_or_default() };
+            }
+            Ok(Operation::Split { sequence, vertex })
+        }
+    }
+}
+
+/// Returns the coordinates of a sequence of the geometry, and whether it is a closed ring.
+fn sequence_mut(geometry: &mut Geometry, sequence: usize) -> Result<(&mut Vec<Coordinate>, bool), Error> {
+    let tag = wkt::tag(geometry);
+    let missing = || Error::InvalidGeometry(format!("no sequence {} of vertices in a {}", sequence, tag));
+    match geometry {
+        Geometry::LineString { coordinates } if sequence == 0 => Ok((coordinates, false)),
+        Geometry::LinearRing { coordinates } if sequence == 0 => Ok((coordinates, true)),
+        Geometry::Polygon { coordinates } => Ok((coordinates.get_mut(sequence).ok_or_else(missing)?, true)),
+        Geometry::MultiLineString { coordinates } => Ok((coordinates.get_mut(sequence).ok_or_else(missing)?, false)),
+        _ => Err(missing()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_undo_and_redo_of_ring_edits() {
+        let polygon = Geometry::Polygon { coordinates: vec![
+            vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+            vec![coord!(1, 1), coord!(1, 2), coord!(2, 2), coord!(2, 1), coord!(1, 1)],
+        ] };
+        let mut session = GeometryEditSession::new(polygon.clone()).with_checks(true);
+        session.insert_vertex(0, 1, coord!(2, -1)).unwrap();
+        session.delete_vertex(1, 2).unwrap();
+        session.move_vertex(1, 2, coord!(2, 1.5)).unwrap();
+        let edited = session.geometry().clone();
+        //the hole cannot leave the shell, and a failed edit is not recorded
+        assert!(session.move_vertex(1, 0, coord!(9, 9)).is_err());
+        assert_eq!(session.geometry(), &edited);
+        while session.undo() {}
+        assert_eq!(session.geometry(), &polygon);
+        assert!(!session.can_undo());
+        while session.redo() {}
+        assert_eq!(session.geometry(), &edited);
+        //a new edit forgets the undone ones
+        session.undo();
+        session.move_vertex(1, 0, coord!(1, 0.5)).unwrap();
+        assert!(!session.can_redo());
+
+        let mut lines = GeometryEditSession::new(Geometry::MultiLineString { coordinates: vec![
+            vec![coord!(0, 0), coord!(1, 0)],
+            vec![coord!(1, 0), coord!(2, 0), coord!(3, 0)],
+        ] });
+        lines.merge(0).unwrap();
+        assert_eq!(lines.geometry().as_text(), "MULTILINESTRING ((0 0, 1 0, 2 0, 3 0))");
+        assert!(lines.merge(0).is_err());
+        assert!(lines.split(0, 3).is_err());
+        lines.undo();
+        assert_eq!(lines.geometry().num_components(), 2);
+        assert!(lines.move_vertex(2, 0, coord!(0, 0)).is_err());
+    }
+}
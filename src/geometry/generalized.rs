@@ -0,0 +1,123 @@
+//! Geometries stored along with simplified versions of themselves, so renderers can draw every
+//! zoom level without simplifying on the fly.
+
+use crate::geometry::Geometry;
+use crate::operation::simplify::simplify;
+
+/// A geometry with a pyramid of levels simplified at increasing tolerances, computed once when
+/// it is built. The levels are all simplified from the original geometry, in parallel with the
+/// `parallel` feature, so their errors do not add up. See [`simplify`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::geometry::generalized::GeneralizedGeometry;
+///
+/// let coast = Geometry::LineString { coordinates: (0..=100).map(|i| coord!(i, (i % 7) as f64 * 0.1 + (i % 31) as f64)).collect() };
+/// let generalized = GeneralizedGeometry::new(coast.clone(), &[10.0, 0.5, 2.0]);
+/// assert_eq!(generalized.tolerances(), vec![0.5, 2.0, 10.0]);
+/// //the finest level whose error is not larger than asked for
+/// assert_eq!(generalized.at_tolerance(0.1), &coast);
+/// assert_eq!(generalized.at_tolerance(3.0), &generalized.levels()[1].1);
+/// assert!(generalized.at_tolerance(50.0).coords_count() < generalized.at_tolerance(1.0).coords_count());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct GeneralizedGeometry {
+    original: Geometry,
+    levels: Vec<(f64, Geometry)>,
+}
+
+impl GeneralizedGeometry {
+    /// Creates the levels of a geometry at the given tolerances, in any order. Repeated
+    /// tolerances give a single level, and a tolerance of zero none, as it is the original.
+    ///
+    /// # Arguments
+    ///
+    /// * `geometry` - The original geometry.
+    /// * `tolerances` - The tolerances of the levels, as the size of a pixel at every zoom level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a tolerance is negative or not a number.
+    pub fn new(geometry: Geometry, tolerances: &[f64]) -> GeneralizedGeometry {
+        if tolerances.iter().any(|t| t.is_nan() || *t < 0.0) {
+            panic!("The tolerances of the levels cannot be negative");
+        }
+        let mut tolerances: Vec<f64> = tolerances.iter().copied().filter(|&t| t > 0.0).collect();
+        tolerances.sort_by(f64::total_cmp);
+        tolerances.dedup();
+        #[cfg(feature = "parallel")]
+        let levels = {
+            use rayon::prelude::*;
+            tolerances.par_iter().map(|&t| (t, simplify(&geometry, t))).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let levels = tolerances.iter().map(|&t| (t, simplify(&geometry, t))).collect();
+        GeneralizedGeometry { original: geometry, levels }
+    }
+
+    /// Returns the original geometry.
+    pub fn original(&self) -> &Geometry {
+        &self.original
+    }
+
+    /// Returns the levels with their tolerances, from the finest to the coarsest.
+    pub fn levels(&self) -> &[(f64, Geometry)] {
+        &self.levels
+    }
+
+    /// Returns the tolerances of the levels, in increasing order.
+    pub fn tolerances(&self) -> Vec<f64> {
+        self.levels.iter().map(|level| level.0).collect()
+    }
+
+    /// Returns the coarsest level whose tolerance is not larger than the given one, or the
+    /// original geometry if there is none.
+    pub fn at_tolerance(&self, tolerance: f64) -> &Geometry {
+        match self.levels.partition_point(|level| level.0 <= tolerance) {
+            0 => &self.original,
+            n => &self.levels[n - 1].1,
+        }
+    }
+
+    /// Returns the original geometry, dropping the levels.
+    pub fn into_original(self) -> Geometry {
+        self.original
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_levels_match_direct_simplification() {
+        let ring: Vec<Coordinate> = (0..200)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / 200.0;
+                let radius = 100.0 + (i % 9) as f64 * 0.7 + (i % 23) as f64 * 0.2;
+                coord!(radius * angle.cos(), radius * angle.sin())
+            })
+            .chain([coord!(100, 0)])
+            .collect();
+        let lake = Geometry::Polygon { coordinates: vec![ring] };
+        let tolerances = [0.0, 8.0, 0.5, 2.0, 2.0, 500.0];
+        let generalized = GeneralizedGeometry::new(lake.clone(), &tolerances);
+        assert_eq!(generalized.tolerances(), vec![0.5, 2.0, 8.0, 500.0]);
+        for (tolerance, level) in generalized.levels() {
+            assert_eq!(level, &simplify(&lake, *tolerance));
+        }
+        //the counts of vertices go down the pyramid, until the lake disappears
+        let counts: Vec<usize> = [0.0, 0.5, 1.0, 2.0, 8.0, 1000.0].iter().map(|&t| generalized.at_tolerance(t).coords_count()).collect();
+        assert!(counts.windows(2).all(|w| w[0] >= w[1]), "{:?}", counts);
+        assert_eq!(counts[0], 201);
+        assert_eq!(generalized.at_tolerance(f64::INFINITY), &Geometry::Polygon { coordinates: vec![] });
+        assert_eq!(generalized.at_tolerance(-1.0), generalized.original());
+        assert_eq!(generalized.into_original(), lake);
+    }
+}
@@ -0,0 +1,146 @@
+//! Straight segments between two coordinates, the building block of lines and rings, with the
+//! robust intersection used by the overlay available on its own.
+
+use crate::algorithm::line_intersection::{self, SegmentIntersection, ZInterpolation};
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// A straight segment from a start coordinate to an end coordinate.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LineSegment {
+    start: Coordinate,
+    end: Coordinate,
+}
+
+impl LineSegment {
+    /// Creates a segment between two coordinates, which may be the same.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first end of the segment.
+    /// * `end` - The second end of the segment.
+    pub fn new(start: Coordinate, end: Coordinate) -> LineSegment {
+        LineSegment { start, end }
+    }
+
+    /// Returns the first end of the segment.
+    pub fn start(&self) -> &Coordinate {
+        &self.start
+    }
+
+    /// Returns the second end of the segment.
+    pub fn end(&self) -> &Coordinate {
+        &self.end
+    }
+
+    /// Returns the planar length of the segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::line_segment::LineSegment;
+    ///
+    /// assert_eq!(LineSegment::new(coord!(0, 0), coord!(3, 4)).length(), 5.0);
+    /// ```
+    pub fn length(&self) -> f64 {
+        self.start.distance_2d(&self.end)
+    }
+
+    /// Returns the planar intersection of the segment with another one: nothing, a single point,
+    /// or the overlap of collinear segments. Orientations are decided exactly and the crossing
+    /// point is computed in double-double arithmetic, so it always lies within the envelopes of
+    /// both segments. The z value of the intersection is the mean of the values interpolated
+    /// along both segments, see [`LineSegment::intersection_with_z`] for other policies.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The segment to intersect with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::line_segment::LineSegment;
+    /// use geoms::algorithm::line_intersection::SegmentIntersection;
+    ///
+    /// let a = LineSegment::new(coord!(0, 0), coord!(4, 4));
+    /// let b = LineSegment::new(coord!(0, 4), coord!(4, 0));
+    /// assert_eq!(a.intersection(&b), SegmentIntersection::Point(coord!(2, 2)));
+    ///
+    /// let c = LineSegment::new(coord!(2, 2), coord!(6, 6));
+    /// assert_eq!(a.intersection(&c), SegmentIntersection::Collinear(coord!(2, 2), coord!(4, 4)));
+    /// assert_eq!(b.intersection(&LineSegment::new(coord!(5, 5), coord!(6, 6))), SegmentIntersection::None);
+    /// ```
+    pub fn intersection(&self, other: &LineSegment) -> SegmentIntersection {
+        self.intersection_with_z(other, ZInterpolation::default())
+    }
+
+    /// Returns the planar intersection of the segment with another one, giving the intersection
+    /// the z value chosen by the policy. See [`LineSegment::intersection`].
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The segment to intersect with.
+    /// * `z` - The policy used to compute the z value of the intersection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::line_segment::LineSegment;
+    /// use geoms::algorithm::line_intersection::{SegmentIntersection, ZInterpolation};
+    ///
+    /// let road = LineSegment::new(coord!(0, 0, 10), coord!(4, 0, 30));
+    /// let bridge = LineSegment::new(coord!(1, -1, 50), coord!(1, 1, 50));
+    /// assert_eq!(road.intersection_with_z(&bridge, ZInterpolation::First), SegmentIntersection::Point(coord!(1, 0, 15)));
+    /// ```
+    pub fn intersection_with_z(&self, other: &LineSegment, z: ZInterpolation) -> SegmentIntersection {
+        line_intersection::intersection(&self.start, &self.end, &other.start, &other.end, z)
+    }
+
+    /// Returns true if the segment shares at least a point with another one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::line_segment::LineSegment;
+    ///
+    /// let a = LineSegment::new(coord!(0, 0), coord!(2, 0));
+    /// assert!(a.intersects(&LineSegment::new(coord!(2, 0), coord!(2, 2))));
+    /// assert!(!a.intersects(&LineSegment::new(coord!(3, 0), coord!(4, 0))));
+    /// ```
+    pub fn intersects(&self, other: &LineSegment) -> bool {
+        self.intersection_with_z(other, ZInterpolation::Zero) != SegmentIntersection::None
+    }
+}
+
+impl From<LineSegment> for Geometry {
+    fn from(segment: LineSegment) -> Geometry {
+        Geometry::LineString { coordinates: vec![segment.start, segment.end] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_intersection_of_nearly_parallel_segments() {
+        //the segments cross at a very shallow angle, far from their ends
+        let tiny = 2f64.powi(-30);
+        let a = LineSegment::new(coord!(0, 0), coord!(1048576, 1));
+        let b = LineSegment::new(coord!(0, tiny), coord!(1048576, 1.0 - tiny));
+        assert_eq!(a.intersection(&b), SegmentIntersection::Point(coord!(524288, 0.5)));
+        //a segment reduced to a point intersects the segments it lies on
+        let point = LineSegment::new(coord!(524288, 0.5), coord!(524288, 0.5));
+        assert!(a.intersects(&point));
+        assert_eq!(Geometry::from(point).coords_count(), 2);
+    }
+}
@@ -0,0 +1,129 @@
+//! Interning of geometries, so the same boundary repeated across many features, as the borders
+//! shared by the levels of administrative areas, is stored once.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::geometry::Geometry;
+
+/// A pool of geometries handing out shared handles, the same handle for geometries with the same
+/// type and coordinates. Coordinates are compared exactly, z included, so geometries differing by
+/// the order or the start of their vertices are kept apart.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::geometry::pool::GeometryPool;
+///
+/// let border = || Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 10)] };
+/// let mut pool = GeometryPool::new();
+/// let county = pool.intern(border());
+/// let state = pool.intern(border());
+/// assert!(Arc::ptr_eq(&county, &state));
+/// assert_eq!(pool.len(), 1);
+///
+/// drop((county, state));
+/// assert_eq!(pool.purge(), 1);
+/// assert!(pool.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GeometryPool {
+    geometries: HashSet<Arc<Geometry>>,
+}
+
+impl GeometryPool {
+    /// Creates an empty pool.
+    pub fn new() -> GeometryPool {
+        GeometryPool::default()
+    }
+
+    /// Returns the handle of the geometry in the pool, adding it if it is not there yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `geometry` - The geometry to intern.
+    pub fn intern(&mut self, geometry: Geometry) -> Arc<Geometry> {
+        match self.geometries.get(&geometry) {
+            Some(shared) => shared.clone(),
+            None => {
+                let shared = Arc::new(geometry);
+                self.geometries.insert(shared.clone());
+                shared
+            }
+        }
+    }
+
+    /// Returns the handle of a geometry equal to the given one, copying it into the pool only if
+    /// it is not there yet. See [`GeometryPool::intern`].
+    pub fn intern_ref(&mut self, geometry: &Geometry) -> Arc<Geometry> {
+        match self.geometries.get(geometry) {
+            Some(shared) => shared.clone(),
+            None => self.intern(geometry.clone()),
+        }
+    }
+
+    /// Returns the handle of a geometry equal to the given one, or None if it is not in the pool.
+    pub fn get(&self, geometry: &Geometry) -> Option<Arc<Geometry>> {
+        self.geometries.get(geometry).cloned()
+    }
+
+    /// Returns the number of distinct geometries in the pool.
+    pub fn len(&self) -> usize {
+        self.geometries.len()
+    }
+
+    /// Returns true if the pool has no geometries.
+    pub fn is_empty(&self) -> bool {
+        self.geometries.is_empty()
+    }
+
+    /// Removes the geometries no handle refers to anymore, returning how many were removed.
+    pub fn purge(&mut self) -> usize {
+        let before = self.geometries.len();
+        self.geometries.retain(|shared| Arc::strong_count(shared) > 1);
+        before - self.geometries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_interning_of_a_hierarchy() {
+        let square = |x: f64, y: f64, size: f64| Geometry::Polygon { coordinates: vec![vec![
+            coord!(x, y), coord!(x + size, y), coord!(x + size, y + size), coord!(x, y + size), coord!(x, y)
+        ]] };
+        let mut pool = GeometryPool::new();
+        //every district repeats the boundary of its region, and the regions the country
+        let handles: Vec<Arc<Geometry>> = (0..100)
+            .flat_map(|i| [square(0.0, 0.0, 100.0), square((i % 10) as f64 * 10.0, 0.0, 10.0), square(0.0, 0.0, 100.0)])
+            .map(|g| pool.intern(g))
+            .collect();
+        assert_eq!(pool.len(), 11);
+        assert_eq!(Arc::strong_count(&handles[0]), 201);
+        //the type and the z of the coordinates tell geometries apart
+        let ring = Geometry::LinearRing { coordinates: square(0.0, 0.0, 100.0).coords_iter().cloned().collect() };
+        let raised = Geometry::Point { coordinates: coord!(0, 0, 1) };
+        assert!(pool.get(&ring).is_none());
+        assert!(!Arc::ptr_eq(&pool.intern_ref(&raised), &pool.intern(Geometry::Point { coordinates: coord!(0, 0) })));
+        assert_eq!(pool.purge(), 2);
+        drop(handles);
+        assert_eq!(pool.purge(), 11);
+    }
+
+    #[test]
+    fn test_signed_zeros_are_interned_once() {
+        let mut pool = GeometryPool::new();
+        let positive = pool.intern(Geometry::Point { coordinates: coord!(0, 0, 0) });
+        let negative = pool.intern(Geometry::Point { coordinates: coord!(-0.0, -0.0, -0.0) });
+        assert!(Arc::ptr_eq(&positive, &negative));
+        assert_eq!(pool.len(), 1);
+    }
+}
@@ -0,0 +1,152 @@
+//! Geometries shared between threads along with caches computed on their first use, so parallel
+//! pipelines testing many points against the same areas index them once.
+
+use std::sync::{Arc, OnceLock};
+
+use crate::algorithm::point_in_polygon::{IndexedPointInAreaLocator, Location};
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+
+/// A shared geometry with its envelope and an index of its polygons, each computed the first time
+/// it is needed and kept in a `OnceLock`. The caches are filled at most once even when threads
+/// race to use them, so a prepared geometry behind a reference or an `Arc` can be queried from
+/// any number of threads. The geometry cannot change, as the caches would go stale.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::geometry::prepared::PreparedGeometry;
+/// use geoms::algorithm::point_in_polygon::Location;
+///
+/// let park = Arc::new(Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)]] });
+/// let prepared = Arc::new(PreparedGeometry::new(park.clone()));
+/// let shared = prepared.clone();
+/// let inside = std::thread::spawn(move || shared.covers_point(&coord!(5, 5)));
+/// assert!(inside.join().unwrap());
+/// assert_eq!(prepared.locate(&coord!(10, 5)), Location::Boundary);
+/// assert!(Arc::ptr_eq(prepared.geometry(), &park));
+/// ```
+pub struct PreparedGeometry {
+    geometry: Arc<Geometry>,
+    envelope: OnceLock<Envelope>,
+    locator: OnceLock<IndexedPointInAreaLocator>,
+}
+
+impl PreparedGeometry {
+    /// Prepares a geometry, owned or already shared. Nothing is computed until it is queried.
+    pub fn new(geometry: impl Into<Arc<Geometry>>) -> PreparedGeometry {
+        PreparedGeometry { geometry: geometry.into(), envelope: OnceLock::new(), locator: OnceLock::new() }
+    }
+
+    /// Returns the shared geometry.
+    pub fn geometry(&self) -> &Arc<Geometry> {
+        &self.geometry
+    }
+
+    /// Returns the envelope of the geometry, computed on the first call.
+    pub fn envelope(&self) -> &Envelope {
+        self.envelope.get_or_init(|| self.geometry.envelope())
+    }
+
+    /// Returns the index of the polygons of the geometry, built on the first call.
+    pub fn locator(&self) -> &IndexedPointInAreaLocator {
+        self.locator.get_or_init(|| IndexedPointInAreaLocator::new(&self.geometry))
+    }
+
+    /// Returns the location of the point relative to the polygons of the geometry. Points outside
+    /// the envelope are exterior without building the index.
+    pub fn locate(&self, point: &Coordinate) -> Location {
+        if !self.envelope().contains_coordinate(point) {
+            return Location::Exterior;
+        }
+        self.locator().locate(point)
+    }
+
+    /// Returns true if the point is inside or on the boundary of the polygons of the geometry.
+    pub fn covers_point(&self, point: &Coordinate) -> bool {
+        self.locate(point) != Location::Exterior
+    }
+
+    /// Returns the shared geometry, dropping the caches.
+    pub fn into_geometry(self) -> Arc<Geometry> {
+        self.geometry
+    }
+}
+
+impl From<Geometry> for PreparedGeometry {
+    fn from(geometry: Geometry) -> Self {
+        PreparedGeometry::new(geometry)
+    }
+}
+
+impl From<Arc<Geometry>> for PreparedGeometry {
+    fn from(geometry: Arc<Geometry>) -> Self {
+        PreparedGeometry::new(geometry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Barrier;
+
+    use super::*;
+    use crate::coord;
+
+    fn grid() -> Geometry {
+        Geometry::MultiPolygon {
+            coordinates: (0..100)
+                .map(|i| {
+                    let (x, y) = ((i % 10) as f64 * 2.0, (i / 10) as f64 * 2.0);
+                    vec![vec![coord!(x, y), coord!(x + 1.0, y), coord!(x + 1.0, y + 1.0), coord!(x, y + 1.0), coord!(x, y)]]
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_caches_filled_once_by_racing_threads() {
+        let prepared = PreparedGeometry::new(grid());
+        let start = Barrier::new(8);
+        let (envelopes, locators): (Vec<usize>, Vec<usize>) = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        start.wait();
+                        (prepared.envelope() as *const Envelope as usize, prepared.locator() as *const IndexedPointInAreaLocator as usize)
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).unzip()
+        });
+        //every thread got the same caches
+        assert!(envelopes.iter().all(|&e| e == envelopes[0]));
+        assert!(locators.iter().all(|&l| l == locators[0]));
+        assert_eq!(*prepared.envelope(), Envelope::new(0.0, 0.0, 19.0, 19.0));
+    }
+
+    #[test]
+    fn test_queries_from_threads_agree_with_the_geometry() {
+        let geometry = Arc::new(grid());
+        let prepared = Arc::new(PreparedGeometry::from(geometry.clone()));
+        let points: Vec<Coordinate> = (0..400).map(|i| coord!((i % 20) as f64 + 0.5, (i / 20) as f64 + 0.5)).collect();
+        let workers: Vec<_> = points
+            .chunks(100)
+            .map(|chunk| {
+                let (prepared, chunk) = (prepared.clone(), chunk.to_vec());
+                std::thread::spawn(move || chunk.iter().filter(|p| prepared.covers_point(p)).count())
+            })
+            .collect();
+        let covered: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+        assert_eq!(covered, points.iter().filter(|p| geometry.contains_point(p)).count());
+        assert_eq!(covered, 100);
+        //the threads are done, so the geometry is shared by the test and the prepared geometry only
+        let prepared = Arc::into_inner(prepared).unwrap();
+        assert_eq!(Arc::strong_count(&geometry), 2);
+        assert!(Arc::ptr_eq(&prepared.into_geometry(), &geometry));
+    }
+}
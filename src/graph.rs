@@ -0,0 +1,527 @@
+//! Networks of nodes joined by lines, such as roads or pipes, built from noded linework.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::cluster::DisjointSet;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::index::kdtree::KdTree;
+
+/// A line of the network joining two nodes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Edge {
+    /// The node at the start of the line.
+    pub from: usize,
+    /// The node at the end of the line.
+    pub to: usize,
+    /// The coordinates of the line, starting and ending at its nodes.
+    pub coordinates: Vec<Coordinate>,
+    /// The planar length of the line.
+    pub length: f64,
+    /// The index of the geometry the line comes from.
+    pub source: usize,
+}
+
+/// A path through the network found by a shortest path search.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Route {
+    /// The nodes visited, from the start to the end.
+    pub nodes: Vec<usize>,
+    /// The edges followed, one fewer than the nodes.
+    pub edges: Vec<usize>,
+    /// The sum of the weights of the edges followed.
+    pub cost: f64,
+    /// The lines of the edges joined end to end in the direction of travel, an empty line string
+    /// when the start is the end.
+    pub line: Geometry,
+}
+
+/// An undirected graph whose edges are lines joining the nodes at their ends.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Graph {
+    nodes: Vec<Coordinate>,
+    edges: Vec<Edge>,
+    incident: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// Builds the graph of noded linework, where lines only meet at their ends. Every line string,
+    /// and every line of a multi line string, is an edge between the nodes at its ends. Ends within
+    /// the tolerance of each other, directly or through other ends, are merged into a single node
+    /// placed at the first of them, and the lines are snapped to it. Other geometries are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The linework.
+    /// * `tolerance` - The distance within which ends are merged, zero to merge only equal ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::graph::Graph;
+    ///
+    /// let lines = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(10.01, 0), coord!(10, 10)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(50, 50), coord!(60, 50)] },
+    /// ];
+    /// let graph = Graph::new(&lines, 0.1);
+    /// assert_eq!((graph.nodes().len(), graph.edges().len()), (5, 3));
+    /// assert_eq!(graph.edges()[1].coordinates[0], coord!(10, 0));
+    /// assert_eq!(graph.connected_components().len(), 2);
+    /// ```
+    pub fn new(lines: &[Geometry], tolerance: f64) -> Graph {
+        let mut paths: Vec<(&[Coordinate], usize)> = Vec::new();
+        for (source, geometry) in lines.iter().enumerate() {
+            match geometry {
+                Geometry::LineString { coordinates } => paths.push((coordinates, source)),
+                Geometry::MultiLineString { coordinates } => {
+                    paths.extend(coordinates.iter().map(|line| (line.as_slice(), source)))
+                }
+                _ => {}
+            }
+        }
+        paths.retain(|(path, _)| path.len() >= 2);
+
+        //the ends of every path, merged with the ones within the tolerance
+        let ends: Vec<Coordinate> =
+            paths.iter().flat_map(|(path, _)| [path[0].clone(), path[path.len() - 1].clone()]).collect();
+        let tree = KdTree::new(&ends);
+        let mut sets = DisjointSet::new(ends.len());
+        for (i, end) in ends.iter().enumerate() {
+            for j in tree.query_radius(end, tolerance) {
+                //the smallest index stays the root, so nodes are placed at the first end
+                sets.union_into(i.max(j), i.min(j));
+            }
+        }
+        let mut node_of = vec![usize::MAX; ends.len()];
+        let mut nodes = Vec::new();
+        for i in 0..ends.len() {
+            let r = sets.find(i);
+            if node_of[r] == usize::MAX {
+                node_of[r] = nodes.len();
+                nodes.push(ends[r].clone());
+            }
+            node_of[i] = node_of[r];
+        }
+
+        let mut incident = vec![Vec::new(); nodes.len()];
+        let mut edges = Vec::with_capacity(paths.len());
+        for (k, (path, source)) in paths.into_iter().enumerate() {
+            let (from, to) = (node_of[2 * k], node_of[2 * k + 1]);
+            let mut coordinates = path.to_vec();
+            let last = coordinates.len() - 1;
+            coordinates[0] = nodes[from].clone();
+            coordinates[last] = nodes[to].clone();
+            let length = coordinates.windows(2).map(|w| (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y())).sum();
+            incident[from].push(edges.len());
+            if to != from {
+                incident[to].push(edges.len());
+            }
+            edges.push(Edge { from, to, coordinates, length, source });
+        }
+        Graph { nodes, edges, incident }
+    }
+
+    /// Returns the locations of the nodes.
+    pub fn nodes(&self) -> &[Coordinate] {
+        &self.nodes
+    }
+
+    /// Returns the edges.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Returns the indices of the edges touching a node.
+    pub fn incident_edges(&self, node: usize) -> &[usize] {
+        &self.incident[node]
+    }
+
+    /// Returns the number of edge ends at a node, a loop counting twice.
+    pub fn degree(&self, node: usize) -> usize {
+        self.incident[node]
+            .iter()
+            .map(|&e| if self.edges[e].from == self.edges[e].to { 2 } else { 1 })
+            .sum()
+    }
+
+    /// Returns the nodes joined to a node by an edge, without repetitions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::graph::Graph;
+    ///
+    /// let lines = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 10)] },
+    /// ];
+    /// let graph = Graph::new(&lines, 0.0);
+    /// assert_eq!(graph.neighbours(0), vec![1, 2]);
+    /// assert_eq!(graph.degree(0), 2);
+    /// ```
+    pub fn neighbours(&self, node: usize) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self.incident[node]
+            .iter()
+            .map(|&e| if self.edges[e].from == node { self.edges[e].to } else { self.edges[e].from })
+            .collect();
+        neighbours.sort();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// Returns the node closest to a coordinate, or None if the graph has no nodes.
+    pub fn nearest_node(&self, coordinate: &Coordinate) -> Option<usize> {
+        KdTree::new(&self.nodes).nearest(coordinate)
+    }
+
+    /// Returns the nodes of every connected part of the graph, each part sorted and the parts
+    /// ordered by their first node.
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let labels = self.component_labels();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for (node, &label) in labels.iter().enumerate() {
+            if label == components.len() {
+                components.push(Vec::new());
+            }
+            components[label].push(node);
+        }
+        components
+    }
+
+    /// Returns true if a path of edges joins the two nodes.
+    pub fn is_connected(&self, a: usize, b: usize) -> bool {
+        let labels = self.component_labels();
+        labels[a] == labels[b]
+    }
+
+    /// Returns the label of the connected part of every node, numbered in the order of their first node.
+    fn component_labels(&self) -> Vec<usize> {
+        let mut labels = vec![usize::MAX; self.nodes.len()];
+        let mut count = 0;
+        for start in 0..self.nodes.len() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            labels[start] = count;
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for neighbour in self.neighbours(node) {
+                    if labels[neighbour] == usize::MAX {
+                        labels[neighbour] = count;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            count += 1;
+        }
+        labels
+    }
+
+    /// Returns the nodes with a single edge end, the dead ends of the network, which often reveal
+    /// lines that fail to meet.
+    pub fn dangles(&self) -> Vec<usize> {
+        (0..self.nodes.len()).filter(|&node| self.degree(node) == 1).collect()
+    }
+
+    /// Returns the edges as pairs of nodes weighted by their length, in the form taken by the
+    /// constructors of other graph libraries such as `petgraph::Graph::from_edges`. With the
+    /// `petgraph` feature, `to_petgraph` converts the whole graph instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::graph::Graph;
+    ///
+    /// let lines = vec![Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(3, 4)] }];
+    /// assert_eq!(Graph::new(&lines, 0.0).edge_list(), vec![(0, 1, 5.0)]);
+    /// ```
+    pub fn edge_list(&self) -> Vec<(usize, usize, f64)> {
+        self.edges.iter().map(|e| (e.from, e.to, e.length)).collect()
+    }
+
+    /// Returns the graph as an undirected petgraph graph, to run the algorithms of petgraph on the
+    /// network. The node weights are the coordinates of the nodes and the edge weights the edges,
+    /// and both keep their indices, so node `i` of this graph is `NodeIndex::new(i)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::graph::Graph;
+    /// use petgraph::algo::dijkstra;
+    /// use petgraph::graph::NodeIndex;
+    /// use petgraph::visit::EdgeRef;
+    ///
+    /// let lines = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(3, 4)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(3, 4), coord!(3, 10)] },
+    /// ];
+    /// let network = Graph::new(&lines, 0.0).to_petgraph();
+    /// assert_eq!(network[NodeIndex::new(1)], coord!(3, 4));
+    /// let costs = dijkstra(&network, NodeIndex::new(0), None, |e| e.weight().length);
+    /// assert_eq!(costs[&NodeIndex::new(2)], 11.0);
+    /// ```
+    #[cfg(feature = "petgraph")]
+    pub fn to_petgraph(&self) -> petgraph::graph::UnGraph<Coordinate, Edge> {
+        let mut graph = petgraph::graph::UnGraph::with_capacity(self.nodes.len(), self.edges.len());
+        for node in &self.nodes {
+            graph.add_node(node.clone());
+        }
+        for edge in &self.edges {
+            graph.add_edge(petgraph::graph::NodeIndex::new(edge.from), petgraph::graph::NodeIndex::new(edge.to), edge.clone());
+        }
+        graph
+    }
+
+    /// Returns the shortest route between two nodes, weighting the edges by their length, or None
+    /// if they are not connected. The search is an A* guided by the straight distance to the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::graph::Graph;
+    ///
+    /// //a direct road with a bend, and a longer one through a third town
+    /// let lines = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 2), coord!(10, 0)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 8)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(10, 0), coord!(5, 8)] },
+    /// ];
+    /// let graph = Graph::new(&lines, 0.0);
+    /// let route = graph.shortest_path(1, 0).unwrap();
+    /// assert_eq!((route.nodes, route.edges), (vec![1, 0], vec![0]));
+    /// assert_eq!(route.line.as_text(), "LINESTRING (10 0, 5 2, 0 0)");
+    /// ```
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Route> {
+        let target = &self.nodes[to];
+        self.search(from, to, |edge| edge.length, |node| {
+            (target.x() - node.x()).hypot(target.y() - node.y())
+        })
+    }
+
+    /// Returns the route between two nodes with the smallest sum of edge weights, or None if they
+    /// are not connected. The search is a Dijkstra.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start node.
+    /// * `to` - The end node.
+    /// * `weight` - The cost of following an edge. It must not be negative, and edges with an
+    ///   infinite cost are never followed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::graph::Graph;
+    ///
+    /// let lines = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 2), coord!(10, 0)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 8)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(10, 0), coord!(5, 8)] },
+    /// ];
+    /// let graph = Graph::new(&lines, 0.0);
+    /// //the direct road is closed
+    /// let route = graph.shortest_path_by(0, 1, |edge| if edge.source == 0 { f64::INFINITY } else { edge.length }).unwrap();
+    /// assert_eq!(route.nodes, vec![0, 2, 1]);
+    /// assert_eq!(route.line.as_text(), "LINESTRING (0 0, 5 8, 10 0)");
+    /// ```
+    pub fn shortest_path_by<F: FnMut(&Edge) -> f64>(&self, from: usize, to: usize, weight: F) -> Option<Route> {
+        self.search(from, to, weight, |_| 0.0)
+    }
+
+    /// Returns the cheapest route between two nodes, exploring the nodes in the order of their
+    /// cost from the start plus an estimate of their cost to the end, which must not overestimate it.
+    fn search<F, H>(&self, from: usize, to: usize, mut weight: F, heuristic: H) -> Option<Route>
+    where
+        F: FnMut(&Edge) -> f64,
+        H: Fn(&Coordinate) -> f64,
+    {
+        //the cost of the cheapest known route to every node, and the edge it arrives by
+        let mut reached = vec![(f64::INFINITY, None); self.nodes.len()];
+        let mut done = vec![false; self.nodes.len()];
+        reached[from].0 = 0.0;
+        let mut queue = BinaryHeap::new();
+        queue.push(Reached(heuristic(&self.nodes[from]), from));
+        while let Some(Reached(_, node)) = queue.pop() {
+            if done[node] {
+                continue;
+            }
+            done[node] = true;
+            if node == to {
+                break;
+            }
+            for &e in &self.incident[node] {
+                let edge = &self.edges[e];
+                let cost = weight(edge);
+                if cost < 0.0 {
+                    panic!("Edge weights must not be negative");
+                }
+                let other = if edge.from == node { edge.to } else { edge.from };
+                let through = reached[node].0 + cost;
+                if through < reached[other].0 {
+                    reached[other] = (through, Some(e));
+                    queue.push(Reached(through + heuristic(&self.nodes[other]), other));
+                }
+            }
+        }
+        if !done[to] {
+            return None;
+        }
+
+        //the edges walked back from the end
+        let mut nodes = vec![to];
+        let mut edges = Vec::new();
+        let mut node = to;
+        while let Some(e) = reached[node].1 {
+            edges.push(e);
+            node = if self.edges[e].to == node { self.edges[e].from } else { self.edges[e].to };
+            nodes.push(node);
+        }
+        nodes.reverse();
+        edges.reverse();
+        let mut coordinates: Vec<Coordinate> = Vec::new();
+        for (&e, &start) in edges.iter().zip(&nodes) {
+            let edge = &self.edges[e];
+            let part: Box<dyn Iterator<Item = &Coordinate>> =
+                if edge.from == start { Box::new(edge.coordinates.iter()) } else { Box::new(edge.coordinates.iter().rev()) };
+            //every part starts where the previous one ends
+            let skip = if coordinates.is_empty() { 0 } else { 1 };
+            coordinates.extend(part.skip(skip).cloned());
+        }
+        Some(Route { nodes, edges, cost: reached[to].0, line: Geometry::LineString { coordinates } })
+    }
+}
+
+/// A node reached at a cost, ordered so that the cheapest comes out of a heap first.
+struct Reached(f64, usize);
+
+impl PartialEq for Reached {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Reached {}
+
+impl PartialOrd for Reached {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Reached {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_chained_ends_and_loops() {
+        //a ring drawn as three lines with slightly misplaced ends, a loop, and a lone line
+        let lines = vec![
+            Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+            Geometry::MultiLineString { coordinates: vec![
+                vec![coord!(10.05, 0), coord!(5, 5), coord!(0.05, 0.05)],
+                vec![coord!(0, 0.1), coord!(0, -10), coord!(0, 0.1)],
+            ] },
+            Geometry::Point { coordinates: coord!(0, 0) },
+            Geometry::LineString { coordinates: vec![coord!(20, 0), coord!(30, 0)] },
+        ];
+        let graph = Graph::new(&lines, 0.08);
+        assert_eq!(graph.nodes(), &[coord!(0, 0), coord!(10, 0), coord!(20, 0), coord!(30, 0)]);
+        assert_eq!(graph.edges().iter().map(|e| (e.from, e.to, e.source)).collect::<Vec<_>>(),
+            vec![(0, 1, 0), (1, 0, 1), (0, 0, 1), (2, 3, 3)]);
+        assert_eq!(graph.edges()[2].coordinates, vec![coord!(0, 0), coord!(0, -10), coord!(0, 0)]);
+        assert_eq!(graph.degree(0), 4);
+        assert_eq!(graph.neighbours(0), vec![0, 1]);
+        assert_eq!(graph.dangles(), vec![2, 3]);
+        assert_eq!(graph.connected_components(), vec![vec![0, 1], vec![2, 3]]);
+        assert!(graph.is_connected(1, 0) && !graph.is_connected(1, 2));
+        assert_eq!(graph.nearest_node(&coord!(18, 1)), Some(2));
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let graph = Graph::new(&[Geometry::LineString { coordinates: vec![coord!(0, 0)] }], 1.0);
+        assert!(graph.nodes().is_empty() && graph.edges().is_empty());
+        assert!(graph.connected_components().is_empty());
+        assert_eq!(graph.nearest_node(&coord!(0, 0)), None);
+    }
+
+    #[test]
+    fn test_astar_agrees_with_dijkstra() {
+        //a grid with crooked streets, so that lengths differ from straight distances
+        let mut lines = Vec::new();
+        for i in 0..6 {
+            for j in 0..6 {
+                let (x, y) = (i as f64 * 10.0, j as f64 * 10.0);
+                let bend = ((i * 7 + j * 3) % 5) as f64;
+                lines.push(Geometry::LineString { coordinates: vec![coord!(x, y), coord!(x + 5.0, y + bend), coord!(x + 10.0, y)] });
+                lines.push(Geometry::LineString { coordinates: vec![coord!(x, y), coord!(x + bend, y + 5.0), coord!(x, y + 10.0)] });
+            }
+        }
+        let graph = Graph::new(&lines, 0.0);
+        for (from, to) in [(0, 40), (3, 17), (25, 2), (11, 11)] {
+            let astar = graph.shortest_path(from, to).unwrap();
+            let dijkstra = graph.shortest_path_by(from, to, |edge| edge.length).unwrap();
+            assert!((astar.cost - dijkstra.cost).abs() < 1e-9, "{} {}", from, to);
+            assert!((astar.line.length() - astar.cost).abs() < 1e-9);
+            assert_eq!(astar.nodes.first(), Some(&from));
+            assert_eq!(astar.nodes.last(), Some(&to));
+        }
+        let same = graph.shortest_path(11, 11).unwrap();
+        assert_eq!((same.nodes, same.cost, same.line), (vec![11], 0.0, Geometry::LineString { coordinates: vec![] }));
+        let closed = graph.shortest_path_by(0, 1, |_| f64::INFINITY);
+        assert_eq!(closed, None);
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn test_petgraph_routes_agree() {
+        use petgraph::graph::NodeIndex;
+        use petgraph::visit::EdgeRef;
+
+        let lines: Vec<Geometry> = (0..5)
+            .flat_map(|i| {
+                let x = i as f64 * 10.0;
+                vec![
+                    Geometry::LineString { coordinates: vec![coord!(x, 0), coord!(x + 5.0, 3), coord!(x + 10.0, 0)] },
+                    Geometry::LineString { coordinates: vec![coord!(x, 0), coord!(x, 10), coord!(x + 10.0, 0)] },
+                ]
+            })
+            .collect();
+        let graph = Graph::new(&lines, 0.0);
+        let network = graph.to_petgraph();
+        assert_eq!((network.node_count(), network.edge_count()), (graph.nodes().len(), graph.edges().len()));
+        assert!(network.edge_references().all(|e| graph.edges()[e.id().index()] == *e.weight()));
+        let costs = petgraph::algo::dijkstra(&network, NodeIndex::new(0), None, |e| e.weight().length);
+        for node in 0..graph.nodes().len() {
+            assert!((costs[&NodeIndex::new(node)] - graph.shortest_path(0, node).unwrap().cost).abs() < 1e-9);
+        }
+    }
+}
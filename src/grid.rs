@@ -0,0 +1,237 @@
+use crate::contour::contour;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+
+/// A regular grid of square cells holding one value each, such as an elevation model.
+/// Rows run from the top of the grid to its bottom and columns from left to right, and the values
+/// are stored row by row. Cells without data hold NaN.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Grid {
+    min_x: f64,
+    max_y: f64,
+    resolution: f64,
+    columns: usize,
+    rows: usize,
+    values: Vec<f64>,
+}
+
+impl Grid {
+    /// Creates a grid without data covering the envelope, anchored at its upper left corner. The
+    /// last column and row are extended past the envelope when its size is not a multiple of the
+    /// resolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `envelope` - The area covered by the grid. It must not be empty.
+    /// * `resolution` - The size of the cells. It must be positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::grid::Grid;
+    ///
+    /// let grid = Grid::new(&Envelope::new(0.0, 0.0, 10.0, 5.0), 2.0);
+    /// assert_eq!((grid.columns(), grid.rows()), (5, 3));
+    /// assert_eq!(grid.envelope(), Envelope::new(0.0, -1.0, 10.0, 5.0));
+    /// assert!(grid.get(0, 0).unwrap().is_nan());
+    /// ```
+    ///
+    /// ```should_panic
+    /// use geoms::envelope::Envelope;
+    /// use geoms::grid::Grid;
+    ///
+    /// Grid::new(&Envelope::new(0.0, 0.0, 10.0, 5.0), 0.0);
+    /// ```
+    pub fn new(envelope: &Envelope, resolution: f64) -> Grid {
+        if envelope.is_empty() {
+            panic!("A grid cannot have an empty envelope");
+        }
+        if !(resolution.is_finite() && resolution > 0.0) {
+            panic!("A grid resolution must be positive");
+        }
+        let columns = ((envelope.width() / resolution).ceil() as usize).max(1);
+        let rows = ((envelope.height() / resolution).ceil() as usize).max(1);
+        Grid {
+            min_x: envelope.min_x(),
+            max_y: envelope.max_y(),
+            resolution,
+            columns,
+            rows,
+            values: vec![f64::NAN; columns * rows],
+        }
+    }
+
+    /// Returns the size of the cells.
+    pub fn resolution(&self) -> f64 {
+        self.resolution
+    }
+
+    /// Returns the number of columns of the grid.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the number of rows of the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the area covered by the cells of the grid.
+    pub fn envelope(&self) -> Envelope {
+        Envelope::new(
+            self.min_x,
+            self.max_y - self.rows as f64 * self.resolution,
+            self.min_x + self.columns as f64 * self.resolution,
+            self.max_y,
+        )
+    }
+
+    /// Returns the area covered by a cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::grid::Grid;
+    ///
+    /// let grid = Grid::new(&Envelope::new(0.0, 0.0, 10.0, 10.0), 5.0);
+    /// assert_eq!(grid.cell_envelope(0, 1), Envelope::new(5.0, 5.0, 10.0, 10.0));
+    /// ```
+    pub fn cell_envelope(&self, row: usize, column: usize) -> Envelope {
+        let x = self.min_x + column as f64 * self.resolution;
+        let y = self.max_y - row as f64 * self.resolution;
+        Envelope::new(x, y - self.resolution, x + self.resolution, y)
+    }
+
+    /// Returns the center of a cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::grid::Grid;
+    ///
+    /// let grid = Grid::new(&Envelope::new(0.0, 0.0, 10.0, 10.0), 5.0);
+    /// assert_eq!(grid.cell_center(1, 0), coord!(2.5, 2.5));
+    /// ```
+    pub fn cell_center(&self, row: usize, column: usize) -> Coordinate {
+        Coordinate::new(
+            self.min_x + (column as f64 + 0.5) * self.resolution,
+            self.max_y - (row as f64 + 0.5) * self.resolution,
+            0.0,
+        )
+    }
+
+    /// Returns the row and column of the cell containing the coordinate, or None if it lies
+    /// outside of the grid. Coordinates on the edge between two cells belong to the cell to the
+    /// right or below.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::grid::Grid;
+    ///
+    /// let grid = Grid::new(&Envelope::new(0.0, 0.0, 10.0, 10.0), 5.0);
+    /// assert_eq!(grid.cell_of(&coord!(7, 1)), Some((1, 1)));
+    /// assert_eq!(grid.cell_of(&coord!(11, 1)), None);
+    /// ```
+    pub fn cell_of(&self, coordinate: &Coordinate) -> Option<(usize, usize)> {
+        let column = ((coordinate.x() - self.min_x) / self.resolution).floor();
+        let row = ((self.max_y - coordinate.y()) / self.resolution).floor();
+        if column < 0.0 || row < 0.0 || column >= self.columns as f64 || row >= self.rows as f64 {
+            return None;
+        }
+        Some((row as usize, column as usize))
+    }
+
+    /// Returns the value of a cell, or None if the cell is outside of the grid.
+    pub fn get(&self, row: usize, column: usize) -> Option<f64> {
+        if row >= self.rows || column >= self.columns {
+            return None;
+        }
+        Some(self.values[row * self.columns + column])
+    }
+
+    /// Sets the value of a cell, which must be inside of the grid.
+    pub fn set(&mut self, row: usize, column: usize, value: f64) {
+        if row >= self.rows || column >= self.columns {
+            panic!("Cell ({}, {}) is outside of the grid", row, column);
+        }
+        self.values[row * self.columns + column] = value;
+    }
+
+    /// Sets the value of every cell.
+    pub fn fill(&mut self, value: f64) {
+        self.values.fill(value);
+    }
+
+    /// Returns the values of the grid, row by row from the top.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Consumes the grid and returns its values, row by row from the top.
+    pub fn into_values(self) -> Vec<f64> {
+        self.values
+    }
+
+    /// Returns the contour lines of the grid at the given z levels, as a multi line string with the
+    /// higher values on the left of the lines. The values are taken at the centers of the cells and
+    /// interpolated linearly in between, leaving out cells without data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::grid::Grid;
+    ///
+    /// let mut grid = Grid::new(&Envelope::new(0.0, 0.0, 2.0, 2.0), 1.0);
+    /// grid.set(0, 0, 0.0);
+    /// grid.set(0, 1, 2.0);
+    /// grid.set(1, 0, 0.0);
+    /// grid.set(1, 1, 2.0);
+    /// assert_eq!(grid.contour(&[1.0]), Geometry::MultiLineString { coordinates: vec![
+    ///     vec![coord!(1, 1.5, 1), coord!(1, 1, 1), coord!(1, 0.5, 1)]
+    /// ] });
+    /// ```
+    pub fn contour(&self, levels: &[f64]) -> Geometry {
+        //the centers of the cells with data, split in two triangles between every four of them
+        let mut points = Vec::new();
+        let mut vertices = vec![None; self.values.len()];
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let value = self.values[row * self.columns + column];
+                if !value.is_nan() {
+                    let center = self.cell_center(row, column);
+                    vertices[row * self.columns + column] = Some(points.len());
+                    points.push(Coordinate::new(center.x(), center.y(), value));
+                }
+            }
+        }
+        let mut triangles = Vec::new();
+        for row in 1..self.rows {
+            for column in 1..self.columns {
+                let vertex = |r: usize, c: usize| vertices[r * self.columns + c];
+                let (top_left, top_right) = (vertex(row - 1, column - 1), vertex(row - 1, column));
+                let (bottom_left, bottom_right) = (vertex(row, column - 1), vertex(row, column));
+                if let (Some(a), Some(b), Some(c)) = (bottom_left, bottom_right, top_right) {
+                    triangles.push([a, b, c]);
+                }
+                if let (Some(a), Some(b), Some(c)) = (bottom_left, top_right, top_left) {
+                    triangles.push([a, b, c]);
+                }
+            }
+        }
+        contour(&points, &triangles, levels)
+    }
+}
@@ -0,0 +1,6 @@
+//! Spatial indexes.
+
+pub mod intervalrtree;
+pub mod kdtree;
+pub mod quadtree;
+pub mod strtree;
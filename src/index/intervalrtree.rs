@@ -0,0 +1,163 @@
+/// The maximum number of children of every node.
+const NODE_CAPACITY: usize = 10;
+
+/// A static R-tree over closed one dimensional intervals, such as the y ranges of the segments
+/// of a ring or the chainage ranges along a route, answering interval and value queries.
+pub struct IntervalRTree<T> {
+    items: Vec<(f64, f64, T)>,
+    nodes: Vec<Node>,
+}
+
+/// A node of the tree. The children of leaves are item indices, and node indices otherwise.
+struct Node {
+    min: f64,
+    max: f64,
+    leaf: bool,
+    children: Vec<usize>,
+}
+
+impl<T> IntervalRTree<T> {
+    /// Builds a tree over items paired with the bounds of their intervals. The bounds may be
+    /// given in any order, and items with a NaN bound are kept but never returned by queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::index::intervalrtree::IntervalRTree;
+    ///
+    /// let tree = IntervalRTree::new(vec![(0.0, 120.0, "a"), (350.0, 80.0, "b")]);
+    /// assert_eq!(tree.len(), 2);
+    /// assert_eq!(tree.get(1).map(|item| (item.0, item.1)), Some((80.0, 350.0)));
+    /// ```
+    pub fn new(items: Vec<(f64, f64, T)>) -> Self {
+        let items: Vec<(f64, f64, T)> = items
+            .into_iter()
+            .map(|(a, b, item)| if a > b { (b, a, item) } else { (a, b, item) })
+            .collect();
+        let mut tree = Self { items, nodes: Vec::new() };
+        let mut entries: Vec<usize> = (0..tree.items.len())
+            .filter(|&i| !tree.items[i].0.is_nan() && !tree.items[i].1.is_nan())
+            .collect();
+        entries.sort_by(|&a, &b| {
+            let (a, b) = (&tree.items[a], &tree.items[b]);
+            (a.0 + a.1).total_cmp(&(b.0 + b.1))
+        });
+        let bounds: Vec<(f64, f64)> = tree.items.iter().map(|item| (item.0, item.1)).collect();
+        let mut level = tree.pack(&entries, &bounds, true);
+        while level.len() > 1 {
+            let bounds: Vec<(f64, f64)> = tree.nodes.iter().map(|node| (node.min, node.max)).collect();
+            level = tree.pack(&level, &bounds, false);
+        }
+        tree
+    }
+
+    /// Groups the entries, already sorted by their midpoints, into nodes of at most NODE_CAPACITY
+    /// children. Returns the indices of the new nodes, which keep the order of the entries.
+    fn pack(&mut self, entries: &[usize], bounds: &[(f64, f64)], leaf: bool) -> Vec<usize> {
+        let mut created = Vec::with_capacity(entries.len().div_ceil(NODE_CAPACITY));
+        for children in entries.chunks(NODE_CAPACITY) {
+            let min = children.iter().map(|&i| bounds[i].0).fold(f64::INFINITY, f64::min);
+            let max = children.iter().map(|&i| bounds[i].1).fold(f64::NEG_INFINITY, f64::max);
+            created.push(self.nodes.len());
+            self.nodes.push(Node { min, max, leaf, children: children.to_vec() });
+        }
+        created
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the tree has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the item at the given index, in the order the tree was built with, with its
+    /// bounds in increasing order.
+    pub fn get(&self, index: usize) -> Option<&(f64, f64, T)> {
+        self.items.get(index)
+    }
+
+    /// Returns the indices of the items whose intervals intersect the closed interval from `min`
+    /// to `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::index::intervalrtree::IntervalRTree;
+    ///
+    /// let tree = IntervalRTree::new((0..100).map(|i| (i as f64 * 10.0, i as f64 * 10.0 + 5.0, i)).collect());
+    /// let mut found = tree.query_indices(104.0, 121.0);
+    /// found.sort();
+    /// assert_eq!(found, vec![10, 11, 12]);
+    /// ```
+    pub fn query_indices(&self, min: f64, max: f64) -> Vec<usize> {
+        let mut found = Vec::new();
+        if let Some(root) = self.root() {
+            self.query_node(root, min, max, &mut found);
+        }
+        found
+    }
+
+    /// Returns the items whose intervals intersect the closed interval from `min` to `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::index::intervalrtree::IntervalRTree;
+    ///
+    /// let chainages = IntervalRTree::new(vec![(0.0, 120.0, "bridge"), (80.0, 350.0, "cutting")]);
+    /// assert_eq!(chainages.query(100.0, 100.0).len(), 2);
+    /// assert_eq!(chainages.query(200.0, 400.0), vec![&"cutting"]);
+    /// ```
+    pub fn query(&self, min: f64, max: f64) -> Vec<&T> {
+        self.query_indices(min, max).into_iter().map(|i| &self.items[i].2).collect()
+    }
+
+    fn root(&self) -> Option<usize> {
+        //nodes are created bottom up, so the root is the last one
+        self.nodes.len().checked_sub(1)
+    }
+
+    fn query_node(&self, node: usize, min: f64, max: f64, found: &mut Vec<usize>) {
+        let node = &self.nodes[node];
+        if node.max < min || node.min > max {
+            return;
+        }
+        for &child in &node.children {
+            if !node.leaf {
+                self.query_node(child, min, max, found);
+            } else if self.items[child].1 >= min && self.items[child].0 <= max {
+                found.push(child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_against_brute_force() {
+        let items: Vec<(f64, f64, usize)> = (0..1000)
+            .map(|i| {
+                let start = (i * 37 % 211) as f64;
+                (start, start - (i % 7) as f64, i)
+            })
+            .collect();
+        let tree = IntervalRTree::new(items.clone());
+        for (min, max) in [(0.0, 10.0), (50.0, 50.0), (120.5, 121.5), (300.0, 400.0), (-10.0, 500.0)] {
+            let mut found = tree.query_indices(min, max);
+            found.sort();
+            let expected: Vec<usize> = (0..items.len())
+                .filter(|&i| items[i].0.max(items[i].1) >= min && items[i].0.min(items[i].1) <= max)
+                .collect();
+            assert_eq!(found, expected);
+        }
+        let empty: IntervalRTree<()> = IntervalRTree::new(vec![(f64::NAN, 1.0, ())]);
+        assert!(empty.query_indices(f64::NEG_INFINITY, f64::INFINITY).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::coordinate::Coordinate;
+
+/// A static 2D KD-tree over a set of points, answering radius and nearest neighbour queries.
+/// Queries return the indices of the points in the slice the tree was built from.
+pub struct KdTree<'a> {
+    points: &'a [Coordinate],
+    /// Point indices arranged so that the median of every range splits it on the axis of its depth.
+    order: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    /// Builds a tree over the given points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::index::kdtree::KdTree;
+    ///
+    /// let points = vec![coord!(0, 0), coord!(1, 1), coord!(5, 5)];
+    /// let tree = KdTree::new(&points);
+    /// assert_eq!(tree.len(), 3);
+    /// ```
+    pub fn new(points: &'a [Coordinate]) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(points, &mut order, 0);
+        Self { points, order }
+    }
+
+    /// Returns the number of points in the tree.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns true if the tree has no points.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Returns the indices of the points within the given planar distance of `center`, boundary included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::index::kdtree::KdTree;
+    ///
+    /// let points = vec![coord!(0, 0), coord!(1, 1), coord!(5, 5), coord!(0, 2)];
+    /// let tree = KdTree::new(&points);
+    /// let mut found = tree.query_radius(&coord!(0, 1), 1.0);
+    /// found.sort();
+    /// assert_eq!(found, vec![0, 1, 3]);
+    /// ```
+    pub fn query_radius(&self, center: &Coordinate, radius: f64) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.radius_in(&self.order, 0, center, radius * radius, radius, &mut found);
+        found
+    }
+
+    fn radius_in(
+        &self,
+        range: &[usize],
+        depth: usize,
+        center: &Coordinate,
+        radius_squared: f64,
+        radius: f64,
+        found: &mut Vec<usize>,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+        let mid = range.len() / 2;
+        let index = range[mid];
+        let point = &self.points[index];
+        if squared_distance(point, center) <= radius_squared {
+            found.push(index);
+        }
+        let delta = center.get_ordinate(depth % 2) - point.get_ordinate(depth % 2);
+        if delta <= radius {
+            self.radius_in(&range[..mid], depth + 1, center, radius_squared, radius, found);
+        }
+        if delta >= -radius {
+            self.radius_in(&range[mid + 1..], depth + 1, center, radius_squared, radius, found);
+        }
+    }
+
+    /// Returns the index of the point closest to `target`, or None if the tree is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::index::kdtree::KdTree;
+    ///
+    /// let points = vec![coord!(0, 0), coord!(1, 1), coord!(5, 5)];
+    /// let tree = KdTree::new(&points);
+    /// assert_eq!(tree.nearest(&coord!(4, 3)), Some(2));
+    /// ```
+    pub fn nearest(&self, target: &Coordinate) -> Option<usize> {
+        let mut best: Option<(usize, f64)> = None;
+        self.nearest_in(&self.order, 0, target, &mut best);
+        best.map(|(index, _)| index)
+    }
+
+    fn nearest_in(&self, range: &[usize], depth: usize, target: &Coordinate, best: &mut Option<(usize, f64)>) {
+        if range.is_empty() {
+            return;
+        }
+        let mid = range.len() / 2;
+        let index = range[mid];
+        let point = &self.points[index];
+        let distance = squared_distance(point, target);
+        if best.is_none_or(|(_, d)| distance < d) {
+            *best = Some((index, distance));
+        }
+        let delta = target.get_ordinate(depth % 2) - point.get_ordinate(depth % 2);
+        let (near, far) = if delta <= 0.0 {
+            (&range[..mid], &range[mid + 1..])
+        } else {
+            (&range[mid + 1..], &range[..mid])
+        };
+        self.nearest_in(near, depth + 1, target, best);
+        if best.is_none_or(|(_, d)| delta * delta < d) {
+            self.nearest_in(far, depth + 1, target, best);
+        }
+    }
+}
+
+fn build(points: &[Coordinate], range: &mut [usize], depth: usize) {
+    if range.len() <= 1 {
+        return;
+    }
+    let axis = depth % 2;
+    let mid = range.len() / 2;
+    range.select_nth_unstable_by(mid, |&a, &b| {
+        points[a].get_ordinate(axis).total_cmp(&points[b].get_ordinate(axis))
+    });
+    let (left, right) = range.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+fn squared_distance(a: &Coordinate, b: &Coordinate) -> f64 {
+    let dx = a.x() - b.x();
+    let dy = a.y() - b.y();
+    dx * dx + dy * dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_against_brute_force() {
+        //a deterministic pseudo random cloud
+        let points: Vec<Coordinate> = (0..500)
+            .map(|i| coord!((i * 37 % 101) as f64 * 0.7, (i * 53 % 97) as f64 * 1.3))
+            .collect();
+        let tree = KdTree::new(&points);
+        for target in [coord!(10, 10), coord!(35.5, 60.2), coord!(-5, 200), coord!(70, 0)] {
+            let mut found = tree.query_radius(&target, 12.0);
+            found.sort();
+            let expected: Vec<usize> =
+                (0..points.len()).filter(|&i| squared_distance(&points[i], &target) <= 144.0).collect();
+            assert_eq!(found, expected);
+
+            let nearest = tree.nearest(&target).unwrap();
+            let min = points.iter().map(|p| squared_distance(p, &target)).fold(f64::INFINITY, f64::min);
+            assert_eq!(squared_distance(&points[nearest], &target), min);
+        }
+    }
+}
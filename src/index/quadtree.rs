@@ -0,0 +1,156 @@
+use crate::envelope::Envelope;
+
+/// The number of items a node holds before it is split into quadrants.
+const NODE_CAPACITY: usize = 8;
+
+/// The depth under which nodes are not split, so piles of identical envelopes stay in one node.
+const MAX_DEPTH: usize = 24;
+
+/// A region quadtree answering envelope queries over a set of items, such as the segments of
+/// many lines. Every item is kept in the smallest quadrant containing its envelope, so long items
+/// stay close to the root while short ones go down where the items are dense.
+pub struct Quadtree<T> {
+    items: Vec<(Envelope, T)>,
+    nodes: Vec<Node>,
+}
+
+struct Node {
+    envelope: Envelope,
+    items: Vec<usize>,
+    children: Option<[usize; 4]>,
+}
+
+impl<T> Quadtree<T> {
+    /// Builds a tree over items paired with their envelopes, covering the extent of all the
+    /// envelopes. Items with empty envelopes are kept but never returned by queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::index::quadtree::Quadtree;
+    ///
+    /// let tree = Quadtree::new((0..100).map(|i| {
+    ///     let x = i as f64;
+    ///     (Envelope::new(x, x, x + 0.5, x + 0.5), i)
+    /// }).collect());
+    /// let mut found = tree.query(&Envelope::new(10.0, 10.0, 12.2, 12.2));
+    /// found.sort();
+    /// assert_eq!(found, vec![&10, &11, &12]);
+    /// ```
+    pub fn new(items: Vec<(Envelope, T)>) -> Self {
+        let extent = items.iter().fold(Envelope::empty(), |extent, item| extent.merge(&item.0));
+        let mut tree = Self { items, nodes: vec![Node { envelope: extent, items: Vec::new(), children: None }] };
+        for i in 0..tree.items.len() {
+            if !tree.items[i].0.is_empty() {
+                tree.insert(i);
+            }
+        }
+        tree
+    }
+
+    /// Puts an item into the smallest node containing it, splitting the node if it gets full.
+    fn insert(&mut self, item: usize) {
+        let envelope = self.items[item].0;
+        let (mut node, mut depth) = (0, 0);
+        while let Some(child) = self.nodes[node].children.and_then(|c| c.into_iter().find(|&c| self.nodes[c].envelope.contains(&envelope))) {
+            node = child;
+            depth += 1;
+        }
+        self.nodes[node].items.push(item);
+        if self.nodes[node].children.is_none() && self.nodes[node].items.len() > NODE_CAPACITY && depth < MAX_DEPTH {
+            self.split(node);
+        }
+    }
+
+    /// Gives a node four quadrants and moves down the items fitting in one of them.
+    fn split(&mut self, node: usize) {
+        let envelope = self.nodes[node].envelope;
+        let (mid_x, mid_y) = ((envelope.min_x() + envelope.max_x()) / 2.0, (envelope.min_y() + envelope.max_y()) / 2.0);
+        let quadrants = [
+            Envelope::new(envelope.min_x(), envelope.min_y(), mid_x, mid_y),
+            Envelope::new(mid_x, envelope.min_y(), envelope.max_x(), mid_y),
+            Envelope::new(envelope.min_x(), mid_y, mid_x, envelope.max_y()),
+            Envelope::new(mid_x, mid_y, envelope.max_x(), envelope.max_y()),
+        ];
+        let first = self.nodes.len();
+        self.nodes.extend(quadrants.map(|envelope| Node { envelope, items: Vec::new(), children: None }));
+        let children = [first, first + 1, first + 2, first + 3];
+        self.nodes[node].children = Some(children);
+        let items = std::mem::take(&mut self.nodes[node].items);
+        for item in items {
+            let envelope = self.items[item].0;
+            match children.into_iter().find(|&c| self.nodes[c].envelope.contains(&envelope)) {
+                Some(child) => self.nodes[child].items.push(item),
+                None => self.nodes[node].items.push(item),
+            }
+        }
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the tree has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the item at the given index, in the order the tree was built with.
+    pub fn get(&self, index: usize) -> Option<&(Envelope, T)> {
+        self.items.get(index)
+    }
+
+    /// Returns the indices of the items whose envelopes intersect the given envelope.
+    pub fn query_indices(&self, envelope: &Envelope) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            let node = &self.nodes[node];
+            if !node.envelope.intersects(envelope) {
+                continue;
+            }
+            found.extend(node.items.iter().filter(|&&i| self.items[i].0.intersects(envelope)));
+            pending.extend(node.children.iter().flatten());
+        }
+        found
+    }
+
+    /// Returns the items whose envelopes intersect the given envelope.
+    pub fn query(&self, envelope: &Envelope) -> Vec<&T> {
+        self.query_indices(envelope).into_iter().map(|i| &self.items[i].1).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queries_match_a_scan() {
+        //long items across the extent among many small ones, some of them repeated
+        let mut items: Vec<(Envelope, usize)> = (0..500)
+            .map(|i| {
+                let (x, y) = ((i * 37 % 101) as f64, (i * 59 % 97) as f64);
+                (Envelope::new(x, y, x + 0.5, y + 0.25), i)
+            })
+            .collect();
+        items.extend((0..20).map(|i| (Envelope::new(i as f64 * 5.0, 0.0, i as f64 * 5.0 + 1.0, 100.0), 500 + i)));
+        items.extend((0..30).map(|i| (Envelope::new(50.0, 50.0, 50.0, 50.0), 520 + i)));
+        items.push((Envelope::empty(), 550));
+        let tree = Quadtree::new(items.clone());
+        assert_eq!(tree.len(), 551);
+        for query in [
+            Envelope::new(10.0, 10.0, 20.0, 30.0),
+            Envelope::new(50.0, 50.0, 50.0, 50.0),
+            Envelope::new(-10.0, 40.0, 200.0, 40.0),
+            Envelope::new(200.0, 200.0, 300.0, 300.0),
+        ] {
+            let mut found: Vec<usize> = tree.query(&query).into_iter().copied().collect();
+            found.sort();
+            let expected: Vec<usize> = items.iter().filter(|item| item.0.intersects(&query)).map(|item| item.1).collect();
+            assert_eq!(found, expected);
+        }
+    }
+}
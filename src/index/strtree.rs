@@ -0,0 +1,395 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+
+/// The maximum number of children of every node.
+const NODE_CAPACITY: usize = 10;
+
+/// A static R-tree bulk loaded with the Sort-Tile-Recursive algorithm, which answers envelope
+/// queries over a set of items.
+pub struct STRtree<T> {
+    items: Vec<(Envelope, T)>,
+    nodes: Vec<Node>,
+}
+
+/// A node of the tree. The children of leaves are item indices, and node indices otherwise.
+struct Node {
+    envelope: Envelope,
+    leaf: bool,
+    children: Vec<usize>,
+}
+
+impl<T> STRtree<T> {
+    /// Builds a tree over items paired with their envelopes. Items with empty envelopes are kept
+    /// but never returned by queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::index::strtree::STRtree;
+    ///
+    /// let tree = STRtree::new(vec![
+    ///     (Envelope::new(0.0, 0.0, 1.0, 1.0), "a"),
+    ///     (Envelope::new(5.0, 5.0, 6.0, 6.0), "b"),
+    /// ]);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn new(items: Vec<(Envelope, T)>) -> Self {
+        let mut tree = Self { items, nodes: Vec::new() };
+        let entries: Vec<usize> = (0..tree.items.len()).filter(|&i| !tree.items[i].0.is_empty()).collect();
+        let envelopes: Vec<Envelope> = tree.items.iter().map(|item| item.0).collect();
+        let mut level = tree.pack(entries, &envelopes, true);
+        while level.len() > 1 {
+            let envelopes: Vec<Envelope> = tree.nodes.iter().map(|node| node.envelope).collect();
+            level = tree.pack(level, &envelopes, false);
+        }
+        tree
+    }
+
+    /// Groups the entries into nodes of at most NODE_CAPACITY children, tiling them by the x and
+    /// then the y of their centers. Returns the indices of the new nodes.
+    fn pack(&mut self, mut entries: Vec<usize>, envelopes: &[Envelope], leaf: bool) -> Vec<usize> {
+        let center = |i: usize, axis: usize| {
+            let envelope = &envelopes[i];
+            match axis {
+                0 => envelope.min_x() + envelope.max_x(),
+                _ => envelope.min_y() + envelope.max_y(),
+            }
+        };
+        let node_count = entries.len().div_ceil(NODE_CAPACITY);
+        let slice_count = (node_count as f64).sqrt().ceil() as usize;
+        let slice_size = slice_count.max(1) * NODE_CAPACITY;
+
+        entries.sort_by(|&a, &b| center(a, 0).total_cmp(&center(b, 0)));
+        let mut created = Vec::with_capacity(node_count);
+        for slice in entries.chunks_mut(slice_size) {
+            slice.sort_by(|&a, &b| center(a, 1).total_cmp(&center(b, 1)));
+            for children in slice.chunks(NODE_CAPACITY) {
+                let envelope = children.iter().fold(Envelope::empty(), |e, &i| e.merge(&envelopes[i]));
+                created.push(self.nodes.len());
+                self.nodes.push(Node { envelope, leaf, children: children.to_vec() });
+            }
+        }
+        created
+    }
+
+    /// Returns the number of items in the tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the tree has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the item at the given index, in the order the tree was built with.
+    pub fn get(&self, index: usize) -> Option<&(Envelope, T)> {
+        self.items.get(index)
+    }
+
+    /// Returns the indices of the items whose envelopes intersect the given envelope, in z too
+    /// when both are boxes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::index::strtree::STRtree;
+    ///
+    /// let tree = STRtree::new((0..100).map(|i| {
+    ///     let x = i as f64;
+    ///     (Envelope::new(x, x, x + 0.5, x + 0.5), i)
+    /// }).collect());
+    /// let mut found = tree.query_indices(&Envelope::new(10.0, 10.0, 12.2, 12.2));
+    /// found.sort();
+    /// assert_eq!(found, vec![10, 11, 12]);
+    /// ```
+    pub fn query_indices(&self, envelope: &Envelope) -> Vec<usize> {
+        let mut found = Vec::new();
+        if let Some(root) = self.root() {
+            self.query_node(root, envelope, &mut found);
+        }
+        found
+    }
+
+    /// Returns the items whose envelopes intersect the given envelope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::index::strtree::STRtree;
+    ///
+    /// let tree = STRtree::new(vec![
+    ///     (Envelope::new(0.0, 0.0, 1.0, 1.0), "a"),
+    ///     (Envelope::new(5.0, 5.0, 6.0, 6.0), "b"),
+    /// ]);
+    /// assert_eq!(tree.query(&Envelope::new(0.5, 0.5, 2.0, 2.0)), vec![&"a"]);
+    /// ```
+    pub fn query(&self, envelope: &Envelope) -> Vec<&T> {
+        self.query_indices(envelope).into_iter().map(|i| &self.items[i].1).collect()
+    }
+
+    /// Returns the index of the item closest to a target along with its distance, or None if no
+    /// item is closer than the bound. The nodes are searched in the order of the distance from
+    /// their envelopes to the envelope of the target, so the distance to an item must never be
+    /// smaller than the distance between the envelopes.
+    ///
+    /// # Arguments
+    ///
+    /// * `envelope` - The envelope of the target.
+    /// * `bound` - The distance from which items are ignored, infinite to consider them all.
+    /// * `distance` - The distance from an item to the target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::envelope::Envelope;
+    /// use geoms::index::strtree::STRtree;
+    ///
+    /// let tree = STRtree::new((0..100).map(|i| {
+    ///     let x = i as f64;
+    ///     (Envelope::new(x, 0.0, x, 0.0), x)
+    /// }).collect());
+    /// let target = Envelope::new(41.7, 3.0, 41.7, 3.0);
+    /// let nearest = tree.nearest(&target, f64::INFINITY, |&x| (x - 41.7f64).hypot(3.0));
+    /// assert_eq!(nearest.map(|(i, _)| i), Some(42));
+    /// assert_eq!(tree.nearest(&target, 2.0, |&x| (x - 41.7f64).hypot(3.0)), None);
+    /// ```
+    pub fn nearest<F>(&self, envelope: &Envelope, bound: f64, mut distance: F) -> Option<(usize, f64)>
+    where
+        F: FnMut(&T) -> f64,
+    {
+        let root = self.root()?;
+        let mut best = None;
+        let mut bound = bound;
+        let mut queue = BinaryHeap::new();
+        queue.push(Candidate(self.nodes[root].envelope.distance(envelope), root));
+        while let Some(Candidate(node_distance, node)) = queue.pop() {
+            //the nodes come out closest first, so none of the rest can hold a closer item
+            if node_distance >= bound {
+                break;
+            }
+            let node = &self.nodes[node];
+            for &child in &node.children {
+                if node.leaf {
+                    let (item_envelope, item) = &self.items[child];
+                    if item_envelope.distance(envelope) >= bound {
+                        continue;
+                    }
+                    let d = distance(item);
+                    if d < bound {
+                        bound = d;
+                        best = Some((child, d));
+                    }
+                } else {
+                    let d = self.nodes[child].envelope.distance(envelope);
+                    if d < bound {
+                        queue.push(Candidate(d, child));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns the index of the root node, or None if the tree is empty.
+    fn root(&self) -> Option<usize> {
+        //nodes are created bottom up, so the root is the last one
+        self.nodes.len().checked_sub(1)
+    }
+
+    fn query_node(&self, node: usize, envelope: &Envelope, found: &mut Vec<usize>) {
+        let node = &self.nodes[node];
+        if !node.envelope.intersects(envelope) {
+            return;
+        }
+        for &child in &node.children {
+            if node.leaf {
+                if self.items[child].0.intersects(envelope) {
+                    found.push(child);
+                }
+            } else {
+                self.query_node(child, envelope, found);
+            }
+        }
+    }
+}
+
+impl<G: Borrow<Geometry>> STRtree<G> {
+    /// Builds a tree over geometries by their envelopes. The geometries can be owned, borrowed or
+    /// shared through an `Arc`, so a single tree can be queried from several threads while the
+    /// geometries are used elsewhere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::index::strtree::STRtree;
+    ///
+    /// let parcels: Vec<Arc<Geometry>> = (0..4)
+    ///     .map(|i| Arc::new(Geometry::Point { coordinates: coord!(i, i) }))
+    ///     .collect();
+    /// let tree = Arc::new(STRtree::from_geometries(parcels.iter().cloned()));
+    /// let shared = tree.clone();
+    /// let found = std::thread::spawn(move || shared.query(&Envelope::new(0.5, 0.5, 2.5, 2.5)).len());
+    /// assert_eq!(found.join().unwrap(), 2);
+    /// assert!(Arc::ptr_eq(&tree.get(3).unwrap().1, &parcels[3]));
+    /// ```
+    pub fn from_geometries(geometries: impl IntoIterator<Item = G>) -> Self {
+        STRtree::new(geometries.into_iter().map(|g| (g.borrow().envelope(), g)).collect())
+    }
+
+    /// Builds a tree over geometries by their boxes, so they can be queried by volume, as
+    /// boreholes or flight paths. Planar query envelopes still find the geometries at any height.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::envelope::Envelope;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::index::strtree::STRtree;
+    ///
+    /// //two boreholes at the same place, one of them deeper
+    /// let boreholes = vec![
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0, 0), coord!(0, 0, -50)] },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0, 0), coord!(0, 0, -200)] },
+    /// ];
+    /// let tree = STRtree::from_geometries_3d(&boreholes);
+    /// let layer = Envelope::new_3d(-10.0, -10.0, -150.0, 10.0, 10.0, -100.0);
+    /// assert_eq!(tree.query_indices(&layer), vec![1]);
+    /// assert_eq!(tree.query_indices(&Envelope::new(-10.0, -10.0, 10.0, 10.0)).len(), 2);
+    /// ```
+    pub fn from_geometries_3d(geometries: impl IntoIterator<Item = G>) -> Self {
+        STRtree::new(geometries.into_iter().map(|g| (g.borrow().envelope_3d(), g)).collect())
+    }
+}
+
+/// A node to search at a distance, ordered so that the closest comes out of a heap first.
+struct Candidate(f64, usize);
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_against_brute_force() {
+        let items: Vec<(Envelope, usize)> = (0..1000)
+            .map(|i| {
+                let x = (i * 37 % 211) as f64;
+                let y = (i * 91 % 197) as f64;
+                (Envelope::new(x, y, x + (i % 7) as f64, y + (i % 5) as f64), i)
+            })
+            .collect();
+        let tree = STRtree::new(items.clone());
+        for query in [
+            Envelope::new(0.0, 0.0, 10.0, 10.0),
+            Envelope::new(50.0, 20.0, 120.0, 45.0),
+            Envelope::new(300.0, 300.0, 400.0, 400.0),
+            Envelope::new(-10.0, -10.0, 500.0, 500.0),
+        ] {
+            let mut found = tree.query_indices(&query);
+            found.sort();
+            let expected: Vec<usize> = (0..items.len()).filter(|&i| items[i].0.intersects(&query)).collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_volume_queries_against_brute_force() {
+        //boxes stacked over the same footprints, with some planar items among them
+        let items: Vec<(Envelope, usize)> = (0..600)
+            .map(|i| {
+                let (x, y, z) = ((i * 37 % 53) as f64, (i * 91 % 47) as f64, (i * 13 % 101) as f64);
+                let envelope = Envelope::new(x, y, x + 2.0, y + 2.0);
+                match i % 10 {
+                    0 => (envelope, i),
+                    _ => (envelope.with_z_range(z, z + (i % 9) as f64), i),
+                }
+            })
+            .collect();
+        let tree = STRtree::new(items.clone());
+        for query in [
+            Envelope::new_3d(0.0, 0.0, 0.0, 20.0, 20.0, 10.0),
+            Envelope::new_3d(10.0, 10.0, 50.0, 30.0, 40.0, 50.0),
+            Envelope::new_3d(-10.0, -10.0, 200.0, 100.0, 100.0, 300.0),
+            Envelope::new(5.0, 5.0, 15.0, 15.0),
+        ] {
+            let mut found = tree.query_indices(&query);
+            found.sort();
+            let expected: Vec<usize> = (0..items.len()).filter(|&i| items[i].0.intersects(&query)).collect();
+            assert_eq!(found, expected);
+        }
+        //above every box only the planar items are found
+        let above = tree.query_indices(&Envelope::new_3d(-10.0, -10.0, 200.0, 100.0, 100.0, 300.0));
+        assert!(!above.is_empty() && above.iter().all(|i| i % 10 == 0));
+    }
+
+    #[test]
+    fn test_queries_from_threads_sharing_geometries() {
+        let squares: Vec<Arc<Geometry>> = (0..200)
+            .map(|i| {
+                let (x, y) = ((i % 20) as f64 * 2.0, (i / 20) as f64 * 2.0);
+                let ring = vec![coord!(x, y), coord!(x + 1.0, y), coord!(x + 1.0, y + 1.0), coord!(x, y + 1.0), coord!(x, y)];
+                Arc::new(Geometry::Polygon { coordinates: vec![ring] })
+            })
+            .collect();
+        let tree = STRtree::from_geometries(squares.iter().cloned());
+        let areas: Vec<f64> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..4)
+                .map(|k| {
+                    let tree = &tree;
+                    scope.spawn(move || {
+                        let row = Envelope::new(0.0, k as f64 * 2.0, 40.0, k as f64 * 2.0 + 0.5);
+                        tree.query(&row).iter().map(|g| g.area()).sum()
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+        assert_eq!(areas, vec![20.0; 4]);
+        //the tree and the workers shared the geometries instead of copying them
+        assert!(squares.iter().all(|g| Arc::strong_count(g) == 2));
+    }
+
+    #[test]
+    fn test_empty() {
+        let tree: STRtree<()> = STRtree::new(vec![]);
+        assert!(tree.query_indices(&Envelope::new(0.0, 0.0, 1.0, 1.0)).is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Estimation of surfaces from points carrying a z value.
+
+use crate::contour::contour;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::grid::Grid;
+use crate::index::strtree::STRtree;
+use crate::triangulate::delaunay;
+
+/// An estimator of the z value of a surface at any planar location.
+pub trait Interpolator {
+    /// Returns the estimated z value at the planar location of the coordinate, or None if the
+    /// surface is not defined there.
+    fn z_at(&self, coordinate: &Coordinate) -> Option<f64>;
+
+    /// Returns a grid over the envelope holding the z value estimated at the center of every cell.
+    /// Cells where the surface is not defined hold NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `envelope` - The area covered by the grid.
+    /// * `resolution` - The size of the cells.
+    fn grid(&self, envelope: &Envelope, resolution: f64) -> Grid {
+        let mut grid = Grid::new(envelope, resolution);
+        for row in 0..grid.rows() {
+            for column in 0..grid.columns() {
+                if let Some(z) = self.z_at(&grid.cell_center(row, column)) {
+                    grid.set(row, column, z);
+                }
+            }
+        }
+        grid
+    }
+}
+
+/// Estimates the surface as the average of the z values of all the points, weighted by the
+/// inverse of their planar distance raised to a power.
+pub struct Idw {
+    points: Vec<Coordinate>,
+    power: f64,
+}
+
+impl Idw {
+    /// Creates an inverse distance weighting estimator.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The points with the known z values.
+    /// * `power` - The power of the distance, usually 2. Higher powers give more influence to the
+    ///   closest points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::interpolate::{Idw, Interpolator};
+    ///
+    /// let idw = Idw::new(&[coord!(0, 0, 10), coord!(2, 0, 20)], 2.0);
+    /// assert_eq!(idw.z_at(&coord!(1, 0)), Some(15.0));
+    /// assert_eq!(idw.z_at(&coord!(2, 0)), Some(20.0));
+    /// assert_eq!(Idw::new(&[], 2.0).z_at(&coord!(1, 0)), None);
+    /// ```
+    pub fn new(points: &[Coordinate], power: f64) -> Idw {
+        Idw { points: points.to_vec(), power }
+    }
+
+    /// Returns the power of the distance.
+    pub fn power(&self) -> f64 {
+        self.power
+    }
+}
+
+impl Interpolator for Idw {
+    fn z_at(&self, coordinate: &Coordinate) -> Option<f64> {
+        let mut weighted = 0.0;
+        let mut weights = 0.0;
+        for point in &self.points {
+            let distance = (point.x() - coordinate.x()).hypot(point.y() - coordinate.y());
+            if distance == 0.0 {
+                return Some(point.z());
+            }
+            let weight = distance.powf(-self.power);
+            weighted += weight * point.z();
+            weights += weight;
+        }
+        if weights == 0.0 {
+            return None;
+        }
+        Some(weighted / weights)
+    }
+}
+
+/// Estimates the surface as the Delaunay triangulation of the points, interpolating linearly
+/// inside of every triangle. The surface is only defined over the convex hull of the points.
+pub struct TinInterpolator {
+    points: Vec<Coordinate>,
+    triangles: Vec<[usize; 3]>,
+    index: STRtree<usize>,
+}
+
+impl TinInterpolator {
+    /// Creates the estimator by triangulating the points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::interpolate::{Interpolator, TinInterpolator};
+    ///
+    /// let tin = TinInterpolator::new(&[coord!(0, 0, 0), coord!(4, 0, 4), coord!(0, 4, 8), coord!(4, 4, 12)]);
+    /// assert_eq!(tin.z_at(&coord!(1, 2)), Some(5.0));
+    /// assert_eq!(tin.z_at(&coord!(5, 2)), None);
+    /// ```
+    pub fn new(points: &[Coordinate]) -> TinInterpolator {
+        let triangles = delaunay(points);
+        let index = STRtree::new(
+            triangles
+                .iter()
+                .enumerate()
+                .map(|(i, t)| (Envelope::of(t.iter().map(|&v| &points[v])), i))
+                .collect(),
+        );
+        TinInterpolator { points: points.to_vec(), triangles, index }
+    }
+
+    /// Returns the points of the estimator.
+    pub fn points(&self) -> &[Coordinate] {
+        &self.points
+    }
+
+    /// Returns the triangles of the estimator as triples of point indices in counter clockwise order.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Returns the triangulation as a TIN geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::interpolate::TinInterpolator;
+    ///
+    /// let tin = TinInterpolator::new(&[coord!(0, 0, 1), coord!(4, 0, 2), coord!(0, 4, 3)]);
+    /// assert_eq!(tin.tin().as_text(), "TIN Z (((0 0 1, 4 0 2, 0 4 3, 0 0 1)))");
+    /// ```
+    pub fn tin(&self) -> Geometry {
+        Geometry::Tin {
+            coordinates: self
+                .triangles
+                .iter()
+                .map(|t| {
+                    let mut ring: Vec<Coordinate> = t.iter().map(|&v| self.points[v].clone()).collect();
+                    ring.push(ring[0].clone());
+                    ring
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the contour lines of the surface at the given z levels, as a multi line string with
+    /// the higher ground on the left of the lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::interpolate::TinInterpolator;
+    ///
+    /// let tin = TinInterpolator::new(&[coord!(0, 0, 0), coord!(4, 0, 4), coord!(0, 4, 0), coord!(4, 4, 4)]);
+    /// assert_eq!(tin.contour(&[1.0]), Geometry::MultiLineString { coordinates: vec![
+    ///     vec![coord!(1, 4, 1), coord!(1, 3, 1), coord!(1, 0, 1)]
+    /// ] });
+    /// ```
+    pub fn contour(&self, levels: &[f64]) -> Geometry {
+        contour(&self.points, &self.triangles, levels)
+    }
+}
+
+impl Interpolator for TinInterpolator {
+    fn z_at(&self, coordinate: &Coordinate) -> Option<f64> {
+        let (x, y) = (coordinate.x(), coordinate.y());
+        let envelope = Envelope::new(x, y, x, y);
+        for &i in self.index.query(&envelope) {
+            let [a, b, c] = self.triangles[i].map(|v| &self.points[v]);
+            //barycentric coordinates, allowing rounding errors on the edges
+            let area = (b.x() - a.x()) * (c.y() - a.y()) - (b.y() - a.y()) * (c.x() - a.x());
+            let u = ((b.x() - x) * (c.y() - y) - (b.y() - y) * (c.x() - x)) / area;
+            let v = ((c.x() - x) * (a.y() - y) - (c.y() - y) * (a.x() - x)) / area;
+            let w = 1.0 - u - v;
+            let tolerance = -1e-12;
+            if u >= tolerance && v >= tolerance && w >= tolerance {
+                return Some(u * a.z() + v * b.z() + w * c.z());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_tin_reproduces_planes() {
+        let plane = |x: f64, y: f64| 3.0 * x - 2.0 * y + 7.0;
+        let points: Vec<Coordinate> = (0..150)
+            .map(|i| {
+                let (x, y) = ((i * 37 % 101) as f64 * 0.7, (i * 53 % 97) as f64 * 1.3);
+                coord!(x, y, plane(x, y))
+            })
+            .collect();
+        let tin = TinInterpolator::new(&points);
+        let grid = tin.grid(&Envelope::new(-10.0, -10.0, 80.0, 140.0), 2.5);
+        let mut defined = 0;
+        for row in 0..grid.rows() {
+            for column in 0..grid.columns() {
+                let z = grid.get(row, column).unwrap();
+                let center = grid.cell_center(row, column);
+                if !z.is_nan() {
+                    defined += 1;
+                    assert!((z - plane(center.x(), center.y())).abs() < 1e-9);
+                }
+            }
+        }
+        assert!(defined > 0 && defined < grid.values().len());
+        for p in &points {
+            assert!((tin.z_at(p).unwrap() - p.z()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_idw_is_bounded() {
+        let points = vec![coord!(0, 0, 1), coord!(10, 0, 5), coord!(5, 8, 3), coord!(2, 2, 4)];
+        let idw = Idw::new(&points, 2.0);
+        for z in idw.grid(&Envelope::new(-5.0, -5.0, 15.0, 15.0), 1.0).values() {
+            assert!((1.0..=5.0).contains(z));
+        }
+        for p in &points {
+            assert_eq!(idw.z_at(p), Some(p.z()));
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Reading and writing of geometries in standard formats.
+
+pub mod diff;
+#[cfg(feature = "flatgeobuf")]
+pub mod flatgeobuf;
+pub mod geojson;
+pub mod wkb;
+pub mod wkt;
+
+use crate::error::Error;
+use crate::geometry::Geometry;
+
+/// How strictly the WKT and WKB readers follow the standards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ParseMode {
+    /// Only input following the OGC Simple Features grammar is accepted: the dimension tags or
+    /// codes must match the number of ordinates, also in nested geometries, and empty points,
+    /// which geometries cannot hold, are rejected.
+    Strict,
+    /// Common deviations are accepted as well: missing `Z` tags, multi point items without
+    /// parentheses, PostGIS `SRID=` prefixes and extended WKB type codes. Empty points inside
+    /// multi points and collections are dropped.
+    #[default]
+    Lenient,
+}
+
+/// The nesting depth accepted by default, deep enough for any real geometry and shallow enough
+/// for the readers not to exhaust the stack on hostile input.
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
+/// Limits on the size and complexity of the geometries the readers accept, which protect services
+/// reading untrusted input. Components are the parts of multi geometries, the rings of polygons,
+/// the patches of surfaces and the members of collections, and the depth is the nesting of
+/// geometries in collections, with 1 for a geometry outside of any collection. Readers stop with
+/// `Error::LimitExceeded` as soon as a limit is passed. The readers recurse once per level of
+/// nesting, so the depth is limited to [`DEFAULT_MAX_DEPTH`] unless raised explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::io::{wkt, Limits, ParseMode};
+///
+/// let limits = Limits::new().with_max_vertices(3);
+/// assert!(wkt::read_with_limits("LINESTRING (0 0, 1 1, 2 2)", ParseMode::Lenient, limits).is_ok());
+/// assert!(wkt::read_with_limits("LINESTRING (0 0, 1 1, 2 2, 3 3)", ParseMode::Lenient, limits).is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Limits {
+    max_vertices: usize,
+    max_depth: usize,
+    max_components: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Limits {
+    /// Returns limits that accept geometries of any size nested at most [`DEFAULT_MAX_DEPTH`]
+    /// levels deep.
+    pub fn new() -> Self {
+        Self { max_vertices: usize::MAX, max_depth: DEFAULT_MAX_DEPTH, max_components: usize::MAX }
+    }
+
+    /// Returns the limits with a maximum number of coordinates.
+    pub fn with_max_vertices(self, max_vertices: usize) -> Self {
+        Self { max_vertices, ..self }
+    }
+
+    /// Returns the limits with a maximum nesting depth. Depths of many thousands may overflow the
+    /// stack of the readers.
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Returns the limits with a maximum number of components.
+    pub fn with_max_components(self, max_components: usize) -> Self {
+        Self { max_components, ..self }
+    }
+
+    /// Returns the maximum number of coordinates.
+    pub fn max_vertices(&self) -> usize {
+        self.max_vertices
+    }
+
+    /// Returns the maximum nesting depth.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Returns the maximum number of components.
+    pub fn max_components(&self) -> usize {
+        self.max_components
+    }
+}
+
+/// Tracks what a reader has used of its limits.
+pub(crate) struct Budget {
+    limits: Limits,
+    vertices: usize,
+    components: usize,
+    depth: usize,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: Limits) -> Self {
+        Self { limits, vertices: 0, components: 0, depth: 0 }
+    }
+
+    /// Accounts for the coordinates about to be read.
+    pub(crate) fn vertices(&mut self, count: usize) -> Result<(), Error> {
+        self.vertices = self.vertices.saturating_add(count);
+        if self.vertices > self.limits.max_vertices {
+            return Err(Error::LimitExceeded(format!("more than {} vertices", self.limits.max_vertices)));
+        }
+        Ok(())
+    }
+
+    /// Accounts for the components about to be read.
+    pub(crate) fn components(&mut self, count: usize) -> Result<(), Error> {
+        self.components = self.components.saturating_add(count);
+        if self.components > self.limits.max_components {
+            return Err(Error::LimitExceeded(format!("more than {} components", self.limits.max_components)));
+        }
+        Ok(())
+    }
+
+    /// Accounts for a geometry being entered, to be matched by a call to `leave`.
+    pub(crate) fn enter(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > self.limits.max_depth {
+            return Err(Error::LimitExceeded(format!("nesting deeper than {}", self.limits.max_depth)));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn leave(&mut self) {
+        self.depth -= 1;
+    }
+}
+
+/// Returns true if any coordinate of the geometry has a z value other than 0.
+pub(crate) fn has_z(geometry: &Geometry) -> bool {
+    geometry.coords_iter().any(|c| c.z() != 0.0)
+}
@@ -0,0 +1,168 @@
+//! Descriptions of the differences between two geometries, for debugging operations such as
+//! overlays and simplifications by comparing their results before and after a change.
+
+use std::fmt;
+
+use crate::algorithm::relate::IntersectionMatrix;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::index::kdtree::KdTree;
+use crate::io::wkt;
+
+/// The differences between two geometries, the old and the new one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GeometryDiff {
+    /// The WKT names of the types of the old and the new geometry.
+    pub types: (&'static str, &'static str),
+    /// The number of coordinates of the old and the new geometry.
+    pub vertices: (usize, usize),
+    /// The number of components of the old and the new geometry.
+    pub components: (usize, usize),
+    /// The index of the first coordinate differing between the geometries, read in order, or None
+    /// if they have the same coordinates.
+    pub first_difference: Option<usize>,
+    /// The largest distance from a vertex of any of the geometries to the closest vertex of the
+    /// other, infinite if only one of them has vertices.
+    pub max_vertex_deviation: f64,
+    /// The area of the new geometry minus the area of the old one.
+    pub area_delta: f64,
+    /// The length of the new geometry minus the length of the old one.
+    pub length_delta: f64,
+    /// The DE-9IM intersection matrix of the old geometry with the new one.
+    pub relation: IntersectionMatrix,
+}
+
+impl GeometryDiff {
+    /// Returns true if the geometries have the same type and coordinates.
+    pub fn is_identical(&self) -> bool {
+        self.types.0 == self.types.1 && self.first_difference.is_none()
+    }
+
+    /// Returns true if the geometries cover the same points, whatever their vertices.
+    pub fn is_topologically_equal(&self) -> bool {
+        self.relation.matches("T*F**FFF*") || (self.vertices == (0, 0))
+    }
+}
+
+/// Returns the differences between an old and a new geometry.
+///
+/// # Arguments
+///
+/// * `a` - The old geometry.
+/// * `b` - The new geometry.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::diff::geometry_diff;
+///
+/// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+/// //the same square with an extra vertex along its first side
+/// let densified = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+/// let diff = geometry_diff(&square, &densified);
+/// assert_eq!(diff.vertices, (5, 6));
+/// assert_eq!(diff.first_difference, Some(1));
+/// assert_eq!(diff.max_vertex_deviation, 2.0);
+/// assert!(!diff.is_identical() && diff.is_topologically_equal());
+/// println!("{}", diff);
+/// ```
+pub fn geometry_diff(a: &Geometry, b: &Geometry) -> GeometryDiff {
+    let first_difference = match a.coords_iter().zip(b.coords_iter()).position(|(p, q)| p != q) {
+        Some(index) => Some(index),
+        None if a.coords_count() != b.coords_count() => Some(a.coords_count().min(b.coords_count())),
+        None => None,
+    };
+    GeometryDiff {
+        types: (wkt::tag(a), wkt::tag(b)),
+        vertices: (a.coords_count(), b.coords_count()),
+        components: (a.num_components(), b.num_components()),
+        first_difference,
+        max_vertex_deviation: deviation(a, b).max(deviation(b, a)),
+        area_delta: b.area() - a.area(),
+        length_delta: b.length() - a.length(),
+        relation: a.relate(b),
+    }
+}
+
+/// Returns the largest distance from a vertex of the first geometry to the closest of the second.
+fn deviation(from: &Geometry, to: &Geometry) -> f64 {
+    if from.coords_count() == 0 {
+        return 0.0;
+    }
+    let to: Vec<Coordinate> = to.coords_iter().cloned().collect();
+    let tree = KdTree::new(&to);
+    from.coords_iter()
+        .map(|p| tree.nearest(p).map_or(f64::INFINITY, |i| p.distance_2d(&to[i])))
+        .fold(0.0, f64::max)
+}
+
+impl fmt::Display for GeometryDiff {
+    /// Writes the differences as a report of aligned lines, one for every measure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::io::diff::geometry_diff;
+    ///
+    /// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(2, 0)] };
+    /// let simplified = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(2, 0)] };
+    /// assert_eq!(geometry_diff(&line, &simplified).to_string(), "\
+    /// type:             LINESTRING -> LINESTRING
+    /// vertices:         3 -> 2 (-1)
+    /// components:       1 -> 1
+    /// first difference: vertex 1
+    /// vertex deviation: 1.4142135623730951
+    /// area delta:       0
+    /// length delta:     -0.8284271247461903
+    /// DE-9IM:           FF1F0F1F2");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "type:             {} -> {}", self.types.0, self.types.1)?;
+        let change = self.vertices.1 as i64 - self.vertices.0 as i64;
+        match change {
+            0 => writeln!(f, "vertices:         {} -> {}", self.vertices.0, self.vertices.1)?,
+            _ => writeln!(f, "vertices:         {} -> {} ({:+})", self.vertices.0, self.vertices.1, change)?,
+        }
+        writeln!(f, "components:       {} -> {}", self.components.0, self.components.1)?;
+        match self.first_difference {
+            Some(index) => writeln!(f, "first difference: vertex {}", index)?,
+            None => writeln!(f, "first difference: none")?,
+        }
+        writeln!(f, "vertex deviation: {}", self.max_vertex_deviation)?;
+        writeln!(f, "area delta:       {}", self.area_delta)?;
+        writeln!(f, "length delta:     {}", self.length_delta)?;
+        write!(f, "DE-9IM:           {}", self.relation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_diff_of_a_clipped_polygon_and_of_empty_geometries() {
+        let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+        let half = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 4), coord!(0, 4), coord!(0, 0)]] };
+        let diff = geometry_diff(&square, &half);
+        assert_eq!(diff.area_delta, -8.0);
+        assert_eq!(diff.length_delta, -4.0);
+        assert_eq!(diff.max_vertex_deviation, 2.0);
+        //the half is within the square
+        assert_eq!(diff.relation.to_string(), "212F11FF2");
+        assert!(diff.relation.matches("T*****FF*") && !diff.is_topologically_equal());
+        assert!(geometry_diff(&square, &square).is_identical());
+
+        let empty = Geometry::MultiPolygon { coordinates: vec![] };
+        let diff = geometry_diff(&empty, &square);
+        assert_eq!((diff.first_difference, diff.max_vertex_deviation), (Some(0), f64::INFINITY));
+        assert_eq!(diff.relation.to_string(), "FFFFFF212");
+        assert!(geometry_diff(&empty, &Geometry::GeometryCollection { geometries: vec![] }).is_topologically_equal());
+    }
+}
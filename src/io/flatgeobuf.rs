@@ -0,0 +1,774 @@
+//! Reading and writing of FlatGeobuf, the binary format storing a header and then every feature
+//! as a FlatBuffers table, available with the `flatgeobuf` feature.
+//!
+//! Features are read one at a time, skipping the spatial index, so files larger than memory can
+//! be processed with the adapters of [`crate::stream::FeatureStream`]. Curves and measures are
+//! not supported.
+
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+
+use flatbuffers::{FlatBufferBuilder, Follow, ForwardsUOffset, InvalidFlatbuffer, Table, Vector, Verifiable, Verifier, WIPOffset};
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::feature::{Feature, FeatureCollection, Value};
+use crate::geometry::Geometry;
+use crate::io::{has_z, Budget, Limits};
+
+/// The bytes every FlatGeobuf file starts with, for version 3.
+const MAGIC: [u8; 8] = [0x66, 0x67, 0x62, 0x03, 0x66, 0x67, 0x62, 0x00];
+
+/// The size of a node of the packed Hilbert R-tree following the header.
+const INDEX_NODE_BYTES: u64 = 40;
+
+//the codes of the geometry types
+const UNKNOWN: u8 = 0;
+const POINT: u8 = 1;
+const LINE_STRING: u8 = 2;
+const POLYGON: u8 = 3;
+const MULTI_POINT: u8 = 4;
+const MULTI_LINE_STRING: u8 = 5;
+const MULTI_POLYGON: u8 = 6;
+const GEOMETRY_COLLECTION: u8 = 7;
+const POLYHEDRAL_SURFACE: u8 = 15;
+const TIN: u8 = 16;
+const TRIANGLE: u8 = 17;
+
+/// The types of the columns holding the properties of the features.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum ColumnType {
+    Byte,
+    UByte,
+    Bool,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    Float,
+    Double,
+    String,
+    Json,
+    DateTime,
+    Binary,
+}
+
+impl ColumnType {
+    fn from_code(code: u8) -> Result<ColumnType, Error> {
+        use ColumnType::*;
+        let types = [Byte, UByte, Bool, Short, UShort, Int, UInt, Long, ULong, Float, Double, String, Json, DateTime, Binary];
+        types.get(code as usize).copied().ok_or_else(|| invalid(&format!("unknown column type {}", code)))
+    }
+}
+
+/// A reader of the features of a FlatGeobuf file, reading a feature only when the next one is
+/// asked for.
+///
+/// The header is read when the reader is created. The properties become numbers, booleans or
+/// strings, with JSON and dates kept as their text, while binary properties are skipped. A
+/// feature that cannot be read gives an error, and reading stops after it, as the next one
+/// cannot be found.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::feature::{Feature, FeatureCollection, Value};
+/// use geoms::geometry::Geometry;
+/// use geoms::io::flatgeobuf::{self, FeatureReader};
+///
+/// let wells = FeatureCollection::new(vec![
+///     Feature::new(Geometry::Point { coordinates: coord!(3, 4) }).with_property("depth", 120.0),
+///     Feature::new(Geometry::Point { coordinates: coord!(5, 6) }).with_property("depth", 80.0),
+/// ]);
+/// let bytes = flatgeobuf::write_features(&wells);
+/// let mut reader = FeatureReader::new(bytes.as_slice()).unwrap();
+/// assert_eq!(reader.features_count(), Some(2));
+/// assert_eq!(reader.columns(), ["depth"]);
+/// let first = reader.next().unwrap().unwrap();
+/// assert_eq!(first.property("depth"), Some(&Value::Number(120.0)));
+/// assert_eq!(reader.count(), 1);
+/// ```
+pub struct FeatureReader<R> {
+    reader: R,
+    limits: Limits,
+    geometry_type: u8,
+    columns: Vec<(String, ColumnType)>,
+    features_count: Option<u64>,
+    //set once the input ends or a feature cannot be read
+    done: bool,
+}
+
+impl<R: Read> FeatureReader<R> {
+    /// Creates a reader accepting geometries of any size, reading the header and skipping the
+    /// spatial index. Returns an error if the input does not start with a FlatGeobuf header.
+    pub fn new(mut reader: R) -> Result<FeatureReader<R>, Error> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(|_| invalid("missing the magic bytes"))?;
+        //the last byte is the patch version, which does not change the layout
+        if magic[..7] != MAGIC[..7] {
+            return Err(invalid("not a FlatGeobuf file of version 3"));
+        }
+        let bytes = read_table(&mut reader)?.ok_or_else(|| invalid("missing the header"))?;
+        let header = flatbuffers::size_prefixed_root::<HeaderTable>(&bytes).map_err(malformed)?;
+        let columns = header.columns().map_or(Ok(Vec::new()), |c| column_types(&c))?;
+        let features_count = header.features_count();
+        let geometry_type = header.geometry_type();
+        let node_size = header.index_node_size();
+        if node_size > 0 && features_count > 0 {
+            let size = index_size(features_count, node_size)?;
+            if io::copy(&mut (&mut reader).take(size), &mut io::sink())? != size {
+                return Err(invalid("the spatial index is cut short"));
+            }
+        }
+        Ok(FeatureReader {
+            reader,
+            limits: Limits::new(),
+            geometry_type,
+            columns,
+            features_count: (features_count > 0).then_some(features_count),
+            done: false,
+        })
+    }
+
+    /// Returns the reader failing on every feature whose geometry passes one of the limits.
+    pub fn with_limits(self, limits: Limits) -> FeatureReader<R> {
+        FeatureReader { limits, ..self }
+    }
+
+    /// Returns the names of the columns declared in the header.
+    pub fn columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Returns the number of features declared in the header, or None if it is not known.
+    pub fn features_count(&self) -> Option<u64> {
+        self.features_count
+    }
+
+    /// Reads the next feature, or None at the end of the input.
+    fn feature(&mut self) -> Result<Option<Feature>, Error> {
+        let bytes = match read_table(&mut self.reader)? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+        let table = flatbuffers::size_prefixed_root::<FeatureTable>(&bytes).map_err(malformed)?;
+        let mut budget = Budget::new(self.limits);
+        let mut feature = match table.geometry() {
+            Some(g) => Feature::new(geometry(&g, self.geometry_type, &mut budget)?),
+            None => Feature::new(Geometry::GeometryCollection { geometries: vec![] }),
+        };
+        let own_columns = table.columns().map(|c| column_types(&c)).transpose()?;
+        let columns = own_columns.as_ref().unwrap_or(&self.columns);
+        if let Some(properties) = table.properties() {
+            read_properties(properties.bytes(), columns, &mut feature)?;
+        }
+        Ok(Some(feature))
+    }
+}
+
+impl<R: Read> Iterator for FeatureReader<R> {
+    type Item = Result<Feature, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let feature = self.feature().transpose();
+        self.done = !matches!(feature, Some(Ok(_)));
+        feature
+    }
+}
+
+/// Returns the features of a FlatGeobuf file, or the first error met reading them.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::feature::{Feature, FeatureCollection};
+/// use geoms::geometry::Geometry;
+/// use geoms::io::flatgeobuf;
+///
+/// let parcel = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 3), coord!(0, 0)]] };
+/// let bytes = flatgeobuf::write_features(&FeatureCollection::new(vec![Feature::new(parcel.clone())]));
+/// assert_eq!(flatgeobuf::read_features(&bytes).unwrap().features()[0].geometry(), &parcel);
+/// assert!(flatgeobuf::read_features(b"fgb").is_err());
+/// ```
+pub fn read_features(bytes: &[u8]) -> Result<FeatureCollection, Error> {
+    FeatureReader::new(bytes)?.collect::<Result<Vec<Feature>, Error>>().map(FeatureCollection::new)
+}
+
+/// Returns the features as a FlatGeobuf file without a spatial index. The header declares the
+/// type of the geometries when they all have the same, and a column for every property. Columns
+/// holding only booleans or only numbers get that type, and the others hold strings, with the
+/// booleans and numbers written as text. Linear rings are written as line strings.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::feature::{Feature, FeatureCollection, Value};
+/// use geoms::geometry::Geometry;
+/// use geoms::io::flatgeobuf;
+///
+/// let stations = FeatureCollection::new(vec![
+///     Feature::new(Geometry::Point { coordinates: coord!(1, 2, 30) }).with_property("code", "A1"),
+///     Feature::new(Geometry::Point { coordinates: coord!(2, 3, 40) }).with_property("code", 7.0),
+/// ]);
+/// let read = flatgeobuf::read_features(&flatgeobuf::write_features(&stations)).unwrap();
+/// assert_eq!(read.features()[0].geometry(), stations.features()[0].geometry());
+/// assert_eq!(read.features()[1].property("code"), Some(&Value::String("7".to_string())));
+/// ```
+pub fn write_features(features: &FeatureCollection) -> Vec<u8> {
+    let columns = columns_of(features);
+    let with_z = features.iter().any(|f| has_z(f.geometry()));
+    let mut types = features.iter().map(|f| type_code(f.geometry()));
+    let first = types.next().unwrap_or(UNKNOWN);
+    let geometry_type = if types.all(|t| t == first) { first } else { UNKNOWN };
+
+    let mut bytes = MAGIC.to_vec();
+    let mut builder = FlatBufferBuilder::new();
+    let column_offsets: Vec<_> = columns
+        .iter()
+        .map(|(name, column_type)| {
+            let name = builder.create_string(name);
+            let start = builder.start_table();
+            builder.push_slot_always(COLUMN_NAME, name);
+            builder.push_slot_always(COLUMN_TYPE, *column_type as u8);
+            WIPOffset::<ColumnTable>::new(builder.end_table(start).value())
+        })
+        .collect();
+    let column_vector = builder.create_vector(&column_offsets);
+    let start = builder.start_table();
+    builder.push_slot_always(HEADER_GEOMETRY_TYPE, geometry_type);
+    builder.push_slot_always(HEADER_HAS_Z, with_z);
+    builder.push_slot_always(HEADER_COLUMNS, column_vector);
+    builder.push_slot_always(HEADER_FEATURES_COUNT, features.len() as u64);
+    builder.push_slot_always(HEADER_INDEX_NODE_SIZE, 0u16);
+    let header = builder.end_table(start);
+    builder.finish_size_prefixed(header, None);
+    bytes.extend_from_slice(builder.finished_data());
+
+    for feature in features.iter() {
+        let mut builder = FlatBufferBuilder::new();
+        let geometry = write_geometry(&mut builder, feature.geometry(), with_z);
+        let properties = builder.create_vector(&properties_of(feature, &columns));
+        let start = builder.start_table();
+        builder.push_slot_always(FEATURE_GEOMETRY, geometry);
+        builder.push_slot_always(FEATURE_PROPERTIES, properties);
+        let table = builder.end_table(start);
+        builder.finish_size_prefixed(table, None);
+        bytes.extend_from_slice(builder.finished_data());
+    }
+    bytes
+}
+
+/// Reads a table preceded by its size, returning both, or None if the input ends before it.
+fn read_table<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, Error> {
+    let mut prefix = [0u8; 4];
+    let mut read = 0;
+    while read < prefix.len() {
+        match reader.read(&mut prefix[read..])? {
+            0 if read == 0 => return Ok(None),
+            0 => return Err(invalid("the size of a table is cut short")),
+            n => read += n,
+        }
+    }
+    let size = u32::from_le_bytes(prefix) as u64;
+    //the table is read in pieces, so a corrupt size does not allocate it upfront, and kept after
+    //its size, which the alignment of its fields counts in
+    let mut bytes = prefix.to_vec();
+    reader.take(size).read_to_end(&mut bytes)?;
+    if ((bytes.len() - prefix.len()) as u64) < size {
+        return Err(invalid("a table is cut short"));
+    }
+    Ok(Some(bytes))
+}
+
+/// Returns the size in bytes of the packed Hilbert R-tree of the features.
+fn index_size(features: u64, node_size: u16) -> Result<u64, Error> {
+    if node_size < 2 {
+        return Err(invalid("the nodes of the spatial index need at least two items"));
+    }
+    //the leaves, and then every level up to the root, which is a level of its own even over a
+    //single feature
+    let (mut level, mut nodes) = (features, features);
+    loop {
+        level = level.div_ceil(node_size as u64);
+        nodes = nodes.saturating_add(level);
+        if level == 1 {
+            break;
+        }
+    }
+    nodes.checked_mul(INDEX_NODE_BYTES).ok_or_else(|| invalid("the spatial index is too large"))
+}
+
+/// Returns the names and types of the columns.
+fn column_types(columns: &Vector<'_, ForwardsUOffset<ColumnTable<'_>>>) -> Result<Vec<(String, ColumnType)>, Error> {
+    columns.iter().map(|c| Ok((c.name().to_string(), ColumnType::from_code(c.column_type())?))).collect()
+}
+
+/// Sets the properties of the feature from their encoding: the index of the column as a 16 bit
+/// integer followed by the value, with strings preceded by their length.
+fn read_properties(bytes: &[u8], columns: &[(String, ColumnType)], feature: &mut Feature) -> Result<(), Error> {
+    let mut cursor = Cursor(bytes);
+    macro_rules! number {
+        ($t:ty) => {
+            Value::Number(<$t>::from_le_bytes(cursor.array()?) as f64)
+        };
+    }
+    while !cursor.0.is_empty() {
+        let index = u16::from_le_bytes(cursor.array()?) as usize;
+        let (name, column_type) = columns.get(index).ok_or_else(|| invalid(&format!("no column {}", index)))?;
+        let value = match column_type {
+            ColumnType::Byte => number!(i8),
+            ColumnType::UByte => number!(u8),
+            ColumnType::Bool => Value::Bool(cursor.array::<1>()?[0] != 0),
+            ColumnType::Short => number!(i16),
+            ColumnType::UShort => number!(u16),
+            ColumnType::Int => number!(i32),
+            ColumnType::UInt => number!(u32),
+            ColumnType::Long => number!(i64),
+            ColumnType::ULong => number!(u64),
+            ColumnType::Float => number!(f32),
+            ColumnType::Double => number!(f64),
+            ColumnType::String | ColumnType::Json | ColumnType::DateTime | ColumnType::Binary => {
+                let length = u32::from_le_bytes(cursor.array()?) as usize;
+                let bytes = cursor.take(length)?;
+                if *column_type == ColumnType::Binary {
+                    continue;
+                }
+                let text = std::str::from_utf8(bytes).map_err(|_| invalid("a string is not UTF-8"))?;
+                Value::String(text.to_string())
+            }
+        };
+        feature.set_property(name, value);
+    }
+    Ok(())
+}
+
+/// The bytes of the properties left to read.
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < n {
+            return Err(invalid("the properties are cut short"));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+}
+
+/// Returns the columns for the properties of the features, in the order of their names.
+fn columns_of(features: &FeatureCollection) -> Vec<(String, ColumnType)> {
+    let mut columns: BTreeMap<&str, ColumnType> = BTreeMap::new();
+    for (name, value) in features.iter().flat_map(|f| f.properties()) {
+        let column_type = match value {
+            Value::Null => continue,
+            Value::Bool(_) => ColumnType::Bool,
+            Value::Number(_) => ColumnType::Double,
+            Value::String(_) => ColumnType::String,
+        };
+        columns
+            .entry(name)
+            .and_modify(|t| {
+                if *t != column_type {
+                    *t = ColumnType::String;
+                }
+            })
+            .or_insert(column_type);
+    }
+    columns.into_iter().map(|(name, t)| (name.to_string(), t)).collect()
+}
+
+/// Returns the encoded properties of the feature, without its nulls.
+fn properties_of(feature: &Feature, columns: &[(String, ColumnType)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (index, (name, column_type)) in columns.iter().enumerate() {
+        let value = match feature.property(name) {
+            None | Some(Value::Null) => continue,
+            Some(value) => value,
+        };
+        bytes.extend_from_slice(&(index as u16).to_le_bytes());
+        match (column_type, value) {
+            (ColumnType::Bool, Value::Bool(b)) => bytes.push(*b as u8),
+            (ColumnType::Double, Value::Number(n)) => bytes.extend_from_slice(&n.to_le_bytes()),
+            (_, value) => {
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Bool(b) => b.to_string(),
+                    Value::Number(n) => n.to_string(),
+                    Value::Null => unreachable!(),
+                };
+                bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+                bytes.extend_from_slice(text.as_bytes());
+            }
+        }
+    }
+    bytes
+}
+
+/// Returns the code of the type of the geometry.
+fn type_code(geometry: &Geometry) -> u8 {
+    match geometry {
+        Geometry::Point { .. } => POINT,
+        Geometry::LineString { .. } | Geometry::LinearRing { .. } => LINE_STRING,
+        Geometry::Polygon { .. } => POLYGON,
+        Geometry::MultiPoint { .. } => MULTI_POINT,
+        Geometry::MultiLineString { .. } => MULTI_LINE_STRING,
+        Geometry::MultiPolygon { .. } => MULTI_POLYGON,
+        Geometry::GeometryCollection { .. } => GEOMETRY_COLLECTION,
+        Geometry::PolyhedralSurface { .. } => POLYHEDRAL_SURFACE,
+        Geometry::Tin { .. } => TIN,
+    }
+}
+
+/// Writes the geometry table: the coordinates of simple geometries with the ends of their
+/// rings or lines, and the parts of the others.
+fn write_geometry<'a>(builder: &mut FlatBufferBuilder<'a>, geometry: &Geometry, with_z: bool) -> WIPOffset<GeometryTable<'a>> {
+    let polygons = |builder: &mut FlatBufferBuilder<'a>, polygons: &[Vec<Vec<Coordinate>>], code: u8| -> Vec<_> {
+        polygons.iter().map(|rings| write_paths(builder, rings.iter().map(|r| r.as_slice()), code, with_z)).collect()
+    };
+    let parts: Vec<WIPOffset<GeometryTable>> = match geometry {
+        Geometry::Point { coordinates } => return write_paths(builder, [std::slice::from_ref(coordinates)], POINT, with_z),
+        Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } | Geometry::MultiPoint { coordinates } => {
+            return write_paths(builder, [coordinates.as_slice()], type_code(geometry), with_z)
+        }
+        Geometry::Polygon { coordinates } | Geometry::MultiLineString { coordinates } => {
+            return write_paths(builder, coordinates.iter().map(|p| p.as_slice()), type_code(geometry), with_z)
+        }
+        Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+            polygons(builder, coordinates, POLYGON)
+        }
+        Geometry::Tin { coordinates } => {
+            coordinates.iter().map(|t| write_paths(builder, [t.as_slice()], TRIANGLE, with_z)).collect()
+        }
+        Geometry::GeometryCollection { geometries } => {
+            geometries.iter().map(|g| write_geometry(builder, g, with_z)).collect()
+        }
+    };
+    let parts = builder.create_vector(&parts);
+    let start = builder.start_table();
+    builder.push_slot_always(GEOMETRY_PARTS, parts);
+    builder.push_slot_always(GEOMETRY_TYPE, type_code(geometry));
+    WIPOffset::new(builder.end_table(start).value())
+}
+
+/// Writes a geometry table holding the coordinates of the paths, with their ends when there are
+/// several.
+fn write_paths<'a, 'p>(
+    builder: &mut FlatBufferBuilder<'a>,
+    paths: impl IntoIterator<Item = &'p [Coordinate]>,
+    code: u8,
+    with_z: bool,
+) -> WIPOffset<GeometryTable<'a>> {
+    let (mut xy, mut z, mut ends) = (Vec::new(), Vec::new(), Vec::new());
+    for path in paths {
+        for c in path {
+            xy.extend([c.x(), c.y()]);
+            z.push(c.z());
+        }
+        ends.push(z.len() as u32);
+    }
+    let ends = (ends.len() > 1).then(|| builder.create_vector(&ends));
+    let xy = builder.create_vector(&xy);
+    let z = with_z.then(|| builder.create_vector(&z));
+    let start = builder.start_table();
+    if let Some(ends) = ends {
+        builder.push_slot_always(GEOMETRY_ENDS, ends);
+    }
+    builder.push_slot_always(GEOMETRY_XY, xy);
+    if let Some(z) = z {
+        builder.push_slot_always(GEOMETRY_Z, z);
+    }
+    builder.push_slot_always(GEOMETRY_TYPE, code);
+    WIPOffset::new(builder.end_table(start).value())
+}
+
+/// Returns the geometry of a table, whose type is the one of the header unless it is unknown.
+fn geometry(table: &GeometryTable, header_type: u8, budget: &mut Budget) -> Result<Geometry, Error> {
+    budget.enter()?;
+    let code = match header_type {
+        UNKNOWN => table.geometry_type(),
+        code => code,
+    };
+    let parts = |budget: &mut Budget, part_type: u8| -> Result<Vec<Geometry>, Error> {
+        let parts = table.parts().map_or_else(Vec::new, |p| p.iter().collect());
+        budget.components(parts.len())?;
+        parts.iter().map(|p| geometry(p, part_type, budget)).collect()
+    };
+    let rings = |g: Geometry| match g {
+        Geometry::Polygon { coordinates } => coordinates,
+        _ => unreachable!(),
+    };
+    let result = match code {
+        POINT => match paths(table, budget)?.pop().and_then(|mut p| p.pop()) {
+            Some(c) => Geometry::Point { coordinates: c },
+            None => return Err(invalid("a point has no coordinates")),
+        },
+        LINE_STRING => Geometry::LineString { coordinates: paths(table, budget)?.into_iter().flatten().collect() },
+        MULTI_POINT => Geometry::MultiPoint { coordinates: paths(table, budget)?.into_iter().flatten().collect() },
+        POLYGON | TRIANGLE => Geometry::Polygon { coordinates: paths(table, budget)? },
+        MULTI_LINE_STRING => Geometry::MultiLineString { coordinates: paths(table, budget)? },
+        MULTI_POLYGON => Geometry::MultiPolygon { coordinates: parts(budget, POLYGON)?.into_iter().map(rings).collect() },
+        POLYHEDRAL_SURFACE => {
+            Geometry::PolyhedralSurface { coordinates: parts(budget, POLYGON)?.into_iter().map(rings).collect() }
+        }
+        TIN => Geometry::Tin {
+            coordinates: parts(budget, TRIANGLE)?.into_iter().flat_map(|t| rings(t).into_iter().take(1)).collect(),
+        },
+        GEOMETRY_COLLECTION => Geometry::GeometryCollection { geometries: parts(budget, UNKNOWN)? },
+        code => return Err(invalid(&format!("unsupported geometry type {}", code))),
+    };
+    budget.leave();
+    Ok(result)
+}
+
+/// Returns the coordinates of a table, split at the ends of its rings or lines.
+fn paths(table: &GeometryTable, budget: &mut Budget) -> Result<Vec<Vec<Coordinate>>, Error> {
+    let xy = table.xy().map_or_else(Vec::new, |v| v.iter().collect::<Vec<f64>>());
+    if !xy.len().is_multiple_of(2) {
+        return Err(invalid("an odd number of ordinates"));
+    }
+    let count = xy.len() / 2;
+    budget.vertices(count)?;
+    let z: Vec<f64> = table.z().map_or_else(Vec::new, |v| v.iter().collect());
+    if !z.is_empty() && z.len() != count {
+        return Err(invalid("the z values do not match the coordinates"));
+    }
+    let ends: Vec<usize> = match table.ends() {
+        Some(ends) if !ends.is_empty() => ends.iter().map(|e| e as usize).collect(),
+        _ => vec![count],
+    };
+    budget.components(ends.len())?;
+    let mut paths = Vec::with_capacity(ends.len());
+    let mut start = 0;
+    for end in ends {
+        if end < start || end > count {
+            return Err(invalid("the ends of the parts are out of order"));
+        }
+        let mut path = Vec::with_capacity(end - start);
+        for i in start..end {
+            let (x, y, z) = (xy[2 * i], xy[2 * i + 1], z.get(i).copied().unwrap_or(0.0));
+            if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+                return Err(invalid("coordinates must be finite"));
+            }
+            path.push(Coordinate::new(x, y, z));
+        }
+        paths.push(path);
+        start = end;
+    }
+    Ok(paths)
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidFlatGeobuf(message.to_string())
+}
+
+fn malformed(error: InvalidFlatbuffer) -> Error {
+    Error::InvalidFlatGeobuf(error.to_string())
+}
+
+//the offsets of the fields in the vtables of the tables, from the schemas of FlatGeobuf
+const HEADER_GEOMETRY_TYPE: u16 = 8;
+const HEADER_HAS_Z: u16 = 10;
+const HEADER_COLUMNS: u16 = 18;
+const HEADER_FEATURES_COUNT: u16 = 20;
+const HEADER_INDEX_NODE_SIZE: u16 = 22;
+const COLUMN_NAME: u16 = 4;
+const COLUMN_TYPE: u16 = 6;
+const FEATURE_GEOMETRY: u16 = 4;
+const FEATURE_PROPERTIES: u16 = 6;
+const FEATURE_COLUMNS: u16 = 8;
+const GEOMETRY_ENDS: u16 = 4;
+const GEOMETRY_XY: u16 = 6;
+const GEOMETRY_Z: u16 = 8;
+const GEOMETRY_TYPE: u16 = 16;
+const GEOMETRY_PARTS: u16 = 18;
+
+/// Declares a view of a FlatBuffers table, with a verifier of the fields it reads and accessors
+/// for them, which are safe once the buffer is verified.
+macro_rules! table {
+    ($name:ident { $($field:ident: $t:ty = $slot:ident $(, $default:expr)?;)* }) => {
+        #[derive(Clone, Copy)]
+        struct $name<'a> {
+            table: Table<'a>,
+        }
+
+        impl<'a> Follow<'a> for $name<'a> {
+            type Inner = $name<'a>;
+
+            unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+                $name { table: Table::new(buf, loc) }
+            }
+        }
+
+        impl<'a> Verifiable for $name<'a> {
+            fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+                v.visit_table(pos)?
+                    $(.visit_field::<table!(@verify $t)>(stringify!($field), $slot, false)?)*
+                    .finish();
+                Ok(())
+            }
+        }
+
+        impl<'a> $name<'a> {
+            $(
+                fn $field(&self) -> table!(@output 'a, $t $(, $default)?) {
+                    //safety: the buffer was verified by flatbuffers::size_prefixed_root
+                    unsafe { self.table.get::<table!(@verify $t)>($slot, table!(@default $($default)?)) $(.unwrap_or($default))? }
+                }
+            )*
+        }
+    };
+    (@verify $t:ty) => { $t };
+    (@output $a:lifetime, $t:ty, $default:expr) => { <$t as Follow<$a>>::Inner };
+    (@output $a:lifetime, $t:ty) => { Option<<$t as Follow<$a>>::Inner> };
+    (@default $default:expr) => { Some($default) };
+    (@default) => { None };
+}
+
+table!(HeaderTable {
+    geometry_type: u8 = HEADER_GEOMETRY_TYPE, 0;
+    columns: ForwardsUOffset<Vector<'a, ForwardsUOffset<ColumnTable<'a>>>> = HEADER_COLUMNS;
+    features_count: u64 = HEADER_FEATURES_COUNT, 0;
+    index_node_size: u16 = HEADER_INDEX_NODE_SIZE, 16;
+});
+
+table!(ColumnTable {
+    name: ForwardsUOffset<&'a str> = COLUMN_NAME, "";
+    column_type: u8 = COLUMN_TYPE, 0;
+});
+
+table!(FeatureTable {
+    geometry: ForwardsUOffset<GeometryTable<'a>> = FEATURE_GEOMETRY;
+    properties: ForwardsUOffset<Vector<'a, u8>> = FEATURE_PROPERTIES;
+    columns: ForwardsUOffset<Vector<'a, ForwardsUOffset<ColumnTable<'a>>>> = FEATURE_COLUMNS;
+});
+
+table!(GeometryTable {
+    ends: ForwardsUOffset<Vector<'a, u32>> = GEOMETRY_ENDS;
+    xy: ForwardsUOffset<Vector<'a, f64>> = GEOMETRY_XY;
+    z: ForwardsUOffset<Vector<'a, f64>> = GEOMETRY_Z;
+    geometry_type: u8 = GEOMETRY_TYPE, 0;
+    parts: ForwardsUOffset<Vector<'a, ForwardsUOffset<GeometryTable<'a>>>> = GEOMETRY_PARTS;
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_round_trip_of_mixed_features() {
+        let square = |x: f64, z: f64| vec![coord!(x, 0, z), coord!(x + 1.0, 0, z), coord!(x + 1.0, 1, z), coord!(x, 1, z), coord!(x, 0, z)];
+        let hole = vec![coord!(0.2, 0.2), coord!(0.2, 0.8), coord!(0.8, 0.8), coord!(0.2, 0.2)];
+        let geometries = vec![
+            Geometry::Point { coordinates: coord!(1, 2, 3) },
+            Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 0), coord!(1, 1)], vec![coord!(2, 2), coord!(3, 3)]] },
+            Geometry::MultiPolygon { coordinates: vec![vec![square(0.0, 0.0), hole], vec![square(5.0, 0.0)]] },
+            Geometry::GeometryCollection { geometries: vec![
+                Geometry::Point { coordinates: coord!(0, 0) },
+                Geometry::GeometryCollection { geometries: vec![Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 1)] }] },
+            ] },
+            Geometry::PolyhedralSurface { coordinates: vec![vec![square(0.0, 1.0)], vec![square(1.0, 1.0)]] },
+            Geometry::Tin { coordinates: vec![vec![coord!(0, 0, 1), coord!(1, 0, 1), coord!(0, 1, 2), coord!(0, 0, 1)]] },
+            Geometry::MultiPoint { coordinates: vec![] },
+        ];
+        let features = FeatureCollection::new(
+            geometries
+                .iter()
+                .enumerate()
+                .map(|(i, g)| {
+                    let feature = Feature::new(g.clone()).with_property("id", i as f64).with_property("even", i % 2 == 0);
+                    match i {
+                        0 => feature.with_property("name", "first").with_property("note", Value::Null),
+                        _ => feature,
+                    }
+                })
+                .collect(),
+        );
+        let bytes = write_features(&features);
+        let mut reader = FeatureReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.geometry_type, UNKNOWN);
+        assert_eq!(reader.columns(), ["even", "id", "name"]);
+        let read: Vec<Feature> = reader.by_ref().map(|f| f.unwrap()).collect();
+        for (feature, geometry) in read.iter().zip(&geometries) {
+            assert_eq!(feature.geometry(), geometry);
+        }
+        //nulls are left out
+        assert_eq!(read[0].properties().len(), 3);
+        assert_eq!(read[0].property("name"), Some(&Value::String("first".to_string())));
+        assert_eq!(read[3].property("even"), Some(&Value::Bool(false)));
+        assert_eq!(read[3].property("name"), None);
+    }
+
+    #[test]
+    fn test_features_after_a_spatial_index() {
+        assert_eq!(index_size(1, 16).unwrap(), 2 * INDEX_NODE_BYTES);
+        assert_eq!(index_size(100, 16).unwrap(), (100 + 7 + 1) * INDEX_NODE_BYTES);
+        assert!(index_size(100, 1).is_err());
+
+        //the header of a file of three points with an index, which the reader skips
+        let points = FeatureCollection::new((0..3).map(|i| Feature::new(Geometry::Point { coordinates: coord!(i, i) })).collect());
+        let written = write_features(&points);
+        let header_end = 12 + u32::from_le_bytes(written[8..12].try_into().unwrap()) as usize;
+        let mut builder = FlatBufferBuilder::new();
+        let start = builder.start_table();
+        builder.push_slot_always(HEADER_GEOMETRY_TYPE, POINT);
+        builder.push_slot_always(HEADER_FEATURES_COUNT, 3u64);
+        let header = builder.end_table(start);
+        builder.finish_size_prefixed(header, None);
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(builder.finished_data());
+        bytes.extend(std::iter::repeat_n(0xff, (index_size(3, 16).unwrap()) as usize));
+        bytes.extend_from_slice(&written[header_end..]);
+        assert_eq!(read_features(&bytes).unwrap(), points);
+        //an index cut short
+        assert!(FeatureReader::new(&bytes[..header_end + 50]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let line = Geometry::LineString { coordinates: (0..10).map(|i| coord!(i, 0)).collect() };
+        let bytes = write_features(&FeatureCollection::new(vec![Feature::new(line.clone()), Feature::new(line)]));
+        assert!(matches!(FeatureReader::new(&b"fgb\x02fgb\x00"[..]), Err(Error::InvalidFlatGeobuf(_))));
+        assert!(matches!(FeatureReader::new(&bytes[..10]), Err(Error::InvalidFlatGeobuf(_))));
+        //the first feature is read, the second is cut short and ends the reading
+        let results: Vec<Result<Feature, Error>> = FeatureReader::new(&bytes[..bytes.len() - 3]).unwrap().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok() && matches!(results[1], Err(Error::InvalidFlatGeobuf(_))));
+        //a corrupt table
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 20;
+        corrupt[last..].fill(0xff);
+        assert!(read_features(&corrupt).is_err());
+        //too many vertices
+        let limited = FeatureReader::new(bytes.as_slice()).unwrap().with_limits(Limits::new().with_max_vertices(9));
+        assert!(limited.map(|f| f.unwrap_err()).all(|e| matches!(e, Error::LimitExceeded(_))));
+    }
+
+    #[test]
+    fn test_streamed_features() {
+        use crate::envelope::Envelope;
+        use crate::stream::FeatureStream;
+
+        let roads = FeatureCollection::new((0..100).map(|i| Feature::new(Geometry::LineString { coordinates: vec![coord!(i, 0), coord!(i, 10)] })).collect());
+        let bytes = write_features(&roads);
+        let clipped = FeatureReader::new(bytes.as_slice()).unwrap().clip(Envelope::new(-0.5, 0.0, 9.5, 5.0)).filter_features(|f| f.geometry().length() > 0.0);
+        let chunks: Vec<usize> = clipped.chunks(4).map(|chunk| chunk.unwrap().len()).collect();
+        assert_eq!(chunks, [4, 4, 2]);
+    }
+}
@@ -0,0 +1,990 @@
+//! Reading and writing of geometries and features as GeoJSON (RFC 7946).
+//!
+//! Coordinates are written as `[x, y]` unless any coordinate of the geometry has a z value other
+//! than 0, in which case they are written as `[x, y, z]`. GeoJSON has no linear rings, polyhedral
+//! surfaces nor TINs: linear rings are written as line strings, and the patches or triangles of
+//! surfaces as the polygons of a multi polygon.
+//!
+//! Features can also be read and written one at a time as GeoJSON text sequences (RFC 8142), each
+//! feature on its own line after a record separator, or as newline delimited GeoJSON without them.
+
+use std::io::{BufRead, Write};
+
+use crate::algorithm::area::ring_signed_area;
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::feature::{Feature, FeatureCollection, Value};
+use crate::geometry::Geometry;
+use crate::io::{has_z, Budget, Limits};
+
+/// The character starting every text of a GeoJSON text sequence.
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// How the rings of polygons are oriented when written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RingOrientation {
+    /// Rings are written as they are.
+    #[default]
+    Preserve,
+    /// Shells are written counter clockwise and holes clockwise, as RFC 7946 requires.
+    Rfc7946,
+}
+
+/// Writes a geometry as GeoJSON, with its rings as they are.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::geojson;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1.5, 2)] };
+/// assert_eq!(geojson::write(&line), r#"{"type":"LineString","coordinates":[[0,0],[1.5,2]]}"#);
+///
+/// let point = Geometry::Point { coordinates: coord!(1, 2, 3) };
+/// assert_eq!(geojson::write(&point), r#"{"type":"Point","coordinates":[1,2,3]}"#);
+/// ```
+pub fn write(geometry: &Geometry) -> String {
+    write_with_orientation(geometry, RingOrientation::Preserve)
+}
+
+/// Writes a geometry as GeoJSON with the rings of its polygons oriented as required.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::geojson::{self, RingOrientation};
+///
+/// //a clockwise shell
+/// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(0, 1), coord!(1, 0), coord!(0, 0)]] };
+/// assert_eq!(
+///     geojson::write_with_orientation(&polygon, RingOrientation::Rfc7946),
+///     r#"{"type":"Polygon","coordinates":[[[0,0],[1,0],[0,1],[0,0]]]}"#
+/// );
+/// ```
+pub fn write_with_orientation(geometry: &Geometry, orientation: RingOrientation) -> String {
+    let mut out = String::new();
+    write_geometry(geometry, has_z(geometry), orientation, &mut out);
+    out
+}
+
+/// Writes a geometry as GeoJSON over several lines, indented by two spaces for every level of
+/// nesting, with every position on a single line. See [`write`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::geojson;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1.5, 2)] };
+/// assert_eq!(
+///     geojson::write_pretty(&line),
+///     "{\n  \"type\": \"LineString\",\n  \"coordinates\": [\n    [0, 0],\n    [1.5, 2]\n  ]\n}"
+/// );
+/// ```
+pub fn write_pretty(geometry: &Geometry) -> String {
+    let json = parse(&write(geometry), usize::MAX).expect("The GeoJSON written is valid");
+    let mut out = String::new();
+    write_json_pretty(&json, 0, &mut out);
+    out
+}
+
+/// Writes a collection of features as a GeoJSON feature collection.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::{Feature, FeatureCollection};
+/// use geoms::io::geojson::{self, RingOrientation};
+///
+/// let wells = FeatureCollection::new(vec![
+///     Feature::new(Geometry::Point { coordinates: coord!(3, 4) }).with_property("name", "north"),
+/// ]);
+/// assert_eq!(
+///     geojson::write_features(&wells, RingOrientation::Preserve),
+///     r#"{"type":"FeatureCollection","features":[{"type":"Feature","geometry":{"type":"Point","coordinates":[3,4]},"properties":{"name":"north"}}]}"#
+/// );
+/// ```
+pub fn write_features(features: &FeatureCollection, orientation: RingOrientation) -> String {
+    let mut out = String::from(r#"{"type":"FeatureCollection","features":["#);
+    for (i, feature) in features.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_feature(feature, orientation, &mut out);
+    }
+    out.push_str("]}");
+    out
+}
+
+/// Writes a collection of features as a GeoJSON text sequence (RFC 8142): every feature on its
+/// own line, after a record separator. See [`SeqWriter`] to write them one at a time.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::{Feature, FeatureCollection};
+/// use geoms::io::geojson::{self, RingOrientation};
+///
+/// let wells = FeatureCollection::new(vec![
+///     Feature::new(Geometry::Point { coordinates: coord!(3, 4) }),
+///     Feature::new(Geometry::Point { coordinates: coord!(5, 6) }).with_property("dry", true),
+/// ]);
+/// assert_eq!(
+///     geojson::write_seq(&wells, RingOrientation::Preserve),
+///     concat!(
+///         "\u{1e}{\"type\":\"Feature\",\"geometry\":{\"type\":\"Point\",\"coordinates\":[3,4]},\"properties\":{}}\n",
+///         "\u{1e}{\"type\":\"Feature\",\"geometry\":{\"type\":\"Point\",\"coordinates\":[5,6]},\"properties\":{\"dry\":true}}\n",
+///     )
+/// );
+/// ```
+pub fn write_seq(features: &FeatureCollection, orientation: RingOrientation) -> String {
+    let mut out = String::new();
+    for feature in features {
+        out.push(RECORD_SEPARATOR);
+        write_feature(feature, orientation, &mut out);
+        out.push('\n');
+    }
+    out
+}
+
+/// A writer of features as a GeoJSON text sequence, writing every feature as soon as it is given.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::Feature;
+/// use geoms::io::geojson::SeqWriter;
+///
+/// let mut writer = SeqWriter::new(Vec::new()).with_separators(false);
+/// writer.write(&Feature::new(Geometry::Point { coordinates: coord!(3, 4) })).unwrap();
+/// let text = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(text, "{\"type\":\"Feature\",\"geometry\":{\"type\":\"Point\",\"coordinates\":[3,4]},\"properties\":{}}\n");
+/// ```
+pub struct SeqWriter<W> {
+    writer: W,
+    orientation: RingOrientation,
+    separators: bool,
+}
+
+impl<W: Write> SeqWriter<W> {
+    /// Creates a writer of RFC 8142 text sequences, keeping the rings as they are.
+    pub fn new(writer: W) -> SeqWriter<W> {
+        SeqWriter { writer, orientation: RingOrientation::Preserve, separators: true }
+    }
+
+    /// Returns the writer orienting the rings of polygons as required.
+    pub fn with_orientation(self, orientation: RingOrientation) -> SeqWriter<W> {
+        SeqWriter { orientation, ..self }
+    }
+
+    /// Returns the writer starting every feature with a record separator if `separators` is true,
+    /// as RFC 8142 requires, or writing newline delimited GeoJSON otherwise.
+    pub fn with_separators(self, separators: bool) -> SeqWriter<W> {
+        SeqWriter { separators, ..self }
+    }
+
+    /// Writes a feature, or returns the error of the underlying writer.
+    pub fn write(&mut self, feature: &Feature) -> Result<(), Error> {
+        let mut out = String::new();
+        if self.separators {
+            out.push(RECORD_SEPARATOR);
+        }
+        write_feature(feature, self.orientation, &mut out);
+        out.push('\n');
+        Ok(self.writer.write_all(out.as_bytes())?)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn write_feature(feature: &Feature, orientation: RingOrientation, out: &mut String) {
+    out.push_str(r#"{"type":"Feature","geometry":"#);
+    let geometry = feature.geometry();
+    write_geometry(geometry, has_z(geometry), orientation, out);
+    out.push_str(r#","properties":{"#);
+    for (i, (name, value)) in feature.properties().iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(name, out);
+        out.push(':');
+        match value {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => out.push_str(&b.to_string()),
+            Value::Number(n) if n.is_finite() => out.push_str(&n.to_string()),
+            Value::Number(_) => out.push_str("null"),
+            Value::String(s) => write_string(s, out),
+        }
+    }
+    out.push_str("}}");
+}
+
+fn write_geometry(geometry: &Geometry, z: bool, orientation: RingOrientation, out: &mut String) {
+    let (kind, member) = match geometry {
+        Geometry::Point { .. } => ("Point", "coordinates"),
+        Geometry::LineString { .. } | Geometry::LinearRing { .. } => ("LineString", "coordinates"),
+        Geometry::Polygon { .. } => ("Polygon", "coordinates"),
+        Geometry::MultiPoint { .. } => ("MultiPoint", "coordinates"),
+        Geometry::MultiLineString { .. } => ("MultiLineString", "coordinates"),
+        Geometry::MultiPolygon { .. } | Geometry::PolyhedralSurface { .. } | Geometry::Tin { .. } => {
+            ("MultiPolygon", "coordinates")
+        }
+        Geometry::GeometryCollection { .. } => ("GeometryCollection", "geometries"),
+    };
+    out.push_str(&format!(r#"{{"type":"{}","{}":"#, kind, member));
+    match geometry {
+        Geometry::Point { coordinates } => write_position(coordinates, z, out),
+        Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } | Geometry::MultiPoint { coordinates } => {
+            write_path(coordinates, z, out)
+        }
+        Geometry::Polygon { coordinates } => write_rings(coordinates, z, orientation, out),
+        Geometry::MultiLineString { coordinates } => write_array(coordinates, out, |line, out| write_path(line, z, out)),
+        Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+            write_array(coordinates, out, |polygon, out| write_rings(polygon, z, orientation, out))
+        }
+        Geometry::Tin { coordinates } => write_array(coordinates, out, |triangle, out| {
+            write_rings(std::slice::from_ref(triangle), z, orientation, out)
+        }),
+        Geometry::GeometryCollection { geometries } => {
+            write_array(geometries, out, |geometry, out| write_geometry(geometry, z, orientation, out))
+        }
+    }
+    out.push('}');
+}
+
+fn write_array<T, F>(items: &[T], out: &mut String, mut write_item: F)
+where
+    F: FnMut(&T, &mut String),
+{
+    out.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_item(item, out);
+    }
+    out.push(']');
+}
+
+fn write_position(coordinate: &Coordinate, z: bool, out: &mut String) {
+    out.push_str(&format!("[{},{}", coordinate.x(), coordinate.y()));
+    if z {
+        out.push_str(&format!(",{}", coordinate.z()));
+    }
+    out.push(']');
+}
+
+fn write_path(path: &[Coordinate], z: bool, out: &mut String) {
+    write_array(path, out, |c, out| write_position(c, z, out))
+}
+
+fn write_rings(rings: &[Vec<Coordinate>], z: bool, orientation: RingOrientation, out: &mut String) {
+    write_array(rings, out, |ring, out| {
+        let area = ring_signed_area(ring);
+        //shells go counter clockwise, with a positive area, and holes clockwise
+        let reverse = orientation == RingOrientation::Rfc7946 && area != 0.0 && (area > 0.0) != std::ptr::eq(ring, &rings[0]);
+        if reverse {
+            let reversed: Vec<Coordinate> = ring.iter().rev().cloned().collect();
+            write_path(&reversed, z, out);
+        } else {
+            write_path(ring, z, out);
+        }
+    })
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Reads a geometry from GeoJSON. The rings of polygons are accepted in either orientation and
+/// kept as they are, and members other than the type and the coordinates or geometries, such as
+/// bounding boxes, are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::geojson;
+///
+/// let polygon = geojson::read(r#"{"type": "Polygon", "coordinates": [[[0, 0], [0, 1], [1, 0], [0, 0]]]}"#).unwrap();
+/// assert_eq!(polygon, Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(0, 1), coord!(1, 0), coord!(0, 0)]] });
+///
+/// assert!(geojson::read(r#"{"type": "Point", "coordinates": [1]}"#).is_err());
+/// ```
+pub fn read(text: &str) -> Result<Geometry, Error> {
+    read_with_limits(text, Limits::new())
+}
+
+/// Reads a geometry from GeoJSON, failing as soon as the geometry passes one of the limits. The
+/// depth limit also bounds the nesting of the JSON document, allowing two levels per geometry
+/// collection and six more for the enclosing feature and the coordinate arrays.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::error::Error;
+/// use geoms::io::{geojson, Limits};
+///
+/// let text = r#"{"type": "MultiPoint", "coordinates": [[0, 0], [1, 1], [2, 2]]}"#;
+/// assert!(geojson::read_with_limits(text, Limits::new().with_max_components(3)).is_ok());
+/// assert!(matches!(geojson::read_with_limits(text, Limits::new().with_max_components(2)), Err(Error::LimitExceeded(_))));
+/// ```
+pub fn read_with_limits(text: &str, limits: Limits) -> Result<Geometry, Error> {
+    let mut budget = Budget::new(limits);
+    geometry(&parse(text, json_depth(limits))?, &mut budget)
+}
+
+/// Reads features from GeoJSON: a feature collection, a single feature, or a bare geometry read
+/// as a feature without properties. Features without a geometry get an empty geometry
+/// collection. Properties holding objects or arrays keep their JSON text as a string.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::Value;
+/// use geoms::io::geojson;
+///
+/// let text = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "geometry": {"type": "Point", "coordinates": [3, 4]}, "properties": {"depth": 120, "tags": ["a", "b"]}},
+///     {"type": "Feature", "geometry": null, "properties": null}
+/// ]}"#;
+/// let features = geojson::read_features(text).unwrap();
+/// assert_eq!(features.len(), 2);
+/// assert_eq!(features.features()[0].property("depth"), Some(&Value::Number(120.0)));
+/// assert_eq!(features.features()[0].property("tags"), Some(&Value::String(r#"["a","b"]"#.to_string())));
+/// assert_eq!(features.features()[1].geometry(), &Geometry::GeometryCollection { geometries: vec![] });
+/// ```
+pub fn read_features(text: &str) -> Result<FeatureCollection, Error> {
+    read_features_with_limits(text, Limits::new())
+}
+
+/// Reads features from GeoJSON, failing as soon as their geometries together pass one of the
+/// limits.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::io::{geojson, Limits};
+///
+/// let text = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "geometry": {"type": "Point", "coordinates": [3, 4]}, "properties": {}},
+///     {"type": "Feature", "geometry": {"type": "Point", "coordinates": [5, 6]}, "properties": {}}
+/// ]}"#;
+/// assert!(geojson::read_features_with_limits(text, Limits::new().with_max_vertices(2)).is_ok());
+/// assert!(geojson::read_features_with_limits(text, Limits::new().with_max_vertices(1)).is_err());
+/// ```
+pub fn read_features_with_limits(text: &str, limits: Limits) -> Result<FeatureCollection, Error> {
+    let json = parse(text, json_depth(limits))?;
+    let mut budget = Budget::new(limits);
+    match member_str(&json, "type")? {
+        "FeatureCollection" => match json.member("features") {
+            Some(Json::Array(features)) => Ok(FeatureCollection::new(
+                features.iter().map(|f| feature(f, &mut budget)).collect::<Result<_, _>>()?,
+            )),
+            _ => Err(invalid("a feature collection must have a 'features' array")),
+        },
+        "Feature" => Ok(FeatureCollection::new(vec![feature(&json, &mut budget)?])),
+        _ => Ok(FeatureCollection::new(vec![Feature::new(geometry(&json, &mut budget)?)])),
+    }
+}
+
+/// Reads the features of a GeoJSON text sequence (RFC 8142), or of newline delimited GeoJSON, as
+/// a collection, or returns the first error. See [`SeqReader`] to read them one at a time.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::io::geojson;
+///
+/// let text = "{\"type\": \"Point\", \"coordinates\": [3, 4]}\n\n{\"type\": \"Point\", \"coordinates\": [5, 6]}\n";
+/// assert_eq!(geojson::read_seq(text).unwrap().len(), 2);
+/// assert!(geojson::read_seq("{\"type\": \"Point\"}").is_err());
+/// ```
+pub fn read_seq(text: &str) -> Result<FeatureCollection, Error> {
+    SeqReader::new(text.as_bytes()).collect::<Result<Vec<Feature>, Error>>().map(FeatureCollection::new)
+}
+
+/// A reader of the features of a GeoJSON text sequence, reading a text only when the next feature
+/// is asked for, so it can be chained with the adapters of [`crate::stream::FeatureStream`].
+///
+/// Once a record separator is met the input is read as an RFC 8142 sequence, where a text goes on
+/// until the next separator and may span several lines. Before that, every line that is not blank
+/// is a text, as in newline delimited GeoJSON. Every text is a feature or a geometry, read as a
+/// feature without properties. A text that cannot be read gives an error, and reading goes on with
+/// the next one.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::io::geojson::SeqReader;
+///
+/// let text = "\u{1e}{\"type\": \"Point\",\n \"coordinates\": [3, 4]}\n\u{1e}{\"type\": \"Point\"}\n\u{1e}{\"type\": \"Point\", \"coordinates\": [5, 6]}\n";
+/// let features: Vec<_> = SeqReader::new(text.as_bytes()).collect();
+/// assert_eq!(features.len(), 3);
+/// assert!(features[0].is_ok() && features[1].is_err() && features[2].is_ok());
+/// ```
+pub struct SeqReader<R> {
+    reader: R,
+    limits: Limits,
+    //whether the input has record separators, and the text read since the last one
+    separated: bool,
+    pending: String,
+}
+
+impl<R: BufRead> SeqReader<R> {
+    /// Creates a reader accepting geometries of any size.
+    pub fn new(reader: R) -> SeqReader<R> {
+        SeqReader { reader, limits: Limits::new(), separated: false, pending: String::new() }
+    }
+
+    /// Returns the reader failing on every feature whose geometry passes one of the limits.
+    pub fn with_limits(self, limits: Limits) -> SeqReader<R> {
+        SeqReader { limits, ..self }
+    }
+
+    /// Reads a text of the sequence as a feature.
+    fn feature(&self, text: &str) -> Result<Feature, Error> {
+        let json = parse(text.trim(), json_depth(self.limits))?;
+        let mut budget = Budget::new(self.limits);
+        match member_str(&json, "type")? {
+            "Feature" => feature(&json, &mut budget),
+            _ => Ok(Feature::new(geometry(&json, &mut budget)?)),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SeqReader<R> {
+    type Item = Result<Feature, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Err(error) => return Some(Err(error.into())),
+                Ok(0) => {
+                    let text = std::mem::take(&mut self.pending);
+                    return (!text.trim().is_empty()).then(|| self.feature(&text));
+                }
+                Ok(_) => {}
+            }
+            if let Some(rest) = line.strip_prefix(RECORD_SEPARATOR) {
+                self.separated = true;
+                let text = std::mem::replace(&mut self.pending, rest.to_string());
+                if !text.trim().is_empty() {
+                    return Some(self.feature(&text));
+                }
+            } else if self.separated {
+                self.pending.push_str(&line);
+            } else if !line.trim().is_empty() {
+                return Some(self.feature(&line));
+            }
+        }
+    }
+}
+
+/// Returns the nesting of JSON values allowed by the depth limit.
+fn json_depth(limits: Limits) -> usize {
+    limits.max_depth().saturating_mul(2).saturating_add(6)
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidGeoJson(message.to_string())
+}
+
+fn member_str<'a>(json: &'a Json, name: &str) -> Result<&'a str, Error> {
+    match json.member(name) {
+        Some(Json::String(value)) => Ok(value),
+        _ => Err(Error::InvalidGeoJson(format!("expected a '{}' string member", name))),
+    }
+}
+
+fn feature(json: &Json, budget: &mut Budget) -> Result<Feature, Error> {
+    if member_str(json, "type")? != "Feature" {
+        return Err(invalid("expected a feature"));
+    }
+    let mut feature = match json.member("geometry") {
+        None | Some(Json::Null) => Feature::new(Geometry::GeometryCollection { geometries: vec![] }),
+        Some(g) => Feature::new(geometry(g, budget)?),
+    };
+    match json.member("properties") {
+        None | Some(Json::Null) => {}
+        Some(Json::Object(members)) => {
+            for (name, value) in members {
+                let value = match value {
+                    Json::Null => Value::Null,
+                    Json::Bool(b) => Value::Bool(*b),
+                    Json::Number(n) => Value::Number(*n),
+                    Json::String(s) => Value::String(s.clone()),
+                    other => Value::String(other.to_string()),
+                };
+                feature.set_property(name, value);
+            }
+        }
+        Some(_) => return Err(invalid("the properties of a feature must be an object")),
+    }
+    Ok(feature)
+}
+
+fn geometry(json: &Json, budget: &mut Budget) -> Result<Geometry, Error> {
+    let kind = member_str(json, "type")?;
+    budget.enter()?;
+    let geometry = if kind == "GeometryCollection" {
+        match json.member("geometries") {
+            Some(geometries) => Geometry::GeometryCollection { geometries: parts(geometries, budget, geometry)? },
+            _ => return Err(invalid("a geometry collection must have a 'geometries' array")),
+        }
+    } else {
+        let coordinates = json.member("coordinates").ok_or_else(|| invalid("a geometry must have a 'coordinates' member"))?;
+        match kind {
+            "Point" => {
+                budget.vertices(1)?;
+                Geometry::Point { coordinates: position(coordinates)? }
+            }
+            "LineString" => Geometry::LineString { coordinates: path(coordinates, budget)? },
+            "Polygon" => Geometry::Polygon { coordinates: parts(coordinates, budget, path)? },
+            "MultiPoint" => Geometry::MultiPoint {
+                coordinates: parts(coordinates, budget, |point, budget| {
+                    budget.vertices(1)?;
+                    position(point)
+                })?,
+            },
+            "MultiLineString" => Geometry::MultiLineString { coordinates: parts(coordinates, budget, path)? },
+            "MultiPolygon" => Geometry::MultiPolygon {
+                coordinates: parts(coordinates, budget, |polygon, budget| parts(polygon, budget, path))?,
+            },
+            _ => return Err(Error::InvalidGeoJson(format!("unknown geometry type '{}'", kind))),
+        }
+    };
+    budget.leave();
+    Ok(geometry)
+}
+
+/// Returns the items of an array of components.
+fn parts<T>(json: &Json, budget: &mut Budget, item: impl Fn(&Json, &mut Budget) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    match json {
+        Json::Array(items) => {
+            budget.components(items.len())?;
+            items.iter().map(|i| item(i, budget)).collect()
+        }
+        _ => Err(invalid("expected an array of components")),
+    }
+}
+
+fn path(json: &Json, budget: &mut Budget) -> Result<Vec<Coordinate>, Error> {
+    match json {
+        Json::Array(items) => {
+            budget.vertices(items.len())?;
+            items.iter().map(position).collect()
+        }
+        _ => Err(invalid("expected an array of positions")),
+    }
+}
+
+/// Returns the coordinate of a position. Values beyond the third, such as measures, are ignored.
+fn position(json: &Json) -> Result<Coordinate, Error> {
+    let values = match json {
+        Json::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                Json::Number(n) => Ok(*n),
+                _ => Err(invalid("positions must hold numbers")),
+            })
+            .collect::<Result<Vec<f64>, Error>>()?,
+        _ => return Err(invalid("expected a position")),
+    };
+    match values[..] {
+        [x, y] => Ok(Coordinate::new(x, y, 0.0)),
+        [x, y, z, ..] => Ok(Coordinate::new(x, y, z)),
+        _ => Err(Error::InvalidGeoJson(format!("expected at least 2 ordinates but found {}", values.len()))),
+    }
+}
+
+/// A parsed JSON value. Objects keep the order of their members.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Returns the first member of an object with the name, or None if there is none or the
+    /// value is not an object.
+    pub(crate) fn member(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(n, _)| n == name).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Json {
+    /// Writes the value as compact JSON.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut out = String::new();
+        write_json(self, &mut out);
+        f.write_str(&out)
+    }
+}
+
+fn write_json(json: &Json, out: &mut String) {
+    match json {
+        Json::Null => out.push_str("null"),
+        Json::Bool(b) => out.push_str(&b.to_string()),
+        Json::Number(n) => out.push_str(&n.to_string()),
+        Json::String(s) => write_string(s, out),
+        Json::Array(items) => write_array(items, out, write_json),
+        Json::Object(members) => {
+            out.push('{');
+            for (i, (name, value)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(name, out);
+                out.push(':');
+                write_json(value, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Writes a JSON value over several lines, indented by the level, keeping arrays of numbers, as
+/// positions, on a single line.
+fn write_json_pretty(json: &Json, level: usize, out: &mut String) {
+    let newline = |out: &mut String, level: usize| {
+        out.push('\n');
+        out.push_str(&"  ".repeat(level));
+    };
+    match json {
+        Json::Array(items) if !items.is_empty() && items.iter().all(|item| matches!(item, Json::Number(_))) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_json(item, out);
+            }
+            out.push(']');
+        }
+        Json::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, level + 1);
+                write_json_pretty(item, level + 1, out);
+            }
+            newline(out, level);
+            out.push(']');
+        }
+        Json::Object(members) if !members.is_empty() => {
+            out.push('{');
+            for (i, (name, value)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, level + 1);
+                write_string(name, out);
+                out.push_str(": ");
+                write_json_pretty(value, level + 1, out);
+            }
+            newline(out, level);
+            out.push('}');
+        }
+        json => write_json(json, out),
+    }
+}
+
+/// Parses a JSON document whose arrays and objects are nested at most `max_depth` levels deep.
+pub(crate) fn parse(text: &str, max_depth: usize) -> Result<Json, Error> {
+    let mut parser = JsonParser { chars: text.chars().collect(), position: 0, depth: 0, max_depth };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.chars.get(parser.position) {
+        None => Ok(value),
+        Some(c) => Err(Error::InvalidGeoJson(format!("unexpected '{}' after the value", c))),
+    }
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl JsonParser {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.position).is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.get(self.position).copied();
+        self.position += 1;
+        c
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(Error::InvalidGeoJson(format!("expected '{}' but found '{}'", expected, c))),
+            None => Err(Error::InvalidGeoJson(format!("expected '{}' but found the end of the input", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, Error> {
+        self.skip_whitespace();
+        match self.chars.get(self.position) {
+            Some(c @ ('{' | '[')) => {
+                let object = *c == '{';
+                self.depth += 1;
+                if self.depth > self.max_depth {
+                    return Err(Error::LimitExceeded(format!("JSON nested deeper than {}", self.max_depth)));
+                }
+                let value = if object { self.object() } else { self.array() };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some(c) if c.is_ascii_digit() || *c == '-' => self.number(),
+            Some(_) => {
+                for (word, value) in [("null", Json::Null), ("true", Json::Bool(true)), ("false", Json::Bool(false))] {
+                    let end = self.position + word.len();
+                    if end <= self.chars.len() && self.chars[self.position..end].iter().copied().eq(word.chars()) {
+                        self.position = end;
+                        return Ok(value);
+                    }
+                }
+                Err(Error::InvalidGeoJson(format!("unexpected '{}'", self.chars[self.position])))
+            }
+            None => Err(invalid("unexpected end of input")),
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, Error> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.position) == Some(&']') {
+            self.position += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(items)),
+                _ => return Err(invalid("expected ',' or ']' in an array")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, Error> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.position) == Some(&'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.chars.get(self.position) != Some(&'"') {
+                return Err(invalid("expected a member name"));
+            }
+            let name = self.string()?;
+            self.expect(':')?;
+            members.push((name, self.value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(members)),
+                _ => return Err(invalid("expected ',' or '}' in an object")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.position += 1;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.next() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    Some('/') => text.push('/'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some('u') => {
+                        let mut code = self.hex()?;
+                        //characters beyond the basic plane are written as surrogate pairs
+                        if (0xd800..0xdc00).contains(&code) && self.chars.get(self.position..self.position + 2) == Some(&['\\', 'u']) {
+                            self.position += 2;
+                            let low = self.hex()?;
+                            code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+                        }
+                        text.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    }
+                    _ => return Err(invalid("invalid escape sequence in a string")),
+                },
+                Some(c) => text.push(c),
+                None => return Err(invalid("unterminated string")),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, Error> {
+        let digits: String = self.chars.get(self.position..self.position + 4).unwrap_or_default().iter().collect();
+        self.position += 4;
+        u32::from_str_radix(&digits, 16).map_err(|_| invalid("invalid unicode escape in a string"))
+    }
+
+    fn number(&mut self) -> Result<Json, Error> {
+        let start = self.position;
+        while self.chars.get(self.position).is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            self.position += 1;
+        }
+        let number: String = self.chars[start..self.position].iter().collect();
+        match number.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Json::Number(value)),
+            _ => Err(Error::InvalidGeoJson(format!("invalid number '{}'", number))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_round_trip() {
+        let texts = [
+            r#"{"type":"Point","coordinates":[1,2]}"#,
+            r#"{"type":"LineString","coordinates":[[0,0],[1,1],[2,0.5]]}"#,
+            r#"{"type":"Polygon","coordinates":[[[0,0],[4,0],[4,4],[0,4],[0,0]],[[1,1],[1,2],[2,2],[1,1]]]}"#,
+            r#"{"type":"MultiPoint","coordinates":[[0,0],[1,1]]}"#,
+            r#"{"type":"MultiLineString","coordinates":[[[0,0],[1,1]],[[2,2],[3,3]]]}"#,
+            r#"{"type":"MultiPolygon","coordinates":[[[[0,0],[1,0],[1,1],[0,0]]],[[[5,5],[6,5],[6,6],[5,5]]]]}"#,
+            r#"{"type":"GeometryCollection","geometries":[{"type":"Point","coordinates":[1,2,3]},{"type":"MultiPolygon","coordinates":[]}]}"#,
+        ];
+        for text in texts {
+            assert_eq!(write(&read(text).unwrap()), text);
+        }
+
+        //escaped properties survive
+        let feature = Feature::new(Geometry::Point { coordinates: coord!(0, 0) })
+            .with_property("name", "a \"quoted\"\nname é")
+            .with_property("flag", true)
+            .with_property("nothing", Value::Null);
+        let collection = FeatureCollection::new(vec![feature]);
+        assert_eq!(read_features(&write_features(&collection, RingOrientation::Preserve)).unwrap(), collection);
+        assert_eq!(read_features(r#"{"type":"Feature","geometry":null,"properties":{"s":"é😀"}}"#).unwrap().features()[0].property("s"), Some(&Value::from("é😀")));
+    }
+
+    #[test]
+    fn test_orientation_and_errors() {
+        //a counter clockwise hole in a clockwise shell, inside a surface patch
+        let rings = vec![
+            vec![coord!(0, 0), coord!(0, 4), coord!(4, 4), coord!(4, 0), coord!(0, 0)],
+            vec![coord!(1, 1), coord!(2, 1), coord!(2, 2), coord!(1, 1)],
+        ];
+        let surface = Geometry::PolyhedralSurface { coordinates: vec![rings.clone()] };
+        let written = write_with_orientation(&surface, RingOrientation::Rfc7946);
+        assert_eq!(
+            written,
+            r#"{"type":"MultiPolygon","coordinates":[[[[0,0],[4,0],[4,4],[0,4],[0,0]],[[1,1],[2,2],[2,1],[1,1]]]]}"#
+        );
+        let Geometry::MultiPolygon { coordinates } = read(&written).unwrap() else { panic!() };
+        assert!(ring_signed_area(&coordinates[0][0]) > 0.0 && ring_signed_area(&coordinates[0][1]) < 0.0);
+        assert_eq!(read(&write(&surface)).unwrap(), Geometry::MultiPolygon { coordinates: vec![rings] });
+
+        for text in [
+            "",
+            "{",
+            r#"{"type":"Point"}"#,
+            r#"{"type":"Circle","coordinates":[0,0]}"#,
+            r#"{"type":"LineString","coordinates":[[0,"1"]]}"#,
+            r#"{"type":"Point","coordinates":[0,0]} x"#,
+            r#"{"type":"Point","coordinates":[0,1e999]}"#,
+        ] {
+            assert!(matches!(read(text), Err(Error::InvalidGeoJson(_))), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_seq_round_trip_through_a_stream() {
+        use crate::stream::FeatureStream;
+
+        let mut writer = SeqWriter::new(Vec::new());
+        for i in 0..5 {
+            let line = Geometry::LineString { coordinates: vec![coord!(i, 0), coord!(i, 10)] };
+            writer.write(&Feature::new(line).with_property("id", i as f64)).unwrap();
+        }
+        let text = writer.into_inner();
+        let kept = SeqReader::new(text.as_slice())
+            .filter_features(|f| f.property("id").and_then(Value::as_f64).is_some_and(|id| id >= 2.0))
+            .collect_features()
+            .unwrap();
+        assert_eq!(kept.len(), 3);
+        assert_eq!(read_seq(&write_seq(&kept, RingOrientation::Preserve)).unwrap(), kept);
+        //a truncated text does not stop the ones after it
+        let truncated = "\u{1e}{\"type\":\"Point\",\"coord\n\u{1e}{\"type\":\"Point\",\"coordinates\":[1,2]}\n";
+        let features: Vec<Result<Feature, Error>> = SeqReader::new(truncated.as_bytes()).collect();
+        assert!(features[0].is_err());
+        assert_eq!(features[1].as_ref().unwrap().geometry(), &Geometry::Point { coordinates: coord!(1, 2) });
+        let limited = SeqReader::new(text.as_slice()).with_limits(Limits::new().with_max_vertices(1));
+        assert!(limited.into_iter().all(|f| matches!(f, Err(Error::LimitExceeded(_)))));
+    }
+}
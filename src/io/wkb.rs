@@ -0,0 +1,653 @@
+//! Reading and writing of geometries as ISO Well Known Binary.
+//!
+//! Geometries are written in 2D unless any of their coordinates has a z value other than 0,
+//! in which case the ISO 3D type codes are used. Linear rings are written as line strings.
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::io::{has_z, Budget, Limits, ParseMode};
+
+/// The byte order of the numbers in a WKB buffer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ByteOrder {
+    BigEndian,
+    #[default]
+    LittleEndian,
+}
+
+/// The case of the letters of hex encoded WKB.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HexCase {
+    /// Uppercase letters, as written by PostGIS.
+    #[default]
+    Upper,
+    Lower,
+}
+
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+const POLYHEDRAL_SURFACE: u32 = 15;
+const TIN: u32 = 16;
+const TRIANGLE: u32 = 17;
+
+//flags of the PostGIS extended WKB type codes
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// Writes a geometry as little endian WKB.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkb;
+///
+/// let point = Geometry::Point { coordinates: coord!(1, 2) };
+/// let bytes = wkb::write(&point);
+/// assert_eq!(bytes.len(), 21);
+/// assert_eq!(bytes[0..5], [1, 1, 0, 0, 0]);
+/// assert_eq!(wkb::read(&bytes).unwrap(), point);
+/// ```
+pub fn write(geometry: &Geometry) -> Vec<u8> {
+    write_with_byte_order(geometry, ByteOrder::LittleEndian)
+}
+
+/// Writes a geometry as WKB with the given byte order.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkb::{self, ByteOrder};
+///
+/// let point = Geometry::Point { coordinates: coord!(1, 2, 3) };
+/// let bytes = wkb::write_with_byte_order(&point, ByteOrder::BigEndian);
+/// assert_eq!(bytes[0..5], [0, 0, 0, 0x03, 0xe9]);
+/// assert_eq!(wkb::read(&bytes).unwrap(), point);
+/// ```
+pub fn write_with_byte_order(geometry: &Geometry, byte_order: ByteOrder) -> Vec<u8> {
+    let mut writer = Writer { out: Vec::new(), byte_order, z: has_z(geometry) };
+    writer.geometry(geometry);
+    writer.out
+}
+
+/// Writes a geometry as little endian WKB encoded as hex digits.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkb::{self, HexCase};
+///
+/// let point = Geometry::Point { coordinates: coord!(1, 2) };
+/// assert_eq!(wkb::write_hex(&point, HexCase::Upper), "0101000000000000000000F03F0000000000000040");
+/// assert_eq!(wkb::write_hex(&point, HexCase::Lower), "0101000000000000000000f03f0000000000000040");
+/// ```
+pub fn write_hex(geometry: &Geometry, case: HexCase) -> String {
+    write(geometry)
+        .iter()
+        .map(|byte| match case {
+            HexCase::Upper => format!("{:02X}", byte),
+            HexCase::Lower => format!("{:02x}", byte),
+        })
+        .collect()
+}
+
+struct Writer {
+    out: Vec<u8>,
+    byte_order: ByteOrder,
+    z: bool,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        match self.byte_order {
+            ByteOrder::BigEndian => self.out.extend_from_slice(&value.to_be_bytes()),
+            ByteOrder::LittleEndian => self.out.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+
+    fn f64(&mut self, value: f64) {
+        match self.byte_order {
+            ByteOrder::BigEndian => self.out.extend_from_slice(&value.to_be_bytes()),
+            ByteOrder::LittleEndian => self.out.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+
+    fn header(&mut self, kind: u32) {
+        self.out.push(match self.byte_order {
+            ByteOrder::BigEndian => 0,
+            ByteOrder::LittleEndian => 1,
+        });
+        self.u32(if self.z { kind + 1000 } else { kind });
+    }
+
+    fn coordinate(&mut self, coordinate: &Coordinate) {
+        self.f64(coordinate.x());
+        self.f64(coordinate.y());
+        if self.z {
+            self.f64(coordinate.z());
+        }
+    }
+
+    fn path(&mut self, path: &[Coordinate]) {
+        self.u32(path.len() as u32);
+        path.iter().for_each(|c| self.coordinate(c));
+    }
+
+    fn rings(&mut self, rings: &[Vec<Coordinate>]) {
+        self.u32(rings.len() as u32);
+        rings.iter().for_each(|ring| self.path(ring));
+    }
+
+    fn geometry(&mut self, geometry: &Geometry) {
+        match geometry {
+            Geometry::Point { coordinates } => {
+                self.header(POINT);
+                self.coordinate(coordinates);
+            }
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+                self.header(LINE_STRING);
+                self.path(coordinates);
+            }
+            Geometry::Polygon { coordinates } => {
+                self.header(POLYGON);
+                self.rings(coordinates);
+            }
+            Geometry::MultiPoint { coordinates } => {
+                self.header(MULTI_POINT);
+                self.u32(coordinates.len() as u32);
+                for c in coordinates {
+                    self.header(POINT);
+                    self.coordinate(c);
+                }
+            }
+            Geometry::MultiLineString { coordinates } => {
+                self.header(MULTI_LINE_STRING);
+                self.u32(coordinates.len() as u32);
+                for line in coordinates {
+                    self.header(LINE_STRING);
+                    self.path(line);
+                }
+            }
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                let kind = match geometry {
+                    Geometry::MultiPolygon { .. } => MULTI_POLYGON,
+                    _ => POLYHEDRAL_SURFACE,
+                };
+                self.header(kind);
+                self.u32(coordinates.len() as u32);
+                for polygon in coordinates {
+                    self.header(POLYGON);
+                    self.rings(polygon);
+                }
+            }
+            Geometry::Tin { coordinates } => {
+                self.header(TIN);
+                self.u32(coordinates.len() as u32);
+                for triangle in coordinates {
+                    self.header(TRIANGLE);
+                    self.u32(1);
+                    self.path(triangle);
+                }
+            }
+            Geometry::GeometryCollection { geometries } => {
+                self.header(GEOMETRY_COLLECTION);
+                self.u32(geometries.len() as u32);
+                geometries.iter().for_each(|g| self.geometry(g));
+            }
+        }
+    }
+}
+
+/// Reads a geometry from WKB, in either byte order, in lenient mode.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::io::wkb;
+///
+/// assert!(wkb::read(&[1, 1, 0, 0, 0]).is_err());
+/// ```
+pub fn read(bytes: &[u8]) -> Result<Geometry, Error> {
+    read_with_mode(bytes, ParseMode::Lenient)
+}
+
+/// Reads a geometry from WKB following the parse mode. Strict mode only accepts ISO type codes
+/// whose dimension matches the one of the enclosing geometry, while lenient mode also accepts
+/// the PostGIS extended type codes, ignoring their SRID, and drops the empty points, written
+/// with NaN coordinates, of multi points and collections.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::{wkb, ParseMode};
+///
+/// //a PostGIS point with z and SRID 4326
+/// let mut bytes = vec![1];
+/// bytes.extend_from_slice(&0xa000_0001u32.to_le_bytes());
+/// bytes.extend_from_slice(&4326u32.to_le_bytes());
+/// for value in [1.0f64, 2.0, 3.0] {
+///     bytes.extend_from_slice(&value.to_le_bytes());
+/// }
+/// assert_eq!(wkb::read_with_mode(&bytes, ParseMode::Lenient).unwrap(), Geometry::Point { coordinates: coord!(1, 2, 3) });
+/// assert!(wkb::read_with_mode(&bytes, ParseMode::Strict).is_err());
+/// ```
+pub fn read_with_mode(bytes: &[u8], mode: ParseMode) -> Result<Geometry, Error> {
+    read_with_limits(bytes, mode, Limits::new())
+}
+
+/// Reads a geometry from WKB following the parse mode, failing as soon as the geometry passes
+/// one of the limits. Counts are checked before anything is read for them.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::error::Error;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::{wkb, Limits, ParseMode};
+///
+/// let line = Geometry::from_text("LINESTRING (0 0, 1 1, 2 2)").unwrap();
+/// let limits = Limits::new().with_max_vertices(2);
+/// assert!(matches!(wkb::read_with_limits(&line.as_binary(), ParseMode::Strict, limits), Err(Error::LimitExceeded(_))));
+/// ```
+pub fn read_with_limits(bytes: &[u8], mode: ParseMode, limits: Limits) -> Result<Geometry, Error> {
+    let mut reader = Reader { bytes, position: 0, mode, budget: Budget::new(limits) };
+    let geometry = match reader.geometry(None)? {
+        Some(geometry) => geometry,
+        None => return Err(Error::InvalidWkb("empty points are not supported".to_string())),
+    };
+    if reader.position != bytes.len() {
+        return Err(Error::InvalidWkb(format!(
+            "{} trailing bytes after the geometry",
+            bytes.len() - reader.position
+        )));
+    }
+    Ok(geometry)
+}
+
+/// Reads a geometry from WKB encoded as hex digits of either case, such as the text output of
+/// PostGIS, in lenient mode. A leading `\x`, as in PostgreSQL byte arrays, is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkb;
+///
+/// //a PostGIS point with SRID 4326
+/// let point = wkb::read_hex("0101000020E6100000000000000000F03F0000000000000040").unwrap();
+/// assert_eq!(point, Geometry::Point { coordinates: coord!(1, 2) });
+///
+/// assert!(wkb::read_hex("0101").is_err());
+/// assert!(wkb::read_hex("01G").is_err());
+/// ```
+pub fn read_hex(text: &str) -> Result<Geometry, Error> {
+    let text = text.trim();
+    let digits = text.strip_prefix("\\x").unwrap_or(text).as_bytes();
+    if !digits.len().is_multiple_of(2) {
+        return Err(Error::InvalidWkb("odd number of hex digits".to_string()));
+    }
+    let bytes = digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| Error::InvalidWkb(format!("invalid hex digits '{}'", String::from_utf8_lossy(pair))))
+        })
+        .collect::<Result<Vec<u8>, Error>>()?;
+    read(&bytes)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    mode: ParseMode,
+    budget: Budget,
+}
+
+/// The byte order and dimension of the geometry being read.
+#[derive(Clone, Copy)]
+struct Header {
+    byte_order: ByteOrder,
+    kind: u32,
+    z: bool,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let end = self.position + N;
+        if end > self.bytes.len() {
+            return Err(Error::InvalidWkb("unexpected end of input".to_string()));
+        }
+        let mut buffer = [0; N];
+        buffer.copy_from_slice(&self.bytes[self.position..end]);
+        self.position = end;
+        Ok(buffer)
+    }
+
+    fn u32(&mut self, byte_order: ByteOrder) -> Result<u32, Error> {
+        let bytes = self.take::<4>()?;
+        Ok(match byte_order {
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+        })
+    }
+
+    fn f64(&mut self, byte_order: ByteOrder) -> Result<f64, Error> {
+        let bytes = self.take::<8>()?;
+        Ok(match byte_order {
+            ByteOrder::BigEndian => f64::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => f64::from_le_bytes(bytes),
+        })
+    }
+
+    /// Reads the header of a geometry, which must have the dimension of the enclosing one in
+    /// strict mode.
+    fn header(&mut self, parent: Option<Header>) -> Result<Header, Error> {
+        let byte_order = match self.take::<1>()? {
+            [0] => ByteOrder::BigEndian,
+            [1] => ByteOrder::LittleEndian,
+            [other] => return Err(Error::InvalidWkb(format!("invalid byte order {}", other))),
+        };
+        let code = self.u32(byte_order)?;
+        let (kind, z) = match code / 1000 {
+            0 => (code, false),
+            1 => (code - 1000, true),
+            _ if self.mode == ParseMode::Lenient && code & EWKB_M == 0 && code & (EWKB_Z | EWKB_SRID) != 0 => {
+                if code & EWKB_SRID != 0 {
+                    self.u32(byte_order)?;
+                }
+                (code & 0x0fff_ffff, code & EWKB_Z != 0)
+            }
+            _ => return Err(Error::InvalidWkb(format!("unsupported geometry type {}", code))),
+        };
+        if self.mode == ParseMode::Strict && parent.is_some_and(|parent| parent.z != z) {
+            return Err(Error::InvalidWkb(format!("geometry type {} does not have the dimension of its parent", code)));
+        }
+        Ok(Header { byte_order, kind, z })
+    }
+
+    /// Reads the header of a nested geometry, which must be of the given type.
+    fn expect_header(&mut self, parent: Header, kind: u32) -> Result<Header, Error> {
+        let header = self.header(Some(parent))?;
+        if header.kind != kind {
+            return Err(Error::InvalidWkb(format!("expected geometry type {} but found {}", kind, header.kind)));
+        }
+        Ok(header)
+    }
+
+    fn count(&mut self, header: Header) -> Result<usize, Error> {
+        let count = self.u32(header.byte_order)? as usize;
+        //every item takes at least one byte, which guards against absurd allocations
+        if count > self.bytes.len() - self.position {
+            return Err(Error::InvalidWkb(format!("count of {} items exceeds the input", count)));
+        }
+        Ok(count)
+    }
+
+    fn coordinate(&mut self, header: Header) -> Result<Coordinate, Error> {
+        let x = self.f64(header.byte_order)?;
+        let y = self.f64(header.byte_order)?;
+        let z = if header.z { self.f64(header.byte_order)? } else { 0.0 };
+        if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+            return Err(Error::InvalidWkb("coordinates must be finite".to_string()));
+        }
+        Ok(Coordinate::new(x, y, z))
+    }
+
+    /// Reads the coordinate of a point, or returns None for an empty point, which has NaN
+    /// coordinates and is only accepted inside multi points and collections in lenient mode.
+    fn point(&mut self, header: Header, nested: bool) -> Result<Option<Coordinate>, Error> {
+        let start = self.position;
+        let x = self.f64(header.byte_order)?;
+        let y = self.f64(header.byte_order)?;
+        if x.is_nan() && y.is_nan() {
+            if header.z {
+                self.f64(header.byte_order)?;
+            }
+            if self.mode == ParseMode::Strict || !nested {
+                return Err(Error::InvalidWkb("empty points are not supported".to_string()));
+            }
+            return Ok(None);
+        }
+        self.position = start;
+        self.budget.vertices(1)?;
+        self.coordinate(header).map(Some)
+    }
+
+    fn path(&mut self, header: Header) -> Result<Vec<Coordinate>, Error> {
+        let count = self.count(header)?;
+        self.budget.vertices(count)?;
+        (0..count).map(|_| self.coordinate(header)).collect()
+    }
+
+    fn rings(&mut self, header: Header) -> Result<Vec<Vec<Coordinate>>, Error> {
+        let count = self.count(header)?;
+        self.budget.components(count)?;
+        (0..count).map(|_| self.path(header)).collect()
+    }
+
+    /// Reads a geometry, or returns None for an empty point inside a collection.
+    fn geometry(&mut self, parent: Option<Header>) -> Result<Option<Geometry>, Error> {
+        let header = self.header(parent)?;
+        self.budget.enter()?;
+        let geometry = match header.kind {
+            POINT => match self.point(header, parent.is_some())? {
+                Some(coordinates) => Geometry::Point { coordinates },
+                None => {
+                    self.budget.leave();
+                    return Ok(None);
+                }
+            },
+            LINE_STRING => Geometry::LineString { coordinates: self.path(header)? },
+            POLYGON => Geometry::Polygon { coordinates: self.rings(header)? },
+            MULTI_POINT => {
+                let count = self.count(header)?;
+                self.budget.components(count)?;
+                let mut coordinates = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let point = self.expect_header(header, POINT)?;
+                    coordinates.extend(self.point(point, true)?);
+                }
+                Geometry::MultiPoint { coordinates }
+            }
+            MULTI_LINE_STRING => {
+                let count = self.count(header)?;
+                self.budget.components(count)?;
+                let mut coordinates = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let line = self.expect_header(header, LINE_STRING)?;
+                    coordinates.push(self.path(line)?);
+                }
+                Geometry::MultiLineString { coordinates }
+            }
+            MULTI_POLYGON | POLYHEDRAL_SURFACE => {
+                let count = self.count(header)?;
+                self.budget.components(count)?;
+                let mut coordinates = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let polygon = self.expect_header(header, POLYGON)?;
+                    coordinates.push(self.rings(polygon)?);
+                }
+                if header.kind == MULTI_POLYGON {
+                    Geometry::MultiPolygon { coordinates }
+                } else {
+                    Geometry::PolyhedralSurface { coordinates }
+                }
+            }
+            TIN => {
+                let count = self.count(header)?;
+                self.budget.components(count)?;
+                let mut coordinates = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let triangle = self.expect_header(header, TRIANGLE)?;
+                    let mut rings = self.rings(triangle)?;
+                    if rings.len() != 1 {
+                        return Err(Error::InvalidWkb("triangles must have exactly one ring".to_string()));
+                    }
+                    coordinates.push(rings.remove(0));
+                }
+                Geometry::Tin { coordinates }
+            }
+            GEOMETRY_COLLECTION => {
+                let count = self.count(header)?;
+                self.budget.components(count)?;
+                let mut geometries = Vec::with_capacity(count);
+                for _ in 0..count {
+                    geometries.extend(self.geometry(Some(header))?);
+                }
+                Geometry::GeometryCollection { geometries }
+            }
+            kind => return Err(Error::InvalidWkb(format!("unsupported geometry type {}", kind))),
+        };
+        self.budget.leave();
+        Ok(Some(geometry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::io::{geojson, wkt, DEFAULT_MAX_DEPTH};
+
+    #[test]
+    fn test_round_trip() {
+        let texts = [
+            "POINT (1 2)",
+            "LINESTRING (0 0, 1 1, 2 0.5)",
+            "POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 2 1, 2 2, 1 1))",
+            "MULTIPOINT Z ((0 0 1), (1 1 2))",
+            "MULTILINESTRING ((0 0, 1 1), (2 2, 3 3))",
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))",
+            "GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1), GEOMETRYCOLLECTION EMPTY)",
+            "POLYHEDRALSURFACE Z (((0 0 0, 0 1 0, 1 1 0, 1 0 0, 0 0 0)), ((0 0 0, 0 1 0, 0 1 1, 0 0 1, 0 0 0)))",
+            "TIN Z (((0 0 0, 0 0 1, 0 1 0, 0 0 0)), ((0 0 0, 0 1 0, 1 1 0, 0 0 0)))",
+        ];
+        for text in texts {
+            let geometry = wkt::read(text).unwrap();
+            for byte_order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+                let bytes = write_with_byte_order(&geometry, byte_order);
+                assert_eq!(read(&bytes).unwrap(), geometry);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let bytes = write(&wkt::read("LINESTRING (0 0, 1 1)").unwrap());
+        assert!(read(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(read(&trailing).is_err());
+
+        let mut huge_count = bytes.clone();
+        huge_count[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read(&huge_count).is_err());
+
+        let mut nan = write(&wkt::read("POINT (1 2)").unwrap());
+        nan[5..13].copy_from_slice(&f64::NAN.to_le_bytes());
+        assert!(read(&nan).is_err());
+    }
+
+    #[test]
+    fn test_modes() {
+        //a 3D collection holding a 2D point and an empty point
+        let mut bytes = write(&wkt::read("GEOMETRYCOLLECTION Z (POINT Z (1 2 3))").unwrap());
+        bytes[5..9].copy_from_slice(&3u32.to_le_bytes());
+        bytes.extend(write(&wkt::read("POINT (4 5)").unwrap()));
+        let mut empty = write(&wkt::read("POINT (0 0)").unwrap());
+        empty[5..21].copy_from_slice(&[f64::NAN.to_le_bytes(), f64::NAN.to_le_bytes()].concat());
+        bytes.extend(&empty);
+        assert_eq!(
+            read_with_mode(&bytes, ParseMode::Lenient).map(|g| wkt::write(&g)),
+            Ok("GEOMETRYCOLLECTION Z (POINT Z (1 2 3), POINT Z (4 5 0))".to_string())
+        );
+        assert!(read_with_mode(&bytes, ParseMode::Strict).is_err());
+        assert!(read_with_mode(&empty, ParseMode::Lenient).is_err());
+
+        //a PostGIS multi point with SRID holding extended points without one
+        let mut ewkb = vec![0];
+        ewkb.extend_from_slice(&(EWKB_SRID | 4).to_be_bytes());
+        ewkb.extend_from_slice(&4326u32.to_be_bytes());
+        ewkb.extend_from_slice(&1u32.to_be_bytes());
+        ewkb.push(0);
+        ewkb.extend_from_slice(&1u32.to_be_bytes());
+        ewkb.extend_from_slice(&[1.5f64.to_be_bytes(), 2.5f64.to_be_bytes()].concat());
+        assert_eq!(read(&ewkb).map(|g| wkt::write(&g)), Ok("MULTIPOINT ((1.5 2.5))".to_string()));
+        assert!(read_with_mode(&ewkb, ParseMode::Strict).is_err());
+        ewkb[1] |= 0x40;
+        assert!(read(&ewkb).is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        //9 vertices, 6 components and a depth of 2, counted the same way by every reader
+        let text = "GEOMETRYCOLLECTION (MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5))), POINT (1 2))";
+        let geometry = wkt::read(text).unwrap();
+        let (bytes, json) = (write(&geometry), geojson::write(&geometry));
+        let read_all = |limits: Limits| {
+            [
+                wkt::read_with_limits(text, ParseMode::Strict, limits),
+                read_with_limits(&bytes, ParseMode::Strict, limits),
+                geojson::read_with_limits(&json, limits),
+            ]
+        };
+        let limits = Limits::new().with_max_vertices(9).with_max_components(6).with_max_depth(2);
+        for result in read_all(limits) {
+            assert_eq!(result, Ok(geometry.clone()));
+        }
+        for limits in [limits.with_max_vertices(8), limits.with_max_components(5), limits.with_max_depth(1)] {
+            for result in read_all(limits) {
+                assert!(matches!(result, Err(Error::LimitExceeded(_))));
+            }
+        }
+
+        //deeply nested JSON fails without exhausting the stack
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(matches!(geojson::read_with_limits(&deep, Limits::new().with_max_depth(8)), Err(Error::LimitExceeded(_))));
+
+        //so do deeply nested collections, with the default limits
+        let levels = 200_000;
+        let deep_wkt = format!("{}POINT (0 0){}", "GEOMETRYCOLLECTION (".repeat(levels), ")".repeat(levels));
+        let mut deep_wkb: Vec<u8> = [1, 7, 0, 0, 0, 1, 0, 0, 0].repeat(levels);
+        deep_wkb.extend_from_slice(&write(&Geometry::Point { coordinates: coord!(0, 0) }));
+        let deep_json = format!(
+            "{}{{\"type\": \"Point\", \"coordinates\": [0, 0]}}{}",
+            r#"{"type": "GeometryCollection", "geometries": ["#.repeat(levels),
+            "]}".repeat(levels)
+        );
+        assert!(matches!(wkt::read(&deep_wkt), Err(Error::LimitExceeded(_))));
+        assert!(matches!(read(&deep_wkb), Err(Error::LimitExceeded(_))));
+        assert!(matches!(geojson::read(&deep_json), Err(Error::LimitExceeded(_))));
+        let nested = |levels: usize| format!("{}POINT (0 0){}", "GEOMETRYCOLLECTION (".repeat(levels - 1), ")".repeat(levels - 1));
+        assert!(wkt::read(&nested(DEFAULT_MAX_DEPTH)).is_ok());
+        assert!(wkt::read(&nested(DEFAULT_MAX_DEPTH + 1)).is_err());
+
+        //the WKT reader stops at the limit before reading the rest of the text
+        let long = "LINESTRING (0 0, 1 1, 2 2, #)";
+        assert!(matches!(wkt::read_with_limits(long, ParseMode::Strict, Limits::new().with_max_vertices(2)), Err(Error::LimitExceeded(_))));
+        assert!(matches!(wkt::read(long), Err(Error::InvalidWkt(_))));
+    }
+}
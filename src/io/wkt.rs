@@ -0,0 +1,676 @@
+//! Reading and writing of geometries as Well Known Text.
+//!
+//! Geometries are written in 2D unless any of their coordinates has a z value other than 0,
+//! in which case they are written with the `Z` tag.
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::io::{has_z, Budget, Limits, ParseMode};
+
+/// Writes a geometry as WKT.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkt;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1.5, 2)] };
+/// assert_eq!(wkt::write(&line), "LINESTRING (0 0, 1.5 2)");
+///
+/// let point = Geometry::Point { coordinates: coord!(1, 2, 3) };
+/// assert_eq!(wkt::write(&point), "POINT Z (1 2 3)");
+/// ```
+pub fn write(geometry: &Geometry) -> String {
+    let mut out = String::new();
+    write_geometry(geometry, Style { z: has_z(geometry), precision: None }, &mut out);
+    out
+}
+
+/// Writes a geometry as WKT with the ordinates rounded to the given number of decimals, and
+/// without trailing zeros.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkt;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0.1234, -0.0001), coord!(1.5, 2)] };
+/// assert_eq!(wkt::write_with_precision(&line, 2), "LINESTRING (0.12 0, 1.5 2)");
+/// ```
+pub fn write_with_precision(geometry: &Geometry, precision: usize) -> String {
+    let mut out = String::new();
+    write_geometry(geometry, Style { z: has_z(geometry), precision: Some(precision) }, &mut out);
+    out
+}
+
+/// Writes a geometry as WKT over several lines, for reading large geometries: the parts of multi
+/// geometries, the rings of polygons and the members of collections go on their own lines,
+/// indented by two spaces for every level of nesting, while the coordinates of every point, line
+/// and ring stay on a single line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkt;
+///
+/// let polygon = Geometry::Polygon { coordinates: vec![
+///     vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 0)],
+///     vec![coord!(1, 1), coord!(2, 1), coord!(2, 2), coord!(1, 1)],
+/// ] };
+/// assert_eq!(wkt::write_pretty(&polygon), "POLYGON (\n  (0 0, 4 0, 4 4, 0 0),\n  (1 1, 2 1, 2 2, 1 1)\n)");
+/// ```
+pub fn write_pretty(geometry: &Geometry) -> String {
+    let text = write(geometry);
+    let mut out = String::with_capacity(text.len());
+    //whether every open parenthesis holds parts on their own lines
+    let mut nested: Vec<bool> = Vec::new();
+    let newline = |out: &mut String, level: usize| {
+        out.push('\n');
+        out.push_str(&"  ".repeat(level));
+    };
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => {
+                let parts = chars.peek().is_some_and(|next| *next == '(' || next.is_ascii_alphabetic());
+                out.push('(');
+                nested.push(parts);
+                if parts {
+                    newline(&mut out, nested.len());
+                }
+            }
+            ')' => {
+                if nested.pop() == Some(true) {
+                    newline(&mut out, nested.len());
+                }
+                out.push(')');
+            }
+            ',' if nested.last() == Some(&true) => {
+                out.push(',');
+                newline(&mut out, nested.len());
+                chars.next_if_eq(&' ');
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// How the coordinates are written: with their z values or not, and rounded to a number of
+/// decimals or in full.
+#[derive(Clone, Copy)]
+struct Style {
+    z: bool,
+    precision: Option<usize>,
+}
+
+fn write_geometry(geometry: &Geometry, style: Style, out: &mut String) {
+    out.push_str(tag(geometry));
+    if style.z {
+        out.push_str(" Z");
+    }
+    out.push(' ');
+    match geometry {
+        Geometry::Point { coordinates } => {
+            out.push('(');
+            write_coordinate(coordinates, style, out);
+            out.push(')');
+        }
+        Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+            write_path(coordinates, style, out)
+        }
+        Geometry::Polygon { coordinates } => write_rings(coordinates, style, out),
+        Geometry::MultiPoint { coordinates } => write_list(coordinates, out, |c, out| {
+            out.push('(');
+            write_coordinate(c, style, out);
+            out.push(')');
+        }),
+        Geometry::MultiLineString { coordinates } => {
+            write_list(coordinates, out, |line, out| write_path(line, style, out))
+        }
+        Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+            write_list(coordinates, out, |polygon, out| write_rings(polygon, style, out))
+        }
+        Geometry::Tin { coordinates } => write_list(coordinates, out, |triangle, out| {
+            out.push('(');
+            write_path(triangle, style, out);
+            out.push(')');
+        }),
+        Geometry::GeometryCollection { geometries } => {
+            write_list(geometries, out, |geometry, out| write_geometry(geometry, style, out))
+        }
+    }
+}
+
+/// Returns the WKT tag of the geometry type.
+pub(crate) fn tag(geometry: &Geometry) -> &'static str {
+    match geometry {
+        Geometry::Point { .. } => "POINT",
+        Geometry::LineString { .. } => "LINESTRING",
+        Geometry::LinearRing { .. } => "LINEARRING",
+        Geometry::Polygon { .. } => "POLYGON",
+        Geometry::MultiPoint { .. } => "MULTIPOINT",
+        Geometry::MultiLineString { .. } => "MULTILINESTRING",
+        Geometry::MultiPolygon { .. } => "MULTIPOLYGON",
+        Geometry::GeometryCollection { .. } => "GEOMETRYCOLLECTION",
+        Geometry::PolyhedralSurface { .. } => "POLYHEDRALSURFACE",
+        Geometry::Tin { .. } => "TIN",
+    }
+}
+
+fn write_coordinate(coordinate: &Coordinate, style: Style, out: &mut String) {
+    write_number(coordinate.x(), style, out);
+    out.push(' ');
+    write_number(coordinate.y(), style, out);
+    if style.z {
+        out.push(' ');
+        write_number(coordinate.z(), style, out);
+    }
+}
+
+fn write_number(value: f64, style: Style, out: &mut String) {
+    match style.precision {
+        None => out.push_str(&value.to_string()),
+        Some(precision) => {
+            let text = format!("{:.*}", precision, value);
+            let text = if text.contains('.') { text.trim_end_matches('0').trim_end_matches('.') } else { &text };
+            //values rounded to zero from below would be written as -0
+            out.push_str(if text == "-0" { "0" } else { text });
+        }
+    }
+}
+
+fn write_list<T, F>(items: &[T], out: &mut String, mut write_item: F)
+where
+    F: FnMut(&T, &mut String),
+{
+    if items.is_empty() {
+        out.push_str("EMPTY");
+        return;
+    }
+    out.push('(');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_item(item, out);
+    }
+    out.push(')');
+}
+
+fn write_path(path: &[Coordinate], style: Style, out: &mut String) {
+    write_list(path, out, |c, out| write_coordinate(c, style, out))
+}
+
+fn write_rings(rings: &[Vec<Coordinate>], style: Style, out: &mut String) {
+    write_list(rings, out, |ring, out| write_path(ring, style, out))
+}
+
+/// Reads a geometry from WKT in lenient mode. Tags are case insensitive and the `Z` tag is
+/// optional for 3D coordinates. Measures are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::wkt;
+///
+/// let polygon = wkt::read("POLYGON ((0 0, 1 0, 1 1, 0 0))").unwrap();
+/// assert_eq!(polygon, Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)]] });
+///
+/// let tin = wkt::read("TIN Z (((0 0 0, 1 0 0, 0 1 1, 0 0 0)))").unwrap();
+/// assert_eq!(tin, Geometry::Tin { coordinates: vec![vec![coord!(0, 0, 0), coord!(1, 0, 0), coord!(0, 1, 1), coord!(0, 0, 0)]] });
+///
+/// assert!(wkt::read("POINT (1)").is_err());
+/// ```
+pub fn read(text: &str) -> Result<Geometry, Error> {
+    read_with_mode(text, ParseMode::Lenient)
+}
+
+/// Reads a geometry from WKT following the parse mode.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::io::{wkt, ParseMode};
+///
+/// let text = "SRID=4326;MULTIPOINT (EMPTY, 1 2)";
+/// assert_eq!(wkt::read_with_mode(text, ParseMode::Lenient).unwrap(), Geometry::MultiPoint { coordinates: vec![coord!(1, 2)] });
+/// assert!(wkt::read_with_mode(text, ParseMode::Strict).is_err());
+///
+/// assert!(wkt::read_with_mode("POINT (1 2 3)", ParseMode::Strict).is_err());
+/// assert!(wkt::read_with_mode("POINT Z (1 2 3)", ParseMode::Strict).is_ok());
+/// ```
+pub fn read_with_mode(text: &str, mode: ParseMode) -> Result<Geometry, Error> {
+    read_with_limits(text, mode, Limits::new())
+}
+
+/// Reads a geometry from WKT following the parse mode, failing as soon as the geometry passes
+/// one of the limits.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::error::Error;
+/// use geoms::io::{wkt, Limits, ParseMode};
+///
+/// let limits = Limits::new().with_max_depth(2).with_max_components(3);
+/// let text = "GEOMETRYCOLLECTION (POINT (0 0), GEOMETRYCOLLECTION (POINT (1 1)))";
+/// assert!(wkt::read_with_limits(text, ParseMode::Strict, limits).is_err());
+/// assert!(matches!(
+///     wkt::read_with_limits("MULTIPOINT ((0 0), (1 1), (2 2), (3 3))", ParseMode::Strict, limits),
+///     Err(Error::LimitExceeded(_))
+/// ));
+/// ```
+pub fn read_with_limits(text: &str, mode: ParseMode, limits: Limits) -> Result<Geometry, Error> {
+    let mut text = text.trim_start();
+    //the PostGIS extended WKT prefix, which carries a spatial reference the geometries cannot hold
+    if mode == ParseMode::Lenient && text.get(..5).is_some_and(|prefix| prefix.eq_ignore_ascii_case("SRID=")) {
+        match text.find(';') {
+            Some(end) => text = &text[end + 1..],
+            None => return Err(Error::InvalidWkt("expected ';' after the SRID".to_string())),
+        }
+    }
+    let mut parser = Parser { tokens: Tokens::new(text), lookahead: None, error: None, mode, z: None, budget: Budget::new(limits) };
+    let geometry = parser.geometry();
+    let rest = parser.next();
+    //an invalid character ends the tokens, which the parser only sees as the end of the input
+    if let Some(error) = parser.error {
+        return Err(error);
+    }
+    let geometry = match geometry? {
+        Some(geometry) => geometry,
+        None => return Err(Error::InvalidWkt("empty points are not supported".to_string())),
+    };
+    match rest {
+        None => Ok(geometry),
+        Some(token) => Err(Error::InvalidWkt(format!("unexpected {} after the geometry", token))),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Open,
+    Close,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Number(number) => write!(f, "'{}'", number),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+/// Splits WKT into tokens one at a time, so the readers never hold more than the token they are
+/// looking at and their limits bound the memory they use.
+struct Tokens<'a> {
+    text: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Tokens { text, chars: text.char_indices().peekable() }
+    }
+
+    /// Returns the next token, or None at the end of the text.
+    fn next_token(&mut self) -> Result<Option<Token>, Error> {
+        while let Some(&(start, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else if c == '(' {
+                self.chars.next();
+                return Ok(Some(Token::Open));
+            } else if c == ')' {
+                self.chars.next();
+                return Ok(Some(Token::Close));
+            } else if c == ',' {
+                self.chars.next();
+                return Ok(Some(Token::Comma));
+            } else if c.is_ascii_alphabetic() {
+                let end = self.take_while(start, |c| c.is_ascii_alphanumeric());
+                return Ok(Some(Token::Word(self.text[start..end].to_ascii_uppercase())));
+            } else if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+                let end = self.take_while(start, |c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+                let number = &self.text[start..end];
+                return match number.parse::<f64>() {
+                    Ok(value) => Ok(Some(Token::Number(value))),
+                    Err(_) => Err(Error::InvalidWkt(format!("invalid number '{}'", number))),
+                };
+            } else {
+                return Err(Error::InvalidWkt(format!("unexpected character '{}'", c)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Consumes the characters matching the predicate, returning where they end.
+    fn take_while(&mut self, start: usize, predicate: impl Fn(char) -> bool) -> usize {
+        let mut end = start;
+        while let Some(&(i, c)) = self.chars.peek() {
+            if !predicate(c) {
+                break;
+            }
+            end = i + c.len_utf8();
+            self.chars.next();
+        }
+        end
+    }
+}
+
+struct Parser<'a> {
+    tokens: Tokens<'a>,
+    //the next token, once it has been looked at
+    lookahead: Option<Token>,
+    //the first error of the tokens, which ends them
+    error: Option<Error>,
+    mode: ParseMode,
+    //whether the geometry being parsed is tagged as 3D, none outside of any geometry
+    z: Option<bool>,
+    budget: Budget,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<&Token> {
+        if self.lookahead.is_none() && self.error.is_none() {
+            match self.tokens.next_token() {
+                Ok(token) => self.lookahead = token,
+                Err(error) => self.error = Some(error),
+            }
+        }
+        self.lookahead.as_ref()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        self.peek();
+        self.lookahead.take()
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), Error> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(Error::InvalidWkt(format!("expected {} but found {}", expected, token))),
+            None => Err(Error::InvalidWkt(format!("expected {} but found the end of the input", expected))),
+        }
+    }
+
+    /// Consumes the `EMPTY` keyword if it is the next token.
+    fn empty(&mut self) -> bool {
+        if self.peek() == Some(&Token::Word("EMPTY".to_string())) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses a tagged geometry, or returns None for an empty point.
+    fn geometry(&mut self) -> Result<Option<Geometry>, Error> {
+        let tag = match self.next() {
+            Some(Token::Word(word)) => word,
+            Some(token) => return Err(Error::InvalidWkt(format!("expected a geometry tag but found {}", token))),
+            None => return Err(Error::InvalidWkt("empty input".to_string())),
+        };
+        let z = match self.peek() {
+            Some(Token::Word(word)) if word == "Z" => {
+                self.next();
+                true
+            }
+            Some(Token::Word(word)) if word == "M" || word == "ZM" => {
+                return Err(Error::InvalidWkt("measures are not supported".to_string()))
+            }
+            _ => false,
+        };
+        self.budget.enter()?;
+        let outer = self.z;
+        if self.mode == ParseMode::Strict && outer.is_some_and(|outer| outer != z) {
+            return Err(Error::InvalidWkt(format!("{} does not have the dimension of its collection", tag)));
+        }
+        self.z = Some(z);
+        let geometry = match tag.as_str() {
+            "POINT" => {
+                if self.empty() {
+                    //empty points are only dropped from collections
+                    if self.mode == ParseMode::Strict || outer.is_none() {
+                        return Err(Error::InvalidWkt("empty points are not supported".to_string()));
+                    }
+                    self.z = outer;
+                    self.budget.leave();
+                    return Ok(None);
+                }
+                self.expect(Token::Open)?;
+                let coordinates = self.coordinate()?;
+                self.expect(Token::Close)?;
+                Geometry::Point { coordinates }
+            }
+            "LINESTRING" => Geometry::LineString { coordinates: self.path()? },
+            "LINEARRING" => Geometry::LinearRing { coordinates: self.path()? },
+            "POLYGON" => Geometry::Polygon { coordinates: self.rings()? },
+            "MULTIPOINT" => Geometry::MultiPoint {
+                coordinates: self.list(Parser::multi_point_item)?.into_iter().flatten().collect(),
+            },
+            "MULTILINESTRING" => Geometry::MultiLineString { coordinates: self.list(Parser::part)? },
+            "MULTIPOLYGON" => Geometry::MultiPolygon { coordinates: self.list(Parser::polygon)? },
+            "POLYHEDRALSURFACE" => Geometry::PolyhedralSurface { coordinates: self.list(Parser::polygon)? },
+            "TIN" => Geometry::Tin { coordinates: self.list(Parser::triangle)? },
+            "GEOMETRYCOLLECTION" => Geometry::GeometryCollection {
+                geometries: self.list(Parser::member)?.into_iter().flatten().collect(),
+            },
+            _ => return Err(Error::InvalidWkt(format!("unknown geometry type '{}'", tag))),
+        };
+        self.z = outer;
+        self.budget.leave();
+        Ok(Some(geometry))
+    }
+
+    /// Parses a parenthesised list of items, or the `EMPTY` keyword.
+    fn list<T>(&mut self, item: fn(&mut Parser<'a>) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        if self.empty() {
+            return Ok(items);
+        }
+        self.expect(Token::Open)?;
+        loop {
+            items.push(item(self)?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::Close) => break,
+                Some(token) => return Err(Error::InvalidWkt(format!("expected ',' or ')' but found {}", token))),
+                None => return Err(Error::InvalidWkt("unexpected end of input".to_string())),
+            }
+        }
+        Ok(items)
+    }
+
+    fn coordinate(&mut self) -> Result<Coordinate, Error> {
+        let mut values = Vec::with_capacity(3);
+        while let Some(&Token::Number(value)) = self.peek() {
+            values.push(value);
+            self.next();
+        }
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(Error::InvalidWkt("coordinates must be finite".to_string()));
+        }
+        if self.mode == ParseMode::Strict {
+            let expected = if self.z == Some(true) { 3 } else { 2 };
+            if values.len() != expected {
+                return Err(Error::InvalidWkt(format!("expected {} ordinates but found {}", expected, values.len())));
+            }
+        }
+        self.budget.vertices(1)?;
+        match values[..] {
+            [x, y] => Ok(Coordinate::new(x, y, 0.0)),
+            [x, y, z] => Ok(Coordinate::new(x, y, z)),
+            _ => Err(Error::InvalidWkt(format!("expected 2 or 3 ordinates but found {}", values.len()))),
+        }
+    }
+
+    fn path(&mut self) -> Result<Vec<Coordinate>, Error> {
+        self.list(Parser::coordinate)
+    }
+
+    /// Parses a path that is a component: a ring, or a part of a multi line string.
+    fn part(&mut self) -> Result<Vec<Coordinate>, Error> {
+        self.budget.components(1)?;
+        self.path()
+    }
+
+    fn rings(&mut self) -> Result<Vec<Vec<Coordinate>>, Error> {
+        self.list(Parser::part)
+    }
+
+    fn polygon(&mut self) -> Result<Vec<Vec<Coordinate>>, Error> {
+        self.budget.components(1)?;
+        self.rings()
+    }
+
+    fn member(&mut self) -> Result<Option<Geometry>, Error> {
+        self.budget.components(1)?;
+        self.geometry()
+    }
+
+    fn triangle(&mut self) -> Result<Vec<Coordinate>, Error> {
+        self.budget.components(1)?;
+        let mut rings = self.rings()?;
+        if rings.len() != 1 {
+            return Err(Error::InvalidWkt("triangles must have exactly one ring".to_string()));
+        }
+        Ok(rings.remove(0))
+    }
+
+    /// Multi point items can be written with or without parentheses, or as `EMPTY`, in lenient mode.
+    fn multi_point_item(&mut self) -> Result<Option<Coordinate>, Error> {
+        self.budget.components(1)?;
+        if self.mode == ParseMode::Lenient && self.empty() {
+            return Ok(None);
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.next();
+            let coordinate = self.coordinate()?;
+            self.expect(Token::Close)?;
+            Ok(Some(coordinate))
+        } else if self.mode == ParseMode::Strict {
+            Err(Error::InvalidWkt("multi point items must be in parentheses".to_string()))
+        } else {
+            self.coordinate().map(Some)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_round_trip() {
+        let texts = [
+            "POINT (1 2)",
+            "LINESTRING (0 0, 1 1, 2 0.5)",
+            "LINEARRING (0 0, 1 0, 1 1, 0 0)",
+            "POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 2 1, 2 2, 1 1))",
+            "MULTIPOINT ((0 0), (1 1))",
+            "MULTILINESTRING ((0 0, 1 1), (2 2, 3 3))",
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))",
+            "GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1))",
+            "POLYHEDRALSURFACE Z (((0 0 0, 0 1 0, 1 1 0, 1 0 0, 0 0 0)), ((0 0 0, 0 1 0, 0 1 1, 0 0 1, 0 0 0)))",
+            "TIN Z (((0 0 0, 0 0 1, 0 1 0, 0 0 0)), ((0 0 0, 0 1 0, 1 1 0, 0 0 0)))",
+            "MULTIPOLYGON EMPTY",
+            "GEOMETRYCOLLECTION EMPTY",
+        ];
+        for text in texts {
+            assert_eq!(write(&read(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn test_read_variants() {
+        assert_eq!(
+            read("multipoint (0 0, 1 1)").unwrap(),
+            Geometry::MultiPoint { coordinates: vec![coord!(0, 0), coord!(1, 1)] }
+        );
+        assert_eq!(read("POINT (1e2 -2.5E-1)").unwrap(), Geometry::Point { coordinates: coord!(100, -0.25) });
+        assert_eq!(read("POINT (1 2 3)").unwrap(), Geometry::Point { coordinates: coord!(1, 2, 3) });
+    }
+
+    #[test]
+    fn test_conformance() {
+        //the input, what lenient mode reads it as, and whether strict mode accepts it
+        let corpus = [
+            //OGC simple features examples
+            ("POINT (10 10)", Some("POINT (10 10)"), true),
+            ("LINESTRING (10 10, 20 20, 30 40)", Some("LINESTRING (10 10, 20 20, 30 40)"), true),
+            ("POLYGON ((10 10, 10 20, 20 20, 20 15, 10 10))", Some("POLYGON ((10 10, 10 20, 20 20, 20 15, 10 10))"), true),
+            ("MULTIPOINT ((10 10), (20 20))", Some("MULTIPOINT ((10 10), (20 20))"), true),
+            ("MULTIPOINT (10 10, 20 20)", Some("MULTIPOINT ((10 10), (20 20))"), false),
+            (
+                "GEOMETRYCOLLECTION (POINT (10 10), POINT (30 30), LINESTRING (15 15, 20 20))",
+                Some("GEOMETRYCOLLECTION (POINT (10 10), POINT (30 30), LINESTRING (15 15, 20 20))"),
+                true,
+            ),
+            ("POINT Z (10 10 5)", Some("POINT Z (10 10 5)"), true),
+            ("POINT (10 10 5)", Some("POINT Z (10 10 5)"), false),
+            ("POINT Z (10 10)", Some("POINT (10 10)"), false),
+            ("GEOMETRYCOLLECTION Z (POINT Z (1 2 3))", Some("GEOMETRYCOLLECTION Z (POINT Z (1 2 3))"), true),
+            ("GEOMETRYCOLLECTION Z (POINT (1 2))", Some("GEOMETRYCOLLECTION (POINT (1 2))"), false),
+            //empties
+            ("MULTIPOLYGON EMPTY", Some("MULTIPOLYGON EMPTY"), true),
+            ("POLYGON Z EMPTY", Some("POLYGON EMPTY"), true),
+            ("MULTIPOLYGON (EMPTY, ((0 0, 1 0, 1 1, 0 0)))", Some("MULTIPOLYGON (EMPTY, ((0 0, 1 0, 1 1, 0 0)))"), true),
+            ("POLYGON ((0 0, 1 0, 1 1, 0 0), EMPTY)", Some("POLYGON ((0 0, 1 0, 1 1, 0 0), EMPTY)"), true),
+            ("GEOMETRYCOLLECTION (MULTIPOLYGON EMPTY, LINESTRING EMPTY)", Some("GEOMETRYCOLLECTION (MULTIPOLYGON EMPTY, LINESTRING EMPTY)"), true),
+            ("GEOMETRYCOLLECTION (GEOMETRYCOLLECTION EMPTY, POINT (1 2))", Some("GEOMETRYCOLLECTION (GEOMETRYCOLLECTION EMPTY, POINT (1 2))"), true),
+            ("GEOMETRYCOLLECTION (POINT EMPTY, POINT (1 2))", Some("GEOMETRYCOLLECTION (POINT (1 2))"), false),
+            ("MULTIPOINT (EMPTY, (1 2))", Some("MULTIPOINT ((1 2))"), false),
+            ("POINT EMPTY", None, false),
+            //numbers
+            ("POINT (1e3 -2.5E-2)", Some("POINT (1000 -0.025)"), true),
+            ("POINT (1E+2 .5)", Some("POINT (100 0.5)"), true),
+            ("POINT (1e400 0)", None, false),
+            ("POINT (1e 0)", None, false),
+            //PostGIS extended WKT
+            ("SRID=4326;POINT (1 2)", Some("POINT (1 2)"), false),
+            ("srid=4326; linestring(0 0,1 1)", Some("LINESTRING (0 0, 1 1)"), false),
+            ("SRID=4326 POINT (1 2)", None, false),
+        ];
+        for (text, lenient, strict) in corpus {
+            assert_eq!(read_with_mode(text, ParseMode::Lenient).ok().map(|g| write(&g)).as_deref(), lenient, "{}", text);
+            assert_eq!(read_with_mode(text, ParseMode::Strict).is_ok(), strict, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("").is_err());
+        assert!(read("POINT EMPTY").is_err());
+        assert!(read("POINT M (1 2 3)").is_err());
+        assert!(read("POINT (1 2 3 4)").is_err());
+        assert!(read("LINESTRING (0 0, 1 1").is_err());
+        assert!(read("LINESTRING (0 0, 1 1) POINT (1 1)").is_err());
+        assert!(read("CIRCLE (0 0)").is_err());
+        assert!(read("TIN (((0 0, 1 0, 0 1, 0 0), (0 0, 1 0, 0 1, 0 0)))").is_err());
+    }
+}
@@ -3,7 +3,68 @@
 //! various computations on geometries, such as area, distance, and intersection.
 //! It is mostly a copy of JTS (Java Topology Suite) library, but written in Rust. Although only 
 //! a set of the features oj JTS will be implemented.
+//!
+//! Geometries, features, indices and the other values of the library hold no reference counted
+//! pointers that are not atomic, and their interior caches are `OnceLock`s, filled once even by
+//! racing threads, as in [`geometry::prepared::PreparedGeometry`]. They are all `Send` and `Sync`
+//! and can be shared between threads as they are or behind an `Arc`, as in parallel pipelines.
 
 
+pub mod algorithm;
+#[cfg(feature = "approx")]
+mod approx_eq;
+pub mod cluster;
+pub mod contour;
 pub mod coordinate;
-pub mod geometry;
\ No newline at end of file
+pub mod crs;
+pub mod envelope;
+pub mod error;
+pub mod feature;
+pub mod geodesic;
+pub mod geography;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod index;
+pub mod interpolate;
+pub mod io;
+pub mod operation;
+pub mod progress;
+pub mod raster;
+pub mod shapes;
+pub mod sort;
+pub mod stream;
+pub mod testing;
+mod trace;
+pub mod trajectory;
+pub mod triangulate;
+
+//fails to compile if any of the values shared between threads stops being Send or Sync
+const _: () = {
+    const fn shareable<T: Send + Sync>() {}
+    shareable::<coordinate::Coordinate>();
+    shareable::<coordinate::coordinate_sequences::CoordinateSequence>();
+    shareable::<envelope::Envelope>();
+    shareable::<error::Error>();
+    shareable::<feature::Feature>();
+    shareable::<feature::FeatureCollection>();
+    shareable::<geography::Geography>();
+    shareable::<geometry::Geometry>();
+    shareable::<geometry::edit_session::GeometryEditSession>();
+    shareable::<geometry::generalized::GeneralizedGeometry>();
+    shareable::<geometry::line_segment::LineSegment>();
+    shareable::<geometry::prepared::PreparedGeometry>();
+    shareable::<geometry::pool::GeometryPool>();
+    shareable::<graph::Graph>();
+    shareable::<grid::Grid>();
+    shareable::<index::intervalrtree::IntervalRTree<geometry::Geometry>>();
+    shareable::<index::kdtree::KdTree<'static>>();
+    shareable::<index::quadtree::Quadtree<geometry::Geometry>>();
+    shareable::<index::strtree::STRtree<std::sync::Arc<geometry::Geometry>>>();
+    shareable::<algorithm::locate::Locator>();
+    shareable::<algorithm::point_in_polygon::IndexedPointInAreaLocator>();
+    shareable::<operation::distance::IndexedFacetDistance<'static>>();
+    shareable::<operation::pipeline::Pipeline>();
+    shareable::<progress::CancellationToken>();
+    shareable::<trajectory::Trajectory>();
+};
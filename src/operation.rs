@@ -0,0 +1,37 @@
+//! Operations computed over whole geometries.
+
+pub mod affine;
+pub mod aggregate;
+pub mod areal_interpolation;
+pub mod buffer;
+pub mod clean;
+pub mod conflate;
+pub mod crossings;
+pub mod diff;
+pub mod distance;
+pub mod edit;
+pub mod eliminate;
+pub mod extract;
+pub mod extrude;
+pub mod fishnet;
+pub mod line_merge;
+pub mod map_match;
+pub mod overlay;
+pub mod partition;
+pub mod pipeline;
+pub mod points;
+pub mod regularize;
+pub mod sample;
+pub mod simplify;
+pub mod snap;
+pub mod tile_clip;
+pub mod valid;
+
+pub use aggregate::aggregate_points;
+pub use areal_interpolation::areal_interpolation;
+pub use crossings::crossings;
+pub use diff::diff_layers;
+pub use distance::{filter_bbox, within_distance};
+pub use partition::partitioned;
+pub use snap::snap_layer;
+pub use tile_clip::tile_clip;
@@ -0,0 +1,229 @@
+//! Affine transformations of geometries in space, such as the rotations aligning geometries
+//! captured in the frame of a sensor with the frame of a map.
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::edit::{EditCoordinates, GeometryEditor};
+
+/// An affine transformation of space, given by the rows of the 3 by 4 matrix mapping x, y, z
+/// and 1 to the new x, y and z. Transformations are chained with [`AffineTransform::then`].
+///
+/// # Examples
+///
+/// ```
+/// use std::f64::consts::FRAC_PI_2;
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::operation::affine::AffineTransform;
+///
+/// //a quarter turn about the z axis, then a step up
+/// let transform = AffineTransform::rotation([0.0, 0.0, 1.0], FRAC_PI_2).then(&AffineTransform::translation(0.0, 0.0, 5.0));
+/// let moved = transform.apply(&coord!(2, 0, 1));
+/// assert!(moved.distance_3d(&coord!(0, 2, 6)) < 1e-12);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AffineTransform {
+    /// The rows of the matrix.
+    pub matrix: [[f64; 4]; 3],
+}
+
+impl AffineTransform {
+    /// Returns the transformation leaving every coordinate where it is.
+    pub fn identity() -> AffineTransform {
+        AffineTransform::translation(0.0, 0.0, 0.0)
+    }
+
+    /// Returns the transformation moving every coordinate by the offsets.
+    pub fn translation(dx: f64, dy: f64, dz: f64) -> AffineTransform {
+        AffineTransform { matrix: [[1.0, 0.0, 0.0, dx], [0.0, 1.0, 0.0, dy], [0.0, 0.0, 1.0, dz]] }
+    }
+
+    /// Returns the transformation scaling every ordinate by its factor about the origin.
+    pub fn scaling(sx: f64, sy: f64, sz: f64) -> AffineTransform {
+        AffineTransform { matrix: [[sx, 0.0, 0.0, 0.0], [0.0, sy, 0.0, 0.0], [0.0, 0.0, sz, 0.0]] }
+    }
+
+    /// Returns the rotation by an angle about an axis through the origin, counter clockwise when
+    /// the axis points towards the viewer, computed with Rodrigues' formula.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The direction of the axis, of any length.
+    /// * `angle` - The angle of the rotation, in radians.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the axis has no length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::affine::AffineTransform;
+    ///
+    /// //a third of a turn about the diagonal takes the x axis to the y axis
+    /// let rotation = AffineTransform::rotation([1.0, 1.0, 1.0], 2.0 * PI / 3.0);
+    /// assert!(rotation.apply(&coord!(1, 0, 0)).distance_3d(&coord!(0, 1, 0)) < 1e-12);
+    /// ```
+    pub fn rotation(axis: [f64; 3], angle: f64) -> AffineTransform {
+        let [x, y, z] = unit(axis, "The axis of the rotation must have a length");
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        AffineTransform {
+            matrix: [
+                [t * x * x + c, t * x * y - s * z, t * x * z + s * y, 0.0],
+                [t * x * y + s * z, t * y * y + c, t * y * z - s * x, 0.0],
+                [t * x * z - s * y, t * y * z + s * x, t * z * z + c, 0.0],
+            ],
+        }
+    }
+
+    /// Returns the rotation by an angle about an axis through a point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::affine::AffineTransform;
+    ///
+    /// let rotation = AffineTransform::rotation_about(&coord!(1, 1, 0), [0.0, 0.0, 1.0], PI);
+    /// assert!(rotation.apply(&coord!(2, 1, 3)).distance_3d(&coord!(0, 1, 3)) < 1e-12);
+    /// ```
+    pub fn rotation_about(point: &Coordinate, axis: [f64; 3], angle: f64) -> AffineTransform {
+        AffineTransform::translation(-point.x(), -point.y(), -point.z())
+            .then(&AffineTransform::rotation(axis, angle))
+            .then(&AffineTransform::translation(point.x(), point.y(), point.z()))
+    }
+
+    /// Returns the rotation given by a quaternion, which is normalized first.
+    ///
+    /// # Arguments
+    ///
+    /// * `quaternion` - The quaternion as its scalar part followed by its vector part, `[w, x, y, z]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quaternion is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::FRAC_PI_2;
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::affine::AffineTransform;
+    ///
+    /// //a quarter turn about the x axis
+    /// let half = FRAC_PI_2 / 2.0;
+    /// let rotation = AffineTransform::from_quaternion([half.cos(), half.sin(), 0.0, 0.0]);
+    /// assert!(rotation.apply(&coord!(0, 1, 0)).distance_3d(&coord!(0, 0, 1)) < 1e-12);
+    /// ```
+    pub fn from_quaternion(quaternion: [f64; 4]) -> AffineTransform {
+        let norm = quaternion.iter().map(|q| q * q).sum::<f64>().sqrt();
+        if norm.is_nan() || norm <= 0.0 {
+            panic!("The quaternion of the rotation cannot be zero");
+        }
+        let [w, x, y, z] = quaternion.map(|q| q / norm);
+        AffineTransform {
+            matrix: [
+                [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0],
+                [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0],
+                [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            ],
+        }
+    }
+
+    /// Returns the transformation applying this one and then the other.
+    pub fn then(&self, other: &AffineTransform) -> AffineTransform {
+        let (a, b) = (&other.matrix, &self.matrix);
+        let mut matrix = [[0.0; 4]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| a[i][k] * b[k][j]).sum::<f64>() + if j == 3 { a[i][3] } else { 0.0 };
+            }
+        }
+        AffineTransform { matrix }
+    }
+
+    /// Returns the transformed coordinate, z included.
+    pub fn apply(&self, coordinate: &Coordinate) -> Coordinate {
+        let [x, y, z] = self.matrix.map(|row| row[0] * coordinate.x() + row[1] * coordinate.y() + row[2] * coordinate.z() + row[3]);
+        Coordinate::new(x, y, z)
+    }
+
+    /// Returns the geometry with every coordinate transformed, keeping its structure. Rings keep
+    /// the order of their vertices, so their orientation in the plane may be reversed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64::consts::FRAC_PI_2;
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::affine::AffineTransform;
+    ///
+    /// //a wall standing on the x axis laid flat by a quarter turn about it
+    /// let wall = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0, 0), coord!(2, 0, 0), coord!(2, 0, 3), coord!(0, 0, 3), coord!(0, 0, 0)]] };
+    /// let floor = AffineTransform::rotation([1.0, 0.0, 0.0], FRAC_PI_2).transform(&wall);
+    /// assert!((floor.area() - 6.0).abs() < 1e-12);
+    /// assert!(floor.coords_iter().all(|c| c.z().abs() < 1e-12));
+    /// ```
+    pub fn transform(&self, geometry: &Geometry) -> Geometry {
+        let mut apply = EditCoordinates(|coordinates: &[Coordinate], _| coordinates.iter().map(|c| self.apply(c)).collect());
+        GeometryEditor::new().edit(geometry, &mut apply).unwrap_or_else(|| geometry.clone())
+    }
+}
+
+/// Returns the vector scaled to a length of one, panicking with the message if it has no length.
+fn unit(vector: [f64; 3], message: &str) -> [f64; 3] {
+    let length = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if length.is_nan() || length <= 0.0 {
+        panic!("{}", message);
+    }
+    vector.map(|v| v / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_rotations_from_axes_and_quaternions_agree() {
+        let points = [coord!(1, 2, 3), coord!(-4, 0.5, 2), coord!(0, 0, -7)];
+        let (axis, angle) = ([0.3, -1.2, 0.8], 1.1f64);
+        let length = (0.3f64 * 0.3 + 1.2 * 1.2 + 0.8 * 0.8).sqrt();
+        let (s, c) = (angle / 2.0).sin_cos();
+        //a quaternion of any length gives the same rotation
+        let quaternion = [c, s * axis[0] / length, s * axis[1] / length, s * axis[2] / length].map(|q| q * 3.0);
+        let by_axis = AffineTransform::rotation(axis, angle);
+        let by_quaternion = AffineTransform::from_quaternion(quaternion);
+        for p in &points {
+            let (a, b) = (by_axis.apply(p), by_quaternion.apply(p));
+            assert!(a.distance_3d(&b) < 1e-12);
+            //rotations keep the distance to the axis and the position along it
+            let along = |q: &Coordinate| (q.x() * axis[0] + q.y() * axis[1] + q.z() * axis[2]) / length;
+            assert!((along(p) - along(&a)).abs() < 1e-12);
+            assert!((p.distance_3d(&coord!(0, 0, 0)) - a.distance_3d(&coord!(0, 0, 0))).abs() < 1e-12);
+        }
+        //turning back undoes the rotation, and a full turn changes nothing
+        let back = by_axis.then(&AffineTransform::rotation(axis, -angle));
+        let full = AffineTransform::rotation(axis, 2.0 * std::f64::consts::PI);
+        for p in &points {
+            assert!(back.apply(p).distance_3d(p) < 1e-12);
+            assert!(full.apply(p).distance_3d(p) < 1e-12);
+        }
+
+        //the length in space of a line is kept about any axis through any point
+        let line = Geometry::LineString { coordinates: points.to_vec() };
+        let rotated = AffineTransform::rotation_about(&coord!(5, -3, 2), axis, angle).transform(&line);
+        assert!((rotated.length_3d() - line.length_3d()).abs() < 1e-9);
+        assert_eq!(AffineTransform::identity().transform(&line), line);
+        assert_eq!(AffineTransform::scaling(2.0, 2.0, 2.0).apply(&coord!(1, 2, 3)), coord!(2, 4, 6));
+    }
+}
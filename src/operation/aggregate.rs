@@ -0,0 +1,98 @@
+//! Summaries of the points falling in every polygon of a layer, such as the number of incidents
+//! per district or the total sales per catchment area.
+
+use crate::algorithm::point_in_polygon::{IndexedPointInAreaLocator, Location};
+use crate::envelope::Envelope;
+use crate::feature::FeatureCollection;
+use crate::index::strtree::STRtree;
+use crate::operation::extract;
+
+/// The summary of the points in a polygon computed by [`aggregate_points`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Statistic<'a> {
+    /// The number of points.
+    Count,
+    /// The sum of a numeric attribute of the points.
+    Sum(&'a str),
+    /// The mean of a numeric attribute of the points.
+    Mean(&'a str),
+    /// The smallest value of a numeric attribute of the points.
+    Min(&'a str),
+    /// The largest value of a numeric attribute of the points.
+    Max(&'a str),
+}
+
+/// Returns the statistic of the points of the point features within every polygon feature, in
+/// the order of the polygons. The points of a multi point count one by one, with the attributes
+/// of their feature, and points on the boundary of a polygon are within it, so a point on the
+/// boundary shared by two polygons counts in both. Only the points whose attribute is a number
+/// are summarised, and the sum of no points is 0, while their mean, minimum and maximum are
+/// None. The polygons are indexed once each, so every point is located quickly.
+///
+/// # Arguments
+///
+/// * `polygons` - The features whose polygons the points are gathered in.
+/// * `points` - The features whose points are summarised. Their lines and polygons are ignored.
+/// * `statistic` - The summary of the points to compute.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::{Feature, FeatureCollection};
+/// use geoms::operation::aggregate::{aggregate_points, Statistic};
+///
+/// let square = |x: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x, 0), coord!(x + 10.0, 0), coord!(x + 10.0, 10), coord!(x, 10), coord!(x, 0)
+/// ]] };
+/// let districts = FeatureCollection::new(vec![Feature::new(square(0.0)), Feature::new(square(20.0))]);
+/// let shops = FeatureCollection::new(vec![
+///     Feature::new(Geometry::Point { coordinates: coord!(1, 1) }).with_property("sales", 10.0),
+///     Feature::new(Geometry::Point { coordinates: coord!(5, 5) }).with_property("sales", 30.0),
+///     Feature::new(Geometry::Point { coordinates: coord!(15, 5) }).with_property("sales", 99.0),
+///     Feature::new(Geometry::Point { coordinates: coord!(9, 9) }),
+/// ]);
+/// assert_eq!(aggregate_points(&districts, &shops, Statistic::Count), [Some(3.0), Some(0.0)]);
+/// assert_eq!(aggregate_points(&districts, &shops, Statistic::Sum("sales")), [Some(40.0), Some(0.0)]);
+/// assert_eq!(aggregate_points(&districts, &shops, Statistic::Mean("sales")), [Some(20.0), None]);
+/// assert_eq!(aggregate_points(&districts, &shops, Statistic::Max("sales")), [Some(30.0), None]);
+/// ```
+pub fn aggregate_points(polygons: &FeatureCollection, points: &FeatureCollection, statistic: Statistic) -> Vec<Option<f64>> {
+    let locators: Vec<IndexedPointInAreaLocator> = polygons.iter().map(|f| IndexedPointInAreaLocator::new(f.geometry())).collect();
+    let tree = STRtree::new(polygons.iter().enumerate().map(|(i, f)| (f.geometry().envelope(), i)).collect());
+    let field = match statistic {
+        Statistic::Count => None,
+        Statistic::Sum(field) | Statistic::Mean(field) | Statistic::Min(field) | Statistic::Max(field) => Some(field),
+    };
+    //the number of points summarised and their sum, minimum and maximum, for every polygon
+    let mut summaries = vec![(0usize, 0.0, f64::INFINITY, f64::NEG_INFINITY); polygons.len()];
+    for feature in points {
+        let value = match field {
+            Some(field) => match feature.property(field).and_then(|v| v.as_f64()) {
+                Some(value) => value,
+                None => continue,
+            },
+            None => 1.0,
+        };
+        for p in extract::points(feature.geometry()) {
+            for &i in tree.query(&Envelope::of([&p])) {
+                if locators[i].locate(&p) != Location::Exterior {
+                    let summary = &mut summaries[i];
+                    *summary = (summary.0 + 1, summary.1 + value, summary.2.min(value), summary.3.max(value));
+                }
+            }
+        }
+    }
+    summaries
+        .into_iter()
+        .map(|(count, sum, min, max)| match statistic {
+            Statistic::Count | Statistic::Sum(_) => Some(sum),
+            _ if count == 0 => None,
+            Statistic::Mean(_) => Some(sum / count as f64),
+            Statistic::Min(_) => Some(min),
+            Statistic::Max(_) => Some(max),
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+//! Transfer of counts, such as the population of census tracts, from a layer of polygons to
+//! another one whose boundaries do not match.
+
+use crate::error::Error;
+use crate::feature::FeatureCollection;
+use crate::index::strtree::STRtree;
+use crate::operation::overlay::{overlay, OverlayOp};
+
+/// Returns the target features with the numeric attributes of the source features distributed
+/// among them in proportion to the area of the source features they cover, assuming the counts
+/// are spread evenly over every source feature. Every target feature gets the sum of the shares
+/// of the source features it overlaps for each field, which is 0 when it overlaps none.
+///
+/// The values a source feature gives away add up to its own value only when the target features
+/// cover it fully without overlapping. Source features without area, or whose attribute is
+/// missing or not a number, are left out.
+///
+/// # Arguments
+///
+/// * `source` - The features holding the counts.
+/// * `target` - The features to distribute the counts to.
+/// * `fields` - The names of the attributes to distribute.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::{Feature, FeatureCollection, Value};
+/// use geoms::operation::areal_interpolation;
+///
+/// let rectangle = |x1: f64, x2: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x1, 0), coord!(x2, 0), coord!(x2, 10), coord!(x1, 10), coord!(x1, 0)
+/// ]] };
+/// let tracts = FeatureCollection::new(vec![
+///     Feature::new(rectangle(0.0, 10.0)).with_property("population", 1000.0),
+///     Feature::new(rectangle(10.0, 20.0)).with_property("population", 300.0),
+/// ]);
+/// let districts = FeatureCollection::new(vec![
+///     Feature::new(rectangle(0.0, 5.0)),
+///     Feature::new(rectangle(5.0, 15.0)),
+///     Feature::new(rectangle(30.0, 40.0)),
+/// ]);
+/// let estimated = areal_interpolation(&tracts, &districts, &["population"]).unwrap();
+/// let population: Vec<Option<f64>> = estimated.iter().map(|f| f.property("population").and_then(Value::as_f64)).collect();
+/// assert_eq!(population, [Some(500.0), Some(650.0), Some(0.0)]);
+/// ```
+pub fn areal_interpolation(source: &FeatureCollection, target: &FeatureCollection, fields: &[&str]) -> Result<FeatureCollection, Error> {
+    let tree = STRtree::new(target.iter().enumerate().map(|(j, f)| (f.geometry().envelope(), j)).collect());
+    let mut totals = vec![vec![0.0; fields.len()]; target.len()];
+    for feature in source {
+        let area = feature.geometry().area();
+        let values: Vec<Option<f64>> = fields.iter().map(|&field| feature.property(field).and_then(|v| v.as_f64())).collect();
+        if area == 0.0 || values.iter().all(Option::is_none) {
+            continue;
+        }
+        for &j in tree.query(&feature.geometry().envelope()) {
+            let shared = overlay(feature.geometry(), target.features()[j].geometry(), OverlayOp::Intersection)?.area();
+            for (total, value) in totals[j].iter_mut().zip(&values) {
+                if let Some(value) = value {
+                    *total += value * shared / area;
+                }
+            }
+        }
+    }
+    Ok(target
+        .iter()
+        .zip(totals)
+        .map(|(feature, totals)| {
+            fields.iter().zip(totals).fold(feature.clone(), |feature, (&field, total)| feature.with_property(field, total))
+        })
+        .collect())
+}
@@ -0,0 +1,584 @@
+//! Buffers of lines whose width changes along them, such as a river widening downstream or the
+//! impact zone of a road narrowing away from a junction, buffers reaching out only in some
+//! directions, such as the coverage of antennas along a route, and the morphological operations
+//! growing and shrinking polygons to fill their small gaps or remove their thin necks.
+
+use crate::algorithm::convex_hull::convex_hull_of;
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::operation::extract;
+use crate::operation::overlay::{overlay, unary_union, unary_union_with_progress, OverlayOp};
+use crate::progress::ProgressSink;
+use crate::shapes::{circle, sector, ARC_SEGMENTS_PER_CIRCLE, MAX_SEGMENTS};
+use crate::trace;
+
+/// The largest number of vertices of the circles a buffer of a number of segments may build by
+/// default, above which it fails with `Error::LimitExceeded` rather than allocating them.
+pub const MAX_BUFFER_VERTICES: usize = 1 << 24;
+
+/// How the circles of a buffer are approximated, and how large they may get, so that buffers of
+/// untrusted geometries or parameters fail with `Error::LimitExceeded` before allocating them.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::error::Error;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::{buffer_with, BufferParameters};
+///
+/// let line = Geometry::LineString { coordinates: (0..100).map(|i| coord!(i, 0)).collect() };
+/// let parameters = BufferParameters { max_vertices: 1000, ..Default::default() };
+/// assert!(matches!(buffer_with(&line, 1.0, &parameters), Err(Error::LimitExceeded(_))));
+/// let coarse = BufferParameters { segments: 8, ..parameters };
+/// assert!(buffer_with(&line, 1.0, &coarse).is_ok());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BufferParameters {
+    /// The number of segments approximating a full circle, at least 3. It is
+    /// [`ARC_SEGMENTS_PER_CIRCLE`] by default.
+    pub segments: usize,
+    /// The largest number of segments of a circle, [`MAX_SEGMENTS`] by default.
+    pub max_segments: usize,
+    /// The largest number of vertices of the circles around all the vertices of the geometry,
+    /// [`MAX_BUFFER_VERTICES`] by default.
+    pub max_vertices: usize,
+}
+
+impl Default for BufferParameters {
+    fn default() -> Self {
+        Self { segments: ARC_SEGMENTS_PER_CIRCLE, max_segments: MAX_SEGMENTS, max_vertices: MAX_BUFFER_VERTICES }
+    }
+}
+
+impl BufferParameters {
+    /// Returns an error if circles of the number of segments around the number of vertices pass
+    /// the limits.
+    pub(crate) fn check(&self, segments: usize, vertices: usize) -> Result<(), Error> {
+        if segments > self.max_segments {
+            return Err(Error::LimitExceeded(format!("more than {} segments in a circle", self.max_segments)));
+        }
+        if segments.saturating_mul(vertices) > self.max_vertices {
+            return Err(Error::LimitExceeded(format!("more than {} vertices in the buffer", self.max_vertices)));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the area within the given distance of every vertex of a line string, the distance
+/// changing linearly along each segment, as a multi polygon. It is the union of the circles around
+/// the vertices and of the convex hulls of the circles at the ends of every segment, approximated
+/// by polygons of the given number of segments. A distance of 0 pinches the buffer at its vertex.
+///
+/// # Arguments
+///
+/// * `line` - The line string to buffer.
+/// * `distances` - The distance at every vertex of the line, finite and not negative.
+/// * `segments` - The number of segments approximating a full circle, at least 3. More than
+///   [`MAX_SEGMENTS`], or more circle vertices than [`MAX_BUFFER_VERTICES`] over the line, give
+///   `Error::LimitExceeded`, as with the default [`BufferParameters`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::variable_buffer;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0), coord!(10, 10)] };
+/// let corridor = variable_buffer(&line, &[1.0, 1.0, 0.0], 64).unwrap();
+/// assert!(corridor.contains_point(&coord!(5, 0.9)));
+/// assert!(corridor.contains_point(&coord!(10.4, 5)));
+/// assert!(!corridor.contains_point(&coord!(10.6, 5)));
+///
+/// assert!(variable_buffer(&line, &[1.0, 1.0], 64).is_err());
+/// ```
+pub fn variable_buffer(line: &Geometry, distances: &[f64], segments: usize) -> Result<Geometry, Error> {
+    let Geometry::LineString { coordinates } = line else {
+        return Err(Error::InvalidGeometry("only line strings have a variable buffer".to_string()));
+    };
+    if distances.len() != coordinates.len() {
+        return Err(Error::InvalidGeometry(format!(
+            "{} distances given for a line of {} vertices",
+            distances.len(),
+            coordinates.len()
+        )));
+    }
+    for &distance in distances {
+        check_distance(distance)?;
+    }
+    BufferParameters::default().check(segments, coordinates.len())?;
+    unary_union(&Geometry::GeometryCollection { geometries: segment_buffers(coordinates, distances, segments) })
+}
+
+/// Returns the areas around every segment of a line, the convex hulls of the circles of the
+/// distances around its ends, or the circle around a single vertex.
+fn segment_buffers(coordinates: &[Coordinate], distances: &[f64], segments: usize) -> Vec<Geometry> {
+    let circles: Vec<Vec<Coordinate>> = coordinates
+        .iter()
+        .zip(distances)
+        .map(|(c, &d)| match circle(c, d, segments) {
+            Geometry::Polygon { coordinates } => coordinates.into_iter().next().unwrap_or_default(),
+            _ => Vec::new(),
+        })
+        .collect();
+    let mut pieces: Vec<Geometry> = circles.windows(2).map(|w| convex_hull_of(w[0].iter().chain(&w[1]))).collect();
+    if circles.len() == 1 {
+        pieces.push(convex_hull_of(&circles[0]));
+    }
+    pieces
+}
+
+/// Returns the buffer of a line string whose distance changes linearly with the length along the
+/// line, from the distance at its start to the one at its end. See [`variable_buffer`].
+///
+/// # Arguments
+///
+/// * `line` - The line string to buffer.
+/// * `start` - The distance at the start of the line, finite and not negative.
+/// * `end` - The distance at the end of the line, finite and not negative.
+/// * `segments` - The number of segments approximating a full circle, at least 3, within the
+///   limits of [`variable_buffer`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::tapered_buffer;
+///
+/// //a river widening from 2 to 6 downstream, at the middle vertex it is 4 wide
+/// let river = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(50, 0), coord!(100, 0)] };
+/// let banks = tapered_buffer(&river, 1.0, 3.0, 32).unwrap();
+/// assert!(banks.contains_point(&coord!(50, 1.9)));
+/// assert!(!banks.contains_point(&coord!(50, 2.1)));
+/// assert!(banks.contains_point(&coord!(100, 2.9)));
+/// ```
+pub fn tapered_buffer(line: &Geometry, start: f64, end: f64, segments: usize) -> Result<Geometry, Error> {
+    let Geometry::LineString { coordinates } = line else {
+        return Err(Error::InvalidGeometry("only line strings have a variable buffer".to_string()));
+    };
+    let mut along = Vec::with_capacity(coordinates.len());
+    let mut length = 0.0;
+    for (i, c) in coordinates.iter().enumerate() {
+        if i > 0 {
+            length += coordinates[i - 1].distance_2d(c);
+        }
+        along.push(length);
+    }
+    let distances: Vec<f64> = along
+        .iter()
+        .map(|&l| if length > 0.0 { start + (end - start) * l / length } else { start })
+        .collect();
+    variable_buffer(line, &distances, segments)
+}
+
+/// Returns the area reached from any point of the geometry by going at most the distance in a
+/// direction between two bearings, in degrees clockwise from north, going clockwise from the
+/// start bearing to the end bearing, as a multi polygon. It is the buffer of the geometry by a
+/// sector instead of a circle, and equal bearings give the ordinary buffer by a circle. The
+/// polygons of the geometry are included, and its arcs are approximated as in
+/// [`crate::shapes::sector`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to buffer.
+/// * `distance` - The distance reached, finite and not negative.
+/// * `start_bearing` - The bearing of the first direction reached.
+/// * `end_bearing` - The bearing of the last direction reached.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::directional_buffer;
+///
+/// //the area north of a road, up to 10 away in directions up to 45 degrees off north
+/// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(100, 0)] };
+/// let north = directional_buffer(&road, 10.0, 315.0, 45.0).unwrap();
+/// assert!(north.contains_point(&coord!(50, 9)));
+/// assert!(north.contains_point(&coord!(-4, 6)));
+/// assert!(!north.contains_point(&coord!(-8, 5)));
+/// assert!(!north.contains_point(&coord!(50, -1)));
+/// ```
+pub fn directional_buffer(geometry: &Geometry, distance: f64, start_bearing: f64, end_bearing: f64) -> Result<Geometry, Error> {
+    check_distance(distance)?;
+    let sweep = match (end_bearing - start_bearing).rem_euclid(360.0) {
+        0.0 => 360.0,
+        sweep => sweep,
+    };
+    //the sector is split into convex pieces, whose buffers of a segment are the convex hulls of
+    //the pieces at both ends
+    let parts = (sweep / 90.0).ceil();
+    let origin = Coordinate::new(0.0, 0.0, 0.0);
+    let pieces: Vec<Vec<Coordinate>> = (0..parts as usize)
+        .map(|k| {
+            let start = start_bearing + sweep * k as f64 / parts;
+            match sector(&origin, distance, start, start + sweep / parts) {
+                Geometry::Polygon { coordinates } => coordinates.into_iter().next().unwrap_or_default(),
+                _ => Vec::new(),
+            }
+        })
+        .collect();
+    let translated = |piece: &[Coordinate], to: &Coordinate| -> Vec<Coordinate> {
+        piece.iter().map(|c| Coordinate::new(c.x() + to.x(), c.y() + to.y(), to.z())).collect()
+    };
+    let mut lines = extract::lines(geometry);
+    lines.extend(geometry.polygons().flatten().cloned());
+    lines.extend(extract::points(geometry).into_iter().map(|p| vec![p]));
+    let mut buffers: Vec<Geometry> = geometry.polygons().map(|p| Geometry::Polygon { coordinates: p.clone() }).collect();
+    for line in lines.iter().filter(|line| !line.is_empty()) {
+        let segments: Vec<(&Coordinate, &Coordinate)> = match line.len() {
+            1 => vec![(&line[0], &line[0])],
+            _ => line.windows(2).map(|w| (&w[0], &w[1])).collect(),
+        };
+        for (a, b) in segments {
+            for piece in &pieces {
+                let ends = [translated(piece, a), translated(piece, b)];
+                buffers.push(convex_hull_of(ends.iter().flatten()));
+            }
+        }
+    }
+    unary_union(&Geometry::GeometryCollection { geometries: buffers })
+}
+
+/// Returns the area within the distance of any part of the geometry, as a multi polygon: the
+/// union of the circles around its points, the buffers of its lines and its polygons grown by the
+/// distance. Arcs have [`ARC_SEGMENTS_PER_CIRCLE`] segments for a full turn, and a distance of 0
+/// keeps only the polygons. See [`buffer_with`] to change the number of segments and the limits.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to buffer.
+/// * `distance` - The distance to buffer by, finite and not negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::buffer;
+///
+/// let site = Geometry::GeometryCollection { geometries: vec![
+///     Geometry::Point { coordinates: coord!(20, 0) },
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+/// ] };
+/// let zone = buffer(&site, 1.0).unwrap();
+/// assert!(zone.contains_point(&coord!(5, 0.9)) && zone.contains_point(&coord!(20.5, 0.5)));
+/// assert!(!zone.contains_point(&coord!(15, 0)));
+/// assert!(buffer(&site, -1.0).is_err());
+/// ```
+pub fn buffer(geometry: &Geometry, distance: f64) -> Result<Geometry, Error> {
+    buffer_with(geometry, distance, &BufferParameters::default())
+}
+
+/// Returns the buffer of the geometry as [`buffer`] does, with arcs of the number of segments of
+/// the parameters, or `Error::LimitExceeded` if its circles would pass their limits.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to buffer.
+/// * `distance` - The distance to buffer by, finite and not negative.
+/// * `parameters` - The number of segments of the circles and their limits.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::error::Error;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::{buffer_with, BufferParameters};
+///
+/// let well = Geometry::Point { coordinates: coord!(0, 0) };
+/// let square = buffer_with(&well, 1.0, &BufferParameters { segments: 4, ..Default::default() }).unwrap();
+/// assert!((square.area() - 2.0).abs() < 1e-9);
+/// //a number of segments from a request is refused rather than allocated
+/// let huge = BufferParameters { segments: usize::MAX, ..Default::default() };
+/// assert!(matches!(buffer_with(&well, 1.0, &huge), Err(Error::LimitExceeded(_))));
+/// ```
+pub fn buffer_with(geometry: &Geometry, distance: f64, parameters: &BufferParameters) -> Result<Geometry, Error> {
+    buffer_of(geometry, distance, parameters, &())
+}
+
+/// Returns the buffer of the geometry as [`buffer`] does, reporting to the sink the unions of
+/// the areas around the points, the segments and the polygons, which take most of the time, or
+/// an error if the sink cancels it. See
+/// [`unary_union_with_progress`](crate::operation::overlay::unary_union_with_progress).
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::error::Error;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::buffer_with_progress;
+/// use geoms::progress::CancellationToken;
+///
+/// let coast = Geometry::LineString { coordinates: (0..100).map(|i| coord!(i, (i % 3) as f64)).collect() };
+/// let bar = AtomicUsize::new(0);
+/// let zone = buffer_with_progress(&coast, 1.0, &|done: usize, total: usize| bar.store(100 * done / total, Ordering::Relaxed)).unwrap();
+/// assert!(zone.contains_point(&coord!(50, 1)));
+/// assert_eq!(bar.into_inner(), 100);
+/// //the user gave up before it started
+/// let token = CancellationToken::new();
+/// token.cancel();
+/// assert_eq!(buffer_with_progress(&coast, 1.0, &token), Err(Error::Cancelled));
+/// ```
+pub fn buffer_with_progress(geometry: &Geometry, distance: f64, progress: &dyn ProgressSink) -> Result<Geometry, Error> {
+    buffer_of(geometry, distance, &BufferParameters::default(), progress)
+}
+
+/// Returns the buffer of the geometry with the parameters, reporting to the sink.
+fn buffer_of(geometry: &Geometry, distance: f64, parameters: &BufferParameters, progress: &dyn ProgressSink) -> Result<Geometry, Error> {
+    trace::span!("buffer", vertices = geometry.coords_count(), distance = distance);
+    check_distance(distance)?;
+    let segments = parameters.segments;
+    parameters.check(segments, geometry.coords_count())?;
+    let mut pieces = Vec::new();
+    if distance > 0.0 {
+        pieces.extend(extract::points(geometry).iter().map(|p| circle(p, distance, segments)));
+        for line in extract::lines(geometry).into_iter().filter(|line| !line.is_empty()) {
+            pieces.extend(segment_buffers(&line, &vec![distance; line.len()], segments));
+        }
+    }
+    //the areas of the dilation of the polygons
+    pieces.extend(ring_buffers(geometry, distance, segments));
+    pieces.extend(geometry.polygons().map(|p| Geometry::Polygon { coordinates: p.clone() }));
+    unary_union_with_progress(&Geometry::GeometryCollection { geometries: pieces }, progress)
+}
+
+/// Returns the polygons of the geometry grown by the distance: the area within the distance of
+/// them, as a multi polygon. Gaps and holes narrower than twice the distance are filled, and
+/// corners are rounded with arcs of [`ARC_SEGMENTS_PER_CIRCLE`] segments for a full turn. The
+/// points and lines of the geometry are ignored.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons are grown.
+/// * `distance` - The distance to grow by, finite and not negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::dilate;
+///
+/// let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)]] };
+/// let grown = dilate(&square, 1.0).unwrap();
+/// //the sides move out by 1, and the corners become quarter circles
+/// assert!((grown.area() - (100.0 + 40.0 + std::f64::consts::PI)).abs() < 0.05);
+/// ```
+pub fn dilate(geometry: &Geometry, distance: f64) -> Result<Geometry, Error> {
+    check_distance(distance)?;
+    let mut pieces = ring_buffers(geometry, distance, ARC_SEGMENTS_PER_CIRCLE);
+    pieces.extend(geometry.polygons().map(|p| Geometry::Polygon { coordinates: p.clone() }));
+    unary_union(&Geometry::GeometryCollection { geometries: pieces })
+}
+
+/// Returns the polygons of the geometry shrunk by the distance: the part of them farther than
+/// the distance from their boundary, as a multi polygon. Parts and necks narrower than twice the
+/// distance disappear, and holes are grown with rounded corners. The points and lines of the
+/// geometry are ignored.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons are shrunk.
+/// * `distance` - The distance to shrink by, finite and not negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::erode;
+///
+/// //two squares joined by a neck 1 wide
+/// let dumbbell = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(10, 0), coord!(10, 4.5), coord!(20, 4.5), coord!(20, 0), coord!(30, 0),
+///     coord!(30, 10), coord!(20, 10), coord!(20, 5.5), coord!(10, 5.5), coord!(10, 10), coord!(0, 10), coord!(0, 0)
+/// ]] };
+/// let eroded = erode(&dumbbell, 1.0).unwrap();
+/// //the squares lose a band 1 wide along their sides, and the neck is cut
+/// assert_eq!(eroded.num_components(), 2);
+/// assert!((eroded.area() - 128.0).abs() < 0.2);
+/// ```
+pub fn erode(geometry: &Geometry, distance: f64) -> Result<Geometry, Error> {
+    check_distance(distance)?;
+    let polygons = Geometry::MultiPolygon { coordinates: geometry.polygons().cloned().collect() };
+    let boundary = unary_union(&Geometry::GeometryCollection { geometries: ring_buffers(geometry, distance, ARC_SEGMENTS_PER_CIRCLE) })?;
+    overlay(&polygons, &boundary, OverlayOp::Difference)
+}
+
+/// Returns the polygons of the geometry eroded and then dilated by the distance, which removes
+/// their parts and necks narrower than twice the distance while keeping the rest of them, with
+/// rounded convex corners. See [`erode`] and [`dilate`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons are opened.
+/// * `distance` - The distance to erode and dilate by, finite and not negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::opening;
+///
+/// //a field with a thin strip sticking out of it
+/// let field = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(10, 0), coord!(10, 4.8), coord!(30, 4.8), coord!(30, 5.2), coord!(10, 5.2), coord!(10, 10), coord!(0, 10), coord!(0, 0)
+/// ]] };
+/// let opened = opening(&field, 0.5).unwrap();
+/// assert!(opened.envelope().max_x() < 10.1);
+/// //the corners of the field are rounded
+/// assert!((opened.area() - (100.0 - (4.0 - std::f64::consts::PI) * 0.25)).abs() < 0.1);
+/// ```
+pub fn opening(geometry: &Geometry, distance: f64) -> Result<Geometry, Error> {
+    dilate(&erode(geometry, distance)?, distance)
+}
+
+/// Returns the polygons of the geometry dilated and then eroded by the distance, which fills
+/// their gaps and holes narrower than twice the distance while keeping the rest of them, with
+/// rounded concave corners. See [`dilate`] and [`erode`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons are closed.
+/// * `distance` - The distance to dilate and erode by, finite and not negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::buffer::closing;
+///
+/// //two fields separated by a ditch 1 wide
+/// let fields = Geometry::MultiPolygon { coordinates: vec![
+///     vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)]],
+///     vec![vec![coord!(11, 0), coord!(21, 0), coord!(21, 10), coord!(11, 10), coord!(11, 0)]],
+/// ] };
+/// let closed = closing(&fields, 1.0).unwrap();
+/// assert_eq!(closed.num_components(), 1);
+/// assert!((closed.area() - 210.0).abs() < 0.5);
+/// ```
+pub fn closing(geometry: &Geometry, distance: f64) -> Result<Geometry, Error> {
+    erode(&dilate(geometry, distance)?, distance)
+}
+
+/// Returns an error if the distance of a buffer is negative or not finite.
+fn check_distance(distance: f64) -> Result<(), Error> {
+    if !distance.is_finite() || distance < 0.0 {
+        return Err(Error::InvalidGeometry(format!("the buffer distance {} is negative or not finite", distance)));
+    }
+    Ok(())
+}
+
+/// Returns the areas within the distance of every segment of the rings of the polygons of the
+/// geometry, the convex hulls of the circles of the number of segments around the ends of the
+/// segments.
+fn ring_buffers(geometry: &Geometry, distance: f64, segments: usize) -> Vec<Geometry> {
+    if distance == 0.0 {
+        return Vec::new();
+    }
+    let Geometry::Polygon { coordinates: rings } = circle(&Coordinate::new(0.0, 0.0, 0.0), distance, segments) else {
+        unreachable!()
+    };
+    let around = |c: &Coordinate| -> Vec<Coordinate> {
+        rings[0].iter().map(|p| Coordinate::new(p.x() + c.x(), p.y() + c.y(), c.z())).collect()
+    };
+    geometry
+        .polygons()
+        .flatten()
+        .flat_map(|ring| ring.windows(2))
+        .map(|w| convex_hull_of(around(&w[0]).iter().chain(&around(&w[1]))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_constant_and_degenerate_distances() {
+        let segment = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+        let capsule = tapered_buffer(&segment, 2.0, 2.0, 256).unwrap();
+        assert!((capsule.area() - (40.0 + 4.0 * PI)).abs() < 0.01);
+        //a single vertex gives a circle, and zero distances give no area
+        let point = Geometry::LineString { coordinates: vec![coord!(3, 3)] };
+        assert!((variable_buffer(&point, &[1.0], 256).unwrap().area() - PI).abs() < 0.001);
+        assert_eq!(variable_buffer(&segment, &[0.0, 0.0], 16).unwrap().area(), 0.0);
+        assert!(variable_buffer(&segment, &[1.0, -1.0], 16).is_err());
+        assert!(tapered_buffer(&Geometry::Point { coordinates: coord!(0, 0) }, 1.0, 1.0, 16).is_err());
+        //numbers of segments from untrusted input fail before allocating the circles
+        assert!(matches!(variable_buffer(&segment, &[1.0, 1.0], usize::MAX), Err(Error::LimitExceeded(_))));
+        let long = Geometry::LineString { coordinates: (0..1000).map(|i| coord!(i, 0)).collect() };
+        assert!(matches!(tapered_buffer(&long, 1.0, 1.0, MAX_SEGMENTS), Err(Error::LimitExceeded(_))));
+        assert!(variable_buffer(&segment, &[1.0, 1.0], MAX_SEGMENTS).is_ok());
+    }
+
+    #[test]
+    fn test_buffer_parameters() {
+        let site = Geometry::GeometryCollection { geometries: vec![
+            Geometry::Point { coordinates: coord!(20, 0) },
+            Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] },
+        ] };
+        let fine = buffer_with(&site, 1.0, &BufferParameters { segments: 256, ..Default::default() }).unwrap();
+        assert!((fine.area() - (PI + 4.0 + 8.0 + PI)).abs() < 0.01);
+        assert_eq!(buffer(&site, 1.0), buffer_with(&site, 1.0, &BufferParameters::default()));
+        //the limits apply to the polygons as to the points
+        let tight = BufferParameters { max_vertices: 5 * 32, ..Default::default() };
+        assert!(matches!(buffer_with(&site, 1.0, &tight), Err(Error::LimitExceeded(_))));
+        let few = BufferParameters { max_segments: 16, ..Default::default() };
+        assert!(matches!(buffer_with(&site, 1.0, &few), Err(Error::LimitExceeded(_))));
+        assert!(buffer_with(&site, 1.0, &BufferParameters { segments: 16, ..few }).is_ok());
+    }
+
+    #[test]
+    fn test_directional_buffer_of_points_and_polygons() {
+        let point = Geometry::Point { coordinates: coord!(1, 1) };
+        let quarter = directional_buffer(&point, 2.0, 0.0, 90.0).unwrap();
+        assert!((quarter.area() / PI - 1.0).abs() < 0.01);
+        //equal bearings reach all around, as a capsule
+        let segment = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+        let all_around = directional_buffer(&segment, 2.0, 90.0, 90.0).unwrap();
+        assert!((all_around.area() / (40.0 + 4.0 * PI) - 1.0).abs() < 0.01);
+        //a square pushed east by 1 becomes a 3 by 2 rectangle
+        let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+        assert!((directional_buffer(&square, 1.0, 90.0, 90.001).unwrap().area() - 6.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_morphology_of_a_polygon_with_a_hole() {
+        let frame = Geometry::Polygon { coordinates: vec![
+            vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+            vec![coord!(4, 4), coord!(4, 6), coord!(6, 6), coord!(6, 4), coord!(4, 4)],
+        ] };
+        //the hole grows while the outside shrinks, and growing by 1 closes the hole
+        assert!((erode(&frame, 1.0).unwrap().area() - (64.0 - 12.0 - PI)).abs() < 0.05);
+        assert!(dilate(&frame, 1.0).unwrap().polygons().all(|p| p.len() == 1));
+        assert!((closing(&frame, 1.0).unwrap().area() - 100.0).abs() < 1e-9);
+        assert_eq!(dilate(&frame, 0.0).unwrap().area(), 96.0);
+        assert!(erode(&frame, f64::NAN).is_err());
+        assert!(dilate(&Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0)] }, 1.0).unwrap().num_components() == 0);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::algorithm::area::ring_area;
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::operation::overlay::{overlay, OverlayOp};
+
+/// Returns the geometry without its spikes, the vertices where the boundary turns back on itself
+/// with an angle narrower than the tolerance, nor its repeated consecutive vertices. Removing a
+/// spike can reveal another one, so vertices are removed until none is left. The ends of lines
+/// are kept, while rings left with less than three distinct vertices are dropped along with the
+/// polygons whose shell they were.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to clean.
+/// * `angle_tolerance` - The narrowest angle between the two segments at a vertex that is kept, in radians.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::clean::remove_spikes;
+///
+/// //a square with a thin spike going out of its top edge, whose base turns back on the edge too
+/// let polygon = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(2, 4), coord!(2.01, 9), coord!(2.02, 4), coord!(0, 4), coord!(0, 0)
+/// ]] };
+/// assert_eq!(remove_spikes(&polygon, 0.1), Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(2.02, 4), coord!(0, 4), coord!(0, 0)
+/// ]] });
+/// ```
+pub fn remove_spikes(geometry: &Geometry, angle_tolerance: f64) -> Geometry {
+    match geometry {
+        Geometry::LineString { coordinates } => {
+            Geometry::LineString { coordinates: despike_line(coordinates, angle_tolerance) }
+        }
+        Geometry::LinearRing { coordinates } => {
+            Geometry::LinearRing { coordinates: despike_ring(coordinates, angle_tolerance).unwrap_or_default() }
+        }
+        Geometry::Polygon { coordinates } => {
+            Geometry::Polygon { coordinates: despike_polygon(coordinates, angle_tolerance).unwrap_or_default() }
+        }
+        Geometry::MultiLineString { coordinates } => Geometry::MultiLineString {
+            coordinates: coordinates.iter().map(|line| despike_line(line, angle_tolerance)).collect(),
+        },
+        Geometry::MultiPolygon { coordinates } => Geometry::MultiPolygon {
+            coordinates: coordinates.iter().filter_map(|polygon| despike_polygon(polygon, angle_tolerance)).collect(),
+        },
+        Geometry::GeometryCollection { geometries } => Geometry::GeometryCollection {
+            geometries: geometries.iter().map(|g| remove_spikes(g, angle_tolerance)).collect(),
+        },
+        _ => geometry.clone(),
+    }
+}
+
+/// Returns the angle at the vertex b between the segments going to a and c.
+fn angle(a: &Coordinate, b: &Coordinate, c: &Coordinate) -> f64 {
+    let (ux, uy) = (a.x() - b.x(), a.y() - b.y());
+    let (vx, vy) = (c.x() - b.x(), c.y() - b.y());
+    (ux * vy - uy * vx).abs().atan2(ux * vx + uy * vy)
+}
+
+fn despike_line(line: &[Coordinate], angle_tolerance: f64) -> Vec<Coordinate> {
+    let mut kept: Vec<Coordinate> = Vec::with_capacity(line.len());
+    for c in line {
+        if kept.last().is_some_and(|last| last.equals_2d(c)) {
+            continue;
+        }
+        //the new vertex can reveal spikes at the vertices kept before it
+        while kept.len() >= 2 && angle(&kept[kept.len() - 2], &kept[kept.len() - 1], c) < angle_tolerance {
+            kept.pop();
+        }
+        if kept.last().is_some_and(|last| last.equals_2d(c)) {
+            continue;
+        }
+        kept.push(c.clone());
+    }
+    kept
+}
+
+/// Returns the closed ring without its spikes, or None if it collapses.
+fn despike_ring(ring: &[Coordinate], angle_tolerance: f64) -> Option<Vec<Coordinate>> {
+    let mut kept = despike_line(ring, angle_tolerance);
+    //the first and last vertices are the same, and the spikes at them are removed around the ring
+    loop {
+        if kept.len() > 1 && kept[0].equals_2d(&kept[kept.len() - 1]) {
+            kept.pop();
+        }
+        if kept.len() < 3 {
+            return None;
+        }
+        let n = kept.len();
+        if angle(&kept[n - 1], &kept[0], &kept[1]) < angle_tolerance {
+            kept.remove(0);
+        } else if angle(&kept[n - 2], &kept[n - 1], &kept[0]) < angle_tolerance {
+            kept.pop();
+        } else {
+            break;
+        }
+    }
+    kept.push(kept[0].clone());
+    Some(kept)
+}
+
+/// Returns the polygon without the spikes of its rings, or None if its shell collapses.
+fn despike_polygon(rings: &[Vec<Coordinate>], angle_tolerance: f64) -> Option<Vec<Vec<Coordinate>>> {
+    let (shell, holes) = rings.split_first()?;
+    let mut cleaned = vec![despike_ring(shell, angle_tolerance)?];
+    cleaned.extend(holes.iter().filter_map(|hole| despike_ring(hole, angle_tolerance)));
+    Some(cleaned)
+}
+
+/// Returns the geometry without its slivers, the polygons and holes narrower than the minimum
+/// width. The width of a ring is estimated as twice its area divided by its perimeter, which is
+/// close to the width of long thin shapes. Polygons whose shell is a sliver are dropped.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to clean.
+/// * `min_width` - The width under which rings are removed.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::clean::remove_slivers;
+///
+/// let square = vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]];
+/// let sliver = vec![vec![coord!(5, 0), coord!(9, 0), coord!(9, 0.01), coord!(5, 0)]];
+/// let polygons = Geometry::MultiPolygon { coordinates: vec![square.clone(), sliver] };
+/// assert_eq!(remove_slivers(&polygons, 0.1), Geometry::MultiPolygon { coordinates: vec![square] });
+/// ```
+pub fn remove_slivers(geometry: &Geometry, min_width: f64) -> Geometry {
+    match geometry {
+        Geometry::Polygon { coordinates } => {
+            Geometry::Polygon { coordinates: desliver_polygon(coordinates, min_width).unwrap_or_default() }
+        }
+        Geometry::MultiPolygon { coordinates } => Geometry::MultiPolygon {
+            coordinates: coordinates.iter().filter_map(|polygon| desliver_polygon(polygon, min_width)).collect(),
+        },
+        Geometry::GeometryCollection { geometries } => Geometry::GeometryCollection {
+            geometries: geometries.iter().map(|g| remove_slivers(g, min_width)).collect(),
+        },
+        _ => geometry.clone(),
+    }
+}
+
+fn is_sliver(ring: &[Coordinate], min_width: f64) -> bool {
+    let perimeter: f64 = ring.windows(2).map(|w| (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y())).sum();
+    perimeter == 0.0 || 2.0 * ring_area(ring) / perimeter < min_width
+}
+
+/// Returns the polygon without its sliver holes, or None if its shell is a sliver.
+fn desliver_polygon(rings: &[Vec<Coordinate>], min_width: f64) -> Option<Vec<Vec<Coordinate>>> {
+    let (shell, holes) = rings.split_first()?;
+    if is_sliver(shell, min_width) {
+        return None;
+    }
+    let mut cleaned = vec![shell.clone()];
+    cleaned.extend(holes.iter().filter(|hole| !is_sliver(hole, min_width)).cloned());
+    Some(cleaned)
+}
+
+/// Returns the polygons of the geometry rebuilt from their noded rings, the equivalent of a
+/// buffer of zero distance without computing any offset. The inside of every polygon is the area
+/// its rings enclose an odd number of times, whatever their orientation, so self intersecting
+/// rings are split where they cross and the parts of holes outside of their shell become
+/// polygons. Rings touching themselves are split into a shell and its holes, overlapping polygons
+/// are merged together, and repeated vertices are dropped. Lines and points are returned
+/// unchanged, and the parts of collections are cleaned one by one.
+///
+/// This is a cheap fix for slightly invalid polygons computed by the overlay, see
+/// [`crate::operation::overlay::overlay`], whose error is returned if the rings cannot be noded.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to clean.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::clean::clean_polygons;
+///
+/// //a bow tie crossing itself at (1, 1)
+/// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 2), coord!(2, 0), coord!(0, 2), coord!(0, 0)]] };
+/// let cleaned = clean_polygons(&polygon).unwrap();
+/// assert_eq!(cleaned.as_text(), "MULTIPOLYGON (((0 0, 1 1, 0 2, 0 0)), ((1 1, 2 0, 2 2, 1 1)))");
+/// assert!(cleaned.is_valid());
+/// ```
+pub fn clean_polygons(geometry: &Geometry) -> Result<Geometry, Error> {
+    match geometry {
+        Geometry::Polygon { .. } | Geometry::MultiPolygon { .. } => {
+            overlay(geometry, &Geometry::MultiPolygon { coordinates: vec![] }, OverlayOp::Union)
+        }
+        Geometry::GeometryCollection { geometries } => Ok(Geometry::GeometryCollection {
+            geometries: geometries.iter().map(clean_polygons).collect::<Result<_, _>>()?,
+        }),
+        _ => Ok(geometry.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_nested_spikes_around_the_ring_start() {
+        //a spike at the closing vertex, and a spike whose removal reveals another one
+        let ring = vec![
+            coord!(0, 10),
+            coord!(0.01, 0),
+            coord!(0.02, 10),
+            coord!(0, 0),
+            coord!(10, 0),
+            coord!(10, 10),
+            coord!(6, 10),
+            coord!(5, 20),
+            coord!(5.1, 18),
+            coord!(5.05, 19),
+            coord!(6.1, 10),
+            coord!(0, 10),
+        ];
+        let Geometry::Polygon { coordinates } = remove_spikes(&Geometry::Polygon { coordinates: vec![ring] }, 0.2) else {
+            panic!()
+        };
+        assert_eq!(
+            coordinates,
+            vec![vec![
+                coord!(0.02, 10),
+                coord!(0, 0),
+                coord!(10, 0),
+                coord!(10, 10),
+                coord!(6.1, 10),
+                coord!(0.02, 10),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_collapsed_rings_are_dropped() {
+        let needle = Geometry::Polygon {
+            coordinates: vec![vec![coord!(0, 0), coord!(10, 0.1), coord!(10, 0), coord!(0, 0)]],
+        };
+        assert_eq!(remove_spikes(&needle, 0.1), Geometry::Polygon { coordinates: vec![] });
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 0), coord!(5, 0), coord!(1, 0.1), coord!(8, 0)] };
+        assert_eq!(remove_spikes(&line, 0.1), Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0.1), coord!(8, 0)] });
+    }
+
+    #[test]
+    fn test_clean_polygons() {
+        //a shell touching itself at (2, 4) around a notch, and a hole crossing the shell, whose
+        //part outside of the shell is enclosed once
+        let shell = vec![
+            coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(2, 4), coord!(3, 2), coord!(1, 2), coord!(2, 4), coord!(0, 4), coord!(0, 0)
+        ];
+        let hole = vec![coord!(3, -1), coord!(3, 1), coord!(5, 1), coord!(5, -1), coord!(3, -1)];
+        let polygon = Geometry::Polygon { coordinates: vec![shell, hole] };
+        assert!(!polygon.is_valid());
+        let cleaned = clean_polygons(&polygon).unwrap();
+        assert_eq!(
+            cleaned,
+            Geometry::MultiPolygon { coordinates: vec![
+                vec![
+                    vec![coord!(0, 0), coord!(3, 0), coord!(3, 1), coord!(4, 1), coord!(4, 4), coord!(2, 4), coord!(0, 4), coord!(0, 0)],
+                    vec![coord!(1, 2), coord!(2, 4), coord!(3, 2), coord!(1, 2)],
+                ],
+                vec![vec![coord!(3, -1), coord!(5, -1), coord!(5, 1), coord!(4, 1), coord!(4, 0), coord!(3, 0), coord!(3, -1)]],
+            ] }
+        );
+        assert!(cleaned.is_valid());
+
+        //overlapping parts are merged, and lines are kept as they are
+        let squares = Geometry::MultiPolygon { coordinates: vec![
+            vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]],
+            vec![vec![coord!(1, 0), coord!(3, 0), coord!(3, 2), coord!(1, 2), coord!(1, 0)]],
+        ] };
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] };
+        let collection = Geometry::GeometryCollection { geometries: vec![squares, line.clone()] };
+        let Geometry::GeometryCollection { geometries } = clean_polygons(&collection).unwrap() else { panic!() };
+        assert_eq!(geometries[0].as_text(), "POLYGON ((0 0, 1 0, 2 0, 3 0, 3 2, 2 2, 1 2, 0 2, 0 0))");
+        assert_eq!(geometries[1], line);
+    }
+}
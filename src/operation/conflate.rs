@@ -0,0 +1,205 @@
+//! Matching of the features of two datasets representing the same things, such as the roads of
+//! two providers, scoring candidate pairs by how close and how overlapping their geometries are
+//! and, optionally, by how similar their attributes are.
+
+use crate::feature::{Feature, FeatureCollection};
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+use crate::operation::distance::hausdorff_distance;
+
+/// A pair of matching features, given by their indices in the collections they come from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Match {
+    /// The index of the feature in the first collection.
+    pub a: usize,
+    /// The index of the feature in the second collection.
+    pub b: usize,
+    /// The Hausdorff distance between the geometries of the features.
+    pub distance: f64,
+    /// The area of the intersection of the geometries of the features over the area of their
+    /// union, or None if any of them has no area.
+    pub overlap: Option<f64>,
+    /// The score of the match between 0 and 1, the mean of the scores of the criteria that apply
+    /// to the pair.
+    pub score: f64,
+}
+
+/// A similarity between 0 and 1 of the attributes of two features, or None if it cannot be told.
+type Similarity<'a> = dyn Fn(&Feature, &Feature) -> Option<f64> + 'a;
+
+/// Finds the features of two collections that correspond to each other.
+///
+/// Pairs of features whose geometries are within the maximum Hausdorff distance of each other
+/// are candidates. A pair of polygonal geometries must also overlap by at least the minimum
+/// overlap, measured as the area of their intersection over the area of their union. Every
+/// candidate is scored by the mean of the scores of the criteria that apply to it: one minus the
+/// distance over the maximum distance, the overlap for polygonal geometries, and the similarity
+/// of the attributes when one is set and can tell. Features are then matched one to one, taking
+/// the candidates by decreasing score.
+pub struct Conflator<'a> {
+    max_distance: f64,
+    min_overlap: f64,
+    similarity: Option<Box<Similarity<'a>>>,
+}
+
+impl<'a> Conflator<'a> {
+    /// Creates a conflator matching geometries within the Hausdorff distance of each other,
+    /// without a minimum overlap nor a similarity of attributes.
+    pub fn new(max_distance: f64) -> Self {
+        Self { max_distance, min_overlap: 0.0, similarity: None }
+    }
+
+    /// Returns a conflator that only matches polygonal geometries overlapping by at least the
+    /// given ratio, between 0 and 1.
+    pub fn min_overlap(self, min_overlap: f64) -> Self {
+        Self { min_overlap, ..self }
+    }
+
+    /// Returns a conflator that scores the attributes of the features with the given similarity,
+    /// which returns a value between 0 and 1, or None to leave the attributes out of the score.
+    /// A similarity of 0 rules the pair out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection};
+    /// use geoms::operation::conflate::Conflator;
+    ///
+    /// let stop = |x: f64, name: &str| Feature::new(Geometry::Point { coordinates: coord!(x, 0) }).with_property("name", name);
+    /// let ours = FeatureCollection::new(vec![stop(0.0, "Mill Lane"), stop(1.0, "Station")]);
+    /// let theirs = FeatureCollection::new(vec![stop(0.6, "Mill Lane"), stop(0.4, "Station")]);
+    ///
+    /// //by distance alone the stops are swapped
+    /// let by_distance = Conflator::new(2.0).matches(&ours, &theirs);
+    /// assert_eq!(by_distance.iter().map(|m| (m.a, m.b)).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+    ///
+    /// let by_name = Conflator::new(2.0)
+    ///     .similarity(|a, b| Some(if a.property("name") == b.property("name") { 1.0 } else { 0.0 }))
+    ///     .matches(&ours, &theirs);
+    /// assert_eq!(by_name.iter().map(|m| (m.a, m.b)).collect::<Vec<_>>(), [(0, 0), (1, 1)]);
+    /// ```
+    pub fn similarity<F>(self, similarity: F) -> Self
+    where
+        F: Fn(&Feature, &Feature) -> Option<f64> + 'a,
+    {
+        Self { similarity: Some(Box::new(similarity)), ..self }
+    }
+
+    /// Returns the matches between the features of the two collections, by decreasing score.
+    /// Every feature is in at most one match, and features with empty geometries are never
+    /// matched.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first collection.
+    /// * `b` - The second collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection};
+    /// use geoms::operation::conflate::Conflator;
+    ///
+    /// let square = |x: f64, y: f64| Feature::new(Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(x, y), coord!(x + 10.0, y), coord!(x + 10.0, y + 10.0), coord!(x, y + 10.0), coord!(x, y)
+    /// ]] });
+    /// let surveyed = FeatureCollection::new(vec![square(0.0, 0.0), square(20.0, 0.0), square(40.0, 0.0)]);
+    /// let digitised = FeatureCollection::new(vec![square(41.0, 0.0), square(0.5, 0.5), square(80.0, 0.0)]);
+    ///
+    /// let matches = Conflator::new(3.0).min_overlap(0.5).matches(&surveyed, &digitised);
+    /// assert_eq!(matches.len(), 2);
+    /// assert_eq!((matches[0].a, matches[0].b), (0, 1));
+    /// assert_eq!(matches[0].overlap, Some(90.25 / 109.75));
+    /// assert_eq!((matches[1].a, matches[1].b, matches[1].distance), (2, 0, 1.0));
+    /// ```
+    pub fn matches(&self, a: &FeatureCollection, b: &FeatureCollection) -> Vec<Match> {
+        let tree = STRtree::new(b.iter().enumerate().map(|(j, f)| (f.geometry().envelope(), j)).collect());
+        let mut candidates: Vec<Match> = Vec::new();
+        for (i, feature) in a.iter().enumerate() {
+            let envelope = feature.geometry().envelope();
+            if envelope.is_empty() {
+                continue;
+            }
+            for &j in tree.query(&envelope.expand_by(self.max_distance)) {
+                if let Some(candidate) = self.score(i, feature, j, &b.features()[j]) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+        //the best candidates are taken first, and ties are broken by the order of the features
+        candidates.sort_by(|p, q| q.score.total_cmp(&p.score).then((p.a, p.b).cmp(&(q.a, q.b))));
+        let mut matched_a = vec![false; a.len()];
+        let mut matched_b = vec![false; b.len()];
+        candidates.retain(|m| {
+            let free = !matched_a[m.a] && !matched_b[m.b];
+            if free {
+                matched_a[m.a] = true;
+                matched_b[m.b] = true;
+            }
+            free
+        });
+        candidates
+    }
+
+    /// Returns the pair as a match with its score, or None if it does not meet the criteria.
+    fn score(&self, i: usize, a: &Feature, j: usize, b: &Feature) -> Option<Match> {
+        let distance = hausdorff_distance(a.geometry(), b.geometry());
+        if distance > self.max_distance {
+            return None;
+        }
+        let overlap = overlap(a.geometry(), b.geometry());
+        if overlap.is_some_and(|overlap| overlap < self.min_overlap) {
+            return None;
+        }
+        let similarity = self.similarity.as_ref().and_then(|similarity| similarity(a, b));
+        if similarity == Some(0.0) {
+            return None;
+        }
+        let closeness = if self.max_distance > 0.0 { 1.0 - distance / self.max_distance } else { 1.0 };
+        let scores: Vec<f64> = [Some(closeness), overlap, similarity].into_iter().flatten().collect();
+        let score = scores.iter().sum::<f64>() / scores.len() as f64;
+        Some(Match { a: i, b: j, distance, overlap, score })
+    }
+}
+
+/// Returns the area of the intersection of the geometries over the area of their union, or None
+/// if any of them has no area.
+fn overlap(a: &Geometry, b: &Geometry) -> Option<f64> {
+    let (area_a, area_b) = (a.area(), b.area());
+    if area_a == 0.0 || area_b == 0.0 {
+        return None;
+    }
+    let shared = a.intersection(b).map_or(0.0, |intersection| intersection.area());
+    Some(shared / (area_a + area_b - shared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_exact_and_empty_features() {
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 5)] };
+        let a = FeatureCollection::new(vec![
+            Feature::new(Geometry::LineString { coordinates: vec![] }),
+            Feature::new(line.clone()),
+        ]);
+        let b = FeatureCollection::new(vec![
+            Feature::new(Geometry::LineString { coordinates: vec![coord!(5, 5), coord!(0, 0)] }),
+            Feature::new(Geometry::GeometryCollection { geometries: vec![] }),
+        ]);
+        //reversed lines are the same geometry, even without any tolerance
+        let matches = Conflator::new(0.0).matches(&a, &b);
+        assert_eq!(matches, vec![Match { a: 1, b: 0, distance: 0.0, overlap: None, score: 1.0 }]);
+        let hook = Conflator::new(1.0).similarity(|_, _| None).matches(&a, &b);
+        assert_eq!(hook, matches);
+    }
+}
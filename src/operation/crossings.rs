@@ -0,0 +1,154 @@
+//! The places where the lines of two layers meet, for checking networks, as roads crossing rivers
+//! without a bridge node or power lines crossing each other without a junction.
+
+use std::collections::HashSet;
+
+use crate::algorithm::line_intersection::{intersection, sweep_segment_pairs, SegmentIntersection, ZInterpolation};
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::extract;
+
+/// A place where a geometry of the first layer meets a geometry of the second.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Crossing {
+    /// The index of the geometry in the first layer.
+    pub a: usize,
+    /// The index of the geometry in the second layer.
+    pub b: usize,
+    /// The point where the geometries meet, or the start of the stretch they share. Its z is
+    /// the mean of the z of both geometries there.
+    pub point: Coordinate,
+    /// The end of the stretch along which the geometries run together, if they do.
+    pub overlap_end: Option<Coordinate>,
+    /// Whether both geometries have a vertex at the point, a node shared by both networks.
+    pub noded: bool,
+}
+
+/// The crossings between two layers, with the number of crossings of every geometry.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Crossings {
+    /// The crossings ordered by the geometries of the first layer, then of the second, then
+    /// along x and y.
+    pub crossings: Vec<Crossing>,
+    /// The number of crossings of every geometry of the first layer.
+    pub counts_a: Vec<usize>,
+    /// The number of crossings of every geometry of the second layer.
+    pub counts_b: Vec<usize>,
+}
+
+impl Crossings {
+    /// Returns the crossings where the geometries do not share a vertex.
+    pub fn unnoded(&self) -> impl Iterator<Item = &Crossing> {
+        self.crossings.iter().filter(|c| !c.noded)
+    }
+}
+
+/// Returns the places where the lines and the rings of the polygons of a layer meet those of
+/// another, with the number of them for every geometry.
+///
+/// The segments of both layers are swept together along the x axis, and every pair of segments
+/// from different layers whose envelopes overlap is intersected. Crossings found twice, as at a
+/// vertex between two segments of the same line, are kept once. Geometries meeting inside of
+/// their own layer are not reported.
+///
+/// # Arguments
+///
+/// * `lines_a` - The geometries of the first layer.
+/// * `lines_b` - The geometries of the second layer.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::crossings;
+///
+/// let roads = vec![
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+///     //this road has a bridge node where it crosses the river
+///     Geometry::LineString { coordinates: vec![coord!(0, 5), coord!(5, 5), coord!(10, 5)] },
+/// ];
+/// let rivers = vec![Geometry::LineString { coordinates: vec![coord!(5, -5), coord!(5, 5), coord!(5, 10)] }];
+/// let result = crossings(&roads, &rivers);
+/// assert_eq!(result.counts_a, [1, 1]);
+/// assert_eq!(result.counts_b, [2]);
+/// let unnoded: Vec<(f64, f64)> = result.unnoded().map(|c| (c.point.x(), c.point.y())).collect();
+/// assert_eq!(unnoded, [(5.0, 0.0)]);
+/// ```
+pub fn crossings(lines_a: &[Geometry], lines_b: &[Geometry]) -> Crossings {
+    //the segments of both layers, with their layer and the index of their geometry
+    let mut owners = Vec::new();
+    let mut ends = Vec::new();
+    for (layer, geometries) in [lines_a, lines_b].into_iter().enumerate() {
+        for (i, geometry) in geometries.iter().enumerate() {
+            let mut paths = extract::lines(geometry);
+            paths.extend(geometry.polygons().flatten().cloned());
+            for w in paths.iter().flat_map(|path| path.windows(2)) {
+                owners.push((layer, i));
+                ends.push((w[0].clone(), w[1].clone()));
+            }
+        }
+    }
+    let segments: Vec<(&Coordinate, &Coordinate)> = ends.iter().map(|(p, q)| (p, q)).collect();
+
+    let mut result = Crossings { crossings: Vec::new(), counts_a: vec![0; lines_a.len()], counts_b: vec![0; lines_b.len()] };
+    let mut seen = HashSet::new();
+    sweep_segment_pairs(&segments, |s, t| {
+        if owners[s].0 == owners[t].0 {
+            return true;
+        }
+        //the segment of the first layer comes first
+        let (s, t) = if owners[s].0 == 0 { (s, t) } else { (t, s) };
+        let ((p1, p2), (q1, q2)) = (segments[s], segments[t]);
+        let (point, overlap_end) = match intersection(p1, p2, q1, q2, ZInterpolation::Average) {
+            SegmentIntersection::None => return true,
+            SegmentIntersection::Point(point) => (point, None),
+            SegmentIntersection::Collinear(start, end) if start.equals_2d(&end) => (start, None),
+            SegmentIntersection::Collinear(start, end) => (start, Some(end)),
+        };
+        let (a, b) = (owners[s].1, owners[t].1);
+        if seen.insert((a, b, point.key())) {
+            let noded = [p1, p2].iter().any(|v| v.equals_2d(&point)) && [q1, q2].iter().any(|v| v.equals_2d(&point));
+            result.crossings.push(Crossing { a, b, point, overlap_end, noded });
+        }
+        true
+    });
+    result.crossings.sort_by(|c, d| {
+        (c.a, c.b).cmp(&(d.a, d.b)).then(c.point.x().total_cmp(&d.point.x())).then(c.point.y().total_cmp(&d.point.y()))
+    });
+    for crossing in &result.crossings {
+        result.counts_a[crossing.a] += 1;
+        result.counts_b[crossing.b] += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_crossings_of_lines_and_rings() {
+        //a zigzag crossing the sides of a square four times, once at one of its own vertices
+        let zigzag = Geometry::LineString { coordinates: vec![coord!(-1, 1), coord!(2, 1), coord!(2, -1), coord!(3, 2), coord!(4, 2), coord!(6, 2)] };
+        let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+        let result = crossings(&[zigzag], &[square.clone(), Geometry::Point { coordinates: coord!(2, 1) }]);
+        let found: Vec<(f64, f64, bool)> = result.crossings.iter().map(|c| (c.point.x(), c.point.y(), c.noded)).collect();
+        assert_eq!(found, [(0.0, 1.0, false), (2.0, 0.0, false), (2.0 + 1.0 / 3.0, 0.0, false), (4.0, 2.0, false)]);
+        assert_eq!((result.counts_a, result.counts_b), (vec![4], vec![4, 0]));
+
+        //a line running along a side of the square, and one ending on a corner
+        let along = Geometry::LineString { coordinates: vec![coord!(4, 1), coord!(4, 3)] };
+        let corner = Geometry::LineString { coordinates: vec![coord!(5, 5), coord!(4, 4)] };
+        let result = crossings(&[square], &[along, corner]);
+        assert_eq!(result.crossings.len(), 2);
+        assert!(result.crossings[0].point.equals_2d(&coord!(4, 1)));
+        assert!(result.crossings[0].overlap_end.as_ref().is_some_and(|end| end.equals_2d(&coord!(4, 3))));
+        assert!(result.crossings[1].point.equals_2d(&coord!(4, 4)));
+        assert!(result.crossings[1].noded && result.crossings[1].overlap_end.is_none());
+        assert_eq!(result.unnoded().count(), 1);
+        assert_eq!(crossings(&[], &[]), Crossings::default());
+    }
+}
@@ -0,0 +1,163 @@
+//! Detection of the changes between two versions of a layer, such as two releases of a cadastre,
+//! telling which features were added, removed or modified and where their geometries changed.
+
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::feature::Feature;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+
+/// The changes from an old version of a layer to a new one.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LayerDiff {
+    /// The indices of the new features without a counterpart in the old layer.
+    pub added: Vec<usize>,
+    /// The indices of the old features without a counterpart in the new layer.
+    pub removed: Vec<usize>,
+    /// The features present in both layers whose geometry or attributes changed.
+    pub modified: Vec<Modification>,
+}
+
+/// A feature present in both versions of a layer that changed between them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Modification {
+    /// The index of the feature in the old layer.
+    pub old: usize,
+    /// The index of the feature in the new layer.
+    pub new: usize,
+    /// The area gained or lost by the feature, the symmetric difference of its old and new
+    /// geometries, as a multi polygon. It is empty when only the attributes changed.
+    pub delta: Geometry,
+    /// Whether the attributes of the feature changed.
+    pub properties_changed: bool,
+}
+
+/// Returns the features added, removed and modified from the old to the new version of a layer.
+///
+/// Features are paired one to one between the layers: first the features with equal geometries,
+/// and then the polygons sharing some area, taking the pairs whose intersection is the largest
+/// part of their union first. A pair whose geometries cover the same area and whose attributes
+/// are equal is unchanged, while any other pair is modified, and the features left without a
+/// pair are removed or added. The lists are ordered by the indices of the features, in the old
+/// layer for modifications, and the layers may be in any order.
+///
+/// # Arguments
+///
+/// * `old` - The features of the old version of the layer.
+/// * `new` - The features of the new version of the layer.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::feature::Feature;
+/// use geoms::operation::diff_layers;
+///
+/// let parcel = |x: f64, width: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x, 0), coord!(x + width, 0), coord!(x + width, 10), coord!(x, 10), coord!(x, 0)
+/// ]] };
+/// let old = vec![
+///     Feature::new(parcel(0.0, 10.0)).with_property("owner", "Ada"),
+///     Feature::new(parcel(10.0, 10.0)).with_property("owner", "Ben"),
+///     Feature::new(parcel(20.0, 10.0)).with_property("owner", "Cy"),
+/// ];
+/// //the second parcel grew into the first one, and the third one was sold and replaced
+/// let new = vec![
+///     Feature::new(parcel(40.0, 10.0)).with_property("owner", "Dee"),
+///     Feature::new(parcel(20.0, 10.0)).with_property("owner", "Eve"),
+///     Feature::new(parcel(0.0, 8.0)).with_property("owner", "Ada"),
+///     Feature::new(parcel(8.0, 12.0)).with_property("owner", "Ben"),
+/// ];
+/// let diff = diff_layers(&old, &new).unwrap();
+/// assert_eq!(diff.added, vec![0]);
+/// assert!(diff.removed.is_empty());
+/// let changes: Vec<_> = diff.modified.iter().map(|m| (m.old, m.new, m.delta.area(), m.properties_changed)).collect();
+/// assert_eq!(changes, vec![(0, 2, 20.0, false), (1, 3, 20.0, false), (2, 1, 0.0, true)]);
+/// ```
+pub fn diff_layers(old: &[Feature], new: &[Feature]) -> Result<LayerDiff, Error> {
+    //pairs of features with the part of their union they share
+    let mut pairs: Vec<(usize, usize, f64)> = Vec::new();
+    let mut by_geometry: HashMap<Vec<u8>, Vec<usize>> = HashMap::new();
+    for (j, feature) in new.iter().enumerate() {
+        by_geometry.entry(feature.geometry().as_binary()).or_default().push(j);
+    }
+    for (i, feature) in old.iter().enumerate() {
+        for &j in by_geometry.get(&feature.geometry().as_binary()).into_iter().flatten() {
+            pairs.push((i, j, f64::INFINITY));
+        }
+    }
+    let areas: Vec<f64> = new.iter().map(|f| f.geometry().area()).collect();
+    let tree = STRtree::new(new.iter().enumerate().map(|(j, f)| (f.geometry().envelope(), j)).collect());
+    for (i, feature) in old.iter().enumerate() {
+        let area = feature.geometry().area();
+        if area == 0.0 {
+            continue;
+        }
+        for &j in tree.query(&feature.geometry().envelope()) {
+            if areas[j] == 0.0 {
+                continue;
+            }
+            let shared = feature.geometry().intersection(new[j].geometry())?.area();
+            if shared > 0.0 {
+                pairs.push((i, j, shared / (area + areas[j] - shared)));
+            }
+        }
+    }
+    pairs.sort_by(|p, q| q.2.total_cmp(&p.2).then((p.0, p.1).cmp(&(q.0, q.1))));
+
+    let mut matched_old = vec![false; old.len()];
+    let mut matched_new = vec![false; new.len()];
+    let mut diff = LayerDiff::default();
+    for (i, j, _) in pairs {
+        if matched_old[i] || matched_new[j] {
+            continue;
+        }
+        matched_old[i] = true;
+        matched_new[j] = true;
+        let (before, after) = (old[i].geometry(), new[j].geometry());
+        let delta = if before == after {
+            Geometry::MultiPolygon { coordinates: vec![] }
+        } else {
+            before.sym_difference(after)?
+        };
+        let properties_changed = old[i].properties() != new[j].properties();
+        if properties_changed || delta.area() > 0.0 {
+            diff.modified.push(Modification { old: i, new: j, delta, properties_changed });
+        }
+    }
+    diff.modified.sort_by_key(|m| m.old);
+    diff.removed = (0..old.len()).filter(|&i| !matched_old[i]).collect();
+    diff.added = (0..new.len()).filter(|&j| !matched_new[j]).collect();
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_points_and_redigitised_polygons() {
+        let well = |x: f64| Feature::new(Geometry::Point { coordinates: coord!(x, 0) });
+        //the same square starting from another vertex
+        let square = Feature::new(Geometry::Polygon { coordinates: vec![vec![
+            coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1), coord!(0, 0)
+        ]] });
+        let redigitised = Feature::new(Geometry::Polygon { coordinates: vec![vec![
+            coord!(1, 1), coord!(0, 1), coord!(0, 0), coord!(1, 0), coord!(1, 1)
+        ]] });
+        let old = vec![well(0.0), square, well(1.0), well(1.0)];
+        let new = vec![well(1.0), well(2.0), redigitised.with_property("surveyed", true)];
+        let diff = diff_layers(&old, &new).unwrap();
+        assert_eq!(diff.added, vec![1]);
+        assert_eq!(diff.removed, vec![0, 3]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!((diff.modified[0].old, diff.modified[0].new), (1, 2));
+        assert!(diff.modified[0].properties_changed);
+        assert_eq!(diff.modified[0].delta.area(), 0.0);
+    }
+}
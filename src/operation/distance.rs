@@ -0,0 +1,911 @@
+use crate::algorithm::line_intersection::{intersection, SegmentIntersection, ZInterpolation};
+use crate::algorithm::point_in_polygon::{locate_in_polygon, Location};
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+
+/// Computes the minimum distance between two geometries.
+///
+/// By default the distance is planar. When the 3D semantics are enabled the z ordinate is
+/// taken into account, in which case polygons are measured through their rings. Planar
+/// distances between geometries with many facets are measured through an
+/// [`IndexedFacetDistance`] of the larger one, instead of between every pair of facets.
+#[derive(Clone, Copy)]
+pub struct DistanceOp<'a> {
+    a: &'a Geometry,
+    b: &'a Geometry,
+    use_z: bool,
+}
+
+impl<'a> DistanceOp<'a> {
+    /// Creates a new planar distance operation between two geometries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::DistanceOp;
+    ///
+    /// let a = Geometry::Point { coordinates: coord!(0, 0, 0) };
+    /// let b = Geometry::Point { coordinates: coord!(3, 4, 12) };
+    /// assert_eq!(DistanceOp::new(&a, &b).distance(), 5.0);
+    /// ```
+    pub fn new(a: &'a Geometry, b: &'a Geometry) -> Self {
+        Self { a, b, use_z: false }
+    }
+
+    /// Returns a new operation that uses 3D semantics if `use_z` is true, or planar semantics otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::DistanceOp;
+    ///
+    /// let a = Geometry::Point { coordinates: coord!(0, 0, 0) };
+    /// let b = Geometry::Point { coordinates: coord!(3, 4, 12) };
+    /// let op = DistanceOp::new(&a, &b).set_3d(true);
+    /// assert!(op.is_3d());
+    /// assert_eq!(op.distance(), 13.0);
+    /// ```
+    pub fn set_3d(&self, use_z: bool) -> Self {
+        Self { use_z, ..*self }
+    }
+
+    /// Returns true if the operation uses 3D semantics.
+    pub fn is_3d(&self) -> bool {
+        self.use_z
+    }
+
+    /// Returns the minimum distance between the geometries, or 0 if any of them is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::DistanceOp;
+    ///
+    /// let polygon = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+    /// let inside = Geometry::Point { coordinates: coord!(2, 2) };
+    /// let outside = Geometry::LineString { coordinates: vec![coord!(6, 0), coord!(6, 4)] };
+    /// assert_eq!(DistanceOp::new(&polygon, &inside).distance(), 0.0);
+    /// assert_eq!(DistanceOp::new(&polygon, &outside).distance(), 2.0);
+    /// ```
+    pub fn distance(&self) -> f64 {
+        self.distance_until(0.0)
+    }
+
+    /// Returns true if the geometries are within the given distance of each other. The search
+    /// stops at the first pair of facets close enough, so this is faster than computing the
+    /// distance. Empty geometries are never within any distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::DistanceOp;
+    ///
+    /// let a = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+    /// let b = Geometry::Point { coordinates: coord!(5, 3) };
+    /// assert!(DistanceOp::new(&a, &b).is_within_distance(3.0));
+    /// assert!(!DistanceOp::new(&a, &b).is_within_distance(2.9));
+    /// ```
+    pub fn is_within_distance(&self, distance: f64) -> bool {
+        !self.a.envelope().is_empty() && !self.b.envelope().is_empty() && self.distance_until(distance) <= distance
+    }
+
+    /// Returns the minimum distance between the geometries, or as soon as a distance of at most
+    /// `stop` is found, a distance between `stop` and the minimum.
+    fn distance_until(&self, stop: f64) -> f64 {
+        let a = Facets::of(self.a);
+        let b = Facets::of(self.b);
+        if a.is_empty() || b.is_empty() {
+            return 0.0;
+        }
+        if !self.use_z && a.len().saturating_mul(b.len()) > INDEXED_PAIRS {
+            let (indexed, other) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+            return IndexedFacetDistance::of(indexed).nearest_until(&other, stop).map_or(0.0, |nearest| nearest.2);
+        }
+        if !self.use_z && (a.intersects_area_of(&b) || b.intersects_area_of(&a)) {
+            return 0.0;
+        }
+
+        let use_z = self.use_z;
+        let point_point = |p, q| if use_z { point_point_3d(p, q) } else { point_point_2d(p, q) };
+        let point_segment = |p, a, b| {
+            if use_z { point_segment_3d(p, a, b) } else { point_segment_2d(p, a, b) }
+        };
+        let segment_segment = |p1, p2, q1, q2| {
+            if use_z { segment_segment_3d(p1, p2, q1, q2) } else { segment_segment_2d(p1, p2, q1, q2) }
+        };
+
+        let mut min = f64::INFINITY;
+        for p in &a.points {
+            for q in &b.points {
+                min = min.min(point_point(p, q));
+            }
+            for (q1, q2) in &b.segments {
+                min = min.min(point_segment(p, q1, q2));
+            }
+            if min <= stop {
+                return min;
+            }
+        }
+        for (p1, p2) in &a.segments {
+            for q in &b.points {
+                min = min.min(point_segment(q, p1, p2));
+            }
+            for (q1, q2) in &b.segments {
+                min = min.min(segment_segment(p1, p2, q1, q2));
+                if min <= stop {
+                    return min;
+                }
+            }
+        }
+        min
+    }
+}
+
+/// The number of pairs of facets above which planar distances are measured through an index.
+const INDEXED_PAIRS: usize = 1 << 16;
+
+/// Returns the indices of the geometries within the given planar distance of the target, in
+/// ascending order. Only the geometries whose envelopes are within the distance of the envelope of
+/// the target are measured, against an index of the facets of the target, and every measure stops
+/// as soon as two facets are close enough.
+///
+/// # Arguments
+///
+/// * `geometries` - The geometries to filter.
+/// * `target` - The geometry to measure the distance from.
+/// * `distance` - The maximum distance, included.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::within_distance;
+///
+/// let wells: Vec<Geometry> = (0..10).map(|i| Geometry::Point { coordinates: coord!(i * 3, 4) }).collect();
+/// let road = Geometry::LineString { coordinates: vec![coord!(6, 0), coord!(12, 0)] };
+/// assert_eq!(within_distance(&wells, &road, 5.0), vec![1, 2, 3, 4, 5]);
+/// ```
+pub fn within_distance(geometries: &[Geometry], target: &Geometry, distance: f64) -> Vec<usize> {
+    indices_within_distance(&geometries.iter().collect::<Vec<_>>(), target, distance)
+}
+
+/// Returns the indices of the geometries intersecting the envelope, in ascending order, as when
+/// drawing a map window. Geometries touching the envelope from outside are included, while a
+/// polygon is not when the envelope lies inside one of its holes.
+///
+/// # Arguments
+///
+/// * `geometries` - The geometries to filter.
+/// * `envelope` - The envelope to intersect.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::filter_bbox;
+///
+/// let roads = vec![
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 10)] },
+///     //its envelope overlaps the window, but the line passes by it
+///     Geometry::LineString { coordinates: vec![coord!(0, 8), coord!(8, 16)] },
+///     Geometry::LineString { coordinates: vec![coord!(20, 0), coord!(20, 10)] },
+/// ];
+/// assert_eq!(filter_bbox(&roads, &Envelope::new(5.0, 0.0, 8.0, 3.0)), vec![]);
+/// assert_eq!(filter_bbox(&roads, &Envelope::new(4.0, 0.0, 8.0, 6.0)), vec![0]);
+/// assert_eq!(filter_bbox(&roads, &Envelope::new(0.0, 0.0, 20.0, 20.0)), vec![0, 1, 2]);
+/// ```
+pub fn filter_bbox(geometries: &[Geometry], envelope: &Envelope) -> Vec<usize> {
+    indices_within_distance(&geometries.iter().collect::<Vec<_>>(), &rectangle(envelope), 0.0)
+}
+
+/// Returns the envelope as a polygon, or an empty polygon if the envelope is empty.
+pub(crate) fn rectangle(envelope: &Envelope) -> Geometry {
+    if envelope.is_empty() {
+        return Geometry::Polygon { coordinates: Vec::new() };
+    }
+    let (x1, y1, x2, y2) = (envelope.min_x(), envelope.min_y(), envelope.max_x(), envelope.max_y());
+    Geometry::Polygon { coordinates: vec![vec![
+        Coordinate::new(x1, y1, 0.0),
+        Coordinate::new(x2, y1, 0.0),
+        Coordinate::new(x2, y2, 0.0),
+        Coordinate::new(x1, y2, 0.0),
+        Coordinate::new(x1, y1, 0.0),
+    ]] }
+}
+
+/// Returns the indices of the geometries within the distance of the target, as
+/// [`within_distance`] does, for geometries that are not stored together in a slice.
+pub(crate) fn indices_within_distance(geometries: &[&Geometry], target: &Geometry, distance: f64) -> Vec<usize> {
+    let envelope = target.envelope();
+    if envelope.is_empty() {
+        return Vec::new();
+    }
+    let tree = STRtree::new(geometries.iter().enumerate().map(|(i, g)| (g.envelope(), i)).collect());
+    let index = IndexedFacetDistance::new(target);
+    let mut found: Vec<usize> = tree
+        .query(&envelope.expand_by(distance))
+        .into_iter()
+        .copied()
+        .filter(|&i| index.is_within_distance(geometries[i], distance))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Returns the discrete Hausdorff distance between two geometries: the largest planar distance
+/// from a vertex of any of them to the other geometry, or 0 if any of them is empty. It measures
+/// how far apart two geometries are at their worst, while [`DistanceOp`] measures it at their
+/// closest. Vertices inside of the polygons of the other geometry are at distance 0, and the
+/// distance is only approximate for long segments far from the vertices of the other geometry.
+///
+/// # Arguments
+///
+/// * `a` - The first geometry.
+/// * `b` - The second geometry.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::distance::hausdorff_distance;
+///
+/// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+/// let track = Geometry::LineString { coordinates: vec![coord!(0, 1), coord!(5, 3), coord!(10, 1)] };
+/// assert_eq!(hausdorff_distance(&road, &track), 3.0);
+/// assert_eq!(road.distance(&track), 1.0);
+/// ```
+pub fn hausdorff_distance(a: &Geometry, b: &Geometry) -> f64 {
+    if a.coords_count() == 0 || b.coords_count() == 0 {
+        return 0.0;
+    }
+    let directed = |from: &Geometry, to: &Geometry| {
+        let index = IndexedFacetDistance::new(to);
+        from.coords_iter()
+            .map(|c| index.distance(&Geometry::Point { coordinates: c.clone() }))
+            .fold(0.0, f64::max)
+    };
+    directed(a, b).max(directed(b, a))
+}
+
+/// Returns the minimum clearance of a geometry: the smallest planar distance between a vertex
+/// and another vertex or a segment not ending at it, or infinity if there are none, as for a
+/// single point. Moving no vertex by less than the clearance can make a valid geometry invalid
+/// or collapse a segment, which makes it a safe upper bound for snapping tolerances.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to measure.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::distance::minimum_clearance;
+///
+/// //a square with a notch reaching 0.5 from its opposite side
+/// let notched = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(6, 10), coord!(5, 0.5), coord!(4, 10), coord!(0, 10), coord!(0, 0)
+/// ]] };
+/// assert_eq!(minimum_clearance(&notched), 0.5);
+/// assert_eq!(minimum_clearance(&Geometry::Point { coordinates: coord!(1, 1) }), f64::INFINITY);
+/// ```
+pub fn minimum_clearance(geometry: &Geometry) -> f64 {
+    clearance(geometry).map_or(f64::INFINITY, |(a, b)| point_point_2d(&a, &b))
+}
+
+/// Returns the line whose length is the minimum clearance of a geometry, from a vertex to the
+/// closest point of another vertex or segment, or an empty line string if the geometry has no
+/// clearance. See [`minimum_clearance`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to measure.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::distance::minimum_clearance_line;
+///
+/// let notched = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(6, 10), coord!(5, 0.5), coord!(4, 10), coord!(0, 10), coord!(0, 0)
+/// ]] };
+/// assert_eq!(minimum_clearance_line(&notched), Geometry::LineString { coordinates: vec![coord!(5, 0.5), coord!(5, 0)] });
+/// ```
+pub fn minimum_clearance_line(geometry: &Geometry) -> Geometry {
+    Geometry::LineString { coordinates: clearance(geometry).map_or(Vec::new(), |(a, b)| vec![a, b]) }
+}
+
+/// Returns the vertex of the geometry closest to another vertex or to a segment not ending at it,
+/// with the closest point of that vertex or segment.
+fn clearance(geometry: &Geometry) -> Option<(Coordinate, Coordinate)> {
+    let facets = Facets::of(geometry);
+    let mut items: Vec<(Envelope, (&Coordinate, &Coordinate))> =
+        facets.segments.iter().map(|&(start, end)| (Envelope::of([start, end]), (start, end))).collect();
+    items.extend(facets.points.iter().map(|&p| (Envelope::of([p]), (p, p))));
+    let tree = STRtree::new(items);
+
+    let mut best: Option<(Coordinate, Coordinate, f64)> = None;
+    for vertex in geometry.coords_iter() {
+        let bound = best.as_ref().map_or(f64::INFINITY, |b| b.2);
+        let found = tree.nearest(&Envelope::of([vertex]), bound, |&(start, end)| {
+            clearance_point(vertex, start, end).map_or(f64::INFINITY, |c| point_point_2d(vertex, &c))
+        });
+        if let Some((i, distance)) = found {
+            let (start, end) = tree.get(i)?.1;
+            best = Some((vertex.clone(), clearance_point(vertex, start, end)?, distance));
+        }
+    }
+    best.map(|(a, b, _)| (a, b))
+}
+
+/// Returns the point of the segment `start` -> `end` closest to the vertex, or its other end when
+/// the segment ends at the vertex, or None if the segment is the vertex itself.
+fn clearance_point(vertex: &Coordinate, start: &Coordinate, end: &Coordinate) -> Option<Coordinate> {
+    match (vertex.equals_2d(start), vertex.equals_2d(end)) {
+        (true, true) => None,
+        (true, false) => Some(end.clone()),
+        (false, true) => Some(start.clone()),
+        (false, false) => Some(project(vertex, start, end)),
+    }
+}
+
+/// Computes the distance from a fixed geometry to many others, indexing the segments and points
+/// of the fixed geometry once so that every query only measures the facets near the other
+/// geometry, as when measuring the distance of millions of points to a coastline.
+///
+/// The distance is planar, and is zero when a geometry lies inside of the polygons of the other.
+pub struct IndexedFacetDistance<'a> {
+    tree: STRtree<IndexedFacet<'a>>,
+    envelope: Envelope,
+    polygons: Vec<&'a [Vec<Coordinate>]>,
+    parts: Vec<&'a Coordinate>,
+}
+
+/// A segment of the indexed geometry, or a point when both ends are the same.
+struct IndexedFacet<'a> {
+    start: &'a Coordinate,
+    end: &'a Coordinate,
+    owner: Option<usize>,
+}
+
+impl<'a> IndexedFacetDistance<'a> {
+    /// Indexes the facets of a geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::IndexedFacetDistance;
+    ///
+    /// let coast = Geometry::LineString { coordinates: (0..1000).map(|i| coord!(i, (i % 2) as f64)).collect() };
+    /// let index = IndexedFacetDistance::new(&coast);
+    /// assert_eq!(index.distance(&Geometry::Point { coordinates: coord!(501, 5) }), 4.0);
+    /// assert_eq!(index.distance(&Geometry::Point { coordinates: coord!(-3, -4) }), 5.0);
+    /// ```
+    pub fn new(geometry: &'a Geometry) -> Self {
+        IndexedFacetDistance::of(Facets::of(geometry))
+    }
+
+    fn of(facets: Facets<'a>) -> Self {
+        let mut items: Vec<(Envelope, IndexedFacet)> = facets
+            .segments
+            .iter()
+            .zip(&facets.owners)
+            .map(|(&(start, end), &owner)| (Envelope::of([start, end]), IndexedFacet { start, end, owner }))
+            .collect();
+        items.extend(facets.points.iter().map(|&p| (Envelope::of([p]), IndexedFacet { start: p, end: p, owner: None })));
+        let envelope = items.iter().fold(Envelope::empty(), |envelope, item| envelope.merge(&item.0));
+        IndexedFacetDistance { tree: STRtree::new(items), envelope, polygons: facets.polygons, parts: facets.parts }
+    }
+
+    /// Returns the minimum planar distance from the indexed geometry to another one, or 0 if any
+    /// of them is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::IndexedFacetDistance;
+    ///
+    /// let lake = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)]] };
+    /// let index = IndexedFacetDistance::new(&lake);
+    /// assert_eq!(index.distance(&Geometry::Point { coordinates: coord!(5, 5) }), 0.0);
+    /// assert_eq!(index.distance(&Geometry::LineString { coordinates: vec![coord!(12, 20), coord!(12, 5)] }), 2.0);
+    /// ```
+    pub fn distance(&self, other: &Geometry) -> f64 {
+        self.nearest_until(&Facets::of(other), 0.0).map_or(0.0, |(_, _, distance)| distance)
+    }
+
+    /// Returns true if the indexed geometry and another one are within the given planar distance
+    /// of each other, stopping at the first pair of facets close enough. Empty geometries are
+    /// never within any distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::IndexedFacetDistance;
+    ///
+    /// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+    /// let index = IndexedFacetDistance::new(&road);
+    /// assert!(index.is_within_distance(&Geometry::Point { coordinates: coord!(5, 3) }, 3.0));
+    /// assert!(!index.is_within_distance(&Geometry::Point { coordinates: coord!(5, 3) }, 2.9));
+    /// ```
+    pub fn is_within_distance(&self, other: &Geometry, distance: f64) -> bool {
+        self.nearest_until(&Facets::of(other), distance).is_some_and(|(_, _, found)| found <= distance)
+    }
+
+    /// Returns the closest points of the indexed geometry and of another one, in that order, or
+    /// None if any of them is empty. When a geometry lies inside of the other both points are the
+    /// same vertex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::distance::IndexedFacetDistance;
+    ///
+    /// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+    /// let index = IndexedFacetDistance::new(&road);
+    /// let well = Geometry::Point { coordinates: coord!(4, 3) };
+    /// assert_eq!(index.nearest_points(&well), Some((coord!(4, 0), coord!(4, 3))));
+    /// ```
+    pub fn nearest_points(&self, other: &Geometry) -> Option<(Coordinate, Coordinate)> {
+        self.nearest_until(&Facets::of(other), 0.0).map(|(a, b, _)| (a, b))
+    }
+
+    /// Returns the closest points of the indexed facets and the other ones and their distance,
+    /// or as soon as a distance of at most `stop` is found, a pair of points between `stop` and
+    /// the minimum apart.
+    fn nearest_until(&self, facets: &Facets, stop: f64) -> Option<(Coordinate, Coordinate, f64)> {
+        if self.tree.is_empty() || facets.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(Coordinate, Coordinate, f64)> = None;
+        let mut queries: Vec<(&Coordinate, &Coordinate)> = facets.points.iter().map(|&p| (p, p)).collect();
+        queries.extend(facets.segments.iter().copied());
+        for (q1, q2) in queries {
+            let bound = best.as_ref().map_or(f64::INFINITY, |b| b.2);
+            let found = self.tree.nearest(&Envelope::of([q1, q2]), bound, |facet| {
+                let (a, b) = closest_points(facet.start, facet.end, q1, q2);
+                point_point_2d(&a, &b)
+            });
+            if let Some((i, distance)) = found {
+                let facet = &self.tree.get(i)?.1;
+                let (a, b) = closest_points(facet.start, facet.end, q1, q2);
+                best = Some((a, b, distance));
+                if distance <= stop {
+                    return best;
+                }
+            }
+        }
+
+        //without crossings, every part lies either completely inside or outside of the polygons
+        for part in &facets.parts {
+            if self.in_polygons(part) {
+                return Some(((*part).clone(), (*part).clone(), 0.0));
+            }
+        }
+        if !facets.polygons.is_empty() {
+            for part in &self.parts {
+                if facets.polygons.iter().any(|p| locate_in_polygon(part, p) != Location::Exterior) {
+                    return Some(((*part).clone(), (*part).clone(), 0.0));
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns true if the point lies inside of the polygons of the indexed geometry, counting the
+    /// crossings of their rings with a ray towards positive x.
+    fn in_polygons(&self, p: &Coordinate) -> bool {
+        if self.polygons.is_empty() || p.x() > self.envelope.max_x() {
+            return false;
+        }
+        let ray = Envelope::new(p.x(), p.y(), self.envelope.max_x(), p.y());
+        let mut inside = vec![false; self.polygons.len()];
+        for facet in self.tree.query(&ray) {
+            let (Some(owner), a, b) = (facet.owner, facet.start, facet.end) else {
+                continue;
+            };
+            if (a.y() > p.y()) != (b.y() > p.y()) {
+                let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+                if p.x() < x {
+                    inside[owner] = !inside[owner];
+                }
+            }
+        }
+        inside.contains(&true)
+    }
+}
+
+/// The points, segments and polygons a geometry is made of.
+struct Facets<'a> {
+    points: Vec<&'a Coordinate>,
+    segments: Vec<(&'a Coordinate, &'a Coordinate)>,
+    polygons: Vec<&'a [Vec<Coordinate>]>,
+    //the index of the polygon every segment bounds, if any
+    owners: Vec<Option<usize>>,
+    //a vertex of every point and path
+    parts: Vec<&'a Coordinate>,
+}
+
+impl<'a> Facets<'a> {
+    fn of(geometry: &'a Geometry) -> Self {
+        let mut facets = Facets {
+            points: Vec::new(),
+            segments: Vec::new(),
+            polygons: Vec::new(),
+            owners: Vec::new(),
+            parts: Vec::new(),
+        };
+        facets.add(geometry);
+        facets
+    }
+
+    fn add(&mut self, geometry: &'a Geometry) {
+        match geometry {
+            Geometry::Point { coordinates } => self.add_point(coordinates),
+            Geometry::MultiPoint { coordinates } => coordinates.iter().for_each(|point| self.add_point(point)),
+            Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+                self.add_path(coordinates, None)
+            }
+            Geometry::MultiLineString { coordinates } => {
+                coordinates.iter().for_each(|line| self.add_path(line, None))
+            }
+            Geometry::Polygon { coordinates } => self.add_polygon(coordinates),
+            Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+                coordinates.iter().for_each(|polygon| self.add_polygon(polygon))
+            }
+            Geometry::Tin { coordinates } => {
+                coordinates.iter().for_each(|triangle| self.add_polygon(std::slice::from_ref(triangle)))
+            }
+            Geometry::GeometryCollection { geometries } => {
+                geometries.iter().for_each(|geometry| self.add(geometry))
+            }
+        }
+    }
+
+    fn add_point(&mut self, point: &'a Coordinate) {
+        self.points.push(point);
+        self.parts.push(point);
+    }
+
+    fn add_path(&mut self, path: &'a [Coordinate], owner: Option<usize>) {
+        if path.len() == 1 {
+            self.points.push(&path[0]);
+        }
+        if let Some(first) = path.first() {
+            self.parts.push(first);
+        }
+        self.segments.extend(path.windows(2).map(|w| (&w[0], &w[1])));
+        self.owners.resize(self.segments.len(), owner);
+    }
+
+    fn add_polygon(&mut self, rings: &'a [Vec<Coordinate>]) {
+        if rings.is_empty() {
+            return;
+        }
+        let owner = Some(self.polygons.len());
+        rings.iter().for_each(|ring| self.add_path(ring, owner));
+        self.polygons.push(rings);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.points.is_empty() && self.segments.is_empty()
+    }
+
+    /// Returns the number of points and segments.
+    fn len(&self) -> usize {
+        self.points.len() + self.segments.len()
+    }
+
+    /// Returns true if any vertex of the facets lies in the interior or boundary of the polygons of `other`.
+    fn intersects_area_of(&self, other: &Facets) -> bool {
+        if other.polygons.is_empty() {
+            return false;
+        }
+        let vertices = self.points.iter().copied().chain(self.segments.iter().map(|s| s.0));
+        for vertex in vertices {
+            if other.polygons.iter().any(|p| locate_in_polygon(vertex, p) != Location::Exterior) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn point_point_2d(p: &Coordinate, q: &Coordinate) -> f64 {
+    (p.x() - q.x()).hypot(p.y() - q.y())
+}
+
+fn point_point_3d(p: &Coordinate, q: &Coordinate) -> f64 {
+    let dx = p.x() - q.x();
+    let dy = p.y() - q.y();
+    let dz = p.z() - q.z();
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+pub(crate) fn point_segment_2d(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    let dx = b.x() - a.x();
+    let dy = b.y() - a.y();
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return point_point_2d(p, a);
+    }
+    let t = (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / length_squared).clamp(0.0, 1.0);
+    (p.x() - (a.x() + t * dx)).hypot(p.y() - (a.y() + t * dy))
+}
+
+fn point_segment_3d(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    let dx = b.x() - a.x();
+    let dy = b.y() - a.y();
+    let dz = b.z() - a.z();
+    let length_squared = dx * dx + dy * dy + dz * dz;
+    if length_squared == 0.0 {
+        return point_point_3d(p, a);
+    }
+    let t = (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy + (p.z() - a.z()) * dz) / length_squared)
+        .clamp(0.0, 1.0);
+    let cx = p.x() - (a.x() + t * dx);
+    let cy = p.y() - (a.y() + t * dy);
+    let cz = p.z() - (a.z() + t * dz);
+    (cx * cx + cy * cy + cz * cz).sqrt()
+}
+
+fn segment_segment_2d(p1: &Coordinate, p2: &Coordinate, q1: &Coordinate, q2: &Coordinate) -> f64 {
+    if intersection(p1, p2, q1, q2, ZInterpolation::Zero) != SegmentIntersection::None {
+        return 0.0;
+    }
+    point_segment_2d(p1, q1, q2)
+        .min(point_segment_2d(p2, q1, q2))
+        .min(point_segment_2d(q1, p1, p2))
+        .min(point_segment_2d(q2, p1, p2))
+}
+
+/// Returns the closest points of the planar segments `p1` -> `p2` and `q1` -> `q2`, either of which
+/// can be a point.
+fn closest_points(p1: &Coordinate, p2: &Coordinate, q1: &Coordinate, q2: &Coordinate) -> (Coordinate, Coordinate) {
+    if p1 != p2 && q1 != q2 {
+        match intersection(p1, p2, q1, q2, ZInterpolation::Zero) {
+            SegmentIntersection::Point(c) | SegmentIntersection::Collinear(c, _) => return (c.clone(), c),
+            SegmentIntersection::None => {}
+        }
+    }
+    [
+        (project(q1, p1, p2), q1.clone()),
+        (project(q2, p1, p2), q2.clone()),
+        (p1.clone(), project(p1, q1, q2)),
+        (p2.clone(), project(p2, q1, q2)),
+    ]
+    .into_iter()
+    .min_by(|a, b| point_point_2d(&a.0, &a.1).total_cmp(&point_point_2d(&b.0, &b.1)))
+    .unwrap()
+}
+
+/// Returns the point of the segment `a` -> `b` closest to `p` in the plane.
+fn project(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> Coordinate {
+    let dx = b.x() - a.x();
+    let dy = b.y() - a.y();
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return a.clone();
+    }
+    let t = (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / length_squared).clamp(0.0, 1.0);
+    Coordinate::new(a.x() + t * dx, a.y() + t * dy, a.z() + t * (b.z() - a.z()))
+}
+
+/// Closest distance between two segments in 3D, following the parametric approach of D. Sunday.
+fn segment_segment_3d(p1: &Coordinate, p2: &Coordinate, q1: &Coordinate, q2: &Coordinate) -> f64 {
+    let u = [p2.x() - p1.x(), p2.y() - p1.y(), p2.z() - p1.z()];
+    let v = [q2.x() - q1.x(), q2.y() - q1.y(), q2.z() - q1.z()];
+    let w = [p1.x() - q1.x(), p1.y() - q1.y(), p1.z() - q1.z()];
+    let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let a = dot(u, u);
+    let b = dot(u, v);
+    let c = dot(v, v);
+    let d = dot(u, w);
+    let e = dot(v, w);
+
+    //degenerate segments are points
+    if a == 0.0 {
+        return point_segment_3d(p1, q1, q2);
+    }
+    if c == 0.0 {
+        return point_segment_3d(q1, p1, p2);
+    }
+
+    let denominator = a * c - b * b;
+    let (mut s_n, mut s_d, mut t_n, mut t_d) = if denominator < f64::EPSILON * a * c {
+        //parallel segments
+        (0.0, 1.0, e, c)
+    } else {
+        (b * e - c * d, denominator, a * e - b * d, denominator)
+    };
+    if s_n < 0.0 {
+        s_n = 0.0;
+        t_n = e;
+        t_d = c;
+    } else if s_n > s_d {
+        s_n = s_d;
+        t_n = e + b;
+        t_d = c;
+    }
+
+    if t_n < 0.0 {
+        t_n = 0.0;
+        s_n = (-d).clamp(0.0, a);
+        s_d = a;
+    } else if t_n > t_d {
+        t_n = t_d;
+        s_n = (b - d).clamp(0.0, a);
+        s_d = a;
+    }
+
+    let s = if s_n == 0.0 { 0.0 } else { s_n / s_d };
+    let t = if t_n == 0.0 { 0.0 } else { t_n / t_d };
+    let closest = [
+        w[0] + s * u[0] - t * v[0],
+        w[1] + s * u[1] - t * v[1],
+        w[2] + s * u[2] - t * v[2],
+    ];
+    dot(closest, closest).sqrt()
+}
+
+/// Returns the length of the segments of the geometry, taking the z ordinate into account if `use_z` is true.
+pub(crate) fn length(geometry: &Geometry, use_z: bool) -> f64 {
+    let point_point = if use_z { point_point_3d } else { point_point_2d };
+    Facets::of(geometry).segments.iter().map(|(a, b)| point_point(a, b)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_segment_segment_3d() {
+        //skew segments, one above the other
+        let d = segment_segment_3d(&coord!(0, 0, 0), &coord!(2, 0, 0), &coord!(1, -1, 3), &coord!(1, 1, 3));
+        assert_eq!(d, 3.0);
+
+        //parallel segments
+        let d = segment_segment_3d(&coord!(0, 0, 0), &coord!(2, 0, 0), &coord!(0, 1, 0), &coord!(2, 1, 0));
+        assert_eq!(d, 1.0);
+
+        //collinear disjoint segments
+        let d = segment_segment_3d(&coord!(0, 0, 0), &coord!(1, 0, 0), &coord!(3, 0, 0), &coord!(5, 0, 0));
+        assert_eq!(d, 2.0);
+
+        //closest points at the endpoints
+        let d = segment_segment_3d(&coord!(0, 0, 0), &coord!(1, 0, 0), &coord!(2, 1, 0), &coord!(2, 2, 0));
+        assert_eq!(d, 2f64.sqrt());
+    }
+
+    #[test]
+    fn test_distance_3d_lines() {
+        let a = Geometry::LineString { coordinates: vec![coord!(0, 0, 0), coord!(10, 0, 0)] };
+        let b = Geometry::LineString { coordinates: vec![coord!(5, -5, 4), coord!(5, 5, 4)] };
+        assert_eq!(DistanceOp::new(&a, &b).distance(), 0.0);
+        assert_eq!(DistanceOp::new(&a, &b).set_3d(true).distance(), 4.0);
+    }
+
+    #[test]
+    fn test_indexed_facet_distance_matches_distance_op() {
+        //a star shaped polygon with a hole, and probes around and inside of it
+        let star: Vec<Coordinate> = (0..=40)
+            .map(|i| {
+                let angle = (i % 40) as f64 * std::f64::consts::PI / 20.0;
+                let r = if i % 2 == 0 { 10.0 } else { 6.0 };
+                coord!(r * angle.cos(), r * angle.sin())
+            })
+            .collect();
+        let hole = vec![coord!(-1, -1), coord!(1, -1), coord!(1, 1), coord!(-1, 1), coord!(-1, -1)];
+        let polygon = Geometry::Polygon { coordinates: vec![star, hole] };
+        let index = IndexedFacetDistance::new(&polygon);
+        for i in 0..200 {
+            let (x, y) = ((i * 37 % 61) as f64 * 0.5 - 15.0, (i * 53 % 59) as f64 * 0.5 - 15.0);
+            let probes = [
+                Geometry::Point { coordinates: coord!(x, y) },
+                Geometry::LineString { coordinates: vec![coord!(x, y), coord!(x + 0.3, y - 0.2)] },
+                Geometry::Polygon {
+                    coordinates: vec![vec![coord!(x, y), coord!(x + 0.5, y), coord!(x, y + 0.5), coord!(x, y)]],
+                },
+            ];
+            for probe in &probes {
+                let expected = DistanceOp::new(&polygon, probe).distance();
+                assert!((index.distance(probe) - expected).abs() < 1e-12, "{:?}", probe);
+                let (a, b) = index.nearest_points(probe).unwrap();
+                assert!((point_point_2d(&a, &b) - expected).abs() < 1e-12);
+            }
+        }
+        let big = Geometry::Polygon {
+            coordinates: vec![vec![coord!(-20, -20), coord!(20, -20), coord!(20, 20), coord!(-20, 20), coord!(-20, -20)]],
+        };
+        assert_eq!(index.distance(&big), 0.0);
+        assert_eq!(index.distance(&Geometry::GeometryCollection { geometries: vec![] }), 0.0);
+    }
+
+    #[test]
+    fn test_distance_of_large_geometries_goes_through_the_index() {
+        //two waves of 400 vertices, too many pairs of segments to compare one by one
+        let wave = |dx: f64, dy: f64| Geometry::LineString {
+            coordinates: (0..400).map(|i| coord!(i as f64 + dx, (i as f64 * 0.1).sin() * 50.0 + dy)).collect(),
+        };
+        let (line, far_line) = (wave(0.0, 0.0), wave(0.5, 10.0));
+        let segments = |g: &Geometry| g.coordinates().windows(2).map(|w| (w[0].clone(), w[1].clone())).collect::<Vec<_>>();
+        let expected = segments(&line)
+            .iter()
+            .flat_map(|(p1, p2)| segments(&far_line).into_iter().map(move |(q1, q2)| segment_segment_2d(p1, p2, &q1, &q2)))
+            .fold(f64::INFINITY, f64::min);
+        let op = DistanceOp::new(&line, &far_line);
+        assert!((op.distance() - expected).abs() < 1e-12);
+        assert!(op.is_within_distance(expected) && !op.is_within_distance(expected * 0.99));
+        //the 3D distance is still measured between every pair
+        assert!(op.set_3d(true).distance() >= op.distance());
+
+        //a large polygon around a line, and a line inside of its hole
+        let ring = |r: f64| -> Vec<Coordinate> {
+            (0..=600)
+                .map(|i| {
+                    let angle = (i % 600) as f64 * std::f64::consts::TAU / 600.0;
+                    coord!(r * angle.cos(), r * angle.sin())
+                })
+                .collect()
+        };
+        let disc = Geometry::Polygon { coordinates: vec![ring(100.0), ring(50.0)] };
+        let inside = Geometry::LineString { coordinates: (0..200).map(|i| coord!(60.0 + i as f64 * 0.1, 0)).collect() };
+        let in_hole = Geometry::LineString { coordinates: (0..200).map(|i| coord!(-10.0 + i as f64 * 0.1, 0)).collect() };
+        assert_eq!(DistanceOp::new(&disc, &inside).distance(), 0.0);
+        assert_eq!(DistanceOp::new(&inside, &disc).distance(), 0.0);
+        assert!((DistanceOp::new(&disc, &in_hole).distance() - 40.0).abs() < 0.01);
+        assert_eq!(within_distance(&[inside, in_hole, line], &disc, 1.0), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_minimum_clearance_of_lines_and_points() {
+        let segment = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(3, 4)] };
+        assert_eq!(minimum_clearance(&segment), 5.0);
+        //repeated vertices do not count, and points count as vertices
+        let repeated = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 0), coord!(3, 4)] };
+        assert_eq!(minimum_clearance(&repeated), 5.0);
+        let points = Geometry::MultiPoint { coordinates: vec![coord!(0, 0), coord!(1, 0), coord!(1, 0), coord!(5, 5)] };
+        assert_eq!(minimum_clearance_line(&points), Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0)] });
+        assert_eq!(minimum_clearance_line(&Geometry::MultiPoint { coordinates: vec![] }), Geometry::LineString { coordinates: vec![] });
+    }
+}
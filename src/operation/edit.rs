@@ -0,0 +1,332 @@
+//! Rewriting of geometries component by component, keeping their structure.
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// What becomes of a component visited by a geometry editor.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Edit {
+    /// The component is kept, and its parts and coordinates are edited in turn.
+    Keep,
+    /// The component is replaced by the geometry, which is not edited any further.
+    Replace(Geometry),
+    /// The component is removed from the geometry holding it.
+    Remove,
+}
+
+/// The role of a coordinate sequence passed to an edit operation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SequenceKind {
+    /// The single coordinate of a point, or one of the coordinates of a multi point.
+    Point,
+    /// The coordinates of a line string or of a part of a multi line string.
+    Line,
+    /// A closed ring of a polygon, of a linear ring, or of a patch of a surface. The edited ring
+    /// should be closed too.
+    Ring,
+}
+
+/// The rewrites applied by a [`GeometryEditor`]. Both methods default to leaving the geometry
+/// as it is, so an operation only implements the one it needs.
+pub trait EditOperation {
+    /// Returns what becomes of a component. Components are visited from the outside in: a
+    /// collection first, then its members, and a multi geometry first, then its parts, each given
+    /// as a geometry of its own.
+    fn edit_component(&mut self, _component: &Geometry) -> Edit {
+        Edit::Keep
+    }
+
+    /// Returns the new coordinates of a sequence of a kept component.
+    fn edit_coordinates(&mut self, coordinates: &[Coordinate], _kind: SequenceKind) -> Vec<Coordinate> {
+        coordinates.to_vec()
+    }
+}
+
+/// An edit operation rewriting every coordinate sequence with a function.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::edit::{EditCoordinates, GeometryEditor};
+///
+/// //reverse every line
+/// let lines = Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 0), coord!(1, 0)], vec![coord!(5, 5), coord!(6, 6)]] };
+/// let mut reverse = EditCoordinates(|c: &[Coordinate], _| c.iter().rev().cloned().collect());
+/// let reversed = GeometryEditor::new().edit(&lines, &mut reverse).unwrap();
+/// assert_eq!(reversed.as_text(), "MULTILINESTRING ((1 0, 0 0), (6 6, 5 5))");
+/// ```
+pub struct EditCoordinates<F>(pub F);
+
+impl<F: FnMut(&[Coordinate], SequenceKind) -> Vec<Coordinate>> EditOperation for EditCoordinates<F> {
+    fn edit_coordinates(&mut self, coordinates: &[Coordinate], kind: SequenceKind) -> Vec<Coordinate> {
+        (self.0)(coordinates, kind)
+    }
+}
+
+/// Walks a geometry and rebuilds it with the components and coordinate sequences rewritten by an
+/// edit operation, keeping the nesting of the collections and the type of the geometries.
+///
+/// A point whose sequence is edited to nothing is removed, as are the parts of multi geometries
+/// replaced by nothing, and a polygon loses the holes edited to nothing and becomes empty with
+/// its shell. Lines, polygons and collections left empty are kept unless the editor is set to
+/// remove them. When the parts of a multi geometry are replaced by geometries of other types,
+/// it becomes a geometry collection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GeometryEditor {
+    remove_empty: bool,
+}
+
+impl GeometryEditor {
+    /// Creates an editor keeping the components left empty.
+    pub fn new() -> Self {
+        Self { remove_empty: false }
+    }
+
+    /// Returns an editor that removes the components left empty by the edit if `remove_empty` is
+    /// true, or keeps them otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::edit::{EditCoordinates, GeometryEditor};
+    ///
+    /// //drop the vertices left of the y axis
+    /// let lines = Geometry::MultiLineString { coordinates: vec![vec![coord!(-2, 0), coord!(-1, 0)], vec![coord!(-1, 1), coord!(3, 1)]] };
+    /// let mut clip = EditCoordinates(|c: &[Coordinate], _| c.iter().filter(|c| c.x() >= 0.0).cloned().collect());
+    /// let edited = GeometryEditor::new().remove_empty(true).edit(&lines, &mut clip).unwrap();
+    /// assert_eq!(edited.as_text(), "MULTILINESTRING ((3 1))");
+    /// ```
+    pub fn remove_empty(&self, remove_empty: bool) -> Self {
+        Self { remove_empty }
+    }
+
+    /// Returns the geometry rebuilt with the edit operation, or None if it was removed, or left
+    /// empty with the editor removing empty components.
+    ///
+    /// # Arguments
+    ///
+    /// * `geometry` - The geometry to edit.
+    /// * `operation` - The rewrites of the components and coordinate sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::operation::edit::{Edit, EditOperation, GeometryEditor};
+    ///
+    /// //replaces polygons by their centroid and removes lines
+    /// struct Collapse;
+    /// impl EditOperation for Collapse {
+    ///     fn edit_component(&mut self, component: &Geometry) -> Edit {
+    ///         match component {
+    ///             Geometry::Polygon { .. } => Edit::Replace(component.centroid().unwrap()),
+    ///             Geometry::LineString { .. } => Edit::Remove,
+    ///             _ => Edit::Keep,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let collection = Geometry::GeometryCollection { geometries: vec![
+    ///     Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] },
+    ///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0)] },
+    /// ] };
+    /// let edited = GeometryEditor::new().edit(&collection, &mut Collapse).unwrap();
+    /// assert_eq!(edited.as_text(), "GEOMETRYCOLLECTION (POINT (1 1))");
+    /// ```
+    pub fn edit<O: EditOperation>(&self, geometry: &Geometry, operation: &mut O) -> Option<Geometry> {
+        let edited = match operation.edit_component(geometry) {
+            Edit::Keep => self.edit_parts(geometry, operation)?,
+            Edit::Replace(replacement) => replacement,
+            Edit::Remove => return None,
+        };
+        if self.remove_empty && is_empty(&edited) {
+            return None;
+        }
+        Some(edited)
+    }
+
+    /// Returns the kept component with its parts and coordinates edited.
+    fn edit_parts<O: EditOperation>(&self, geometry: &Geometry, operation: &mut O) -> Option<Geometry> {
+        Some(match geometry {
+            Geometry::Point { coordinates } => {
+                let edited = operation.edit_coordinates(std::slice::from_ref(coordinates), SequenceKind::Point);
+                Geometry::Point { coordinates: edited.into_iter().next()? }
+            }
+            Geometry::LineString { coordinates } => {
+                Geometry::LineString { coordinates: operation.edit_coordinates(coordinates, SequenceKind::Line) }
+            }
+            Geometry::LinearRing { coordinates } => {
+                Geometry::LinearRing { coordinates: operation.edit_coordinates(coordinates, SequenceKind::Ring) }
+            }
+            Geometry::Polygon { coordinates } => Geometry::Polygon { coordinates: edit_rings(coordinates, operation) },
+            Geometry::MultiPoint { coordinates } => {
+                let parts = coordinates.iter().map(|c| Geometry::Point { coordinates: c.clone() }).collect();
+                self.rebuild(parts, operation, |part| match part {
+                    Geometry::Point { coordinates } => Some(coordinates),
+                    _ => None,
+                }, |coordinates| Geometry::MultiPoint { coordinates })
+            }
+            Geometry::MultiLineString { coordinates } => {
+                let parts = coordinates.iter().map(|c| Geometry::LineString { coordinates: c.clone() }).collect();
+                self.rebuild(parts, operation, |part| match part {
+                    Geometry::LineString { coordinates } => Some(coordinates),
+                    _ => None,
+                }, |coordinates| Geometry::MultiLineString { coordinates })
+            }
+            Geometry::MultiPolygon { coordinates } => {
+                let parts = coordinates.iter().map(|c| Geometry::Polygon { coordinates: c.clone() }).collect();
+                self.rebuild(parts, operation, |part| match part {
+                    Geometry::Polygon { coordinates } => Some(coordinates),
+                    _ => None,
+                }, |coordinates| Geometry::MultiPolygon { coordinates })
+            }
+            Geometry::GeometryCollection { geometries } => Geometry::GeometryCollection {
+                geometries: geometries.iter().filter_map(|g| self.edit(g, operation)).collect(),
+            },
+            Geometry::PolyhedralSurface { coordinates } => Geometry::PolyhedralSurface {
+                coordinates: coordinates.iter().map(|patch| edit_rings(patch, operation)).filter(|patch| !patch.is_empty()).collect(),
+            },
+            Geometry::Tin { coordinates } => Geometry::Tin {
+                coordinates: coordinates
+                    .iter()
+                    .map(|triangle| operation.edit_coordinates(triangle, SequenceKind::Ring))
+                    .filter(|triangle| !triangle.is_empty())
+                    .collect(),
+            },
+        })
+    }
+
+    /// Returns the multi geometry made of the edited parts, or a collection if some of them
+    /// changed type.
+    fn rebuild<O, T, P, M>(&self, parts: Vec<Geometry>, operation: &mut O, unwrap: P, multi: M) -> Geometry
+    where
+        O: EditOperation,
+        P: Fn(Geometry) -> Option<T>,
+        M: Fn(Vec<T>) -> Geometry,
+    {
+        let kind = parts.first().map(std::mem::discriminant);
+        let edited: Vec<Geometry> = parts.iter().filter_map(|part| self.edit(part, operation)).collect();
+        if edited.iter().all(|part| Some(std::mem::discriminant(part)) == kind) {
+            multi(edited.into_iter().filter_map(unwrap).collect())
+        } else {
+            Geometry::GeometryCollection { geometries: edited }
+        }
+    }
+}
+
+/// Returns the edited rings of a polygon, without the holes edited to nothing, or no ring if the
+/// shell was.
+fn edit_rings<O: EditOperation>(rings: &[Vec<Coordinate>], operation: &mut O) -> Vec<Vec<Coordinate>> {
+    let mut edited: Vec<Vec<Coordinate>> = Vec::with_capacity(rings.len());
+    for (k, ring) in rings.iter().enumerate() {
+        let ring = operation.edit_coordinates(ring, SequenceKind::Ring);
+        if ring.is_empty() {
+            if k == 0 {
+                return vec![];
+            }
+            continue;
+        }
+        edited.push(ring);
+    }
+    edited
+}
+
+/// Returns an empty geometry of the type of the geometry, or an empty collection for points,
+/// standing for a geometry an editor removing empty components left nothing of.
+pub(crate) fn empty_like(geometry: &Geometry) -> Geometry {
+    match geometry {
+        Geometry::LineString { .. } => Geometry::LineString { coordinates: vec![] },
+        Geometry::LinearRing { .. } => Geometry::LinearRing { coordinates: vec![] },
+        Geometry::Polygon { .. } => Geometry::Polygon { coordinates: vec![] },
+        Geometry::MultiPoint { .. } => Geometry::MultiPoint { coordinates: vec![] },
+        Geometry::MultiLineString { .. } => Geometry::MultiLineString { coordinates: vec![] },
+        Geometry::MultiPolygon { .. } => Geometry::MultiPolygon { coordinates: vec![] },
+        Geometry::PolyhedralSurface { .. } => Geometry::PolyhedralSurface { coordinates: vec![] },
+        Geometry::Tin { .. } => Geometry::Tin { coordinates: vec![] },
+        Geometry::Point { .. } | Geometry::GeometryCollection { .. } => Geometry::GeometryCollection { geometries: vec![] },
+    }
+}
+
+/// Returns true if the geometry has no coordinates.
+fn is_empty(geometry: &Geometry) -> bool {
+    match geometry {
+        Geometry::Point { .. } => false,
+        Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } | Geometry::MultiPoint { coordinates } => {
+            coordinates.is_empty()
+        }
+        Geometry::Polygon { coordinates } | Geometry::MultiLineString { coordinates } | Geometry::Tin { coordinates } => {
+            coordinates.is_empty()
+        }
+        Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => coordinates.is_empty(),
+        Geometry::GeometryCollection { geometries } => geometries.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_nesting_and_changed_types() {
+        //polygons with a small hole lose it, and points far away become lines
+        struct Simplify;
+        impl EditOperation for Simplify {
+            fn edit_component(&mut self, component: &Geometry) -> Edit {
+                match component {
+                    Geometry::Point { coordinates } if coordinates.x() > 5.0 => {
+                        Edit::Replace(Geometry::LineString { coordinates: vec![coordinates.clone(), coord!(0, 0)] })
+                    }
+                    _ => Edit::Keep,
+                }
+            }
+
+            fn edit_coordinates(&mut self, coordinates: &[Coordinate], kind: SequenceKind) -> Vec<Coordinate> {
+                let small = kind == SequenceKind::Ring && crate::algorithm::area::ring_area(coordinates) < 1.0;
+                if small {
+                    vec![]
+                } else {
+                    coordinates.to_vec()
+                }
+            }
+        }
+
+        let square = vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)];
+        let hole = vec![coord!(1, 1), coord!(1, 1.5), coord!(1.5, 1.5), coord!(1.5, 1), coord!(1, 1)];
+        let geometry = Geometry::GeometryCollection { geometries: vec![
+            Geometry::GeometryCollection { geometries: vec![
+                Geometry::Polygon { coordinates: vec![square.clone(), hole.clone()] },
+                Geometry::Polygon { coordinates: vec![hole.clone()] },
+            ] },
+            Geometry::MultiPoint { coordinates: vec![coord!(1, 1), coord!(2, 2)] },
+            Geometry::MultiPoint { coordinates: vec![coord!(1, 1), coord!(9, 9)] },
+        ] };
+        let edited = GeometryEditor::new().edit(&geometry, &mut Simplify).unwrap();
+        assert_eq!(edited, Geometry::GeometryCollection { geometries: vec![
+            Geometry::GeometryCollection { geometries: vec![
+                Geometry::Polygon { coordinates: vec![square.clone()] },
+                Geometry::Polygon { coordinates: vec![] },
+            ] },
+            Geometry::MultiPoint { coordinates: vec![coord!(1, 1), coord!(2, 2)] },
+            Geometry::GeometryCollection { geometries: vec![
+                Geometry::Point { coordinates: coord!(1, 1) },
+                Geometry::LineString { coordinates: vec![coord!(9, 9), coord!(0, 0)] },
+            ] },
+        ] });
+
+        //the empty polygon goes, and the collection holding only it too
+        let edited = GeometryEditor::new().remove_empty(true).edit(&geometry, &mut Simplify).unwrap();
+        let Geometry::GeometryCollection { geometries } = &edited else { panic!() };
+        assert_eq!(geometries[0], Geometry::GeometryCollection { geometries: vec![Geometry::Polygon { coordinates: vec![square] }] });
+        let only_hole = Geometry::GeometryCollection { geometries: vec![Geometry::Polygon { coordinates: vec![hole] }] };
+        assert_eq!(GeometryEditor::new().remove_empty(true).edit(&only_hole, &mut Simplify), None);
+    }
+}
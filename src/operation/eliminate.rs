@@ -0,0 +1,157 @@
+//! Elimination of small polygons into their neighbours, as done with the slivers left between
+//! the polygons of two layers after an overlay.
+
+use crate::algorithm::area::polygon_area;
+use crate::algorithm::line_intersection::{intersection, sweep_segment_pairs, SegmentIntersection, ZInterpolation};
+use crate::cluster::DisjointSet;
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+use crate::operation::overlay::{overlay, OverlayOp};
+
+/// The neighbour a small polygon is merged into by [`eliminate`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EliminateStrategy {
+    /// The neighbour with the largest area.
+    LargestArea,
+    /// The neighbour sharing the longest border with the polygon.
+    LongestBorder,
+}
+
+/// Returns the polygons of a layer with those smaller than the minimum area merged into a
+/// neighbour sharing part of their boundary, in the order of the layer. The merged polygons are
+/// None, and the neighbours they were merged into are replaced by their union. The polygons are
+/// eliminated from the smallest, and a polygon grown past the minimum area by earlier merges is
+/// kept. Small polygons without neighbours, or touching their neighbours only at points, are kept
+/// too. Returns an error if a union cannot be computed consistently.
+///
+/// # Arguments
+///
+/// * `polygons` - The polygons of the layer, expected not to overlap.
+/// * `min_area` - The area under which polygons are eliminated.
+/// * `strategy` - How the neighbour to merge a polygon into is chosen.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::eliminate::{eliminate, EliminateStrategy};
+///
+/// let rectangle = |x0: f64, x1: f64, y1: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x0, 0), coord!(x1, 0), coord!(x1, y1), coord!(x0, y1), coord!(x0, 0)
+/// ]] };
+/// //a sliver between a large field, sharing a short border with it, and a small one
+/// let layer = vec![rectangle(-90.0, 10.0, 1.0), rectangle(10.0, 10.1, 2.0), rectangle(10.1, 12.0, 2.0)];
+///
+/// let merged = eliminate(&layer, 1.0, EliminateStrategy::LargestArea).unwrap();
+/// assert!((merged[0].as_ref().unwrap().area() - 100.2).abs() < 1e-12);
+/// assert_eq!(merged[1], None);
+///
+/// let merged = eliminate(&layer, 1.0, EliminateStrategy::LongestBorder).unwrap();
+/// assert_eq!(merged[0].as_ref().unwrap().area(), 100.0);
+/// assert!((merged[2].as_ref().unwrap().area() - 4.0).abs() < 1e-12);
+/// ```
+pub fn eliminate(polygons: &[Geometry], min_area: f64, strategy: EliminateStrategy) -> Result<Vec<Option<Geometry>>, Error> {
+    let mut current: Vec<Option<Geometry>> = polygons.iter().map(|p| Some(p.clone())).collect();
+    let mut areas: Vec<f64> = polygons.iter().map(area).collect();
+    //the polygon every polygon was merged into, itself if it was not
+    let mut targets = DisjointSet::new(polygons.len());
+    let tree = STRtree::new(polygons.iter().enumerate().map(|(i, p)| (p.envelope(), i)).collect());
+
+    let mut order: Vec<usize> = (0..polygons.len()).filter(|&i| areas[i] < min_area).collect();
+    order.sort_by(|&a, &b| areas[a].total_cmp(&areas[b]));
+    for small in order {
+        if areas[small] >= min_area {
+            continue;
+        }
+        let Some(geometry) = current[small].take() else { continue };
+        let mut neighbours: Vec<usize> = tree
+            .query(&geometry.envelope())
+            .into_iter()
+            .map(|&i| targets.find(i))
+            .filter(|&i| i != small)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        let best = neighbours
+            .into_iter()
+            .filter_map(|i| {
+                let border = shared_border_length(&geometry, current[i].as_ref()?);
+                let score = match strategy {
+                    EliminateStrategy::LargestArea => areas[i],
+                    EliminateStrategy::LongestBorder => border,
+                };
+                (border > 0.0).then_some((i, score))
+            })
+            .reduce(|best, candidate| if candidate.1 > best.1 { candidate } else { best });
+        match best {
+            Some((target, _)) => {
+                let merged = overlay(current[target].as_ref().unwrap_or(&geometry), &geometry, OverlayOp::Union)?;
+                areas[target] += areas[small];
+                current[target] = Some(merged);
+                targets.union_into(small, target);
+            }
+            None => current[small] = Some(geometry),
+        }
+    }
+    Ok(current)
+}
+
+/// Returns the area of the polygons of the geometry.
+fn area(geometry: &Geometry) -> f64 {
+    geometry.polygons().map(|p| polygon_area(p)).sum()
+}
+
+/// Returns the length of the boundary shared by the polygons of two geometries, where segments
+/// of their rings overlap.
+fn shared_border_length(a: &Geometry, b: &Geometry) -> f64 {
+    let rings = |g: &Geometry, source: usize| -> Vec<(Coordinate, Coordinate, usize)> {
+        g.polygons()
+            .flatten()
+            .flat_map(|ring| ring.windows(2).map(|w| (w[0].clone(), w[1].clone(), source)).collect::<Vec<_>>())
+            .collect()
+    };
+    let segments: Vec<(Coordinate, Coordinate, usize)> = rings(a, 0).into_iter().chain(rings(b, 1)).collect();
+    let ends: Vec<(&Coordinate, &Coordinate)> = segments.iter().map(|(p, q, _)| (p, q)).collect();
+    let mut length = 0.0;
+    sweep_segment_pairs(&ends, |i, j| {
+        let ((p1, p2, s), (q1, q2, t)) = (&segments[i], &segments[j]);
+        if s != t {
+            if let SegmentIntersection::Collinear(c, d) = intersection(p1, p2, q1, q2, ZInterpolation::Zero) {
+                length += c.distance_2d(&d);
+            }
+        }
+        true
+    });
+    length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_chain_of_small_polygons() {
+        let square = |x: f64, size: f64| Geometry::Polygon { coordinates: vec![vec![
+            coord!(x, 0), coord!(x + size, 0), coord!(x + size, size), coord!(x, size), coord!(x, 0)
+        ]] };
+        //two small squares in a row next to a large one, and a small one touching it at a corner
+        let layer = vec![
+            square(0.0, 4.0),
+            square(4.0, 0.5),
+            square(4.5, 0.5),
+            Geometry::Polygon { coordinates: vec![vec![coord!(-1, -1), coord!(0, -1), coord!(0, 0), coord!(-1, 0), coord!(-1, -1)]] },
+        ];
+        let merged = eliminate(&layer, 2.0, EliminateStrategy::LongestBorder).unwrap();
+        //the second square is merged into the first, then the third into their union
+        assert_eq!(merged[1], None);
+        assert_eq!(merged[2], None);
+        assert_eq!(merged[0].as_ref().unwrap().area(), 16.5);
+        assert_eq!(merged[3], Some(layer[3].clone()));
+        assert_eq!(eliminate(&[], 1.0, EliminateStrategy::LargestArea).unwrap(), vec![]);
+    }
+}
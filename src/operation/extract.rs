@@ -0,0 +1,94 @@
+//! Extraction of the components of one dimension from geometries of any type, such as the mixed
+//! collections returned by overlays.
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the coordinates of the points and multi points of the geometry, searching nested
+/// collections.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::extract;
+///
+/// let collection = Geometry::GeometryCollection { geometries: vec![
+///     Geometry::Point { coordinates: coord!(0, 0) },
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] },
+///     Geometry::GeometryCollection { geometries: vec![Geometry::MultiPoint { coordinates: vec![coord!(2, 2), coord!(3, 3)] }] },
+/// ] };
+/// assert_eq!(extract::points(&collection), vec![coord!(0, 0), coord!(2, 2), coord!(3, 3)]);
+/// ```
+pub fn points(geometry: &Geometry) -> Vec<Coordinate> {
+    let mut points = Vec::new();
+    visit(geometry, &mut |g| match g {
+        Geometry::Point { coordinates } => points.push(coordinates.clone()),
+        Geometry::MultiPoint { coordinates } => points.extend(coordinates.iter().cloned()),
+        _ => {}
+    });
+    points
+}
+
+/// Returns the coordinates of the line strings, linear rings and parts of multi line strings of
+/// the geometry, searching nested collections. The rings of polygons are not included.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::extract;
+///
+/// let collection = Geometry::GeometryCollection { geometries: vec![
+///     Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 0), coord!(1, 0)], vec![coord!(2, 0), coord!(3, 0)]] },
+///     Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 0)]] },
+/// ] };
+/// assert_eq!(extract::lines(&collection).len(), 2);
+/// ```
+pub fn lines(geometry: &Geometry) -> Vec<Vec<Coordinate>> {
+    let mut lines = Vec::new();
+    visit(geometry, &mut |g| match g {
+        Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => lines.push(coordinates.clone()),
+        Geometry::MultiLineString { coordinates } => lines.extend(coordinates.iter().cloned()),
+        _ => {}
+    });
+    lines
+}
+
+/// Returns the rings of the polygons and parts of multi polygons of the geometry, searching
+/// nested collections. The patches of polyhedral surfaces and TINs are not included.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::extract;
+///
+/// let square = vec![vec![coord!(0, 0), coord!(1, 0), coord!(1, 1), coord!(0, 1), coord!(0, 0)]];
+/// let collection = Geometry::GeometryCollection { geometries: vec![
+///     Geometry::Point { coordinates: coord!(5, 5) },
+///     Geometry::MultiPolygon { coordinates: vec![square.clone(), square.clone()] },
+/// ] };
+/// assert_eq!(extract::polygons(&collection), vec![square.clone(), square]);
+/// ```
+pub fn polygons(geometry: &Geometry) -> Vec<Vec<Vec<Coordinate>>> {
+    geometry.polygons().cloned().collect()
+}
+
+/// Calls `f` with the geometry, or with every geometry of nested collections.
+pub(crate) fn visit<F: FnMut(&Geometry)>(geometry: &Geometry, f: &mut F) {
+    match geometry {
+        Geometry::GeometryCollection { geometries } => {
+            for g in geometries {
+                visit(g, f);
+            }
+        }
+        _ => f(geometry),
+    }
+}
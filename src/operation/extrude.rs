@@ -0,0 +1,121 @@
+//! Extrusion of polygons into prisms, for simple 2.5D models of buildings from their footprints
+//! and heights.
+
+use crate::algorithm::area::ring_signed_area;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the polygons of a geometry extruded by a height into a closed polyhedral surface, or
+/// None if the geometry has no polygons. Every vertex is raised from its own z, so footprints on
+/// sloping ground keep their base. A negative height extrudes downwards, as for basements.
+///
+/// Every polygon gives its base, its top, and a wall for every edge of its rings, holes included,
+/// in that order. All the patches are oriented so their normals point out of the prism: the
+/// shells are made counter clockwise and the holes clockwise, and the base is reversed. Repeated
+/// consecutive vertices give no wall.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons to extrude.
+/// * `height` - The distance to extrude along z.
+///
+/// # Panics
+///
+/// Panics if the height is zero or not finite.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::extrude::extrude;
+///
+/// let footprint = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 3), coord!(0, 3), coord!(0, 0)]] };
+/// let building = extrude(&footprint, 10.0).unwrap();
+/// assert_eq!(building.num_components(), 6);
+/// assert_eq!(building.area_3d(), 2.0 * 12.0 + 14.0 * 10.0);
+/// //the prism is closed
+/// assert_eq!(building.boundary(), Geometry::MultiLineString { coordinates: vec![] });
+/// ```
+pub fn extrude(geometry: &Geometry, height: f64) -> Option<Geometry> {
+    if !height.is_finite() || height == 0.0 {
+        panic!("The height of the extrusion must be finite and not zero");
+    }
+    let mut patches = Vec::new();
+    for polygon in geometry.polygons() {
+        patches.extend(prism(polygon, height));
+    }
+    match patches.is_empty() {
+        true => None,
+        false => Some(Geometry::PolyhedralSurface { coordinates: patches }),
+    }
+}
+
+/// Returns the base, the top and the walls of the prism of a polygon.
+fn prism(rings: &[Vec<Coordinate>], height: f64) -> Vec<Vec<Vec<Coordinate>>> {
+    //the shell counter clockwise and the holes clockwise, without repeated vertices
+    let rings: Vec<Vec<Coordinate>> = rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            let mut ring = ring.clone();
+            ring.dedup_by(|a, b| a.equals_2d(b));
+            if (ring_signed_area(&ring) > 0.0) != (i == 0) {
+                ring.reverse();
+            }
+            ring
+        })
+        .filter(|ring| ring.len() >= 4)
+        .collect();
+    if rings.is_empty() {
+        return Vec::new();
+    }
+    let (low, high) = if height > 0.0 { (0.0, height) } else { (height, 0.0) };
+    let lift = |c: &Coordinate, dz: f64| Coordinate::new(c.x(), c.y(), c.z() + dz);
+    let base = rings.iter().map(|ring| ring.iter().rev().map(|c| lift(c, low)).collect()).collect();
+    let top = rings.iter().map(|ring| ring.iter().map(|c| lift(c, high)).collect()).collect();
+    let mut patches = vec![base, top];
+    for w in rings.iter().flat_map(|ring| ring.windows(2)) {
+        let (a, b) = (&w[0], &w[1]);
+        patches.push(vec![vec![lift(a, low), lift(b, low), lift(b, high), lift(a, high), lift(a, low)]]);
+    }
+    patches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::area::ring_normal;
+    use crate::coord;
+
+    #[test]
+    fn test_prisms_are_closed_and_face_outwards() {
+        //a clockwise frame with a counter clockwise hole and a repeated vertex, on sloping ground
+        let frame = Geometry::Polygon { coordinates: vec![
+            vec![coord!(0, 0, 0), coord!(0, 10, 5), coord!(10, 10, 5), coord!(10, 0, 0), coord!(10, 0, 0), coord!(0, 0, 0)],
+            vec![coord!(4, 4, 2), coord!(6, 4, 2), coord!(6, 6, 3), coord!(4, 6, 3), coord!(4, 4, 2)],
+        ] };
+        for height in [3.0, -3.0] {
+            let prism = extrude(&frame, height).unwrap();
+            assert_eq!(prism.num_components(), 2 + 4 + 4);
+            assert_eq!(prism.boundary(), Geometry::MultiLineString { coordinates: vec![] });
+            //the walls are as high as the extrusion, and all the patches point away from the center
+            let center = [5.0, 5.0, 2.5 + height / 2.0];
+            let patches = match &prism {
+                Geometry::PolyhedralSurface { coordinates } => coordinates.clone(),
+                _ => unreachable!(),
+            };
+            for patch in &patches {
+                let normal = ring_normal(&patch[0]);
+                let c = &patch[0][0];
+                let hole = patch[0].iter().all(|p| (4.0..=6.0).contains(&p.x()) && (4.0..=6.0).contains(&p.y()));
+                let outwards = normal[0] * (c.x() - center[0]) + normal[1] * (c.y() - center[1]) + normal[2] * (c.z() - center[2]);
+                assert!(if hole { outwards < 0.0 } else { outwards > 0.0 }, "{:?}", patch);
+            }
+            let walls = Geometry::PolyhedralSurface { coordinates: patches[2..].to_vec() };
+            assert!((walls.area_3d() - 3.0 * (40.0 + 8.0)).abs() < 1e-9);
+        }
+        assert_eq!(extrude(&Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 1)] }, 1.0), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Shares of the cells of a regular grid covered by polygons, for moving statistics gathered
+//! over areas, such as census tracts, onto a grid.
+
+use crate::algorithm::area::ring_area;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+
+/// A cell of a grid covered by a geometry, with the fraction of its area that is covered.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CellShare {
+    /// The index of the cell along x, from the cell whose left side is on the origin.
+    pub column: i64,
+    /// The index of the cell along y, from the cell whose bottom side is on the origin.
+    pub row: i64,
+    /// The area covered by the cell.
+    pub envelope: Envelope,
+    /// The fraction of the area of the cell covered by the geometry, from 0 to 1.
+    pub share: f64,
+}
+
+/// Returns the cells of a grid of square cells anchored at the origin that the polygons of a
+/// geometry cover, each with the fraction of its area covered, ordered by row and then by column.
+///
+/// Every ring is clipped to the columns of the grid and then to the cells of every column with
+/// the algorithm of Sutherland and Hodgman, and the areas of the holes in a cell are taken out of
+/// the area of the shells in it. The polygons are expected to be valid and not to overlap, or
+/// their shares add up. Cells only touched by the polygons are left out.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons to grid.
+/// * `cell_size` - The size of the cells.
+///
+/// # Panics
+///
+/// Panics if the size of the cells is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::fishnet::grid_shares;
+///
+/// //a tract covering a cell and half of the one on its right
+/// let tract = Geometry::Polygon { coordinates: vec![vec![coord!(10, 0), coord!(25, 0), coord!(25, 10), coord!(10, 10), coord!(10, 0)]] };
+/// let shares: Vec<(i64, i64, f64)> = grid_shares(&tract, 10.0).iter().map(|c| (c.column, c.row, c.share)).collect();
+/// assert_eq!(shares, [(1, 0, 1.0), (2, 0, 0.5)]);
+/// ```
+pub fn grid_shares(geometry: &Geometry, cell_size: f64) -> Vec<CellShare> {
+    if cell_size.is_nan() || cell_size <= 0.0 {
+        panic!("The size of the cells must be positive");
+    }
+    let cell_area = cell_size * cell_size;
+    let mut areas: Vec<((i64, i64), f64)> = Vec::new();
+    for polygon in geometry.polygons() {
+        for (i, ring) in polygon.iter().enumerate() {
+            let sign = if i == 0 { 1.0 } else { -1.0 };
+            let envelope = Envelope::of_slice(ring);
+            if envelope.is_empty() {
+                continue;
+            }
+            let cells = |min: f64, max: f64| (min / cell_size).floor() as i64..=(max / cell_size).floor() as i64;
+            for column in cells(envelope.min_x(), envelope.max_x()) {
+                let (left, right) = (column as f64 * cell_size, (column + 1) as f64 * cell_size);
+                let strip = clip_to_range(ring, 0, left, right);
+                if strip.len() < 3 {
+                    continue;
+                }
+                for row in cells(envelope.min_y(), envelope.max_y()) {
+                    let (bottom, top) = (row as f64 * cell_size, (row + 1) as f64 * cell_size);
+                    let piece = clip_to_range(&strip, 1, bottom, top);
+                    if piece.len() >= 3 {
+                        areas.push(((row, column), sign * ring_area(&piece)));
+                    }
+                }
+            }
+        }
+    }
+    areas.sort_by_key(|(cell, _)| *cell);
+    let mut shares: Vec<CellShare> = Vec::new();
+    for ((row, column), area) in areas {
+        match shares.last_mut() {
+            Some(last) if (last.row, last.column) == (row, column) => last.share += area / cell_area,
+            _ => {
+                let (x, y) = (column as f64 * cell_size, row as f64 * cell_size);
+                let envelope = Envelope::new(x, y, x + cell_size, y + cell_size);
+                shares.push(CellShare { column, row, envelope, share: area / cell_area });
+            }
+        }
+    }
+    //rounding leaves slivers of area in the cells only touched by the rings
+    shares.retain(|cell| cell.share > 1e-12);
+    for cell in &mut shares {
+        cell.share = cell.share.min(1.0);
+    }
+    shares
+}
+
+/// Returns the ring clipped to a range of x or y, closed, as a polygon of the same orientation.
+fn clip_to_range(ring: &[Coordinate], axis: usize, min: f64, max: f64) -> Vec<Coordinate> {
+    let value = |c: &Coordinate| if axis == 0 { c.x() } else { c.y() };
+    let mut points: Vec<Coordinate> = ring.to_vec();
+    for (bound, inside) in [(min, 1.0), (max, -1.0)] {
+        if points.is_empty() {
+            break;
+        }
+        //the side of the bound a point is on, positive inside
+        let side = |c: &Coordinate| (value(c) - bound) * inside;
+        let mut clipped = Vec::with_capacity(points.len() + 2);
+        for (k, current) in points.iter().enumerate() {
+            let previous = &points[(k + points.len() - 1) % points.len()];
+            let (s, t) = (side(previous), side(current));
+            if (s >= 0.0) != (t >= 0.0) {
+                let f = s / (s - t);
+                clipped.push(Coordinate::new(
+                    previous.x() + f * (current.x() - previous.x()),
+                    previous.y() + f * (current.y() - previous.y()),
+                    0.0,
+                ));
+            }
+            if t >= 0.0 {
+                clipped.push(current.clone());
+            }
+        }
+        points = clipped;
+    }
+    if let Some(first) = points.first().cloned() {
+        points.push(first);
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::shapes;
+
+    #[test]
+    fn test_shares_add_up_to_the_area() {
+        //a disc with a square hole, across cells on both sides of the origin
+        let disc = shapes::circle(&coord!(0.3, -0.2), 7.5, 300);
+        let mut rings = disc.polygons().next().unwrap().clone();
+        rings.push(vec![coord!(-2, -2), coord!(-2, 2), coord!(2, 2), coord!(2, -2), coord!(-2, -2)]);
+        let polygon = Geometry::Polygon { coordinates: rings };
+        let shares = grid_shares(&polygon, 2.0);
+        let total: f64 = shares.iter().map(|cell| cell.share * 4.0).sum();
+        assert!((total - polygon.area()).abs() < 1e-9, "{} {}", total, polygon.area());
+        assert!(shares.iter().all(|cell| cell.share > 0.0 && cell.share <= 1.0));
+        assert!(shares.windows(2).all(|w| (w[0].row, w[0].column) < (w[1].row, w[1].column)));
+        //the cells of the hole are not covered, and those around it are full
+        assert!(!shares.iter().any(|cell| (-1..=0).contains(&cell.column) && (-1..=0).contains(&cell.row)));
+        let full = shares.iter().find(|cell| (cell.column, cell.row) == (1, 1)).unwrap();
+        assert!((full.share - 1.0).abs() < 1e-12);
+        assert_eq!(full.envelope, Envelope::new(2.0, 2.0, 4.0, 4.0));
+
+        //a triangle across four cells, and a line which covers nothing
+        let triangle = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(0, 2), coord!(0, 0)]] };
+        let shares: Vec<(i64, i64, f64)> = grid_shares(&triangle, 1.0).iter().map(|c| (c.column, c.row, c.share)).collect();
+        assert_eq!(shares, [(0, 0, 1.0), (1, 0, 0.5), (0, 1, 0.5)]);
+        assert!(grid_shares(&Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(5, 5)] }, 1.0).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+//! Merging of lines that meet end to end into the longest possible lines.
+
+use std::collections::HashMap;
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::extract;
+
+/// Returns the lines of the geometry joined where exactly two of their ends meet, as a multi
+/// line string. Lines are broken where one, three or more ends meet, lines forming a loop are
+/// merged into a closed line, and lines are reversed where needed to follow the one they are
+/// joined to. The lines of polygons are not included, and lines with fewer than two coordinates
+/// are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::line_merge::line_merge;
+///
+/// let lines = Geometry::MultiLineString { coordinates: vec![
+///     vec![coord!(0, 0), coord!(1, 0)],
+///     vec![coord!(2, 0), coord!(1, 0)],
+///     vec![coord!(2, 0), coord!(3, 0), coord!(3, 1)],
+///     //a fork at (3, 1)
+///     vec![coord!(3, 1), coord!(4, 2)],
+///     vec![coord!(3, 1), coord!(2, 2)],
+/// ] };
+/// assert_eq!(
+///     line_merge(&lines).as_text(),
+///     "MULTILINESTRING ((0 0, 1 0, 2 0, 3 0, 3 1), (3 1, 4 2), (3 1, 2 2))"
+/// );
+/// ```
+pub fn line_merge(geometry: &Geometry) -> Geometry {
+    let lines: Vec<Vec<Coordinate>> = extract::lines(geometry).into_iter().filter(|line| line.len() > 1).collect();
+    let mut incident: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        incident.entry(line[0].key()).or_default().push(i);
+        incident.entry(line[line.len() - 1].key()).or_default().push(i);
+    }
+    let mut used = vec![false; lines.len()];
+    let walk = |from: &Coordinate, first: usize, used: &mut Vec<bool>| {
+        let mut merged = vec![from.clone()];
+        let mut line = first;
+        loop {
+            used[line] = true;
+            let forward = lines[line][0].key() == merged.last().unwrap().key();
+            if forward {
+                merged.extend(lines[line][1..].iter().cloned());
+            } else {
+                merged.extend(lines[line].iter().rev().skip(1).cloned());
+            }
+            let next = &incident[&merged.last().unwrap().key()];
+            if next.len() != 2 {
+                break;
+            }
+            line = if next[0] == line { next[1] } else { next[0] };
+            if used[line] {
+                break;
+            }
+        }
+        merged
+    };
+    let mut merged = Vec::new();
+    //merged lines run between nodes where other than two ends meet
+    for i in 0..lines.len() {
+        for end in [&lines[i][0], &lines[i][lines[i].len() - 1]] {
+            if !used[i] && incident[&end.key()].len() != 2 {
+                merged.push(walk(end, i, &mut used));
+            }
+        }
+    }
+    //what is left are loops
+    for i in 0..lines.len() {
+        if !used[i] {
+            merged.push(walk(&lines[i][0], i, &mut used));
+        }
+    }
+    Geometry::MultiLineString { coordinates: merged }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_loops_and_reversals() {
+        //a square split into four reversed or forward sides, and a separate closed line
+        let lines = Geometry::GeometryCollection { geometries: vec![
+            Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0)] },
+            Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(1, 0)] },
+            Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(0, 1)] },
+            Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 1)] },
+            Geometry::LinearRing { coordinates: vec![coord!(5, 5), coord!(6, 5), coord!(6, 6), coord!(5, 5)] },
+            Geometry::LineString { coordinates: vec![coord!(9, 9)] },
+        ] };
+        let merged = line_merge(&lines);
+        assert_eq!(
+            merged.as_text(),
+            "MULTILINESTRING ((0 0, 1 0, 1 1, 0 1, 0 0), (5 5, 6 5, 6 6, 5 5))"
+        );
+        assert_eq!(merged.length(), 4.0 + 2.0 + 2f64.sqrt());
+    }
+}
@@ -0,0 +1,453 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+
+/// The position on the network a point of a trace was matched to.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MatchedPoint {
+    /// The index of the network line the point was matched to.
+    pub line: usize,
+    /// The distance along the line from its start to the matched position.
+    pub offset: f64,
+    /// The matched position.
+    pub coordinate: Coordinate,
+    /// The distance from the point of the trace to the matched position.
+    pub distance: f64,
+}
+
+/// The result of matching a trace to a network.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapMatch {
+    /// The route followed along the network, a line string, or a multi line string when parts of
+    /// the trace could not be connected through the network.
+    pub path: Geometry,
+    /// The matched position of every point of the trace, or None for the points without any line
+    /// within the search radius.
+    pub points: Vec<Option<MatchedPoint>>,
+}
+
+/// Returns the most likely route of a trace, such as a GPS track, along a network of lines, or
+/// None if the trace is not a line string.
+///
+/// Every point of the trace can be matched to the closest position of every line within the
+/// search radius. The matches are chosen with a hidden Markov model: matches far from their point
+/// are unlikely, with a standard deviation of half the search radius, and so are consecutive
+/// matches whose distance along the network differs much from the straight distance between
+/// their points. The lines of the network are connected where they share an endpoint, and can be
+/// travelled in both directions.
+///
+/// # Arguments
+///
+/// * `trace` - The line string of the recorded positions.
+/// * `network` - The line strings of the network. Other geometries are ignored.
+/// * `search_radius` - The largest distance from a point of the trace to its match.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::map_match::map_match;
+///
+/// //a street turning north at a crossing, and a parallel street to the south
+/// let network = vec![
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(100, 0)] },
+///     Geometry::LineString { coordinates: vec![coord!(100, 0), coord!(100, 100)] },
+///     Geometry::LineString { coordinates: vec![coord!(100, 0), coord!(200, 0)] },
+///     Geometry::LineString { coordinates: vec![coord!(0, -12), coord!(200, -12)] },
+/// ];
+/// let trace = Geometry::LineString { coordinates: vec![coord!(10, -5), coord!(60, -5), coord!(96, 10), coord!(104, 60)] };
+/// let matched = map_match(&trace, &network, 20.0).unwrap();
+/// assert_eq!(matched.path.as_text(), "LINESTRING (10 0, 60 0, 100 0, 100 10, 100 60)");
+/// let lines: Vec<usize> = matched.points.iter().map(|p| p.as_ref().unwrap().line).collect();
+/// assert_eq!(lines, vec![0, 0, 1, 1]);
+/// ```
+pub fn map_match(trace: &Geometry, network: &[Geometry], search_radius: f64) -> Option<MapMatch> {
+    let Geometry::LineString { coordinates: trace } = trace else {
+        return None;
+    };
+    let network = Network::new(network);
+    let sigma = search_radius / 2.0;
+    let beta = search_radius;
+
+    let candidates: Vec<Vec<MatchedPoint>> = trace.iter().map(|p| network.candidates(p, search_radius)).collect();
+
+    //viterbi over the points with candidates, restarting where no transition is possible
+    let mut scores: Vec<Vec<f64>> = Vec::with_capacity(trace.len());
+    let mut previous: Vec<Vec<Option<usize>>> = Vec::with_capacity(trace.len());
+    let mut last: Option<usize> = None;
+    for (t, step) in candidates.iter().enumerate() {
+        let emission: Vec<f64> = step.iter().map(|c| -0.5 * (c.distance / sigma).powi(2)).collect();
+        let mut step_scores = emission.clone();
+        let mut step_previous = vec![None; step.len()];
+        if let Some(s) = last {
+            let straight = (trace[t].x() - trace[s].x()).hypot(trace[t].y() - trace[s].y());
+            for (j, to) in step.iter().enumerate() {
+                let mut best = f64::NEG_INFINITY;
+                for (i, from) in candidates[s].iter().enumerate() {
+                    let route = network.route_length(from, to);
+                    let score = scores[s][i] - (route - straight).abs() / beta;
+                    if score > best {
+                        best = score;
+                        step_previous[j] = Some(i);
+                    }
+                }
+                step_scores[j] = best + emission[j];
+            }
+            if step_scores.iter().all(|s| *s == f64::NEG_INFINITY) {
+                //the network does not connect the points, so the chain starts again
+                step_scores = emission;
+                step_previous = vec![None; step.len()];
+            }
+        }
+        if !step.is_empty() {
+            last = Some(t);
+        }
+        scores.push(step_scores);
+        previous.push(step_previous);
+    }
+
+    //backtracking from the best final candidate
+    let mut chosen: Vec<Option<usize>> = vec![None; trace.len()];
+    let mut t = last;
+    let mut next: Option<usize> = None;
+    while let Some(s) = t {
+        let j = match next {
+            Some(j) => j,
+            None => (0..scores[s].len()).max_by(|&a, &b| scores[s][a].total_cmp(&scores[s][b]))?,
+        };
+        chosen[s] = Some(j);
+        next = previous[s][j];
+        //where the chain started again, the one before ends at its own best candidate
+        t = (0..s).rev().find(|&k| !candidates[k].is_empty());
+    }
+
+    let points: Vec<Option<MatchedPoint>> = chosen
+        .iter()
+        .enumerate()
+        .map(|(t, j)| j.map(|j| candidates[t][j].clone()))
+        .collect();
+
+    //the route between consecutive matches, split where the chain restarted
+    let mut parts: Vec<Vec<Coordinate>> = Vec::new();
+    let mut current: Vec<Coordinate> = Vec::new();
+    let mut before: Option<(usize, usize)> = None;
+    for (t, j) in chosen.iter().enumerate() {
+        let Some(j) = *j else {
+            continue;
+        };
+        match before {
+            Some((s, i)) if previous[t][j] == Some(i) => {
+                extend(&mut current, network.route(&candidates[s][i], &candidates[t][j]));
+            }
+            _ => {
+                if current.len() > 1 {
+                    parts.push(current);
+                }
+                current = vec![candidates[t][j].coordinate.clone()];
+            }
+        }
+        before = Some((t, j));
+    }
+    if current.len() > 1 {
+        parts.push(current);
+    }
+    let path = if parts.len() == 1 {
+        Geometry::LineString { coordinates: parts.pop().unwrap_or_default() }
+    } else {
+        Geometry::MultiLineString { coordinates: parts }
+    };
+    Some(MapMatch { path, points })
+}
+
+/// Appends coordinates to a path, leaving out the ones repeating the last.
+fn extend(path: &mut Vec<Coordinate>, coordinates: Vec<Coordinate>) {
+    for c in coordinates {
+        if path.last().is_none_or(|last| !last.equals_2d(&c)) {
+            path.push(c);
+        }
+    }
+}
+
+/// The lines of a network, connected at their shared endpoints.
+struct Network<'a> {
+    lines: Vec<&'a [Coordinate]>,
+    //the distance along every line to each of its vertices
+    offsets: Vec<Vec<f64>>,
+    //the start and end nodes of every line
+    ends: Vec<(usize, usize)>,
+    //the lines leaving every node
+    incident: Vec<Vec<usize>>,
+    segments: STRtree<(usize, usize)>,
+    //the shortest distances from the nodes already searched, and the line leading to every node
+    searched: std::cell::RefCell<HashMap<usize, Reach>>,
+}
+
+/// The shortest distance to every node of the network and the line it is reached by.
+type Reach = Vec<(f64, Option<usize>)>;
+
+/// A node of the network and the distance to it along a line.
+type End = (usize, f64);
+
+impl<'a> Network<'a> {
+    fn new(network: &'a [Geometry]) -> Self {
+        let mut lines = Vec::new();
+        let mut offsets = Vec::new();
+        let mut ends = Vec::new();
+        let mut nodes: HashMap<(u64, u64), usize> = HashMap::new();
+        let mut incident: Vec<Vec<usize>> = Vec::new();
+        let mut segments = Vec::new();
+        for geometry in network {
+            //the indices of the lines are the ones of the geometries, so the others are kept empty
+            let line: &[Coordinate] = match geometry {
+                Geometry::LineString { coordinates } if !coordinates.is_empty() => coordinates,
+                _ => &[],
+            };
+            let index = lines.len();
+            let mut node = |c: &Coordinate| {
+                let next = nodes.len();
+                let node = *nodes.entry((c.x().to_bits(), c.y().to_bits())).or_insert(next);
+                if node == incident.len() {
+                    incident.push(Vec::new());
+                }
+                incident[node].push(index);
+                node
+            };
+            if let (Some(first), Some(last)) = (line.first(), line.last()) {
+                ends.push((node(first), node(last)));
+            } else {
+                ends.push((usize::MAX, usize::MAX));
+            }
+            let mut offset = 0.0;
+            let mut line_offsets = vec![0.0];
+            for (k, w) in line.windows(2).enumerate() {
+                offset += (w[1].x() - w[0].x()).hypot(w[1].y() - w[0].y());
+                line_offsets.push(offset);
+                segments.push((Envelope::of([&w[0], &w[1]]), (index, k)));
+            }
+            lines.push(line);
+            offsets.push(line_offsets);
+        }
+        Network {
+            lines,
+            offsets,
+            ends,
+            incident,
+            segments: STRtree::new(segments),
+            searched: std::cell::RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn length(&self, line: usize) -> f64 {
+        self.offsets[line].last().copied().unwrap_or(0.0)
+    }
+
+    /// Returns the closest position of every line within the radius of the point.
+    fn candidates(&self, p: &Coordinate, radius: f64) -> Vec<MatchedPoint> {
+        let envelope = Envelope::new(p.x() - radius, p.y() - radius, p.x() + radius, p.y() + radius);
+        let mut best: HashMap<usize, MatchedPoint> = HashMap::new();
+        for &(line, k) in self.segments.query(&envelope) {
+            let (a, b) = (&self.lines[line][k], &self.lines[line][k + 1]);
+            let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+            let squared = dx * dx + dy * dy;
+            let t = if squared == 0.0 { 0.0 } else { (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / squared).clamp(0.0, 1.0) };
+            let coordinate = Coordinate::new(a.x() + t * dx, a.y() + t * dy, a.z() + t * (b.z() - a.z()));
+            let distance = (p.x() - coordinate.x()).hypot(p.y() - coordinate.y());
+            if distance > radius || best.get(&line).is_some_and(|c| c.distance <= distance) {
+                continue;
+            }
+            let offset = self.offsets[line][k] + t * squared.sqrt();
+            best.insert(line, MatchedPoint { line, offset, coordinate, distance });
+        }
+        let mut candidates: Vec<MatchedPoint> = best.into_values().collect();
+        candidates.sort_by_key(|c| c.line);
+        candidates
+    }
+
+    /// Returns the shortest distances from a node to all the others, with the line leading to them.
+    fn search(&self, source: usize) -> std::cell::Ref<'_, Reach> {
+        if !self.searched.borrow().contains_key(&source) {
+            let mut reached = vec![(f64::INFINITY, None); self.incident.len()];
+            reached[source].0 = 0.0;
+            let mut queue = BinaryHeap::new();
+            queue.push(Reached(0.0, source));
+            while let Some(Reached(distance, node)) = queue.pop() {
+                if distance > reached[node].0 {
+                    continue;
+                }
+                for &line in &self.incident[node] {
+                    let (start, end) = self.ends[line];
+                    let other = if start == node { end } else { start };
+                    let through = distance + self.length(line);
+                    if through < reached[other].0 {
+                        reached[other] = (through, Some(line));
+                        queue.push(Reached(through, other));
+                    }
+                }
+            }
+            self.searched.borrow_mut().insert(source, reached);
+        }
+        std::cell::Ref::map(self.searched.borrow(), |searched| &searched[&source])
+    }
+
+    /// Returns the ends of the lines of two positions through which the shortest route between
+    /// them goes, as the node and the offset on the line for each, with the length of the route.
+    fn shortest_ends(&self, from: &MatchedPoint, to: &MatchedPoint) -> Option<(End, End, f64)> {
+        let (from_start, from_end) = self.ends[from.line];
+        let (to_start, to_end) = self.ends[to.line];
+        let mut best = None;
+        for (node, offset) in [(from_start, 0.0), (from_end, self.length(from.line))] {
+            let reached = self.search(node);
+            for (other, other_offset) in [(to_start, 0.0), (to_end, self.length(to.line))] {
+                let length = (from.offset - offset).abs() + reached[other].0 + (to.offset - other_offset).abs();
+                if length.is_finite() && best.as_ref().is_none_or(|b: &(_, _, f64)| length < b.2) {
+                    best = Some(((node, offset), (other, other_offset), length));
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns the length of the shortest route between two positions, infinite if the network
+    /// does not connect them.
+    fn route_length(&self, from: &MatchedPoint, to: &MatchedPoint) -> f64 {
+        let along = if from.line == to.line { (to.offset - from.offset).abs() } else { f64::INFINITY };
+        let through = self.shortest_ends(from, to).map_or(f64::INFINITY, |(_, _, length)| length);
+        along.min(through)
+    }
+
+    /// Returns the coordinates of the shortest route between two positions.
+    fn route(&self, from: &MatchedPoint, to: &MatchedPoint) -> Vec<Coordinate> {
+        let ends = self.shortest_ends(from, to);
+        if from.line == to.line && ends.as_ref().is_none_or(|e| (to.offset - from.offset).abs() <= e.2) {
+            return self.substring(from.line, from.offset, to.offset);
+        }
+        let Some(((start_node, start_offset), (end_node, end_offset), _)) = ends else {
+            return Vec::new();
+        };
+        //the lines between the two nodes, walked back from the end
+        let mut lines = Vec::new();
+        let reached = self.search(start_node);
+        let mut node = end_node;
+        while node != start_node {
+            let Some(line) = reached[node].1 else {
+                break;
+            };
+            let (start, end) = self.ends[line];
+            let forward = end == node;
+            lines.push((line, forward));
+            node = if forward { start } else { end };
+        }
+        drop(reached);
+        let mut path = self.substring(from.line, from.offset, start_offset);
+        for &(line, forward) in lines.iter().rev() {
+            let length = self.length(line);
+            let part = if forward { self.substring(line, 0.0, length) } else { self.substring(line, length, 0.0) };
+            extend(&mut path, part);
+        }
+        extend(&mut path, self.substring(to.line, end_offset, to.offset));
+        path
+    }
+
+    /// Returns the part of a line between two distances along it, reversed if the first is larger.
+    fn substring(&self, line: usize, from: f64, to: f64) -> Vec<Coordinate> {
+        if from > to {
+            let mut part = self.substring(line, to, from);
+            part.reverse();
+            return part;
+        }
+        let (coordinates, offsets) = (self.lines[line], &self.offsets[line]);
+        let at = |offset: f64| {
+            let k = offsets.partition_point(|&o| o < offset).clamp(1, offsets.len() - 1);
+            let (a, b) = (&coordinates[k - 1], &coordinates[k]);
+            let span = offsets[k] - offsets[k - 1];
+            let t = if span == 0.0 { 0.0 } else { ((offset - offsets[k - 1]) / span).clamp(0.0, 1.0) };
+            Coordinate::new(a.x() + t * (b.x() - a.x()), a.y() + t * (b.y() - a.y()), a.z() + t * (b.z() - a.z()))
+        };
+        if coordinates.len() < 2 {
+            return coordinates.to_vec();
+        }
+        let mut part = vec![at(from)];
+        for (c, &o) in coordinates.iter().zip(offsets) {
+            if o > from && o < to {
+                part.push(c.clone());
+            }
+        }
+        extend(&mut part, vec![at(to)]);
+        part
+    }
+}
+
+/// A node reached at a distance, ordered so that the closest comes out of a heap first.
+struct Reached(f64, usize);
+
+impl PartialEq for Reached {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Reached {}
+
+impl PartialOrd for Reached {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Reached {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_noisy_trace_on_a_grid() {
+        //streets every 100 units, split at every crossing
+        let mut network = Vec::new();
+        for i in 0..=4 {
+            for j in 0..4 {
+                let (a, b) = (i as f64 * 100.0, j as f64 * 100.0);
+                network.push(Geometry::LineString { coordinates: vec![coord!(a, b), coord!(a, b + 100.0)] });
+                network.push(Geometry::LineString { coordinates: vec![coord!(b, a), coord!(b + 100.0, a)] });
+            }
+        }
+        //east along y = 0, then north along x = 300, sampled every 30 units with alternating noise
+        let mut trace = Vec::new();
+        for k in 1..20 {
+            let d = k as f64 * 30.0;
+            let noise = if k % 2 == 0 { 8.0 } else { -8.0 };
+            trace.push(if d <= 300.0 { coord!(d, noise) } else { coord!(300.0 + noise, d - 300.0) });
+        }
+        let matched = map_match(&Geometry::LineString { coordinates: trace }, &network, 30.0).unwrap();
+        let Geometry::LineString { coordinates } = &matched.path else { panic!("{:?}", matched.path) };
+        assert!(coordinates.iter().all(|c| c.y() == 0.0 || c.x() == 300.0));
+        assert!((matched.path.length() - 540.0).abs() < 1e-9);
+        assert!(matched.points.iter().all(|p| p.as_ref().unwrap().distance <= 8.0));
+    }
+
+    #[test]
+    fn test_disconnected_network() {
+        let network = vec![
+            Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] },
+            Geometry::Point { coordinates: coord!(15, 0) },
+            Geometry::LineString { coordinates: vec![coord!(20, 0), coord!(30, 0)] },
+        ];
+        let trace = Geometry::LineString { coordinates: vec![coord!(1, 1), coord!(9, 1), coord!(15, 30), coord!(21, 1), coord!(29, 1)] };
+        let matched = map_match(&trace, &network, 2.0).unwrap();
+        assert_eq!(matched.path.as_text(), "MULTILINESTRING ((1 0, 9 0), (21 0, 29 0))");
+        assert_eq!(matched.points[2], None);
+        assert_eq!(matched.points[3].as_ref().map(|p| p.line), Some(2));
+        assert_eq!(map_match(&Geometry::Point { coordinates: coord!(0, 0) }, &network, 2.0), None);
+    }
+}
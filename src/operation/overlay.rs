@@ -0,0 +1,1253 @@
+//! Boolean operations between the polygons of two geometries: intersection, union, difference
+//! and symmetric difference.
+//!
+//! The rings of both geometries are split at their intersections, the edges of the resulting
+//! arrangement are labelled with the location of their two sides relative to each geometry, and
+//! the edges separating the selected area from the rest are assembled into the polygons of the
+//! result. When floating point errors leave the arrangement inconsistent, the overlay is run again
+//! on snapped and then on snap rounded coordinates.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::algorithm::area::ring_signed_area;
+use crate::algorithm::line_intersection::{intersection, point_on_segment, sweep_segment_pairs, SegmentIntersection, ZInterpolation};
+use crate::algorithm::point_in_polygon::{locate_in_polygon, locate_in_ring, Location};
+use crate::algorithm::skeleton::merge_segments;
+use crate::cluster::DisjointSet;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::index::kdtree::KdTree;
+use crate::index::strtree::STRtree;
+use crate::operation::distance::rectangle;
+use crate::operation::{extract, points};
+use crate::progress::{step, ProgressSink};
+use crate::sort::hilbert_sort;
+use crate::trace;
+
+/// The boolean operation computed by an overlay.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OverlayOp {
+    /// The area covered by both geometries.
+    Intersection,
+    /// The area covered by either geometry.
+    Union,
+    /// The area covered by the first geometry but not by the second.
+    Difference,
+    /// The area covered by exactly one of the geometries.
+    SymDifference,
+}
+
+impl OverlayOp {
+    /// Returns true if a place inside of the first geometry or not, and of the second or not, is
+    /// part of the result.
+    pub(crate) fn select(self, a: bool, b: bool) -> bool {
+        match self {
+            OverlayOp::Intersection => a && b,
+            OverlayOp::Union => a || b,
+            OverlayOp::Difference => a && !b,
+            OverlayOp::SymDifference => a != b,
+        }
+    }
+}
+
+/// How the coordinates computed by an overlay are represented.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum PrecisionModel {
+    /// Coordinates keep the full precision of floating point numbers.
+    #[default]
+    Floating,
+    /// Coordinates are rounded to multiples of the inverse of the scale, so that a scale of 100
+    /// keeps two decimals.
+    Fixed(f64),
+}
+
+/// Settings trading the accuracy of an overlay for its robustness.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct OverlayOptions {
+    /// The distance within which vertices are snapped to each other and to the segments passing
+    /// by before the overlay, or None to snap only when the overlay fails without snapping, with
+    /// tolerances derived from the size of the geometries.
+    pub snap_tolerance: Option<f64>,
+    /// The precision of the coordinates. A fixed precision snap rounds the geometries to its
+    /// grid, which always gives a consistent arrangement, while a floating precision only falls
+    /// back to snap rounding when snapping fails.
+    pub precision_model: PrecisionModel,
+    /// How the vertices created where the segments of the geometries cross get their z. With
+    /// [`ZInterpolation::First`], it is taken from the segment of the first geometry.
+    pub z_interpolation: ZInterpolation,
+}
+
+/// How the segments of the rings are split at their intersections.
+#[derive(Debug, Clone, Copy)]
+enum Noding {
+    /// At the intersection points computed in floating point.
+    Floating,
+    /// As with floating, after snapping the vertices within the tolerance together and onto the
+    /// segments.
+    Snapping(f64),
+    /// At the nodes of a grid of the given scale, with every segment passing through the pixel of
+    /// a node bent to it.
+    SnapRounding(f64),
+}
+
+/// Returns the result of a boolean operation between the polygons of two geometries, or an error
+/// if no consistent result could be computed. The points and lines of the geometries are
+/// ignored, and the polygons of a geometry may overlap, in which case their union is used. The
+/// inside of a polygon is the area its rings enclose an odd number of times, so the orientation
+/// of the rings does not matter and self intersecting rings are accepted.
+///
+/// The overlay is first computed in floating point. If its intersections turn out inconsistent,
+/// it is computed again with the vertices snapped together within growing tolerances, and then
+/// with the coordinates snap rounded to growing grids, all derived from the size of the
+/// geometries. See [`overlay_with`] to set the tolerance and the precision.
+///
+/// The result is a polygon, or a multi polygon when it has several parts or none. Shells are
+/// counter clockwise and holes clockwise, and rings touching themselves are split into simple
+/// rings where they touch. The vertices created at intersections get the mean of the z values of
+/// both segments, which [`overlay_with`] can change.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::{overlay, OverlayOp};
+///
+/// let square = |x: f64, y: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x, y), coord!(x + 2.0, y), coord!(x + 2.0, y + 2.0), coord!(x, y + 2.0), coord!(x, y)
+/// ]] };
+/// let (a, b) = (square(0.0, 0.0), square(1.0, 1.0));
+/// assert_eq!(overlay(&a, &b, OverlayOp::Intersection).unwrap().as_text(), "POLYGON ((1 1, 2 1, 2 2, 1 2, 1 1))");
+/// assert_eq!(overlay(&a, &b, OverlayOp::Union).unwrap().area(), 7.0);
+/// assert_eq!(overlay(&a, &b, OverlayOp::Difference).unwrap().area(), 3.0);
+/// assert_eq!(overlay(&a, &b, OverlayOp::SymDifference).unwrap().area(), 6.0);
+/// ```
+pub fn overlay(a: &Geometry, b: &Geometry, op: OverlayOp) -> Result<Geometry, Error> {
+    overlay_with(a, b, op, &OverlayOptions::default())
+}
+
+/// Returns the result of a boolean operation between the polygons of two geometries with the
+/// given robustness settings, or an error if no consistent result could be computed. See
+/// [`overlay`].
+///
+/// With a fixed precision model, the geometries are snap rounded to its grid and no other
+/// attempt is made. Otherwise the overlay is computed with the snap tolerance if one is given,
+/// and without snapping if not, before falling back to snap rounding.
+///
+/// # Arguments
+///
+/// * `a` - The first geometry.
+/// * `b` - The second geometry.
+/// * `op` - The boolean operation.
+/// * `options` - The snap tolerance, the precision model and the interpolation of z.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::{overlay_with, OverlayOp, OverlayOptions, PrecisionModel};
+///
+/// let a = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(3, 0), coord!(0, 3), coord!(0, 0)]] };
+/// let b = Geometry::Polygon { coordinates: vec![vec![coord!(0, 1), coord!(3, 1), coord!(3, 2.4), coord!(0, 2.4), coord!(0, 1)]] };
+/// let options = OverlayOptions { precision_model: PrecisionModel::Fixed(1.0), ..Default::default() };
+/// let result = overlay_with(&a, &b, OverlayOp::Intersection, &options).unwrap();
+/// assert_eq!(result.as_text(), "POLYGON ((0 1, 2 1, 1 2, 0 2, 0 1))");
+/// ```
+pub fn overlay_with(a: &Geometry, b: &Geometry, op: OverlayOp, options: &OverlayOptions) -> Result<Geometry, Error> {
+    trace::span!("overlay", op = tracing::field::debug(op), vertices = a.coords_count() + b.coords_count());
+    let inputs: [Vec<Vec<Vec<Coordinate>>>; 2] = [a.polygons().cloned().collect(), b.polygons().cloned().collect()];
+    let polygons = match options.precision_model {
+        PrecisionModel::Fixed(scale) => {
+            if !(scale.is_finite() && scale > 0.0) {
+                panic!("The scale of a fixed precision model must be positive");
+            }
+            compute(&inputs, op, Noding::SnapRounding(scale), options.z_interpolation)?
+        }
+        PrecisionModel::Floating => {
+            let envelope = a.envelope().merge(&b.envelope());
+            //the size of the rounding errors follows the magnitude of the coordinates
+            let size = [envelope.min_x(), envelope.min_y(), envelope.max_x(), envelope.max_y()]
+                .iter()
+                .fold(envelope.width().max(envelope.height()), |size, c| size.max(c.abs()));
+            let mut attempts = match options.snap_tolerance {
+                Some(tolerance) => vec![Noding::Snapping(tolerance)],
+                None => vec![Noding::Floating],
+            };
+            if envelope.is_empty() || size == 0.0 || !size.is_finite() {
+                attempts.truncate(1);
+            } else {
+                if options.snap_tolerance.is_none() {
+                    attempts.extend([1e-12, 1e-10, 1e-8].map(|f| Noding::Snapping(f * size)));
+                }
+                attempts.extend([1e-12, 1e-10, 1e-8].map(|f| Noding::SnapRounding(1.0 / (f * size))));
+            }
+            let mut result = Err(Error::Topology("no overlay was attempted".to_string()));
+            for noding in attempts {
+                result = compute(&inputs, op, noding, options.z_interpolation);
+                if result.is_ok() {
+                    break;
+                }
+                trace::event!("falling back", failed = tracing::field::debug(noding));
+            }
+            result?
+        }
+    };
+    Ok(if polygons.len() == 1 {
+        Geometry::Polygon { coordinates: polygons.into_iter().next().unwrap_or_default() }
+    } else {
+        Geometry::MultiPolygon { coordinates: polygons }
+    })
+}
+
+/// Returns the union of the parts of the highest dimension of a single geometry, such as a
+/// collection coming out of another operation, or an error if no consistent result could be
+/// computed. Parts of lower dimensions are dropped.
+///
+/// * Polygons are merged by a cascaded union: they are sorted along the Hilbert curve and
+///   unioned by pairs of neighbours, then pairs of the results, until one is left. The result is
+///   a polygon or a multi polygon, as returned by [`overlay`].
+/// * Without polygons, the segments of the lines are kept once and merged into lines where
+///   exactly two meet, as a multi line string. Crossing lines are not split.
+/// * Without lines, the distinct points are returned as a multi point.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::unary_union;
+///
+/// let square = |x: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x, 0), coord!(x + 2.0, 0), coord!(x + 2.0, 2), coord!(x, 2), coord!(x, 0)
+/// ]] };
+/// let collection = Geometry::GeometryCollection { geometries: vec![
+///     square(0.0), square(1.0), square(2.0),
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(9, 9)] },
+/// ] };
+/// assert_eq!(unary_union(&collection).unwrap().as_text(), "POLYGON ((0 0, 1 0, 2 0, 3 0, 4 0, 4 2, 3 2, 2 2, 1 2, 0 2, 0 0))");
+///
+/// let lines = Geometry::MultiLineString { coordinates: vec![
+///     vec![coord!(0, 0), coord!(1, 0), coord!(2, 0)],
+///     vec![coord!(2, 0), coord!(1, 0)],
+/// ] };
+/// assert_eq!(unary_union(&lines).unwrap().as_text(), "MULTILINESTRING ((0 0, 1 0, 2 0))");
+/// ```
+pub fn unary_union(geometry: &Geometry) -> Result<Geometry, Error> {
+    unary_union_with_progress(geometry, &())
+}
+
+/// Returns the union of the parts of the geometry as [`unary_union`] does, reporting every union
+/// of two polygons to the sink, out of one less than the number of polygons, or an error if the
+/// sink cancels it.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Mutex;
+///
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::unary_union_with_progress;
+///
+/// let squares = Geometry::MultiPolygon { coordinates: (0..4).map(|i| vec![vec![
+///     coord!(i, 0), coord!(i + 2, 0), coord!(i + 2, 2), coord!(i, 2), coord!(i, 0)
+/// ]]).collect() };
+/// let percents = Mutex::new(Vec::new());
+/// let union = unary_union_with_progress(&squares, &|done, total| percents.lock().unwrap().push(100 * done / total)).unwrap();
+/// assert_eq!(union.area(), 10.0);
+/// assert_eq!(percents.into_inner().unwrap(), [33, 66, 100]);
+/// ```
+pub fn unary_union_with_progress(geometry: &Geometry, progress: &dyn ProgressSink) -> Result<Geometry, Error> {
+    let mut parts: Vec<Geometry> = geometry.polygons().map(|p| Geometry::Polygon { coordinates: p.clone() }).collect();
+    trace::span!("unary_union", parts = parts.len());
+    if parts.is_empty() {
+        let lines = extract::lines(geometry);
+        if lines.is_empty() {
+            return Ok(Geometry::MultiPoint { coordinates: points::dedup(&extract::points(geometry)) });
+        }
+        let segments = lines.iter().flat_map(|line| line.windows(2)).map(|w| (w[0].clone(), w[1].clone())).collect();
+        return Ok(Geometry::MultiLineString { coordinates: merge_segments(segments) });
+    }
+    if parts.len() == 1 {
+        let union = overlay(&parts[0], &Geometry::MultiPolygon { coordinates: vec![] }, OverlayOp::Union)?;
+        step(progress, 1, 1)?;
+        return Ok(union);
+    }
+    //neighbours along the curve overlap the most, keeping the intermediate results small
+    hilbert_sort(&mut parts);
+    let (mut done, total) = (0, parts.len() - 1);
+    while parts.len() > 1 {
+        parts = parts
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => {
+                    let union = overlay(a, b, OverlayOp::Union)?;
+                    done += 1;
+                    step(progress, done, total)?;
+                    Ok(union)
+                }
+                _ => Ok(pair[0].clone()),
+            })
+            .collect::<Result<_, Error>>()?;
+    }
+    Ok(parts.pop().unwrap())
+}
+
+/// Returns a single geometry noded and dissolved as a whole, as `ST_UnaryUnion` does, or an error
+/// if no consistent result could be computed. Unlike [`unary_union`], parts of every dimension
+/// are kept where they are not covered by parts of a higher dimension:
+///
+/// * Overlapping polygons are merged into a polygon or a multi polygon, see [`unary_union`].
+/// * Lines are split where they cross each other or the boundary of the polygons, the pieces
+///   inside of the polygons or on their boundary are dropped, and the rest are merged where
+///   exactly two meet, as a multi line string.
+/// * Distinct points off the polygons and lines are kept as a multi point.
+///
+/// The result is the only non empty one of them, or a geometry collection of the polygons, lines
+/// and points when several are left, which is empty for empty geometries.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::self_union;
+///
+/// //two crossing roads and a stop along one of them
+/// let network = Geometry::GeometryCollection { geometries: vec![
+///     Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(4, 0)] },
+///     Geometry::LineString { coordinates: vec![coord!(2, -2), coord!(2, 2)] },
+///     Geometry::Point { coordinates: coord!(1, 0) },
+/// ] };
+/// assert_eq!(
+///     self_union(&network).unwrap().as_text(),
+///     "MULTILINESTRING ((0 0, 2 0), (2 0, 4 0), (2 -2, 2 0), (2 0, 2 2))"
+/// );
+/// ```
+pub fn self_union(geometry: &Geometry) -> Result<Geometry, Error> {
+    let area = match geometry.polygons().next() {
+        Some(_) => unary_union(geometry)?,
+        None => Geometry::MultiPolygon { coordinates: vec![] },
+    };
+    let dissolved: Vec<&Vec<Vec<Coordinate>>> = area.polygons().collect();
+    let outside = |c: &Coordinate| dissolved.iter().all(|rings| locate_in_polygon(c, rings) == Location::Exterior);
+
+    //the lines are noded together with the boundary of the polygons, as the source 1
+    let mut segments = Vec::new();
+    for line in extract::lines(geometry) {
+        for w in line.windows(2).filter(|w| !w[0].equals_2d(&w[1])) {
+            segments.push(Segment { start: w[0].clone(), end: w[1].clone(), source: 0, owner: 0 });
+        }
+    }
+    if !segments.is_empty() {
+        for ring in dissolved.iter().flat_map(|rings| rings.iter()) {
+            for w in ring.windows(2) {
+                segments.push(Segment { start: w[0].clone(), end: w[1].clone(), source: 1, owner: 0 });
+            }
+        }
+    }
+    let pieces: Vec<(Coordinate, Coordinate)> = node(segments, ZInterpolation::default())
+        .into_iter()
+        .filter(|s| s.source == 0)
+        .filter(|s| outside(&Coordinate::new((s.start.x() + s.end.x()) / 2.0, (s.start.y() + s.end.y()) / 2.0, 0.0)))
+        .map(|s| (s.start, s.end))
+        .collect();
+    let lines = merge_segments(pieces);
+    let points: Vec<Coordinate> = points::dedup(&extract::points(geometry))
+        .into_iter()
+        .filter(|p| outside(p) && !lines.iter().any(|line| line.windows(2).any(|w| point_on_segment(p, &w[0], &w[1]))))
+        .collect();
+
+    Ok(combine(area, lines, points))
+}
+
+/// Returns the parts of the geometry within the envelope, or an error if the polygons could not
+/// be clipped consistently. Polygons are intersected with the envelope as a rectangle, lines are
+/// cut where they leave it, interpolating the z of the new ends, and points outside of it are
+/// dropped. The boundary of the envelope belongs to it. As for [`self_union`], the result is the
+/// only non empty one of the polygons, the lines and the points, or a collection of them. See
+/// [`clip_with`] to set how the polygons are overlaid and the new ends of lines get their z.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::clip;
+///
+/// //a road going in and out of the window
+/// let road = Geometry::LineString { coordinates: vec![coord!(-1, 1), coord!(3, 1), coord!(3, 5), coord!(1, 5), coord!(1, 3)] };
+/// let clipped = clip(&road, &Envelope::new(0.0, 0.0, 4.0, 4.0)).unwrap();
+/// assert_eq!(clipped.as_text(), "MULTILINESTRING ((0 1, 3 1, 3 4), (1 4, 1 3))");
+/// ```
+pub fn clip(geometry: &Geometry, envelope: &Envelope) -> Result<Geometry, Error> {
+    clip_with(geometry, envelope, &OverlayOptions::default())
+}
+
+/// Returns the parts of the geometry within the envelope as [`clip`] does, overlaying the
+/// polygons with the given options. The envelope has no z, so the new ends of lines get the z
+/// interpolated along them unless the policy is [`ZInterpolation::Zero`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::algorithm::line_intersection::ZInterpolation;
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::{clip_with, OverlayOptions};
+///
+/// let pipe = Geometry::LineString { coordinates: vec![coord!(0, 0, 10), coord!(4, 0, 30)] };
+/// let window = Envelope::new(1.0, -1.0, 3.0, 1.0);
+/// let clipped = clip_with(&pipe, &window, &OverlayOptions::default()).unwrap();
+/// assert_eq!(clipped.as_text(), "MULTILINESTRING Z ((1 0 15, 3 0 25))");
+/// let options = OverlayOptions { z_interpolation: ZInterpolation::Zero, ..Default::default() };
+/// assert_eq!(clip_with(&pipe, &window, &options).unwrap().as_text(), "MULTILINESTRING ((1 0, 3 0))");
+/// ```
+pub fn clip_with(geometry: &Geometry, envelope: &Envelope, options: &OverlayOptions) -> Result<Geometry, Error> {
+    let area = match geometry.polygons().next() {
+        Some(_) => overlay_with(geometry, &rectangle(envelope), OverlayOp::Intersection, options)?,
+        None => Geometry::MultiPolygon { coordinates: vec![] },
+    };
+    let mut lines = Vec::new();
+    for line in extract::lines(geometry) {
+        let mut piece: Vec<Coordinate> = Vec::new();
+        for w in line.windows(2) {
+            match clip_segment(&w[0], &w[1], envelope, options.z_interpolation) {
+                Some((start, end)) => {
+                    //a piece goes on until it leaves the envelope
+                    if piece.last().is_none_or(|last| !last.equals_2d(&start)) {
+                        lines.extend(Some(std::mem::take(&mut piece)).filter(|p| p.len() > 1));
+                        piece.push(start);
+                    }
+                    if piece.last().is_none_or(|last| !last.equals_2d(&end)) {
+                        piece.push(end);
+                    }
+                }
+                None => lines.extend(Some(std::mem::take(&mut piece)).filter(|p| p.len() > 1)),
+            }
+        }
+        lines.extend(Some(piece).filter(|p| p.len() > 1));
+    }
+    let points = extract::points(geometry).into_iter().filter(|p| envelope.contains_coordinate(p)).collect();
+    Ok(combine(area, lines, points))
+}
+
+/// Returns the part of a segment within the envelope, or None if it misses it, using the
+/// algorithm of Liang and Barsky. New ends get a z of 0 with the zero policy, and the z
+/// interpolated along the segment otherwise.
+fn clip_segment(a: &Coordinate, b: &Coordinate, envelope: &Envelope, z: ZInterpolation) -> Option<(Coordinate, Coordinate)> {
+    if envelope.is_empty() {
+        return None;
+    }
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [
+        (-dx, a.x() - envelope.min_x()),
+        (dx, envelope.max_x() - a.x()),
+        (-dy, a.y() - envelope.min_y()),
+        (dy, envelope.max_y() - a.y()),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    let at = |t: f64| match t {
+        0.0 => a.clone(),
+        1.0 => b.clone(),
+        _ if z == ZInterpolation::Zero => Coordinate::new(a.x() + t * dx, a.y() + t * dy, 0.0),
+        _ => Coordinate::new(a.x() + t * dx, a.y() + t * dy, a.z() + t * (b.z() - a.z())),
+    };
+    Some((at(t0), at(t1)))
+}
+
+/// Returns the lines of the geometry split where they cross or touch the lines or the boundary of
+/// the polygons of the blade, as a multi line string. The new vertices get the mean of the z
+/// values of the line and the blade, which [`split_with`] can change. Points and polygons of the
+/// geometry are left out, as splitting polygons is not supported yet.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::split;
+///
+/// //a road crossing a river twice
+/// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0)] };
+/// let river = Geometry::LineString { coordinates: vec![coord!(2, -1), coord!(4, 1), coord!(6, -1)] };
+/// assert_eq!(split(&road, &river).as_text(), "MULTILINESTRING ((0 0, 3 0), (3 0, 5 0), (5 0, 10 0))");
+/// ```
+pub fn split(geometry: &Geometry, blade: &Geometry) -> Geometry {
+    split_with(geometry, blade, &OverlayOptions::default())
+}
+
+/// Returns the lines of the geometry split by the blade as [`split`] does, with the z of the new
+/// vertices following the policy of the options, the line being the first segment.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::algorithm::line_intersection::ZInterpolation;
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::{split_with, OverlayOptions};
+///
+/// let pipe = Geometry::LineString { coordinates: vec![coord!(0, 0, 10), coord!(4, 0, 30)] };
+/// let wall = Geometry::LineString { coordinates: vec![coord!(1, -1), coord!(1, 1)] };
+/// let options = OverlayOptions { z_interpolation: ZInterpolation::First, ..Default::default() };
+/// assert_eq!(split_with(&pipe, &wall, &options).as_text(), "MULTILINESTRING Z ((0 0 10, 1 0 15), (1 0 15, 4 0 30))");
+/// ```
+pub fn split_with(geometry: &Geometry, blade: &Geometry, options: &OverlayOptions) -> Geometry {
+    let mut segments = Vec::new();
+    for (i, line) in extract::lines(geometry).iter().enumerate() {
+        for w in line.windows(2).filter(|w| !w[0].equals_2d(&w[1])) {
+            segments.push(Segment { start: w[0].clone(), end: w[1].clone(), source: 0, owner: i });
+        }
+    }
+    let rings = extract::polygons(blade).into_iter().flatten();
+    for path in extract::lines(blade).into_iter().chain(rings) {
+        for w in path.windows(2).filter(|w| !w[0].equals_2d(&w[1])) {
+            segments.push(Segment { start: w[0].clone(), end: w[1].clone(), source: 1, owner: 0 });
+        }
+    }
+    //only the crossings with the blade are computed, so the lines keep their own crossings
+    let noded = node_where(segments, options.z_interpolation, |p, q| p.source != q.source);
+    let cuts: HashSet<(u64, u64)> =
+        noded.iter().filter(|s| s.source == 1).flat_map(|s| [s.start.key(), s.end.key()]).collect();
+    let mut pieces: Vec<Vec<Coordinate>> = Vec::new();
+    let mut owner = None;
+    for s in noded.into_iter().filter(|s| s.source == 0) {
+        //a piece goes on along its line until it reaches the blade
+        if owner != Some(s.owner) || cuts.contains(&s.start.key()) {
+            pieces.push(vec![s.start]);
+            owner = Some(s.owner);
+        }
+        pieces.last_mut().unwrap().push(s.end);
+    }
+    Geometry::MultiLineString { coordinates: pieces }
+}
+
+/// Returns the only non empty one of the polygons, lines and points, or a collection of them.
+pub(crate) fn combine(area: Geometry, lines: Vec<Vec<Coordinate>>, points: Vec<Coordinate>) -> Geometry {
+    let mut parts = Vec::new();
+    if area.num_components() > 0 {
+        parts.push(area);
+    }
+    if !lines.is_empty() {
+        parts.push(Geometry::MultiLineString { coordinates: lines });
+    }
+    if !points.is_empty() {
+        parts.push(Geometry::MultiPoint { coordinates: points });
+    }
+    match parts.len() {
+        1 => parts.pop().unwrap(),
+        _ => Geometry::GeometryCollection { geometries: parts },
+    }
+}
+
+/// A segment of a ring of one of the geometries.
+#[derive(Debug, Clone)]
+struct Segment {
+    start: Coordinate,
+    end: Coordinate,
+    //the geometry, 0 or 1, and the index of its polygon the ring belongs to
+    source: usize,
+    owner: usize,
+}
+
+/// An edge of the arrangement, with the segments lying on it.
+struct Edge {
+    start: Coordinate,
+    end: Coordinate,
+    //the geometry and the polygon of every segment
+    sides: Vec<(usize, usize)>,
+}
+
+/// Identifies a location exactly, with the negative zero taken as zero.
+type Key = (u64, u64);
+
+/// Returns the polygons of the result of the operation, computed with the given noding.
+fn compute(
+    inputs: &[Vec<Vec<Vec<Coordinate>>>; 2],
+    op: OverlayOp,
+    noding: Noding,
+    z: ZInterpolation,
+) -> Result<Vec<Vec<Vec<Coordinate>>>, Error> {
+    let inputs = match noding {
+        Noding::Floating => inputs.clone(),
+        Noding::Snapping(tolerance) => snap_vertices(inputs, tolerance),
+        Noding::SnapRounding(scale) => inputs.clone().map(|polygons| {
+            polygons
+                .into_iter()
+                .map(|rings| rings.into_iter().map(|ring| ring.iter().map(|c| round(c, scale)).collect()).collect())
+                .collect()
+        }),
+    };
+
+    let mut segments = Vec::new();
+    for (source, polygons) in inputs.iter().enumerate() {
+        for (owner, rings) in polygons.iter().enumerate() {
+            //the inside of a polygon is where its rings are crossed an odd number of times, so the
+            //orientation of the rings does not matter and self intersecting rings are allowed
+            for ring in rings {
+                for w in ring.windows(2) {
+                    if !w[0].equals_2d(&w[1]) {
+                        segments.push(Segment { start: w[0].clone(), end: w[1].clone(), source, owner });
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    let input_segments = segments.len();
+    let segments = match noding {
+        Noding::Floating => node(segments, z),
+        Noding::Snapping(tolerance) => {
+            let vertices: Vec<Coordinate> = segments.iter().flat_map(|s| [s.start.clone(), s.end.clone()]).collect();
+            node(split_at_nodes(segments, &vertices, tolerance, |n, a, b| segment_distance(n, a, b) <= tolerance), z)
+        }
+        Noding::SnapRounding(scale) => snap_round(segments, scale, z)?,
+    };
+    trace::event!("noded", segments = segments.len(), splits = segments.len().saturating_sub(input_segments));
+    check_noding(&segments)?;
+
+    //the segments lying on the same edge are merged
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut index: HashMap<(Key, Key), usize> = HashMap::new();
+    for segment in segments {
+        let (from, to) = (segment.start.key(), segment.end.key());
+        let forward = from < to;
+        let i = *index.entry(if forward { (from, to) } else { (to, from) }).or_insert_with(|| {
+            let (start, end) = if forward { (&segment.start, &segment.end) } else { (&segment.end, &segment.start) };
+            edges.push(Edge { start: start.clone(), end: end.clone(), sides: Vec::new() });
+            edges.len() - 1
+        });
+        edges[i].sides.push((segment.source, segment.owner));
+    }
+
+    //the location of both sides of every edge relative to both geometries
+    let locators = [0, 1].map(|source| Locator::new(&edges, source, inputs[source].len()));
+    let mut result = Vec::new();
+    for edge in &edges {
+        let mut left = [false; 2];
+        let mut right = [false; 2];
+        for source in 0..2 {
+            for (l, r) in locators[source].sides(edge) {
+                left[source] |= l;
+                right[source] |= r;
+            }
+        }
+        let (left, right) = (op.select(left[0], left[1]), op.select(right[0], right[1]));
+        if left && !right {
+            result.push((edge.start.clone(), edge.end.clone()));
+        } else if right && !left {
+            result.push((edge.end.clone(), edge.start.clone()));
+        }
+    }
+    assemble(result)
+}
+
+/// Returns the coordinate rounded to the grid of the scale.
+fn round(c: &Coordinate, scale: f64) -> Coordinate {
+    Coordinate::new((c.x() * scale).round() / scale, (c.y() * scale).round() / scale, c.z())
+}
+
+/// Returns the polygons with the vertices within the tolerance of each other, directly or through
+/// other vertices, moved to the first of them, and the repeated vertices this creates removed.
+fn snap_vertices(inputs: &[Vec<Vec<Vec<Coordinate>>>; 2], tolerance: f64) -> [Vec<Vec<Vec<Coordinate>>>; 2] {
+    let vertices: Vec<Coordinate> = inputs.iter().flatten().flatten().flatten().cloned().collect();
+    let tree = KdTree::new(&vertices);
+    let mut sets = DisjointSet::new(vertices.len());
+    for (i, vertex) in vertices.iter().enumerate() {
+        for j in tree.query_radius(vertex, tolerance) {
+            sets.union_into(i.max(j), i.min(j));
+        }
+    }
+    let mut i = 0;
+    inputs.clone().map(|polygons| {
+        polygons
+            .into_iter()
+            .map(|rings| {
+                rings
+                    .into_iter()
+                    .map(|ring| {
+                        let mut snapped: Vec<Coordinate> = Vec::with_capacity(ring.len());
+                        for _ in &ring {
+                            let c = &vertices[sets.find(i)];
+                            if snapped.last().is_none_or(|last| !last.equals_2d(c)) {
+                                snapped.push(c.clone());
+                            }
+                            i += 1;
+                        }
+                        snapped
+                    })
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+/// Returns the segments split at the intersections computed between them, whose z follows the
+/// policy with the segment of the first geometry first.
+fn node(segments: Vec<Segment>, z: ZInterpolation) -> Vec<Segment> {
+    node_where(segments, z, |_, _| true)
+}
+
+/// Returns the segments split at the intersections computed between the pairs of them the
+/// predicate accepts, as [`node`] does.
+fn node_where<F>(segments: Vec<Segment>, z: ZInterpolation, accept: F) -> Vec<Segment>
+where
+    F: Fn(&Segment, &Segment) -> bool,
+{
+    let mut splits: Vec<Vec<Coordinate>> = vec![Vec::new(); segments.len()];
+    let ends: Vec<(&Coordinate, &Coordinate)> = segments.iter().map(|s| (&s.start, &s.end)).collect();
+    sweep_segment_pairs(&ends, |i, j| {
+        if !accept(&segments[i], &segments[j]) {
+            return true;
+        }
+        let (p, q) = first_source(&segments[i], &segments[j]);
+        match intersection(&p.start, &p.end, &q.start, &q.end, z) {
+            SegmentIntersection::None => {}
+            SegmentIntersection::Point(c) => {
+                splits[i].push(c.clone());
+                splits[j].push(c);
+            }
+            SegmentIntersection::Collinear(c, d) => {
+                splits[i].extend([c.clone(), d.clone()]);
+                splits[j].extend([c, d]);
+            }
+        }
+        true
+    });
+    split_segments(segments, splits)
+}
+
+/// Returns the segments of the paths split where they cross or touch each other, without the
+/// segments of no length.
+pub(crate) fn node_paths<'a>(paths: impl IntoIterator<Item = &'a [Coordinate]>) -> Vec<(Coordinate, Coordinate)> {
+    let segments = paths
+        .into_iter()
+        .flat_map(|path| path.windows(2))
+        .filter(|w| !w[0].equals_2d(&w[1]))
+        .map(|w| Segment { start: w[0].clone(), end: w[1].clone(), source: 0, owner: 0 })
+        .collect();
+    node(segments, ZInterpolation::default()).into_iter().map(|s| (s.start, s.end)).collect()
+}
+
+/// Returns the two segments with the one of the first geometry first.
+fn first_source<'a>(p: &'a Segment, q: &'a Segment) -> (&'a Segment, &'a Segment) {
+    if p.source <= q.source {
+        (p, q)
+    } else {
+        (q, p)
+    }
+}
+
+/// Returns the segments split at the nodes the predicate accepts for them, found among the nodes
+/// within the distance of their envelope.
+fn split_at_nodes<F>(segments: Vec<Segment>, nodes: &[Coordinate], distance: f64, accept: F) -> Vec<Segment>
+where
+    F: Fn(&Coordinate, &Coordinate, &Coordinate) -> bool,
+{
+    let tree = STRtree::new(nodes.iter().map(|n| (Envelope::of([n]), n)).collect());
+    let splits = segments
+        .iter()
+        .map(|s| {
+            let envelope = Envelope::of([&s.start, &s.end]).expand_by(distance);
+            tree.query(&envelope)
+                .into_iter()
+                .filter(|n| !n.equals_2d(&s.start) && !n.equals_2d(&s.end) && accept(n, &s.start, &s.end))
+                .map(|&n| n.clone())
+                .collect()
+        })
+        .collect();
+    split_segments(segments, splits)
+}
+
+/// Returns the segments split at the given points, ordered along every segment.
+fn split_segments(segments: Vec<Segment>, mut splits: Vec<Vec<Coordinate>>) -> Vec<Segment> {
+    let mut result = Vec::with_capacity(segments.len());
+    for (segment, points) in segments.into_iter().zip(splits.iter_mut()) {
+        let (dx, dy) = (segment.end.x() - segment.start.x(), segment.end.y() - segment.start.y());
+        let along = |c: &Coordinate| (c.x() - segment.start.x()) * dx + (c.y() - segment.start.y()) * dy;
+        points.sort_by(|a, b| along(a).total_cmp(&along(b)));
+        let mut chain = vec![segment.start.clone()];
+        for c in points.drain(..).chain([segment.end.clone()]) {
+            if !chain.iter().any(|d| d.equals_2d(&c)) {
+                chain.push(c);
+            }
+        }
+        //the end may have been pushed before a point equal to it
+        if !chain[chain.len() - 1].equals_2d(&segment.end) {
+            chain.retain(|c| !c.equals_2d(&segment.end));
+            chain.push(segment.end.clone());
+        }
+        for w in chain.windows(2) {
+            result.push(Segment { start: w[0].clone(), end: w[1].clone(), ..segment.clone() });
+        }
+    }
+    result
+}
+
+/// Returns the segments snap rounded to the grid of the scale: the intersections are rounded to
+/// the grid, and every segment passing through the pixel around a node is bent to the node,
+/// until no segment crosses another away from the nodes. The z of the nodes follows the policy.
+fn snap_round(segments: Vec<Segment>, scale: f64, z: ZInterpolation) -> Result<Vec<Segment>, Error> {
+    let half = 0.5 / scale;
+    let mut segments: Vec<Segment> = segments.into_iter().filter(|s| !s.start.equals_2d(&s.end)).collect();
+    for _ in 0..32 {
+        let mut nodes: Vec<Coordinate> = segments.iter().flat_map(|s| [s.start.clone(), s.end.clone()]).collect();
+        let ends: Vec<(&Coordinate, &Coordinate)> = segments.iter().map(|s| (&s.start, &s.end)).collect();
+        sweep_segment_pairs(&ends, |i, j| {
+            let (p, q) = first_source(&segments[i], &segments[j]);
+            if let SegmentIntersection::Point(c) = intersection(&p.start, &p.end, &q.start, &q.end, z) {
+                nodes.push(round(&c, scale));
+            }
+            true
+        });
+        nodes.sort_by_key(Coordinate::key);
+        nodes.dedup_by(|a, b| a.equals_2d(b));
+        let count = segments.len();
+        segments = split_at_nodes(segments, &nodes, half, |n, a, b| crosses_pixel(a, b, n, half));
+        if segments.len() == count {
+            return Ok(segments);
+        }
+    }
+    Err(Error::Topology("snap rounding did not converge".to_string()))
+}
+
+/// Returns true if the segment from a to b meets the square of the given half size around the center.
+fn crosses_pixel(a: &Coordinate, b: &Coordinate, center: &Coordinate, half: f64) -> bool {
+    //clipping of the segment by the four sides of the square, after Liang and Barsky
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [
+        (-dx, a.x() - (center.x() - half)),
+        (dx, center.x() + half - a.x()),
+        (-dy, a.y() - (center.y() - half)),
+        (dy, center.y() + half - a.y()),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return false;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    t0 <= t1
+}
+
+fn segment_distance(p: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let squared = dx * dx + dy * dy;
+    let t = if squared == 0.0 { 0.0 } else { (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / squared).clamp(0.0, 1.0) };
+    (p.x() - a.x() - t * dx).hypot(p.y() - a.y() - t * dy)
+}
+
+/// Returns an error if two segments meet elsewhere than at a shared end, unless they are equal.
+fn check_noding(segments: &[Segment]) -> Result<(), Error> {
+    let ends: Vec<(&Coordinate, &Coordinate)> = segments.iter().map(|s| (&s.start, &s.end)).collect();
+    let mut found = None;
+    sweep_segment_pairs(&ends, |i, j| {
+        let (p, q) = (&segments[i], &segments[j]);
+        let is_end = |c: &Coordinate, s: &Segment| c.equals_2d(&s.start) || c.equals_2d(&s.end);
+        match intersection(&p.start, &p.end, &q.start, &q.end, ZInterpolation::Zero) {
+            SegmentIntersection::None => {}
+            SegmentIntersection::Point(c) if is_end(&c, p) && is_end(&c, q) => {}
+            SegmentIntersection::Collinear(..) if is_end(&p.start, q) && is_end(&p.end, q) => {}
+            SegmentIntersection::Point(c) | SegmentIntersection::Collinear(c, _) => found = Some(c),
+        }
+        found.is_none()
+    });
+    match found {
+        Some(c) => Err(Error::Topology(format!("found a non noded intersection at ({} {})", c.x(), c.y()))),
+        None => Ok(()),
+    }
+}
+
+/// Finds the polygons of a geometry containing a point, from the edges bounding them.
+struct Locator {
+    tree: STRtree<(usize, Coordinate, Coordinate)>,
+    envelope: Envelope,
+    source: usize,
+    owners: usize,
+}
+
+impl Locator {
+    fn new(edges: &[Edge], source: usize, owners: usize) -> Locator {
+        let mut items = Vec::new();
+        for edge in edges {
+            for &(s, owner) in &edge.sides {
+                if s == source {
+                    items.push((Envelope::of([&edge.start, &edge.end]), (owner, edge.start.clone(), edge.end.clone())));
+                }
+            }
+        }
+        let envelope = items.iter().fold(Envelope::empty(), |envelope, item| envelope.merge(&item.0));
+        Locator { tree: STRtree::new(items), envelope, source, owners }
+    }
+
+    /// Returns whether every polygon contains the left and the right side of the edge.
+    fn sides(&self, edge: &Edge) -> Vec<(bool, bool)> {
+        let p = Coordinate::new((edge.start.x() + edge.end.x()) / 2.0, (edge.start.y() + edge.end.y()) / 2.0, 0.0);
+        //the edge is a boundary of the polygons having an odd number of segments on it
+        let mut bounding = vec![false; self.owners];
+        for &(_, owner) in edge.sides.iter().filter(|s| s.0 == self.source) {
+            bounding[owner] = !bounding[owner];
+        }
+        //the crossings of a ray towards positive x from the middle of the edge, other than the edge
+        //itself, give the side just to the east of it, or just to the north of horizontal edges
+        let mut inside = vec![false; self.owners];
+        if !self.envelope.is_empty() && p.x() <= self.envelope.max_x() {
+            let ray = Envelope::new(p.x(), p.y(), self.envelope.max_x(), p.y());
+            for (owner, a, b) in self.tree.query(&ray) {
+                if a.equals_2d(&edge.start) && b.equals_2d(&edge.end) {
+                    continue;
+                }
+                if (a.y() > p.y()) != (b.y() > p.y()) {
+                    let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+                    if p.x() < x {
+                        inside[*owner] = !inside[*owner];
+                    }
+                }
+            }
+        }
+        let (dx, dy) = (edge.end.x() - edge.start.x(), edge.end.y() - edge.start.y());
+        let computed_side_is_left = if dy == 0.0 { dx > 0.0 } else { dy < 0.0 };
+        inside
+            .into_iter()
+            .zip(bounding)
+            .map(|(inside, bounding)| {
+                let other = inside != bounding;
+                if computed_side_is_left {
+                    (inside, other)
+                } else {
+                    (other, inside)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the polygons bounded by edges directed with their inside on the left.
+fn assemble(edges: Vec<(Coordinate, Coordinate)>) -> Result<Vec<Vec<Vec<Coordinate>>>, Error> {
+    let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (i, (start, _)) in edges.iter().enumerate() {
+        outgoing.entry(start.key()).or_default().push(i);
+    }
+    let angle = |from: &Coordinate, to: &Coordinate| (to.y() - from.y()).atan2(to.x() - from.x());
+
+    let mut used = vec![false; edges.len()];
+    let mut shells: Vec<Vec<Coordinate>> = Vec::new();
+    let mut holes: Vec<Vec<Coordinate>> = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let mut ring = vec![edges[first].0.clone()];
+        let mut current = first;
+        loop {
+            let (a, b) = (&edges[current].0, &edges[current].1);
+            //where the boundary touches itself, the edge bounding the same part of the inside is
+            //the first one clockwise from the way back
+            let back = angle(b, a);
+            let next = outgoing
+                .get(&b.key())
+                .into_iter()
+                .flatten()
+                .copied()
+                .min_by(|&i, &j| {
+                    let turn = |e: usize| (back - angle(b, &edges[e].1)).rem_euclid(std::f64::consts::TAU);
+                    turn(i).total_cmp(&turn(j))
+                })
+                .ok_or_else(|| Error::Topology(format!("the boundary of the result ends at ({} {})", b.x(), b.y())))?;
+            if next == first {
+                break;
+            }
+            if used[next] {
+                return Err(Error::Topology(format!("the boundary of the result branches at ({} {})", b.x(), b.y())));
+            }
+            used[next] = true;
+            ring.push(b.clone());
+            current = next;
+        }
+        for mut ring in split_at_touches(ring) {
+            //rings start at their lowest left vertex, so the result does not depend on hashing
+            let lowest = (0..ring.len())
+                .min_by(|&i, &j| ring[i].x().total_cmp(&ring[j].x()).then(ring[i].y().total_cmp(&ring[j].y())))
+                .unwrap_or(0);
+            ring.rotate_left(lowest);
+            ring.push(ring[0].clone());
+            let area = ring_signed_area(&ring);
+            if area > 0.0 {
+                shells.push(ring);
+            } else if area < 0.0 {
+                holes.push(ring);
+            }
+        }
+    }
+
+    //every hole goes to the smallest shell around it
+    let areas: Vec<f64> = shells.iter().map(|shell| ring_signed_area(shell)).collect();
+    let mut polygons: Vec<Vec<Vec<Coordinate>>> = shells.into_iter().map(|shell| vec![shell]).collect();
+    for hole in holes {
+        let (a, b) = (&hole[0], &hole[1]);
+        let inner = Coordinate::new((a.x() + b.x()) / 2.0, (a.y() + b.y()) / 2.0, 0.0);
+        let shell = (0..polygons.len())
+            .filter(|&i| locate_in_ring(&inner, &polygons[i][0]) == Location::Interior)
+            .min_by(|&i, &j| areas[i].total_cmp(&areas[j]))
+            .ok_or_else(|| Error::Topology(format!("the hole at ({} {}) has no shell", a.x(), a.y())))?;
+        polygons[shell].push(hole);
+    }
+    let first = |ring: &Vec<Coordinate>| (ring[0].x(), ring[0].y());
+    for polygon in &mut polygons {
+        polygon[1..].sort_by(|p, q| first(p).0.total_cmp(&first(q).0).then(first(p).1.total_cmp(&first(q).1)));
+    }
+    polygons.sort_by(|p, q| first(&p[0]).0.total_cmp(&first(&q[0]).0).then(first(&p[0]).1.total_cmp(&first(&q[0]).1)));
+    Ok(polygons)
+}
+
+/// Returns the open ring split into simple open rings at the vertices it passes through more than
+/// once. The loops cut off a shell this way are its holes, turning the other way round.
+fn split_at_touches(ring: Vec<Coordinate>) -> Vec<Vec<Coordinate>> {
+    let mut rings = Vec::new();
+    let mut stack: Vec<Coordinate> = Vec::with_capacity(ring.len());
+    let mut positions: HashMap<Key, usize> = HashMap::new();
+    for c in ring {
+        if let Some(&i) = positions.get(&c.key()) {
+            //the vertices since the previous visit form a loop, which is closed at that visit
+            let looped = stack.split_off(i + 1);
+            for v in &looped {
+                positions.remove(&v.key());
+            }
+            let mut closed = vec![stack[i].clone()];
+            closed.extend(looped);
+            rings.push(closed);
+        } else {
+            positions.insert(c.key(), stack.len());
+            stack.push(c);
+        }
+    }
+    rings.push(stack);
+    rings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Vec<Coordinate>> {
+        vec![vec![coord!(x, y), coord!(x + size, y), coord!(x + size, y + size), coord!(x, y + size), coord!(x, y)]]
+    }
+
+    #[test]
+    fn test_holes_touching_and_overlapping_parts() {
+        //a square with a hole, and a collection of two overlapping squares crossing the hole
+        let mut frame = square(0.0, 0.0, 10.0);
+        frame.push(vec![coord!(3, 3), coord!(3, 7), coord!(7, 7), coord!(7, 3), coord!(3, 3)]);
+        let a = Geometry::Polygon { coordinates: frame };
+        let b = Geometry::GeometryCollection { geometries: vec![
+            Geometry::Polygon { coordinates: square(2.0, 4.0, 3.0) },
+            Geometry::Polygon { coordinates: square(4.0, 4.0, 2.0) },
+        ] };
+        let union = overlay(&a, &b, OverlayOp::Union).unwrap();
+        assert_eq!(union.area(), 100.0 - 16.0 + 8.0);
+        let Geometry::Polygon { coordinates } = &union else { panic!("{:?}", union) };
+        assert_eq!(coordinates.len(), 2);
+        assert_eq!(overlay(&a, &b, OverlayOp::Intersection).unwrap().area(), 3.0);
+        assert_eq!(overlay(&b, &a, OverlayOp::Difference).unwrap().area(), 8.0);
+        assert_eq!(overlay(&a, &b, OverlayOp::SymDifference).unwrap().area(), 84.0 - 3.0 + 8.0);
+
+        //squares touching at a corner stay two polygons
+        let (c, d) = (Geometry::Polygon { coordinates: square(0.0, 0.0, 1.0) }, Geometry::Polygon { coordinates: square(1.0, 1.0, 1.0) });
+        let Geometry::MultiPolygon { coordinates } = overlay(&c, &d, OverlayOp::Union).unwrap() else { panic!() };
+        assert_eq!(coordinates.len(), 2);
+        assert_eq!(overlay(&c, &d, OverlayOp::Intersection).unwrap(), Geometry::MultiPolygon { coordinates: vec![] });
+        assert_eq!(overlay(&c, &Geometry::MultiPolygon { coordinates: vec![] }, OverlayOp::Union).unwrap(), c);
+    }
+
+    #[test]
+    fn test_nearly_coincident_edges() {
+        //a square and the same square turned by a tiny angle about a point far away, so that
+        //their edges cross at very flat angles
+        let (sin, cos) = 1e-13f64.sin_cos();
+        let turn = |c: &Coordinate| Coordinate::new(1e3 + (c.x() - 1e3) * cos - c.y() * sin, (c.x() - 1e3) * sin + c.y() * cos, 0.0);
+        let a = square(0.1, 0.3, 10.7);
+        let b = vec![a[0].iter().map(turn).collect::<Vec<_>>()];
+        let (a, b) = (Geometry::Polygon { coordinates: a }, Geometry::Polygon { coordinates: b });
+        for op in [OverlayOp::Intersection, OverlayOp::Union, OverlayOp::Difference, OverlayOp::SymDifference] {
+            let result = overlay(&a, &b, op).unwrap();
+            assert!(result.is_valid(), "{:?}", op);
+            let expected = if matches!(op, OverlayOp::Intersection | OverlayOp::Union) { a.area() } else { 0.0 };
+            assert!((result.area() - expected).abs() < 1e-6, "{:?} {}", op, result.area());
+        }
+
+        //a star and the same star with every other vertex moved by a few ulps, where the floating
+        //point intersections are inconsistent and the overlay falls back to snapping
+        let star: Vec<Coordinate> = (0..=17)
+            .map(|i| {
+                let angle = (i % 17) as f64 / 17.0 * std::f64::consts::TAU;
+                let radius = 10.0 * (0.5 + 0.1 * ((i % 17 * 7 + 2) % 5) as f64);
+                Coordinate::new(radius * angle.cos(), radius * angle.sin(), 0.0)
+            })
+            .collect();
+        let moved: Vec<Coordinate> = star
+            .iter()
+            .enumerate()
+            .map(|(i, c)| if i % 17 % 2 == 0 { Coordinate::new(c.x() + 6e-14, c.y() - 5e-14, 0.0) } else { c.clone() })
+            .collect();
+        let inputs = [vec![vec![star.clone()]], vec![vec![moved.clone()]]];
+        assert!(matches!(compute(&inputs, OverlayOp::Union, Noding::Floating, ZInterpolation::Average), Err(Error::Topology(_))));
+        let (c, d) = (Geometry::Polygon { coordinates: vec![star] }, Geometry::Polygon { coordinates: vec![moved] });
+        let union = overlay(&c, &d, OverlayOp::Union).unwrap();
+        assert!(union.is_valid());
+        assert!((union.area() - c.area()).abs() < 1e-9);
+
+        //a fixed precision puts every vertex on its grid
+        let options = OverlayOptions { precision_model: PrecisionModel::Fixed(10.0), ..Default::default() };
+        let result = overlay_with(&a, &b, OverlayOp::Union, &options).unwrap();
+        assert!(result.coordinates().iter().all(|c| (c.x() * 10.0 - (c.x() * 10.0).round()).abs() < 1e-9));
+        assert!((result.area() - a.area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unary_union_matches_sequential_union() {
+        //a grid of overlapping discs, given in an order that jumps around
+        let discs: Vec<Geometry> = (0..36)
+            .map(|i| (i * 7) % 36)
+            .map(|i| crate::shapes::circle(&coord!((i % 6) as f64 * 1.5, (i / 6) as f64 * 1.5), 1.0, 24))
+            .collect();
+        let cascaded = unary_union(&Geometry::GeometryCollection { geometries: discs.clone() }).unwrap();
+        let mut sequential = discs[0].clone();
+        for disc in &discs[1..] {
+            sequential = overlay(&sequential, disc, OverlayOp::Union).unwrap();
+        }
+        assert!((cascaded.area() - sequential.area()).abs() < 1e-9);
+        assert!(matches!(cascaded, Geometry::Polygon { .. }));
+        assert!(overlay(&cascaded, &sequential, OverlayOp::SymDifference).unwrap().area() < 1e-9);
+    }
+
+    #[test]
+    fn test_self_union_of_mixed_collection() {
+        //a line crossing itself, a point on it, a point inside of the square and one away from all
+        let collection = Geometry::GeometryCollection { geometries: vec![
+            Geometry::LineString { coordinates: vec![coord!(10, 0), coord!(14, 4), coord!(14, 0), coord!(10, 4)] },
+            Geometry::MultiPoint { coordinates: vec![coord!(11, 1), coord!(1, 1), coord!(20, 20), coord!(20, 20)] },
+            Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] },
+        ] };
+        let Geometry::GeometryCollection { geometries } = self_union(&collection).unwrap() else {
+            panic!("Expected a geometry collection")
+        };
+        assert_eq!(geometries.len(), 3);
+        assert_eq!(geometries[0].area(), 4.0);
+        //the line is split where it crosses itself
+        assert_eq!(geometries[1].num_components(), 3);
+        assert!((geometries[1].length() - (8.0 * 2f64.sqrt() + 4.0)).abs() < 1e-12);
+        assert_eq!(geometries[2], Geometry::MultiPoint { coordinates: vec![coord!(20, 20)] });
+        assert_eq!(self_union(&Geometry::GeometryCollection { geometries: vec![] }).unwrap().num_components(), 0);
+    }
+
+    #[test]
+    fn test_clip_of_mixed_collection() {
+        let window = Envelope::new(0.0, 0.0, 10.0, 10.0);
+        let collection = Geometry::GeometryCollection { geometries: vec![
+            Geometry::Polygon { coordinates: vec![vec![coord!(5, 5), coord!(15, 5), coord!(15, 15), coord!(5, 15), coord!(5, 5)]] },
+            //a line rising from 0 to 20 while crossing the window, and one touching its corner
+            Geometry::LineString { coordinates: vec![coord!(-5, 2, 0), coord!(15, 2, 20)] },
+            Geometry::LineString { coordinates: vec![coord!(9, 11), coord!(11, 9)] },
+            Geometry::MultiPoint { coordinates: vec![coord!(10, 0), coord!(11, 0)] },
+        ] };
+        let Geometry::GeometryCollection { geometries } = clip(&collection, &window).unwrap() else {
+            panic!("Expected a geometry collection")
+        };
+        assert_eq!(geometries[0].area(), 25.0);
+        assert_eq!(geometries[1], Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 2, 5), coord!(10, 2, 15)]] });
+        assert_eq!(geometries[2], Geometry::MultiPoint { coordinates: vec![coord!(10, 0)] });
+        assert_eq!(clip(&collection, &Envelope::empty()).unwrap().num_components(), 0);
+    }
+
+    #[test]
+    fn test_z_interpolation_of_overlay_vertices() {
+        //a plate at a height of 10 and one at 20 crossing it, whose boundaries cross at (2 1) and (1 2)
+        let plate = |x: f64, z: f64| Geometry::Polygon { coordinates: vec![vec![
+            coord!(x, x, z), coord!(x + 2.0, x, z), coord!(x + 2.0, x + 2.0, z), coord!(x, x + 2.0, z), coord!(x, x, z)
+        ]] };
+        let (a, b) = (plate(0.0, 10.0), plate(1.0, 20.0));
+        let crossings = |z_interpolation: ZInterpolation, precision_model: PrecisionModel| -> Vec<f64> {
+            let options = OverlayOptions { z_interpolation, precision_model, ..Default::default() };
+            let result = overlay_with(&a, &b, OverlayOp::Intersection, &options).unwrap();
+            result.coords_iter().filter(|c| c.equals_2d(&coord!(2, 1)) || c.equals_2d(&coord!(1, 2))).map(|c| c.z()).collect()
+        };
+        for precision_model in [PrecisionModel::Floating, PrecisionModel::Fixed(10.0)] {
+            assert_eq!(crossings(ZInterpolation::Average, precision_model), [15.0, 15.0]);
+            assert_eq!(crossings(ZInterpolation::First, precision_model), [10.0, 10.0]);
+            assert_eq!(crossings(ZInterpolation::Zero, precision_model), [0.0, 0.0]);
+        }
+        //the policy follows the order of the geometries, not of their segments
+        let options = OverlayOptions { z_interpolation: ZInterpolation::First, ..Default::default() };
+        let swapped = overlay_with(&b, &a, OverlayOp::Intersection, &options).unwrap();
+        assert!(swapped.coords_iter().filter(|c| c.equals_2d(&coord!(2, 1))).all(|c| c.z() == 20.0));
+
+        //so do the new ends of clipped lines
+        let line = Geometry::LineString { coordinates: vec![coord!(-5, 2, 0), coord!(15, 2, 20)] };
+        let window = Envelope::new(0.0, 0.0, 10.0, 10.0);
+        let options = OverlayOptions { z_interpolation: ZInterpolation::Zero, ..Default::default() };
+        assert_eq!(clip_with(&line, &window, &options).unwrap(), Geometry::MultiLineString { coordinates: vec![vec![coord!(0, 2), coord!(10, 2)]] });
+    }
+
+    #[test]
+    fn test_split_of_lines() {
+        //a sloping line crossing a square, a bend touching its boundary and a loop crossing itself
+        let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)]] };
+        let lines = Geometry::MultiLineString { coordinates: vec![
+            vec![coord!(-2, 1, 0), coord!(6, 1, 8)],
+            vec![coord!(5, 5), coord!(4, 3), coord!(5, 2)],
+            vec![coord!(1, 3), coord!(3, 2), coord!(3, 3), coord!(1, 2)],
+        ] };
+        let pieces = split(&lines, &square);
+        assert_eq!(pieces, Geometry::MultiLineString { coordinates: vec![
+            vec![coord!(-2, 1, 0), coord!(0, 1, 1)],
+            vec![coord!(0, 1, 1), coord!(4, 1, 3)],
+            vec![coord!(4, 1, 3), coord!(6, 1, 8)],
+            vec![coord!(5, 5), coord!(4, 3)],
+            vec![coord!(4, 3), coord!(5, 2)],
+            vec![coord!(1, 3), coord!(3, 2), coord!(3, 3), coord!(1, 2)],
+        ] });
+        //the new vertices of a line raised above the blade take its z only
+        let options = OverlayOptions { z_interpolation: ZInterpolation::First, ..Default::default() };
+        let raised = Geometry::LineString { coordinates: vec![coord!(-2, 1, 10), coord!(6, 1, 18)] };
+        let ends: Vec<f64> = split_with(&raised, &square, &options).coords_iter().map(|c| c.z()).collect();
+        assert_eq!(ends, [10.0, 12.0, 12.0, 16.0, 16.0, 18.0]);
+        //nothing to cut with
+        assert_eq!(split(&raised, &Geometry::MultiPoint { coordinates: vec![coord!(0, 1)] }).num_components(), 1);
+    }
+}
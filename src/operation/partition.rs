@@ -0,0 +1,274 @@
+//! Operations run tile by tile over large layers, such as the union or the intersection of
+//! national datasets, which would be too slow or too large to compute in one piece.
+
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+use crate::operation::distance::point_segment_2d;
+use crate::operation::overlay::{clip, combine, unary_union};
+use crate::operation::{extract, points};
+use crate::trace;
+
+/// A tile of a partitioned operation, with the geometries of every layer it has to process.
+#[derive(Debug, Clone)]
+pub struct Tile<'a> {
+    /// The column and the row of the tile, from the bottom left of the extent of the layers.
+    pub position: (usize, usize),
+    /// The part of the extent the tile is responsible for. The result of the tile is clipped to
+    /// it, so the results of neighbouring tiles only meet along their seams.
+    pub envelope: Envelope,
+    /// The envelope grown by the overlap, which the geometries of the tile are selected with.
+    pub extent: Envelope,
+    layers: Vec<Vec<&'a Geometry>>,
+}
+
+impl<'a> Tile<'a> {
+    /// Returns the geometries of a layer whose envelope meets the extent of the tile, whole and
+    /// in the order of the layer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such layer.
+    pub fn layer(&self, layer: usize) -> &[&'a Geometry] {
+        &self.layers[layer]
+    }
+
+    /// Returns the geometries of the first layer. See [`Tile::layer`].
+    pub fn geometries(&self) -> &[&'a Geometry] {
+        self.layer(0)
+    }
+}
+
+/// Returns the result of an operation run over square tiles covering the layers, merged into a
+/// single geometry.
+///
+/// The extent of the layers is split into tiles of the given size, and the geometries of every
+/// layer are selected for each tile through an index by the extent of the tile, its envelope
+/// grown by the overlap, so operations looking around a place, as buffers, see all they need.
+/// The result of every tile is clipped to its envelope, and the parts of the results touching
+/// the seams between tiles are merged: polygons are unioned, lines are kept once and merged where
+/// exactly two meet, and points are kept once, while the vertices the seams added along straight
+/// edges are removed. Tiles without geometries are skipped. With the `parallel` feature the tiles
+/// are processed on all cores. The result is, as for [`crate::operation::overlay::clip`], the
+/// only non empty one of the polygons, lines and points, or a collection of them.
+///
+/// # Arguments
+///
+/// * `layers` - The geometries of every layer the operation takes.
+/// * `tile_size` - The width and height of the tiles.
+/// * `overlap` - The distance the extent of every tile goes beyond its envelope.
+/// * `operation` - The operation run on every tile, which should return the same result as over
+///   the whole layers within the envelope of the tile.
+///
+/// # Panics
+///
+/// Panics if the size of the tiles is not positive or the overlap is negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::unary_union;
+/// use geoms::operation::partitioned;
+///
+/// let square = |x: f64, y: f64| Geometry::Polygon { coordinates: vec![vec![
+///     coord!(x, y), coord!(x + 3.0, y), coord!(x + 3.0, y + 3.0), coord!(x, y + 3.0), coord!(x, y)
+/// ]] };
+/// //a row of overlapping parcels dissolved over tiles of 4 by 4
+/// let parcels: Vec<Geometry> = (0..5).map(|i| square(2.0 * i as f64, 0.0)).collect();
+/// let union = partitioned(&[&parcels], 4.0, 0.0, |tile| {
+///     unary_union(&Geometry::GeometryCollection { geometries: tile.geometries().iter().map(|g| (*g).clone()).collect() })
+/// })
+/// .unwrap();
+/// //the corners of the parcels stay, but not the crossings of the seams at x = 4 and x = 8
+/// assert_eq!(union.as_text(), "MULTIPOLYGON (((0 0, 2 0, 3 0, 5 0, 6 0, 7 0, 9 0, 11 0, 11 3, 9 3, 7 3, 6 3, 5 3, 3 3, 2 3, 0 3, 0 0)))");
+/// ```
+pub fn partitioned<'a, F>(layers: &[&'a [Geometry]], tile_size: f64, overlap: f64, operation: F) -> Result<Geometry, Error>
+where
+    F: Fn(&Tile<'a>) -> Result<Geometry, Error> + Sync,
+{
+    if tile_size.is_nan() || tile_size <= 0.0 {
+        panic!("The size of the tiles must be positive");
+    }
+    if overlap.is_nan() || overlap < 0.0 {
+        panic!("The overlap of the tiles cannot be negative");
+    }
+    trace::span!("partitioned", layers = layers.len(), tile_size = tile_size);
+    let extent = layers.iter().flat_map(|layer| layer.iter()).fold(Envelope::empty(), |e, g| e.merge(&g.envelope()));
+    if extent.is_empty() {
+        return Ok(combine(Geometry::MultiPolygon { coordinates: vec![] }, vec![], vec![]));
+    }
+    let columns = ((extent.width() / tile_size).ceil() as usize).max(1);
+    let rows = ((extent.height() / tile_size).ceil() as usize).max(1);
+    let trees: Vec<STRtree<()>> = layers
+        .iter()
+        .map(|layer| STRtree::new(layer.iter().map(|g| (g.envelope(), ())).collect()))
+        .collect();
+    let mut tiles = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            let (x, y) = (extent.min_x() + column as f64 * tile_size, extent.min_y() + row as f64 * tile_size);
+            let envelope = Envelope::new(x, y, x + tile_size, y + tile_size);
+            let grown = envelope.expand_by(overlap);
+            let selected: Vec<Vec<&Geometry>> = layers
+                .iter()
+                .zip(&trees)
+                .map(|(layer, tree)| {
+                    let mut indices = tree.query_indices(&grown);
+                    indices.sort_unstable();
+                    indices.into_iter().map(|i| &layer[i]).collect()
+                })
+                .collect();
+            if selected.iter().any(|layer| !layer.is_empty()) {
+                tiles.push(Tile { position: (column, row), envelope, extent: grown, layers: selected });
+            }
+        }
+    }
+    trace::event!("tiled", tiles = tiles.len(), columns = columns, rows = rows);
+    let run = |tile: &Tile<'a>| -> Result<Geometry, Error> { clip(&operation(tile)?, &tile.envelope) };
+    #[cfg(feature = "parallel")]
+    let results: Vec<Geometry> = {
+        use rayon::prelude::*;
+        tiles.par_iter().map(run).collect::<Result<_, _>>()?
+    };
+    #[cfg(not(feature = "parallel"))]
+    let results: Vec<Geometry> = tiles.iter().map(run).collect::<Result<_, _>>()?;
+
+    let seams = Seams { extent, tile_size, columns, rows };
+    let (mut polygons, mut seam_polygons) = (Vec::new(), Vec::new());
+    let (mut lines, mut seam_lines) = (Vec::new(), Vec::new());
+    let (mut points, mut seam_points) = (Vec::new(), Vec::new());
+    for (tile, result) in tiles.iter().zip(results) {
+        for polygon in result.polygons() {
+            match seams.inside(&tile.envelope, &polygon[0]) {
+                true => polygons.push(polygon.clone()),
+                false => seam_polygons.push(polygon.clone()),
+            }
+        }
+        for line in extract::lines(&result) {
+            match seams.inside(&tile.envelope, &line) {
+                true => lines.push(line),
+                false => seam_lines.push(line),
+            }
+        }
+        for point in extract::points(&result) {
+            match seams.inside(&tile.envelope, std::slice::from_ref(&point)) {
+                true => points.push(point),
+                false => seam_points.push(point),
+            }
+        }
+    }
+    if !seam_polygons.is_empty() {
+        let merged = unary_union(&Geometry::MultiPolygon { coordinates: seam_polygons })?;
+        polygons.extend(
+            merged.polygons().map(|rings| rings.iter().map(|ring| seams.remove_vertices(ring, true)).collect()),
+        );
+    }
+    if !seam_lines.is_empty() {
+        let merged = unary_union(&Geometry::MultiLineString { coordinates: seam_lines })?;
+        lines.extend(extract::lines(&merged).iter().map(|line| seams.remove_vertices(line, false)));
+    }
+    points.extend(points::dedup(&seam_points));
+    Ok(combine(Geometry::MultiPolygon { coordinates: polygons }, lines, points))
+}
+
+/// The lines between the tiles of a partition.
+struct Seams {
+    extent: Envelope,
+    tile_size: f64,
+    columns: usize,
+    rows: usize,
+}
+
+impl Seams {
+    /// Returns the distance under which a coordinate is taken to be on a seam.
+    fn tolerance(&self) -> f64 {
+        self.tile_size * 1e-9
+    }
+
+    /// Returns true if the coordinates are away from the boundary of the envelope of a tile.
+    fn inside(&self, envelope: &Envelope, coordinates: &[Coordinate]) -> bool {
+        let inner = envelope.expand_by(-self.tolerance());
+        coordinates.iter().all(|c| {
+            c.x() > inner.min_x() && c.x() < inner.max_x() && c.y() > inner.min_y() && c.y() < inner.max_y()
+        })
+    }
+
+    /// Returns true if the coordinate is on a line between two columns or two rows of tiles.
+    fn on_seam(&self, c: &Coordinate) -> bool {
+        let near = |value: f64, start: f64, count: usize| {
+            (1..count).any(|i| (value - (start + i as f64 * self.tile_size)).abs() <= self.tolerance())
+        };
+        near(c.x(), self.extent.min_x(), self.columns) || near(c.y(), self.extent.min_y(), self.rows)
+    }
+
+    /// Returns the path without the vertices on the seams that lie on the straight line between
+    /// their neighbours.
+    fn remove_vertices(&self, path: &[Coordinate], closed: bool) -> Vec<Coordinate> {
+        let n = if closed { path.len() - 1 } else { path.len() };
+        if n < 3 {
+            return path.to_vec();
+        }
+        let mut kept: Vec<Coordinate> = (0..n)
+            .filter(|&i| {
+                let (prev, next) = match (closed, i) {
+                    (false, 0) => return true,
+                    (false, i) if i == n - 1 => return true,
+                    _ => (&path[(i + n - 1) % n], &path[(i + 1) % n]),
+                };
+                !self.on_seam(&path[i]) || point_segment_2d(&path[i], prev, next) > self.tolerance()
+            })
+            .map(|i| path[i].clone())
+            .collect();
+        if closed {
+            if kept.len() < 3 {
+                return path.to_vec();
+            }
+            kept.push(kept[0].clone());
+        }
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_intersection_of_two_layers_over_many_tiles() {
+        let square = |x: f64, y: f64, size: f64| Geometry::Polygon { coordinates: vec![vec![
+            coord!(x, y), coord!(x + size, y), coord!(x + size, y + size), coord!(x, y + size), coord!(x, y)
+        ]] };
+        //a grid of parcels and a few large zones crossing several tiles
+        let parcels: Vec<Geometry> = (0..10).flat_map(|i| (0..10).map(move |j| square(i as f64 * 2.0, j as f64 * 2.0, 1.5))).collect();
+        let zones = vec![square(1.0, 1.0, 7.0), square(10.5, 3.5, 6.0)];
+        let intersect = |tile: &Tile| {
+            let parcels = unary_union(&Geometry::GeometryCollection { geometries: tile.layer(0).iter().map(|g| (*g).clone()).collect() })?;
+            let zones = unary_union(&Geometry::GeometryCollection { geometries: tile.layer(1).iter().map(|g| (*g).clone()).collect() })?;
+            parcels.intersection(&zones)
+        };
+        let tiled = partitioned(&[&parcels, &zones], 3.0, 0.0, intersect).unwrap();
+        let whole = partitioned(&[&parcels, &zones], 100.0, 0.0, intersect).unwrap();
+        assert!((tiled.area() - whole.area()).abs() < 1e-9);
+        assert_eq!(tiled.num_components(), whole.num_components());
+        //the seams leave no extra vertices
+        assert_eq!(tiled.coords_count(), whole.coords_count());
+
+        //points on the seams are kept once, and lines along them too
+        let network = vec![
+            Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(6, 0), coord!(6, 9)] },
+            Geometry::MultiPoint { coordinates: vec![coord!(3, 3), coord!(4, 4)] },
+        ];
+        let copied = partitioned(&[&network], 3.0, 1.0, |tile| {
+            Ok(Geometry::GeometryCollection { geometries: tile.geometries().iter().map(|g| (*g).clone()).collect() })
+        })
+        .unwrap();
+        assert_eq!(copied.as_text(), "GEOMETRYCOLLECTION (MULTILINESTRING ((0 0, 6 0, 6 9)), MULTIPOINT ((4 4), (3 3)))");
+        assert_eq!(partitioned(&[], 1.0, 0.0, |_| unreachable!()).unwrap().num_components(), 0);
+    }
+}
@@ -0,0 +1,263 @@
+//! Pipelines of cleaning and generalization stages, configured once and applied to geometries or
+//! whole layers, reporting what every stage did.
+
+use crate::error::Error;
+use crate::feature::{Feature, FeatureCollection};
+use crate::geometry::Geometry;
+
+/// A stage of a [`Pipeline`], applying one of the operations on geometries.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Stage {
+    /// Rounds the coordinates to a grid of the size, see [`Geometry::snap_to_grid`].
+    SnapToGrid(f64),
+    /// Removes the spikes narrower than the angle in radians, see [`Geometry::remove_spikes`].
+    RemoveSpikes(f64),
+    /// Simplifies with the tolerance, see [`Geometry::simplify`].
+    Simplify(f64),
+    /// Squares the outlines of polygons with the angle tolerance in radians, see
+    /// [`Geometry::regularize`].
+    Regularize(f64),
+    /// Removes the holes smaller than the area, see [`Geometry::remove_small_holes`].
+    RemoveSmallHoles(f64),
+    /// Removes the polygons smaller than the area, see [`Geometry::filter_by_area`].
+    FilterByArea(f64),
+    /// Rebuilds polygons from their noded rings, see [`Geometry::clean`].
+    MakeValid,
+}
+
+impl Stage {
+    /// Returns the geometry processed by the stage.
+    pub fn apply(&self, geometry: &Geometry) -> Result<Geometry, Error> {
+        Ok(match *self {
+            Stage::SnapToGrid(size) => geometry.snap_to_grid(size),
+            Stage::RemoveSpikes(angle_tolerance) => geometry.remove_spikes(angle_tolerance),
+            Stage::Simplify(tolerance) => geometry.simplify(tolerance),
+            Stage::Regularize(angle_tolerance) => geometry.regularize(angle_tolerance),
+            Stage::RemoveSmallHoles(min_area) => geometry.remove_small_holes(min_area),
+            Stage::FilterByArea(min_area) => geometry.filter_by_area(min_area),
+            Stage::MakeValid => geometry.clean()?,
+        })
+    }
+}
+
+/// What a stage of a pipeline did to the geometries it processed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct StageReport {
+    /// The stage.
+    pub stage: Stage,
+    /// The number of geometries processed.
+    pub geometries: usize,
+    /// The number of geometries the stage changed.
+    pub changed: usize,
+    /// The number of geometries with coordinates the stage left without any.
+    pub emptied: usize,
+    /// The number of coordinates of the geometries before the stage.
+    pub vertices_before: usize,
+    /// The number of coordinates of the geometries after the stage.
+    pub vertices_after: usize,
+}
+
+impl StageReport {
+    fn new(stage: Stage) -> Self {
+        StageReport { stage, geometries: 0, changed: 0, emptied: 0, vertices_before: 0, vertices_after: 0 }
+    }
+
+    /// Adds the counts of another report of the same stage.
+    fn merge(&mut self, other: &StageReport) {
+        self.geometries += other.geometries;
+        self.changed += other.changed;
+        self.emptied += other.emptied;
+        self.vertices_before += other.vertices_before;
+        self.vertices_after += other.vertices_after;
+    }
+}
+
+/// A sequence of stages applied in order to geometries, built by chaining the stages.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::pipeline::Pipeline;
+///
+/// let pipeline = Pipeline::new().snap_to_grid(0.5).remove_spikes(0.1).simplify(1.0).make_valid();
+/// let parcel = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(5, 0.2), coord!(10, 0), coord!(10.1, 10), coord!(5, 10), coord!(5, 20), coord!(4.9, 10), coord!(0, 10), coord!(0, 0)
+/// ]] };
+/// let (cleaned, reports) = pipeline.run(&parcel).unwrap();
+/// assert_eq!(cleaned.as_text(), "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))");
+/// assert_eq!(reports.len(), 4);
+/// //the spike collapses to a line when snapped, and is removed by the next stage
+/// assert_eq!((reports[1].vertices_before, reports[1].vertices_after), (9, 7));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    /// Creates a pipeline without stages, leaving the geometries as they are.
+    pub fn new() -> Self {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Returns the pipeline followed by the stage.
+    pub fn stage(mut self, stage: Stage) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// Returns the pipeline followed by the rounding of coordinates to a grid of the size.
+    pub fn snap_to_grid(self, size: f64) -> Self {
+        self.stage(Stage::SnapToGrid(size))
+    }
+
+    /// Returns the pipeline followed by the removal of spikes narrower than the angle in radians.
+    pub fn remove_spikes(self, angle_tolerance: f64) -> Self {
+        self.stage(Stage::RemoveSpikes(angle_tolerance))
+    }
+
+    /// Returns the pipeline followed by the simplification with the tolerance.
+    pub fn simplify(self, tolerance: f64) -> Self {
+        self.stage(Stage::Simplify(tolerance))
+    }
+
+    /// Returns the pipeline followed by the regularization of polygon outlines with the angle
+    /// tolerance in radians.
+    pub fn regularize(self, angle_tolerance: f64) -> Self {
+        self.stage(Stage::Regularize(angle_tolerance))
+    }
+
+    /// Returns the pipeline followed by the removal of holes smaller than the area.
+    pub fn remove_small_holes(self, min_area: f64) -> Self {
+        self.stage(Stage::RemoveSmallHoles(min_area))
+    }
+
+    /// Returns the pipeline followed by the removal of polygons smaller than the area.
+    pub fn filter_by_area(self, min_area: f64) -> Self {
+        self.stage(Stage::FilterByArea(min_area))
+    }
+
+    /// Returns the pipeline followed by the rebuilding of polygons from their noded rings.
+    pub fn make_valid(self) -> Self {
+        self.stage(Stage::MakeValid)
+    }
+
+    /// Returns the stages of the pipeline, in the order they are applied.
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// Returns the geometry processed by every stage in turn, or the error of the first stage
+    /// that failed.
+    pub fn apply(&self, geometry: &Geometry) -> Result<Geometry, Error> {
+        self.stages.iter().try_fold(geometry.clone(), |geometry, stage| stage.apply(&geometry))
+    }
+
+    /// Returns the geometry processed by every stage in turn along with a report of every stage,
+    /// or the error of the first stage that failed.
+    pub fn run(&self, geometry: &Geometry) -> Result<(Geometry, Vec<StageReport>), Error> {
+        let mut reports = Vec::with_capacity(self.stages.len());
+        let mut current = geometry.clone();
+        for stage in &self.stages {
+            let processed = stage.apply(&current)?;
+            let (before, after) = (current.coords_count(), processed.coords_count());
+            reports.push(StageReport {
+                stage: *stage,
+                geometries: 1,
+                changed: usize::from(processed != current),
+                emptied: usize::from(before > 0 && after == 0),
+                vertices_before: before,
+                vertices_after: after,
+            });
+            current = processed;
+        }
+        Ok((current, reports))
+    }
+
+    /// Returns the features with their geometries processed by the pipeline, keeping their
+    /// properties, along with the reports of every stage summed over the features, and the index
+    /// and the error of every feature that failed, which is left out of the result and of the
+    /// reports. With the `parallel` feature the features are processed on all cores.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::geometry::Geometry;
+    /// use geoms::feature::{Feature, FeatureCollection};
+    /// use geoms::operation::pipeline::Pipeline;
+    ///
+    /// let square = |size: f64| Geometry::Polygon { coordinates: vec![vec![
+    ///     coord!(0, 0), coord!(size, 0), coord!(size, size), coord!(0, size), coord!(0, 0)
+    /// ]] };
+    /// let layer = FeatureCollection::new(vec![Feature::new(square(10.0)), Feature::new(square(0.1))]);
+    /// let (cleaned, reports, errors) = Pipeline::new().snap_to_grid(1.0).apply_collection(&layer);
+    /// assert_eq!(cleaned.len(), 2);
+    /// assert_eq!((reports[0].geometries, reports[0].changed, reports[0].emptied), (2, 1, 1));
+    /// assert!(errors.is_empty());
+    /// ```
+    pub fn apply_collection(&self, collection: &FeatureCollection) -> (FeatureCollection, Vec<StageReport>, Vec<(usize, Error)>) {
+        let run = |feature: &Feature| -> Result<(Feature, Vec<StageReport>), Error> {
+            let (geometry, reports) = self.run(feature.geometry())?;
+            let mut processed = feature.clone();
+            processed.set_geometry(geometry);
+            Ok((processed, reports))
+        };
+        #[cfg(feature = "parallel")]
+        let results: Vec<Result<(Feature, Vec<StageReport>), Error>> = {
+            use rayon::prelude::*;
+            collection.features().par_iter().map(run).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<Result<(Feature, Vec<StageReport>), Error>> = collection.features().iter().map(run).collect();
+
+        let mut processed = FeatureCollection::default();
+        let mut totals: Vec<StageReport> = self.stages.iter().map(|&stage| StageReport::new(stage)).collect();
+        let mut errors = Vec::new();
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok((feature, reports)) => {
+                    processed.push(feature);
+                    totals.iter_mut().zip(&reports).for_each(|(total, report)| total.merge(report));
+                }
+                Err(error) => errors.push((i, error)),
+            }
+        }
+        (processed, totals, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_reports_of_layer() {
+        let layer = FeatureCollection::new(vec![
+            Feature::new(Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0.01), coord!(2, 0)] }).with_property("id", 1.0),
+            Feature::new(Geometry::Point { coordinates: coord!(3, 3) }),
+        ]);
+        let pipeline = Pipeline::new().simplify(0.1).stage(Stage::FilterByArea(1.0));
+        assert_eq!(pipeline.stages(), [Stage::Simplify(0.1), Stage::FilterByArea(1.0)]);
+        let (processed, reports, errors) = pipeline.apply_collection(&layer);
+        assert!(errors.is_empty());
+        assert_eq!(processed.features()[0].geometry().coords_count(), 2);
+        assert_eq!(processed.features()[0].property("id"), layer.features()[0].property("id"));
+        assert_eq!(reports[0], StageReport {
+            stage: Stage::Simplify(0.1),
+            geometries: 2,
+            changed: 1,
+            emptied: 0,
+            vertices_before: 4,
+            vertices_after: 3,
+        });
+        assert_eq!(reports[1].changed, 0);
+        assert_eq!(Pipeline::new().apply(layer.features()[1].geometry()).unwrap(), *layer.features()[1].geometry());
+    }
+}
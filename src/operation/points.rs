@@ -0,0 +1,61 @@
+//! Set operations over the points of geometries, which compare points by their planar
+//! coordinates through hashing.
+
+use std::collections::HashSet;
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::extract;
+use crate::operation::overlay::OverlayOp;
+
+/// Returns the coordinates without the ones at the planar location of an earlier one.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::operation::points::dedup;
+///
+/// let points = vec![coord!(0, 0), coord!(1, 1), coord!(0, 0, 5), coord!(2, 2), coord!(1, 1)];
+/// assert_eq!(dedup(&points), vec![coord!(0, 0), coord!(1, 1), coord!(2, 2)]);
+/// ```
+pub fn dedup(points: &[Coordinate]) -> Vec<Coordinate> {
+    let mut seen = HashSet::with_capacity(points.len());
+    points.iter().filter(|c| seen.insert(c.key())).cloned().collect()
+}
+
+/// Returns the distinct points of the points and multi points of two geometries selected by a
+/// boolean operation, as a multi point. Points of the first geometry come first, in their order,
+/// followed by the selected points of the second one.
+///
+/// # Arguments
+///
+/// * `a` - The first geometry.
+/// * `b` - The second geometry.
+/// * `op` - The boolean operation.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::OverlayOp;
+/// use geoms::operation::points::point_overlay;
+///
+/// let a = Geometry::MultiPoint { coordinates: vec![coord!(0, 0), coord!(1, 1), coord!(2, 2)] };
+/// let b = Geometry::MultiPoint { coordinates: vec![coord!(2, 2), coord!(3, 3), coord!(0, 0)] };
+/// assert_eq!(point_overlay(&a, &b, OverlayOp::Intersection).as_text(), "MULTIPOINT ((0 0), (2 2))");
+/// assert_eq!(point_overlay(&a, &b, OverlayOp::Union).as_text(), "MULTIPOINT ((0 0), (1 1), (2 2), (3 3))");
+/// assert_eq!(point_overlay(&a, &b, OverlayOp::Difference).as_text(), "MULTIPOINT ((1 1))");
+/// assert_eq!(point_overlay(&a, &b, OverlayOp::SymDifference).as_text(), "MULTIPOINT ((1 1), (3 3))");
+/// ```
+pub fn point_overlay(a: &Geometry, b: &Geometry, op: OverlayOp) -> Geometry {
+    let (a, b) = (dedup(&extract::points(a)), dedup(&extract::points(b)));
+    let in_a: HashSet<(u64, u64)> = a.iter().map(Coordinate::key).collect();
+    let in_b: HashSet<(u64, u64)> = b.iter().map(Coordinate::key).collect();
+    let mut coordinates: Vec<Coordinate> = a.into_iter().filter(|c| op.select(true, in_b.contains(&c.key()))).collect();
+    coordinates.extend(b.into_iter().filter(|c| !in_a.contains(&c.key()) && op.select(false, true)));
+    Geometry::MultiPoint { coordinates }
+}
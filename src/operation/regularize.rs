@@ -0,0 +1,165 @@
+//! Regularization of building footprints, squaring the outlines traced by hand or extracted from
+//! imagery.
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::algorithm::calipers::dominant_direction;
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// Returns the geometry with the outlines of its polygons regularized: vertices where the
+/// boundary turns by less than the tolerance are removed, straightening near collinear edges,
+/// then the edges within the tolerance of the main direction of their ring, or of a right angle
+/// to it, are turned into that direction about their middle, which squares near orthogonal
+/// corners. The other edges keep their direction, and the vertices are moved to where the lines
+/// of the edges meet. Consecutive edges made parallel are joined by a short perpendicular step.
+/// Rings left with fewer than three vertices are dropped along with the polygons whose shell
+/// they were, and other geometries are returned unchanged.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to regularize.
+/// * `angle_tolerance` - The largest change of direction that is removed, in radians.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::regularize::regularize;
+///
+/// //a footprint with slightly skewed corners and a vertex along its bottom edge
+/// let footprint = Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(5, 0.05), coord!(10, 0), coord!(10.1, 6), coord!(0, 6), coord!(0, 0)
+/// ]] };
+/// let regular = regularize(&footprint, 5f64.to_radians());
+/// assert_eq!(regular.coords_count(), 5);
+/// assert!((regular.area() - 60.3).abs() < 0.1);
+/// ```
+pub fn regularize(geometry: &Geometry, angle_tolerance: f64) -> Geometry {
+    match geometry {
+        Geometry::Polygon { coordinates } => {
+            Geometry::Polygon { coordinates: regularize_polygon(coordinates, angle_tolerance).unwrap_or_default() }
+        }
+        Geometry::MultiPolygon { coordinates } => Geometry::MultiPolygon {
+            coordinates: coordinates.iter().filter_map(|polygon| regularize_polygon(polygon, angle_tolerance)).collect(),
+        },
+        Geometry::GeometryCollection { geometries } => Geometry::GeometryCollection {
+            geometries: geometries.iter().map(|g| regularize(g, angle_tolerance)).collect(),
+        },
+        _ => geometry.clone(),
+    }
+}
+
+/// Returns the polygon with its rings regularized, or None if its shell collapses.
+fn regularize_polygon(rings: &[Vec<Coordinate>], angle_tolerance: f64) -> Option<Vec<Vec<Coordinate>>> {
+    let (shell, holes) = rings.split_first()?;
+    let mut regular = vec![regularize_ring(shell, angle_tolerance)?];
+    regular.extend(holes.iter().filter_map(|hole| regularize_ring(hole, angle_tolerance)));
+    Some(regular)
+}
+
+/// Returns the angle the boundary turns by at the vertex b, between 0 and π.
+fn turn(a: &Coordinate, b: &Coordinate, c: &Coordinate) -> f64 {
+    let (ux, uy) = (b.x() - a.x(), b.y() - a.y());
+    let (vx, vy) = (c.x() - b.x(), c.y() - b.y());
+    (ux * vy - uy * vx).abs().atan2(ux * vx + uy * vy)
+}
+
+/// Returns the closed ring regularized, or None if it collapses.
+fn regularize_ring(ring: &[Coordinate], angle_tolerance: f64) -> Option<Vec<Coordinate>> {
+    let mut vertices = ring.to_vec();
+    vertices.dedup_by(|a, b| a.equals_2d(b));
+    if vertices.len() > 1 && vertices[0].equals_2d(&vertices[vertices.len() - 1]) {
+        vertices.pop();
+    }
+    //straighten the gentlest turns first, as removing a vertex sharpens the turns next to it
+    while vertices.len() >= 3 {
+        let n = vertices.len();
+        let (gentlest, smallest) = (0..n)
+            .map(|i| (i, turn(&vertices[(i + n - 1) % n], &vertices[i], &vertices[(i + 1) % n])))
+            .fold((0, f64::INFINITY), |best, t| if t.1 < best.1 { t } else { best });
+        if smallest >= angle_tolerance {
+            break;
+        }
+        vertices.remove(gentlest);
+    }
+    let n = vertices.len();
+    if n < 3 {
+        return None;
+    }
+    let closed: Vec<Coordinate> = vertices.iter().chain(vertices.first()).cloned().collect();
+    let theta = dominant_direction(&closed);
+
+    //every edge as a point and a unit direction, turned to the closest main direction if near it
+    let lines: Vec<((f64, f64), (f64, f64))> = closed
+        .windows(2)
+        .map(|w| {
+            let (dx, dy) = (w[1].x() - w[0].x(), w[1].y() - w[0].y());
+            let middle = ((w[0].x() + w[1].x()) / 2.0, (w[0].y() + w[1].y()) / 2.0);
+            let angle = dy.atan2(dx);
+            let main = theta + ((angle - theta) / FRAC_PI_2).round() * FRAC_PI_2;
+            let direction = if (angle - main).abs() < angle_tolerance { main } else { angle };
+            (middle, (direction.cos(), direction.sin()))
+        })
+        .collect();
+    let mut regular: Vec<Coordinate> = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        let ((p, u), (q, v)) = (lines[(i + n - 1) % n], lines[i]);
+        let cross = u.0 * v.1 - u.1 * v.0;
+        if cross.abs() < 1e-9 {
+            //parallel edges are joined by a step through the projections of their shared vertex
+            let c = &vertices[i];
+            for ((ox, oy), (dx, dy)) in [(p, u), (q, v)] {
+                let t = (c.x() - ox) * dx + (c.y() - oy) * dy;
+                regular.push(Coordinate::new(ox + t * dx, oy + t * dy, c.z()));
+            }
+        } else {
+            let t = ((q.0 - p.0) * v.1 - (q.1 - p.1) * v.0) / cross;
+            regular.push(Coordinate::new(p.0 + t * u.0, p.1 + t * u.1, vertices[i].z()));
+        }
+    }
+    regular.dedup_by(|a, b| a.equals_2d(b));
+    if regular.len() > 1 && regular[0].equals_2d(&regular[regular.len() - 1]) {
+        regular.pop();
+    }
+    if regular.len() < 3 {
+        return None;
+    }
+    regular.push(regular[0].clone());
+    Some(regular)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_regularize_turned_footprint_with_hole() {
+        //an L shaped building turned by 20 degrees, with skewed corners and a courtyard
+        let (s, c) = 20f64.to_radians().sin_cos();
+        let rotate = |x: f64, y: f64| coord!(x * c - y * s, x * s + y * c);
+        let building = Geometry::Polygon { coordinates: vec![
+            vec![rotate(0.0, 0.0), rotate(20.0, 0.3), rotate(20.2, 10.0), rotate(10.0, 10.0), rotate(9.8, 20.0), rotate(0.0, 20.0), rotate(0.0, 0.0)],
+            vec![rotate(2.0, 2.0), rotate(2.0, 4.0), rotate(4.1, 4.0), rotate(4.0, 2.0), rotate(2.0, 2.0)],
+        ] };
+        let regular = regularize(&building, 5f64.to_radians());
+        let Geometry::Polygon { coordinates } = &regular else { panic!("Expected a polygon") };
+        assert_eq!(coordinates.len(), 2);
+        assert_eq!(coordinates[0].len(), 7);
+        //every corner is a right angle
+        for ring in coordinates {
+            let n = ring.len() - 1;
+            for i in 0..n {
+                let angle = turn(&ring[(i + n - 1) % n], &ring[i], &ring[i + 1]);
+                assert!((angle - FRAC_PI_2).abs() < 1e-9);
+            }
+        }
+        //a sharp tolerance keeps everything but the straight vertices
+        let square = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(1, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(0, 0)]] };
+        assert_eq!(regularize(&square, 1e-6).coords_count(), 5);
+        assert_eq!(regularize(&Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(1, 1), coord!(2, 2), coord!(0, 0)]] }, 0.1).coords_count(), 0);
+    }
+}
@@ -0,0 +1,203 @@
+//! Random points spread uniformly over polygons or along lines, for Monte Carlo analyses and
+//! synthetic datasets.
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::extract;
+use crate::triangulate::triangulate_polygon;
+
+/// A small seeded generator of uniform numbers, for reproducible samples without another
+/// dependency. Any other source of numbers from 0 to 1 can be used with the sampling functions,
+/// such as `|| rng.gen::<f64>()` with the `rand` crate.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::operation::sample::SplitMix64;
+///
+/// let mut a = SplitMix64::new(7);
+/// let mut b = SplitMix64::new(7);
+/// let u = a.next_f64();
+/// assert!((0.0..1.0).contains(&u));
+/// assert_eq!(u, b.next_f64());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Creates a generator from a seed. The same seed always gives the same numbers.
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    /// Returns the next number of 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns the next number from 0 included to 1 excluded.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Returns points spread uniformly at random over the polygons of a geometry, or none if they
+/// have no area. The polygons are triangulated, a triangle is drawn for every point with a
+/// chance proportional to its area, and the point is drawn uniformly in it, with its z
+/// interpolated from the corners.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose polygons to sample.
+/// * `n` - The number of points.
+/// * `rng` - The source of uniform numbers from 0 included to 1 excluded.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::sample::{sample_points, SplitMix64};
+///
+/// let field = Geometry::Polygon { coordinates: vec![vec![coord!(0, 0), coord!(10, 0), coord!(10, 5), coord!(0, 5), coord!(0, 0)]] };
+/// let mut rng = SplitMix64::new(42);
+/// let points = sample_points(&field, 100, &mut || rng.next_f64());
+/// assert_eq!(points.len(), 100);
+/// assert!(points.iter().all(|p| field.contains_point(p)));
+/// ```
+pub fn sample_points<R: FnMut() -> f64>(geometry: &Geometry, n: usize, rng: &mut R) -> Vec<Coordinate> {
+    let triangles: Vec<[Coordinate; 3]> = geometry.polygons().flat_map(|rings| triangulate_polygon(rings)).collect();
+    let weights: Vec<f64> = triangles
+        .iter()
+        .map(|[a, b, c]| ((b.x() - a.x()) * (c.y() - a.y()) - (c.x() - a.x()) * (b.y() - a.y())).abs())
+        .collect();
+    let Some(index) = Cumulative::new(&weights) else {
+        return vec![];
+    };
+    (0..n)
+        .map(|_| {
+            let [a, b, c] = &triangles[index.draw(rng())];
+            let (mut s, mut t) = (rng(), rng());
+            //points beyond the diagonal of the parallelogram are folded back into the triangle
+            if s + t > 1.0 {
+                (s, t) = (1.0 - s, 1.0 - t);
+            }
+            let at = |p: f64, q: f64, r: f64| p + s * (q - p) + t * (r - p);
+            Coordinate::new(at(a.x(), b.x(), c.x()), at(a.y(), b.y(), c.y()), at(a.z(), b.z(), c.z()))
+        })
+        .collect()
+}
+
+/// Returns points spread uniformly at random along the lines of a geometry and the rings of its
+/// polygons, or none if they have no length. A segment is drawn for every point with a chance
+/// proportional to its planar length, and the point is drawn uniformly along it, with its z
+/// interpolated from the ends.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry whose lines to sample.
+/// * `n` - The number of points.
+/// * `rng` - The source of uniform numbers from 0 included to 1 excluded.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::sample::{sample_points_on_line, SplitMix64};
+///
+/// let road = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0), coord!(10, 10)] };
+/// let mut rng = SplitMix64::new(1);
+/// let points = sample_points_on_line(&road, 50, &mut || rng.next_f64());
+/// assert!(points.iter().all(|p| (p.y() == 0.0 && p.x() <= 10.0) || (p.x() == 10.0 && p.y() <= 10.0)));
+/// ```
+pub fn sample_points_on_line<R: FnMut() -> f64>(geometry: &Geometry, n: usize, rng: &mut R) -> Vec<Coordinate> {
+    let mut paths = extract::lines(geometry);
+    paths.extend(geometry.polygons().flatten().cloned());
+    let segments: Vec<(&Coordinate, &Coordinate)> = paths.iter().flat_map(|path| path.windows(2)).map(|w| (&w[0], &w[1])).collect();
+    let weights: Vec<f64> = segments.iter().map(|(a, b)| (b.x() - a.x()).hypot(b.y() - a.y())).collect();
+    let Some(index) = Cumulative::new(&weights) else {
+        return vec![];
+    };
+    (0..n)
+        .map(|_| {
+            let (a, b) = segments[index.draw(rng())];
+            let t = rng();
+            Coordinate::new(a.x() + t * (b.x() - a.x()), a.y() + t * (b.y() - a.y()), a.z() + t * (b.z() - a.z()))
+        })
+        .collect()
+}
+
+/// The running sums of weights, drawing items with a chance proportional to their weight.
+struct Cumulative {
+    sums: Vec<f64>,
+}
+
+impl Cumulative {
+    /// Returns the running sums, or None if the weights add up to nothing.
+    fn new(weights: &[f64]) -> Option<Cumulative> {
+        let sums: Vec<f64> = weights
+            .iter()
+            .scan(0.0, |sum, w| {
+                *sum += w;
+                Some(*sum)
+            })
+            .collect();
+        match sums.last() {
+            Some(&total) if total > 0.0 => Some(Cumulative { sums }),
+            _ => None,
+        }
+    }
+
+    /// Returns the item at a fraction of the total weight, never one without weight.
+    fn draw(&self, u: f64) -> usize {
+        let target = u * self.sums[self.sums.len() - 1];
+        self.sums.partition_point(|&sum| sum <= target).min(self.sums.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_samples_are_uniform() {
+        //a frame whose hole and parts get no points, with twice the area on its right half
+        let frame = Geometry::MultiPolygon { coordinates: vec![
+            vec![
+                vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+                vec![coord!(1, 1), coord!(1, 9), coord!(4, 9), coord!(4, 1), coord!(1, 1)],
+            ],
+            vec![vec![coord!(20, 0, 10), coord!(30, 0, 10), coord!(30, 10, 10), coord!(20, 10, 10), coord!(20, 0, 10)]],
+        ] };
+        let mut rng = SplitMix64::new(2024);
+        let points = sample_points(&frame, 20_000, &mut || rng.next_f64());
+        assert!(points.iter().all(|p| frame.contains_point(p) || p.x() == 1.0 || p.x() == 4.0));
+        assert!(!points.iter().any(|p| p.x() > 1.0 && p.x() < 4.0 && p.y() > 1.0 && p.y() < 9.0));
+        //the square on the right has 100 of the 176 units of area, and a z of 10
+        let right: Vec<&Coordinate> = points.iter().filter(|p| p.x() >= 20.0).collect();
+        let share = right.len() as f64 / points.len() as f64;
+        assert!((share - 100.0 / 176.0).abs() < 0.02, "{}", share);
+        assert!(right.iter().all(|p| (p.z() - 10.0).abs() < 1e-9));
+        let upper = right.iter().filter(|p| p.y() > 5.0).count() as f64 / right.len() as f64;
+        assert!((upper - 0.5).abs() < 0.02, "{}", upper);
+
+        //the second segment is three times as long as the first
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 1), coord!(3, 1)] };
+        let points = sample_points_on_line(&line, 20_000, &mut || rng.next_f64());
+        let share = points.iter().filter(|p| p.y() == 1.0 && p.x() > 0.0).count() as f64 / points.len() as f64;
+        assert!((share - 0.75).abs() < 0.02, "{}", share);
+        let point = Geometry::Point { coordinates: coord!(0, 0) };
+        assert!(sample_points(&point, 10, &mut || rng.next_f64()).is_empty());
+        assert!(sample_points_on_line(&point, 10, &mut || rng.next_f64()).is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+//! Simplification of lines and polygons, removing the vertices that add little to their shape.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+use crate::operation::distance::point_segment_2d;
+use crate::operation::edit::{empty_like, EditCoordinates, GeometryEditor, SequenceKind};
+
+/// Returns the geometry simplified with the algorithm of Douglas and Peucker: the vertices of
+/// every line and ring are removed as long as they lie within the tolerance of the segment
+/// joining the vertices kept around them. The ends of lines are kept, as are the closing vertices
+/// of rings. Rings left with fewer than four coordinates are removed, along with the polygons
+/// whose shell they were, and the geometry becomes empty if nothing is left. Points are kept,
+/// and the result is not guaranteed to be valid, as rings may cross once simplified.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to simplify.
+/// * `tolerance` - The largest planar distance from a removed vertex to the simplified line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::simplify::simplify;
+///
+/// let track = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(1, 0.1), coord!(2, -0.1), coord!(3, 5), coord!(4, 6)] };
+/// assert_eq!(simplify(&track, 0.5).as_text(), "LINESTRING (0 0, 2 -0.1, 3 5, 4 6)");
+/// ```
+pub fn simplify(geometry: &Geometry, tolerance: f64) -> Geometry {
+    let mut simplify = EditCoordinates(|coordinates: &[Coordinate], kind| match kind {
+        SequenceKind::Point => coordinates.to_vec(),
+        SequenceKind::Line => douglas_peucker(coordinates, tolerance),
+        SequenceKind::Ring => {
+            let ring = douglas_peucker(coordinates, tolerance);
+            if ring.len() < 4 {
+                return vec![];
+            }
+            ring
+        }
+    });
+    GeometryEditor::new()
+        .remove_empty(true)
+        .edit(geometry, &mut simplify)
+        .unwrap_or_else(|| empty_like(geometry))
+}
+
+/// Returns the vertices of the line kept by the Douglas Peucker algorithm.
+fn douglas_peucker(line: &[Coordinate], tolerance: f64) -> Vec<Coordinate> {
+    if line.len() < 3 {
+        return line.to_vec();
+    }
+    let mut keep = vec![false; line.len()];
+    keep[0] = true;
+    keep[line.len() - 1] = true;
+    let mut ranges = vec![(0, line.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let furthest = (first + 1..last)
+            .map(|i| (i, point_segment_2d(&line[i], &line[first], &line[last])))
+            .fold(None, |best: Option<(usize, f64)>, c| if best.is_none_or(|b| c.1 > b.1) { Some(c) } else { best });
+        if let Some((i, distance)) = furthest {
+            if distance > tolerance {
+                keep[i] = true;
+                ranges.push((first, i));
+                ranges.push((i, last));
+            }
+        }
+    }
+    line.iter().zip(keep).filter(|(_, keep)| *keep).map(|(c, _)| c.clone()).collect()
+}
+
+/// Returns the importance of every vertex of a line as its effective area, the area of the
+/// triangle it forms with its neighbours when it is removed by the algorithm of Visvalingam and
+/// Whyatt. The vertex of least area is removed first, its neighbours are measured again, and the
+/// importance of a vertex is never less than that of a vertex removed before it, so keeping the
+/// vertices whose importance reaches a threshold gives the line simplified down to that area.
+/// Clients can then simplify for every zoom level with a threshold instead of running the
+/// simplification again. The ends are infinitely important, so the closing vertices of rings are
+/// kept too.
+///
+/// # Arguments
+///
+/// * `line` - The vertices of the line.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::operation::simplify::vertex_importance;
+///
+/// let track = vec![coord!(0, 0), coord!(1, 0.1), coord!(2, -0.1), coord!(3, 5), coord!(4, 6)];
+/// let importance = vertex_importance(&track);
+/// assert_eq!(importance[0], f64::INFINITY);
+/// //the track for a zoom level where areas under 1 do not show
+/// let kept: Vec<&Coordinate> = track.iter().zip(&importance).filter(|(_, &i)| i >= 1.0).map(|(c, _)| c).collect();
+/// assert_eq!(kept, [&coord!(0, 0), &coord!(2, -0.1), &coord!(3, 5), &coord!(4, 6)]);
+/// ```
+pub fn vertex_importance(line: &[Coordinate]) -> Vec<f64> {
+    let n = line.len();
+    let mut importance = vec![f64::INFINITY; n];
+    if n < 3 {
+        return importance;
+    }
+    let area = |a: &Coordinate, b: &Coordinate, c: &Coordinate| {
+        ((b.x() - a.x()) * (c.y() - a.y()) - (c.x() - a.x()) * (b.y() - a.y())).abs() / 2.0
+    };
+    //the vertices left as a linked list, and the areas of those still in the line
+    let (mut previous, mut next): (Vec<usize>, Vec<usize>) = ((0..n).map(|i| i.saturating_sub(1)).collect(), (1..=n).collect());
+    let mut current: Vec<f64> = (0..n).map(|i| if i == 0 || i == n - 1 { f64::INFINITY } else { area(&line[i - 1], &line[i], &line[i + 1]) }).collect();
+    let mut heap: BinaryHeap<Removal> = (1..n - 1).map(|i| Removal(current[i], i)).collect();
+    let mut floor = 0.0f64;
+    while let Some(Removal(effective, i)) = heap.pop() {
+        if effective.to_bits() != current[i].to_bits() || importance[i].is_finite() {
+            continue;
+        }
+        floor = floor.max(effective);
+        importance[i] = floor;
+        let (p, q) = (previous[i], next[i]);
+        next[p] = q;
+        previous[q] = p;
+        for j in [p, q] {
+            if j != 0 && j != n - 1 {
+                current[j] = area(&line[previous[j]], &line[j], &line[next[j]]);
+                heap.push(Removal(current[j], j));
+            }
+        }
+    }
+    importance
+}
+
+/// A vertex to remove with its area, ordered so that the smallest area, then the first vertex,
+/// comes out of a heap first.
+struct Removal(f64, usize);
+
+impl PartialEq for Removal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Removal {}
+
+impl PartialOrd for Removal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Removal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_simplify_polygon_with_small_hole() {
+        let polygon = Geometry::Polygon { coordinates: vec![
+            vec![coord!(0, 0), coord!(5, 0.1), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)],
+            vec![coord!(4, 4), coord!(4, 4.2), coord!(4.2, 4.2), coord!(4, 4)],
+        ] };
+        //the vertex along the bottom edge and the small hole are removed
+        assert_eq!(simplify(&polygon, 0.5).as_text(), "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))");
+        //the closing vertex is kept, so a square collapses with a large tolerance
+        assert_eq!(simplify(&polygon, 20.0), Geometry::Polygon { coordinates: vec![] });
+        let multi = Geometry::MultiPolygon { coordinates: vec![polygon.polygons().next().unwrap().clone()] };
+        assert_eq!(simplify(&multi, 20.0), Geometry::MultiPolygon { coordinates: vec![] });
+        assert_eq!(simplify(&polygon, 0.0), polygon);
+    }
+
+    #[test]
+    fn test_vertex_importance_is_progressive() {
+        let line: Vec<Coordinate> = (0..40).map(|i| coord!(i, ((i * 7919) % 13) as f64 * 0.3 + (i as f64 * 0.2).sin() * 4.0)).collect();
+        let importance = vertex_importance(&line);
+        assert!(importance[0].is_infinite() && importance[39].is_infinite());
+        //removing the vertices by hand in order of least area gives the same weights
+        let mut kept: Vec<usize> = (0..line.len()).collect();
+        let mut floor = 0.0f64;
+        while kept.len() > 2 {
+            let areas: Vec<f64> = (1..kept.len() - 1)
+                .map(|k| {
+                    let (a, b, c) = (&line[kept[k - 1]], &line[kept[k]], &line[kept[k + 1]]);
+                    ((b.x() - a.x()) * (c.y() - a.y()) - (c.x() - a.x()) * (b.y() - a.y())).abs() / 2.0
+                })
+                .collect();
+            let k = (0..areas.len()).fold(0, |best, k| if areas[k] < areas[best] { k } else { best }) + 1;
+            floor = floor.max(areas[k - 1]);
+            assert_eq!(importance[kept[k]], floor);
+            kept.remove(k);
+        }
+        assert_eq!(vertex_importance(&line[..2]), [f64::INFINITY; 2]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Snapping of geometries to the vertices of reference geometries, as when conflating a layer to a
+//! more accurate one so their shared boundaries match exactly.
+
+use std::collections::HashSet;
+
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+use crate::operation::distance::point_segment_2d;
+use crate::operation::edit::{empty_like, EditCoordinates, EditOperation, GeometryEditor, SequenceKind};
+
+/// Snaps the vertices of a geometry to the vertices of the reference within the tolerance. See
+/// [`snap_layer`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to snap.
+/// * `reference` - The geometry to snap to.
+/// * `tolerance` - The maximum planar distance a vertex is moved, or from a segment to the
+///   reference vertices added to it.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::snap::snap;
+///
+/// let road = Geometry::LineString { coordinates: vec![coord!(0, 0.1), coord!(10, -0.1)] };
+/// let survey = Geometry::MultiPoint { coordinates: vec![coord!(0, 0), coord!(5, 0.5), coord!(10, 0)] };
+/// assert_eq!(snap(&road, &survey, 0.6).as_text(), "LINESTRING (0 0, 5 0.5, 10 0)");
+/// ```
+pub fn snap(geometry: &Geometry, reference: &Geometry, tolerance: f64) -> Geometry {
+    let mut snapped = [geometry.clone()];
+    snap_layer(&mut snapped, std::slice::from_ref(reference), tolerance);
+    let [snapped] = snapped;
+    snapped
+}
+
+/// Snaps every geometry of a layer to the vertices of a reference layer, indexing the reference
+/// once for the whole layer. Every vertex within the tolerance of reference vertices is moved to
+/// the nearest of them, keeping its z, and then the reference vertices within the tolerance of a
+/// segment, but not of its ends, are added to it, so that boundaries running along the reference
+/// follow all its vertices. Repeated consecutive vertices are removed.
+///
+/// Geometries snapped into invalid ones, such as a polygon collapsing or crossing itself, are
+/// left as they were, while geometries that were already invalid are snapped regardless.
+///
+/// # Arguments
+///
+/// * `layer` - The geometries to snap, in place.
+/// * `reference` - The geometries to snap to.
+/// * `tolerance` - The maximum planar distance a vertex is moved, or from a segment to the
+///   reference vertices added to it.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::snap_layer;
+///
+/// let parcels = vec![Geometry::Polygon { coordinates: vec![vec![
+///     coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(0, 10), coord!(0, 0)
+/// ]] }];
+/// //a road whose edge bends slightly into the parcel
+/// let road = Geometry::LineString { coordinates: vec![coord!(0.1, 10), coord!(5, 9.9), coord!(10.1, 10)] };
+/// let mut layer = parcels.clone();
+/// snap_layer(&mut layer, &[road], 0.2);
+/// assert_eq!(layer[0].as_text(), "POLYGON ((0 0, 10 0, 10.1 10, 5 9.9, 0.1 10, 0 0))");
+///
+/// //a polygon collapsing under the tolerance is left as it was
+/// let mut layer = parcels.clone();
+/// snap_layer(&mut layer, &[Geometry::Point { coordinates: coord!(5, 5) }], 20.0);
+/// assert_eq!(layer, parcels);
+/// ```
+pub fn snap_layer(layer: &mut [Geometry], reference: &[Geometry], tolerance: f64) {
+    let vertices: Vec<&Coordinate> = reference.iter().flat_map(|g| g.coords_iter()).collect();
+    let mut snapper = Snapper {
+        tree: STRtree::new(vertices.into_iter().map(|v| (Envelope::of([v]), v)).collect()),
+        tolerance,
+    };
+    for geometry in layer.iter_mut() {
+        if let Some(snapped) = GeometryEditor::new().edit(geometry, &mut snapper) {
+            if snapped.is_valid() || !geometry.is_valid() {
+                *geometry = snapped;
+            }
+        }
+    }
+}
+
+/// Returns the geometry with the x and y of its coordinates rounded to the nearest multiple of
+/// the grid size, keeping their z. Repeated consecutive vertices are removed, then lines left
+/// with fewer than two coordinates and rings left with fewer than four are removed, along with
+/// the polygons whose shell they were, and the geometry becomes empty if nothing is left.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to snap.
+/// * `size` - The spacing of the grid, which must be positive.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::snap::snap_to_grid;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0.12, 0.04), coord!(0.98, 0.11), coord!(1.04, 0.09), coord!(2.26, 1.61)] };
+/// assert_eq!(snap_to_grid(&line, 0.5).as_text(), "LINESTRING (0 0, 1 0, 2.5 1.5)");
+/// ```
+pub fn snap_to_grid(geometry: &Geometry, size: f64) -> Geometry {
+    if size.is_nan() || size <= 0.0 {
+        panic!("The size of the grid must be positive");
+    }
+    let mut round = EditCoordinates(|coordinates: &[Coordinate], kind| {
+        let mut rounded: Vec<Coordinate> = coordinates
+            .iter()
+            .map(|c| Coordinate::new((c.x() / size).round() * size, (c.y() / size).round() * size, c.z()))
+            .collect();
+        if kind == SequenceKind::Point {
+            return rounded;
+        }
+        rounded.dedup_by(|a, b| a.equals_2d(b));
+        let min = if kind == SequenceKind::Ring { 4 } else { 2 };
+        if rounded.len() < min {
+            return vec![];
+        }
+        rounded
+    });
+    GeometryEditor::new()
+        .remove_empty(true)
+        .edit(geometry, &mut round)
+        .unwrap_or_else(|| empty_like(geometry))
+}
+
+/// An edit operation snapping every coordinate sequence to the indexed reference vertices.
+struct Snapper<'a> {
+    tree: STRtree<&'a Coordinate>,
+    tolerance: f64,
+}
+
+impl Snapper<'_> {
+    /// Returns the reference vertices within the tolerance of the envelope.
+    fn candidates(&self, envelope: Envelope) -> Vec<&Coordinate> {
+        self.tree.query(&envelope.expand_by(self.tolerance)).into_iter().copied().collect()
+    }
+}
+
+impl EditOperation for Snapper<'_> {
+    fn edit_coordinates(&mut self, coordinates: &[Coordinate], kind: SequenceKind) -> Vec<Coordinate> {
+        let moved: Vec<Coordinate> = coordinates
+            .iter()
+            .map(|c| {
+                self.candidates(Envelope::of([c]))
+                    .into_iter()
+                    .map(|v| (c.distance_2d(v), v))
+                    .filter(|(d, _)| *d <= self.tolerance)
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .map_or_else(|| c.clone(), |(_, v)| Coordinate::new(v.x(), v.y(), c.z()))
+            })
+            .collect();
+        if kind == SequenceKind::Point {
+            return moved;
+        }
+        let present: HashSet<(u64, u64)> = moved.iter().map(Coordinate::key).collect();
+        let mut snapped: Vec<Coordinate> = Vec::with_capacity(moved.len());
+        for (i, c) in moved.iter().enumerate() {
+            if snapped.last().is_none_or(|last| !last.equals_2d(c)) {
+                snapped.push(c.clone());
+            }
+            let Some(next) = moved.get(i + 1) else { continue };
+            let (dx, dy) = (next.x() - c.x(), next.y() - c.y());
+            let mut inserted: Vec<&Coordinate> = self
+                .candidates(Envelope::of([c, next]))
+                .into_iter()
+                .filter(|v| !present.contains(&v.key()))
+                .filter(|v| v.distance_2d(c) > self.tolerance && v.distance_2d(next) > self.tolerance)
+                .filter(|v| point_segment_2d(v, c, next) <= self.tolerance)
+                .collect();
+            inserted.sort_by(|a, b| {
+                let along = |v: &Coordinate| (v.x() - c.x()) * dx + (v.y() - c.y()) * dy;
+                along(a).total_cmp(&along(b))
+            });
+            //the z of added vertices is interpolated along the segment
+            let length = dx.hypot(dy);
+            for v in inserted {
+                let t = c.distance_2d(v) / length;
+                let added = Coordinate::new(v.x(), v.y(), c.z() + t.min(1.0) * (next.z() - c.z()));
+                if !snapped.last().unwrap().equals_2d(&added) {
+                    snapped.push(added);
+                }
+            }
+        }
+        snapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_shared_boundary() {
+        //two parcels digitised separately, whose shared edge does not quite match
+        let left = Geometry::Polygon { coordinates: vec![vec![
+            coord!(0, 0), coord!(5, 0), coord!(5, 5), coord!(5, 10), coord!(0, 10), coord!(0, 0)
+        ]] };
+        let mut right = vec![Geometry::Polygon { coordinates: vec![vec![
+            coord!(5.05, 0), coord!(10, 0), coord!(10, 10), coord!(4.95, 10), coord!(5.05, 0)
+        ]] }];
+        snap_layer(&mut right, std::slice::from_ref(&left), 0.1);
+        assert_eq!(right[0].as_text(), "POLYGON ((5 0, 10 0, 10 10, 5 10, 5 5, 5 0))");
+        assert!(right[0].is_valid());
+        //the parcels only share their boundary
+        assert_eq!(left.intersection(&right[0]).unwrap().area(), 0.0);
+        //a geometry far from the reference is left as it was
+        let mut far = vec![Geometry::Point { coordinates: coord!(50, 50) }];
+        snap_layer(&mut far, &[left], 0.1);
+        assert_eq!(far[0].as_text(), "POINT (50 50)");
+    }
+}
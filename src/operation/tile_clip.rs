@@ -0,0 +1,178 @@
+//! Preparation of geometries for vector tiles, clipping them to the tile with a margin and moving
+//! them onto the integer grid of the tile, as done before encoding them.
+
+use crate::algorithm::area::ring_signed_area;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::operation::edit::{Edit, EditOperation, GeometryEditor, SequenceKind};
+use crate::operation::overlay::clip;
+
+/// Returns a geometry clipped to a tile and moved onto the integer grid of the tile, or an error
+/// if its polygons could not be clipped.
+///
+/// The geometry is clipped to the tile grown by the buffer on every side, so lines and polygons
+/// drawn across the edges of neighbouring tiles meet without seams. The coordinates are then
+/// scaled so the tile spans from 0 to the extent, with y going down from the top of the tile,
+/// and rounded to integers, dropping z. Repeated consecutive vertices are removed, and lines
+/// left with a single vertex and rings left with no area are dropped, as are the polygons whose
+/// shell they were. Shells are made to have a positive area in the grid of the tile and holes a
+/// negative one, which is clockwise on a map, as vector tiles expect. The result is the only
+/// non empty one of the polygons, the lines and the points, or a collection of them, and it is
+/// not guaranteed to be valid, as rounding may make rings touch.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry in the coordinates of the tile envelope.
+/// * `tile` - The envelope of the tile.
+/// * `buffer` - The margin around the tile, in units of the tile grid, as pixels.
+/// * `extent` - The size of the grid of the tile, as 4096.
+///
+/// # Panics
+///
+/// Panics if the tile is empty or flat, if the extent is zero or if the buffer is negative.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::tile_clip;
+///
+/// let tile = Envelope::new(0.0, 0.0, 100.0, 100.0);
+/// //a park overflowing the tile on its right, counter clockwise on the map
+/// let park = Geometry::Polygon { coordinates: vec![vec![coord!(50, 10), coord!(150, 10), coord!(150, 60), coord!(50, 60), coord!(50, 10)]] };
+/// let clipped = tile_clip(&park, &tile, 8.0, 256).unwrap();
+/// assert_eq!(clipped.as_text(), "POLYGON ((128 230, 128 102, 264 102, 264 230, 128 230))");
+/// ```
+pub fn tile_clip(geometry: &Geometry, tile: &Envelope, buffer: f64, extent: u32) -> Result<Geometry, Error> {
+    if tile.is_empty() || tile.width() <= 0.0 || tile.height() <= 0.0 {
+        panic!("The tile must have an area");
+    }
+    if extent == 0 {
+        panic!("The extent of the tile must be positive");
+    }
+    if buffer.is_nan() || buffer < 0.0 {
+        panic!("The buffer of the tile cannot be negative");
+    }
+    let (scale_x, scale_y) = (extent as f64 / tile.width(), extent as f64 / tile.height());
+    let window = Envelope::new(
+        tile.min_x() - buffer / scale_x,
+        tile.min_y() - buffer / scale_y,
+        tile.max_x() + buffer / scale_x,
+        tile.max_y() + buffer / scale_y,
+    );
+    let clipped = clip(geometry, &window)?;
+    let mut quantize = Quantize { tile: *tile, scale_x, scale_y };
+    let empty = || Geometry::GeometryCollection { geometries: vec![] };
+    Ok(match GeometryEditor::new().remove_empty(true).edit(&clipped, &mut quantize) {
+        Some(Geometry::GeometryCollection { mut geometries }) if geometries.len() == 1 => geometries.pop().unwrap(),
+        Some(quantized) => quantized,
+        None => empty(),
+    })
+}
+
+/// Moves the coordinates onto the grid of a tile, dropping the degenerate parts and fixing the
+/// winding of the rings.
+struct Quantize {
+    tile: Envelope,
+    scale_x: f64,
+    scale_y: f64,
+}
+
+impl Quantize {
+    /// Returns the coordinates on the grid, without repeated consecutive vertices.
+    fn grid(&self, coordinates: &[Coordinate]) -> Vec<Coordinate> {
+        let mut grid: Vec<Coordinate> = coordinates
+            .iter()
+            .map(|c| {
+                let x = ((c.x() - self.tile.min_x()) * self.scale_x).round();
+                let y = ((self.tile.max_y() - c.y()) * self.scale_y).round();
+                Coordinate::new(x + 0.0, y + 0.0, 0.0)
+            })
+            .collect();
+        grid.dedup();
+        grid
+    }
+}
+
+impl EditOperation for Quantize {
+    fn edit_component(&mut self, component: &Geometry) -> Edit {
+        let rings = match component {
+            Geometry::Polygon { coordinates } => coordinates,
+            _ => return Edit::Keep,
+        };
+        let mut polygon = Vec::with_capacity(rings.len());
+        for (i, ring) in rings.iter().enumerate() {
+            let mut ring = self.grid(ring);
+            let area = ring_signed_area(&ring);
+            if ring.len() < 4 || area == 0.0 {
+                match i {
+                    0 => return Edit::Remove,
+                    _ => continue,
+                }
+            }
+            if (area > 0.0) != (i == 0) {
+                ring.reverse();
+            }
+            polygon.push(ring);
+        }
+        Edit::Replace(Geometry::Polygon { coordinates: polygon })
+    }
+
+    fn edit_coordinates(&mut self, coordinates: &[Coordinate], kind: SequenceKind) -> Vec<Coordinate> {
+        let grid = self.grid(coordinates);
+        match kind {
+            SequenceKind::Line if grid.len() < 2 => vec![],
+            _ => grid,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_tile_clip_of_mixed_layers() {
+        let tile = Envelope::new(1000.0, 2000.0, 1100.0, 2100.0);
+        let square = |x: f64, y: f64, size: f64| vec![coord!(x, y), coord!(x + size, y), coord!(x + size, y + size), coord!(x, y + size), coord!(x, y)];
+        let shell: Vec<Coordinate> = square(990.0, 1990.0, 120.0).into_iter().rev().collect();
+        let collection = Geometry::GeometryCollection { geometries: vec![
+            //a clockwise shell over the whole tile with a hole, and a polygon too small for the grid
+            Geometry::MultiPolygon { coordinates: vec![
+                vec![shell, square(1040.0, 2040.0, 20.0)],
+                vec![square(1050.0, 2050.0, 0.1)],
+            ] },
+            //a road crossing the tile, and one shorter than a cell
+            Geometry::MultiLineString { coordinates: vec![
+                vec![coord!(900, 2050, 3), coord!(1200, 2050, 3)],
+                vec![coord!(1010, 2010), coord!(1010.1, 2010.1)],
+            ] },
+            Geometry::MultiPoint { coordinates: vec![coord!(1025, 2075), coord!(1101, 2050), coord!(1200, 2050)] },
+        ] };
+        let clipped = tile_clip(&collection, &tile, 16.0, 256).unwrap();
+        assert_eq!(
+            clipped.as_text(),
+            "GEOMETRYCOLLECTION (MULTIPOLYGON (((-16 272, -16 -16, 272 -16, 272 272, -16 272), \
+             (102 154, 154 154, 154 102, 102 102, 102 154))), \
+             MULTILINESTRING ((-16 128, 272 128)), MULTIPOINT ((64 64), (259 128)))"
+        );
+        let polygon = match &clipped {
+            Geometry::GeometryCollection { geometries } => &geometries[0],
+            _ => unreachable!(),
+        };
+        let rings = polygon.polygons().next().unwrap();
+        assert!(ring_signed_area(&rings[0]) > 0.0 && ring_signed_area(&rings[1]) < 0.0);
+
+        //without a buffer nothing leaves the grid, and geometries outside of the tile vanish
+        let clipped = tile_clip(&collection, &tile, 0.0, 4096).unwrap();
+        assert!(clipped.coords_iter().all(|c| (0.0..=4096.0).contains(&c.x()) && (0.0..=4096.0).contains(&c.y())));
+        let far = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 10)] };
+        assert_eq!(tile_clip(&far, &tile, 64.0, 4096).unwrap(), Geometry::GeometryCollection { geometries: vec![] });
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::algorithm::area::ring_area_3d;
+use crate::algorithm::line_intersection::{intersection, sweep_segment_pairs, SegmentIntersection, ZInterpolation};
+use crate::algorithm::point_in_polygon::{locate_in_polygon, locate_in_ring, Location};
+use crate::coord;
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::index::strtree::STRtree;
+
+/// Returns true if the geometry passes the basic validity checks:
+///
+/// * Line strings have at least two distinct points.
+/// * Rings are closed, have at least four points and are simple.
+/// * The holes of a polygon do not lie outside of its shell, do not overlap each other and do not
+///   lie inside of each other.
+/// * The polygons of a multi polygon do not overlap, and their boundaries only touch at points.
+/// * The patches of polyhedral surfaces and TINs are closed and non degenerate in 3D, triangles have
+///   exactly three vertices, and every edge is shared by at most two patches which traverse it in
+///   opposite directions, so the surface is consistently oriented.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::valid::is_valid;
+///
+/// let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(0, 0)] };
+/// assert!(!is_valid(&line));
+///
+/// let tin = Geometry::Tin { coordinates: vec![
+///     vec![coord!(0, 0, 0), coord!(1, 0, 0), coord!(0, 1, 0), coord!(0, 0, 0)],
+///     vec![coord!(1, 0, 0), coord!(1, 1, 1), coord!(0, 1, 0), coord!(1, 0, 0)],
+/// ] };
+/// assert!(is_valid(&tin));
+/// ```
+pub fn is_valid(geometry: &Geometry) -> bool {
+    validity_errors(geometry).is_empty()
+}
+
+/// The kind of a problem making a geometry invalid.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ValidityErrorKind {
+    /// A line has less than two distinct points, or a ring less than four points.
+    TooFewPoints,
+    /// A ring does not end where it starts.
+    RingNotClosed,
+    /// A ring has the same point twice in a row.
+    RepeatedPoint,
+    /// A ring crosses or touches itself.
+    SelfIntersection,
+    /// A hole of a polygon goes outside of its shell.
+    HoleOutsideShell,
+    /// Two holes of a polygon cross, or their boundaries touch along a line.
+    OverlappingHoles,
+    /// A hole of a polygon lies inside of another one.
+    NestedHoles,
+    /// Two polygons of a multi polygon overlap, or their boundaries touch along a line.
+    OverlappingPolygons,
+    /// A patch of a surface is not closed, is degenerate in 3D, or is a triangle without exactly
+    /// three vertices.
+    InvalidPatch,
+    /// An edge of a surface is traversed twice in the same direction, or by more than two patches.
+    NonManifoldEdge,
+}
+
+/// A problem making a geometry invalid, and where it is.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidityError {
+    /// What the problem is.
+    pub kind: ValidityErrorKind,
+    /// A coordinate of the geometry where the problem is, or None for components without any
+    /// coordinate.
+    pub location: Option<Coordinate>,
+}
+
+impl fmt::Display for ValidityError {
+    /// Returns the string representation of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::operation::valid::{ValidityError, ValidityErrorKind};
+    ///
+    /// let error = ValidityError { kind: ValidityErrorKind::SelfIntersection, location: Some(coord!(1, 2.5)) };
+    /// assert_eq!(error.to_string(), "self intersection at (1 2.5)");
+    ///
+    /// let error = ValidityError { kind: ValidityErrorKind::TooFewPoints, location: None };
+    /// assert_eq!(error.to_string(), "too few points");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            ValidityErrorKind::TooFewPoints => "too few points",
+            ValidityErrorKind::RingNotClosed => "ring not closed",
+            ValidityErrorKind::RepeatedPoint => "repeated point",
+            ValidityErrorKind::SelfIntersection => "self intersection",
+            ValidityErrorKind::HoleOutsideShell => "hole outside of shell",
+            ValidityErrorKind::OverlappingHoles => "overlapping holes",
+            ValidityErrorKind::NestedHoles => "nested holes",
+            ValidityErrorKind::OverlappingPolygons => "overlapping polygons",
+            ValidityErrorKind::InvalidPatch => "invalid patch",
+            ValidityErrorKind::NonManifoldEdge => "non manifold edge",
+        };
+        match &self.location {
+            Some(location) => write!(f, "{} at ({} {})", kind, location.x(), location.y()),
+            None => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// Returns every problem found by the checks of [`is_valid`], in the order of the components of
+/// the geometry, rather than stopping at the first one. Each crossing of a ring is reported at
+/// its own location.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::valid::{validity_errors, ValidityErrorKind};
+///
+/// let polygons = Geometry::MultiPolygon { coordinates: vec![
+///     //a bow tie
+///     vec![vec![coord!(0, 0), coord!(2, 2), coord!(2, 0), coord!(0, 2), coord!(0, 0)]],
+///     //a ring left open
+///     vec![vec![coord!(5, 0), coord!(6, 0), coord!(6, 1), coord!(5, 1)]],
+/// ] };
+/// let errors = validity_errors(&polygons);
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!((errors[0].kind, errors[0].location.clone()), (ValidityErrorKind::SelfIntersection, Some(coord!(1, 1))));
+/// assert_eq!((errors[1].kind, errors[1].location.clone()), (ValidityErrorKind::RingNotClosed, Some(coord!(5, 0))));
+/// ```
+pub fn validity_errors(geometry: &Geometry) -> Vec<ValidityError> {
+    let mut errors = Vec::new();
+    check(geometry, &mut errors);
+    errors
+}
+
+fn check(geometry: &Geometry, errors: &mut Vec<ValidityError>) {
+    match geometry {
+        Geometry::Point { .. } | Geometry::MultiPoint { .. } => {}
+        Geometry::LineString { coordinates } => check_line(coordinates, errors),
+        Geometry::LinearRing { coordinates } => check_ring(coordinates, errors),
+        Geometry::Polygon { coordinates } => check_polygon(coordinates, errors),
+        Geometry::MultiLineString { coordinates } => coordinates.iter().for_each(|line| check_line(line, errors)),
+        Geometry::MultiPolygon { coordinates } => {
+            coordinates.iter().for_each(|polygon| check_polygon(polygon, errors));
+            check_disjoint_interiors(coordinates, errors);
+        }
+        Geometry::GeometryCollection { geometries } => geometries.iter().for_each(|g| check(g, errors)),
+        Geometry::PolyhedralSurface { coordinates } => {
+            for patch in coordinates {
+                if patch.is_empty() {
+                    errors.push(error(ValidityErrorKind::InvalidPatch, &[]));
+                }
+                for ring in patch.iter().filter(|ring| !is_valid_patch_ring(ring)) {
+                    errors.push(error(ValidityErrorKind::InvalidPatch, ring));
+                }
+            }
+            check_oriented_manifold(coordinates.iter().flatten(), errors);
+        }
+        Geometry::Tin { coordinates } => {
+            for triangle in coordinates.iter().filter(|t| t.len() != 4 || !is_valid_patch_ring(t)) {
+                errors.push(error(ValidityErrorKind::InvalidPatch, triangle));
+            }
+            check_oriented_manifold(coordinates.iter(), errors);
+        }
+    }
+}
+
+/// Returns an error located at the first coordinate of the sequence, if it has one.
+fn error(kind: ValidityErrorKind, coordinates: &[Coordinate]) -> ValidityError {
+    ValidityError { kind, location: coordinates.first().cloned() }
+}
+
+/// Returns an error located at a coordinate.
+fn error_at(kind: ValidityErrorKind, location: Coordinate) -> ValidityError {
+    ValidityError { kind, location: Some(location) }
+}
+
+fn check_line(coordinates: &[Coordinate], errors: &mut Vec<ValidityError>) {
+    if !(coordinates.len() >= 2 && coordinates.iter().any(|c| !c.equals_2d(&coordinates[0]))) {
+        errors.push(error(ValidityErrorKind::TooFewPoints, coordinates));
+    }
+}
+
+fn check_ring(coordinates: &[Coordinate], errors: &mut Vec<ValidityError>) {
+    if coordinates.len() < 4 {
+        errors.push(error(ValidityErrorKind::TooFewPoints, coordinates));
+    }
+    if coordinates.is_empty() {
+        return;
+    }
+    if !coordinates[0].equals_2d(&coordinates[coordinates.len() - 1]) {
+        errors.push(error(ValidityErrorKind::RingNotClosed, coordinates));
+    }
+    let line = Geometry::LineString { coordinates: coordinates.to_vec() };
+    if line.is_simple() {
+        return;
+    }
+    let before = errors.len();
+    for w in coordinates.windows(2).filter(|w| w[0].equals_2d(&w[1])) {
+        errors.push(error(ValidityErrorKind::RepeatedPoint, &w[..1]));
+    }
+    if let Geometry::MultiPoint { coordinates: points } = line.self_intersections() {
+        errors.extend(points.into_iter().map(|location| error_at(ValidityErrorKind::SelfIntersection, location)));
+    }
+    //the ring is not simple for a reason the locations above do not show
+    if errors.len() == before {
+        errors.push(error(ValidityErrorKind::SelfIntersection, coordinates));
+    }
+}
+
+fn check_polygon(rings: &[Vec<Coordinate>], errors: &mut Vec<ValidityError>) {
+    let shell = match rings.first() {
+        Some(shell) => shell,
+        None => return,
+    };
+    rings.iter().for_each(|ring| check_ring(ring, errors));
+    for hole in &rings[1..] {
+        if let Some(outside) = hole.iter().find(|c| locate_in_ring(c, shell) == Location::Exterior) {
+            errors.push(error_at(ValidityErrorKind::HoleOutsideShell, outside.clone()));
+        }
+    }
+    let holes: Vec<Vec<Vec<Coordinate>>> = rings[1..].iter().map(|hole| vec![hole.clone()]).collect();
+    for (location, nested) in overlaps(&holes) {
+        let kind = if nested { ValidityErrorKind::NestedHoles } else { ValidityErrorKind::OverlappingHoles };
+        errors.push(error_at(kind, location));
+    }
+}
+
+/// Reports every pair of polygons whose interiors overlap.
+fn check_disjoint_interiors(polygons: &[Vec<Vec<Coordinate>>], errors: &mut Vec<ValidityError>) {
+    errors.extend(overlaps(polygons).into_iter().map(|(location, _)| error_at(ValidityErrorKind::OverlappingPolygons, location)));
+}
+
+/// Returns a location for every pair of polygons whose boundaries cross or share a segment, or
+/// where one polygon has a vertex or the midpoint of an edge of its shell inside of the other, once
+/// per pair in the order of the pairs. The flag is true for the pairs whose boundaries neither
+/// cross nor share a segment, where one polygon lies inside of the other.
+fn overlaps(polygons: &[Vec<Vec<Coordinate>>]) -> Vec<(Coordinate, bool)> {
+    let mut owners = Vec::new();
+    let mut segments = Vec::new();
+    for (i, polygon) in polygons.iter().enumerate() {
+        for w in polygon.iter().flat_map(|ring| ring.windows(2)) {
+            owners.push(i);
+            segments.push((&w[0], &w[1]));
+        }
+    }
+    //the first problem found for every pair of polygons
+    let mut found: HashMap<(usize, usize), (Coordinate, bool)> = HashMap::new();
+    sweep_segment_pairs(&segments, |s, t| {
+        let pair = (owners[s].min(owners[t]), owners[s].max(owners[t]));
+        if pair.0 == pair.1 || found.contains_key(&pair) {
+            return true;
+        }
+        let ((p1, p2), (q1, q2)) = (segments[s], segments[t]);
+        match intersection(p1, p2, q1, q2, ZInterpolation::Zero) {
+            SegmentIntersection::Point(p) if ![p1, p2, q1, q2].iter().any(|c| c.equals_2d(&p)) => {
+                found.insert(pair, (p, false));
+            }
+            SegmentIntersection::Collinear(a, b) if !a.equals_2d(&b) => {
+                found.insert(pair, (a, false));
+            }
+            _ => {}
+        }
+        true
+    });
+    //the boundaries only touch at points, so the polygons overlap if one is inside of the other
+    let shells: Vec<&[Coordinate]> = polygons.iter().map(|p| p.first().map_or(&[][..], |shell| &shell[..])).collect();
+    let tree = STRtree::new(shells.iter().enumerate().map(|(i, shell)| (Envelope::of_slice(shell), i)).collect());
+    for (i, shell) in shells.iter().enumerate() {
+        for &j in tree.query(&Envelope::of_slice(shell)) {
+            if i == j || found.contains_key(&(i.min(j), i.max(j))) {
+                continue;
+            }
+            let midpoints = shell.windows(2).map(|w| coord!((w[0].x() + w[1].x()) / 2.0, (w[0].y() + w[1].y()) / 2.0));
+            if let Some(inside) = shell.iter().cloned().chain(midpoints).find(|c| locate_in_polygon(c, &polygons[j]) == Location::Interior) {
+                found.insert((i.min(j), i.max(j)), (inside, true));
+            }
+        }
+    }
+    let mut found: Vec<((usize, usize), (Coordinate, bool))> = found.into_iter().collect();
+    found.sort_by_key(|(pair, _)| *pair);
+    found.into_iter().map(|(_, overlap)| overlap).collect()
+}
+
+fn is_valid_patch_ring(ring: &[Coordinate]) -> bool {
+    ring.len() >= 4 && ring[0] == ring[ring.len() - 1] && ring_area_3d(ring) > 0.0
+}
+
+/// Reports the edges used more than once in the same direction, which are also those used by more
+/// than two rings.
+fn check_oriented_manifold<'a>(rings: impl Iterator<Item = &'a Vec<Coordinate>>, errors: &mut Vec<ValidityError>) {
+    //for every undirected edge, the number of times it is traversed in each direction, in the
+    //order the edges are first met
+    let mut edges: HashMap<(&Coordinate, &Coordinate), (usize, usize, usize)> = HashMap::new();
+    for ring in rings {
+        for w in ring.windows(2) {
+            let (a, b) = (&w[0], &w[1]);
+            let forward = (a.x(), a.y(), a.z()) < (b.x(), b.y(), b.z());
+            let key = if forward { (a, b) } else { (b, a) };
+            let order = edges.len();
+            let entry = edges.entry(key).or_insert((order, 0, 0));
+            if forward {
+                entry.1 += 1;
+            } else {
+                entry.2 += 1;
+            }
+        }
+    }
+    let mut shared: Vec<(usize, &Coordinate)> = edges
+        .into_iter()
+        .filter(|(_, (_, forward, backward))| *forward > 1 || *backward > 1)
+        .map(|((a, _), (order, _, _))| (order, a))
+        .collect();
+    shared.sort_by_key(|(order, _)| *order);
+    errors.extend(shared.into_iter().map(|(_, a)| error_at(ValidityErrorKind::NonManifoldEdge, a.clone())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_all_errors_are_reported() {
+        let collection = Geometry::GeometryCollection { geometries: vec![
+            Geometry::LineString { coordinates: vec![coord!(3, 3)] },
+            //a shell with a repeated point and a spike folding back over its last edge, and a
+            //hole partly outside of it
+            Geometry::Polygon { coordinates: vec![
+                vec![coord!(0, 0), coord!(4, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 6), coord!(0, 0)],
+                vec![coord!(1, 1), coord!(5, 1), coord!(1, 2), coord!(1, 1)],
+            ] },
+            Geometry::Tin { coordinates: vec![
+                vec![coord!(0, 0, 0), coord!(1, 0, 0), coord!(0, 1, 0), coord!(0, 0, 0)],
+                vec![coord!(0, 0, 0), coord!(1, 0, 0), coord!(0, 0, 1), coord!(0, 0, 0)],
+                vec![coord!(2, 2, 2), coord!(2, 2, 2)],
+            ] },
+        ] };
+        let errors: Vec<(ValidityErrorKind, Coordinate)> = validity_errors(&collection).into_iter().map(|e| (e.kind, e.location.unwrap())).collect();
+        assert_eq!(errors, vec![
+            (ValidityErrorKind::TooFewPoints, coord!(3, 3)),
+            (ValidityErrorKind::RepeatedPoint, coord!(4, 0)),
+            (ValidityErrorKind::SelfIntersection, coord!(0, 4)),
+            (ValidityErrorKind::SelfIntersection, coord!(0, 6)),
+            (ValidityErrorKind::HoleOutsideShell, coord!(5, 1)),
+            (ValidityErrorKind::InvalidPatch, coord!(2, 2, 2)),
+            (ValidityErrorKind::NonManifoldEdge, coord!(0, 0, 0)),
+        ]);
+        assert!(!is_valid(&collection));
+        assert!(validity_errors(&Geometry::Polygon { coordinates: vec![] }).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_polygons() {
+        let square = |x: f64, y: f64, size: f64| vec![vec![
+            coord!(x, y), coord!(x + size, y), coord!(x + size, y + size), coord!(x, y + size), coord!(x, y)
+        ]];
+        let errors = |polygons: Vec<Vec<Vec<Coordinate>>>| -> Vec<Coordinate> {
+            validity_errors(&Geometry::MultiPolygon { coordinates: polygons }).into_iter().map(|e| {
+                assert_eq!(e.kind, ValidityErrorKind::OverlappingPolygons);
+                e.location.unwrap()
+            }).collect()
+        };
+        //touching at a corner, and one inside the hole of the other
+        let mut framed = square(10.0, 0.0, 4.0);
+        framed.push(vec![coord!(11, 1), coord!(11, 3), coord!(13, 3), coord!(13, 1), coord!(11, 1)]);
+        assert!(errors(vec![square(0.0, 0.0, 2.0), square(2.0, 2.0, 2.0), framed, square(11.5, 1.5, 1.0)]).is_empty());
+        //crossing, sharing an edge, and nested
+        assert_eq!(errors(vec![square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0)]), vec![coord!(1, 2)]);
+        assert_eq!(errors(vec![square(0.0, 0.0, 2.0), square(2.0, 1.0, 2.0)]), vec![coord!(2, 1)]);
+        assert_eq!(errors(vec![square(0.0, 0.0, 4.0), square(1.0, 1.0, 1.0)]), vec![coord!(1, 1)]);
+    }
+
+    #[test]
+    fn test_empty_components_have_no_location() {
+        let kinds = |geometry: Geometry| -> Vec<(ValidityErrorKind, Option<Coordinate>)> {
+            validity_errors(&geometry).into_iter().map(|e| (e.kind, e.location)).collect()
+        };
+        let too_few = vec![(ValidityErrorKind::TooFewPoints, None)];
+        assert_eq!(kinds(Geometry::LineString { coordinates: vec![] }), too_few);
+        assert_eq!(kinds(Geometry::LinearRing { coordinates: vec![] }), too_few);
+        assert_eq!(kinds(Geometry::Polygon { coordinates: vec![vec![]] }), too_few);
+        assert_eq!(kinds(Geometry::PolyhedralSurface { coordinates: vec![vec![]] }), vec![(ValidityErrorKind::InvalidPatch, None)]);
+        assert!(!is_valid(&Geometry::LineString { coordinates: vec![] }));
+        assert_eq!(validity_errors(&Geometry::LinearRing { coordinates: vec![] })[0].to_string(), "too few points");
+    }
+
+    #[test]
+    fn test_holes_do_not_overlap_or_nest() {
+        let square = |x: f64, y: f64, size: f64| vec![
+            coord!(x, y), coord!(x, y + size), coord!(x + size, y + size), coord!(x + size, y), coord!(x, y)
+        ];
+        let errors = |holes: Vec<Vec<Coordinate>>| -> Vec<(ValidityErrorKind, Coordinate)> {
+            let mut rings = vec![vec![coord!(0, 0), coord!(20, 0), coord!(20, 20), coord!(0, 20), coord!(0, 0)]];
+            rings.extend(holes);
+            validity_errors(&Geometry::Polygon { coordinates: rings }).into_iter().map(|e| (e.kind, e.location.unwrap())).collect()
+        };
+        //apart, and touching at a corner
+        assert!(errors(vec![square(1.0, 1.0, 2.0), square(3.0, 3.0, 2.0), square(10.0, 10.0, 2.0)]).is_empty());
+        //crossing, and sharing an edge
+        assert_eq!(errors(vec![square(1.0, 1.0, 4.0), square(3.0, 3.0, 4.0)]), vec![(ValidityErrorKind::OverlappingHoles, coord!(3, 5))]);
+        assert_eq!(errors(vec![square(1.0, 1.0, 2.0), square(3.0, 1.0, 2.0)]), vec![(ValidityErrorKind::OverlappingHoles, coord!(3, 1))]);
+        //one inside of the other
+        assert_eq!(errors(vec![square(1.0, 1.0, 8.0), square(2.0, 2.0, 2.0)]), vec![(ValidityErrorKind::NestedHoles, coord!(2, 2))]);
+        assert_eq!(errors(vec![square(2.0, 2.0, 2.0), square(1.0, 1.0, 8.0)]), vec![(ValidityErrorKind::NestedHoles, coord!(2, 2))]);
+    }
+}
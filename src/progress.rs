@@ -0,0 +1,147 @@
+//! Progress reports and cancellation of long operations, such as the union of thousands of
+//! polygons, so applications can show progress bars and abort cleanly.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::Error;
+
+/// Receives the progress of an operation and tells it whether to stop. Operations taking a sink
+/// report the number of steps done out of the total as they go, and return
+/// [`Error::Cancelled`] soon after the sink asks them to stop.
+///
+/// Closures taking the steps done and the total are sinks that never cancel, a
+/// [`CancellationToken`] is a sink that reports nowhere, and `()` does neither.
+pub trait ProgressSink: Sync {
+    /// Receives the number of steps done out of the total. The total may grow as the operation
+    /// learns about its work, but the steps done never go back.
+    fn report(&self, done: usize, total: usize);
+
+    /// Returns true if the operation should stop as soon as possible.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl ProgressSink for () {
+    fn report(&self, _: usize, _: usize) {}
+}
+
+impl<F: Fn(usize, usize) + Sync> ProgressSink for F {
+    fn report(&self, done: usize, total: usize) {
+        self(done, total)
+    }
+}
+
+/// A flag shared between the clones of a token, cancelling the operations it is given to when
+/// any of them is cancelled, as from the thread of a user interface.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::error::Error;
+/// use geoms::geometry::Geometry;
+/// use geoms::operation::overlay::unary_union_with_progress;
+/// use geoms::progress::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// handle.cancel();
+/// let squares = Geometry::MultiPolygon { coordinates: (0..10).map(|i| vec![vec![
+///     coord!(i, 0), coord!(i + 2, 0), coord!(i + 2, 2), coord!(i, 2), coord!(i, 0)
+/// ]]).collect() };
+/// assert_eq!(unary_union_with_progress(&squares, &token), Err(Error::Cancelled));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Returns a token that is not cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels the operations given this token or any of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl ProgressSink for CancellationToken {
+    fn report(&self, _: usize, _: usize) {}
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Reports the progress to the sink, and returns an error if it asks to stop.
+pub(crate) fn step(progress: &dyn ProgressSink, done: usize, total: usize) -> Result<(), Error> {
+    progress.report(done, total);
+    match progress.is_cancelled() {
+        true => Err(Error::Cancelled),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+    use crate::geometry::Geometry;
+    use crate::operation::buffer::buffer_with_progress;
+    use crate::operation::overlay::unary_union_with_progress;
+    use crate::triangulate::delaunay_with_progress;
+    use std::sync::Mutex;
+
+    /// Records the reports and cancels after a number of them.
+    struct Recorder {
+        reports: Mutex<Vec<(usize, usize)>>,
+        limit: usize,
+    }
+
+    impl ProgressSink for Recorder {
+        fn report(&self, done: usize, total: usize) {
+            self.reports.lock().unwrap().push((done, total));
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.reports.lock().unwrap().len() >= self.limit
+        }
+    }
+
+    #[test]
+    fn test_reports_and_cancellation_of_long_operations() {
+        let recorder = |limit: usize| Recorder { reports: Mutex::new(Vec::new()), limit };
+        let squares = Geometry::MultiPolygon { coordinates: (0..8).map(|i| vec![vec![
+            coord!(i, 0), coord!(i + 2, 0), coord!(i + 2, 2), coord!(i, 2), coord!(i, 0)
+        ]]).collect() };
+        //eight parts are unioned in seven steps
+        let unlimited = recorder(usize::MAX);
+        assert_eq!(unary_union_with_progress(&squares, &unlimited).unwrap().area(), 18.0);
+        let reports = unlimited.reports.into_inner().unwrap();
+        assert_eq!(reports, (1..=7).map(|done| (done, 7)).collect::<Vec<_>>());
+        let limited = recorder(3);
+        assert_eq!(unary_union_with_progress(&squares, &limited), Err(Error::Cancelled));
+        assert_eq!(limited.reports.into_inner().unwrap().len(), 3);
+
+        let line = Geometry::LineString { coordinates: (0..50).map(|i| coord!(i, (i % 2) as f64)).collect() };
+        let reports = Mutex::new(Vec::new());
+        let buffered = buffer_with_progress(&line, 0.5, &|done, total| reports.lock().unwrap().push((done, total))).unwrap();
+        assert_eq!(buffered.num_components(), 1);
+        assert_eq!(reports.into_inner().unwrap().last(), Some(&(48, 48)));
+        assert_eq!(buffer_with_progress(&line, 0.5, &recorder(10)), Err(Error::Cancelled));
+
+        let points: Vec<Coordinate> = (0..3000).map(|i| coord!((i * 7919 % 3001) as f64, (i * 104_729 % 2999) as f64)).collect();
+        let triangles = delaunay_with_progress(&points, &()).unwrap();
+        let counted = Mutex::new(0);
+        assert_eq!(delaunay_with_progress(&points, &|_, _| *counted.lock().unwrap() += 1).unwrap(), triangles);
+        assert!(*counted.lock().unwrap() > 1);
+        assert_eq!(delaunay_with_progress(&points, &recorder(1)), Err(Error::Cancelled));
+    }
+}
@@ -0,0 +1,512 @@
+//! Conversion of geometries to grids of cells.
+//!
+//! Points cover the cell containing them as given by [`Grid::cell_of`], with the right and bottom
+//! edges of the grid counted in its last column and row. Lines cover every cell they touch, even
+//! at a single corner.
+
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+use crate::grid::Grid;
+
+/// The rule deciding which cells a polygon covers. Points always cover the cell they lie in, and
+/// lines the cells they touch.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Rule {
+    /// The cells whose center lies in the polygon.
+    #[default]
+    CenterPoint,
+    /// The cells whose center lies in the polygon and the cells touched by its boundary.
+    AllTouched,
+}
+
+/// Returns a grid over the envelope holding 1 in the cells covered by the geometry and 0 elsewhere.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to rasterize.
+/// * `envelope` - The area covered by the grid.
+/// * `resolution` - The size of the cells.
+/// * `rule` - The rule deciding which cells a polygon covers.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::raster::{rasterize, Rule};
+///
+/// let triangle = Geometry::Polygon { coordinates: vec![
+///     vec![coord!(0, 0), coord!(3.2, 0), coord!(0, 3.2), coord!(0, 0)]
+/// ] };
+/// let envelope = Envelope::new(0.0, 0.0, 3.0, 3.0);
+/// assert_eq!(rasterize(&triangle, &envelope, 1.0, Rule::CenterPoint).into_values(), vec![
+///     1.0, 0.0, 0.0,
+///     1.0, 1.0, 0.0,
+///     1.0, 1.0, 1.0,
+/// ]);
+/// assert_eq!(rasterize(&triangle, &envelope, 1.0, Rule::AllTouched).into_values(), vec![
+///     1.0, 1.0, 0.0,
+///     1.0, 1.0, 1.0,
+///     1.0, 1.0, 1.0,
+/// ]);
+/// ```
+pub fn rasterize(geometry: &Geometry, envelope: &Envelope, resolution: f64, rule: Rule) -> Grid {
+    let mut grid = Grid::new(envelope, resolution);
+    grid.fill(0.0);
+    burn(geometry, &mut grid, 1.0, rule);
+    grid
+}
+
+/// Sets the cells of the grid covered by the geometry to the value, leaving the others unchanged.
+/// Burning several geometries with different values produces a weight or category grid.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to burn.
+/// * `grid` - The grid receiving the value.
+/// * `value` - The value given to the covered cells.
+/// * `rule` - The rule deciding which cells a polygon covers.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::grid::Grid;
+/// use geoms::raster::{burn, Rule};
+///
+/// let mut grid = Grid::new(&Envelope::new(0.0, 0.0, 4.0, 1.0), 1.0);
+/// let line = Geometry::LineString { coordinates: vec![coord!(0.5, 0.5), coord!(2.5, 0.5)] };
+/// burn(&line, &mut grid, 5.0, Rule::CenterPoint);
+/// burn(&Geometry::Point { coordinates: coord!(3.5, 0.5) }, &mut grid, 7.0, Rule::CenterPoint);
+/// assert_eq!(grid.values()[..], [5.0, 5.0, 5.0, 7.0]);
+/// ```
+pub fn burn(geometry: &Geometry, grid: &mut Grid, value: f64, rule: Rule) {
+    for (row, column) in cells(geometry, grid, rule) {
+        grid.set(row, column, value);
+    }
+}
+
+/// Returns the row and column of the cells of the grid covered by the geometry, row by row.
+pub(crate) fn cells(geometry: &Geometry, grid: &Grid, rule: Rule) -> Vec<(usize, usize)> {
+    let mut covered = vec![false; grid.rows() * grid.columns()];
+    mark(geometry, grid, rule, &mut |row, column| covered[row * grid.columns() + column] = true);
+    (0..covered.len()).filter(|&i| covered[i]).map(|i| (i / grid.columns(), i % grid.columns())).collect()
+}
+
+fn mark(geometry: &Geometry, grid: &Grid, rule: Rule, cover: &mut impl FnMut(usize, usize)) {
+    match geometry {
+        Geometry::Point { coordinates } => mark_point(coordinates, grid, cover),
+        Geometry::MultiPoint { coordinates } => {
+            for point in coordinates {
+                mark_point(point, grid, cover);
+            }
+        }
+        Geometry::LineString { coordinates } | Geometry::LinearRing { coordinates } => {
+            mark_line(coordinates, grid, cover)
+        }
+        Geometry::MultiLineString { coordinates } => {
+            for line in coordinates {
+                mark_line(line, grid, cover);
+            }
+        }
+        Geometry::Polygon { coordinates } => mark_polygon(coordinates, grid, rule, cover),
+        Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+            for polygon in coordinates {
+                mark_polygon(polygon, grid, rule, cover);
+            }
+        }
+        Geometry::Tin { coordinates } => {
+            for triangle in coordinates {
+                mark_polygon(std::slice::from_ref(triangle), grid, rule, cover);
+            }
+        }
+        Geometry::GeometryCollection { geometries } => {
+            for geometry in geometries {
+                mark(geometry, grid, rule, cover);
+            }
+        }
+    }
+}
+
+fn mark_point(point: &Coordinate, grid: &Grid, cover: &mut impl FnMut(usize, usize)) {
+    if let Some((row, column)) = clamped_cell_of(point.x(), point.y(), grid) {
+        cover(row, column);
+    }
+}
+
+/// Returns the cell containing the location, counting the right and bottom edges of the grid in
+/// its last column and row.
+fn clamped_cell_of(x: f64, y: f64, grid: &Grid) -> Option<(usize, usize)> {
+    let envelope = grid.envelope();
+    if !envelope.contains_coordinate(&Coordinate::new(x, y, 0.0)) {
+        return None;
+    }
+    let column = ((x - envelope.min_x()) / grid.resolution()).floor() as usize;
+    let row = ((envelope.max_y() - y) / grid.resolution()).floor() as usize;
+    Some((row.min(grid.rows() - 1), column.min(grid.columns() - 1)))
+}
+
+/// Covers the cells the line touches, walking every segment column by column.
+fn mark_line(line: &[Coordinate], grid: &Grid, cover: &mut impl FnMut(usize, usize)) {
+    if line.len() == 1 {
+        mark_point(&line[0], grid, cover);
+    }
+    let envelope = grid.envelope();
+    let resolution = grid.resolution();
+    //the first and last cells whose closed extent reaches the range of distances from the origin
+    let range = |from: f64, to: f64, count: usize| {
+        let first = ((from / resolution).ceil() - 1.0).max(0.0) as usize;
+        let last = ((to / resolution).floor() as usize).min(count - 1);
+        (first, last)
+    };
+    for segment in line.windows(2) {
+        let (a, b) = if segment[0].x() <= segment[1].x() {
+            (&segment[0], &segment[1])
+        } else {
+            (&segment[1], &segment[0])
+        };
+        //the part of the segment inside of the grid
+        let x_min = a.x().max(envelope.min_x());
+        let x_max = b.x().min(envelope.max_x());
+        if x_min > x_max {
+            continue;
+        }
+        let y_at = |x: f64| a.y() + (x - a.x()) * (b.y() - a.y()) / (b.x() - a.x());
+        let (first, last) = range(x_min - envelope.min_x(), x_max - envelope.min_x(), grid.columns());
+        for column in first..=last {
+            //the y range of the segment in the column
+            let left = (envelope.min_x() + column as f64 * resolution).max(x_min);
+            let right = (envelope.min_x() + (column + 1) as f64 * resolution).min(x_max);
+            let (y1, y2) = if a.x() == b.x() { (a.y(), b.y()) } else { (y_at(left), y_at(right)) };
+            let y_min = y1.min(y2).max(envelope.min_y());
+            let y_max = y1.max(y2).min(envelope.max_y());
+            if y_min > y_max {
+                continue;
+            }
+            let (top, bottom) = range(envelope.max_y() - y_max, envelope.max_y() - y_min, grid.rows());
+            for row in top..=bottom {
+                cover(row, column);
+            }
+        }
+    }
+}
+
+/// Covers the cells whose center lies in the polygon, scanning the rows of the grid with the
+/// even-odd rule, and the cells touched by the rings if required.
+fn mark_polygon(rings: &[Vec<Coordinate>], grid: &Grid, rule: Rule, cover: &mut impl FnMut(usize, usize)) {
+    let envelope = grid.envelope();
+    let resolution = grid.resolution();
+    let extent = Envelope::of(rings.iter().flatten());
+    if !extent.intersects(&envelope) {
+        return;
+    }
+    let mut crossings = Vec::new();
+    for row in 0..grid.rows() {
+        let y = envelope.max_y() - (row as f64 + 0.5) * resolution;
+        if y < extent.min_y() || y > extent.max_y() {
+            continue;
+        }
+        crossings.clear();
+        for ring in rings {
+            for edge in ring.windows(2) {
+                let (a, b) = (&edge[0], &edge[1]);
+                if (a.y() <= y) != (b.y() <= y) {
+                    crossings.push(a.x() + (y - a.y()) * (b.x() - a.x()) / (b.y() - a.y()));
+                }
+            }
+        }
+        crossings.sort_by(f64::total_cmp);
+        for span in crossings.chunks_exact(2) {
+            //the columns whose center lies in the span
+            let first = ((span[0] - envelope.min_x()) / resolution - 0.5).ceil().max(0.0) as usize;
+            let last = ((span[1] - envelope.min_x()) / resolution - 0.5).ceil().min(grid.columns() as f64) as usize;
+            for column in first..last {
+                cover(row, column);
+            }
+        }
+    }
+    if rule == Rule::AllTouched {
+        for ring in rings {
+            mark_line(ring, grid, cover);
+        }
+    }
+}
+
+/// Statistics of the values of the grid cells covered by a geometry. With partial cell weighting,
+/// every cell counts as the fraction of its area covered by the geometry.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ZonalStats {
+    count: f64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl ZonalStats {
+    /// Returns the number of covered cells with data, or their total weight.
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    /// Returns the sum of the values of the covered cells, each multiplied by its weight.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Returns the smallest value of the covered cells, or None if no cell with data is covered.
+    pub fn min(&self) -> Option<f64> {
+        if self.count > 0.0 { Some(self.min) } else { None }
+    }
+
+    /// Returns the largest value of the covered cells, or None if no cell with data is covered.
+    pub fn max(&self) -> Option<f64> {
+        if self.count > 0.0 { Some(self.max) } else { None }
+    }
+
+    /// Returns the weighted mean of the values of the covered cells, or None if no cell with data
+    /// is covered.
+    pub fn mean(&self) -> Option<f64> {
+        if self.count > 0.0 { Some(self.sum / self.count) } else { None }
+    }
+
+    fn of(values: impl Iterator<Item = (f64, f64)>) -> ZonalStats {
+        let mut stats = ZonalStats { count: 0.0, sum: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY };
+        for (value, weight) in values {
+            if value.is_nan() || weight <= 0.0 {
+                continue;
+            }
+            stats.count += weight;
+            stats.sum += value * weight;
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+        }
+        stats
+    }
+}
+
+/// Returns the statistics of the values of the grid cells covered by a geometry, skipping cells
+/// without data.
+///
+/// # Arguments
+///
+/// * `geometry` - The zone, usually a polygon.
+/// * `grid` - The grid with the values.
+/// * `rule` - The rule deciding which cells a polygon covers.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::grid::Grid;
+/// use geoms::raster::{zonal_stats, Rule};
+///
+/// let mut grid = Grid::new(&Envelope::new(0.0, 0.0, 2.0, 2.0), 1.0);
+/// grid.set(0, 0, 1.0);
+/// grid.set(0, 1, 2.0);
+/// grid.set(1, 0, 3.0);
+/// let zone = Geometry::Polygon { coordinates: vec![
+///     vec![coord!(0, 0), coord!(2, 0), coord!(2, 1.8), coord!(0, 1.8), coord!(0, 0)]
+/// ] };
+/// let stats = zonal_stats(&zone, &grid, Rule::CenterPoint);
+/// assert_eq!(stats.count(), 3.0);
+/// assert_eq!(stats.sum(), 6.0);
+/// assert_eq!((stats.min(), stats.max(), stats.mean()), (Some(1.0), Some(3.0), Some(2.0)));
+/// ```
+pub fn zonal_stats(geometry: &Geometry, grid: &Grid, rule: Rule) -> ZonalStats {
+    ZonalStats::of(cells(geometry, grid, rule).into_iter().map(|(row, column)| (grid.get(row, column).unwrap(), 1.0)))
+}
+
+/// Returns the statistics of the values of the grid cells covered by a geometry, weighting every
+/// cell by the fraction of its area covered by the polygons of the geometry. Points and lines give
+/// a weight of 1 to the cells they cover. Cells without data are skipped.
+///
+/// # Arguments
+///
+/// * `geometry` - The zone, usually a polygon.
+/// * `grid` - The grid with the values.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::envelope::Envelope;
+/// use geoms::geometry::Geometry;
+/// use geoms::grid::Grid;
+/// use geoms::raster::zonal_stats_weighted;
+///
+/// let mut grid = Grid::new(&Envelope::new(0.0, 0.0, 2.0, 1.0), 1.0);
+/// grid.set(0, 0, 10.0);
+/// grid.set(0, 1, 20.0);
+/// let zone = Geometry::Polygon { coordinates: vec![
+///     vec![coord!(0, 0), coord!(1.5, 0), coord!(1.5, 1), coord!(0, 1), coord!(0, 0)]
+/// ] };
+/// let stats = zonal_stats_weighted(&zone, &grid);
+/// assert_eq!(stats.count(), 1.5);
+/// assert_eq!(stats.sum(), 20.0);
+/// ```
+pub fn zonal_stats_weighted(geometry: &Geometry, grid: &Grid) -> ZonalStats {
+    let mut weights = vec![0.0; grid.rows() * grid.columns()];
+    weigh(geometry, grid, &mut weights);
+    ZonalStats::of(weights.into_iter().zip(grid.values()).map(|(weight, &value)| (value, weight.min(1.0))))
+}
+
+/// Adds to the weights of the cells the fraction of their area covered by the geometry.
+fn weigh(geometry: &Geometry, grid: &Grid, weights: &mut [f64]) {
+    let columns = grid.columns();
+    match geometry {
+        Geometry::Polygon { coordinates } => weigh_polygon(coordinates, grid, weights),
+        Geometry::MultiPolygon { coordinates } | Geometry::PolyhedralSurface { coordinates } => {
+            for polygon in coordinates {
+                weigh_polygon(polygon, grid, weights);
+            }
+        }
+        Geometry::Tin { coordinates } => {
+            for triangle in coordinates {
+                weigh_polygon(std::slice::from_ref(triangle), grid, weights);
+            }
+        }
+        Geometry::GeometryCollection { geometries } => {
+            for geometry in geometries {
+                weigh(geometry, grid, weights);
+            }
+        }
+        _ => {
+            for (row, column) in cells(geometry, grid, Rule::CenterPoint) {
+                weights[row * columns + column] = 1.0;
+            }
+        }
+    }
+}
+
+/// Cells away from the rings are either fully covered or not at all, so only the cells touched by
+/// the rings are clipped to measure their coverage.
+fn weigh_polygon(rings: &[Vec<Coordinate>], grid: &Grid, weights: &mut [f64]) {
+    let columns = grid.columns();
+    let mut touched = vec![false; weights.len()];
+    for ring in rings {
+        mark_line(ring, grid, &mut |row, column| touched[row * columns + column] = true);
+    }
+    mark_polygon(rings, grid, Rule::CenterPoint, &mut |row, column| {
+        if !touched[row * columns + column] {
+            weights[row * columns + column] += 1.0;
+        }
+    });
+    let cell_area = grid.resolution() * grid.resolution();
+    for (i, _) in touched.iter().enumerate().filter(|(_, &touched)| touched) {
+        let cell = grid.cell_envelope(i / columns, i % columns);
+        let mut areas = rings.iter().map(|ring| clipped_area(ring, &cell));
+        let shell = areas.next().unwrap_or(0.0);
+        let holes: f64 = areas.sum();
+        weights[i] += ((shell - holes) / cell_area).max(0.0);
+    }
+}
+
+/// Returns the area of the part of a ring inside of an envelope, clipping it against every side
+/// of the envelope with the Sutherland-Hodgman algorithm.
+fn clipped_area(ring: &[Coordinate], envelope: &Envelope) -> f64 {
+    let mut points: Vec<(f64, f64)> = ring.iter().map(|c| (c.x(), c.y())).collect();
+    //the distance inside of every side, positive inside
+    let sides: [&dyn Fn((f64, f64)) -> f64; 4] = [
+        &|p| p.0 - envelope.min_x(),
+        &|p| envelope.max_x() - p.0,
+        &|p| p.1 - envelope.min_y(),
+        &|p| envelope.max_y() - p.1,
+    ];
+    for inside in sides {
+        let mut clipped = Vec::with_capacity(points.len() + 4);
+        for k in 0..points.len() {
+            let (a, b) = (points[k], points[(k + 1) % points.len()]);
+            let (da, db) = (inside(a), inside(b));
+            if da >= 0.0 {
+                clipped.push(a);
+            }
+            if (da >= 0.0) != (db >= 0.0) {
+                let t = da / (da - db);
+                clipped.push((a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1)));
+            }
+        }
+        points = clipped;
+        if points.is_empty() {
+            return 0.0;
+        }
+    }
+    let n = points.len();
+    let sum: f64 = (0..n)
+        .map(|k| {
+            let (a, b) = (points[k], points[(k + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+    (sum / 2.0).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_polygon_with_hole() {
+        let polygon = Geometry::Polygon {
+            coordinates: vec![
+                vec![coord!(0, 0), coord!(5, 0), coord!(5, 5), coord!(0, 5), coord!(0, 0)],
+                vec![coord!(1, 1), coord!(1, 4), coord!(4, 4), coord!(4, 1), coord!(1, 1)],
+            ],
+        };
+        let envelope = Envelope::new(-1.0, -1.0, 6.0, 6.0);
+        let grid = rasterize(&polygon, &envelope, 1.0, Rule::CenterPoint);
+        assert_eq!(grid.values().iter().sum::<f64>(), 16.0);
+        assert_eq!(grid.get(3, 3), Some(0.0));
+        assert_eq!(grid.get(1, 1), Some(1.0));
+        //the boundary of the hole touches the cells around it
+        let grid = rasterize(&polygon, &envelope, 1.0, Rule::AllTouched);
+        assert_eq!(grid.values().iter().sum::<f64>(), 48.0);
+        assert_eq!(grid.get(3, 3), Some(0.0));
+    }
+
+    #[test]
+    fn test_weighted_zonal_stats() {
+        //a diamond covering half of a 2 by 2 square of cells
+        let diamond = Geometry::Polygon {
+            coordinates: vec![vec![coord!(1, 0), coord!(2, 1), coord!(1, 2), coord!(0, 1), coord!(1, 0)]],
+        };
+        let mut grid = Grid::new(&Envelope::new(0.0, 0.0, 2.0, 2.0), 1.0);
+        grid.fill(4.0);
+        let stats = zonal_stats_weighted(&diamond, &grid);
+        assert!((stats.count() - 2.0).abs() < 1e-12);
+        assert!((stats.sum() - 8.0).abs() < 1e-12);
+        //weights add up to the area of the polygon in cells, holes and all
+        let polygon = Geometry::Polygon {
+            coordinates: vec![
+                vec![coord!(0.3, 0.2), coord!(7.7, 0.4), coord!(6.1, 5.9), coord!(0.3, 0.2)],
+                vec![coord!(3.1, 1.1), coord!(4.2, 2.9), coord!(5.3, 1.3), coord!(3.1, 1.1)],
+            ],
+        };
+        let mut grid = Grid::new(&Envelope::new(0.0, 0.0, 8.0, 6.0), 0.5);
+        grid.fill(1.0);
+        let stats = zonal_stats_weighted(&polygon, &grid);
+        assert!((stats.count() * 0.25 - polygon.area()).abs() < 1e-9);
+        assert_eq!(zonal_stats_weighted(&polygon, &Grid::new(&Envelope::new(0.0, 0.0, 8.0, 6.0), 0.5)).mean(), None);
+    }
+
+    #[test]
+    fn test_diagonal_line() {
+        let line = Geometry::LineString { coordinates: vec![coord!(-5, -5), coord!(2.5, 2.5)] };
+        let grid = rasterize(&line, &Envelope::new(0.0, 0.0, 4.0, 4.0), 1.0, Rule::CenterPoint);
+        let covered: Vec<(usize, usize)> = (0..4)
+            .flat_map(|row| (0..4).map(move |column| (row, column)))
+            .filter(|&(row, column)| grid.get(row, column) == Some(1.0))
+            .collect();
+        assert_eq!(covered, vec![(1, 1), (1, 2), (2, 0), (2, 1), (2, 2), (3, 0), (3, 1)]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Constructors for common synthetic shapes. Angles are given in radians, counter clockwise from
+//! the positive x axis, except for the bearings of sectors, and polygons are built with counter
+//! clockwise shells.
+
+use std::f64::consts::TAU;
+
+use crate::coordinate::Coordinate;
+use crate::geometry::Geometry;
+
+/// The number of segments used by [`arc`] to approximate a full circle.
+pub const ARC_SEGMENTS_PER_CIRCLE: usize = 32;
+
+/// The largest number of segments of the polygons approximating circles and ellipses, far finer
+/// than any use needs, which keeps a number of segments taken from user input from allocating
+/// without bound.
+pub const MAX_SEGMENTS: usize = 1 << 16;
+
+/// Returns a polygon approximating a circle.
+///
+/// # Arguments
+///
+/// * `center` - The center of the circle. Its z value is given to every vertex.
+/// * `radius` - The radius of the circle.
+/// * `segments` - The number of segments of the polygon, at least 3.
+///
+/// # Panics
+///
+/// Panics if there are fewer than 3 segments or more than [`MAX_SEGMENTS`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::shapes::circle;
+///
+/// let square = circle(&coord!(0, 0), 1.0, 4);
+/// assert!((square.area() - 2.0).abs() < 1e-12);
+///
+/// let circle = circle(&coord!(0, 0), 1.0, 1000);
+/// assert!((circle.area() - std::f64::consts::PI).abs() < 1e-4);
+/// ```
+pub fn circle(center: &Coordinate, radius: f64, segments: usize) -> Geometry {
+    ellipse(center, radius, radius, 0.0, segments)
+}
+
+/// Returns a polygon approximating an ellipse.
+///
+/// # Arguments
+///
+/// * `center` - The center of the ellipse. Its z value is given to every vertex.
+/// * `rx` - The radius along the x axis, before rotation.
+/// * `ry` - The radius along the y axis, before rotation.
+/// * `rotation` - The rotation of the ellipse around its center.
+/// * `segments` - The number of segments of the polygon, at least 3.
+///
+/// # Panics
+///
+/// Panics if there are fewer than 3 segments or more than [`MAX_SEGMENTS`].
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::shapes::ellipse;
+///
+/// let ellipse = ellipse(&coord!(0, 0), 2.0, 1.0, std::f64::consts::FRAC_PI_2, 4);
+/// if let Geometry::Polygon { coordinates } = ellipse {
+///     //the first vertex lies on the rotated x axis
+///     assert!(coordinates[0][0].equals_2d_with_tolerance(&coord!(0, 2), 1e-12));
+///     assert_eq!(coordinates[0].len(), 5);
+/// }
+/// ```
+pub fn ellipse(center: &Coordinate, rx: f64, ry: f64, rotation: f64, segments: usize) -> Geometry {
+    if segments < 3 {
+        panic!("A polygon needs at least 3 segments");
+    }
+    if segments > MAX_SEGMENTS {
+        panic!("A polygon can have at most {} segments", MAX_SEGMENTS);
+    }
+    let (sin, cos) = rotation.sin_cos();
+    let mut ring: Vec<Coordinate> = (0..segments)
+        .map(|i| {
+            let angle = TAU * i as f64 / segments as f64;
+            let (x, y) = (rx * angle.cos(), ry * angle.sin());
+            Coordinate::new(center.x() + x * cos - y * sin, center.y() + x * sin + y * cos, center.z())
+        })
+        .collect();
+    ring.push(ring[0].clone());
+    Geometry::Polygon { coordinates: vec![ring] }
+}
+
+/// Returns a line string approximating a circular arc, using [`ARC_SEGMENTS_PER_CIRCLE`] segments
+/// for a full turn. The arc goes counter clockwise if `end_angle` is greater than `start_angle`, and
+/// clockwise otherwise.
+///
+/// # Arguments
+///
+/// * `center` - The center of the arc. Its z value is given to every vertex.
+/// * `radius` - The radius of the arc.
+/// * `start_angle` - The angle of the first vertex.
+/// * `end_angle` - The angle of the last vertex.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::shapes::arc;
+///
+/// let quarter = arc(&coord!(0, 0), 1.0, 0.0, std::f64::consts::FRAC_PI_2);
+/// if let Geometry::LineString { coordinates } = quarter {
+///     assert_eq!(coordinates.len(), 9);
+///     assert!(coordinates[0].equals_2d_with_tolerance(&coord!(1, 0), 1e-12));
+///     assert!(coordinates[8].equals_2d_with_tolerance(&coord!(0, 1), 1e-12));
+/// }
+/// ```
+pub fn arc(center: &Coordinate, radius: f64, start_angle: f64, end_angle: f64) -> Geometry {
+    let sweep = end_angle - start_angle;
+    let segments = ((sweep.abs() / TAU * ARC_SEGMENTS_PER_CIRCLE as f64).ceil() as usize).max(1);
+    let coordinates = (0..=segments)
+        .map(|i| {
+            let angle = start_angle + sweep * i as f64 / segments as f64;
+            Coordinate::new(center.x() + radius * angle.cos(), center.y() + radius * angle.sin(), center.z())
+        })
+        .collect();
+    Geometry::LineString { coordinates }
+}
+
+/// Returns a polygon approximating the sector of a circle between two bearings, in degrees
+/// clockwise from north as in [`Geometry::bearing_to`], going clockwise from the start bearing to
+/// the end bearing. The arc uses [`ARC_SEGMENTS_PER_CIRCLE`] segments for a full turn, and equal
+/// bearings give the whole circle.
+///
+/// # Arguments
+///
+/// * `center` - The center of the circle. Its z value is given to every vertex.
+/// * `radius` - The radius of the circle.
+/// * `start_bearing` - The bearing of the first side of the sector.
+/// * `end_bearing` - The bearing of the second side of the sector.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::shapes::sector;
+///
+/// //the coverage of an antenna pointing north east, wrapping around north
+/// let coverage = sector(&coord!(0, 0), 10.0, 330.0, 120.0);
+/// assert!(coverage.contains_point(&coord!(0, 5)));
+/// assert!(coverage.contains_point(&coord!(5, -1)));
+/// assert!(!coverage.contains_point(&coord!(-5, 1)));
+/// let exact = 100.0 * std::f64::consts::PI * 150.0 / 360.0;
+/// assert!((coverage.area() / exact - 1.0).abs() < 0.01);
+/// ```
+pub fn sector(center: &Coordinate, radius: f64, start_bearing: f64, end_bearing: f64) -> Geometry {
+    let sweep = match (end_bearing - start_bearing).rem_euclid(360.0) {
+        0.0 => 360.0,
+        sweep => sweep,
+    };
+    //bearings turn clockwise from north, so the arc is walked backwards to keep the shell counter
+    //clockwise
+    let start_angle = (90.0 - start_bearing - sweep).to_radians();
+    let Geometry::LineString { coordinates: mut ring } = arc(center, radius, start_angle, start_angle + sweep.to_radians()) else {
+        unreachable!()
+    };
+    if sweep < 360.0 {
+        ring.insert(0, center.clone());
+        ring.push(center.clone());
+    } else {
+        let first = ring[0].clone();
+        *ring.last_mut().unwrap() = first;
+    }
+    Geometry::Polygon { coordinates: vec![ring] }
+}
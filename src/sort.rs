@@ -0,0 +1,176 @@
+//! Spatial sorting of geometries along space filling curves, so geometries close in the sorted
+//! order are also close in space, which helps operations that process geometries in batches,
+//! such as building spatial indexes or merging many polygons.
+
+use crate::envelope::Envelope;
+use crate::geometry::Geometry;
+
+/// The order of the grids geometries are placed on for sorting, 2^16 cells along each axis.
+const SORT_ORDER: u32 = 16;
+
+/// Returns the distance along the Hilbert curve filling a grid of 2^order by 2^order cells to
+/// the cell at column `x` and row `y`. The curve starts at the cell (0, 0) and ends at the cell
+/// (2^order - 1, 0), and consecutive distances are always neighbouring cells.
+///
+/// # Arguments
+///
+/// * `x` - The column of the cell, below 2^order.
+/// * `y` - The row of the cell, below 2^order.
+/// * `order` - The order of the curve, at most 32.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::sort::hilbert_index;
+///
+/// //the curve of order 1 visits the four cells in a U shape
+/// let cells = [(0, 0), (0, 1), (1, 1), (1, 0)];
+/// for (distance, (x, y)) in cells.into_iter().enumerate() {
+///     assert_eq!(hilbert_index(x, y, 1), distance as u64);
+/// }
+/// assert_eq!(hilbert_index(3, 0, 2), 15);
+/// ```
+pub fn hilbert_index(x: u32, y: u32, order: u32) -> u64 {
+    assert!(order <= 32, "The order of the curve must be at most 32");
+    let n: u64 = 1 << order;
+    let (mut x, mut y) = (x as u64, y as u64);
+    assert!(x < n && y < n, "The cell must lie on the grid of the curve");
+    let mut distance = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u64;
+        let ry = (y & s > 0) as u64;
+        distance += s * s * ((3 * rx) ^ ry);
+        //rotates the quadrant so the curve inside it has the standard orientation
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    distance
+}
+
+/// Returns the position of the cell at column `x` and row `y` along the Morton, or Z order,
+/// curve: the bits of both values interleaved, with the bits of `x` in the even positions.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::sort::morton_index;
+///
+/// assert_eq!(morton_index(1, 0), 1);
+/// assert_eq!(morton_index(0, 1), 2);
+/// assert_eq!(morton_index(3, 3), 15);
+/// assert_eq!(morton_index(u32::MAX, 0), 0x5555_5555_5555_5555);
+/// ```
+pub fn morton_index(x: u32, y: u32) -> u64 {
+    spread(x) | (spread(y) << 1)
+}
+
+/// Returns the bits of the value moved to the even positions of a 64 bit value.
+fn spread(value: u32) -> u64 {
+    let mut v = value as u64;
+    v = (v | (v << 16)) & 0x0000_ffff_0000_ffff;
+    v = (v | (v << 8)) & 0x00ff_00ff_00ff_00ff;
+    v = (v | (v << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+    v
+}
+
+/// Sorts geometries along the Hilbert curve by the centers of their envelopes, placed on a grid
+/// spanning the centers of all of them. Empty geometries are moved to the end, and the sort is
+/// stable.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::sort::hilbert_sort;
+///
+/// let mut points: Vec<Geometry> = [(10, 0), (0, 0), (10, 10), (0, 10)]
+///     .into_iter()
+///     .map(|(x, y)| Geometry::Point { coordinates: coord!(x, y) })
+///     .collect();
+/// hilbert_sort(&mut points);
+/// let order: Vec<String> = points.iter().map(|p| p.as_text()).collect();
+/// assert_eq!(order, ["POINT (0 0)", "POINT (0 10)", "POINT (10 10)", "POINT (10 0)"]);
+/// ```
+pub fn hilbert_sort(geometries: &mut [Geometry]) {
+    sort_by_curve(geometries, |x, y| hilbert_index(x, y, SORT_ORDER));
+}
+
+/// Sorts geometries along the Morton curve by the centers of their envelopes, as
+/// [`hilbert_sort`] does. The Morton order is cheaper to compute but jumps further between
+/// consecutive cells.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::sort::morton_sort;
+///
+/// let mut points: Vec<Geometry> = [(10, 10), (0, 10), (10, 0), (0, 0)]
+///     .into_iter()
+///     .map(|(x, y)| Geometry::Point { coordinates: coord!(x, y) })
+///     .collect();
+/// morton_sort(&mut points);
+/// let order: Vec<String> = points.iter().map(|p| p.as_text()).collect();
+/// assert_eq!(order, ["POINT (0 0)", "POINT (10 0)", "POINT (0 10)", "POINT (10 10)"]);
+/// ```
+pub fn morton_sort(geometries: &mut [Geometry]) {
+    sort_by_curve(geometries, morton_index);
+}
+
+fn sort_by_curve(geometries: &mut [Geometry], index: impl Fn(u32, u32) -> u64) {
+    let centers: Vec<_> = geometries.iter().filter_map(|g| g.envelope().center()).collect();
+    let extent = Envelope::of(&centers);
+    let cells = ((1u64 << SORT_ORDER) - 1) as f64;
+    //degenerate extents put every geometry in the first cell along that axis
+    let scale = |value: f64, min: f64, max: f64| {
+        if max > min {
+            ((value - min) / (max - min) * cells).round() as u32
+        } else {
+            0
+        }
+    };
+    //empty geometries, without a center, go last
+    geometries.sort_by_cached_key(|g| match g.envelope().center() {
+        Some(c) => (false, index(scale(c.x(), extent.min_x(), extent.max_x()), scale(c.y(), extent.min_y(), extent.max_y()))),
+        None => (true, 0),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hilbert_index_is_a_continuous_bijection() {
+        for order in [1, 3, 5] {
+            let n = 1u32 << order;
+            let mut cells = vec![None; (n * n) as usize];
+            for x in 0..n {
+                for y in 0..n {
+                    let d = hilbert_index(x, y, order) as usize;
+                    assert!(cells[d].is_none());
+                    cells[d] = Some((x as i64, y as i64));
+                }
+            }
+            //consecutive cells along the curve are neighbours
+            for w in cells.windows(2) {
+                let ((x1, y1), (x2, y2)) = (w[0].unwrap(), w[1].unwrap());
+                assert_eq!((x1 - x2).abs() + (y1 - y2).abs(), 1);
+            }
+        }
+        assert_eq!(hilbert_index(u32::MAX, 0, 32), u64::MAX);
+    }
+}
@@ -0,0 +1,315 @@
+//! Lazy processing of features read one at a time, so files larger than memory can be filtered,
+//! reprojected, clipped and buffered while holding only a few features at once.
+//!
+//! Features are read lazily by [`CsvReader`], [`crate::io::geojson::SeqReader`] and, with the
+//! `flatgeobuf` feature, `io::flatgeobuf::FeatureReader`.
+
+use std::io::BufRead;
+
+use crate::coordinate::Coordinate;
+use crate::crs::{self, Crs};
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::feature::{Feature, FeatureCollection, Value};
+use crate::geometry::Geometry;
+use crate::io::wkt;
+use crate::operation::{buffer, overlay};
+
+/// The names of the columns holding the geometries as WKT, compared ignoring case.
+const WKT_COLUMNS: [&str; 4] = ["wkt", "geometry", "geom", "the_geom"];
+
+/// The names of the columns holding the x and the y of points, compared ignoring case.
+const X_COLUMNS: [&str; 4] = ["x", "lon", "lng", "longitude"];
+const Y_COLUMNS: [&str; 3] = ["y", "lat", "latitude"];
+
+/// The columns of a CSV file the geometries are read from.
+#[derive(Debug, Clone, Copy)]
+enum GeometryColumns {
+    Wkt(usize),
+    Point(usize, usize),
+}
+
+/// A reader of features from CSV, reading a record only when the next feature is asked for.
+///
+/// The first record names the columns. The geometries are read as WKT from a column named `wkt`,
+/// `geometry`, `geom` or `the_geom`, or as points from columns named `x` and `y`, `lon` or `lng`
+/// and `lat`, or `longitude` and `latitude`, ignoring case. The other columns become attributes:
+/// numbers, `true` and `false` are read as such, empty fields as nulls and anything else as
+/// strings. Fields can be quoted with double quotes, doubled inside them, to hold commas and line
+/// breaks.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::feature::Value;
+/// use geoms::stream::CsvReader;
+///
+/// let text = "name,lon,lat\n\"Well, north\",3,4\nsouth,3,-4\n";
+/// let mut reader = CsvReader::new(text.as_bytes()).unwrap();
+/// let well = reader.next().unwrap().unwrap();
+/// assert_eq!(well.geometry().coords_iter().next(), Some(&coord!(3, 4)));
+/// assert_eq!(well.property("name"), Some(&Value::String("Well, north".to_string())));
+/// assert_eq!(reader.count(), 1);
+/// ```
+pub struct CsvReader<R> {
+    reader: R,
+    columns: Vec<String>,
+    geometry: GeometryColumns,
+    //the number of the last line read, for the errors
+    line: usize,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    /// Creates a reader, reading the names of the columns. Returns an error if they cannot be read
+    /// or none of them holds geometries.
+    pub fn new(reader: R) -> Result<CsvReader<R>, Error> {
+        let mut csv = CsvReader { reader, columns: Vec::new(), geometry: GeometryColumns::Wkt(0), line: 0 };
+        csv.columns = csv.record().ok_or_else(|| Error::InvalidCsv("missing the names of the columns".to_string()))??;
+        let find = |names: &[&str]| csv.columns.iter().position(|c| names.iter().any(|n| c.trim().eq_ignore_ascii_case(n)));
+        csv.geometry = match (find(&WKT_COLUMNS), find(&X_COLUMNS), find(&Y_COLUMNS)) {
+            (Some(wkt), _, _) => GeometryColumns::Wkt(wkt),
+            (None, Some(x), Some(y)) => GeometryColumns::Point(x, y),
+            _ => return Err(Error::InvalidCsv("no column holds the geometries".to_string())),
+        };
+        Ok(csv)
+    }
+
+    /// Returns the names of the columns.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Reads the fields of the next record that is not a blank line, or None at the end.
+    fn record(&mut self) -> Option<Result<Vec<String>, Error>> {
+        let mut text = String::new();
+        loop {
+            match self.reader.read_line(&mut text) {
+                Err(error) => return Some(Err(error.into())),
+                Ok(0) if text.is_empty() => return None,
+                Ok(0) => return Some(Err(Error::InvalidCsv(format!("line {}: unterminated quoted field", self.line)))),
+                Ok(_) => self.line += 1,
+            }
+            //a line break inside quotes belongs to the field
+            if text.matches('"').count().is_multiple_of(2) {
+                let record = text.strip_suffix('\n').unwrap_or(&text);
+                let record = record.strip_suffix('\r').unwrap_or(record);
+                if record.trim().is_empty() {
+                    text.clear();
+                    continue;
+                }
+                return Some(Ok(fields(record)));
+            }
+        }
+    }
+
+    /// Returns the feature of a record.
+    fn feature(&self, fields: Vec<String>) -> Result<Feature, Error> {
+        if fields.len() != self.columns.len() {
+            return Err(Error::InvalidCsv(format!(
+                "line {}: expected {} fields, found {}",
+                self.line,
+                self.columns.len(),
+                fields.len()
+            )));
+        }
+        let geometry = match self.geometry {
+            GeometryColumns::Wkt(column) => wkt::read(&fields[column])?,
+            GeometryColumns::Point(x, y) => {
+                let ordinate = |field: &str| {
+                    field.trim().parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| {
+                        Error::InvalidCsv(format!("line {}: '{}' is not a coordinate", self.line, field))
+                    })
+                };
+                Geometry::Point { coordinates: Coordinate::new(ordinate(&fields[x])?, ordinate(&fields[y])?, 0.0) }
+            }
+        };
+        let skipped = match self.geometry {
+            GeometryColumns::Wkt(column) => [column, column],
+            GeometryColumns::Point(x, y) => [x, y],
+        };
+        let mut feature = Feature::new(geometry);
+        for (i, field) in fields.into_iter().enumerate().filter(|(i, _)| !skipped.contains(i)) {
+            feature.set_property(&self.columns[i], value(field));
+        }
+        Ok(feature)
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = Result<Feature, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let fields = self.record()?;
+        Some(fields.and_then(|fields| self.feature(fields)))
+    }
+}
+
+/// Splits a CSV record into its fields, removing the quotes.
+fn fields(record: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().expect("There is always a field").push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().expect("There is always a field").push(c),
+        }
+    }
+    fields
+}
+
+/// Returns the attribute value of a CSV field.
+fn value(field: String) -> Value {
+    match field.as_str() {
+        "" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        text => match text.parse::<f64>() {
+            Ok(number) if number.is_finite() => Value::Number(number),
+            _ => Value::String(field),
+        },
+    }
+}
+
+/// Adapters chaining operations on features read one at a time. Every adapter is lazy, handling a
+/// feature only when the next one is asked for, and passes errors through untouched, so the first
+/// error can be stopped at when collecting into a `Result`.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::envelope::Envelope;
+/// use geoms::stream::{CsvReader, FeatureStream};
+///
+/// let text = "id,wkt\n1,\"LINESTRING (0 0, 10 0)\"\n2,POINT (50 50)\n3,POINT (1 1)\n";
+/// let clipped = CsvReader::new(text.as_bytes())
+///     .unwrap()
+///     .filter_features(|f| f.property("id").and_then(|id| id.as_f64()) != Some(3.0))
+///     .clip(Envelope::new(0.0, -1.0, 5.0, 1.0))
+///     .collect_features()
+///     .unwrap();
+/// assert_eq!(clipped.len(), 1);
+/// assert_eq!(clipped.features()[0].geometry().length(), 5.0);
+/// ```
+pub trait FeatureStream: Iterator<Item = Result<Feature, Error>> + Sized {
+    /// Keeps the features for which the predicate is true.
+    fn filter_features<P>(self, mut predicate: P) -> impl Iterator<Item = Result<Feature, Error>>
+    where
+        P: FnMut(&Feature) -> bool,
+    {
+        self.filter(move |feature| feature.as_ref().map_or(true, &mut predicate))
+    }
+
+    /// Replaces the geometry of every feature by the one returned by the function, or stops the
+    /// feature with the error returned.
+    fn map_geometries<F>(self, mut f: F) -> impl Iterator<Item = Result<Feature, Error>>
+    where
+        F: FnMut(&Geometry) -> Result<Geometry, Error>,
+    {
+        self.map(move |feature| {
+            let mut feature = feature?;
+            let geometry = f(feature.geometry())?;
+            feature.set_geometry(geometry);
+            Ok(feature)
+        })
+    }
+
+    /// Transforms the geometries from one reference system to another. See
+    /// [`crs::transform_geometry`].
+    fn reproject(self, from: Crs, to: Crs) -> impl Iterator<Item = Result<Feature, Error>> {
+        self.map_geometries(move |geometry| crs::transform_geometry(geometry, from, to))
+    }
+
+    /// Clips the geometries to the envelope, dropping the features left without any part of them.
+    /// See [`overlay::clip`].
+    fn clip(self, envelope: Envelope) -> impl Iterator<Item = Result<Feature, Error>> {
+        self.filter_features(move |feature| feature.geometry().envelope().intersects(&envelope))
+            .map_geometries(move |geometry| overlay::clip(geometry, &envelope))
+            .filter_features(|feature| feature.geometry().coords_count() > 0)
+    }
+
+    /// Replaces the geometries by their buffers. See [`buffer::buffer`].
+    fn buffer(self, distance: f64) -> impl Iterator<Item = Result<Feature, Error>> {
+        self.map_geometries(move |geometry| buffer::buffer(geometry, distance))
+    }
+
+    /// Groups the features into collections of the given size, the last one possibly smaller,
+    /// so they can be processed or written in batches. An error is returned in place of the
+    /// collection it interrupts, which goes on after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size is 0.
+    fn chunks(self, size: usize) -> Chunks<Self> {
+        if size == 0 {
+            panic!("The size of the chunks must be positive");
+        }
+        Chunks { features: self, size, chunk: Vec::new() }
+    }
+
+    /// Collects the features into a collection, or returns the first error.
+    fn collect_features(self) -> Result<FeatureCollection, Error> {
+        self.collect::<Result<Vec<Feature>, Error>>().map(FeatureCollection::new)
+    }
+}
+
+impl<I: Iterator<Item = Result<Feature, Error>>> FeatureStream for I {}
+
+/// The collections of features made by [`FeatureStream::chunks`].
+pub struct Chunks<I> {
+    features: I,
+    size: usize,
+    chunk: Vec<Feature>,
+}
+
+impl<I: Iterator<Item = Result<Feature, Error>>> Iterator for Chunks<I> {
+    type Item = Result<FeatureCollection, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.chunk.len() < self.size {
+            match self.features.next() {
+                Some(Ok(feature)) => self.chunk.push(feature),
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            }
+        }
+        if self.chunk.is_empty() {
+            return None;
+        }
+        Some(Ok(FeatureCollection::new(std::mem::take(&mut self.chunk))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_of_a_csv_with_errors() {
+        let mut text = String::from("id,x,y,note\n");
+        for i in 0..25 {
+            text.push_str(&format!("{},{},0,\"line\nbreak, \"\"quoted\"\"\"\n", i, i));
+            if i == 12 {
+                text.push_str("bad,not a number,0,\n\n");
+            }
+        }
+        let reader = CsvReader::new(text.as_bytes()).unwrap();
+        assert_eq!(reader.columns(), ["id", "x", "y", "note"]);
+        let chunks: Vec<Result<FeatureCollection, Error>> = reader.buffer(1.0).chunks(10).collect();
+        let sizes: Vec<Result<usize, Error>> = chunks.iter().map(|c| c.as_ref().map(|c| c.len()).map_err(Clone::clone)).collect();
+        assert_eq!(sizes, vec![Ok(10), Err(Error::InvalidCsv("line 28: 'not a number' is not a coordinate".to_string())), Ok(10), Ok(5)]);
+        let first = &chunks[0].as_ref().unwrap().features()[3];
+        assert!((first.geometry().area() - std::f64::consts::PI).abs() < 0.05);
+        assert_eq!(first.property("note"), Some(&Value::String("line\nbreak, \"quoted\"".to_string())));
+        assert_eq!(first.property("x"), None);
+        assert!(CsvReader::new("a,b\n1,2\n".as_bytes()).is_err());
+        assert!(CsvReader::new("wkt\n\"POINT (0 0)\n".as_bytes()).unwrap().next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Snapshot tests of geometries: results are written as normalized WKT with a fixed precision and
+//! compared with fixtures stored next to the tests, giving the same text on every platform.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::algorithm::area::ring_signed_area;
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geometry::Geometry;
+use crate::io::diff::{geometry_diff, GeometryDiff};
+use crate::io::wkt;
+
+/// The environment variable that, when set, makes snapshots overwrite their fixtures instead of
+/// comparing with them, to accept a change of the results.
+pub const UPDATE_VARIABLE: &str = "COMPASS_UPDATE_SNAPSHOTS";
+
+/// Returns the geometry in a normal form, so that geometries covering the same vertices in the
+/// same way are written the same whatever the order they were built in.
+///
+/// Lines start at their smallest end, rings start at their smallest vertex with shells going
+/// counterclockwise and holes clockwise, and the parts of multi geometries, the holes of polygons
+/// and the members of collections are sorted. Vertices are ordered by x, then y, then z, and
+/// negative zeros become zeros. The patches of surfaces are sorted and rotated but keep their
+/// orientation, which tells their outside.
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to normalize.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::testing::normalize;
+///
+/// let clockwise = Geometry::Polygon { coordinates: vec![vec![coord!(2, 2), coord!(2, 0), coord!(0, 0), coord!(0, 2), coord!(2, 2)]] };
+/// assert_eq!(normalize(&clockwise).as_text(), "POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0))");
+/// ```
+pub fn normalize(geometry: &Geometry) -> Geometry {
+    match geometry {
+        Geometry::Point { coordinates } => Geometry::Point { coordinates: normalize_coordinate(coordinates) },
+        Geometry::LineString { coordinates } => Geometry::LineString { coordinates: normalize_line(coordinates) },
+        Geometry::LinearRing { coordinates } => Geometry::LinearRing { coordinates: normalize_ring(coordinates, Some(true)) },
+        Geometry::MultiPoint { coordinates } => {
+            let mut points: Vec<Coordinate> = coordinates.iter().map(normalize_coordinate).collect();
+            points.sort_by(compare_coordinates);
+            Geometry::MultiPoint { coordinates: points }
+        }
+        Geometry::Polygon { coordinates } => Geometry::Polygon { coordinates: normalize_polygon(coordinates) },
+        Geometry::MultiLineString { coordinates } => {
+            let mut lines: Vec<Vec<Coordinate>> = coordinates.iter().map(|line| normalize_line(line)).collect();
+            lines.sort_by(|a, b| compare_paths(a, b));
+            Geometry::MultiLineString { coordinates: lines }
+        }
+        Geometry::MultiPolygon { coordinates } => {
+            let mut polygons: Vec<Vec<Vec<Coordinate>>> = coordinates.iter().map(|p| normalize_polygon(p)).collect();
+            polygons.sort_by(|a, b| compare_paths(a.iter().flatten(), b.iter().flatten()));
+            Geometry::MultiPolygon { coordinates: polygons }
+        }
+        Geometry::Tin { coordinates } => Geometry::Tin { coordinates: normalize_patches(coordinates) },
+        Geometry::PolyhedralSurface { coordinates } => {
+            let mut patches: Vec<Vec<Vec<Coordinate>>> = coordinates.iter().map(|p| normalize_patches(p)).collect();
+            patches.sort_by(|a, b| compare_paths(a.iter().flatten(), b.iter().flatten()));
+            Geometry::PolyhedralSurface { coordinates: patches }
+        }
+        Geometry::GeometryCollection { geometries } => {
+            let mut members: Vec<Geometry> = geometries.iter().map(normalize).collect();
+            members.sort_by(|a, b| {
+                wkt::tag(a).cmp(wkt::tag(b)).then_with(|| compare_paths(a.coords_iter(), b.coords_iter()))
+            });
+            Geometry::GeometryCollection { geometries: members }
+        }
+    }
+}
+
+/// Returns the WKT of the normalized geometry with its ordinates rounded to the given number of
+/// decimals, the text stored by snapshots. See [`normalize`].
+///
+/// # Arguments
+///
+/// * `geometry` - The geometry to write.
+/// * `precision` - The number of decimals of the ordinates.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::testing::normalized_wkt;
+///
+/// let points = Geometry::MultiPoint { coordinates: vec![coord!(1.0 / 3.0, 1), coord!(-0.0, 0)] };
+/// assert_eq!(normalized_wkt(&points, 3), "MULTIPOINT ((0 0), (0.333 1))");
+/// ```
+pub fn normalized_wkt(geometry: &Geometry, precision: usize) -> String {
+    wkt::write_with_precision(&normalize(geometry), precision)
+}
+
+/// A directory of fixtures that geometries are compared with, one WKT file per snapshot.
+///
+/// A snapshot missing its fixture writes it and passes, so new tests record their first results,
+/// and every fixture is rewritten when the [`UPDATE_VARIABLE`] environment variable is set.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    directory: PathBuf,
+    precision: usize,
+    tolerance: f64,
+}
+
+impl Snapshot {
+    /// Returns snapshots stored in the given directory, written with 9 decimals and compared
+    /// with a tolerance of 1e-9, which absorbs a last decimal rounded the other way.
+    pub fn new(directory: impl Into<PathBuf>) -> Snapshot {
+        Snapshot { directory: directory.into(), precision: 9, tolerance: 1e-9 }
+    }
+
+    /// Sets the number of decimals of the ordinates written to the fixtures.
+    pub fn with_precision(mut self, precision: usize) -> Snapshot {
+        self.precision = precision;
+        self
+    }
+
+    /// Sets the largest difference allowed between an ordinate of a geometry and the same
+    /// ordinate in its fixture.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Snapshot {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns the path of the fixture of a snapshot.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(format!("{}.wkt", name))
+    }
+
+    /// Returns None if the normalized geometry matches the fixture of the snapshot, or the
+    /// differences from the fixture to the geometry if it does not, and an error if the fixture
+    /// cannot be read or written. Geometries match when they have the same type and structure,
+    /// and their ordinates are equal one by one within the tolerance.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the snapshot, which names its fixture.
+    /// * `geometry` - The geometry to compare.
+    pub fn compare(&self, name: &str, geometry: &Geometry) -> Result<Option<GeometryDiff>, Error> {
+        let path = self.path(name);
+        let text = normalized_wkt(geometry, self.precision);
+        if std::env::var_os(UPDATE_VARIABLE).is_some() || !path.exists() {
+            fs::create_dir_all(&self.directory)?;
+            fs::write(&path, format!("{}\n", text))?;
+            return Ok(None);
+        }
+        let expected = wkt::read(fs::read_to_string(&path)?.trim())?;
+        let actual = wkt::read(&text)?;
+        match geometry_matches(&expected, &actual, self.tolerance) {
+            true => Ok(None),
+            false => Ok(Some(geometry_diff(&expected, &actual))),
+        }
+    }
+
+    /// Checks the geometry against the fixture of the snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the normalized WKT of the geometry and the differences from the fixture if they
+    /// do not match, or if the fixture cannot be read or written.
+    pub fn assert_matches(&self, name: &str, geometry: &Geometry) {
+        let path = self.path(name);
+        match self.compare(name, geometry) {
+            Ok(None) => {}
+            Ok(Some(diff)) => panic!(
+                "The snapshot {} does not match {}\n{}\n{}",
+                name,
+                path.display(),
+                normalized_wkt(geometry, self.precision),
+                diff
+            ),
+            Err(error) => panic!("The snapshot {} cannot use {}: {}", name, path.display(), error),
+        }
+    }
+}
+
+/// Checks a geometry against the fixture `tests/snapshots/<name>.wkt` of the crate under test,
+/// with the default precision and tolerance of [`Snapshot`].
+///
+/// # Panics
+///
+/// Panics if the geometry does not match the fixture. See [`Snapshot::assert_matches`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::geometry::Geometry;
+/// use geoms::testing::snapshot;
+///
+/// let point = Geometry::Point { coordinates: coord!(1, 1) };
+/// snapshot("point_buffer", &point.buffer_circle(1.0, 8).unwrap());
+/// ```
+pub fn snapshot(name: &str, geometry: &Geometry) {
+    //cargo sets the manifest directory of the crate whose tests run
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+    Snapshot::new(root.join("tests").join("snapshots")).assert_matches(name, geometry);
+}
+
+fn normalize_coordinate(c: &Coordinate) -> Coordinate {
+    //adding zero turns -0 into 0
+    Coordinate::new(c.x() + 0.0, c.y() + 0.0, c.z() + 0.0)
+}
+
+fn compare_coordinates(a: &Coordinate, b: &Coordinate) -> Ordering {
+    a.x().total_cmp(&b.x()).then(a.y().total_cmp(&b.y())).then(a.z().total_cmp(&b.z()))
+}
+
+fn compare_paths<'a>(a: impl IntoIterator<Item = &'a Coordinate>, b: impl IntoIterator<Item = &'a Coordinate>) -> Ordering {
+    let (mut a, mut b) = (a.into_iter(), b.into_iter());
+    loop {
+        match (a.next(), b.next()) {
+            (Some(p), Some(q)) => match compare_coordinates(p, q) {
+                Ordering::Equal => continue,
+                order => return order,
+            },
+            //a path comes before the longer paths it starts
+            (p, q) => return p.is_some().cmp(&q.is_some()),
+        }
+    }
+}
+
+fn normalize_line(line: &[Coordinate]) -> Vec<Coordinate> {
+    let mut line: Vec<Coordinate> = line.iter().map(normalize_coordinate).collect();
+    if line.len() > 1 && compare_coordinates(&line[line.len() - 1], &line[0]).is_lt() {
+        line.reverse();
+    }
+    line
+}
+
+/// Returns the closed ring starting at its smallest vertex, going counterclockwise if `ccw` is
+/// true, clockwise if it is false, or in its own direction if it is None.
+fn normalize_ring(ring: &[Coordinate], ccw: Option<bool>) -> Vec<Coordinate> {
+    let mut ring: Vec<Coordinate> = ring.iter().map(normalize_coordinate).collect();
+    if ring.len() < 3 || ring[0] != ring[ring.len() - 1] {
+        return ring;
+    }
+    if let Some(ccw) = ccw {
+        let area = ring_signed_area(&ring);
+        if area != 0.0 && (area > 0.0) != ccw {
+            ring.reverse();
+        }
+    }
+    ring.pop();
+    let start = (0..ring.len()).min_by(|&i, &j| compare_coordinates(&ring[i], &ring[j])).unwrap_or(0);
+    ring.rotate_left(start);
+    ring.push(ring[0].clone());
+    ring
+}
+
+fn normalize_polygon(rings: &[Vec<Coordinate>]) -> Vec<Vec<Coordinate>> {
+    let mut rings: Vec<Vec<Coordinate>> =
+        rings.iter().enumerate().map(|(i, ring)| normalize_ring(ring, Some(i == 0))).collect();
+    if rings.len() > 2 {
+        rings[1..].sort_by(|a, b| compare_paths(a, b));
+    }
+    rings
+}
+
+fn normalize_patches(patches: &[Vec<Coordinate>]) -> Vec<Vec<Coordinate>> {
+    let mut patches: Vec<Vec<Coordinate>> = patches.iter().map(|p| normalize_ring(p, None)).collect();
+    patches.sort_by(|a, b| compare_paths(a, b));
+    patches
+}
+
+/// Returns true if the geometries have the same type and structure and their ordinates differ by
+/// at most the tolerance.
+fn geometry_matches(a: &Geometry, b: &Geometry, tolerance: f64) -> bool {
+    let close = |p: &Coordinate, q: &Coordinate| {
+        //z is NaN in both for 2D coordinates
+        let near = |u: f64, v: f64| (u - v).abs() <= tolerance || (u.is_nan() && v.is_nan());
+        near(p.x(), q.x()) && near(p.y(), q.y()) && near(p.z(), q.z())
+    };
+    let path = |a: &[Coordinate], b: &[Coordinate]| a.len() == b.len() && a.iter().zip(b).all(|(p, q)| close(p, q));
+    let paths = |a: &[Vec<Coordinate>], b: &[Vec<Coordinate>]| a.len() == b.len() && a.iter().zip(b).all(|(p, q)| path(p, q));
+    match (a, b) {
+        (Geometry::Point { coordinates: a }, Geometry::Point { coordinates: b }) => close(a, b),
+        (Geometry::LineString { coordinates: a }, Geometry::LineString { coordinates: b })
+        | (Geometry::LinearRing { coordinates: a }, Geometry::LinearRing { coordinates: b })
+        | (Geometry::MultiPoint { coordinates: a }, Geometry::MultiPoint { coordinates: b }) => path(a, b),
+        (Geometry::Polygon { coordinates: a }, Geometry::Polygon { coordinates: b })
+        | (Geometry::MultiLineString { coordinates: a }, Geometry::MultiLineString { coordinates: b })
+        | (Geometry::Tin { coordinates: a }, Geometry::Tin { coordinates: b }) => paths(a, b),
+        (Geometry::MultiPolygon { coordinates: a }, Geometry::MultiPolygon { coordinates: b })
+        | (Geometry::PolyhedralSurface { coordinates: a }, Geometry::PolyhedralSurface { coordinates: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| paths(a, b))
+        }
+        (Geometry::GeometryCollection { geometries: a }, Geometry::GeometryCollection { geometries: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| geometry_matches(a, b, tolerance))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord;
+
+    #[test]
+    fn test_snapshots_of_geometries_built_in_any_order() {
+        let polygon = |shell: Vec<Coordinate>, hole: Vec<Coordinate>| Geometry::Polygon { coordinates: vec![shell, hole] };
+        let a = Geometry::GeometryCollection { geometries: vec![
+            polygon(
+                vec![coord!(0, 0), coord!(4, 0), coord!(4, 4), coord!(0, 4), coord!(0, 0)],
+                vec![coord!(1, 1), coord!(1, 2), coord!(2, 2), coord!(2, 1), coord!(1, 1)],
+            ),
+            Geometry::LineString { coordinates: vec![coord!(5, 5), coord!(6, 6)] },
+        ] };
+        //the same geometries, clockwise, from other vertices and in the other order
+        let b = Geometry::GeometryCollection { geometries: vec![
+            Geometry::LineString { coordinates: vec![coord!(6, 6), coord!(5, 5)] },
+            polygon(
+                vec![coord!(4, 4), coord!(4, 0), coord!(0, 0), coord!(0, 4), coord!(4, 4)],
+                vec![coord!(2, 2), coord!(1, 2), coord!(1, 1), coord!(2, 1), coord!(2, 2)],
+            ),
+        ] };
+        assert_eq!(normalized_wkt(&a, 9), normalized_wkt(&b, 9));
+        assert_eq!(
+            normalized_wkt(&a, 9),
+            "GEOMETRYCOLLECTION (LINESTRING (5 5, 6 6), POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 1 2, 2 2, 2 1, 1 1)))"
+        );
+
+        let directory = std::env::temp_dir().join(format!("compass-snapshots-{}", std::process::id()));
+        let snapshots = Snapshot::new(&directory).with_precision(3).with_tolerance(0.01);
+        //the first comparison records the fixture
+        assert_eq!(snapshots.compare("collection", &a).unwrap(), None);
+        assert_eq!(fs::read_to_string(snapshots.path("collection")).unwrap(), format!("{}\n", normalized_wkt(&a, 3)));
+        snapshots.assert_matches("collection", &b);
+        let nudged = Geometry::GeometryCollection { geometries: vec![
+            a.geometry_n(0).unwrap(),
+            Geometry::LineString { coordinates: vec![coord!(5.004, 5), coord!(6, 6)] },
+        ] };
+        assert_eq!(snapshots.compare("collection", &nudged).unwrap(), None);
+        let moved = Geometry::GeometryCollection { geometries: vec![
+            a.geometry_n(0).unwrap(),
+            Geometry::LineString { coordinates: vec![coord!(5.5, 5), coord!(6, 6)] },
+        ] };
+        let diff = snapshots.compare("collection", &moved).unwrap().unwrap();
+        assert_eq!(diff.first_difference, Some(0));
+        assert_eq!(diff.max_vertex_deviation, 0.5);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
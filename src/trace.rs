@@ -0,0 +1,93 @@
+//! Spans and events of the `tracing` feature around the major operations, telling the vertices
+//! processed, the intersections found and the robustness fallbacks taken. Without the feature the
+//! macros expand to nothing and their fields are not evaluated.
+
+/// Enters a debug span named after the operation until the end of the enclosing block, with the
+/// given fields.
+macro_rules! span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+/// Emits a debug event with the given fields and message within the current span.
+macro_rules! event {
+    ($message:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($field = $value,)* $message);
+    };
+}
+
+pub(crate) use event;
+pub(crate) use span;
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::coord;
+    use crate::coordinate::Coordinate;
+    use crate::geometry::Geometry;
+    use crate::operation::buffer::buffer;
+
+    /// Records the names of the spans and the messages of the events with their fields.
+    #[derive(Default)]
+    struct Recorder {
+        next: AtomicU64,
+        lines: Mutex<Vec<String>>,
+    }
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes) -> Id {
+            let mut fields = Fields(span.metadata().name().to_string());
+            span.record(&mut fields);
+            self.lines.lock().unwrap().push(fields.0);
+            Id::from_u64(self.next.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _: &Id, _: &Record) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.lines.lock().unwrap().push(fields.0.trim().to_string());
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_spans_of_a_buffer() {
+        let recorder = std::sync::Arc::new(Recorder::default());
+        let line = Geometry::LineString { coordinates: vec![coord!(0, 0), coord!(10, 0), coord!(10, 10)] };
+        let zone = tracing::subscriber::with_default(recorder.clone(), || buffer(&line, 1.0).unwrap());
+        assert_eq!(zone.num_components(), 1);
+        let lines = recorder.lines.lock().unwrap();
+        assert_eq!(lines[0], "buffer vertices=3 distance=1.0");
+        assert_eq!(lines[1], "unary_union parts=2");
+        assert!(lines.iter().any(|line| line.starts_with("overlay op=Union vertices=")));
+        assert!(lines.iter().any(|line| line.starts_with("message=noded segments=")));
+    }
+}
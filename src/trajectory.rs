@@ -0,0 +1,292 @@
+//! Movement data: paths whose vertices carry the time at which they were visited.
+
+use std::ops::Range;
+
+use crate::coordinate::Coordinate;
+use crate::error::Error;
+use crate::geodesic::{self, Mode};
+use crate::geometry::Geometry;
+
+/// A path with a timestamp for every vertex, such as a GPS track. The times are kept in a
+/// parallel array, in any unit as long as it is the same everywhere, typically seconds.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trajectory {
+    coordinates: Vec<Coordinate>,
+    times: Vec<f64>,
+    mode: Mode,
+}
+
+/// A place where a trajectory stayed for a while, such as a stop of a delivery truck.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StayPoint {
+    /// The mean of the positions recorded during the stay.
+    pub location: Coordinate,
+    /// The time of the first position of the stay.
+    pub arrival: f64,
+    /// The time of the last position of the stay.
+    pub departure: f64,
+}
+
+impl Trajectory {
+    /// Creates a trajectory from its positions and the times they were recorded, which must be
+    /// as many, finite and not decreasing. Distances are planar until another mode is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::trajectory::Trajectory;
+    ///
+    /// let track = Trajectory::new(vec![coord!(0, 0), coord!(30, 40)], vec![0.0, 10.0]).unwrap();
+    /// assert_eq!(track.duration(), 10.0);
+    /// assert_eq!(track.speed_profile(), vec![5.0]);
+    ///
+    /// assert!(Trajectory::new(vec![coord!(0, 0), coord!(30, 40)], vec![10.0, 0.0]).is_err());
+    /// ```
+    pub fn new(coordinates: Vec<Coordinate>, times: Vec<f64>) -> Result<Trajectory, Error> {
+        if coordinates.len() != times.len() {
+            return Err(Error::InvalidTrajectory(format!(
+                "{} positions but {} times",
+                coordinates.len(),
+                times.len()
+            )));
+        }
+        if times.iter().any(|t| !t.is_finite()) {
+            return Err(Error::InvalidTrajectory("the times must be finite".to_string()));
+        }
+        if times.windows(2).any(|w| w[1] < w[0]) {
+            return Err(Error::InvalidTrajectory("the times must not decrease".to_string()));
+        }
+        Ok(Trajectory { coordinates, times, mode: Mode::Planar })
+    }
+
+    /// Creates a trajectory from a line string and the times of its vertices.
+    pub fn from_line(line: &Geometry, times: Vec<f64>) -> Result<Trajectory, Error> {
+        match line {
+            Geometry::LineString { coordinates } => Trajectory::new(coordinates.clone(), times),
+            _ => Err(Error::InvalidTrajectory("the geometry is not a line string".to_string())),
+        }
+    }
+
+    /// Returns the trajectory measuring distances with the given mode. In geodesic mode the
+    /// positions are longitudes and latitudes, and distances are in meters.
+    pub fn with_mode(self, mode: Mode) -> Trajectory {
+        Trajectory { mode, ..self }
+    }
+
+    /// Returns the positions of the trajectory.
+    pub fn coordinates(&self) -> &[Coordinate] {
+        &self.coordinates
+    }
+
+    /// Returns the times of the positions of the trajectory.
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Returns the path of the trajectory as a line string.
+    pub fn line(&self) -> Geometry {
+        Geometry::LineString { coordinates: self.coordinates.clone() }
+    }
+
+    /// Returns the time elapsed between the first and the last positions.
+    pub fn duration(&self) -> f64 {
+        match (self.times.first(), self.times.last()) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the distance travelled along the trajectory.
+    pub fn length(&self) -> f64 {
+        self.coordinates.windows(2).map(|w| self.distance(&w[0], &w[1])).sum()
+    }
+
+    fn distance(&self, a: &Coordinate, b: &Coordinate) -> f64 {
+        match self.mode {
+            Mode::Planar => (b.x() - a.x()).hypot(b.y() - a.y()),
+            Mode::Geodesic => geodesic::distance(a, b),
+        }
+    }
+
+    /// Returns the mean speed over every segment of the trajectory. Segments travelled in no time
+    /// have an infinite speed, or a speed of zero if they have no length either.
+    pub fn speed_profile(&self) -> Vec<f64> {
+        self.coordinates
+            .windows(2)
+            .zip(self.times.windows(2))
+            .map(|(c, t)| {
+                let distance = self.distance(&c[0], &c[1]);
+                if distance == 0.0 {
+                    0.0
+                } else {
+                    distance / (t[1] - t[0])
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the position at a time, interpolated linearly between the recorded positions, or
+    /// None if the time is outside of the trajectory. When several positions share the time,
+    /// the first one is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::trajectory::Trajectory;
+    ///
+    /// let track = Trajectory::new(vec![coord!(0, 0, 100), coord!(10, 0, 200)], vec![0.0, 10.0]).unwrap();
+    /// assert_eq!(track.position_at(2.5), Some(coord!(2.5, 0, 125)));
+    /// assert_eq!(track.position_at(11.0), None);
+    /// ```
+    pub fn position_at(&self, time: f64) -> Option<Coordinate> {
+        let (&first, &last) = (self.times.first()?, self.times.last()?);
+        if time < first || time > last {
+            return None;
+        }
+        //the index of the first position recorded at or after the time
+        let i = self.times.partition_point(|&t| t < time);
+        if self.times[i] == time || i == 0 {
+            return Some(self.coordinates[i].clone());
+        }
+        let (a, b) = (&self.coordinates[i - 1], &self.coordinates[i]);
+        let f = (time - self.times[i - 1]) / (self.times[i] - self.times[i - 1]);
+        Some(Coordinate::new(
+            a.x() + f * (b.x() - a.x()),
+            a.y() + f * (b.y() - a.y()),
+            a.z() + f * (b.z() - a.z()),
+        ))
+    }
+
+    /// Returns the trajectory sampled at regular times from its start, the last sample being at
+    /// or before its end. The samples are interpolated linearly.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two samples. It must be positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::trajectory::Trajectory;
+    ///
+    /// let track = Trajectory::new(vec![coord!(0, 0), coord!(10, 0), coord!(10, 5)], vec![0.0, 10.0, 20.0]).unwrap();
+    /// let resampled = track.resample_by_time(7.0);
+    /// assert_eq!(resampled.times(), &[0.0, 7.0, 14.0]);
+    /// assert_eq!(resampled.coordinates(), &[coord!(0, 0), coord!(7, 0), coord!(10, 2)]);
+    /// ```
+    pub fn resample_by_time(&self, interval: f64) -> Trajectory {
+        if !(interval.is_finite() && interval > 0.0) {
+            panic!("The resampling interval must be positive");
+        }
+        let mut coordinates = Vec::new();
+        let mut times = Vec::new();
+        if let Some(&start) = self.times.first() {
+            let samples = (self.duration() / interval).floor() as usize;
+            for k in 0..=samples {
+                let time = start + k as f64 * interval;
+                if let Some(position) = self.position_at(time) {
+                    coordinates.push(position);
+                    times.push(time);
+                }
+            }
+        }
+        Trajectory { coordinates, times, mode: self.mode }
+    }
+
+    /// Returns the part of the trajectory within a time range, starting and ending with
+    /// positions interpolated at its bounds, or None if the range does not overlap the trajectory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::trajectory::Trajectory;
+    ///
+    /// let track = Trajectory::new(vec![coord!(0, 0), coord!(10, 0), coord!(10, 10)], vec![0.0, 10.0, 20.0]).unwrap();
+    /// let part = track.slice_by_time(5.0..15.0).unwrap();
+    /// assert_eq!(part.coordinates(), &[coord!(5, 0), coord!(10, 0), coord!(10, 5)]);
+    /// assert_eq!(part.times(), &[5.0, 10.0, 15.0]);
+    /// assert!(track.slice_by_time(30.0..40.0).is_none());
+    /// ```
+    pub fn slice_by_time(&self, range: Range<f64>) -> Option<Trajectory> {
+        let (&first, &last) = (self.times.first()?, self.times.last()?);
+        let (start, end) = (range.start.max(first), range.end.min(last));
+        if start > end {
+            return None;
+        }
+        let mut coordinates = vec![self.position_at(start)?];
+        let mut times = vec![start];
+        for (c, &t) in self.coordinates.iter().zip(&self.times) {
+            if t > start && t < end {
+                coordinates.push(c.clone());
+                times.push(t);
+            }
+        }
+        if end > start {
+            coordinates.push(self.position_at(end)?);
+            times.push(end);
+        }
+        Some(Trajectory { coordinates, times, mode: self.mode })
+    }
+
+    /// Returns the places where the trajectory stayed within a distance for at least a duration.
+    ///
+    /// Starting from every position not yet part of a stay, the following positions are taken
+    /// while they remain within the distance of it, and they make a stay if they span the
+    /// duration. The search then continues after the stay.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_distance` - The largest distance from the first position of a stay to the others.
+    /// * `min_duration` - The shortest time a stay can last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoms::coord;
+    /// use geoms::coordinate::Coordinate;
+    /// use geoms::trajectory::Trajectory;
+    ///
+    /// let track = Trajectory::new(
+    ///     vec![coord!(0, 0), coord!(100, 0), coord!(101, 1), coord!(99, 0), coord!(100, 0), coord!(200, 0)],
+    ///     vec![0.0, 60.0, 120.0, 300.0, 600.0, 660.0],
+    /// ).unwrap();
+    /// let stays = track.stay_points(5.0, 300.0);
+    /// assert_eq!(stays.len(), 1);
+    /// assert_eq!(stays[0].location, coord!(100, 0.25));
+    /// assert_eq!((stays[0].arrival, stays[0].departure), (60.0, 600.0));
+    /// ```
+    pub fn stay_points(&self, max_distance: f64, min_duration: f64) -> Vec<StayPoint> {
+        let mut stays = Vec::new();
+        let mut i = 0;
+        while i < self.coordinates.len() {
+            let mut j = i + 1;
+            while j < self.coordinates.len() && self.distance(&self.coordinates[i], &self.coordinates[j]) <= max_distance {
+                j += 1;
+            }
+            //the positions from i to j - 1 are within the distance of the first one
+            if self.times[j - 1] - self.times[i] >= min_duration && j - 1 > i {
+                let count = (j - i) as f64;
+                let (x, y, z) = self.coordinates[i..j]
+                    .iter()
+                    .fold((0.0, 0.0, 0.0), |(x, y, z), c| (x + c.x(), y + c.y(), z + c.z()));
+                stays.push(StayPoint {
+                    location: Coordinate::new(x / count, y / count, z / count),
+                    arrival: self.times[i],
+                    departure: self.times[j - 1],
+                });
+                i = j;
+            } else {
+                i += 1;
+            }
+        }
+        stays
+    }
+}
@@ -0,0 +1,494 @@
+//! Delaunay triangulation of point sets, and triangulation of polygons by ear clipping.
+
+use std::collections::HashMap;
+
+use crate::algorithm::area::ring_signed_area;
+use crate::algorithm::dd::in_circle;
+use crate::algorithm::line_intersection::{orientation, point_on_segment, Orientation};
+use crate::algorithm::point_in_polygon::{locate_in_polygon, Location};
+use crate::coordinate::Coordinate;
+use crate::envelope::Envelope;
+use crate::error::Error;
+use crate::progress::{step, ProgressSink};
+use crate::trace;
+
+/// Returns the Delaunay triangulation of a set of points as triples of point indices, with every
+/// triangle in counter clockwise order. Only the planar projection of the points is used; repeated
+/// points are triangulated once, through their first occurrence, and collinear sets produce no triangles.
+///
+/// The triangulation follows the incremental algorithm of Bowyer and Watson, sweeping the points
+/// along the x axis as proposed by P. Bourke. The outside of the convex hull is covered by ghost
+/// triangles sharing a vertex at infinity, so no bounding super triangle is needed.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::triangulate::delaunay;
+///
+/// let points = vec![coord!(0, 0), coord!(2, 0), coord!(2, 2), coord!(0, 2), coord!(1, 1)];
+/// let triangles = delaunay(&points);
+/// assert_eq!(triangles.len(), 4);
+/// assert!(triangles.iter().all(|t| t.contains(&4)));
+/// ```
+pub fn delaunay(points: &[Coordinate]) -> Vec<[usize; 3]> {
+    //the unit sink never cancels
+    delaunay_with_progress(points, &()).unwrap_or_default()
+}
+
+/// The number of points inserted into a triangulation between two reports of its progress.
+const REPORT_INTERVAL: usize = 1024;
+
+/// Returns the Delaunay triangulation of a set of points as [`delaunay`] does, reporting the
+/// number of points inserted to the sink every thousand or so, or an error if the sink cancels
+/// it.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::triangulate::delaunay_with_progress;
+///
+/// let points: Vec<Coordinate> = (0..5000).map(|i| coord!(i % 100, i / 100)).collect();
+/// let triangles = delaunay_with_progress(&points, &|done, total| println!("{} of {} points", done, total)).unwrap();
+/// assert_eq!(triangles.len(), 2 * 99 * 49);
+/// ```
+pub fn delaunay_with_progress(points: &[Coordinate], progress: &dyn ProgressSink) -> Result<Vec<[usize; 3]>, Error> {
+    trace::span!("delaunay", points = points.len());
+    //work relative to the center of the points to keep the predicates accurate
+    let center = match Envelope::of(points).center() {
+        Some(center) => center,
+        None => return Ok(vec![]),
+    };
+    let vertices: Vec<(f64, f64)> = points.iter().map(|p| (p.x() - center.x(), p.y() - center.y())).collect();
+
+    //sorted unique points
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| vertices[a].0.total_cmp(&vertices[b].0).then(vertices[a].1.total_cmp(&vertices[b].1)));
+    order.dedup_by(|a, b| vertices[*a] == vertices[*b]);
+
+    //the first triangle is made of the first two points and the first point not collinear with them
+    let first = match (2..order.len()).find(|&k| orient(&vertices, order[0], order[1], order[k]) != 0.0) {
+        Some(first) => first,
+        None => return Ok(vec![]),
+    };
+    let (a, b, c) = if orient(&vertices, order[0], order[1], order[first]) > 0.0 {
+        (order[0], order[1], order[first])
+    } else {
+        (order[0], order[first], order[1])
+    };
+    let mut active: Vec<Triangle> = vec![
+        Triangle::new([a, b, c], &vertices),
+        Triangle::ghost(b, a),
+        Triangle::ghost(c, b),
+        Triangle::ghost(a, c),
+    ];
+    let mut completed: Vec<Triangle> = Vec::new();
+    let mut cavity: Vec<Triangle> = Vec::new();
+    let mut owners: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut pending: Vec<usize> = Vec::new();
+
+    //the skipped collinear points come first to keep the insertions sorted by x
+    let insertions = order[2..first].iter().chain(&order[first + 1..]);
+    let total = order.len();
+    for (k, &i) in insertions.enumerate() {
+        if k % REPORT_INTERVAL == 0 {
+            step(progress, k + 3, total)?;
+        }
+        let (px, py) = vertices[i];
+        let mut kept = Vec::with_capacity(active.len() + 2);
+        for triangle in active.drain(..) {
+            if triangle.is_completed_before(px) {
+                completed.push(triangle);
+            } else if triangle.conflicts(i, px, py, &vertices) {
+                cavity.push(triangle);
+            } else {
+                kept.push(triangle);
+            }
+        }
+        active = kept;
+
+        //rounding errors on nearly cocircular points can leave triangles in the cavity that are
+        //not visible from the point, they are given back until the cavity is star shaped
+        owners.clear();
+        for (k, triangle) in cavity.iter().enumerate() {
+            owners.extend(triangle.edges().map(|edge| (edge, k)));
+        }
+        let mut inside = vec![true; cavity.len()];
+        pending.extend(0..cavity.len());
+        while let Some(k) = pending.pop() {
+            let hidden = inside[k]
+                && cavity[k].edges().any(|(u, v)| {
+                    u != GHOST && v != GHOST && !owners.contains_key(&(v, u)) && orient(&vertices, u, v, i) <= 0.0
+                });
+            if hidden {
+                inside[k] = false;
+                for (u, v) in cavity[k].edges() {
+                    owners.remove(&(u, v));
+                    //the neighbours across the edges now lie on the boundary
+                    if let Some(&neighbour) = owners.get(&(v, u)) {
+                        pending.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        for (k, triangle) in cavity.drain(..).enumerate() {
+            if !inside[k] {
+                active.push(triangle);
+                continue;
+            }
+            //the boundary of the cavity is made of the edges whose twin is not in the cavity
+            for (u, v) in triangle.edges() {
+                if owners.contains_key(&(v, u)) {
+                    continue;
+                }
+                active.push(if u == GHOST {
+                    Triangle::ghost(v, i)
+                } else if v == GHOST {
+                    Triangle::ghost(i, u)
+                } else {
+                    Triangle::new([u, v, i], &vertices)
+                });
+            }
+        }
+    }
+
+    step(progress, total, total)?;
+    Ok(completed
+        .into_iter()
+        .chain(active)
+        .filter(|t| t.v[2] != GHOST)
+        .map(|t| t.v)
+        .collect())
+}
+
+/// Returns a triangulation of a polygon by ear clipping, as triangles in counter clockwise order
+/// covering the polygon without overlapping. Only the planar projection of the coordinates is
+/// used, and the triangles have the vertices of the polygon as corners, so no point is added.
+///
+/// The holes are first joined to the shell by bridges to the closest visible vertex, from the
+/// hole reaching furthest along the x axis to the last, which turns the polygon into a single
+/// ring touching itself along the bridges. Ears, convex corners whose triangle contains no other
+/// vertex, are then cut from that ring until a single triangle remains. Collinear vertices are
+/// dropped along the way, and rings without area give no triangles.
+///
+/// # Arguments
+///
+/// * `rings` - The shell and the holes of the polygon, expected to be valid.
+///
+/// # Examples
+///
+/// ```
+/// use geoms::coord;
+/// use geoms::coordinate::Coordinate;
+/// use geoms::triangulate::triangulate_polygon;
+///
+/// let frame = vec![
+///     vec![coord!(0, 0), coord!(3, 0), coord!(3, 3), coord!(0, 3), coord!(0, 0)],
+///     vec![coord!(1, 1), coord!(1, 2), coord!(2, 2), coord!(2, 1), coord!(1, 1)],
+/// ];
+/// let triangles = triangulate_polygon(&frame);
+/// assert_eq!(triangles.len(), 8);
+/// ```
+pub fn triangulate_polygon(rings: &[Vec<Coordinate>]) -> Vec<[Coordinate; 3]> {
+    let mut prepared = rings.iter().map(|ring| ring_vertices(ring));
+    let mut outer = match prepared.next() {
+        Some(Some(shell)) => shell,
+        _ => return vec![],
+    };
+    if ring_signed_area(&outer) < 0.0 {
+        outer.reverse();
+    }
+    let mut holes: Vec<Vec<Coordinate>> = prepared
+        .flatten()
+        .map(|mut hole| {
+            if ring_signed_area(&hole) > 0.0 {
+                hole.reverse();
+            }
+            hole
+        })
+        .collect();
+    let max_x = |ring: &[Coordinate]| ring.iter().map(|c| c.x()).fold(f64::NEG_INFINITY, f64::max);
+    holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+    let closed: Vec<Vec<Coordinate>> = std::iter::once(&outer)
+        .chain(&holes)
+        .map(|ring| ring.iter().chain(ring.first()).cloned().collect())
+        .collect();
+    for (k, hole) in holes.iter().enumerate() {
+        bridge_hole(&mut outer, hole, &holes[k + 1..], &closed);
+    }
+    clip_ears(outer)
+}
+
+/// Returns the vertices of a ring without the closing and repeated ones, or None if fewer than
+/// three are left.
+fn ring_vertices(ring: &[Coordinate]) -> Option<Vec<Coordinate>> {
+    let mut vertices = ring.to_vec();
+    vertices.dedup_by(|a, b| a.equals_2d(b));
+    if vertices.len() > 1 && vertices[0].equals_2d(&vertices[vertices.len() - 1]) {
+        vertices.pop();
+    }
+    (vertices.len() >= 3 && ring_signed_area(&vertices) != 0.0).then_some(vertices)
+}
+
+/// Joins a clockwise hole to the outer ring through the rightmost vertex of the hole and the
+/// closest vertex of the outer ring that can be reached without leaving the polygon or crossing
+/// any ring, including the holes not joined yet.
+fn bridge_hole(outer: &mut Vec<Coordinate>, hole: &[Coordinate], pending: &[Vec<Coordinate>], rings: &[Vec<Coordinate>]) {
+    let start = (0..hole.len()).max_by(|&a, &b| hole[a].x().total_cmp(&hole[b].x())).unwrap_or(0);
+    let h = &hole[start];
+    let mut candidates: Vec<usize> = (0..outer.len()).collect();
+    candidates.sort_by(|&a, &b| h.distance_2d(&outer[a]).total_cmp(&h.distance_2d(&outer[b])));
+    let n = outer.len();
+    let edges = (0..n)
+        .map(|i| (&outer[i], &outer[(i + 1) % n]))
+        .chain(pending.iter().flat_map(|ring| (0..ring.len()).map(move |i| (&ring[i], &ring[(i + 1) % ring.len()]))))
+        .collect::<Vec<_>>();
+    let visible = |v: &Coordinate| {
+        let middle = Coordinate::new((h.x() + v.x()) / 2.0, (h.y() + v.y()) / 2.0, 0.0);
+        !v.equals_2d(h)
+            && locate_in_polygon(&middle, rings) == Location::Interior
+            && edges.iter().all(|(a, b)| !crosses(h, v, a, b))
+            && edges.iter().all(|(a, _)| a.equals_2d(h) || a.equals_2d(v) || !point_on_segment(a, h, v))
+    };
+    let Some(&target) = candidates.iter().find(|&&i| visible(&outer[i])) else {
+        return;
+    };
+    //go around the hole from its rightmost vertex back to it, then back to the outer ring
+    let detour: Vec<Coordinate> = (0..=hole.len())
+        .map(|k| hole[(start + k) % hole.len()].clone())
+        .chain([outer[target].clone()])
+        .collect();
+    outer.splice(target + 1..target + 1, detour);
+}
+
+/// Returns true if the segments `p` -> `q` and `a` -> `b` cross at a point interior to both.
+fn crosses(p: &Coordinate, q: &Coordinate, a: &Coordinate, b: &Coordinate) -> bool {
+    let (o1, o2) = (orientation(p, q, a), orientation(p, q, b));
+    let (o3, o4) = (orientation(a, b, p), orientation(a, b, q));
+    o1 != Orientation::Collinear && o2 != Orientation::Collinear && o1 != o2
+        && o3 != Orientation::Collinear && o4 != Orientation::Collinear && o3 != o4
+}
+
+/// Returns the triangles cut from a counter clockwise ring, without its closing vertex, by
+/// clipping its ears one after another.
+fn clip_ears(mut ring: Vec<Coordinate>) -> Vec<[Coordinate; 3]> {
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    let (mut i, mut stalled) = (0, 0);
+    while ring.len() >= 3 {
+        let n = ring.len();
+        let (a, b, c) = (&ring[(i + n - 1) % n], &ring[i % n], &ring[(i + 1) % n]);
+        match orientation(a, b, c) {
+            Orientation::Collinear => {}
+            Orientation::CounterClockwise if n == 3 || is_ear(&ring, i % n) => {
+                triangles.push([a.clone(), b.clone(), c.clone()]);
+            }
+            _ => {
+                stalled += 1;
+                //no ear left, which only happens with invalid polygons
+                if stalled > n {
+                    break;
+                }
+                i = (i + 1) % n;
+                continue;
+            }
+        }
+        ring.remove(i % n);
+        stalled = 0;
+        i = (i + n - 2) % (n - 1);
+    }
+    triangles
+}
+
+/// Returns true if the corner of the ring at the index is an ear: its triangle contains no other
+/// vertex of the ring, apart from repeated corners at the ends of the bridges to the holes.
+fn is_ear(ring: &[Coordinate], i: usize) -> bool {
+    let n = ring.len();
+    let (a, b, c) = (&ring[(i + n - 1) % n], &ring[i], &ring[(i + 1) % n]);
+    ring.iter().all(|p| {
+        p.equals_2d(a)
+            || p.equals_2d(b)
+            || p.equals_2d(c)
+            || orientation(a, b, p) == Orientation::Clockwise
+            || orientation(b, c, p) == Orientation::Clockwise
+            || orientation(c, a, p) == Orientation::Clockwise
+    })
+}
+
+/// The vertex at infinity shared by the ghost triangles.
+const GHOST: usize = usize::MAX;
+
+/// Returns twice the signed area of the triangle, positive if it is counter clockwise, and 0 if its
+/// vertices are collinear up to the rounding errors of the computation.
+fn orient(vertices: &[(f64, f64)], a: usize, b: usize, c: usize) -> f64 {
+    let (ax, ay) = vertices[a];
+    let (bx, by) = vertices[b];
+    let (cx, cy) = vertices[c];
+    let (left, right) = ((bx - ax) * (cy - ay), (by - ay) * (cx - ax));
+    let det = left - right;
+    if det.abs() <= (left.abs() + right.abs()) * 1e-12 {
+        return 0.0;
+    }
+    det
+}
+
+/// A triangle with its circumcircle. Ghost triangles have the vertex at infinity last, and
+/// their first two vertices form an edge of the convex hull with the outside to its left.
+struct Triangle {
+    v: [usize; 3],
+    cx: f64,
+    cy: f64,
+    r2: f64,
+}
+
+impl Triangle {
+    /// Creates the triangle from counter clockwise vertices.
+    fn new(v: [usize; 3], vertices: &[(f64, f64)]) -> Self {
+        let (ax, ay) = vertices[v[0]];
+        let (bx, by) = vertices[v[1]];
+        let (cx, cy) = vertices[v[2]];
+        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+        let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+        let ux = (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d;
+        let uy = (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d;
+        Triangle { v, cx: ux, cy: uy, r2: (ax - ux).powi(2) + (ay - uy).powi(2) }
+    }
+
+    fn ghost(a: usize, b: usize) -> Self {
+        Triangle { v: [a, b, GHOST], cx: 0.0, cy: 0.0, r2: f64::INFINITY }
+    }
+
+    /// Returns the directed edges of the triangle.
+    fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..3).map(|k| (self.v[k], self.v[(k + 1) % 3]))
+    }
+
+    /// Returns true if the sweep at `px` has left the circumcircle behind, so no later point can
+    /// conflict with the triangle.
+    fn is_completed_before(&self, px: f64) -> bool {
+        let dx = px - self.cx;
+        self.v[2] != GHOST && dx > 0.0 && dx * dx > self.r2
+    }
+
+    /// Returns true if the point lies in the circumcircle of the triangle. Ghost triangles conflict
+    /// with the points beyond their hull edge, or lying inside of it.
+    fn conflicts(&self, i: usize, px: f64, py: f64, vertices: &[(f64, f64)]) -> bool {
+        if self.v[2] != GHOST {
+            let distance = (px - self.cx).powi(2) + (py - self.cy).powi(2);
+            if (distance - self.r2).abs() > self.r2 * 1e-9 {
+                return distance < self.r2;
+            }
+            //the point is too close to the circle for the cached circumcircle to decide
+            let [a, b, c] = self.v.map(|v| Coordinate::new(vertices[v].0, vertices[v].1, 0.0));
+            return in_circle(&a, &b, &c, &Coordinate::new(px, py, 0.0)) > 0;
+        }
+        let orientation = orient(vertices, self.v[0], self.v[1], i);
+        if orientation != 0.0 {
+            return orientation > 0.0;
+        }
+        let ((ax, ay), (bx, by)) = (vertices[self.v[0]], vertices[self.v[1]]);
+        (px - ax) * (px - bx) + (py - ay) * (py - by) < 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::line_intersection::{orientation, Orientation};
+    use crate::coord;
+
+    fn in_circumcircle(t: &[usize; 3], p: &Coordinate, points: &[Coordinate]) -> bool {
+        let (a, b, c) = (&points[t[0]], &points[t[1]], &points[t[2]]);
+        let (adx, ady) = (a.x() - p.x(), a.y() - p.y());
+        let (bdx, bdy) = (b.x() - p.x(), b.y() - p.y());
+        let (cdx, cdy) = (c.x() - p.x(), c.y() - p.y());
+        let det = (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+            - (bdx * bdx + bdy * bdy) * (adx * cdy - cdx * ady)
+            + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady);
+        det > 1e-9
+    }
+
+    #[test]
+    fn test_delaunay_property() {
+        let points: Vec<Coordinate> = (0..200)
+            .map(|i| coord!((i * 37 % 101) as f64 * 0.7, (i * 53 % 97) as f64 * 1.3))
+            .collect();
+        let triangles = delaunay(&points);
+        for t in &triangles {
+            assert_eq!(orientation(&points[t[0]], &points[t[1]], &points[t[2]]), Orientation::CounterClockwise);
+            for (i, p) in points.iter().enumerate() {
+                if !t.contains(&i) {
+                    assert!(!in_circumcircle(t, p, &points));
+                }
+            }
+        }
+        //the triangles cover the convex hull
+        let hull = crate::algorithm::convex_hull::convex_hull(&points);
+        let area: f64 = triangles
+            .iter()
+            .map(|t| crate::algorithm::area::ring_area(&[points[t[0]].clone(), points[t[1]].clone(), points[t[2]].clone(), points[t[0]].clone()]))
+            .sum();
+        assert!((area - hull.area()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nearly_cocircular_points() {
+        let circle = |radius: f64, n: usize| {
+            (0..n).map(move |i| {
+                let angle = i as f64 / n as f64 * std::f64::consts::TAU;
+                coord!(radius * angle.cos(), radius * angle.sin())
+            })
+        };
+        //concentric rings, whose rounding leaves triangles in the cavities that must be given back
+        let rings: Vec<Coordinate> = (1..=5).flat_map(|r| circle(r as f64, 500)).collect();
+        //a circle moved off by rounding sized amounts, around its center
+        let mut wheel: Vec<Coordinate> = circle(100.0, 2000)
+            .enumerate()
+            .map(|(i, c)| coord!(c.x() * (1.0 + (i % 13) as f64 * 1e-15), c.y()))
+            .collect();
+        wheel.push(coord!(0, 0));
+        for points in [rings, wheel] {
+            let triangles = delaunay(&points);
+            assert_eq!(triangles, delaunay(&points));
+            assert!(triangles
+                .iter()
+                .all(|t| orientation(&points[t[0]], &points[t[1]], &points[t[2]]) == Orientation::CounterClockwise));
+            //the triangles cover the convex hull without overlapping
+            let hull = crate::algorithm::convex_hull::convex_hull(&points);
+            let area: f64 = triangles
+                .iter()
+                .map(|t| crate::algorithm::area::ring_area(&[points[t[0]].clone(), points[t[1]].clone(), points[t[2]].clone(), points[t[0]].clone()]))
+                .sum();
+            assert!((area - hull.area()).abs() < 1e-6);
+            //a triangulation of n points with h on the hull has 2n - h - 2 triangles
+            assert_eq!(triangles.len(), 2 * points.len() - (hull.coordinates().len() - 1) - 2);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        assert!(delaunay(&[]).is_empty());
+        assert!(delaunay(&[coord!(0, 0), coord!(1, 1), coord!(2, 2)]).is_empty());
+        assert_eq!(delaunay(&[coord!(0, 0), coord!(1, 0), coord!(0, 1), coord!(1, 0)]).len(), 1);
+    }
+
+    #[test]
+    fn test_triangulation_of_a_polygon_with_holes() {
+        let polygon = vec![
+            vec![coord!(0, 0), coord!(10, 0), coord!(10, 10), coord!(5, 4), coord!(0, 10), coord!(0, 0)],
+            vec![coord!(1, 1), coord!(1, 3), coord!(3, 3), coord!(3, 1), coord!(1, 1)],
+            //a clockwise hole is reversed
+            vec![coord!(6, 1), coord!(9, 1), coord!(9, 3), coord!(6, 3), coord!(6, 1)],
+        ];
+        let triangles = triangulate_polygon(&polygon);
+        //a ring with n vertices and h holes gives n + 2h - 2 triangles
+        assert_eq!(triangles.len(), 5 + 8 + 2 * 2 - 2);
+        let area: f64 = triangles.iter().map(|[a, b, c]| ring_signed_area(&[a.clone(), b.clone(), c.clone(), a.clone()])).sum();
+        assert!((area - crate::algorithm::area::polygon_area(&polygon)).abs() < 1e-12);
+        assert!(triangles.iter().all(|[a, b, c]| orientation(a, b, c) == Orientation::CounterClockwise));
+    }
+
+}